@@ -0,0 +1,88 @@
+//! The `from_path` fuzz target, which parses untrusted input with the generated parser of [`FuzzRoute`]. None of the
+//! parsers may panic, and a path over the limits must be rejected before any route is tried.
+//!
+//! The code the derive generates calls the router runtime, which is part of the app's binary, so `src/main.rs`
+//! includes this file as a module when `cargo fuzz` builds the app.
+
+use crate::url_path::UrlPath;
+use crate::{ParseLimit, Routable};
+use dioxus::prelude::*;
+use std::str::FromStr;
+
+/// A route for every kind of segment the parser handles, with limits low enough that the fuzzer reaches them
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(max_path_len = 4096, max_segments = 64)]
+enum FuzzRoute {
+    #[route("/" FuzzHome)]
+    FuzzHome {},
+    #[route("/users/(id)/posts/(?page)" FuzzPosts)]
+    FuzzPosts { id: u32, page: Option<u32> },
+    #[route("/v(version)-beta/(name)?(query)#(section)" FuzzRelease)]
+    FuzzRelease {
+        version: u8,
+        name: String,
+        query: String,
+        section: Option<String>,
+    },
+    #[route("/files/(...path)" FuzzFiles)]
+    FuzzFiles { path: UrlPath },
+    #[route(not_found FuzzNotFound)]
+    FuzzNotFound { path: Vec<String> },
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn FuzzHome(cx: Scope) -> Element {
+    render! { "Home" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn FuzzPosts(cx: Scope, id: u32, page: Option<u32>) -> Element {
+    render! { "Posts of {id}, page {page:?}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn FuzzRelease(
+    cx: Scope,
+    version: u8,
+    name: String,
+    query: String,
+    section: Option<String>,
+) -> Element {
+    render! { "Release {version} {name} {query} {section:?}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn FuzzFiles(cx: Scope, path: UrlPath) -> Element {
+    render! { "Files {path:?}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn FuzzNotFound(cx: Scope, path: Vec<String>) -> Element {
+    let path = path.join("/");
+    render! { "Nothing at /{path}" }
+}
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| fuzz_parse(data));
+
+fn fuzz_parse(data: &[u8]) {
+    let Ok(path) = std::str::from_utf8(data) else {
+        return;
+    };
+    match FuzzRoute::from_str(path) {
+        Ok(route) => {
+            let _ = FuzzRoute::from_str(&route.to_string());
+        }
+        Err(err) if path.len() > FuzzRoute::parse_limits().max_path_len => {
+            assert!(matches!(err.limit, Some(ParseLimit::PathTooLong { .. })));
+        }
+        Err(_) => {}
+    }
+    let _ = FuzzRoute::try_parse(path);
+    let _ = FuzzRoute::from_segments(path.split('/'));
+    let _ = FuzzRoute::match_pattern(path);
+}
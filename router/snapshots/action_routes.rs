@@ -0,0 +1,710 @@
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum HomeParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+}
+impl ::std::fmt::Display for HomeParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "")?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for HomeParseError {}
+impl HomeParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        false
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum OldHomeParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+}
+impl ::std::fmt::Display for OldHomeParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "old-home")?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for OldHomeParseError {}
+impl OldHomeParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        false
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum LogoutParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+}
+impl ::std::fmt::Display for LogoutParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "logout")?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for LogoutParseError {}
+impl LogoutParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        false
+    }
+}
+#[derive(Debug, PartialEq)]
+pub enum RouteMatchError {
+    Home { error: HomeParseError, span: ::std::ops::Range<usize> },
+    OldHome { error: OldHomeParseError, span: ::std::ops::Range<usize> },
+    Logout { error: LogoutParseError, span: ::std::ops::Range<usize> },
+}
+impl ::std::error::Error for RouteMatchError {
+    fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+        match self {
+            Self::Home { error, .. } => Some(error),
+            Self::OldHome { error, .. } => Some(error),
+            Self::Logout { error, .. } => Some(error),
+        }
+    }
+}
+impl ::std::fmt::Display for RouteMatchError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::Home { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Home), "/",
+                    error
+                )?
+            }
+            Self::OldHome { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(OldHome),
+                    "/old-home", error
+                )?
+            }
+            Self::Logout { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Logout),
+                    "/logout", error
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl RouteMatchError {
+    /// The byte range of the parsed input that failed to match. The range refers to the original
+    /// input, before any segment was percent-decoded.
+    pub fn span(&self) -> ::std::ops::Range<usize> {
+        match self {
+            Self::Home { span, .. } => span.clone(),
+            Self::OldHome { span, .. } => span.clone(),
+            Self::Logout { span, .. } => span.clone(),
+        }
+    }
+    /// Render the input with a line of carets under the part that failed to match.
+    pub fn highlight(&self, input: &str) -> String {
+        let span = self.span();
+        let start = input.get(..span.start).map_or(0, |before| before.chars().count());
+        let len = input.get(span).map_or(0, |failed| failed.chars().count());
+        format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(len.max(1)))
+    }
+}
+impl AttemptedRoute for RouteMatchError {
+    fn span(&self) -> ::std::ops::Range<usize> {
+        Self::span(self)
+    }
+    fn is_decode_error(&self) -> bool {
+        match self {
+            Self::Home { error, .. } => error.is_decode_error(),
+            Self::OldHome { error, .. } => error.is_decode_error(),
+            Self::Logout { error, .. } => error.is_decode_error(),
+        }
+    }
+}
+impl<'a> TryFrom<&'a str> for Route {
+    type Error = <Self as ::std::str::FromStr>::Err;
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+impl ::std::str::FromStr for Route {
+    type Err = RouteParseError<RouteMatchError>;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_path(s)
+    }
+}
+impl Route {
+    fn parse_path(s: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if s.len() > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len: s.len(),
+                    max: 8192,
+                }),
+            );
+        }
+        let origin = url_path_start(s);
+        let s = &s[origin..];
+        let s = s.split_once('#').map_or(s, |(rest, _)| rest);
+        let s = s.split_once('?').map_or(s, |(path, _)| path);
+        let (path, offset) = match s.strip_prefix('/') {
+            Some(path) => (path, origin + 1),
+            None => (s, origin),
+        };
+        Self::match_segments(SegmentCursor::new(path.split('/'), offset))
+    }
+    /// Parse a route from a path that is already split into segments, like the path parts extracted
+    /// by an HTTP framework. The segments must not include the leading slash; no segments at all
+    /// matches the root route.
+    ///
+    /// This behaves exactly like parsing the joined path with [`std::str::FromStr`]. Spans in the
+    /// errors refer to the joined path.
+    pub fn from_segments<'a>(
+        segments: impl Iterator<Item = &'a str> + Clone,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().next().is_none() {
+            return Self::match_segments(SegmentCursor::new(::std::iter::once(""), 1));
+        }
+        Self::match_segments(SegmentCursor::new(segments, 1))
+    }
+    /// Parse a location under the base path the app is served from, like `/app/blog/1` under `/app`.
+    /// A slash at the end of the base is ignored, and locations outside of it, like `/application`,
+    /// are rejected with [`ParseLimit::OutsideBase`] without trying any route.
+    pub fn parse_with_base(
+        location: &str,
+        base: &str,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        match strip_base_path(base, location) {
+            Some(location) => location.parse(),
+            None => {
+                Err(
+                    RouteParseError::limit(ParseLimit::OutsideBase {
+                        base: base.to_string(),
+                    }),
+                )
+            }
+        }
+    }
+    fn match_segments<'a>(
+        mut segments: SegmentCursor<impl Iterator<Item = &'a str> + Clone>,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().take(256 + 1).count() > 256 {
+            return Err(
+                RouteParseError::limit(ParseLimit::TooManySegments {
+                    max: 256,
+                }),
+            );
+        }
+        let len = segments.end();
+        if len > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len,
+                    max: 8192,
+                }),
+            );
+        }
+        let mut errors = Vec::new();
+        if let Some(segment) = segments.next() {
+            if "" == segment {
+                let mut segments = segments.clone();
+                let remaining_segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                let next_segment = segments_clone.next();
+                let segment_after_next = segments_clone.next();
+                match (next_segment, segment_after_next) {
+                    (None, _) | (Some(""), None) => {
+                        return Ok(Route::Home {});
+                    }
+                    _ => {
+                        let span = remaining_segments.rest_span();
+                        let mut trailing = String::new();
+                        for seg in remaining_segments {
+                            trailing += seg;
+                            trailing += "/";
+                        }
+                        trailing.pop();
+                        errors
+                            .push(RouteMatchError::Home {
+                                error: HomeParseError::ExtraSegments(trailing),
+                                span,
+                            })
+                    }
+                }
+                if let Some(segment) = segments.next() {}
+            } else {
+                errors
+                    .push(RouteMatchError::Home {
+                        error: HomeParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "old-home" == segment {
+                let mut segments = segments.clone();
+                let remaining_segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                let next_segment = segments_clone.next();
+                let segment_after_next = segments_clone.next();
+                match (next_segment, segment_after_next) {
+                    (None, _) | (Some(""), None) => {
+                        return Ok(Route::OldHome {});
+                    }
+                    _ => {
+                        let span = remaining_segments.rest_span();
+                        let mut trailing = String::new();
+                        for seg in remaining_segments {
+                            trailing += seg;
+                            trailing += "/";
+                        }
+                        trailing.pop();
+                        errors
+                            .push(RouteMatchError::OldHome {
+                                error: OldHomeParseError::ExtraSegments(trailing),
+                                span,
+                            })
+                    }
+                }
+                if let Some(segment) = segments.next() {}
+            } else {
+                errors
+                    .push(RouteMatchError::OldHome {
+                        error: OldHomeParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "logout" == segment {
+                let mut segments = segments.clone();
+                let remaining_segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                let next_segment = segments_clone.next();
+                let segment_after_next = segments_clone.next();
+                match (next_segment, segment_after_next) {
+                    (None, _) | (Some(""), None) => {
+                        return Ok(Route::Logout {});
+                    }
+                    _ => {
+                        let span = remaining_segments.rest_span();
+                        let mut trailing = String::new();
+                        for seg in remaining_segments {
+                            trailing += seg;
+                            trailing += "/";
+                        }
+                        trailing.pop();
+                        errors
+                            .push(RouteMatchError::Logout {
+                                error: LogoutParseError::ExtraSegments(trailing),
+                                span,
+                            })
+                    }
+                }
+                if let Some(segment) = segments.next() {}
+            } else {
+                errors
+                    .push(RouteMatchError::Logout {
+                        error: LogoutParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+        }
+        Err(RouteParseError {
+            attempted_routes: errors,
+            limit: None,
+        })
+    }
+}
+impl ::std::fmt::Display for Route {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        Routable::write_path(self, f)
+    }
+}
+impl Routable for Route {
+    fn render<'a>(self, cx: &'a ScopeState) -> Element<'a> {
+        match self {
+            Self::Home {} => {
+                render! {
+                    Home {}
+                }
+            }
+            Self::OldHome { .. } => None,
+            Self::Logout { .. } => None,
+        }
+    }
+    fn write_path(&self, f: &mut impl ::std::fmt::Write) -> ::std::fmt::Result {
+        match self {
+            Self::Home {} => {
+                write!(f, "/{}", "")?;
+            }
+            Self::OldHome {} => {
+                write!(f, "/{}", "old-home")?;
+            }
+            Self::Logout {} => {
+                write!(f, "/{}", "logout")?;
+            }
+        }
+        Ok(())
+    }
+    fn matched_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Home {} => vec![],
+            Self::OldHome {} => vec![],
+            Self::Logout {} => vec![],
+        }
+    }
+    fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        Self::params_diff(self, other)
+    }
+    fn differing_fields(&self, other: &Self) -> Option<Vec<&'static str>> {
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Home {}, Self::Home {}) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                Some(fields)
+            }
+            (Self::OldHome {}, Self::OldHome {}) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                Some(fields)
+            }
+            (Self::Logout {}, Self::Logout {}) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                Some(fields)
+            }
+            _ => None,
+        }
+    }
+    fn static_routes() -> Vec<Self> {
+        Self::static_routes()
+    }
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Home { .. } => "/",
+            Self::OldHome { .. } => "/old-home",
+            Self::Logout { .. } => "/logout",
+        }
+    }
+    fn route_patterns() -> &'static [&'static str] {
+        Self::ROUTE_PATTERNS
+    }
+    fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        Self::from_pattern_and_params(pattern, params)
+    }
+    fn action(&self) -> Option<fn(&mut Router<Self>)> {
+        #[allow(unreachable_patterns)]
+        match self {
+            Self::OldHome { .. } => Some(to_home),
+            Self::Logout { .. } => Some(logout),
+            _ => None,
+        }
+    }
+    fn status_hint(&self) -> u16 {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => 200,
+        }
+    }
+    fn cache_hint(&self) -> Option<&'static str> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn palette_routes() -> Vec<Self> {
+        let mut routes = Vec::new();
+        routes.push(Self::Home {});
+        routes.push(Self::OldHome {});
+        routes.push(Self::Logout {});
+        routes
+    }
+    fn action_is_dry_run_safe(&self) -> bool {
+        #[allow(unreachable_patterns)]
+        match self {
+            Self::OldHome { .. } => true,
+            _ => false,
+        }
+    }
+    fn parse_limits() -> ParseLimits {
+        ParseLimits {
+            max_path_len: 8192,
+            max_segments: 256,
+        }
+    }
+    fn rejected(limit: ParseLimit) -> <Self as ::std::str::FromStr>::Err {
+        RouteParseError::<RouteMatchError>::limit(limit).into()
+    }
+    fn head(&self) -> HeadMeta {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => HeadMeta::default(),
+        }
+    }
+}
+impl Route {
+    /// The path of this route. The same as its `Display` output.
+    pub fn to_path(&self) -> String {
+        let mut path = String::new();
+        Routable::write_path(self, &mut path).expect("a segment failed to display");
+        path
+    }
+    /// Parse a route from a path. The same as parsing it with [`std::str::FromStr`].
+    pub fn from_path(path: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        <Self as ::std::str::FromStr>::from_str(path)
+    }
+}
+impl Route {
+    /// The pattern of the variant called `name`, like the pattern of a route a server handles
+    /// separately. Returns `None` if no variant has that name.
+    pub fn pattern_of_variant(name: &str) -> Option<&'static str> {
+        match name {
+            "Home" => Some("/"),
+            "OldHome" => Some("/old-home"),
+            "Logout" => Some("/logout"),
+            _ => None,
+        }
+    }
+    /// Find the pattern of the route a path corresponds to without parsing any dynamic segments.
+    ///
+    /// Dynamic segments match any value here, so a path can resolve to a pattern even if its segments
+    /// would fail to parse as the route's fields.
+    #[allow(unused_variables)]
+    pub fn match_pattern(path: &str) -> Option<&'static str> {
+        let mut segments = path.strip_prefix('/').unwrap_or(path).split('/');
+        if let Some(segment) = segments.next() {
+            if "" == segment {
+                let mut segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                if let (None, _) | (Some(""), None)
+                    = (segments_clone.next(), segments_clone.next()) {
+                    return Some("/");
+                }
+                if let Some(segment) = segments.next() {}
+            }
+            if "old-home" == segment {
+                let mut segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                if let (None, _) | (Some(""), None)
+                    = (segments_clone.next(), segments_clone.next()) {
+                    return Some("/old-home");
+                }
+                if let Some(segment) = segments.next() {}
+            }
+            if "logout" == segment {
+                let mut segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                if let (None, _) | (Some(""), None)
+                    = (segments_clone.next(), segments_clone.next()) {
+                    return Some("/logout");
+                }
+                if let Some(segment) = segments.next() {}
+            }
+        }
+        None
+    }
+    /// Build a route from one of the patterns in [`Self::ROUTE_PATTERNS`] and the values of its
+    /// dynamic segments, like routes stored as data in a CMS.
+    ///
+    /// Parameters are looked up by the name of their segment and are parsed as they are, without
+    /// percent-decoding. A catch-all parameter is split on `/`. Parameters the pattern doesn't use
+    /// are ignored.
+    pub fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        #[allow(unused_variables)]
+        let param = |name: &'static str| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+                .ok_or(BuildError::MissingParam(name))
+        };
+        match pattern {
+            "/" => Ok(Route::Home {}),
+            "/old-home" => Ok(Route::OldHome {}),
+            "/logout" => Ok(Route::Logout {}),
+            _ => Err(BuildError::UnknownPattern(pattern.to_string())),
+        }
+    }
+    /// The route pattern of every variant in the order the parser tries them.
+    ///
+    /// Only patterns that parse into a variant are listed here. Patterns that are never
+    /// matched directly (like redirects or fallbacks) belong in their own constants. Routes
+    /// with `feature = "..."` are listed in every build; check `gated_routes` for the ones
+    /// that are compiled out.
+    pub const ROUTE_PATTERNS: &'static [&'static str] = &["/", "/old-home", "/logout"];
+    /// The patterns in [`Self::ROUTE_PATTERNS`] of routes that run an action instead of rendering a
+    /// page. Tools that list pages, like site maps, should leave these out.
+    pub const ACTION_ROUTE_PATTERNS: &'static [&'static str] = &["/old-home", "/logout"];
+    /// Every route with the name and type of its segments as JSON, and the locale of localized
+    /// patterns as `hreflang`. Save it as the `#[routable(baseline = "...")]` file to check later
+    /// versions of the routes against this one.
+    pub const ROUTE_TABLE_JSON: &'static str = "{\n  \"routes\": [\n    {\"variant\": \"Home\", \"pattern\": \"/\", \"segments\": []},\n    {\"variant\": \"OldHome\", \"pattern\": \"/old-home\", \"segments\": []},\n    {\"variant\": \"Logout\", \"pattern\": \"/logout\", \"segments\": []}\n  ]\n}\n";
+    /// A hash of the routes that changes when a pattern, the type of a segment or the metadata of a route
+    /// changes. Put it in asset URLs to invalidate cached pages when the routes change; the precache
+    /// manifest uses it as the revision of every page.
+    pub const ROUTES_HASH: &'static str = "842ed14d0403cc81";
+    /// Split the pages into the paths a service worker can cache ahead of time and the patterns that
+    /// must always be fetched from the network.
+    ///
+    /// Only routes without dynamic segments whose `cache = "..."` hint allows shared caching are
+    /// precached. Every other page is network only, with dynamic segments written as `*` and
+    /// catch-alls as `**`. Action routes are in neither list.
+    pub fn precache_manifest() -> PrecacheManifest {
+        PrecacheManifest {
+            precache: vec![],
+            revision: Self::ROUTES_HASH,
+            network_only_patterns: vec!["/"],
+        }
+    }
+}
+///The paths of the routes of [`Route`] without dynamic segments, like `Route::Variant` as `VARIANT_PATH`
+#[allow(dead_code)]
+pub mod route_paths {
+    ///The path of [`Route::Home`]
+    pub const HOME_PATH: &str = "/";
+    ///The path of [`Route::OldHome`]
+    pub const OLD_HOME_PATH: &str = "/old-home";
+    ///The path of [`Route::Logout`]
+    pub const LOGOUT_PATH: &str = "/logout";
+}
+impl Route {
+    /// Every variant with its pattern and the segments of it, in the order they are declared. Routes
+    /// behind a feature are listed even when the feature is off, like in [`Self::ROUTE_PATTERNS`].
+    pub const SITE_MAP: &'static [RouteInfo] = &[
+        RouteInfo {
+            variant: "Home",
+            pattern: "/",
+            segments: &[SegmentInfo::Static("")],
+        },
+        RouteInfo {
+            variant: "OldHome",
+            pattern: "/old-home",
+            segments: &[SegmentInfo::Static("old-home")],
+        },
+        RouteInfo {
+            variant: "Logout",
+            pattern: "/logout",
+            segments: &[SegmentInfo::Static("logout")],
+        },
+    ];
+    /// Every route without dynamic segments, like the pages a static site generator can render ahead of
+    /// time. Routes with a dynamic or catch-all segment, a query or a fragment are left out.
+    #[allow(unused_mut, clippy::vec_init_then_push)]
+    pub fn static_routes() -> Vec<Self> {
+        let mut routes = Vec::new();
+        routes.push(Route::Home {});
+        routes.push(Route::OldHome {});
+        routes.push(Route::Logout {});
+        routes
+    }
+}
+impl Route {
+    /// Check if two routes are the same variant, ignoring the values of their parameters.
+    pub fn same_variant(&self, other: &Self) -> bool {
+        ::std::mem::discriminant(self) == ::std::mem::discriminant(other)
+    }
+    /// List the parameters whose serialized values differ between two routes of the same
+    /// variant as `(name, self_value, other_value)`. Routes of different variants have no
+    /// comparable parameters and produce an empty list.
+    pub fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        let mut diff = Vec::new();
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Home {}, Self::Home {}) => {}
+            (Self::OldHome {}, Self::OldHome {}) => {}
+            (Self::Logout {}, Self::Logout {}) => {}
+            _ => {}
+        }
+        diff
+    }
+    /// The number of segments in the path of this route. A catch-all counts every segment it holds.
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Home { .. } => 0usize,
+            Self::OldHome { .. } => 1usize,
+            Self::Logout { .. } => 1usize,
+        }
+    }
+    /// The first static segment in the pattern of this route, if it has one.
+    pub fn section(&self) -> Option<&'static str> {
+        match self {
+            Self::Home { .. } => None,
+            Self::OldHome { .. } => Some("old-home"),
+            Self::Logout { .. } => Some("logout"),
+        }
+    }
+}
+/// Lets a route be used directly as an attribute value, like `a { href: &route }`.
+///
+/// The route is written straight into the scope's bump allocator instead of an intermediate `String`.
+impl<'a> IntoAttributeValue<'a> for &Route {
+    fn into_value(
+        self,
+        bump: &'a dioxus::core::exports::bumpalo::Bump,
+    ) -> dioxus::core::AttributeValue<'a> {
+        format_args!("{}", self).into_value(bump)
+    }
+}
+const _: () = {
+    fn __router_segment_value<T: SegmentValue>() {}
+    fn __router_check_segments() {}
+};
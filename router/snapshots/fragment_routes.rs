@@ -0,0 +1,1384 @@
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum HomeParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+}
+impl ::std::fmt::Display for HomeParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "")?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for HomeParseError {}
+impl HomeParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        false
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum DocsParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    pageParseError(SegmentError<<String as FromRouteSegment>::Err>),
+    sectionParseError(SegmentError<<String as ::std::str::FromStr>::Err>),
+    MissingFragment,
+}
+impl ::std::fmt::Display for DocsParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "docs")?
+            }
+            Self::pageParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(page),
+                    stringify!(String), err
+                )?
+            }
+            Self::sectionParseError(err) => {
+                write!(
+                    f, "Fragment '#({}:{})' {}", stringify!(section), stringify!(Option <
+                    String >), err
+                )?
+            }
+            Self::MissingFragment => {
+                write!(f, "Fragment '#({})' is missing", stringify!(section))?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for DocsParseError {}
+impl DocsParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(
+            self, Self::pageParseError(SegmentError::DecodeError(_)) |
+            Self::sectionParseError(SegmentError::DecodeError(_))
+        )
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum SearchParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    queryParseError(<String as FromQuery>::Err),
+    resultParseError(SegmentError<<u32 as ::std::str::FromStr>::Err>),
+    MissingFragment,
+}
+impl ::std::fmt::Display for SearchParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "search")?
+            }
+            Self::queryParseError(err) => {
+                write!(
+                    f, "Query '?({}:{})' did not match: {}", stringify!(query),
+                    stringify!(String), err
+                )?
+            }
+            Self::resultParseError(err) => {
+                write!(
+                    f, "Fragment '#({}:{})' {}", stringify!(result), stringify!(u32), err
+                )?
+            }
+            Self::MissingFragment => {
+                write!(f, "Fragment '#({})' is missing", stringify!(result))?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for SearchParseError {}
+impl SearchParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(self, Self::resultParseError(SegmentError::DecodeError(_)))
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum FileParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    pathParseError(<Vec<String> as FromRouteSegments>::Err),
+    lineParseError(SegmentError<<u32 as ::std::str::FromStr>::Err>),
+    MissingFragment,
+}
+impl ::std::fmt::Display for FileParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "files")?
+            }
+            Self::pathParseError(err) => {
+                write!(
+                    f, "Catch-all segment '({}:{})' did not match: {}", stringify!(path),
+                    stringify!(Vec < String >), err
+                )?
+            }
+            Self::lineParseError(err) => {
+                write!(
+                    f, "Fragment '#({}:{})' {}", stringify!(line), stringify!(Option <
+                    u32 >), err
+                )?
+            }
+            Self::MissingFragment => {
+                write!(f, "Fragment '#({})' is missing", stringify!(line))?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for FileParseError {}
+impl FileParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(self, Self::lineParseError(SegmentError::DecodeError(_)))
+    }
+}
+#[derive(Debug, PartialEq)]
+pub enum RouteMatchError {
+    Home { error: HomeParseError, span: ::std::ops::Range<usize> },
+    Docs { error: DocsParseError, span: ::std::ops::Range<usize> },
+    Search { error: SearchParseError, span: ::std::ops::Range<usize> },
+    File { error: FileParseError, span: ::std::ops::Range<usize> },
+}
+impl ::std::error::Error for RouteMatchError {
+    fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+        match self {
+            Self::Home { error, .. } => Some(error),
+            Self::Docs { error, .. } => Some(error),
+            Self::Search { error, .. } => Some(error),
+            Self::File { error, .. } => Some(error),
+        }
+    }
+}
+impl ::std::fmt::Display for RouteMatchError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::Home { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Home), "/",
+                    error
+                )?
+            }
+            Self::Docs { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Docs),
+                    "/docs/(page)#(section)", error
+                )?
+            }
+            Self::Search { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Search),
+                    "/search?(query)#(result)", error
+                )?
+            }
+            Self::File { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(File),
+                    "/files/(...path)#(line)", error
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl RouteMatchError {
+    /// The byte range of the parsed input that failed to match. The range refers to the original
+    /// input, before any segment was percent-decoded.
+    pub fn span(&self) -> ::std::ops::Range<usize> {
+        match self {
+            Self::Home { span, .. } => span.clone(),
+            Self::Docs { span, .. } => span.clone(),
+            Self::Search { span, .. } => span.clone(),
+            Self::File { span, .. } => span.clone(),
+        }
+    }
+    /// Render the input with a line of carets under the part that failed to match.
+    pub fn highlight(&self, input: &str) -> String {
+        let span = self.span();
+        let start = input.get(..span.start).map_or(0, |before| before.chars().count());
+        let len = input.get(span).map_or(0, |failed| failed.chars().count());
+        format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(len.max(1)))
+    }
+}
+impl AttemptedRoute for RouteMatchError {
+    fn span(&self) -> ::std::ops::Range<usize> {
+        Self::span(self)
+    }
+    fn is_decode_error(&self) -> bool {
+        match self {
+            Self::Home { error, .. } => error.is_decode_error(),
+            Self::Docs { error, .. } => error.is_decode_error(),
+            Self::Search { error, .. } => error.is_decode_error(),
+            Self::File { error, .. } => error.is_decode_error(),
+        }
+    }
+}
+impl<'a> TryFrom<&'a str> for Route {
+    type Error = <Self as ::std::str::FromStr>::Err;
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+impl ::std::str::FromStr for Route {
+    type Err = RouteParseError<RouteMatchError>;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_path(s)
+    }
+}
+impl Route {
+    fn parse_path(s: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if s.len() > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len: s.len(),
+                    max: 8192,
+                }),
+            );
+        }
+        let origin = url_path_start(s);
+        let s = &s[origin..];
+        let (s, __router_fragment) = match s.split_once('#') {
+            Some((rest, fragment)) => (rest, Some((origin + rest.len(), fragment))),
+            None => (s, None),
+        };
+        let (s, __router_query) = match s.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (s, None),
+        };
+        let (path, offset) = match s.strip_prefix('/') {
+            Some(path) => (path, origin + 1),
+            None => (s, origin),
+        };
+        Self::match_segments(
+            SegmentCursor::new(path.split('/'), offset),
+            __router_query,
+            __router_fragment,
+        )
+    }
+    /// Parse a route from a path that is already split into segments, like the path parts extracted
+    /// by an HTTP framework. The segments must not include the leading slash; no segments at all
+    /// matches the root route.
+    ///
+    /// This behaves exactly like parsing the joined path with [`std::str::FromStr`]. Spans in the
+    /// errors refer to the joined path.
+    pub fn from_segments<'a>(
+        segments: impl Iterator<Item = &'a str> + Clone,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().next().is_none() {
+            return Self::match_segments(
+                SegmentCursor::new(::std::iter::once(""), 1),
+                None,
+                None,
+            );
+        }
+        Self::match_segments(SegmentCursor::new(segments, 1), None, None)
+    }
+    /// Parse a location under the base path the app is served from, like `/app/blog/1` under `/app`.
+    /// A slash at the end of the base is ignored, and locations outside of it, like `/application`,
+    /// are rejected with [`ParseLimit::OutsideBase`] without trying any route.
+    pub fn parse_with_base(
+        location: &str,
+        base: &str,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        match strip_base_path(base, location) {
+            Some(location) => location.parse(),
+            None => {
+                Err(
+                    RouteParseError::limit(ParseLimit::OutsideBase {
+                        base: base.to_string(),
+                    }),
+                )
+            }
+        }
+    }
+    fn match_segments<'a>(
+        mut segments: SegmentCursor<impl Iterator<Item = &'a str> + Clone>,
+        __router_query: Option<&str>,
+        __router_fragment: Option<(usize, &str)>,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().take(256 + 1).count() > 256 {
+            return Err(
+                RouteParseError::limit(ParseLimit::TooManySegments {
+                    max: 256,
+                }),
+            );
+        }
+        let len = segments.end();
+        if len > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len,
+                    max: 8192,
+                }),
+            );
+        }
+        let mut errors = Vec::new();
+        if let Some(segment) = segments.next() {
+            if "" == segment {
+                let mut segments = segments.clone();
+                let remaining_segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                let next_segment = segments_clone.next();
+                let segment_after_next = segments_clone.next();
+                match (next_segment, segment_after_next) {
+                    (None, _) | (Some(""), None) => {
+                        return Ok(Route::Home {});
+                    }
+                    _ => {
+                        let span = remaining_segments.rest_span();
+                        let mut trailing = String::new();
+                        for seg in remaining_segments {
+                            trailing += seg;
+                            trailing += "/";
+                        }
+                        trailing.pop();
+                        errors
+                            .push(RouteMatchError::Home {
+                                error: HomeParseError::ExtraSegments(trailing),
+                                span,
+                            })
+                    }
+                }
+                if let Some(segment) = segments.next() {}
+            } else {
+                errors
+                    .push(RouteMatchError::Home {
+                        error: HomeParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "docs" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() => {
+                            Err(RouteMatchError::Docs {
+                                error: DocsParseError::EmptySegment(stringify!(page)),
+                                span: segments.span(),
+                            })
+                        }
+                        Some(decoded) => {
+                            <String as FromRouteSegment>::from_route_segment(&decoded)
+                                .map_err(|err| RouteMatchError::Docs {
+                                    error: DocsParseError::pageParseError(
+                                        SegmentError::from_parse_error(segment, err),
+                                    ),
+                                    span: segments.span(),
+                                })
+                        }
+                        None => {
+                            Err(RouteMatchError::Docs {
+                                error: DocsParseError::pageParseError(
+                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                    };
+                    match parsed {
+                        Ok(page) => {
+                            let remaining_segments = segments.clone();
+                            let mut segments_clone = segments.clone();
+                            let next_segment = segments_clone.next();
+                            let segment_after_next = segments_clone.next();
+                            match (next_segment, segment_after_next) {
+                                (None, _) | (Some(""), None) => {
+                                    let parsed = match __router_fragment {
+                                        Some((start, fragment)) if !fragment.is_empty() => {
+                                            match decode_path_segment(fragment) {
+                                                Some(decoded) => {
+                                                    <String as ::std::str::FromStr>::from_str(&decoded)
+                                                        .map(Some)
+                                                        .map_err(|err| RouteMatchError::Docs {
+                                                            error: DocsParseError::sectionParseError(
+                                                                SegmentError::from_parse_error(fragment, err),
+                                                            ),
+                                                            span: start..start + fragment.len() + 1,
+                                                        })
+                                                }
+                                                None => {
+                                                    Err(RouteMatchError::Docs {
+                                                        error: DocsParseError::sectionParseError(
+                                                            SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                                        ),
+                                                        span: start..start + fragment.len() + 1,
+                                                    })
+                                                }
+                                            }
+                                        }
+                                        _ => {
+                                            (&MissingFragment::<Option<String>>::new())
+                                                .missing_fragment()
+                                                .ok_or(RouteMatchError::Docs {
+                                                    error: DocsParseError::MissingFragment,
+                                                    span: segments.end()..segments.end(),
+                                                })
+                                        }
+                                    };
+                                    match parsed {
+                                        Ok(section) => {
+                                            return Ok(Route::Docs { page, section });
+                                        }
+                                        Err(err) => errors.push(err),
+                                    }
+                                }
+                                _ => {
+                                    let span = remaining_segments.rest_span();
+                                    let mut trailing = String::new();
+                                    for seg in remaining_segments {
+                                        trailing += seg;
+                                        trailing += "/";
+                                    }
+                                    trailing.pop();
+                                    errors
+                                        .push(RouteMatchError::Docs {
+                                            error: DocsParseError::ExtraSegments(trailing),
+                                            span,
+                                        })
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::Docs {
+                        error: DocsParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "search" == segment {
+                let mut segments = segments.clone();
+                let remaining_segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                let next_segment = segments_clone.next();
+                let segment_after_next = segments_clone.next();
+                match (next_segment, segment_after_next) {
+                    (None, _) | (Some(""), None) => {
+                        let parsed = <String as FromQuery>::from_query(
+                                __router_query.unwrap_or_default(),
+                            )
+                            .map_err(|err| RouteMatchError::Search {
+                                error: SearchParseError::queryParseError(err),
+                                span: segments
+                                    .end()..segments.end()
+                                    + __router_query.map_or(0, |query| query.len() + 1),
+                            });
+                        match parsed {
+                            Ok(query) => {
+                                let parsed = match __router_fragment {
+                                    Some((start, fragment)) if !fragment.is_empty() => {
+                                        match decode_path_segment(fragment) {
+                                            Some(decoded) => {
+                                                <u32 as ::std::str::FromStr>::from_str(&decoded)
+                                                    .map_err(|err| RouteMatchError::Search {
+                                                        error: SearchParseError::resultParseError(
+                                                            SegmentError::from_parse_error(fragment, err),
+                                                        ),
+                                                        span: start..start + fragment.len() + 1,
+                                                    })
+                                            }
+                                            None => {
+                                                Err(RouteMatchError::Search {
+                                                    error: SearchParseError::resultParseError(
+                                                        SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                                    ),
+                                                    span: start..start + fragment.len() + 1,
+                                                })
+                                            }
+                                        }
+                                    }
+                                    _ => {
+                                        (&MissingFragment::<u32>::new())
+                                            .missing_fragment()
+                                            .ok_or(RouteMatchError::Search {
+                                                error: SearchParseError::MissingFragment,
+                                                span: segments.end()..segments.end(),
+                                            })
+                                    }
+                                };
+                                match parsed {
+                                    Ok(result) => {
+                                        return Ok(Route::Search { query, result });
+                                    }
+                                    Err(err) => errors.push(err),
+                                }
+                            }
+                            Err(err) => errors.push(err),
+                        }
+                    }
+                    _ => {
+                        let span = remaining_segments.rest_span();
+                        let mut trailing = String::new();
+                        for seg in remaining_segments {
+                            trailing += seg;
+                            trailing += "/";
+                        }
+                        trailing.pop();
+                        errors
+                            .push(RouteMatchError::Search {
+                                error: SearchParseError::ExtraSegments(trailing),
+                                span,
+                            })
+                    }
+                }
+                if let Some(segment) = segments.next() {}
+            } else {
+                errors
+                    .push(RouteMatchError::Search {
+                        error: SearchParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "files" == segment {
+                let mut segments = segments.clone();
+                if segments.clone().next().is_none() {
+                    match <Vec<
+                        String,
+                    > as FromRouteSegments>::from_route_segments(::std::iter::empty()) {
+                        Ok(path) => {
+                            let parsed = match __router_fragment {
+                                Some((start, fragment)) if !fragment.is_empty() => {
+                                    match decode_path_segment(fragment) {
+                                        Some(decoded) => {
+                                            <u32 as ::std::str::FromStr>::from_str(&decoded)
+                                                .map(Some)
+                                                .map_err(|err| RouteMatchError::File {
+                                                    error: FileParseError::lineParseError(
+                                                        SegmentError::from_parse_error(fragment, err),
+                                                    ),
+                                                    span: start..start + fragment.len() + 1,
+                                                })
+                                        }
+                                        None => {
+                                            Err(RouteMatchError::File {
+                                                error: FileParseError::lineParseError(
+                                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                                ),
+                                                span: start..start + fragment.len() + 1,
+                                            })
+                                        }
+                                    }
+                                }
+                                _ => {
+                                    (&MissingFragment::<Option<u32>>::new())
+                                        .missing_fragment()
+                                        .ok_or(RouteMatchError::File {
+                                            error: FileParseError::MissingFragment,
+                                            span: segments.end()..segments.end(),
+                                        })
+                                }
+                            };
+                            match parsed {
+                                Ok(line) => {
+                                    return Ok(Route::File { path, line });
+                                }
+                                Err(err) => errors.push(err),
+                            }
+                        }
+                        Err(err) => {
+                            errors
+                                .push(RouteMatchError::File {
+                                    error: FileParseError::pathParseError(err),
+                                    span: segments.end()..segments.end(),
+                                })
+                        }
+                    }
+                }
+                if let Some(segment) = segments.next() {
+                    let __router_trailing_slash = segment.is_empty()
+                        && segments.clone().next().is_none();
+                    let __router_rest = ::std::iter::once(segment)
+                        .chain(segments.clone())
+                        .take(if __router_trailing_slash { 0 } else { usize::MAX });
+                    let parsed = <Vec<
+                        String,
+                    > as FromRouteSegments>::from_route_segments(__router_rest)
+                        .map_err(|err| RouteMatchError::File {
+                            error: FileParseError::pathParseError(err),
+                            span: segments.span().start..segments.end(),
+                        });
+                    match parsed {
+                        Ok(path) => {
+                            let parsed = match __router_fragment {
+                                Some((start, fragment)) if !fragment.is_empty() => {
+                                    match decode_path_segment(fragment) {
+                                        Some(decoded) => {
+                                            <u32 as ::std::str::FromStr>::from_str(&decoded)
+                                                .map(Some)
+                                                .map_err(|err| RouteMatchError::File {
+                                                    error: FileParseError::lineParseError(
+                                                        SegmentError::from_parse_error(fragment, err),
+                                                    ),
+                                                    span: start..start + fragment.len() + 1,
+                                                })
+                                        }
+                                        None => {
+                                            Err(RouteMatchError::File {
+                                                error: FileParseError::lineParseError(
+                                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                                ),
+                                                span: start..start + fragment.len() + 1,
+                                            })
+                                        }
+                                    }
+                                }
+                                _ => {
+                                    (&MissingFragment::<Option<u32>>::new())
+                                        .missing_fragment()
+                                        .ok_or(RouteMatchError::File {
+                                            error: FileParseError::MissingFragment,
+                                            span: segments.end()..segments.end(),
+                                        })
+                                }
+                            };
+                            match parsed {
+                                Ok(line) => {
+                                    return Ok(Route::File { path, line });
+                                }
+                                Err(err) => errors.push(err),
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::File {
+                        error: FileParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+        }
+        Err(RouteParseError {
+            attempted_routes: errors,
+            limit: None,
+        })
+    }
+}
+impl ::std::fmt::Display for Route {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        Routable::write_path(self, f)
+    }
+}
+impl Routable for Route {
+    fn render<'a>(self, cx: &'a ScopeState) -> Element<'a> {
+        match self {
+            Self::Home {} => {
+                render! {
+                    Home {}
+                }
+            }
+            Self::Docs { page, section } => {
+                render! {
+                    Docs { page : page, section : section, }
+                }
+            }
+            Self::Search { query, result } => {
+                render! {
+                    Search { query : query, result : result, }
+                }
+            }
+            Self::File { path, line } => {
+                render! {
+                    File { path : path, line : line, }
+                }
+            }
+        }
+    }
+    fn write_path(&self, f: &mut impl ::std::fmt::Write) -> ::std::fmt::Result {
+        match self {
+            Self::Home {} => {
+                write!(f, "/{}", "")?;
+            }
+            Self::Docs { page, section } => {
+                write!(f, "/{}", "docs")?;
+                debug_assert!(
+                    ! DisplayRouteSegment(page).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(page)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(page)))?;
+                if let Some(fragment) = section {
+                    write!(f, "#{}", EncodedSegment(fragment))?;
+                }
+            }
+            Self::Search { query, result } => {
+                write!(f, "/{}", "search")?;
+                if ToQuery::has_query(query) {
+                    write!(f, "?{}", DisplayQuery(query))?;
+                }
+                write!(f, "#{}", EncodedSegment(result))?;
+            }
+            Self::File { path, line } => {
+                write!(f, "/{}", "files")?;
+                if ToRouteSegments::has_route_segments(path) {
+                    write!(f, "/")?;
+                    write!(f, "{}", DisplayRouteSegments(path))?;
+                }
+                if let Some(fragment) = line {
+                    write!(f, "#{}", EncodedSegment(fragment))?;
+                }
+            }
+        }
+        Ok(())
+    }
+    fn matched_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Home {} => vec![],
+            Self::Docs { page, section } => {
+                vec![
+                    (stringify!(page), DisplayRouteSegment(page).to_string()),
+                    (stringify!(section), section.as_ref().map(ToString::to_string)
+                    .unwrap_or_default())
+                ]
+            }
+            Self::Search { query, result } => {
+                vec![
+                    (stringify!(query), ToQuery::to_query_string(query)),
+                    (stringify!(result), result.to_string())
+                ]
+            }
+            Self::File { path, line } => {
+                vec![
+                    (stringify!(path), ToRouteSegments::to_route_segments_string(path)),
+                    (stringify!(line), line.as_ref().map(ToString::to_string)
+                    .unwrap_or_default())
+                ]
+            }
+        }
+    }
+    fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        Self::params_diff(self, other)
+    }
+    fn differing_fields(&self, other: &Self) -> Option<Vec<&'static str>> {
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Home {}, Self::Home {}) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                Some(fields)
+            }
+            (
+                Self::Docs { page: self_page, section: self_section },
+                Self::Docs { page: other_page, section: other_section },
+            ) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_page != other_page {
+                    fields.push(stringify!(page));
+                }
+                if self_section != other_section {
+                    fields.push(stringify!(section));
+                }
+                Some(fields)
+            }
+            (
+                Self::Search { query: self_query, result: self_result },
+                Self::Search { query: other_query, result: other_result },
+            ) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_query != other_query {
+                    fields.push(stringify!(query));
+                }
+                if self_result != other_result {
+                    fields.push(stringify!(result));
+                }
+                Some(fields)
+            }
+            (
+                Self::File { path: self_path, line: self_line },
+                Self::File { path: other_path, line: other_line },
+            ) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_path != other_path {
+                    fields.push(stringify!(path));
+                }
+                if self_line != other_line {
+                    fields.push(stringify!(line));
+                }
+                Some(fields)
+            }
+            _ => None,
+        }
+    }
+    fn static_routes() -> Vec<Self> {
+        Self::static_routes()
+    }
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Home { .. } => "/",
+            Self::Docs { .. } => "/docs/(page)#(section)",
+            Self::Search { .. } => "/search?(query)#(result)",
+            Self::File { .. } => "/files/(...path)#(line)",
+        }
+    }
+    fn route_patterns() -> &'static [&'static str] {
+        Self::ROUTE_PATTERNS
+    }
+    fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        Self::from_pattern_and_params(pattern, params)
+    }
+    fn action(&self) -> Option<fn(&mut Router<Self>)> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn status_hint(&self) -> u16 {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => 200,
+        }
+    }
+    fn cache_hint(&self) -> Option<&'static str> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn parses_query() -> bool {
+        true
+    }
+    fn includes_query(&self) -> bool {
+        matches!(self, Self::Search { .. })
+    }
+    fn parses_fragment() -> bool {
+        true
+    }
+    fn set_fragment(&mut self, __router_fragment: Option<&str>) {
+        #[allow(unreachable_patterns)]
+        match self {
+            Self::Docs { section, .. } => {
+                let value = match __router_fragment {
+                    Some(fragment) if !fragment.is_empty() => {
+                        decode_path_segment(fragment)
+                            .and_then(|decoded| {
+                                <String as ::std::str::FromStr>::from_str(&decoded)
+                                    .map(Some)
+                                    .ok()
+                            })
+                    }
+                    _ => (&MissingFragment::<Option<String>>::new()).missing_fragment(),
+                };
+                if let Some(value) = value {
+                    *section = value;
+                }
+            }
+            Self::Search { result, .. } => {
+                let value = match __router_fragment {
+                    Some(fragment) if !fragment.is_empty() => {
+                        decode_path_segment(fragment)
+                            .and_then(|decoded| {
+                                <u32 as ::std::str::FromStr>::from_str(&decoded).ok()
+                            })
+                    }
+                    _ => (&MissingFragment::<u32>::new()).missing_fragment(),
+                };
+                if let Some(value) = value {
+                    *result = value;
+                }
+            }
+            Self::File { line, .. } => {
+                let value = match __router_fragment {
+                    Some(fragment) if !fragment.is_empty() => {
+                        decode_path_segment(fragment)
+                            .and_then(|decoded| {
+                                <u32 as ::std::str::FromStr>::from_str(&decoded)
+                                    .map(Some)
+                                    .ok()
+                            })
+                    }
+                    _ => (&MissingFragment::<Option<u32>>::new()).missing_fragment(),
+                };
+                if let Some(value) = value {
+                    *line = value;
+                }
+            }
+            _ => {}
+        }
+    }
+    fn fragment_id(&self) -> Option<String> {
+        #[allow(unreachable_patterns)]
+        match self {
+            Self::Docs { section, .. } => section.as_ref().map(ToString::to_string),
+            Self::Search { result, .. } => Some(result.to_string()),
+            Self::File { line, .. } => line.as_ref().map(ToString::to_string),
+            _ => None,
+        }
+    }
+    fn palette_routes() -> Vec<Self> {
+        let mut routes = Vec::new();
+        routes.push(Self::Home {});
+        routes
+    }
+    fn parse_limits() -> ParseLimits {
+        ParseLimits {
+            max_path_len: 8192,
+            max_segments: 256,
+        }
+    }
+    fn rejected(limit: ParseLimit) -> <Self as ::std::str::FromStr>::Err {
+        RouteParseError::<RouteMatchError>::limit(limit).into()
+    }
+    fn head(&self) -> HeadMeta {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => HeadMeta::default(),
+        }
+    }
+}
+impl Route {
+    /// The path of this route. The same as its `Display` output.
+    pub fn to_path(&self) -> String {
+        let mut path = String::new();
+        Routable::write_path(self, &mut path).expect("a segment failed to display");
+        path
+    }
+    /// Parse a route from a path. The same as parsing it with [`std::str::FromStr`].
+    pub fn from_path(path: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        <Self as ::std::str::FromStr>::from_str(path)
+    }
+}
+impl Route {
+    /// The pattern of the variant called `name`, like the pattern of a route a server handles
+    /// separately. Returns `None` if no variant has that name.
+    pub fn pattern_of_variant(name: &str) -> Option<&'static str> {
+        match name {
+            "Home" => Some("/"),
+            "Docs" => Some("/docs/(page)#(section)"),
+            "Search" => Some("/search?(query)#(result)"),
+            "File" => Some("/files/(...path)#(line)"),
+            _ => None,
+        }
+    }
+    /// Find the pattern of the route a path corresponds to without parsing any dynamic segments.
+    ///
+    /// Dynamic segments match any value here, so a path can resolve to a pattern even if its segments
+    /// would fail to parse as the route's fields.
+    #[allow(unused_variables)]
+    pub fn match_pattern(path: &str) -> Option<&'static str> {
+        let mut segments = path.strip_prefix('/').unwrap_or(path).split('/');
+        if let Some(segment) = segments.next() {
+            if "" == segment {
+                let mut segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                if let (None, _) | (Some(""), None)
+                    = (segments_clone.next(), segments_clone.next()) {
+                    return Some("/");
+                }
+                if let Some(segment) = segments.next() {}
+            }
+            if "docs" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments_clone = segments.clone();
+                    if let (None, _) | (Some(""), None)
+                        = (segments_clone.next(), segments_clone.next()) {
+                        return Some("/docs/(page)#(section)");
+                    }
+                }
+            }
+            if "search" == segment {
+                let mut segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                if let (None, _) | (Some(""), None)
+                    = (segments_clone.next(), segments_clone.next()) {
+                    return Some("/search?(query)#(result)");
+                }
+                if let Some(segment) = segments.next() {}
+            }
+            if "files" == segment {
+                let mut segments = segments.clone();
+                if segments.clone().next().is_none() {
+                    return Some("/files/(...path)#(line)");
+                }
+                if let Some(segment) = segments.next() {
+                    return Some("/files/(...path)#(line)");
+                }
+            }
+        }
+        None
+    }
+    /// Build a route from one of the patterns in [`Self::ROUTE_PATTERNS`] and the values of its
+    /// dynamic segments, like routes stored as data in a CMS.
+    ///
+    /// Parameters are looked up by the name of their segment and are parsed as they are, without
+    /// percent-decoding. A catch-all parameter is split on `/`. Parameters the pattern doesn't use
+    /// are ignored.
+    pub fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        #[allow(unused_variables)]
+        let param = |name: &'static str| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+                .ok_or(BuildError::MissingParam(name))
+        };
+        match pattern {
+            "/" => Ok(Route::Home {}),
+            "/docs/(page)#(section)" => {
+                let page = {
+                    let value = param(stringify!(page))?;
+                    <String as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(page),
+                            error: err.to_string(),
+                        })?
+                };
+                let section = match param(stringify!(section)) {
+                    Ok(value) if !value.is_empty() => {
+                        <String as ::std::str::FromStr>::from_str(value)
+                            .map(Some)
+                            .map_err(|err| BuildError::InvalidParam {
+                                name: stringify!(section),
+                                error: err.to_string(),
+                            })?
+                    }
+                    _ => {
+                        (&MissingFragment::<Option<String>>::new())
+                            .missing_fragment()
+                            .ok_or(BuildError::MissingParam(stringify!(section)))?
+                    }
+                };
+                Ok(Route::Docs { page, section })
+            }
+            "/search?(query)#(result)" => {
+                let query = {
+                    let value = param(stringify!(query))?;
+                    <String as FromQuery>::from_query(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(query),
+                            error: err.to_string(),
+                        })?
+                };
+                let result = match param(stringify!(result)) {
+                    Ok(value) if !value.is_empty() => {
+                        <u32 as ::std::str::FromStr>::from_str(value)
+                            .map_err(|err| BuildError::InvalidParam {
+                                name: stringify!(result),
+                                error: err.to_string(),
+                            })?
+                    }
+                    _ => {
+                        (&MissingFragment::<u32>::new())
+                            .missing_fragment()
+                            .ok_or(BuildError::MissingParam(stringify!(result)))?
+                    }
+                };
+                Ok(Route::Search { query, result })
+            }
+            "/files/(...path)#(line)" => {
+                let path = {
+                    let value = param(stringify!(path))?;
+                    <Vec<
+                        String,
+                    > as FromRouteSegments>::from_route_segments(
+                            value
+                                .split('/')
+                                .take(if value.is_empty() { 0 } else { usize::MAX }),
+                        )
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(path),
+                            error: err.to_string(),
+                        })?
+                };
+                let line = match param(stringify!(line)) {
+                    Ok(value) if !value.is_empty() => {
+                        <u32 as ::std::str::FromStr>::from_str(value)
+                            .map(Some)
+                            .map_err(|err| BuildError::InvalidParam {
+                                name: stringify!(line),
+                                error: err.to_string(),
+                            })?
+                    }
+                    _ => {
+                        (&MissingFragment::<Option<u32>>::new())
+                            .missing_fragment()
+                            .ok_or(BuildError::MissingParam(stringify!(line)))?
+                    }
+                };
+                Ok(Route::File { path, line })
+            }
+            _ => Err(BuildError::UnknownPattern(pattern.to_string())),
+        }
+    }
+    /// The route pattern of every variant in the order the parser tries them.
+    ///
+    /// Only patterns that parse into a variant are listed here. Patterns that are never
+    /// matched directly (like redirects or fallbacks) belong in their own constants. Routes
+    /// with `feature = "..."` are listed in every build; check `gated_routes` for the ones
+    /// that are compiled out.
+    pub const ROUTE_PATTERNS: &'static [&'static str] = &[
+        "/",
+        "/docs/(page)#(section)",
+        "/search?(query)#(result)",
+        "/files/(...path)#(line)",
+    ];
+    /// The patterns in [`Self::ROUTE_PATTERNS`] of routes that run an action instead of rendering a
+    /// page. Tools that list pages, like site maps, should leave these out.
+    pub const ACTION_ROUTE_PATTERNS: &'static [&'static str] = &[];
+    /// Every route with the name and type of its segments as JSON, and the locale of localized
+    /// patterns as `hreflang`. Save it as the `#[routable(baseline = "...")]` file to check later
+    /// versions of the routes against this one.
+    pub const ROUTE_TABLE_JSON: &'static str = "{\n  \"routes\": [\n    {\"variant\": \"Home\", \"pattern\": \"/\", \"segments\": []},\n    {\"variant\": \"Docs\", \"pattern\": \"/docs/(page)#(section)\", \"segments\": [{\"name\": \"page\", \"type\": \"String\"}, {\"name\": \"section\", \"type\": \"Option<String>\"}]},\n    {\"variant\": \"Search\", \"pattern\": \"/search?(query)#(result)\", \"segments\": [{\"name\": \"query\", \"type\": \"String\"}, {\"name\": \"result\", \"type\": \"u32\"}]},\n    {\"variant\": \"File\", \"pattern\": \"/files/(...path)#(line)\", \"segments\": [{\"name\": \"path\", \"type\": \"Vec<String>\"}, {\"name\": \"line\", \"type\": \"Option<u32>\"}]}\n  ]\n}\n";
+    /// A hash of the routes that changes when a pattern, the type of a segment or the metadata of a route
+    /// changes. Put it in asset URLs to invalidate cached pages when the routes change; the precache
+    /// manifest uses it as the revision of every page.
+    pub const ROUTES_HASH: &'static str = "6803bce1b1c26895";
+    /// Split the pages into the paths a service worker can cache ahead of time and the patterns that
+    /// must always be fetched from the network.
+    ///
+    /// Only routes without dynamic segments whose `cache = "..."` hint allows shared caching are
+    /// precached. Every other page is network only, with dynamic segments written as `*` and
+    /// catch-alls as `**`. Action routes are in neither list.
+    pub fn precache_manifest() -> PrecacheManifest {
+        PrecacheManifest {
+            precache: vec![],
+            revision: Self::ROUTES_HASH,
+            network_only_patterns: vec!["/", "/docs/*", "/search", "/files/**"],
+        }
+    }
+}
+///The paths of the routes of [`Route`] without dynamic segments, like `Route::Variant` as `VARIANT_PATH`
+#[allow(dead_code)]
+pub mod route_paths {
+    ///The path of [`Route::Home`]
+    pub const HOME_PATH: &str = "/";
+}
+impl Route {
+    /// Every variant with its pattern and the segments of it, in the order they are declared. Routes
+    /// behind a feature are listed even when the feature is off, like in [`Self::ROUTE_PATTERNS`].
+    pub const SITE_MAP: &'static [RouteInfo] = &[
+        RouteInfo {
+            variant: "Home",
+            pattern: "/",
+            segments: &[SegmentInfo::Static("")],
+        },
+        RouteInfo {
+            variant: "Docs",
+            pattern: "/docs/(page)#(section)",
+            segments: &[
+                SegmentInfo::Static("docs"),
+                SegmentInfo::Dynamic("page", "String"),
+                SegmentInfo::Fragment("section", "Option<String>"),
+            ],
+        },
+        RouteInfo {
+            variant: "Search",
+            pattern: "/search?(query)#(result)",
+            segments: &[
+                SegmentInfo::Static("search"),
+                SegmentInfo::Query("query", "String"),
+                SegmentInfo::Fragment("result", "u32"),
+            ],
+        },
+        RouteInfo {
+            variant: "File",
+            pattern: "/files/(...path)#(line)",
+            segments: &[
+                SegmentInfo::Static("files"),
+                SegmentInfo::CatchAll("path", "Vec<String>"),
+                SegmentInfo::Fragment("line", "Option<u32>"),
+            ],
+        },
+    ];
+    /// Every route without dynamic segments, like the pages a static site generator can render ahead of
+    /// time. Routes with a dynamic or catch-all segment, a query or a fragment are left out.
+    #[allow(unused_mut, clippy::vec_init_then_push)]
+    pub fn static_routes() -> Vec<Self> {
+        let mut routes = Vec::new();
+        routes.push(Route::Home {});
+        routes
+    }
+}
+impl Route {
+    /// Check if two routes are the same variant, ignoring the values of their parameters.
+    pub fn same_variant(&self, other: &Self) -> bool {
+        ::std::mem::discriminant(self) == ::std::mem::discriminant(other)
+    }
+    /// List the parameters whose serialized values differ between two routes of the same
+    /// variant as `(name, self_value, other_value)`. Routes of different variants have no
+    /// comparable parameters and produce an empty list.
+    pub fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        let mut diff = Vec::new();
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Home {}, Self::Home {}) => {}
+            (
+                Self::Docs { page: self_page, section: self_section },
+                Self::Docs { page: other_page, section: other_section },
+            ) => {
+                let self_value = DisplayRouteSegment(self_page).to_string();
+                let other_value = DisplayRouteSegment(other_page).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(page), self_value, other_value));
+                }
+                let self_value = self_section
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                let other_value = other_section
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                if self_value != other_value {
+                    diff.push((stringify!(section), self_value, other_value));
+                }
+            }
+            (
+                Self::Search { query: self_query, result: self_result },
+                Self::Search { query: other_query, result: other_result },
+            ) => {
+                let self_value = ToQuery::to_query_string(self_query);
+                let other_value = ToQuery::to_query_string(other_query);
+                if self_value != other_value {
+                    diff.push((stringify!(query), self_value, other_value));
+                }
+                let self_value = self_result.to_string();
+                let other_value = other_result.to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(result), self_value, other_value));
+                }
+            }
+            (
+                Self::File { path: self_path, line: self_line },
+                Self::File { path: other_path, line: other_line },
+            ) => {
+                let self_value = ToRouteSegments::to_route_segments_string(self_path);
+                let other_value = ToRouteSegments::to_route_segments_string(other_path);
+                if self_value != other_value {
+                    diff.push((stringify!(path), self_value, other_value));
+                }
+                let self_value = self_line
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                let other_value = other_line
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                if self_value != other_value {
+                    diff.push((stringify!(line), self_value, other_value));
+                }
+            }
+            _ => {}
+        }
+        diff
+    }
+    /// The number of segments in the path of this route. A catch-all counts every segment it holds.
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Home { .. } => 0usize,
+            Self::Docs { .. } => 2usize,
+            Self::Search { .. } => 1usize,
+            Self::File { path, .. } => {
+                1usize + ToRouteSegments::route_segment_count(path)
+            }
+        }
+    }
+    /// The first static segment in the pattern of this route, if it has one.
+    pub fn section(&self) -> Option<&'static str> {
+        match self {
+            Self::Home { .. } => None,
+            Self::Docs { .. } => Some("docs"),
+            Self::Search { .. } => Some("search"),
+            Self::File { .. } => Some("files"),
+        }
+    }
+}
+/// Lets a route be used directly as an attribute value, like `a { href: &route }`.
+///
+/// The route is written straight into the scope's bump allocator instead of an intermediate `String`.
+impl<'a> IntoAttributeValue<'a> for &Route {
+    fn into_value(
+        self,
+        bump: &'a dioxus::core::exports::bumpalo::Bump,
+    ) -> dioxus::core::AttributeValue<'a> {
+        format_args!("{}", self).into_value(bump)
+    }
+}
+const _: () = {
+    fn __router_segment_value<T: SegmentValue>() {}
+    fn __router_check_segments() {
+        __router_segment_value::<String>();
+    }
+};
@@ -0,0 +1,1017 @@
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum ItemParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    idParseError(SegmentError<<u64 as FromRouteSegment>::Err>),
+}
+impl ::std::fmt::Display for ItemParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "items")?
+            }
+            Self::idParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(id), stringify!(u64),
+                    err
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for ItemParseError {}
+impl ItemParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(self, Self::idParseError(SegmentError::DecodeError(_)))
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum FilteredItemParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    filtersParseError(SegmentError<<MatrixParams as FromMatrix>::Err>),
+    idParseError(SegmentError<<u64 as FromRouteSegment>::Err>),
+}
+impl ::std::fmt::Display for FilteredItemParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "items")?
+            }
+            Self::filtersParseError(err) => {
+                write!(
+                    f, "Matrix params ';({}:{})' {}", stringify!(filters),
+                    stringify!(MatrixParams), err
+                )?
+            }
+            Self::idParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(id), stringify!(u64),
+                    err
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for FilteredItemParseError {}
+impl FilteredItemParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(
+            self, Self::filtersParseError(SegmentError::DecodeError(_)) |
+            Self::idParseError(SegmentError::DecodeError(_))
+        )
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum VariantParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    colorParseError(SegmentError<<String as FromRouteSegment>::Err>),
+    optionsParseError(SegmentError<<MatrixParams as FromMatrix>::Err>),
+}
+impl ::std::fmt::Display for VariantParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "variants")?
+            }
+            Self::colorParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(color),
+                    stringify!(String), err
+                )?
+            }
+            Self::optionsParseError(err) => {
+                write!(
+                    f, "Matrix params ';({}:{})' {}", stringify!(options),
+                    stringify!(MatrixParams), err
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for VariantParseError {}
+impl VariantParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(
+            self, Self::colorParseError(SegmentError::DecodeError(_)) |
+            Self::optionsParseError(SegmentError::DecodeError(_))
+        )
+    }
+}
+#[derive(Debug, PartialEq)]
+pub enum RouteMatchError {
+    Item { error: ItemParseError, span: ::std::ops::Range<usize> },
+    FilteredItem { error: FilteredItemParseError, span: ::std::ops::Range<usize> },
+    Variant { error: VariantParseError, span: ::std::ops::Range<usize> },
+}
+impl ::std::error::Error for RouteMatchError {
+    fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+        match self {
+            Self::Item { error, .. } => Some(error),
+            Self::FilteredItem { error, .. } => Some(error),
+            Self::Variant { error, .. } => Some(error),
+        }
+    }
+}
+impl ::std::fmt::Display for RouteMatchError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::Item { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Item),
+                    "/items/(id)", error
+                )?
+            }
+            Self::FilteredItem { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(FilteredItem),
+                    "/items;(filters)/(id)", error
+                )?
+            }
+            Self::Variant { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Variant),
+                    "/variants/(color);(options)", error
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl RouteMatchError {
+    /// The byte range of the parsed input that failed to match. The range refers to the original
+    /// input, before any segment was percent-decoded.
+    pub fn span(&self) -> ::std::ops::Range<usize> {
+        match self {
+            Self::Item { span, .. } => span.clone(),
+            Self::FilteredItem { span, .. } => span.clone(),
+            Self::Variant { span, .. } => span.clone(),
+        }
+    }
+    /// Render the input with a line of carets under the part that failed to match.
+    pub fn highlight(&self, input: &str) -> String {
+        let span = self.span();
+        let start = input.get(..span.start).map_or(0, |before| before.chars().count());
+        let len = input.get(span).map_or(0, |failed| failed.chars().count());
+        format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(len.max(1)))
+    }
+}
+impl AttemptedRoute for RouteMatchError {
+    fn span(&self) -> ::std::ops::Range<usize> {
+        Self::span(self)
+    }
+    fn is_decode_error(&self) -> bool {
+        match self {
+            Self::Item { error, .. } => error.is_decode_error(),
+            Self::FilteredItem { error, .. } => error.is_decode_error(),
+            Self::Variant { error, .. } => error.is_decode_error(),
+        }
+    }
+}
+impl<'a> TryFrom<&'a str> for Route {
+    type Error = <Self as ::std::str::FromStr>::Err;
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+impl ::std::str::FromStr for Route {
+    type Err = RouteParseError<RouteMatchError>;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_path(s)
+    }
+}
+impl Route {
+    fn parse_path(s: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if s.len() > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len: s.len(),
+                    max: 8192,
+                }),
+            );
+        }
+        let origin = url_path_start(s);
+        let s = &s[origin..];
+        let s = s.split_once('#').map_or(s, |(rest, _)| rest);
+        let s = s.split_once('?').map_or(s, |(path, _)| path);
+        let (path, offset) = match s.strip_prefix('/') {
+            Some(path) => (path, origin + 1),
+            None => (s, origin),
+        };
+        Self::match_segments(SegmentCursor::new(path.split('/'), offset))
+    }
+    /// Parse a route from a path that is already split into segments, like the path parts extracted
+    /// by an HTTP framework. The segments must not include the leading slash; no segments at all
+    /// matches the root route.
+    ///
+    /// This behaves exactly like parsing the joined path with [`std::str::FromStr`]. Spans in the
+    /// errors refer to the joined path.
+    pub fn from_segments<'a>(
+        segments: impl Iterator<Item = &'a str> + Clone,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().next().is_none() {
+            return Self::match_segments(SegmentCursor::new(::std::iter::once(""), 1));
+        }
+        Self::match_segments(SegmentCursor::new(segments, 1))
+    }
+    /// Parse a location under the base path the app is served from, like `/app/blog/1` under `/app`.
+    /// A slash at the end of the base is ignored, and locations outside of it, like `/application`,
+    /// are rejected with [`ParseLimit::OutsideBase`] without trying any route.
+    pub fn parse_with_base(
+        location: &str,
+        base: &str,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        match strip_base_path(base, location) {
+            Some(location) => location.parse(),
+            None => {
+                Err(
+                    RouteParseError::limit(ParseLimit::OutsideBase {
+                        base: base.to_string(),
+                    }),
+                )
+            }
+        }
+    }
+    fn match_segments<'a>(
+        mut segments: SegmentCursor<impl Iterator<Item = &'a str> + Clone>,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().take(256 + 1).count() > 256 {
+            return Err(
+                RouteParseError::limit(ParseLimit::TooManySegments {
+                    max: 256,
+                }),
+            );
+        }
+        let len = segments.end();
+        if len > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len,
+                    max: 8192,
+                }),
+            );
+        }
+        let mut errors = Vec::new();
+        if let Some(segment) = segments.next() {
+            if "items" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() => {
+                            Err(RouteMatchError::Item {
+                                error: ItemParseError::EmptySegment(stringify!(id)),
+                                span: segments.span(),
+                            })
+                        }
+                        Some(decoded) => {
+                            <u64 as FromRouteSegment>::from_route_segment(&decoded)
+                                .map_err(|err| RouteMatchError::Item {
+                                    error: ItemParseError::idParseError(
+                                        SegmentError::from_parse_error(segment, err),
+                                    ),
+                                    span: segments.span(),
+                                })
+                        }
+                        None => {
+                            Err(RouteMatchError::Item {
+                                error: ItemParseError::idParseError(
+                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                    };
+                    match parsed {
+                        Ok(id) => {
+                            let remaining_segments = segments.clone();
+                            let mut segments_clone = segments.clone();
+                            let next_segment = segments_clone.next();
+                            let segment_after_next = segments_clone.next();
+                            match (next_segment, segment_after_next) {
+                                (None, _) | (Some(""), None) => {
+                                    return Ok(Route::Item { id });
+                                }
+                                _ => {
+                                    let span = remaining_segments.rest_span();
+                                    let mut trailing = String::new();
+                                    for seg in remaining_segments {
+                                        trailing += seg;
+                                        trailing += "/";
+                                    }
+                                    trailing.pop();
+                                    errors
+                                        .push(RouteMatchError::Item {
+                                            error: ItemParseError::ExtraSegments(trailing),
+                                            span,
+                                        })
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::Item {
+                        error: ItemParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "variants" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let parsed = {
+                        let (segment, __router_matrix) = split_matrix(segment);
+                        let parsed = match decode_path_segment(segment) {
+                            _ if segment.is_empty() => {
+                                Err(RouteMatchError::Variant {
+                                    error: VariantParseError::EmptySegment(stringify!(color)),
+                                    span: segments.span(),
+                                })
+                            }
+                            Some(decoded) => {
+                                <String as FromRouteSegment>::from_route_segment(&decoded)
+                                    .map_err(|err| RouteMatchError::Variant {
+                                        error: VariantParseError::colorParseError(
+                                            SegmentError::from_parse_error(segment, err),
+                                        ),
+                                        span: segments.span(),
+                                    })
+                            }
+                            None => {
+                                Err(RouteMatchError::Variant {
+                                    error: VariantParseError::colorParseError(
+                                        SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                    ),
+                                    span: segments.span(),
+                                })
+                            }
+                        };
+                        parsed
+                            .and_then(|value| {
+                                let parsed = parse_matrix::<MatrixParams>(__router_matrix)
+                                    .map_err(|err| RouteMatchError::Variant {
+                                        error: VariantParseError::optionsParseError(err),
+                                        span: segments.span(),
+                                    });
+                                parsed.map(|matrix| (value, matrix))
+                            })
+                    };
+                    match parsed {
+                        Ok((color, options)) => {
+                            let remaining_segments = segments.clone();
+                            let mut segments_clone = segments.clone();
+                            let next_segment = segments_clone.next();
+                            let segment_after_next = segments_clone.next();
+                            match (next_segment, segment_after_next) {
+                                (None, _) | (Some(""), None) => {
+                                    return Ok(Route::Variant { color, options });
+                                }
+                                _ => {
+                                    let span = remaining_segments.rest_span();
+                                    let mut trailing = String::new();
+                                    for seg in remaining_segments {
+                                        trailing += seg;
+                                        trailing += "/";
+                                    }
+                                    trailing.pop();
+                                    errors
+                                        .push(RouteMatchError::Variant {
+                                            error: VariantParseError::ExtraSegments(trailing),
+                                            span,
+                                        })
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::Variant {
+                        error: VariantParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            let parsed = {
+                let (segment, __router_matrix) = split_matrix(segment);
+                let parsed = if segment == "items" {
+                    Ok(())
+                } else {
+                    Err(RouteMatchError::FilteredItem {
+                        error: FilteredItemParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+                };
+                parsed
+                    .and_then(|value| {
+                        let parsed = parse_matrix::<MatrixParams>(__router_matrix)
+                            .map_err(|err| RouteMatchError::FilteredItem {
+                                error: FilteredItemParseError::filtersParseError(err),
+                                span: segments.span(),
+                            });
+                        parsed.map(|matrix| (value, matrix))
+                    })
+            };
+            match parsed {
+                Ok((_, filters)) => {
+                    let mut segments = segments.clone();
+                    if let Some(segment) = segments.next() {
+                        let parsed = match decode_path_segment(segment) {
+                            _ if segment.is_empty() => {
+                                Err(RouteMatchError::FilteredItem {
+                                    error: FilteredItemParseError::EmptySegment(stringify!(id)),
+                                    span: segments.span(),
+                                })
+                            }
+                            Some(decoded) => {
+                                <u64 as FromRouteSegment>::from_route_segment(&decoded)
+                                    .map_err(|err| RouteMatchError::FilteredItem {
+                                        error: FilteredItemParseError::idParseError(
+                                            SegmentError::from_parse_error(segment, err),
+                                        ),
+                                        span: segments.span(),
+                                    })
+                            }
+                            None => {
+                                Err(RouteMatchError::FilteredItem {
+                                    error: FilteredItemParseError::idParseError(
+                                        SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                    ),
+                                    span: segments.span(),
+                                })
+                            }
+                        };
+                        match parsed {
+                            Ok(id) => {
+                                let remaining_segments = segments.clone();
+                                let mut segments_clone = segments.clone();
+                                let next_segment = segments_clone.next();
+                                let segment_after_next = segments_clone.next();
+                                match (next_segment, segment_after_next) {
+                                    (None, _) | (Some(""), None) => {
+                                        return Ok(Route::FilteredItem { filters, id });
+                                    }
+                                    _ => {
+                                        let span = remaining_segments.rest_span();
+                                        let mut trailing = String::new();
+                                        for seg in remaining_segments {
+                                            trailing += seg;
+                                            trailing += "/";
+                                        }
+                                        trailing.pop();
+                                        errors
+                                            .push(RouteMatchError::FilteredItem {
+                                                error: FilteredItemParseError::ExtraSegments(trailing),
+                                                span,
+                                            })
+                                    }
+                                }
+                            }
+                            Err(err) => {
+                                errors.push(err);
+                            }
+                        }
+                    }
+                }
+                Err(err) => {
+                    errors.push(err);
+                }
+            }
+        }
+        Err(RouteParseError {
+            attempted_routes: errors,
+            limit: None,
+        })
+    }
+}
+impl ::std::fmt::Display for Route {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        Routable::write_path(self, f)
+    }
+}
+impl Routable for Route {
+    fn render<'a>(self, cx: &'a ScopeState) -> Element<'a> {
+        match self {
+            Self::Item { id } => {
+                render! {
+                    Item { id : id, }
+                }
+            }
+            Self::FilteredItem { filters, id } => {
+                render! {
+                    FilteredItem { filters : filters, id : id, }
+                }
+            }
+            Self::Variant { color, options } => {
+                render! {
+                    Variant { color : color, options : options, }
+                }
+            }
+        }
+    }
+    fn write_path(&self, f: &mut impl ::std::fmt::Write) -> ::std::fmt::Result {
+        match self {
+            Self::Item { id } => {
+                write!(f, "/{}", "items")?;
+                debug_assert!(
+                    ! DisplayRouteSegment(id).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(id)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(id)))?;
+            }
+            Self::FilteredItem { filters, id } => {
+                write!(f, "/{}", "items")?;
+                write!(f, "{}", DisplayMatrix(filters))?;
+                debug_assert!(
+                    ! DisplayRouteSegment(id).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(id)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(id)))?;
+            }
+            Self::Variant { color, options } => {
+                write!(f, "/{}", "variants")?;
+                debug_assert!(
+                    ! DisplayRouteSegment(color).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(color)
+                );
+                write!(
+                    f, "/{}", EncodedSegmentWith(& DisplayRouteSegment(color),
+                    EncodeSet::Unreserved)
+                )?;
+                write!(f, "{}", DisplayMatrix(options))?;
+            }
+        }
+        Ok(())
+    }
+    fn matched_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Item { id } => {
+                vec![(stringify!(id), DisplayRouteSegment(id).to_string())]
+            }
+            Self::FilteredItem { filters, id } => {
+                vec![
+                    (stringify!(filters), matrix_string(filters)), (stringify!(id),
+                    DisplayRouteSegment(id).to_string())
+                ]
+            }
+            Self::Variant { color, options } => {
+                vec![
+                    (stringify!(color), DisplayRouteSegment(color).to_string()),
+                    (stringify!(options), matrix_string(options))
+                ]
+            }
+        }
+    }
+    fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        Self::params_diff(self, other)
+    }
+    fn differing_fields(&self, other: &Self) -> Option<Vec<&'static str>> {
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Item { id: self_id }, Self::Item { id: other_id }) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_id != other_id {
+                    fields.push(stringify!(id));
+                }
+                Some(fields)
+            }
+            (
+                Self::FilteredItem { filters: self_filters, id: self_id },
+                Self::FilteredItem { filters: other_filters, id: other_id },
+            ) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_filters != other_filters {
+                    fields.push(stringify!(filters));
+                }
+                if self_id != other_id {
+                    fields.push(stringify!(id));
+                }
+                Some(fields)
+            }
+            (
+                Self::Variant { color: self_color, options: self_options },
+                Self::Variant { color: other_color, options: other_options },
+            ) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_color != other_color {
+                    fields.push(stringify!(color));
+                }
+                if self_options != other_options {
+                    fields.push(stringify!(options));
+                }
+                Some(fields)
+            }
+            _ => None,
+        }
+    }
+    fn static_routes() -> Vec<Self> {
+        Self::static_routes()
+    }
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Item { .. } => "/items/(id)",
+            Self::FilteredItem { .. } => "/items;(filters)/(id)",
+            Self::Variant { .. } => "/variants/(color);(options)",
+        }
+    }
+    fn route_patterns() -> &'static [&'static str] {
+        Self::ROUTE_PATTERNS
+    }
+    fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        Self::from_pattern_and_params(pattern, params)
+    }
+    fn action(&self) -> Option<fn(&mut Router<Self>)> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn status_hint(&self) -> u16 {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => 200,
+        }
+    }
+    fn cache_hint(&self) -> Option<&'static str> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn parse_limits() -> ParseLimits {
+        ParseLimits {
+            max_path_len: 8192,
+            max_segments: 256,
+        }
+    }
+    fn rejected(limit: ParseLimit) -> <Self as ::std::str::FromStr>::Err {
+        RouteParseError::<RouteMatchError>::limit(limit).into()
+    }
+    fn head(&self) -> HeadMeta {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => HeadMeta::default(),
+        }
+    }
+}
+impl Route {
+    /// The path of this route. The same as its `Display` output.
+    pub fn to_path(&self) -> String {
+        let mut path = String::new();
+        Routable::write_path(self, &mut path).expect("a segment failed to display");
+        path
+    }
+    /// Parse a route from a path. The same as parsing it with [`std::str::FromStr`].
+    pub fn from_path(path: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        <Self as ::std::str::FromStr>::from_str(path)
+    }
+}
+impl Route {
+    /// The pattern of the variant called `name`, like the pattern of a route a server handles
+    /// separately. Returns `None` if no variant has that name.
+    pub fn pattern_of_variant(name: &str) -> Option<&'static str> {
+        match name {
+            "Item" => Some("/items/(id)"),
+            "FilteredItem" => Some("/items;(filters)/(id)"),
+            "Variant" => Some("/variants/(color);(options)"),
+            _ => None,
+        }
+    }
+    /// Find the pattern of the route a path corresponds to without parsing any dynamic segments.
+    ///
+    /// Dynamic segments match any value here, so a path can resolve to a pattern even if its segments
+    /// would fail to parse as the route's fields.
+    #[allow(unused_variables)]
+    pub fn match_pattern(path: &str) -> Option<&'static str> {
+        let mut segments = path.strip_prefix('/').unwrap_or(path).split('/');
+        if let Some(segment) = segments.next() {
+            if "items" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments_clone = segments.clone();
+                    if let (None, _) | (Some(""), None)
+                        = (segments_clone.next(), segments_clone.next()) {
+                        return Some("/items/(id)");
+                    }
+                }
+            }
+            if "variants" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments_clone = segments.clone();
+                    if let (None, _) | (Some(""), None)
+                        = (segments_clone.next(), segments_clone.next()) {
+                        return Some("/variants/(color);(options)");
+                    }
+                }
+            }
+            if split_matrix(segment).0 == "items" {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments_clone = segments.clone();
+                    if let (None, _) | (Some(""), None)
+                        = (segments_clone.next(), segments_clone.next()) {
+                        return Some("/items;(filters)/(id)");
+                    }
+                }
+            }
+        }
+        None
+    }
+    /// Build a route from one of the patterns in [`Self::ROUTE_PATTERNS`] and the values of its
+    /// dynamic segments, like routes stored as data in a CMS.
+    ///
+    /// Parameters are looked up by the name of their segment and are parsed as they are, without
+    /// percent-decoding. A catch-all parameter is split on `/`. Parameters the pattern doesn't use
+    /// are ignored.
+    pub fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        #[allow(unused_variables)]
+        let param = |name: &'static str| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+                .ok_or(BuildError::MissingParam(name))
+        };
+        match pattern {
+            "/items/(id)" => {
+                let id = {
+                    let value = param(stringify!(id))?;
+                    <u64 as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(id),
+                            error: err.to_string(),
+                        })?
+                };
+                Ok(Route::Item { id })
+            }
+            "/items;(filters)/(id)" => {
+                let filters = {
+                    let value = param(stringify!(filters))?;
+                    parse_matrix::<MatrixParams>(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(filters),
+                            error: err.to_string(),
+                        })?
+                };
+                let id = {
+                    let value = param(stringify!(id))?;
+                    <u64 as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(id),
+                            error: err.to_string(),
+                        })?
+                };
+                Ok(Route::FilteredItem { filters, id })
+            }
+            "/variants/(color);(options)" => {
+                let color = {
+                    let value = param(stringify!(color))?;
+                    <String as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(color),
+                            error: err.to_string(),
+                        })?
+                };
+                let options = {
+                    let value = param(stringify!(options))?;
+                    parse_matrix::<MatrixParams>(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(options),
+                            error: err.to_string(),
+                        })?
+                };
+                Ok(Route::Variant { color, options })
+            }
+            _ => Err(BuildError::UnknownPattern(pattern.to_string())),
+        }
+    }
+    /// The route pattern of every variant in the order the parser tries them.
+    ///
+    /// Only patterns that parse into a variant are listed here. Patterns that are never
+    /// matched directly (like redirects or fallbacks) belong in their own constants. Routes
+    /// with `feature = "..."` are listed in every build; check `gated_routes` for the ones
+    /// that are compiled out.
+    pub const ROUTE_PATTERNS: &'static [&'static str] = &[
+        "/items/(id)",
+        "/variants/(color);(options)",
+        "/items;(filters)/(id)",
+    ];
+    /// The patterns in [`Self::ROUTE_PATTERNS`] of routes that run an action instead of rendering a
+    /// page. Tools that list pages, like site maps, should leave these out.
+    pub const ACTION_ROUTE_PATTERNS: &'static [&'static str] = &[];
+    /// Every route with the name and type of its segments as JSON, and the locale of localized
+    /// patterns as `hreflang`. Save it as the `#[routable(baseline = "...")]` file to check later
+    /// versions of the routes against this one.
+    pub const ROUTE_TABLE_JSON: &'static str = "{\n  \"routes\": [\n    {\"variant\": \"Item\", \"pattern\": \"/items/(id)\", \"segments\": [{\"name\": \"id\", \"type\": \"u64\"}]},\n    {\"variant\": \"FilteredItem\", \"pattern\": \"/items;(filters)/(id)\", \"segments\": [{\"name\": \"filters\", \"type\": \"MatrixParams\"}, {\"name\": \"id\", \"type\": \"u64\"}]},\n    {\"variant\": \"Variant\", \"pattern\": \"/variants/(color);(options)\", \"segments\": [{\"name\": \"color\", \"type\": \"String\"}, {\"name\": \"options\", \"type\": \"MatrixParams\"}]}\n  ]\n}\n";
+    /// A hash of the routes that changes when a pattern, the type of a segment or the metadata of a route
+    /// changes. Put it in asset URLs to invalidate cached pages when the routes change; the precache
+    /// manifest uses it as the revision of every page.
+    pub const ROUTES_HASH: &'static str = "d7de83ec703ef423";
+    /// Split the pages into the paths a service worker can cache ahead of time and the patterns that
+    /// must always be fetched from the network.
+    ///
+    /// Only routes without dynamic segments whose `cache = "..."` hint allows shared caching are
+    /// precached. Every other page is network only, with dynamic segments written as `*` and
+    /// catch-alls as `**`. Action routes are in neither list.
+    pub fn precache_manifest() -> PrecacheManifest {
+        PrecacheManifest {
+            precache: vec![],
+            revision: Self::ROUTES_HASH,
+            network_only_patterns: vec!["/items/*", "/variants/**", "/items*/*"],
+        }
+    }
+}
+///The paths of the routes of [`Route`] without dynamic segments, like `Route::Variant` as `VARIANT_PATH`
+#[allow(dead_code)]
+pub mod route_paths {}
+impl Route {
+    /// Every variant with its pattern and the segments of it, in the order they are declared. Routes
+    /// behind a feature are listed even when the feature is off, like in [`Self::ROUTE_PATTERNS`].
+    pub const SITE_MAP: &'static [RouteInfo] = &[
+        RouteInfo {
+            variant: "Item",
+            pattern: "/items/(id)",
+            segments: &[SegmentInfo::Static("items"), SegmentInfo::Dynamic("id", "u64")],
+        },
+        RouteInfo {
+            variant: "FilteredItem",
+            pattern: "/items;(filters)/(id)",
+            segments: &[
+                SegmentInfo::Static("items"),
+                SegmentInfo::Matrix("filters", "MatrixParams"),
+                SegmentInfo::Dynamic("id", "u64"),
+            ],
+        },
+        RouteInfo {
+            variant: "Variant",
+            pattern: "/variants/(color);(options)",
+            segments: &[
+                SegmentInfo::Static("variants"),
+                SegmentInfo::Dynamic("color", "String"),
+                SegmentInfo::Matrix("options", "MatrixParams"),
+            ],
+        },
+    ];
+    /// Every route without dynamic segments, like the pages a static site generator can render ahead of
+    /// time. Routes with a dynamic or catch-all segment, a query or a fragment are left out.
+    #[allow(unused_mut, clippy::vec_init_then_push)]
+    pub fn static_routes() -> Vec<Self> {
+        let mut routes = Vec::new();
+        routes
+    }
+}
+impl Route {
+    /// Check if two routes are the same variant, ignoring the values of their parameters.
+    pub fn same_variant(&self, other: &Self) -> bool {
+        ::std::mem::discriminant(self) == ::std::mem::discriminant(other)
+    }
+    /// List the parameters whose serialized values differ between two routes of the same
+    /// variant as `(name, self_value, other_value)`. Routes of different variants have no
+    /// comparable parameters and produce an empty list.
+    pub fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        let mut diff = Vec::new();
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Item { id: self_id }, Self::Item { id: other_id }) => {
+                let self_value = DisplayRouteSegment(self_id).to_string();
+                let other_value = DisplayRouteSegment(other_id).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(id), self_value, other_value));
+                }
+            }
+            (
+                Self::FilteredItem { filters: self_filters, id: self_id },
+                Self::FilteredItem { filters: other_filters, id: other_id },
+            ) => {
+                let self_value = matrix_string(self_filters);
+                let other_value = matrix_string(other_filters);
+                if self_value != other_value {
+                    diff.push((stringify!(filters), self_value, other_value));
+                }
+                let self_value = DisplayRouteSegment(self_id).to_string();
+                let other_value = DisplayRouteSegment(other_id).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(id), self_value, other_value));
+                }
+            }
+            (
+                Self::Variant { color: self_color, options: self_options },
+                Self::Variant { color: other_color, options: other_options },
+            ) => {
+                let self_value = DisplayRouteSegment(self_color).to_string();
+                let other_value = DisplayRouteSegment(other_color).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(color), self_value, other_value));
+                }
+                let self_value = matrix_string(self_options);
+                let other_value = matrix_string(other_options);
+                if self_value != other_value {
+                    diff.push((stringify!(options), self_value, other_value));
+                }
+            }
+            _ => {}
+        }
+        diff
+    }
+    /// The number of segments in the path of this route. A catch-all counts every segment it holds.
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Item { .. } => 2usize,
+            Self::FilteredItem { .. } => 2usize,
+            Self::Variant { .. } => 2usize,
+        }
+    }
+    /// The first static segment in the pattern of this route, if it has one.
+    pub fn section(&self) -> Option<&'static str> {
+        match self {
+            Self::Item { .. } => Some("items"),
+            Self::FilteredItem { .. } => Some("items"),
+            Self::Variant { .. } => Some("variants"),
+        }
+    }
+}
+/// Lets a route be used directly as an attribute value, like `a { href: &route }`.
+///
+/// The route is written straight into the scope's bump allocator instead of an intermediate `String`.
+impl<'a> IntoAttributeValue<'a> for &Route {
+    fn into_value(
+        self,
+        bump: &'a dioxus::core::exports::bumpalo::Bump,
+    ) -> dioxus::core::AttributeValue<'a> {
+        format_args!("{}", self).into_value(bump)
+    }
+}
+const _: () = {
+    fn __router_segment_value<T: SegmentValue>() {}
+    fn __router_check_segments() {
+        __router_segment_value::<u64>();
+        __router_segment_value::<String>();
+    }
+};
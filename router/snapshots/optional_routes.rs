@@ -0,0 +1,969 @@
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum HomeParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+}
+impl ::std::fmt::Display for HomeParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "")?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for HomeParseError {}
+impl HomeParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        false
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum PostsParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    pageParseError(SegmentError<<u32 as FromRouteSegment>::Err>),
+}
+impl ::std::fmt::Display for PostsParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "posts")?
+            }
+            Self::pageParseError(err) => {
+                write!(
+                    f, "Optional segment '(?{}:{})' {}", stringify!(page),
+                    stringify!(Option < u32 >), err
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for PostsParseError {}
+impl PostsParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(self, Self::pageParseError(SegmentError::DecodeError(_)))
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum TagParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    tagParseError(SegmentError<<String as FromRouteSegment>::Err>),
+    sortParseError(SegmentError<<String as FromRouteSegment>::Err>),
+}
+impl ::std::fmt::Display for TagParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "tags")?
+            }
+            Self::tagParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(tag),
+                    stringify!(String), err
+                )?
+            }
+            Self::sortParseError(err) => {
+                write!(
+                    f, "Optional segment '(?{}:{})' {}", stringify!(sort),
+                    stringify!(String), err
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for TagParseError {}
+impl TagParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(
+            self, Self::tagParseError(SegmentError::DecodeError(_)) |
+            Self::sortParseError(SegmentError::DecodeError(_))
+        )
+    }
+}
+#[derive(Debug, PartialEq)]
+pub enum RouteMatchError {
+    Home { error: HomeParseError, span: ::std::ops::Range<usize> },
+    Posts { error: PostsParseError, span: ::std::ops::Range<usize> },
+    Tag { error: TagParseError, span: ::std::ops::Range<usize> },
+}
+impl ::std::error::Error for RouteMatchError {
+    fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+        match self {
+            Self::Home { error, .. } => Some(error),
+            Self::Posts { error, .. } => Some(error),
+            Self::Tag { error, .. } => Some(error),
+        }
+    }
+}
+impl ::std::fmt::Display for RouteMatchError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::Home { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Home), "/",
+                    error
+                )?
+            }
+            Self::Posts { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Posts),
+                    "/posts/(?page)", error
+                )?
+            }
+            Self::Tag { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Tag),
+                    "/tags/(tag)/(?sort)", error
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl RouteMatchError {
+    /// The byte range of the parsed input that failed to match. The range refers to the original
+    /// input, before any segment was percent-decoded.
+    pub fn span(&self) -> ::std::ops::Range<usize> {
+        match self {
+            Self::Home { span, .. } => span.clone(),
+            Self::Posts { span, .. } => span.clone(),
+            Self::Tag { span, .. } => span.clone(),
+        }
+    }
+    /// Render the input with a line of carets under the part that failed to match.
+    pub fn highlight(&self, input: &str) -> String {
+        let span = self.span();
+        let start = input.get(..span.start).map_or(0, |before| before.chars().count());
+        let len = input.get(span).map_or(0, |failed| failed.chars().count());
+        format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(len.max(1)))
+    }
+}
+impl AttemptedRoute for RouteMatchError {
+    fn span(&self) -> ::std::ops::Range<usize> {
+        Self::span(self)
+    }
+    fn is_decode_error(&self) -> bool {
+        match self {
+            Self::Home { error, .. } => error.is_decode_error(),
+            Self::Posts { error, .. } => error.is_decode_error(),
+            Self::Tag { error, .. } => error.is_decode_error(),
+        }
+    }
+}
+impl<'a> TryFrom<&'a str> for Route {
+    type Error = <Self as ::std::str::FromStr>::Err;
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+impl ::std::str::FromStr for Route {
+    type Err = RouteParseError<RouteMatchError>;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_path(s)
+    }
+}
+impl Route {
+    fn parse_path(s: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if s.len() > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len: s.len(),
+                    max: 8192,
+                }),
+            );
+        }
+        let origin = url_path_start(s);
+        let s = &s[origin..];
+        let s = s.split_once('#').map_or(s, |(rest, _)| rest);
+        let s = s.split_once('?').map_or(s, |(path, _)| path);
+        let (path, offset) = match s.strip_prefix('/') {
+            Some(path) => (path, origin + 1),
+            None => (s, origin),
+        };
+        Self::match_segments(SegmentCursor::new(path.split('/'), offset))
+    }
+    /// Parse a route from a path that is already split into segments, like the path parts extracted
+    /// by an HTTP framework. The segments must not include the leading slash; no segments at all
+    /// matches the root route.
+    ///
+    /// This behaves exactly like parsing the joined path with [`std::str::FromStr`]. Spans in the
+    /// errors refer to the joined path.
+    pub fn from_segments<'a>(
+        segments: impl Iterator<Item = &'a str> + Clone,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().next().is_none() {
+            return Self::match_segments(SegmentCursor::new(::std::iter::once(""), 1));
+        }
+        Self::match_segments(SegmentCursor::new(segments, 1))
+    }
+    /// Parse a location under the base path the app is served from, like `/app/blog/1` under `/app`.
+    /// A slash at the end of the base is ignored, and locations outside of it, like `/application`,
+    /// are rejected with [`ParseLimit::OutsideBase`] without trying any route.
+    pub fn parse_with_base(
+        location: &str,
+        base: &str,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        match strip_base_path(base, location) {
+            Some(location) => location.parse(),
+            None => {
+                Err(
+                    RouteParseError::limit(ParseLimit::OutsideBase {
+                        base: base.to_string(),
+                    }),
+                )
+            }
+        }
+    }
+    fn match_segments<'a>(
+        mut segments: SegmentCursor<impl Iterator<Item = &'a str> + Clone>,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().take(256 + 1).count() > 256 {
+            return Err(
+                RouteParseError::limit(ParseLimit::TooManySegments {
+                    max: 256,
+                }),
+            );
+        }
+        let len = segments.end();
+        if len > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len,
+                    max: 8192,
+                }),
+            );
+        }
+        let mut errors = Vec::new();
+        if let Some(segment) = segments.next() {
+            if "" == segment {
+                let mut segments = segments.clone();
+                let remaining_segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                let next_segment = segments_clone.next();
+                let segment_after_next = segments_clone.next();
+                match (next_segment, segment_after_next) {
+                    (None, _) | (Some(""), None) => {
+                        return Ok(Route::Home {});
+                    }
+                    _ => {
+                        let span = remaining_segments.rest_span();
+                        let mut trailing = String::new();
+                        for seg in remaining_segments {
+                            trailing += seg;
+                            trailing += "/";
+                        }
+                        trailing.pop();
+                        errors
+                            .push(RouteMatchError::Home {
+                                error: HomeParseError::ExtraSegments(trailing),
+                                span,
+                            })
+                    }
+                }
+                if let Some(segment) = segments.next() {}
+            } else {
+                errors
+                    .push(RouteMatchError::Home {
+                        error: HomeParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "posts" == segment {
+                let mut segments = segments.clone();
+                if segments.clone().next().is_none() {
+                    {
+                        let page = None;
+                        return Ok(Route::Posts { page });
+                    }
+                }
+                if let Some(segment) = segments.next() {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() && segments.clone().next().is_none() => {
+                            Ok(None)
+                        }
+                        _ if segment.is_empty() => {
+                            Err(RouteMatchError::Posts {
+                                error: PostsParseError::EmptySegment(stringify!(page)),
+                                span: segments.span(),
+                            })
+                        }
+                        Some(decoded) => {
+                            <u32 as FromRouteSegment>::from_route_segment(&decoded)
+                                .map(Some)
+                                .map_err(|err| RouteMatchError::Posts {
+                                    error: PostsParseError::pageParseError(
+                                        SegmentError::from_parse_error(segment, err),
+                                    ),
+                                    span: segments.span(),
+                                })
+                        }
+                        None => {
+                            Err(RouteMatchError::Posts {
+                                error: PostsParseError::pageParseError(
+                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                    };
+                    match parsed {
+                        Ok(page) => {
+                            let remaining_segments = segments.clone();
+                            let mut segments_clone = segments.clone();
+                            let next_segment = segments_clone.next();
+                            let segment_after_next = segments_clone.next();
+                            match (next_segment, segment_after_next) {
+                                (None, _) | (Some(""), None) => {
+                                    return Ok(Route::Posts { page });
+                                }
+                                _ => {
+                                    let span = remaining_segments.rest_span();
+                                    let mut trailing = String::new();
+                                    for seg in remaining_segments {
+                                        trailing += seg;
+                                        trailing += "/";
+                                    }
+                                    trailing.pop();
+                                    errors
+                                        .push(RouteMatchError::Posts {
+                                            error: PostsParseError::ExtraSegments(trailing),
+                                            span,
+                                        })
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::Posts {
+                        error: PostsParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "tags" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() => {
+                            Err(RouteMatchError::Tag {
+                                error: TagParseError::EmptySegment(stringify!(tag)),
+                                span: segments.span(),
+                            })
+                        }
+                        Some(decoded) => {
+                            <String as FromRouteSegment>::from_route_segment(&decoded)
+                                .map_err(|err| RouteMatchError::Tag {
+                                    error: TagParseError::tagParseError(
+                                        SegmentError::from_parse_error(segment, err),
+                                    ),
+                                    span: segments.span(),
+                                })
+                        }
+                        None => {
+                            Err(RouteMatchError::Tag {
+                                error: TagParseError::tagParseError(
+                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                    };
+                    match parsed {
+                        Ok(tag) => {
+                            let mut segments = segments.clone();
+                            if let Some(segment) = segments.next() {
+                                let parsed = match decode_path_segment(segment) {
+                                    _ if segment.is_empty()
+                                        && segments.clone().next().is_none() => {
+                                        Ok(<String as ::std::default::Default>::default())
+                                    }
+                                    _ if segment.is_empty() => {
+                                        Err(RouteMatchError::Tag {
+                                            error: TagParseError::EmptySegment(stringify!(sort)),
+                                            span: segments.span(),
+                                        })
+                                    }
+                                    Some(decoded) => {
+                                        <String as FromRouteSegment>::from_route_segment(&decoded)
+                                            .map_err(|err| RouteMatchError::Tag {
+                                                error: TagParseError::sortParseError(
+                                                    SegmentError::from_parse_error(segment, err),
+                                                ),
+                                                span: segments.span(),
+                                            })
+                                    }
+                                    None => {
+                                        Err(RouteMatchError::Tag {
+                                            error: TagParseError::sortParseError(
+                                                SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                            ),
+                                            span: segments.span(),
+                                        })
+                                    }
+                                };
+                                match parsed {
+                                    Ok(sort) => {
+                                        let remaining_segments = segments.clone();
+                                        let mut segments_clone = segments.clone();
+                                        let next_segment = segments_clone.next();
+                                        let segment_after_next = segments_clone.next();
+                                        match (next_segment, segment_after_next) {
+                                            (None, _) | (Some(""), None) => {
+                                                return Ok(Route::Tag { tag, sort });
+                                            }
+                                            _ => {
+                                                let span = remaining_segments.rest_span();
+                                                let mut trailing = String::new();
+                                                for seg in remaining_segments {
+                                                    trailing += seg;
+                                                    trailing += "/";
+                                                }
+                                                trailing.pop();
+                                                errors
+                                                    .push(RouteMatchError::Tag {
+                                                        error: TagParseError::ExtraSegments(trailing),
+                                                        span,
+                                                    })
+                                            }
+                                        }
+                                    }
+                                    Err(err) => {
+                                        errors.push(err);
+                                    }
+                                }
+                            } else {
+                                {
+                                    let sort = <String as ::std::default::Default>::default();
+                                    return Ok(Route::Tag { tag, sort });
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::Tag {
+                        error: TagParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+        }
+        Err(RouteParseError {
+            attempted_routes: errors,
+            limit: None,
+        })
+    }
+}
+impl ::std::fmt::Display for Route {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        Routable::write_path(self, f)
+    }
+}
+impl Routable for Route {
+    fn render<'a>(self, cx: &'a ScopeState) -> Element<'a> {
+        match self {
+            Self::Home {} => {
+                render! {
+                    Home {}
+                }
+            }
+            Self::Posts { page } => {
+                render! {
+                    Posts { page : page, }
+                }
+            }
+            Self::Tag { tag, sort } => {
+                render! {
+                    Tag { tag : tag, sort : sort, }
+                }
+            }
+        }
+    }
+    fn write_path(&self, f: &mut impl ::std::fmt::Write) -> ::std::fmt::Result {
+        match self {
+            Self::Home {} => {
+                write!(f, "/{}", "")?;
+            }
+            Self::Posts { page } => {
+                write!(f, "/{}", "posts")?;
+                if let Some(page) = page {
+                    debug_assert!(
+                        ! DisplayRouteSegment(page).to_string().is_empty(),
+                        "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                        stringify!(page)
+                    );
+                    write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(page)))?;
+                }
+            }
+            Self::Tag { tag, sort } => {
+                write!(f, "/{}", "tags")?;
+                debug_assert!(
+                    ! DisplayRouteSegment(tag).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(tag)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(tag)))?;
+                if !DisplayRouteSegment(sort).to_string().is_empty() {
+                    write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(sort)))?;
+                }
+            }
+        }
+        Ok(())
+    }
+    fn matched_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Home {} => vec![],
+            Self::Posts { page } => {
+                vec![
+                    (stringify!(page), page.as_ref().map(| value |
+                    DisplayRouteSegment(value).to_string()).unwrap_or_default())
+                ]
+            }
+            Self::Tag { tag, sort } => {
+                vec![
+                    (stringify!(tag), DisplayRouteSegment(tag).to_string()),
+                    (stringify!(sort), DisplayRouteSegment(sort).to_string())
+                ]
+            }
+        }
+    }
+    fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        Self::params_diff(self, other)
+    }
+    fn differing_fields(&self, other: &Self) -> Option<Vec<&'static str>> {
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Home {}, Self::Home {}) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                Some(fields)
+            }
+            (Self::Posts { page: self_page }, Self::Posts { page: other_page }) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_page != other_page {
+                    fields.push(stringify!(page));
+                }
+                Some(fields)
+            }
+            (
+                Self::Tag { tag: self_tag, sort: self_sort },
+                Self::Tag { tag: other_tag, sort: other_sort },
+            ) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_tag != other_tag {
+                    fields.push(stringify!(tag));
+                }
+                if self_sort != other_sort {
+                    fields.push(stringify!(sort));
+                }
+                Some(fields)
+            }
+            _ => None,
+        }
+    }
+    fn static_routes() -> Vec<Self> {
+        Self::static_routes()
+    }
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Home { .. } => "/",
+            Self::Posts { .. } => "/posts/(?page)",
+            Self::Tag { .. } => "/tags/(tag)/(?sort)",
+        }
+    }
+    fn route_patterns() -> &'static [&'static str] {
+        Self::ROUTE_PATTERNS
+    }
+    fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        Self::from_pattern_and_params(pattern, params)
+    }
+    fn action(&self) -> Option<fn(&mut Router<Self>)> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn status_hint(&self) -> u16 {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => 200,
+        }
+    }
+    fn cache_hint(&self) -> Option<&'static str> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn palette_routes() -> Vec<Self> {
+        let mut routes = Vec::new();
+        routes.push(Self::Home {});
+        routes
+    }
+    fn parse_limits() -> ParseLimits {
+        ParseLimits {
+            max_path_len: 8192,
+            max_segments: 256,
+        }
+    }
+    fn rejected(limit: ParseLimit) -> <Self as ::std::str::FromStr>::Err {
+        RouteParseError::<RouteMatchError>::limit(limit).into()
+    }
+    fn head(&self) -> HeadMeta {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => HeadMeta::default(),
+        }
+    }
+}
+impl Route {
+    /// The path of this route. The same as its `Display` output.
+    pub fn to_path(&self) -> String {
+        let mut path = String::new();
+        Routable::write_path(self, &mut path).expect("a segment failed to display");
+        path
+    }
+    /// Parse a route from a path. The same as parsing it with [`std::str::FromStr`].
+    pub fn from_path(path: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        <Self as ::std::str::FromStr>::from_str(path)
+    }
+}
+impl Route {
+    /// The pattern of the variant called `name`, like the pattern of a route a server handles
+    /// separately. Returns `None` if no variant has that name.
+    pub fn pattern_of_variant(name: &str) -> Option<&'static str> {
+        match name {
+            "Home" => Some("/"),
+            "Posts" => Some("/posts/(?page)"),
+            "Tag" => Some("/tags/(tag)/(?sort)"),
+            _ => None,
+        }
+    }
+    /// Find the pattern of the route a path corresponds to without parsing any dynamic segments.
+    ///
+    /// Dynamic segments match any value here, so a path can resolve to a pattern even if its segments
+    /// would fail to parse as the route's fields.
+    #[allow(unused_variables)]
+    pub fn match_pattern(path: &str) -> Option<&'static str> {
+        let mut segments = path.strip_prefix('/').unwrap_or(path).split('/');
+        if let Some(segment) = segments.next() {
+            if "" == segment {
+                let mut segments = segments.clone();
+                let mut segments_clone = segments.clone();
+                if let (None, _) | (Some(""), None)
+                    = (segments_clone.next(), segments_clone.next()) {
+                    return Some("/");
+                }
+                if let Some(segment) = segments.next() {}
+            }
+            if "posts" == segment {
+                let mut segments = segments.clone();
+                if segments.clone().next().is_none() {
+                    return Some("/posts/(?page)");
+                }
+                if let Some(segment) = segments.next() {
+                    let mut segments_clone = segments.clone();
+                    if let (None, _) | (Some(""), None)
+                        = (segments_clone.next(), segments_clone.next()) {
+                        return Some("/posts/(?page)");
+                    }
+                }
+            }
+            if "tags" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments = segments.clone();
+                    if let Some(segment) = segments.next() {
+                        let mut segments_clone = segments.clone();
+                        if let (None, _) | (Some(""), None)
+                            = (segments_clone.next(), segments_clone.next()) {
+                            return Some("/tags/(tag)/(?sort)");
+                        }
+                    } else {
+                        return Some("/tags/(tag)/(?sort)");
+                    }
+                }
+            }
+        }
+        None
+    }
+    /// Build a route from one of the patterns in [`Self::ROUTE_PATTERNS`] and the values of its
+    /// dynamic segments, like routes stored as data in a CMS.
+    ///
+    /// Parameters are looked up by the name of their segment and are parsed as they are, without
+    /// percent-decoding. A catch-all parameter is split on `/`. Parameters the pattern doesn't use
+    /// are ignored.
+    pub fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        #[allow(unused_variables)]
+        let param = |name: &'static str| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+                .ok_or(BuildError::MissingParam(name))
+        };
+        match pattern {
+            "/" => Ok(Route::Home {}),
+            "/posts/(?page)" => {
+                let page = match param(stringify!(page)) {
+                    Ok(value) if !value.is_empty() => {
+                        <u32 as FromRouteSegment>::from_route_segment(value)
+                            .map(Some)
+                            .map_err(|err| BuildError::InvalidParam {
+                                name: stringify!(page),
+                                error: err.to_string(),
+                            })?
+                    }
+                    _ => None,
+                };
+                Ok(Route::Posts { page })
+            }
+            "/tags/(tag)/(?sort)" => {
+                let tag = {
+                    let value = param(stringify!(tag))?;
+                    <String as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(tag),
+                            error: err.to_string(),
+                        })?
+                };
+                let sort = match param(stringify!(sort)) {
+                    Ok(value) if !value.is_empty() => {
+                        <String as FromRouteSegment>::from_route_segment(value)
+                            .map_err(|err| BuildError::InvalidParam {
+                                name: stringify!(sort),
+                                error: err.to_string(),
+                            })?
+                    }
+                    _ => <String as ::std::default::Default>::default(),
+                };
+                Ok(Route::Tag { tag, sort })
+            }
+            _ => Err(BuildError::UnknownPattern(pattern.to_string())),
+        }
+    }
+    /// The route pattern of every variant in the order the parser tries them.
+    ///
+    /// Only patterns that parse into a variant are listed here. Patterns that are never
+    /// matched directly (like redirects or fallbacks) belong in their own constants. Routes
+    /// with `feature = "..."` are listed in every build; check `gated_routes` for the ones
+    /// that are compiled out.
+    pub const ROUTE_PATTERNS: &'static [&'static str] = &[
+        "/",
+        "/posts/(?page)",
+        "/tags/(tag)/(?sort)",
+    ];
+    /// The patterns in [`Self::ROUTE_PATTERNS`] of routes that run an action instead of rendering a
+    /// page. Tools that list pages, like site maps, should leave these out.
+    pub const ACTION_ROUTE_PATTERNS: &'static [&'static str] = &[];
+    /// Every route with the name and type of its segments as JSON, and the locale of localized
+    /// patterns as `hreflang`. Save it as the `#[routable(baseline = "...")]` file to check later
+    /// versions of the routes against this one.
+    pub const ROUTE_TABLE_JSON: &'static str = "{\n  \"routes\": [\n    {\"variant\": \"Home\", \"pattern\": \"/\", \"segments\": []},\n    {\"variant\": \"Posts\", \"pattern\": \"/posts/(?page)\", \"segments\": [{\"name\": \"page\", \"type\": \"Option<u32>\"}]},\n    {\"variant\": \"Tag\", \"pattern\": \"/tags/(tag)/(?sort)\", \"segments\": [{\"name\": \"tag\", \"type\": \"String\"}, {\"name\": \"sort\", \"type\": \"String\"}]}\n  ]\n}\n";
+    /// A hash of the routes that changes when a pattern, the type of a segment or the metadata of a route
+    /// changes. Put it in asset URLs to invalidate cached pages when the routes change; the precache
+    /// manifest uses it as the revision of every page.
+    pub const ROUTES_HASH: &'static str = "000834a39acab247";
+    /// Split the pages into the paths a service worker can cache ahead of time and the patterns that
+    /// must always be fetched from the network.
+    ///
+    /// Only routes without dynamic segments whose `cache = "..."` hint allows shared caching are
+    /// precached. Every other page is network only, with dynamic segments written as `*` and
+    /// catch-alls as `**`. Action routes are in neither list.
+    pub fn precache_manifest() -> PrecacheManifest {
+        PrecacheManifest {
+            precache: vec![],
+            revision: Self::ROUTES_HASH,
+            network_only_patterns: vec!["/", "/posts/**", "/tags/*/**"],
+        }
+    }
+}
+///The paths of the routes of [`Route`] without dynamic segments, like `Route::Variant` as `VARIANT_PATH`
+#[allow(dead_code)]
+pub mod route_paths {
+    ///The path of [`Route::Home`]
+    pub const HOME_PATH: &str = "/";
+}
+impl Route {
+    /// Every variant with its pattern and the segments of it, in the order they are declared. Routes
+    /// behind a feature are listed even when the feature is off, like in [`Self::ROUTE_PATTERNS`].
+    pub const SITE_MAP: &'static [RouteInfo] = &[
+        RouteInfo {
+            variant: "Home",
+            pattern: "/",
+            segments: &[SegmentInfo::Static("")],
+        },
+        RouteInfo {
+            variant: "Posts",
+            pattern: "/posts/(?page)",
+            segments: &[
+                SegmentInfo::Static("posts"),
+                SegmentInfo::Optional("page", "Option<u32>"),
+            ],
+        },
+        RouteInfo {
+            variant: "Tag",
+            pattern: "/tags/(tag)/(?sort)",
+            segments: &[
+                SegmentInfo::Static("tags"),
+                SegmentInfo::Dynamic("tag", "String"),
+                SegmentInfo::Optional("sort", "String"),
+            ],
+        },
+    ];
+    /// Every route without dynamic segments, like the pages a static site generator can render ahead of
+    /// time. Routes with a dynamic or catch-all segment, a query or a fragment are left out.
+    #[allow(unused_mut, clippy::vec_init_then_push)]
+    pub fn static_routes() -> Vec<Self> {
+        let mut routes = Vec::new();
+        routes.push(Route::Home {});
+        routes
+    }
+}
+impl Route {
+    /// Check if two routes are the same variant, ignoring the values of their parameters.
+    pub fn same_variant(&self, other: &Self) -> bool {
+        ::std::mem::discriminant(self) == ::std::mem::discriminant(other)
+    }
+    /// List the parameters whose serialized values differ between two routes of the same
+    /// variant as `(name, self_value, other_value)`. Routes of different variants have no
+    /// comparable parameters and produce an empty list.
+    pub fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        let mut diff = Vec::new();
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Home {}, Self::Home {}) => {}
+            (Self::Posts { page: self_page }, Self::Posts { page: other_page }) => {
+                let self_value = self_page
+                    .as_ref()
+                    .map(|value| DisplayRouteSegment(value).to_string())
+                    .unwrap_or_default();
+                let other_value = other_page
+                    .as_ref()
+                    .map(|value| DisplayRouteSegment(value).to_string())
+                    .unwrap_or_default();
+                if self_value != other_value {
+                    diff.push((stringify!(page), self_value, other_value));
+                }
+            }
+            (
+                Self::Tag { tag: self_tag, sort: self_sort },
+                Self::Tag { tag: other_tag, sort: other_sort },
+            ) => {
+                let self_value = DisplayRouteSegment(self_tag).to_string();
+                let other_value = DisplayRouteSegment(other_tag).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(tag), self_value, other_value));
+                }
+                let self_value = DisplayRouteSegment(self_sort).to_string();
+                let other_value = DisplayRouteSegment(other_sort).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(sort), self_value, other_value));
+                }
+            }
+            _ => {}
+        }
+        diff
+    }
+    /// The number of segments in the path of this route. A catch-all counts every segment it holds.
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Home { .. } => 0usize,
+            Self::Posts { page, .. } => 1usize + usize::from(page.is_some()),
+            Self::Tag { .. } => {
+                2usize + usize::from(!DisplayRouteSegment(sort).to_string().is_empty())
+            }
+        }
+    }
+    /// The first static segment in the pattern of this route, if it has one.
+    pub fn section(&self) -> Option<&'static str> {
+        match self {
+            Self::Home { .. } => None,
+            Self::Posts { .. } => Some("posts"),
+            Self::Tag { .. } => Some("tags"),
+        }
+    }
+}
+/// Lets a route be used directly as an attribute value, like `a { href: &route }`.
+///
+/// The route is written straight into the scope's bump allocator instead of an intermediate `String`.
+impl<'a> IntoAttributeValue<'a> for &Route {
+    fn into_value(
+        self,
+        bump: &'a dioxus::core::exports::bumpalo::Bump,
+    ) -> dioxus::core::AttributeValue<'a> {
+        format_args!("{}", self).into_value(bump)
+    }
+}
+const _: () = {
+    fn __router_segment_value<T: SegmentValue>() {}
+    fn __router_check_segments() {
+        __router_segment_value::<u32>();
+        __router_segment_value::<String>();
+    }
+};
@@ -0,0 +1,1156 @@
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum PostParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    idParseError(SegmentError<<u32 as FromRouteSegment>::Err>),
+}
+impl ::std::fmt::Display for PostParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "blog' or 'posts' or 'p")?
+            }
+            Self::idParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(id), stringify!(u32),
+                    err
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for PostParseError {}
+impl PostParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(self, Self::idParseError(SegmentError::DecodeError(_)))
+    }
+}
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum RepoParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    userParseError(SegmentError<<String as FromRouteSegment>::Err>),
+    StaticSegment2ParseError,
+    repoParseError(SegmentError<<String as FromRouteSegment>::Err>),
+    StaticSegment1ParseError,
+}
+impl ::std::fmt::Display for RepoParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "users")?
+            }
+            Self::userParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(user),
+                    stringify!(String), err
+                )?
+            }
+            Self::StaticSegment2ParseError => {
+                write!(f, "Static segment '{}' did not match", "repos")?
+            }
+            Self::repoParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(repo),
+                    stringify!(String), err
+                )?
+            }
+            Self::StaticSegment1ParseError => {
+                write!(f, "Static segment '{}' did not match", "by")?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for RepoParseError {}
+impl RepoParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(
+            self, Self::userParseError(SegmentError::DecodeError(_)) |
+            Self::repoParseError(SegmentError::DecodeError(_))
+        )
+    }
+}
+#[derive(Debug, PartialEq)]
+pub enum RouteMatchError {
+    Post { error: PostParseError, span: ::std::ops::Range<usize> },
+    Repo { error: RepoParseError, span: ::std::ops::Range<usize> },
+}
+impl ::std::error::Error for RouteMatchError {
+    fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+        match self {
+            Self::Post { error, .. } => Some(error),
+            Self::Repo { error, .. } => Some(error),
+        }
+    }
+}
+impl ::std::fmt::Display for RouteMatchError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::Post { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Post),
+                    "/blog/(id)", error
+                )?
+            }
+            Self::Repo { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(Repo),
+                    "/users/(user)/repos/(repo)", error
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl RouteMatchError {
+    /// The byte range of the parsed input that failed to match. The range refers to the original
+    /// input, before any segment was percent-decoded.
+    pub fn span(&self) -> ::std::ops::Range<usize> {
+        match self {
+            Self::Post { span, .. } => span.clone(),
+            Self::Repo { span, .. } => span.clone(),
+        }
+    }
+    /// Render the input with a line of carets under the part that failed to match.
+    pub fn highlight(&self, input: &str) -> String {
+        let span = self.span();
+        let start = input.get(..span.start).map_or(0, |before| before.chars().count());
+        let len = input.get(span).map_or(0, |failed| failed.chars().count());
+        format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(len.max(1)))
+    }
+}
+impl AttemptedRoute for RouteMatchError {
+    fn span(&self) -> ::std::ops::Range<usize> {
+        Self::span(self)
+    }
+    fn is_decode_error(&self) -> bool {
+        match self {
+            Self::Post { error, .. } => error.is_decode_error(),
+            Self::Repo { error, .. } => error.is_decode_error(),
+        }
+    }
+}
+impl<'a> TryFrom<&'a str> for Route {
+    type Error = <Self as ::std::str::FromStr>::Err;
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+impl ::std::str::FromStr for Route {
+    type Err = RouteParseError<RouteMatchError>;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_path(s)
+    }
+}
+impl Route {
+    fn parse_path(s: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if s.len() > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len: s.len(),
+                    max: 8192,
+                }),
+            );
+        }
+        let origin = url_path_start(s);
+        let s = &s[origin..];
+        let s = s.split_once('#').map_or(s, |(rest, _)| rest);
+        let s = s.split_once('?').map_or(s, |(path, _)| path);
+        let (path, offset) = match s.strip_prefix('/') {
+            Some(path) => (path, origin + 1),
+            None => (s, origin),
+        };
+        Self::match_segments(SegmentCursor::new(path.split('/'), offset))
+    }
+    /// Parse a route from a path that is already split into segments, like the path parts extracted
+    /// by an HTTP framework. The segments must not include the leading slash; no segments at all
+    /// matches the root route.
+    ///
+    /// This behaves exactly like parsing the joined path with [`std::str::FromStr`]. Spans in the
+    /// errors refer to the joined path.
+    pub fn from_segments<'a>(
+        segments: impl Iterator<Item = &'a str> + Clone,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().next().is_none() {
+            return Self::match_segments(SegmentCursor::new(::std::iter::once(""), 1));
+        }
+        Self::match_segments(SegmentCursor::new(segments, 1))
+    }
+    /// Parse a location under the base path the app is served from, like `/app/blog/1` under `/app`.
+    /// A slash at the end of the base is ignored, and locations outside of it, like `/application`,
+    /// are rejected with [`ParseLimit::OutsideBase`] without trying any route.
+    pub fn parse_with_base(
+        location: &str,
+        base: &str,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        match strip_base_path(base, location) {
+            Some(location) => location.parse(),
+            None => {
+                Err(
+                    RouteParseError::limit(ParseLimit::OutsideBase {
+                        base: base.to_string(),
+                    }),
+                )
+            }
+        }
+    }
+    fn match_segments<'a>(
+        mut segments: SegmentCursor<impl Iterator<Item = &'a str> + Clone>,
+    ) -> Result<Self, RouteParseError<RouteMatchError>> {
+        if segments.clone().take(256 + 1).count() > 256 {
+            return Err(
+                RouteParseError::limit(ParseLimit::TooManySegments {
+                    max: 256,
+                }),
+            );
+        }
+        let len = segments.end();
+        if len > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len,
+                    max: 8192,
+                }),
+            );
+        }
+        let mut errors = Vec::new();
+        if let Some(segment) = segments.next() {
+            if "blog" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() => {
+                            Err(RouteMatchError::Post {
+                                error: PostParseError::EmptySegment(stringify!(id)),
+                                span: segments.span(),
+                            })
+                        }
+                        Some(decoded) => {
+                            <u32 as FromRouteSegment>::from_route_segment(&decoded)
+                                .map_err(|err| RouteMatchError::Post {
+                                    error: PostParseError::idParseError(
+                                        SegmentError::from_parse_error(segment, err),
+                                    ),
+                                    span: segments.span(),
+                                })
+                        }
+                        None => {
+                            Err(RouteMatchError::Post {
+                                error: PostParseError::idParseError(
+                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                    };
+                    match parsed {
+                        Ok(id) => {
+                            let remaining_segments = segments.clone();
+                            let mut segments_clone = segments.clone();
+                            let next_segment = segments_clone.next();
+                            let segment_after_next = segments_clone.next();
+                            match (next_segment, segment_after_next) {
+                                (None, _) | (Some(""), None) => {
+                                    return Ok(Route::Post { id });
+                                }
+                                _ => {
+                                    let span = remaining_segments.rest_span();
+                                    let mut trailing = String::new();
+                                    for seg in remaining_segments {
+                                        trailing += seg;
+                                        trailing += "/";
+                                    }
+                                    trailing.pop();
+                                    errors
+                                        .push(RouteMatchError::Post {
+                                            error: PostParseError::ExtraSegments(trailing),
+                                            span,
+                                        })
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::Post {
+                        error: PostParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "posts" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() => {
+                            Err(RouteMatchError::Post {
+                                error: PostParseError::EmptySegment(stringify!(id)),
+                                span: segments.span(),
+                            })
+                        }
+                        Some(decoded) => {
+                            <u32 as FromRouteSegment>::from_route_segment(&decoded)
+                                .map_err(|err| RouteMatchError::Post {
+                                    error: PostParseError::idParseError(
+                                        SegmentError::from_parse_error(segment, err),
+                                    ),
+                                    span: segments.span(),
+                                })
+                        }
+                        None => {
+                            Err(RouteMatchError::Post {
+                                error: PostParseError::idParseError(
+                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                    };
+                    match parsed {
+                        Ok(id) => {
+                            let remaining_segments = segments.clone();
+                            let mut segments_clone = segments.clone();
+                            let next_segment = segments_clone.next();
+                            let segment_after_next = segments_clone.next();
+                            match (next_segment, segment_after_next) {
+                                (None, _) | (Some(""), None) => {
+                                    return Ok(Route::Post { id });
+                                }
+                                _ => {
+                                    let span = remaining_segments.rest_span();
+                                    let mut trailing = String::new();
+                                    for seg in remaining_segments {
+                                        trailing += seg;
+                                        trailing += "/";
+                                    }
+                                    trailing.pop();
+                                    errors
+                                        .push(RouteMatchError::Post {
+                                            error: PostParseError::ExtraSegments(trailing),
+                                            span,
+                                        })
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::Post {
+                        error: PostParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "p" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() => {
+                            Err(RouteMatchError::Post {
+                                error: PostParseError::EmptySegment(stringify!(id)),
+                                span: segments.span(),
+                            })
+                        }
+                        Some(decoded) => {
+                            <u32 as FromRouteSegment>::from_route_segment(&decoded)
+                                .map_err(|err| RouteMatchError::Post {
+                                    error: PostParseError::idParseError(
+                                        SegmentError::from_parse_error(segment, err),
+                                    ),
+                                    span: segments.span(),
+                                })
+                        }
+                        None => {
+                            Err(RouteMatchError::Post {
+                                error: PostParseError::idParseError(
+                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                    };
+                    match parsed {
+                        Ok(id) => {
+                            let remaining_segments = segments.clone();
+                            let mut segments_clone = segments.clone();
+                            let next_segment = segments_clone.next();
+                            let segment_after_next = segments_clone.next();
+                            match (next_segment, segment_after_next) {
+                                (None, _) | (Some(""), None) => {
+                                    return Ok(Route::Post { id });
+                                }
+                                _ => {
+                                    let span = remaining_segments.rest_span();
+                                    let mut trailing = String::new();
+                                    for seg in remaining_segments {
+                                        trailing += seg;
+                                        trailing += "/";
+                                    }
+                                    trailing.pop();
+                                    errors
+                                        .push(RouteMatchError::Post {
+                                            error: PostParseError::ExtraSegments(trailing),
+                                            span,
+                                        })
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::Post {
+                        error: PostParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            if "users" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() => {
+                            Err(RouteMatchError::Repo {
+                                error: RepoParseError::EmptySegment(stringify!(user)),
+                                span: segments.span(),
+                            })
+                        }
+                        Some(decoded) => {
+                            <String as FromRouteSegment>::from_route_segment(&decoded)
+                                .map_err(|err| RouteMatchError::Repo {
+                                    error: RepoParseError::userParseError(
+                                        SegmentError::from_parse_error(segment, err),
+                                    ),
+                                    span: segments.span(),
+                                })
+                        }
+                        None => {
+                            Err(RouteMatchError::Repo {
+                                error: RepoParseError::userParseError(
+                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                    };
+                    match parsed {
+                        Ok(user) => {
+                            let mut segments = segments.clone();
+                            if let Some(segment) = segments.next() {
+                                let parsed = if segment == "repos" {
+                                    Ok(())
+                                } else {
+                                    Err(RouteMatchError::Repo {
+                                        error: RepoParseError::StaticSegment2ParseError,
+                                        span: segments.span(),
+                                    })
+                                };
+                                match parsed {
+                                    Ok(_) => {
+                                        let mut segments = segments.clone();
+                                        if let Some(segment) = segments.next() {
+                                            let parsed = match decode_path_segment(segment) {
+                                                _ if segment.is_empty() => {
+                                                    Err(RouteMatchError::Repo {
+                                                        error: RepoParseError::EmptySegment(stringify!(repo)),
+                                                        span: segments.span(),
+                                                    })
+                                                }
+                                                Some(decoded) => {
+                                                    <String as FromRouteSegment>::from_route_segment(&decoded)
+                                                        .map_err(|err| RouteMatchError::Repo {
+                                                            error: RepoParseError::repoParseError(
+                                                                SegmentError::from_parse_error(segment, err),
+                                                            ),
+                                                            span: segments.span(),
+                                                        })
+                                                }
+                                                None => {
+                                                    Err(RouteMatchError::Repo {
+                                                        error: RepoParseError::repoParseError(
+                                                            SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                                        ),
+                                                        span: segments.span(),
+                                                    })
+                                                }
+                                            };
+                                            match parsed {
+                                                Ok(repo) => {
+                                                    let remaining_segments = segments.clone();
+                                                    let mut segments_clone = segments.clone();
+                                                    let next_segment = segments_clone.next();
+                                                    let segment_after_next = segments_clone.next();
+                                                    match (next_segment, segment_after_next) {
+                                                        (None, _) | (Some(""), None) => {
+                                                            return Ok(Route::Repo { user, repo });
+                                                        }
+                                                        _ => {
+                                                            let span = remaining_segments.rest_span();
+                                                            let mut trailing = String::new();
+                                                            for seg in remaining_segments {
+                                                                trailing += seg;
+                                                                trailing += "/";
+                                                            }
+                                                            trailing.pop();
+                                                            errors
+                                                                .push(RouteMatchError::Repo {
+                                                                    error: RepoParseError::ExtraSegments(trailing),
+                                                                    span,
+                                                                })
+                                                        }
+                                                    }
+                                                }
+                                                Err(err) => {
+                                                    errors.push(err);
+                                                }
+                                            }
+                                        }
+                                    }
+                                    Err(err) => {
+                                        errors.push(err);
+                                    }
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(RouteMatchError::Repo {
+                        error: RepoParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+            let parsed = match decode_path_segment(segment) {
+                _ if segment.is_empty() => {
+                    Err(RouteMatchError::Repo {
+                        error: RepoParseError::EmptySegment(stringify!(repo)),
+                        span: segments.span(),
+                    })
+                }
+                Some(decoded) => {
+                    <String as FromRouteSegment>::from_route_segment(&decoded)
+                        .map_err(|err| RouteMatchError::Repo {
+                            error: RepoParseError::repoParseError(
+                                SegmentError::from_parse_error(segment, err),
+                            ),
+                            span: segments.span(),
+                        })
+                }
+                None => {
+                    Err(RouteMatchError::Repo {
+                        error: RepoParseError::repoParseError(
+                            SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                        ),
+                        span: segments.span(),
+                    })
+                }
+            };
+            match parsed {
+                Ok(repo) => {
+                    let mut segments = segments.clone();
+                    if let Some(segment) = segments.next() {
+                        let parsed = if segment == "by" {
+                            Ok(())
+                        } else {
+                            Err(RouteMatchError::Repo {
+                                error: RepoParseError::StaticSegment1ParseError,
+                                span: segments.span(),
+                            })
+                        };
+                        match parsed {
+                            Ok(_) => {
+                                let mut segments = segments.clone();
+                                if let Some(segment) = segments.next() {
+                                    let parsed = match decode_path_segment(segment) {
+                                        _ if segment.is_empty() => {
+                                            Err(RouteMatchError::Repo {
+                                                error: RepoParseError::EmptySegment(stringify!(user)),
+                                                span: segments.span(),
+                                            })
+                                        }
+                                        Some(decoded) => {
+                                            <String as FromRouteSegment>::from_route_segment(&decoded)
+                                                .map_err(|err| RouteMatchError::Repo {
+                                                    error: RepoParseError::userParseError(
+                                                        SegmentError::from_parse_error(segment, err),
+                                                    ),
+                                                    span: segments.span(),
+                                                })
+                                        }
+                                        None => {
+                                            Err(RouteMatchError::Repo {
+                                                error: RepoParseError::userParseError(
+                                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                                ),
+                                                span: segments.span(),
+                                            })
+                                        }
+                                    };
+                                    match parsed {
+                                        Ok(user) => {
+                                            let remaining_segments = segments.clone();
+                                            let mut segments_clone = segments.clone();
+                                            let next_segment = segments_clone.next();
+                                            let segment_after_next = segments_clone.next();
+                                            match (next_segment, segment_after_next) {
+                                                (None, _) | (Some(""), None) => {
+                                                    return Ok(Route::Repo { repo, user });
+                                                }
+                                                _ => {
+                                                    let span = remaining_segments.rest_span();
+                                                    let mut trailing = String::new();
+                                                    for seg in remaining_segments {
+                                                        trailing += seg;
+                                                        trailing += "/";
+                                                    }
+                                                    trailing.pop();
+                                                    errors
+                                                        .push(RouteMatchError::Repo {
+                                                            error: RepoParseError::ExtraSegments(trailing),
+                                                            span,
+                                                        })
+                                                }
+                                            }
+                                        }
+                                        Err(err) => {
+                                            errors.push(err);
+                                        }
+                                    }
+                                }
+                            }
+                            Err(err) => {
+                                errors.push(err);
+                            }
+                        }
+                    }
+                }
+                Err(err) => {
+                    errors.push(err);
+                }
+            }
+        }
+        Err(RouteParseError {
+            attempted_routes: errors,
+            limit: None,
+        })
+    }
+}
+impl ::std::fmt::Display for Route {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        Routable::write_path(self, f)
+    }
+}
+impl Routable for Route {
+    fn render<'a>(self, cx: &'a ScopeState) -> Element<'a> {
+        match self {
+            Self::Post { id } => {
+                render! {
+                    Post { id : id, }
+                }
+            }
+            Self::Repo { user, repo } => {
+                render! {
+                    Repo { user : user, repo : repo, }
+                }
+            }
+        }
+    }
+    fn write_path(&self, f: &mut impl ::std::fmt::Write) -> ::std::fmt::Result {
+        match self {
+            Self::Post { id } => {
+                write!(f, "/{}", "blog")?;
+                debug_assert!(
+                    ! DisplayRouteSegment(id).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(id)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(id)))?;
+            }
+            Self::Repo { user, repo } => {
+                write!(f, "/{}", "users")?;
+                debug_assert!(
+                    ! DisplayRouteSegment(user).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(user)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(user)))?;
+                write!(f, "/{}", "repos")?;
+                debug_assert!(
+                    ! DisplayRouteSegment(repo).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(repo)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(repo)))?;
+            }
+        }
+        Ok(())
+    }
+    fn matched_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Post { id } => {
+                vec![(stringify!(id), DisplayRouteSegment(id).to_string())]
+            }
+            Self::Repo { user, repo } => {
+                vec![
+                    (stringify!(user), DisplayRouteSegment(user).to_string()),
+                    (stringify!(repo), DisplayRouteSegment(repo).to_string())
+                ]
+            }
+        }
+    }
+    fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        Self::params_diff(self, other)
+    }
+    fn differing_fields(&self, other: &Self) -> Option<Vec<&'static str>> {
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Post { id: self_id }, Self::Post { id: other_id }) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_id != other_id {
+                    fields.push(stringify!(id));
+                }
+                Some(fields)
+            }
+            (
+                Self::Repo { user: self_user, repo: self_repo },
+                Self::Repo { user: other_user, repo: other_repo },
+            ) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_user != other_user {
+                    fields.push(stringify!(user));
+                }
+                if self_repo != other_repo {
+                    fields.push(stringify!(repo));
+                }
+                Some(fields)
+            }
+            _ => None,
+        }
+    }
+    fn static_routes() -> Vec<Self> {
+        Self::static_routes()
+    }
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Post { .. } => "/blog/(id)",
+            Self::Repo { .. } => "/users/(user)/repos/(repo)",
+        }
+    }
+    fn route_patterns() -> &'static [&'static str] {
+        Self::ROUTE_PATTERNS
+    }
+    fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        Self::from_pattern_and_params(pattern, params)
+    }
+    fn action(&self) -> Option<fn(&mut Router<Self>)> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn status_hint(&self) -> u16 {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => 200,
+        }
+    }
+    fn cache_hint(&self) -> Option<&'static str> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn parse_limits() -> ParseLimits {
+        ParseLimits {
+            max_path_len: 8192,
+            max_segments: 256,
+        }
+    }
+    fn rejected(limit: ParseLimit) -> <Self as ::std::str::FromStr>::Err {
+        RouteParseError::<RouteMatchError>::limit(limit).into()
+    }
+    fn head(&self) -> HeadMeta {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => HeadMeta::default(),
+        }
+    }
+}
+impl Route {
+    /// The path of this route. The same as its `Display` output.
+    pub fn to_path(&self) -> String {
+        let mut path = String::new();
+        Routable::write_path(self, &mut path).expect("a segment failed to display");
+        path
+    }
+    /// Parse a route from a path. The same as parsing it with [`std::str::FromStr`].
+    pub fn from_path(path: &str) -> Result<Self, RouteParseError<RouteMatchError>> {
+        <Self as ::std::str::FromStr>::from_str(path)
+    }
+}
+impl Route {
+    /// The pattern of the variant called `name`, like the pattern of a route a server handles
+    /// separately. Returns `None` if no variant has that name.
+    pub fn pattern_of_variant(name: &str) -> Option<&'static str> {
+        match name {
+            "Post" => Some("/blog/(id)"),
+            "Repo" => Some("/users/(user)/repos/(repo)"),
+            _ => None,
+        }
+    }
+    /// Find the pattern of the route a path corresponds to without parsing any dynamic segments.
+    ///
+    /// Dynamic segments match any value here, so a path can resolve to a pattern even if its segments
+    /// would fail to parse as the route's fields.
+    #[allow(unused_variables)]
+    pub fn match_pattern(path: &str) -> Option<&'static str> {
+        let mut segments = path.strip_prefix('/').unwrap_or(path).split('/');
+        if let Some(segment) = segments.next() {
+            if "blog" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments_clone = segments.clone();
+                    if let (None, _) | (Some(""), None)
+                        = (segments_clone.next(), segments_clone.next()) {
+                        return Some("/blog/(id)");
+                    }
+                }
+            }
+            if "posts" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments_clone = segments.clone();
+                    if let (None, _) | (Some(""), None)
+                        = (segments_clone.next(), segments_clone.next()) {
+                        return Some("/posts/(id)");
+                    }
+                }
+            }
+            if "p" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments_clone = segments.clone();
+                    if let (None, _) | (Some(""), None)
+                        = (segments_clone.next(), segments_clone.next()) {
+                        return Some("/p/(id)");
+                    }
+                }
+            }
+            if "users" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments = segments.clone();
+                    if let Some(segment) = segments.next() {
+                        if segment == "repos" {
+                            let mut segments = segments.clone();
+                            if let Some(segment) = segments.next() {
+                                let mut segments_clone = segments.clone();
+                                if let (None, _) | (Some(""), None)
+                                    = (segments_clone.next(), segments_clone.next()) {
+                                    return Some("/users/(user)/repos/(repo)");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            let mut segments = segments.clone();
+            if let Some(segment) = segments.next() {
+                if segment == "by" {
+                    let mut segments = segments.clone();
+                    if let Some(segment) = segments.next() {
+                        let mut segments_clone = segments.clone();
+                        if let (None, _) | (Some(""), None)
+                            = (segments_clone.next(), segments_clone.next()) {
+                            return Some("/(repo)/by/(user)");
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+    /// Build a route from one of the patterns in [`Self::ROUTE_PATTERNS`] and the values of its
+    /// dynamic segments, like routes stored as data in a CMS.
+    ///
+    /// Parameters are looked up by the name of their segment and are parsed as they are, without
+    /// percent-decoding. A catch-all parameter is split on `/`. Parameters the pattern doesn't use
+    /// are ignored.
+    pub fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        #[allow(unused_variables)]
+        let param = |name: &'static str| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+                .ok_or(BuildError::MissingParam(name))
+        };
+        match pattern {
+            "/blog/(id)" => {
+                let id = {
+                    let value = param(stringify!(id))?;
+                    <u32 as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(id),
+                            error: err.to_string(),
+                        })?
+                };
+                Ok(Route::Post { id })
+            }
+            "/posts/(id)" => {
+                let id = {
+                    let value = param(stringify!(id))?;
+                    <u32 as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(id),
+                            error: err.to_string(),
+                        })?
+                };
+                Ok(Route::Post { id })
+            }
+            "/p/(id)" => {
+                let id = {
+                    let value = param(stringify!(id))?;
+                    <u32 as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(id),
+                            error: err.to_string(),
+                        })?
+                };
+                Ok(Route::Post { id })
+            }
+            "/users/(user)/repos/(repo)" => {
+                let user = {
+                    let value = param(stringify!(user))?;
+                    <String as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(user),
+                            error: err.to_string(),
+                        })?
+                };
+                let repo = {
+                    let value = param(stringify!(repo))?;
+                    <String as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(repo),
+                            error: err.to_string(),
+                        })?
+                };
+                Ok(Route::Repo { user, repo })
+            }
+            "/(repo)/by/(user)" => {
+                let repo = {
+                    let value = param(stringify!(repo))?;
+                    <String as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(repo),
+                            error: err.to_string(),
+                        })?
+                };
+                let user = {
+                    let value = param(stringify!(user))?;
+                    <String as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(user),
+                            error: err.to_string(),
+                        })?
+                };
+                Ok(Route::Repo { repo, user })
+            }
+            _ => Err(BuildError::UnknownPattern(pattern.to_string())),
+        }
+    }
+    /// The route pattern of every variant in the order the parser tries them.
+    ///
+    /// Only patterns that parse into a variant are listed here. Patterns that are never
+    /// matched directly (like redirects or fallbacks) belong in their own constants. Routes
+    /// with `feature = "..."` are listed in every build; check `gated_routes` for the ones
+    /// that are compiled out.
+    pub const ROUTE_PATTERNS: &'static [&'static str] = &[
+        "/blog/(id)",
+        "/posts/(id)",
+        "/p/(id)",
+        "/users/(user)/repos/(repo)",
+        "/(repo)/by/(user)",
+    ];
+    /// The patterns in [`Self::ROUTE_PATTERNS`] of routes that run an action instead of rendering a
+    /// page. Tools that list pages, like site maps, should leave these out.
+    pub const ACTION_ROUTE_PATTERNS: &'static [&'static str] = &[];
+    /// Every route with the name and type of its segments as JSON, and the locale of localized
+    /// patterns as `hreflang`. Save it as the `#[routable(baseline = "...")]` file to check later
+    /// versions of the routes against this one.
+    pub const ROUTE_TABLE_JSON: &'static str = "{\n  \"routes\": [\n    {\"variant\": \"Post\", \"pattern\": \"/blog/(id)\", \"segments\": [{\"name\": \"id\", \"type\": \"u32\"}]},\n    {\"variant\": \"Post\", \"pattern\": \"/posts/(id)\", \"segments\": [{\"name\": \"id\", \"type\": \"u32\"}]},\n    {\"variant\": \"Post\", \"pattern\": \"/p/(id)\", \"segments\": [{\"name\": \"id\", \"type\": \"u32\"}]},\n    {\"variant\": \"Repo\", \"pattern\": \"/users/(user)/repos/(repo)\", \"segments\": [{\"name\": \"user\", \"type\": \"String\"}, {\"name\": \"repo\", \"type\": \"String\"}]},\n    {\"variant\": \"Repo\", \"pattern\": \"/(repo)/by/(user)\", \"segments\": [{\"name\": \"repo\", \"type\": \"String\"}, {\"name\": \"user\", \"type\": \"String\"}]}\n  ]\n}\n";
+    /// A hash of the routes that changes when a pattern, the type of a segment or the metadata of a route
+    /// changes. Put it in asset URLs to invalidate cached pages when the routes change; the precache
+    /// manifest uses it as the revision of every page.
+    pub const ROUTES_HASH: &'static str = "5617569154363026";
+    /// Split the pages into the paths a service worker can cache ahead of time and the patterns that
+    /// must always be fetched from the network.
+    ///
+    /// Only routes without dynamic segments whose `cache = "..."` hint allows shared caching are
+    /// precached. Every other page is network only, with dynamic segments written as `*` and
+    /// catch-alls as `**`. Action routes are in neither list.
+    pub fn precache_manifest() -> PrecacheManifest {
+        PrecacheManifest {
+            precache: vec![],
+            revision: Self::ROUTES_HASH,
+            network_only_patterns: vec![
+                "/blog/*", "/posts/*", "/p/*", "/users/*/repos/*", "/*/by/*"
+            ],
+        }
+    }
+}
+///The paths of the routes of [`Route`] without dynamic segments, like `Route::Variant` as `VARIANT_PATH`
+#[allow(dead_code)]
+pub mod route_paths {}
+impl Route {
+    /// Every variant with its pattern and the segments of it, in the order they are declared. Routes
+    /// behind a feature are listed even when the feature is off, like in [`Self::ROUTE_PATTERNS`].
+    pub const SITE_MAP: &'static [RouteInfo] = &[
+        RouteInfo {
+            variant: "Post",
+            pattern: "/blog/(id)",
+            segments: &[SegmentInfo::Static("blog"), SegmentInfo::Dynamic("id", "u32")],
+        },
+        RouteInfo {
+            variant: "Repo",
+            pattern: "/users/(user)/repos/(repo)",
+            segments: &[
+                SegmentInfo::Static("users"),
+                SegmentInfo::Dynamic("user", "String"),
+                SegmentInfo::Static("repos"),
+                SegmentInfo::Dynamic("repo", "String"),
+            ],
+        },
+    ];
+    /// Every route without dynamic segments, like the pages a static site generator can render ahead of
+    /// time. Routes with a dynamic or catch-all segment, a query or a fragment are left out.
+    #[allow(unused_mut, clippy::vec_init_then_push)]
+    pub fn static_routes() -> Vec<Self> {
+        let mut routes = Vec::new();
+        routes
+    }
+}
+impl Route {
+    /// Check if two routes are the same variant, ignoring the values of their parameters.
+    pub fn same_variant(&self, other: &Self) -> bool {
+        ::std::mem::discriminant(self) == ::std::mem::discriminant(other)
+    }
+    /// List the parameters whose serialized values differ between two routes of the same
+    /// variant as `(name, self_value, other_value)`. Routes of different variants have no
+    /// comparable parameters and produce an empty list.
+    pub fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        let mut diff = Vec::new();
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (Self::Post { id: self_id }, Self::Post { id: other_id }) => {
+                let self_value = DisplayRouteSegment(self_id).to_string();
+                let other_value = DisplayRouteSegment(other_id).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(id), self_value, other_value));
+                }
+            }
+            (
+                Self::Repo { user: self_user, repo: self_repo },
+                Self::Repo { user: other_user, repo: other_repo },
+            ) => {
+                let self_value = DisplayRouteSegment(self_user).to_string();
+                let other_value = DisplayRouteSegment(other_user).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(user), self_value, other_value));
+                }
+                let self_value = DisplayRouteSegment(self_repo).to_string();
+                let other_value = DisplayRouteSegment(other_repo).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(repo), self_value, other_value));
+                }
+            }
+            _ => {}
+        }
+        diff
+    }
+    /// The number of segments in the path of this route. A catch-all counts every segment it holds.
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Post { .. } => 2usize,
+            Self::Repo { .. } => 4usize,
+        }
+    }
+    /// The first static segment in the pattern of this route, if it has one.
+    pub fn section(&self) -> Option<&'static str> {
+        match self {
+            Self::Post { .. } => Some("blog"),
+            Self::Repo { .. } => Some("users"),
+        }
+    }
+}
+/// Lets a route be used directly as an attribute value, like `a { href: &route }`.
+///
+/// The route is written straight into the scope's bump allocator instead of an intermediate `String`.
+impl<'a> IntoAttributeValue<'a> for &Route {
+    fn into_value(
+        self,
+        bump: &'a dioxus::core::exports::bumpalo::Bump,
+    ) -> dioxus::core::AttributeValue<'a> {
+        format_args!("{}", self).into_value(bump)
+    }
+}
+const _: () = {
+    fn __router_segment_value<T: SegmentValue>() {}
+    fn __router_check_segments() {
+        __router_segment_value::<u32>();
+        __router_segment_value::<String>();
+    }
+};
@@ -0,0 +1,790 @@
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq)]
+pub enum SearchRouteParseError {
+    ExtraSegments(String),
+    EmptySegment(&'static str),
+    MissingPrefix(&'static str),
+    MissingSuffix(&'static str),
+    StaticSegment0ParseError,
+    queryParseError(SegmentError<<String as FromRouteSegment>::Err>),
+    pageParseError(SegmentError<<u32 as FromRouteSegment>::Err>),
+    sortParseError(SegmentError<<String as FromRouteSegment>::Err>),
+}
+impl ::std::fmt::Display for SearchRouteParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::ExtraSegments(segments) => {
+                write!(f, "Found additional trailing segments: {segments}")?
+            }
+            Self::EmptySegment(name) => write!(f, "Dynamic segment '({name})' is empty")?,
+            Self::MissingPrefix(prefix) => {
+                write!(f, "Segment does not start with '{prefix}'")?
+            }
+            Self::MissingSuffix(suffix) => {
+                write!(f, "Segment does not end with '{suffix}'")?
+            }
+            Self::StaticSegment0ParseError => {
+                write!(f, "Static segment '{}' did not match", "search")?
+            }
+            Self::queryParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(query),
+                    stringify!(String), err
+                )?
+            }
+            Self::pageParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(page), stringify!(u32),
+                    err
+                )?
+            }
+            Self::sortParseError(err) => {
+                write!(
+                    f, "Dynamic segment '({}:{})' {}", stringify!(sort),
+                    stringify!(String), err
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl ::std::error::Error for SearchRouteParseError {}
+impl SearchRouteParseError {
+    /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+    pub fn is_decode_error(&self) -> bool {
+        matches!(
+            self, Self::queryParseError(SegmentError::DecodeError(_)) |
+            Self::pageParseError(SegmentError::DecodeError(_)) |
+            Self::sortParseError(SegmentError::DecodeError(_))
+        )
+    }
+}
+#[derive(Debug, PartialEq)]
+pub enum SearchRouteMatchError {
+    SearchRoute { error: SearchRouteParseError, span: ::std::ops::Range<usize> },
+}
+impl ::std::error::Error for SearchRouteMatchError {
+    fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+        match self {
+            Self::SearchRoute { error, .. } => Some(error),
+        }
+    }
+}
+impl ::std::fmt::Display for SearchRouteMatchError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            Self::SearchRoute { error, .. } => {
+                write!(
+                    f, "Route '{}' ('{}') did not match:\n{}", stringify!(SearchRoute),
+                    "/search/(query)/(page)/(sort)", error
+                )?
+            }
+        }
+        Ok(())
+    }
+}
+impl SearchRouteMatchError {
+    /// The byte range of the parsed input that failed to match. The range refers to the original
+    /// input, before any segment was percent-decoded.
+    pub fn span(&self) -> ::std::ops::Range<usize> {
+        match self {
+            Self::SearchRoute { span, .. } => span.clone(),
+        }
+    }
+    /// Render the input with a line of carets under the part that failed to match.
+    pub fn highlight(&self, input: &str) -> String {
+        let span = self.span();
+        let start = input.get(..span.start).map_or(0, |before| before.chars().count());
+        let len = input.get(span).map_or(0, |failed| failed.chars().count());
+        format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(len.max(1)))
+    }
+}
+impl AttemptedRoute for SearchRouteMatchError {
+    fn span(&self) -> ::std::ops::Range<usize> {
+        Self::span(self)
+    }
+    fn is_decode_error(&self) -> bool {
+        match self {
+            Self::SearchRoute { error, .. } => error.is_decode_error(),
+        }
+    }
+}
+impl<'a> TryFrom<&'a str> for SearchRoute {
+    type Error = <Self as ::std::str::FromStr>::Err;
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+impl ::std::str::FromStr for SearchRoute {
+    type Err = RouteParseError<SearchRouteMatchError>;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_path(s)
+    }
+}
+impl SearchRoute {
+    fn parse_path(s: &str) -> Result<Self, RouteParseError<SearchRouteMatchError>> {
+        if s.len() > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len: s.len(),
+                    max: 8192,
+                }),
+            );
+        }
+        let origin = url_path_start(s);
+        let s = &s[origin..];
+        let s = s.split_once('#').map_or(s, |(rest, _)| rest);
+        let s = s.split_once('?').map_or(s, |(path, _)| path);
+        let (path, offset) = match s.strip_prefix('/') {
+            Some(path) => (path, origin + 1),
+            None => (s, origin),
+        };
+        Self::match_segments(SegmentCursor::new(path.split('/'), offset))
+    }
+    /// Parse a route from a path that is already split into segments, like the path parts extracted
+    /// by an HTTP framework. The segments must not include the leading slash; no segments at all
+    /// matches the root route.
+    ///
+    /// This behaves exactly like parsing the joined path with [`std::str::FromStr`]. Spans in the
+    /// errors refer to the joined path.
+    pub fn from_segments<'a>(
+        segments: impl Iterator<Item = &'a str> + Clone,
+    ) -> Result<Self, RouteParseError<SearchRouteMatchError>> {
+        if segments.clone().next().is_none() {
+            return Self::match_segments(SegmentCursor::new(::std::iter::once(""), 1));
+        }
+        Self::match_segments(SegmentCursor::new(segments, 1))
+    }
+    /// Parse a location under the base path the app is served from, like `/app/blog/1` under `/app`.
+    /// A slash at the end of the base is ignored, and locations outside of it, like `/application`,
+    /// are rejected with [`ParseLimit::OutsideBase`] without trying any route.
+    pub fn parse_with_base(
+        location: &str,
+        base: &str,
+    ) -> Result<Self, RouteParseError<SearchRouteMatchError>> {
+        match strip_base_path(base, location) {
+            Some(location) => location.parse(),
+            None => {
+                Err(
+                    RouteParseError::limit(ParseLimit::OutsideBase {
+                        base: base.to_string(),
+                    }),
+                )
+            }
+        }
+    }
+    fn match_segments<'a>(
+        mut segments: SegmentCursor<impl Iterator<Item = &'a str> + Clone>,
+    ) -> Result<Self, RouteParseError<SearchRouteMatchError>> {
+        if segments.clone().take(256 + 1).count() > 256 {
+            return Err(
+                RouteParseError::limit(ParseLimit::TooManySegments {
+                    max: 256,
+                }),
+            );
+        }
+        let len = segments.end();
+        if len > 8192 {
+            return Err(
+                RouteParseError::limit(ParseLimit::PathTooLong {
+                    len,
+                    max: 8192,
+                }),
+            );
+        }
+        let mut errors = Vec::new();
+        if let Some(segment) = segments.next() {
+            if "search" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() => {
+                            Err(SearchRouteMatchError::SearchRoute {
+                                error: SearchRouteParseError::EmptySegment(
+                                    stringify!(query),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                        Some(decoded) => {
+                            <String as FromRouteSegment>::from_route_segment(&decoded)
+                                .map_err(|err| SearchRouteMatchError::SearchRoute {
+                                    error: SearchRouteParseError::queryParseError(
+                                        SegmentError::from_parse_error(segment, err),
+                                    ),
+                                    span: segments.span(),
+                                })
+                        }
+                        None => {
+                            Err(SearchRouteMatchError::SearchRoute {
+                                error: SearchRouteParseError::queryParseError(
+                                    SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                ),
+                                span: segments.span(),
+                            })
+                        }
+                    };
+                    match parsed {
+                        Ok(query) => {
+                            let mut segments = segments.clone();
+                            if let Some(segment) = segments.next() {
+                                let parsed = match decode_path_segment(segment) {
+                                    _ if segment.is_empty() => {
+                                        Err(SearchRouteMatchError::SearchRoute {
+                                            error: SearchRouteParseError::EmptySegment(
+                                                stringify!(page),
+                                            ),
+                                            span: segments.span(),
+                                        })
+                                    }
+                                    Some(decoded) => {
+                                        <u32 as FromRouteSegment>::from_route_segment(&decoded)
+                                            .map_err(|err| SearchRouteMatchError::SearchRoute {
+                                                error: SearchRouteParseError::pageParseError(
+                                                    SegmentError::from_parse_error(segment, err),
+                                                ),
+                                                span: segments.span(),
+                                            })
+                                    }
+                                    None => {
+                                        Err(SearchRouteMatchError::SearchRoute {
+                                            error: SearchRouteParseError::pageParseError(
+                                                SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                            ),
+                                            span: segments.span(),
+                                        })
+                                    }
+                                };
+                                match parsed {
+                                    Ok(page) => {
+                                        let mut segments = segments.clone();
+                                        if let Some(segment) = segments.next() {
+                                            let parsed = match decode_path_segment(segment) {
+                                                _ if segment.is_empty() => {
+                                                    Err(SearchRouteMatchError::SearchRoute {
+                                                        error: SearchRouteParseError::EmptySegment(
+                                                            stringify!(sort),
+                                                        ),
+                                                        span: segments.span(),
+                                                    })
+                                                }
+                                                Some(decoded) => {
+                                                    <String as FromRouteSegment>::from_route_segment(&decoded)
+                                                        .map_err(|err| SearchRouteMatchError::SearchRoute {
+                                                            error: SearchRouteParseError::sortParseError(
+                                                                SegmentError::from_parse_error(segment, err),
+                                                            ),
+                                                            span: segments.span(),
+                                                        })
+                                                }
+                                                None => {
+                                                    Err(SearchRouteMatchError::SearchRoute {
+                                                        error: SearchRouteParseError::sortParseError(
+                                                            SegmentError::DecodeError(DecodeError::InvalidUtf8),
+                                                        ),
+                                                        span: segments.span(),
+                                                    })
+                                                }
+                                            };
+                                            match parsed {
+                                                Ok(sort) => {
+                                                    let remaining_segments = segments.clone();
+                                                    let mut segments_clone = segments.clone();
+                                                    let next_segment = segments_clone.next();
+                                                    let segment_after_next = segments_clone.next();
+                                                    match (next_segment, segment_after_next) {
+                                                        (None, _) | (Some(""), None) => {
+                                                            return Ok(SearchRoute { query, page, sort });
+                                                        }
+                                                        _ => {
+                                                            let span = remaining_segments.rest_span();
+                                                            let mut trailing = String::new();
+                                                            for seg in remaining_segments {
+                                                                trailing += seg;
+                                                                trailing += "/";
+                                                            }
+                                                            trailing.pop();
+                                                            errors
+                                                                .push(SearchRouteMatchError::SearchRoute {
+                                                                    error: SearchRouteParseError::ExtraSegments(trailing),
+                                                                    span,
+                                                                })
+                                                        }
+                                                    }
+                                                }
+                                                Err(err) => {
+                                                    errors.push(err);
+                                                }
+                                            }
+                                        }
+                                    }
+                                    Err(err) => {
+                                        errors.push(err);
+                                    }
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            errors.push(err);
+                        }
+                    }
+                }
+            } else {
+                errors
+                    .push(SearchRouteMatchError::SearchRoute {
+                        error: SearchRouteParseError::StaticSegment0ParseError,
+                        span: segments.span(),
+                    })
+            }
+        }
+        Err(RouteParseError {
+            attempted_routes: errors,
+            limit: None,
+        })
+    }
+}
+impl ::std::fmt::Display for SearchRoute {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        Routable::write_path(self, f)
+    }
+}
+impl Routable for SearchRoute {
+    fn render<'a>(self, cx: &'a ScopeState) -> Element<'a> {
+        match self {
+            Self { query, page, sort } => {
+                render! {
+                    Search { query : query, page : page, sort : sort, }
+                }
+            }
+        }
+    }
+    fn write_path(&self, f: &mut impl ::std::fmt::Write) -> ::std::fmt::Result {
+        match self {
+            Self { query, page, sort } => {
+                write!(f, "/{}", "search")?;
+                debug_assert!(
+                    ! DisplayRouteSegment(query).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(query)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(query)))?;
+                debug_assert!(
+                    ! DisplayRouteSegment(page).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(page)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(page)))?;
+                debug_assert!(
+                    ! DisplayRouteSegment(sort).to_string().is_empty(),
+                    "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                    stringify!(sort)
+                );
+                write!(f, "/{}", EncodedSegment(& DisplayRouteSegment(sort)))?;
+            }
+        }
+        Ok(())
+    }
+    fn matched_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self { query, page, sort } => {
+                vec![
+                    (stringify!(query), DisplayRouteSegment(query).to_string()),
+                    (stringify!(page), DisplayRouteSegment(page).to_string()),
+                    (stringify!(sort), DisplayRouteSegment(sort).to_string())
+                ]
+            }
+        }
+    }
+    fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        Self::params_diff(self, other)
+    }
+    fn differing_fields(&self, other: &Self) -> Option<Vec<&'static str>> {
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (
+                Self { query: self_query, page: self_page, sort: self_sort },
+                Self { query: other_query, page: other_page, sort: other_sort },
+            ) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                if self_query != other_query {
+                    fields.push(stringify!(query));
+                }
+                if self_page != other_page {
+                    fields.push(stringify!(page));
+                }
+                if self_sort != other_sort {
+                    fields.push(stringify!(sort));
+                }
+                Some(fields)
+            }
+            _ => None,
+        }
+    }
+    fn static_routes() -> Vec<Self> {
+        Self::static_routes()
+    }
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self { .. } => "/search/(query)/(page)/(sort)",
+        }
+    }
+    fn route_patterns() -> &'static [&'static str] {
+        Self::ROUTE_PATTERNS
+    }
+    fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        Self::from_pattern_and_params(pattern, params)
+    }
+    fn action(&self) -> Option<fn(&mut Router<Self>)> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn status_hint(&self) -> u16 {
+        #[allow(unreachable_patterns)]
+        match self {
+            Self { .. } => 200u16,
+            _ => 200,
+        }
+    }
+    fn cache_hint(&self) -> Option<&'static str> {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => None,
+        }
+    }
+    fn parse_limits() -> ParseLimits {
+        ParseLimits {
+            max_path_len: 8192,
+            max_segments: 256,
+        }
+    }
+    fn rejected(limit: ParseLimit) -> <Self as ::std::str::FromStr>::Err {
+        RouteParseError::<SearchRouteMatchError>::limit(limit).into()
+    }
+    fn head(&self) -> HeadMeta {
+        #[allow(unreachable_patterns)]
+        match self {
+            _ => HeadMeta::default(),
+        }
+    }
+}
+impl SearchRoute {
+    /// The path of this route. The same as its `Display` output.
+    pub fn to_path(&self) -> String {
+        let mut path = String::new();
+        Routable::write_path(self, &mut path).expect("a segment failed to display");
+        path
+    }
+    /// Parse a route from a path. The same as parsing it with [`std::str::FromStr`].
+    pub fn from_path(
+        path: &str,
+    ) -> Result<Self, RouteParseError<SearchRouteMatchError>> {
+        <Self as ::std::str::FromStr>::from_str(path)
+    }
+}
+impl SearchRoute {
+    /// The pattern of the variant called `name`, like the pattern of a route a server handles
+    /// separately. Returns `None` if no variant has that name.
+    pub fn pattern_of_variant(name: &str) -> Option<&'static str> {
+        match name {
+            "SearchRoute" => Some("/search/(query)/(page)/(sort)"),
+            _ => None,
+        }
+    }
+    /// Find the pattern of the route a path corresponds to without parsing any dynamic segments.
+    ///
+    /// Dynamic segments match any value here, so a path can resolve to a pattern even if its segments
+    /// would fail to parse as the route's fields.
+    #[allow(unused_variables)]
+    pub fn match_pattern(path: &str) -> Option<&'static str> {
+        let mut segments = path.strip_prefix('/').unwrap_or(path).split('/');
+        if let Some(segment) = segments.next() {
+            if "search" == segment {
+                let mut segments = segments.clone();
+                if let Some(segment) = segments.next() {
+                    let mut segments = segments.clone();
+                    if let Some(segment) = segments.next() {
+                        let mut segments = segments.clone();
+                        if let Some(segment) = segments.next() {
+                            let mut segments_clone = segments.clone();
+                            if let (None, _) | (Some(""), None)
+                                = (segments_clone.next(), segments_clone.next()) {
+                                return Some("/search/(query)/(page)/(sort)");
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+    /// Build a route from one of the patterns in [`Self::ROUTE_PATTERNS`] and the values of its
+    /// dynamic segments, like routes stored as data in a CMS.
+    ///
+    /// Parameters are looked up by the name of their segment and are parsed as they are, without
+    /// percent-decoding. A catch-all parameter is split on `/`. Parameters the pattern doesn't use
+    /// are ignored.
+    pub fn from_pattern_and_params(
+        pattern: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Self, BuildError> {
+        #[allow(unused_variables)]
+        let param = |name: &'static str| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+                .ok_or(BuildError::MissingParam(name))
+        };
+        match pattern {
+            "/search/(query)/(page)/(sort)" => {
+                let query = {
+                    let value = param(stringify!(query))?;
+                    <String as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(query),
+                            error: err.to_string(),
+                        })?
+                };
+                let page = {
+                    let value = param(stringify!(page))?;
+                    <u32 as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(page),
+                            error: err.to_string(),
+                        })?
+                };
+                let sort = {
+                    let value = param(stringify!(sort))?;
+                    <String as FromRouteSegment>::from_route_segment(value)
+                        .map_err(|err| BuildError::InvalidParam {
+                            name: stringify!(sort),
+                            error: err.to_string(),
+                        })?
+                };
+                Ok(SearchRoute { query, page, sort })
+            }
+            _ => Err(BuildError::UnknownPattern(pattern.to_string())),
+        }
+    }
+    /// The route pattern of every variant in the order the parser tries them.
+    ///
+    /// Only patterns that parse into a variant are listed here. Patterns that are never
+    /// matched directly (like redirects or fallbacks) belong in their own constants. Routes
+    /// with `feature = "..."` are listed in every build; check `gated_routes` for the ones
+    /// that are compiled out.
+    pub const ROUTE_PATTERNS: &'static [&'static str] = &[
+        "/search/(query)/(page)/(sort)",
+    ];
+    /// The patterns in [`Self::ROUTE_PATTERNS`] of routes that run an action instead of rendering a
+    /// page. Tools that list pages, like site maps, should leave these out.
+    pub const ACTION_ROUTE_PATTERNS: &'static [&'static str] = &[];
+    /// Every route with the name and type of its segments as JSON, and the locale of localized
+    /// patterns as `hreflang`. Save it as the `#[routable(baseline = "...")]` file to check later
+    /// versions of the routes against this one.
+    pub const ROUTE_TABLE_JSON: &'static str = "{\n  \"routes\": [\n    {\"variant\": \"SearchRoute\", \"pattern\": \"/search/(query)/(page)/(sort)\", \"segments\": [{\"name\": \"query\", \"type\": \"String\"}, {\"name\": \"page\", \"type\": \"u32\"}, {\"name\": \"sort\", \"type\": \"String\"}]}\n  ]\n}\n";
+    /// A hash of the routes that changes when a pattern, the type of a segment or the metadata of a route
+    /// changes. Put it in asset URLs to invalidate cached pages when the routes change; the precache
+    /// manifest uses it as the revision of every page.
+    pub const ROUTES_HASH: &'static str = "8a68b7ce6f99fb54";
+    /// Split the pages into the paths a service worker can cache ahead of time and the patterns that
+    /// must always be fetched from the network.
+    ///
+    /// Only routes without dynamic segments whose `cache = "..."` hint allows shared caching are
+    /// precached. Every other page is network only, with dynamic segments written as `*` and
+    /// catch-alls as `**`. Action routes are in neither list.
+    pub fn precache_manifest() -> PrecacheManifest {
+        PrecacheManifest {
+            precache: vec![],
+            revision: Self::ROUTES_HASH,
+            network_only_patterns: vec!["/search/*/*/*"],
+        }
+    }
+}
+///The paths of the routes of [`SearchRoute`] without dynamic segments, like `SearchRoute::Variant` as `VARIANT_PATH`
+#[allow(dead_code)]
+pub mod search_route_paths {}
+impl SearchRoute {
+    /// Every variant with its pattern and the segments of it, in the order they are declared. Routes
+    /// behind a feature are listed even when the feature is off, like in [`Self::ROUTE_PATTERNS`].
+    pub const SITE_MAP: &'static [RouteInfo] = &[
+        RouteInfo {
+            variant: "SearchRoute",
+            pattern: "/search/(query)/(page)/(sort)",
+            segments: &[
+                SegmentInfo::Static("search"),
+                SegmentInfo::Dynamic("query", "String"),
+                SegmentInfo::Dynamic("page", "u32"),
+                SegmentInfo::Dynamic("sort", "String"),
+            ],
+        },
+    ];
+    /// Every route without dynamic segments, like the pages a static site generator can render ahead of
+    /// time. Routes with a dynamic or catch-all segment, a query or a fragment are left out.
+    #[allow(unused_mut, clippy::vec_init_then_push)]
+    pub fn static_routes() -> Vec<Self> {
+        let mut routes = Vec::new();
+        routes
+    }
+}
+impl SearchRoute {
+    /// Check if two routes are the same variant, ignoring the values of their parameters.
+    pub fn same_variant(&self, other: &Self) -> bool {
+        ::std::mem::discriminant(self) == ::std::mem::discriminant(other)
+    }
+    /// List the parameters whose serialized values differ between two routes of the same
+    /// variant as `(name, self_value, other_value)`. Routes of different variants have no
+    /// comparable parameters and produce an empty list.
+    pub fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        let mut diff = Vec::new();
+        #[allow(unreachable_patterns)]
+        match (self, other) {
+            (
+                Self { query: self_query, page: self_page, sort: self_sort },
+                Self { query: other_query, page: other_page, sort: other_sort },
+            ) => {
+                let self_value = DisplayRouteSegment(self_query).to_string();
+                let other_value = DisplayRouteSegment(other_query).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(query), self_value, other_value));
+                }
+                let self_value = DisplayRouteSegment(self_page).to_string();
+                let other_value = DisplayRouteSegment(other_page).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(page), self_value, other_value));
+                }
+                let self_value = DisplayRouteSegment(self_sort).to_string();
+                let other_value = DisplayRouteSegment(other_sort).to_string();
+                if self_value != other_value {
+                    diff.push((stringify!(sort), self_value, other_value));
+                }
+            }
+            _ => {}
+        }
+        diff
+    }
+    /// The number of segments in the path of this route. A catch-all counts every segment it holds.
+    pub fn depth(&self) -> usize {
+        match self {
+            Self { .. } => 4usize,
+        }
+    }
+    /// The first static segment in the pattern of this route, if it has one.
+    pub fn section(&self) -> Option<&'static str> {
+        match self {
+            Self { .. } => Some("search"),
+        }
+    }
+}
+/// Lets a route be used directly as an attribute value, like `a { href: &route }`.
+///
+/// The route is written straight into the scope's bump allocator instead of an intermediate `String`.
+impl<'a> IntoAttributeValue<'a> for &SearchRoute {
+    fn into_value(
+        self,
+        bump: &'a dioxus::core::exports::bumpalo::Bump,
+    ) -> dioxus::core::AttributeValue<'a> {
+        format_args!("{}", self).into_value(bump)
+    }
+}
+///A builder for [`SearchRoute`]. Every segment must be set before the route can be built.
+#[must_use]
+pub struct SearchRouteBuilder<QueryState, PageState, SortState> {
+    query: QueryState,
+    page: PageState,
+    sort: SortState,
+}
+impl SearchRoute {
+    /// Start building this route one segment at a time.
+    pub fn builder() -> SearchRouteBuilder<(), (), ()> {
+        SearchRouteBuilder {
+            query: (),
+            page: (),
+            sort: (),
+        }
+    }
+}
+impl<PageState, SortState> SearchRouteBuilder<(), PageState, SortState> {
+    pub fn query(
+        self,
+        query: String,
+    ) -> SearchRouteBuilder<String, PageState, SortState> {
+        SearchRouteBuilder {
+            query,
+            page: self.page,
+            sort: self.sort,
+        }
+    }
+}
+impl<QueryState, SortState> SearchRouteBuilder<QueryState, (), SortState> {
+    pub fn page(self, page: u32) -> SearchRouteBuilder<QueryState, u32, SortState> {
+        SearchRouteBuilder {
+            page,
+            query: self.query,
+            sort: self.sort,
+        }
+    }
+}
+impl<QueryState, PageState> SearchRouteBuilder<QueryState, PageState, ()> {
+    pub fn sort(
+        self,
+        sort: String,
+    ) -> SearchRouteBuilder<QueryState, PageState, String> {
+        SearchRouteBuilder {
+            sort,
+            query: self.query,
+            page: self.page,
+        }
+    }
+}
+impl SearchRouteBuilder<String, u32, String> {
+    pub fn build(self) -> SearchRoute {
+        SearchRoute {
+            query: self.query,
+            page: self.page,
+            sort: self.sort,
+        }
+    }
+}
+impl SearchRoute {
+    /// The current version of the serialized route format.
+    pub const VERSION: u32 = 2;
+    /// Serialize the route with a version marker, like `v3:/blog/1`, so it can still be parsed after
+    /// the route patterns change. Use [`std::fmt::Display`] for URLs.
+    pub fn to_versioned_string(&self) -> String {
+        format!("v{}:{}", Self::VERSION, self)
+    }
+    /// Parse a route serialized by [`Self::to_versioned_string`]. Routes from older versions are
+    /// passed through the `migrate(...)` functions first, and strings without a version marker are
+    /// parsed as plain paths.
+    pub fn from_versioned_str(
+        s: &str,
+    ) -> Result<Self, <Self as ::std::str::FromStr>::Err> {
+        let versioned = s
+            .strip_prefix('v')
+            .and_then(|rest| rest.split_once(':'))
+            .and_then(|(version, path)| Some((version.parse::<u32>().ok()?, path)));
+        match versioned {
+            Some((version, path)) if version <= Self::VERSION => {
+                let mut path = path.to_string();
+                if version <= 1 {
+                    path = migrate_v1(&path);
+                }
+                path.parse()
+            }
+            _ => s.parse(),
+        }
+    }
+}
+const _: () = {
+    fn __router_segment_value<T: SegmentValue>() {}
+    fn __router_check_segments() {
+        __router_segment_value::<String>();
+        __router_segment_value::<u32>();
+    }
+};
@@ -0,0 +1,484 @@
+//! Compare the routes of a type against a baseline exported from an earlier version of it, set with
+//! `#[routable(baseline = "routes_baseline.json")]`, so refactors can't silently break bookmarked URLs.
+//!
+//! The baseline is the `ROUTE_TABLE_JSON` of the type, saved to a file. Routes are compared by the shape of their
+//! pattern, with the names of dynamic segments left out, so renaming a segment doesn't count as a change. A pattern
+//! that is gone is an error unless a `redirect("/old" => "/new")` covers it. A segment whose type changed is a
+//! warning, since old URLs may still parse.
+
+/// A route in the exported route table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRoute {
+    pub variant: String,
+    pub pattern: String,
+    /// The locale of a localized pattern, for the `hreflang` of the alternate links in a sitemap. Only routes with
+    /// `locale(...)` patterns have one, on their own pattern as well.
+    pub hreflang: Option<String>,
+    /// The note of a route declared with `deprecated = "..."`
+    pub deprecated: Option<String>,
+    /// The team of a route declared with `owner = "..."`. Routes without an owner leave it out
+    pub owner: Option<String>,
+    /// The name and type of every dynamic and catch-all segment, in the order they appear in the pattern
+    pub segments: Vec<(String, String)>,
+}
+
+/// A difference between the baseline and the current routes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// No route has the shape of this baseline pattern anymore
+    Removed { pattern: String },
+    /// A segment of a pattern that still exists has another type
+    TypeChanged {
+        pattern: String,
+        segment: String,
+        old: String,
+        new: String,
+    },
+    /// A redirect points to a pattern no current route has
+    UnknownRedirectTarget { from: String, to: String },
+}
+
+impl Change {
+    /// If the change breaks old URLs, which fails the build instead of warning
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::TypeChanged { .. })
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Self::Removed { pattern } => format!(
+                "The route '{pattern}' in the baseline was removed. Add redirect(\"{pattern}\" => \"...\") to #[routable(...)] if it moved, or export the route table again if it was removed on purpose"
+            ),
+            Self::TypeChanged {
+                pattern,
+                segment,
+                old,
+                new,
+            } => format!(
+                "The segment '{segment}' of the route '{pattern}' changed from {old} to {new} since the baseline, so old URLs may not parse"
+            ),
+            Self::UnknownRedirectTarget { from, to } => {
+                format!("The redirect from '{from}' goes to '{to}', but no route has that pattern")
+            }
+        }
+    }
+}
+
+/// The pattern with every dynamic segment written as `*` and a catch-all as `**`, which is what stays the same when
+/// a segment is only renamed.
+fn shape(pattern: &str) -> String {
+    let mut shape = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '(' {
+            shape.push(c);
+            continue;
+        }
+        let inner: String = chars.by_ref().take_while(|c| *c != ')').collect();
+        shape.push_str(match inner.starts_with("...") {
+            true => "**",
+            false => "*",
+        });
+    }
+    shape
+}
+
+/// Compare the current routes to the baseline. `redirects` are the `(from, to)` patterns of moved routes.
+pub fn compare(
+    old: &[TableRoute],
+    new: &[TableRoute],
+    redirects: &[(String, String)],
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for (from, to) in redirects {
+        if !new.iter().any(|route| shape(&route.pattern) == shape(to)) {
+            changes.push(Change::UnknownRedirectTarget {
+                from: from.clone(),
+                to: to.clone(),
+            });
+        }
+    }
+
+    for old_route in old {
+        let old_shape = shape(&old_route.pattern);
+        if redirects.iter().any(|(from, _)| shape(from) == old_shape) {
+            continue;
+        }
+        let Some(new_route) = new.iter().find(|route| shape(&route.pattern) == old_shape) else {
+            changes.push(Change::Removed {
+                pattern: old_route.pattern.clone(),
+            });
+            continue;
+        };
+        for ((_, old_type), (segment, new_type)) in
+            old_route.segments.iter().zip(&new_route.segments)
+        {
+            if old_type != new_type {
+                changes.push(Change::TypeChanged {
+                    pattern: new_route.pattern.clone(),
+                    segment: segment.clone(),
+                    old: old_type.clone(),
+                    new: new_type.clone(),
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// The type as it is written in the route table: the tokens without the spaces `quote` puts between them, except
+/// where two words would run together, like `Vec<String>` or `&'static str`.
+pub fn type_name(ty: &syn::Type) -> String {
+    let tokens = quote::quote!(#ty).to_string();
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut name = String::new();
+    let mut chars = tokens.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ' ' && !(is_word(name.chars().last()) && is_word(chars.peek().copied())) {
+            continue;
+        }
+        name.push(c);
+    }
+    name
+}
+
+/// Write the route table as JSON, with one route per line so changes to it are easy to review.
+pub fn to_json(routes: &[TableRoute]) -> String {
+    let mut json = String::from("{\n  \"routes\": [");
+    for (i, route) in routes.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str("\n    {\"variant\": ");
+        write_string(&mut json, &route.variant);
+        json.push_str(", \"pattern\": ");
+        write_string(&mut json, &route.pattern);
+        if let Some(hreflang) = &route.hreflang {
+            json.push_str(", \"hreflang\": ");
+            write_string(&mut json, hreflang);
+        }
+        if let Some(deprecated) = &route.deprecated {
+            json.push_str(", \"deprecated\": ");
+            write_string(&mut json, deprecated);
+        }
+        if let Some(owner) = &route.owner {
+            json.push_str(", \"owner\": ");
+            write_string(&mut json, owner);
+        }
+        json.push_str(", \"segments\": [");
+        for (i, (name, ty)) in route.segments.iter().enumerate() {
+            if i > 0 {
+                json.push_str(", ");
+            }
+            json.push_str("{\"name\": ");
+            write_string(&mut json, name);
+            json.push_str(", \"type\": ");
+            write_string(&mut json, ty);
+            json.push('}');
+        }
+        json.push_str("]}");
+    }
+    json.push_str("\n  ]\n}\n");
+    json
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// The subset of JSON values the route table uses
+#[derive(Debug)]
+enum Json {
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn get(&self, key: &str) -> Result<&Json, String> {
+        match self {
+            Self::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value)
+                .ok_or_else(|| format!("Missing the key \"{key}\"")),
+            _ => Err(format!("Expected an object with the key \"{key}\"")),
+        }
+    }
+
+    /// The value of `key`, which may be left out of the object
+    fn get_optional(&self, key: &str) -> Result<Option<&Json>, String> {
+        match self {
+            Self::Object(fields) => Ok(fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value)),
+            _ => Err(format!("Expected an object with the key \"{key}\"")),
+        }
+    }
+
+    fn as_str(&self) -> Result<&str, String> {
+        match self {
+            Self::String(value) => Ok(value),
+            _ => Err("Expected a string".to_string()),
+        }
+    }
+
+    fn as_array(&self) -> Result<&[Json], String> {
+        match self {
+            Self::Array(values) => Ok(values),
+            _ => Err("Expected an array".to_string()),
+        }
+    }
+}
+
+/// Read a route table written by [`to_json`]. Only strings, arrays and objects are supported.
+pub fn parse_json(source: &str) -> Result<Vec<TableRoute>, String> {
+    let mut parser = Parser {
+        chars: source.chars().peekable(),
+    };
+    let table = parser.value()?;
+    parser.skip_whitespace();
+    if parser.chars.peek().is_some() {
+        return Err("Unexpected characters after the route table".to_string());
+    }
+
+    table
+        .get("routes")?
+        .as_array()?
+        .iter()
+        .map(|route| {
+            let segments = route
+                .get("segments")?
+                .as_array()?
+                .iter()
+                .map(|segment| {
+                    Ok((
+                        segment.get("name")?.as_str()?.to_string(),
+                        segment.get("type")?.as_str()?.to_string(),
+                    ))
+                })
+                .collect::<Result<_, String>>()?;
+            Ok(TableRoute {
+                variant: route.get("variant")?.as_str()?.to_string(),
+                pattern: route.get("pattern")?.as_str()?.to_string(),
+                hreflang: route
+                    .get_optional("hreflang")?
+                    .map(|hreflang| hreflang.as_str().map(String::from))
+                    .transpose()?,
+                deprecated: route
+                    .get_optional("deprecated")?
+                    .map(|note| note.as_str().map(String::from))
+                    .transpose()?,
+                owner: route
+                    .get_optional("owner")?
+                    .map(|owner| owner.as_str().map(String::from))
+                    .transpose()?,
+                segments,
+            })
+        })
+        .collect()
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("Expected '{expected}', found '{c}'")),
+            None => Err(format!("Expected '{expected}', found the end of the file")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('"') => self.string().map(Json::String),
+            Some('[') => {
+                self.chars.next();
+                let mut values = Vec::new();
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&']').is_none() {
+                    loop {
+                        values.push(self.value()?);
+                        self.skip_whitespace();
+                        match self.chars.next() {
+                            Some(',') => {}
+                            Some(']') => break,
+                            _ => return Err("Expected ',' or ']' in an array".to_string()),
+                        }
+                    }
+                }
+                Ok(Json::Array(values))
+            }
+            Some('{') => {
+                self.chars.next();
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&'}').is_none() {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(':')?;
+                        fields.push((key, self.value()?));
+                        self.skip_whitespace();
+                        match self.chars.next() {
+                            Some(',') => {}
+                            Some('}') => break,
+                            _ => return Err("Expected ',' or '}' in an object".to_string()),
+                        }
+                    }
+                }
+                Ok(Json::Object(fields))
+            }
+            Some(c) => Err(format!(
+                "Unexpected '{c}'. Only strings, arrays and objects are supported"
+            )),
+            None => Err("Unexpected end of the file".to_string()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.chars.next() {
+                    Some('u') => {
+                        let hex: String = self.chars.by_ref().take(4).collect();
+                        let c = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("Invalid escape '\\u{hex}'"))?;
+                        value.push(c);
+                    }
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(c @ ('"' | '\\' | '/')) => value.push(c),
+                    _ => return Err("Invalid escape in a string".to_string()),
+                },
+                Some(c) => value.push(c),
+                None => return Err("Unterminated string".to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn route(pattern: &str, segments: &[(&str, &str)]) -> TableRoute {
+    TableRoute {
+        variant: "Variant".to_string(),
+        pattern: pattern.to_string(),
+        hreflang: None,
+        deprecated: None,
+        owner: None,
+        segments: segments
+            .iter()
+            .map(|(name, ty)| (name.to_string(), ty.to_string()))
+            .collect(),
+    }
+}
+
+#[test]
+fn route_table_json_round_trip() {
+    let routes = vec![
+        route("/", &[]),
+        route(
+            "/blog/(id)/(...rest)",
+            &[("id", "u32"), ("rest", "Vec<String>")],
+        ),
+        route("/say/\"hi\"\\", &[]),
+        TableRoute {
+            hreflang: Some("de".to_string()),
+            ..route("/ueber-uns", &[])
+        },
+        TableRoute {
+            deprecated: Some("use /dashboard".to_string()),
+            ..route("/old-dashboard", &[])
+        },
+        TableRoute {
+            owner: Some("payments-team".to_string()),
+            ..route("/checkout", &[])
+        },
+    ];
+    assert_eq!(parse_json(&to_json(&routes)), Ok(routes));
+    assert_eq!(parse_json("{\"routes\": []}"), Ok(Vec::new()));
+    assert!(parse_json("{\"routes\": [1]}").is_err());
+    assert!(parse_json("{\"routes\": []} trailing").is_err());
+}
+
+#[test]
+fn table_type_names() {
+    let name = |ty: &str| type_name(&syn::parse_str(ty).unwrap());
+    assert_eq!(name("Vec<String>"), "Vec<String>");
+    assert_eq!(
+        name("std::borrow::Cow<'static, str>"),
+        "std::borrow::Cow<'static,str>"
+    );
+    assert_eq!(name("&'static str"), "&'static str");
+}
+
+#[test]
+fn baseline_changes() {
+    let old = vec![
+        route("/", &[]),
+        route("/posts/(id)", &[("id", "u32")]),
+        route("/rss.xml", &[]),
+        route("/users/(name)", &[("name", "String")]),
+    ];
+    let new = vec![
+        route("/", &[]),
+        // Renaming a segment keeps the shape of the URL
+        route("/posts/(post_id)", &[("post_id", "u64")]),
+        route("/feed.xml", &[]),
+    ];
+
+    assert_eq!(
+        compare(
+            &old,
+            &new,
+            &[("/rss.xml".to_string(), "/feed.xml".to_string())]
+        ),
+        [
+            Change::TypeChanged {
+                pattern: "/posts/(post_id)".to_string(),
+                segment: "post_id".to_string(),
+                old: "u32".to_string(),
+                new: "u64".to_string(),
+            },
+            Change::Removed {
+                pattern: "/users/(name)".to_string()
+            },
+        ]
+    );
+    assert!(compare(&old, &new, &[]).iter().any(|change| *change
+        == Change::Removed {
+            pattern: "/rss.xml".to_string()
+        }));
+    assert_eq!(
+        compare(&[], &new, &[("/old".to_string(), "/missing".to_string())]),
+        [Change::UnknownRedirectTarget {
+            from: "/old".to_string(),
+            to: "/missing".to_string()
+        }]
+    );
+    assert!(compare(&old, &old, &[]).is_empty());
+}
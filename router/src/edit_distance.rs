@@ -0,0 +1,26 @@
+//! The distance between two names, for "did you mean" suggestions. The derive suggests page files for a missing
+//! `#[route(page)]`, and the app includes this file with `#[path]` to suggest routes on its not found page.
+
+/// The number of characters that have to be inserted, removed or replaced to turn `a` into `b`
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let replace = previous[j] + usize::from(a != *b);
+            current.push(replace.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[test]
+fn edit_distances() {
+    assert_eq!(edit_distance("search", "search"), 0);
+    assert_eq!(edit_distance("search", "serch"), 1);
+    assert_eq!(edit_distance("search", "saerch"), 2);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("café", "cafe"), 1);
+}
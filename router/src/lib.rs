@@ -1,31 +1,136 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::{__private::Span, format_ident, quote, ToTokens};
-use route::Route;
+use quote::{__private::Span, format_ident, quote, quote_spanned, ToTokens};
+use route::{Route, RouteGroup, RouteSegment};
 use route_tree::RouteTreeSegment;
-use syn::{parse_macro_input, Ident};
+use syn::{parse_macro_input, spanned::Spanned, Ident, LitStr};
 
-use proc_macro2::TokenStream as TokenStream2;
+use options::{RoutableOptions, TrailingSlash};
+use proc_macro2::{Literal, TokenStream as TokenStream2};
 
+mod baseline;
+mod edit_distance;
+mod link;
+mod options;
+mod reserved;
 mod route;
+mod route_segments;
 mod route_tree;
+mod routed_pages;
+mod routes_file;
+mod routes_hash;
+mod segment_enum;
+mod segment_newtype;
+mod size_report;
+#[cfg(test)]
+mod snapshot_tests;
+#[cfg(test)]
+use snapshot_tests::expand_str;
 
-#[proc_macro_derive(Routable, attributes(route))]
+#[proc_macro_derive(Routable, attributes(route, routable, route_segment, group, end_group))]
 pub fn derive_routable(input: TokenStream) -> TokenStream {
     let routes_enum = parse_macro_input!(input as syn::DeriveInput);
 
-    let route_enum = match RouteEnum::parse(routes_enum) {
+    expand(routes_enum).into()
+}
+
+/// Use a fieldless enum as a dynamic route segment, written as the kebab-case name of each variant. Rename a
+/// variant with `#[segment(rename = "...")]`. Also generates `ALL` and `SEGMENT_VALUES` consts with every variant.
+#[proc_macro_derive(RouteSegmentEnum, attributes(segment))]
+pub fn derive_route_segment_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    segment_enum::expand(input).into()
+}
+
+/// Use a newtype like `struct UserId(u64)` as a dynamic route segment. `Display` and `FromStr` delegate to the
+/// wrapped type, and parse errors are wrapped in a generated `UserIdSegmentError`.
+#[proc_macro_derive(RouteSegment)]
+pub fn derive_route_segment(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    segment_newtype::expand(input).into()
+}
+
+/// Generate a routable enum from a routes file, like `routes_from_file!("src/routes.toml")`. The path is relative to
+/// the crate root. See the `routes_file` module for the format.
+#[proc_macro]
+pub fn routes_from_file(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as syn::LitStr);
+
+    routes_file::expand(path).into()
+}
+
+/// Parse and write a single route pattern in a hand-written `Routable` impl, like `route_segments!("/blog/(id: u64)")`.
+/// Every dynamic segment is followed by its type. See the `route_segments` module for what it evaluates to.
+#[proc_macro]
+pub fn route_segments(input: TokenStream) -> TokenStream {
+    let pattern = parse_macro_input!(input with route::parse_pattern_literal);
+
+    route_segments::expand(pattern).into()
+}
+
+/// Check a literal path against a routable type with `#[routable(link_macro)]` while the crate is built, and evaluate
+/// to the route it parses as, like `link!(Route, "/blog/42")`. See the `link` module for which paths are checked.
+#[proc_macro]
+pub fn link(input: TokenStream) -> TokenStream {
+    link::expand(input.into()).into()
+}
+
+/// Fail the build if a public page component of a module has no route, like `#[routed_pages(crate::pages)]` on the
+/// routable type. Place it above `#[derive(Routable)]`. See the `routed_pages` module for how pages are found.
+#[proc_macro_attribute]
+pub fn routed_pages(attr: TokenStream, item: TokenStream) -> TokenStream {
+    routed_pages::expand(attr.into(), item.into()).into()
+}
+
+/// Generate everything the derive emits for a routable type
+fn expand(input: syn::DeriveInput) -> TokenStream2 {
+    let route_enum = match RouteEnum::parse(input) {
         Ok(route_enum) => route_enum,
-        Err(err) => return TokenStream2::from(err.to_compile_error()).into(),
+        Err(err) => return err.to_compile_error(),
     };
 
+    if route_enum.options.size_report {
+        eprint!("{}", route_enum.size_report());
+    }
+
     let error_type = route_enum.error_type();
     let parse_impl = route_enum.parse_impl();
     let display_impl = route_enum.impl_display();
     let routable_impl = route_enum.routable_impl();
+    let inherent_impl = route_enum.inherent_impl();
+    let patterns_impl = route_enum.patterns_impl();
+    let paths_module = route_enum.paths_module();
+    let site_map_impl = route_enum.site_map_impl();
+    let comparison_impl = route_enum.comparison_impl();
+    let attribute_value_impl = route_enum.attribute_value_impl();
+    let builder_impl = route_enum.builder_impl();
+    let versioned_impl = route_enum.versioned_impl();
+    let lenient_impl = route_enum.lenient_impl();
+    let http_impl = route_enum.http_impl();
+    let shared_impl = route_enum.shared_impl();
+    let strict_display_impl = route_enum.strict_display_impl();
+    let conflict_test = route_enum.conflict_test();
+    let fixtures_module = route_enum.fixtures_module();
+    let link_macro = route_enum.link_macro();
+    let nested_impl = route_enum.nested_impl();
+    let child_impl = route_enum.child_impl();
+    let baseline_check = route_enum.baseline_check();
+    let segment_type_checks = route_enum.segment_type_checks();
+    // A glob import, so items of the same name in the module still shadow the router's and two types deriving in
+    // the same module don't import anything twice
+    let runtime_import = route_enum.options.runtime.as_ref().map(|runtime| {
+        quote! {
+            #[allow(unused_imports)]
+            use #runtime::*;
+        }
+    });
 
     quote! {
+        #runtime_import
+
         #route_enum
 
         #error_type
@@ -35,64 +140,433 @@ pub fn derive_routable(input: TokenStream) -> TokenStream {
         #display_impl
 
         #routable_impl
+
+        #inherent_impl
+
+        #patterns_impl
+
+        #paths_module
+
+        #site_map_impl
+
+        #comparison_impl
+
+        #attribute_value_impl
+
+        #builder_impl
+
+        #versioned_impl
+
+        #lenient_impl
+
+        #http_impl
+
+        #shared_impl
+
+        #strict_display_impl
+
+        #conflict_test
+
+        #fixtures_module
+
+        #link_macro
+
+        #nested_impl
+
+        #child_impl
+
+        #baseline_check
+
+        #segment_type_checks
     }
-    .into()
 }
 
 struct RouteEnum {
     route_name: Ident,
     routes: Vec<Route>,
+    options: RoutableOptions,
+    /// The dynamic segments of `#[routable(shared = "...")]` and their type, which is the same in every variant
+    shared_fields: Vec<(Ident, syn::Type)>,
 }
 
-impl RouteEnum {
-    fn parse(input: syn::DeriveInput) -> syn::Result<Self> {
-        let name = &input.ident;
+/// Check that every locale of a localized route is listed in `locales(...)`, if the type lists its locales.
+fn check_locales(routes: &[Route], options: &RoutableOptions) -> syn::Result<()> {
+    if options.locales.is_empty() {
+        return Ok(());
+    }
+    let locales = routes
+        .iter()
+        .filter(|route| !route.localized.is_empty())
+        .flat_map(|route| std::iter::once(route).chain(&route.localized))
+        .filter_map(|route| route.locale.as_ref());
+    for locale in locales {
+        if !options
+            .locales
+            .iter()
+            .any(|(listed, _)| *listed == locale.value())
+        {
+            return Err(syn::Error::new_spanned(
+                locale,
+                format!(
+                    "The locale '{}' is missing from #[routable(locales(...))]",
+                    locale.value()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
 
-        if let syn::Data::Enum(data) = input.data {
-            let mut routes = Vec::new();
+/// Check that only one variant is the `#[route(not_found)]` fallback, and that there are other routes to fall back
+/// from.
+fn check_not_found(routes: &[Route]) -> syn::Result<()> {
+    let mut fallbacks = routes
+        .iter()
+        .filter_map(|route| Some((route, route.not_found.as_ref()?)));
+    let Some((first, not_found)) = fallbacks.next() else {
+        return Ok(());
+    };
+    if let Some((second, other)) = fallbacks.next() {
+        let mut error = syn::Error::new_spanned(
+            other,
+            format!(
+                "Only one variant can be #[route(not_found)], but {} already is, so {} can't be",
+                first.route_name, second.route_name
+            ),
+        );
+        error.combine(syn::Error::new_spanned(
+            not_found,
+            format!("{} is the not_found variant here", first.route_name),
+        ));
+        return Err(error);
+    }
+    if routes.len() == 1 {
+        return Err(syn::Error::new_spanned(
+            not_found,
+            "The not_found variant is returned when no other route matches, so the enum needs at least one other route",
+        ));
+    }
+    Ok(())
+}
 
-            for variant in data.variants {
-                let route = Route::parse(variant)?;
-                routes.push(route);
+/// Check that no shortcut is the same as another one or starts with all of its keys, since the longer one could never
+/// be typed.
+fn check_shortcuts(routes: &[Route]) -> syn::Result<()> {
+    let shortcuts: Vec<_> = routes
+        .iter()
+        .filter_map(|route| Some((route, route.shortcut.as_ref()?)))
+        .collect();
+    for (i, (route, shortcut)) in shortcuts.iter().enumerate() {
+        let keys: Vec<String> = shortcut
+            .value()
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        for (other, other_shortcut) in &shortcuts[..i] {
+            let other_keys: Vec<String> = other_shortcut
+                .value()
+                .split_whitespace()
+                .map(String::from)
+                .collect();
+            let (shorter, longer) = match keys.len() <= other_keys.len() {
+                true => (&keys, &other_keys),
+                false => (&other_keys, &keys),
+            };
+            if longer.starts_with(shorter) {
+                return Err(syn::Error::new_spanned(
+                    shortcut,
+                    format!(
+                        "The shortcut '{}' of {} conflicts with '{}' of {}",
+                        keys.join(" "),
+                        route.route_name,
+                        other_keys.join(" "),
+                        other.route_name
+                    ),
+                ));
             }
+        }
+    }
+    Ok(())
+}
+
+/// How a field is read from a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SegmentRole {
+    Dynamic,
+    CatchAll,
+    /// A catch-all with `#[route_segment(include_query)]`, whose value ends with the query
+    Query,
+}
+
+impl SegmentRole {
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Dynamic => "a dynamic segment",
+            Self::CatchAll => "a catch-all",
+            Self::Query => "a catch-all with the query",
+        }
+    }
+}
 
-            let myself = Self {
-                route_name: name.clone(),
-                routes,
+/// Check that a field type is read the same way in every variant. A type that is a dynamic segment in one variant
+/// and a catch-all in another is parsed with `FromStr` in one and with `FromRouteSegments` in the other, and a
+/// catch-all with the query gets a different value than one without, so the two can drift apart unnoticed. Fields
+/// with `#[route_segment(role_aware)]` are left out.
+fn check_segment_roles(routes: &[Route]) -> syn::Result<()> {
+    let mut sites: Vec<(String, SegmentRole, &Ident, &syn::Type)> = Vec::new();
+    for route in routes {
+        for segment in &route.route_segments {
+            let (ident, ty, role) = match segment {
+                // Matrix params are only ever parsed with `FromMatrix`, queries with `FromQuery` and fragments as a
+                // whole with `FromStr`
+                RouteSegment::Static(_)
+                | RouteSegment::Matrix(..)
+                | RouteSegment::Query(..)
+                | RouteSegment::Fragment(..) => continue,
+                RouteSegment::Dynamic(ident, ty, _) => (ident, ty, SegmentRole::Dynamic),
+                // The value of an optional `Option<T>` segment is parsed as a `T`
+                RouteSegment::Optional(ident, ty) => (
+                    ident,
+                    route::option_inner(ty).unwrap_or(ty),
+                    SegmentRole::Dynamic,
+                ),
+                RouteSegment::CatchAll(ident, ty) => match route.includes_query() {
+                    true => (ident, ty, SegmentRole::Query),
+                    false => (ident, ty, SegmentRole::CatchAll),
+                },
             };
+            if route
+                .segment_options(ident)
+                .is_some_and(|options| options.role_aware)
+            {
+                continue;
+            }
+            let name = ty.to_token_stream().to_string();
+            let other = sites
+                .iter()
+                .find(|(other, other_role, ..)| *other == name && *other_role != role);
+            if let Some((_, other_role, other_route, other_ty)) = other {
+                let mut error = syn::Error::new_spanned(
+                    ty,
+                    format!(
+                        "`{name}` is {} in {} but {} in {other_route}, so the two can encode it differently. \
+                         Add #[route_segment(role_aware)] to both fields if it is parsed the same way in both",
+                        role.describe(),
+                        route.route_name,
+                        other_role.describe(),
+                    ),
+                );
+                error.combine(syn::Error::new_spanned(
+                    other_ty,
+                    format!("`{name}` is {} here", other_role.describe()),
+                ));
+                return Err(error);
+            }
+            sites.push((name, role, &route.route_name, ty));
+        }
+    }
+    Ok(())
+}
 
-            Ok(myself)
-        } else {
-            Err(syn::Error::new_spanned(
-                input.clone(),
-                "Routable can only be derived for enums",
-            ))
+/// Find the type of every dynamic segment in the shared segments, which must be the same in every variant.
+fn shared_fields(shared: &LitStr, routes: &[Route]) -> syn::Result<Vec<(Ident, syn::Type)>> {
+    let count = shared.value().split('/').count() - 1;
+
+    let mut fields: Vec<(Ident, syn::Type)> = Vec::new();
+    for route in routes {
+        for segment in &route.route_segments[..count] {
+            match segment {
+                RouteSegment::Static(_) => {}
+                RouteSegment::Dynamic(ident, ty, _) => {
+                    match fields.iter().find(|(field, _)| field == ident) {
+                        Some((_, expected)) if expected != ty => {
+                            return Err(syn::Error::new_spanned(
+                                ty,
+                                format!(
+                                    "The shared segment '{}' of '{}' is a {}, but it is a {} in the other variants",
+                                    ident,
+                                    route.route_name,
+                                    ty.to_token_stream(),
+                                    expected.to_token_stream(),
+                                ),
+                            ));
+                        }
+                        Some(_) => {}
+                        None => fields.push((ident.clone(), ty.clone())),
+                    }
+                }
+                RouteSegment::CatchAll(..) => {
+                    return Err(syn::Error::new_spanned(
+                        shared,
+                        "Shared segments can't contain a catch-all segment",
+                    ));
+                }
+                RouteSegment::Optional(..) => {
+                    return Err(syn::Error::new_spanned(
+                        shared,
+                        "Shared segments can't contain an optional segment",
+                    ));
+                }
+                RouteSegment::Matrix(..) => {
+                    return Err(syn::Error::new_spanned(
+                        shared,
+                        "Shared segments can't have matrix params",
+                    ));
+                }
+                RouteSegment::Query(..) | RouteSegment::Fragment(..) => {
+                    return Err(syn::Error::new_spanned(
+                        shared,
+                        "Shared segments can't have a query or a fragment",
+                    ));
+                }
+            }
         }
     }
+    Ok(fields)
+}
 
-    fn impl_display(&self) -> TokenStream2 {
-        let mut display_match = Vec::new();
+impl RouteEnum {
+    fn parse(input: syn::DeriveInput) -> syn::Result<Self> {
+        let name = &input.ident;
+        let options = RoutableOptions::parse(&input.attrs)?;
 
-        for route in &self.routes {
-            display_match.push(route.display_match());
+        match &input.data {
+            syn::Data::Enum(data) => {
+                let mut routes = Vec::new();
+                // The errors of every variant are reported together, instead of one compile at a time
+                let mut errors = None;
+
+                // The `#[group(...)]` the variants are in, until the variant with `#[end_group]`
+                let mut group: Option<(&syn::Attribute, RouteGroup)> = None;
+
+                for variant in &data.variants {
+                    if let Some(attr) = variant
+                        .attrs
+                        .iter()
+                        .find(|attr| attr.path.is_ident("group"))
+                    {
+                        if group.is_some() {
+                            route::push_error(
+                                &mut errors,
+                                syn::Error::new_spanned(
+                                    attr,
+                                    "Groups can't be nested. End the group before this one with #[end_group] on its last variant",
+                                ),
+                            );
+                        }
+                        // A group that doesn't parse still pairs with its `#[end_group]`
+                        let parsed = attr.parse_args::<RouteGroup>().unwrap_or_else(|error| {
+                            route::push_error(&mut errors, error);
+                            RouteGroup::default()
+                        });
+                        group = Some((attr, parsed));
+                    }
+                    let route = Route::parse_in_group(
+                        variant.clone(),
+                        options.shared.as_ref(),
+                        group.as_ref().map(|(_, group)| group),
+                    )
+                    .and_then(|mut route| {
+                        route.set_default_locale(&options.default_locale())?;
+                        route.set_path_options(&options)?;
+                        Ok(route)
+                    });
+                    match route {
+                        Ok(route) => routes.push(route),
+                        Err(error) => route::push_error(&mut errors, error),
+                    }
+                    if let Some(attr) = variant
+                        .attrs
+                        .iter()
+                        .find(|attr| attr.path.is_ident("end_group"))
+                    {
+                        if group.take().is_none() {
+                            route::push_error(
+                                &mut errors,
+                                syn::Error::new_spanned(
+                                    attr,
+                                    "#[end_group] ends the #[group(...)] of this or an earlier variant, but there is none",
+                                ),
+                            );
+                        }
+                    }
+                }
+                if let Some((attr, _)) = group {
+                    route::push_error(
+                        &mut errors,
+                        syn::Error::new_spanned(
+                            attr,
+                            "This group isn't ended. Add #[end_group] to its last variant",
+                        ),
+                    );
+                }
+                if let Some(errors) = errors {
+                    return Err(errors);
+                }
+                check_not_found(&routes)?;
+                route_tree::check_limits(name, &routes)?;
+                route_tree::check_reachable(&routes)?;
+                check_shortcuts(&routes)?;
+                check_locales(&routes, &options)?;
+                check_segment_roles(&routes)?;
+                let shared_fields = match &options.shared {
+                    Some(shared) => shared_fields(shared, &routes)?,
+                    None => Vec::new(),
+                };
+
+                Ok(Self {
+                    route_name: name.clone(),
+                    routes,
+                    options,
+                    shared_fields,
+                })
+            }
+            syn::Data::Struct(data) => {
+                if let Some(shared) = &options.shared {
+                    return Err(syn::Error::new_spanned(
+                        shared,
+                        "Shared segments are only supported on enums. Add them to the struct's route instead",
+                    ));
+                }
+                let mut route = Route::parse_struct(&input, &data.fields)?;
+                route.set_default_locale(&options.default_locale())?;
+                route.set_path_options(&options)?;
+                check_locales(std::slice::from_ref(&route), &options)?;
+
+                Ok(Self {
+                    route_name: name.clone(),
+                    routes: vec![route],
+                    options,
+                    shared_fields: Vec::new(),
+                })
+            }
+            _ => Err(syn::Error::new_spanned(
+                input.clone(),
+                "Routable can only be derived for enums or structs",
+            )),
         }
+    }
 
+    fn impl_display(&self) -> TokenStream2 {
         let name = &self.route_name;
 
         quote! {
-            impl std::fmt::Display for #name {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    match self {
-                        #(#display_match)*
-                    }
-                    Ok(())
+            impl ::std::fmt::Display for #name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    Routable::write_path(self, f)
                 }
             }
         }
     }
 
+    /// Every route followed by the routes of its localized patterns and aliases, which the parser tries like any other
+    /// route
+    fn patterns(&self) -> impl Iterator<Item = &Route> {
+        self.routes.iter().flat_map(Route::with_patterns)
+    }
+
     fn parse_impl(&self) -> TokenStream2 {
-        let tree = RouteTreeSegment::build(&self.routes);
+        let tree = RouteTreeSegment::build(self.patterns());
         let name = &self.route_name;
 
         let error_name = format_ident!("{}MatchError", self.route_name);
@@ -100,20 +574,198 @@ impl RouteEnum {
             .into_iter()
             .map(|t| t.to_tokens(self.route_name.clone(), error_name.clone()));
 
+        // Only routes with an `include_query` catch-all or a `?(query)` see the query. Other routes ignore it, like a
+        // query a location from the browser has
+        let (split_query, query_param, query_arg, no_query) = match self.parses_query() {
+            true => (
+                quote! {
+                    let (s, __router_query) = match s.split_once('?') {
+                        Some((path, query)) => (path, Some(query)),
+                        None => (s, None),
+                    };
+                },
+                quote! { , __router_query: Option<&str> },
+                quote! { , __router_query },
+                quote! { , None },
+            ),
+            false => (
+                quote! { let s = s.split_once('?').map_or(s, |(path, _)| path); },
+                quote! {},
+                quote! {},
+                quote! {},
+            ),
+        };
+        // Routes with a `#(fragment)` get it with the offset of its `#`, which is split off before the query. Other
+        // routes ignore it
+        let (split_fragment, fragment_param, fragment_arg, no_fragment) =
+            match self.parses_fragment() {
+                true => (
+                    quote! {
+                        let (s, __router_fragment) = match s.split_once('#') {
+                            Some((rest, fragment)) => (rest, Some((origin + rest.len(), fragment))),
+                            None => (s, None),
+                        };
+                    },
+                    quote! { , __router_fragment: Option<(usize, &str)> },
+                    quote! { , __router_fragment },
+                    quote! { , None },
+                ),
+                false => (
+                    quote! { let s = s.split_once('#').map_or(s, |(rest, _)| rest); },
+                    quote! {},
+                    quote! {},
+                    quote! {},
+                ),
+            };
+        let max_path_len = Literal::usize_unsuffixed(self.options.max_path_len());
+        let max_segments = Literal::usize_unsuffixed(self.options.max_segments());
+        let parse_error = self.parse_error_type();
+        let box_parse_error = self.box_parse_error();
+        let shared_guard = self.shared_guard_check();
+        let require_trailing_slash = self.require_trailing_slash(quote! {
+            return Err(RouteParseError::limit(ParseLimit::MissingTrailingSlash))
+        });
+        let normalize_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.normalize_match())
+            .collect();
+        let (normalize, normalized) = match normalize_match.is_empty() {
+            true => (quote! {}, quote! {}),
+            false => (
+                quote! { .map(Self::normalized) },
+                quote! {
+                    /// Canonicalize the values of a parsed route with the `normalize = ...` function of its variant.
+                    fn normalized(mut self) -> Self {
+                        #[allow(unreachable_patterns)]
+                        match &mut self {
+                            #(#normalize_match)*
+                            _ => {}
+                        }
+                        self
+                    }
+                },
+            ),
+        };
+        let (from_str, not_found) = match self.not_found_impl() {
+            Some(not_found) => (
+                quote! {
+                    match Self::parse_path(s) {
+                        Err(err) if err.limit.is_none() => Self::not_found(s).ok_or(err),
+                        parsed => parsed,
+                    } #box_parse_error
+                },
+                not_found,
+            ),
+            None => (quote! { Self::parse_path(s) #box_parse_error }, quote! {}),
+        };
+        let downcast = self.options.boxed_errors.then(|| {
+            quote! {
+                /// The error inside a boxed parse error of this type, with the error of every route that was tried.
+                /// Returns `None` for errors that didn't come from parsing this type.
+                pub(crate) fn parse_error<'e>(
+                    err: &'e (dyn ::std::error::Error + Send + Sync + 'static),
+                ) -> Option<&'e RouteParseError<#error_name>> {
+                    err.downcast_ref()
+                }
+            }
+        });
+
         quote! {
             impl<'a> TryFrom<&'a str> for #name {
-                type Error = <Self as std::str::FromStr>::Err;
+                type Error = <Self as ::std::str::FromStr>::Err;
 
                 fn try_from(s: &'a str) -> Result<Self, Self::Error> {
                     s.parse()
                 }
             }
 
-            impl std::str::FromStr for #name {
-                type Err = RouteParseError<#error_name>;
+            impl ::std::str::FromStr for #name {
+                type Err = #parse_error;
 
                 fn from_str(s: &str) -> Result<Self, Self::Err> {
-                    let mut segments = s.strip_prefix('/').unwrap_or(s).split('/');
+                    #from_str
+                }
+            }
+
+            impl #name {
+                #downcast
+
+                #not_found
+
+                #normalized
+
+                fn parse_path(s: &str) -> Result<Self, RouteParseError<#error_name>> {
+                    // Reject long paths before splitting them, so untrusted input can't make the parser do
+                    // unbounded work
+                    if s.len() > #max_path_len {
+                        return Err(RouteParseError::limit(ParseLimit::PathTooLong {
+                            len: s.len(),
+                            max: #max_path_len,
+                        }));
+                    }
+                    // A full url, like `window.location.href`, is parsed from its path
+                    let origin = url_path_start(s);
+                    let s = &s[origin..];
+                    #split_fragment
+                    #split_query
+                    // Spans refer to the original, still percent-encoded input, including the leading slash
+                    let (path, offset) = match s.strip_prefix('/') {
+                        Some(path) => (path, origin + 1),
+                        None => (s, origin),
+                    };
+                    #require_trailing_slash
+                    Self::match_segments(SegmentCursor::new(path.split('/'), offset) #query_arg #fragment_arg) #normalize
+                }
+
+                /// Parse a route from a path that is already split into segments, like the path parts extracted
+                /// by an HTTP framework. The segments must not include the leading slash; no segments at all
+                /// matches the root route.
+                ///
+                /// This behaves exactly like parsing the joined path with [`std::str::FromStr`]. Spans in the
+                /// errors refer to the joined path.
+                pub fn from_segments<'a>(
+                    segments: impl Iterator<Item = &'a str> + Clone,
+                ) -> Result<Self, #parse_error> {
+                    if segments.clone().next().is_none() {
+                        return Self::match_segments(SegmentCursor::new(::std::iter::once(""), 1) #no_query #no_fragment)
+                            #normalize #box_parse_error;
+                    }
+                    Self::match_segments(SegmentCursor::new(segments, 1) #no_query #no_fragment) #normalize #box_parse_error
+                }
+
+                /// Parse a location under the base path the app is served from, like `/app/blog/1` under `/app`.
+                /// A slash at the end of the base is ignored, and locations outside of it, like `/application`,
+                /// are rejected with [`ParseLimit::OutsideBase`] without trying any route.
+                pub fn parse_with_base(location: &str, base: &str) -> Result<Self, #parse_error> {
+                    match strip_base_path(base, location) {
+                        Some(location) => location.parse(),
+                        None => Err(RouteParseError::limit(ParseLimit::OutsideBase {
+                            base: base.to_string(),
+                        })) #box_parse_error,
+                    }
+                }
+
+                fn match_segments<'a>(
+                    mut segments: SegmentCursor<impl Iterator<Item = &'a str> + Clone>
+                    #query_param
+                    #fragment_param
+                ) -> Result<Self, RouteParseError<#error_name>> {
+                    if segments.clone().take(#max_segments + 1).count() > #max_segments {
+                        return Err(RouteParseError::limit(ParseLimit::TooManySegments {
+                            max: #max_segments,
+                        }));
+                    }
+                    let len = segments.end();
+                    if len > #max_path_len {
+                        return Err(RouteParseError::limit(ParseLimit::PathTooLong {
+                            len,
+                            max: #max_path_len,
+                        }));
+                    }
+
+                    #shared_guard
+
                     let mut errors = Vec::new();
 
                     if let Some(segment) = segments.next() {
@@ -122,85 +774,2651 @@ impl RouteEnum {
 
                     Err(RouteParseError {
                         attempted_routes: errors,
+                        limit: None,
                     })
                 }
             }
         }
     }
 
-    fn error_name(&self) -> Ident {
-        Ident::new(
-            &(self.route_name.to_string() + "MatchError"),
-            Span::call_site(),
-        )
+    /// `try_parse` and the constructor of the `#[route(not_found)]` variant, which `FromStr` returns instead of an
+    /// error when no route matches. Paths over the parse limits are still errors, so they never reach the fallback.
+    fn not_found_impl(&self) -> Option<TokenStream2> {
+        let route = self.routes.iter().find(|route| route.not_found.is_some())?;
+        let name = &self.route_name;
+        let parse_error = self.parse_error_type();
+        let box_parse_error = self.box_parse_error();
+        let construct = route.construct(name.clone());
+        let bind = route.path_segments().iter().map(|segment| {
+            let RouteSegment::CatchAll(ident, ty) = segment else {
+                unreachable!("the route of the not_found variant is a catch-all")
+            };
+            quote! {
+                let #ident = <#ty as FromRouteSegments>::from_route_segments(
+                    path.split('/').take(if path.is_empty() { 0 } else { usize::MAX }),
+                )
+                .ok()?;
+            }
+        });
+        let variant = &route.route_name;
+        let doc = format!(
+            "Parse a location like [`std::str::FromStr`], but return the error instead of [`{name}::{variant}`] when no route matches."
+        );
+
+        Some(quote! {
+            #[doc = #doc]
+            pub fn try_parse(s: &str) -> Result<Self, #parse_error> {
+                Self::parse_path(s) #box_parse_error
+            }
+
+            /// The not_found variant for a location no route matched, with the path of the location if it has a
+            /// field for it. Returns `None` if the path doesn't parse as the field.
+            #[allow(unused_variables)]
+            fn not_found(s: &str) -> Option<Self> {
+                let s = &s[url_path_start(s)..];
+                let path = s.find(['?', '#']).map_or(s, |end| &s[..end]);
+                let path = path.strip_prefix('/').unwrap_or(path);
+                #(#bind)*
+                Some(#construct)
+            }
+        })
     }
 
-    fn error_type(&self) -> TokenStream2 {
-        let match_error_name = self.error_name();
+    /// Run the `shared_guard = ...` of the enum on the values of the shared segments before any route is tried, so a
+    /// path it rejects fails without parsing the rest. Paths whose shared segments don't parse are left to the
+    /// routes, which report why.
+    fn shared_guard_check(&self) -> TokenStream2 {
+        let (Some(shared), Some(guard), Some(route)) = (
+            &self.options.shared,
+            &self.options.shared_guard,
+            self.routes.first(),
+        ) else {
+            return quote! {};
+        };
+        let count = shared.value().split('/').count() - 1;
+        let parse = route.route_segments[..count]
+            .iter()
+            .map(|segment| match segment {
+                RouteSegment::Static(segment) => {
+                    let ne = route.static_ne(quote! { segments.next()? }, segment);
+                    quote! {
+                        if #ne {
+                            return None;
+                        }
+                    }
+                }
+                _ => {
+                    let ident = segment.name();
+                    let value = segment.parse_value(
+                        quote! { segment },
+                        ident
+                            .as_ref()
+                            .is_some_and(|ident| route.allows_empty(ident)),
+                    );
+                    quote! {
+                        let segment = segments.next()?;
+                        let #ident = #value?;
+                    }
+                }
+            });
+        let fields = self.shared_fields.iter().map(|(field, _)| field);
+        let call = quote_spanned! {guard.span()=> #guard(#(&#fields),*) };
 
-        let mut type_defs = Vec::new();
-        let mut error_variants = Vec::new();
-        let mut display_match = Vec::new();
+        quote! {
+            let checked = (|| {
+                let mut segments = segments.clone();
+                #(#parse)*
+                Some(#call)
+            })();
+            if let Some(Err(reason)) = checked {
+                return Err(RouteParseError::limit(ParseLimit::SharedGuard {
+                    reason: reason.to_string(),
+                }));
+            }
+        }
+    }
 
-        for route in &self.routes {
-            let route_name = &route.route_name;
+    /// With `trailing_slash = "require"`, strip the slash at the end of the `path` before it is split into
+    /// segments, and run `missing` if there is none. The root path is the slash, so it is left as it is.
+    fn require_trailing_slash(&self, missing: TokenStream2) -> TokenStream2 {
+        if self.options.trailing_slash != TrailingSlash::Require {
+            return quote! {};
+        }
+        quote! {
+            let path = match path.strip_suffix('/') {
+                Some(path) => path,
+                None if path.is_empty() => path,
+                None => #missing,
+            };
+        }
+    }
 
-            let error_name = Ident::new(&format!("{}ParseError", route_name), Span::call_site());
-            let route_str = &route.route;
+    /// If a route of this enum parses the query, which means the parser splits the query off the path first
+    fn parses_query(&self) -> bool {
+        self.routes
+            .iter()
+            .any(|route| route.includes_query() || route.has_query())
+    }
+
+    /// If a route of this enum parses the fragment, which means the parser splits the fragment off the location
+    /// before the query
+    fn parses_fragment(&self) -> bool {
+        self.routes.iter().any(Route::has_fragment)
+    }
+
+    /// Measure the generated code of every route. This only reports on the expansion and doesn't change it.
+    fn size_report(&self) -> String {
+        let sizes = self
+            .routes
+            .iter()
+            .map(|route| size_report::RouteSize::measure(route, &self.route_name))
+            .collect();
+        size_report::format_report(&self.route_name, sizes)
+    }
 
-            error_variants.push(quote! { #route_name(#error_name) });
-            display_match.push(quote! { Self::#route_name(err) => write!(f, "Route '{}' ('{}') did not match:\n{}", stringify!(#route_name), #route_str, err)? });
-            type_defs.push(route.error_type());
+    /// Generate inherent methods for the common conversions so they work without importing any trait.
+    fn inherent_impl(&self) -> Option<TokenStream2> {
+        if self.options.no_inherent {
+            return None;
         }
+        let name = &self.route_name;
+        let parse_error = self.parse_error_type();
 
-        quote! {
-            #(#type_defs)*
+        Some(quote! {
+            impl #name {
+                /// The path of this route. The same as its `Display` output.
+                pub fn to_path(&self) -> String {
+                    let mut path = String::new();
+                    // Writing to a string only fails if a segment's `Display` impl fails
+                    Routable::write_path(self, &mut path).expect("a segment failed to display");
+                    path
+                }
 
-            #[derive(Debug, PartialEq)]
-            pub enum #match_error_name {
-                #(#error_variants),*
+                /// Parse a route from a path. The same as parsing it with [`std::str::FromStr`].
+                pub fn from_path(path: &str) -> Result<Self, #parse_error> {
+                    <Self as ::std::str::FromStr>::from_str(path)
+                }
             }
+        })
+    }
 
-            impl std::fmt::Display for #match_error_name {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    match self {
-                        #(#display_match),*
+    fn patterns_impl(&self) -> TokenStream2 {
+        let name = &self.route_name;
+
+        let mut routes = Vec::new();
+        for segment in RouteTreeSegment::build(self.patterns()) {
+            segment.routes_in_match_order(&mut routes);
+        }
+        let patterns = routes.iter().map(|route| &route.route);
+        let action_patterns = routes
+            .iter()
+            .filter(|route| route.action.is_some())
+            .map(|route| &route.route);
+        let precache = routes
+            .iter()
+            .filter(|route| route.is_precacheable())
+            .map(|route| &route.route);
+        let network_only = routes
+            .iter()
+            .filter(|route| route.action.is_none() && !route.is_precacheable())
+            .map(|route| route.glob_pattern());
+        let split_path = match self.options.trailing_slash {
+            TrailingSlash::Accept => {
+                quote! { let mut segments = path.strip_prefix('/').unwrap_or(path).split('/'); }
+            }
+            TrailingSlash::Require => {
+                let require_trailing_slash = self.require_trailing_slash(quote! { return None });
+                quote! {
+                    let path = path.strip_prefix('/').unwrap_or(path);
+                    #require_trailing_slash
+                    let mut segments = path.split('/');
+                }
+            }
+        };
+        let pattern_matcher = RouteTreeSegment::build(self.patterns())
+            .into_iter()
+            .map(|segment| segment.to_pattern_tokens());
+        let pattern_params_match = self
+            .patterns()
+            .map(|route| route.pattern_params_match(name));
+        let route_table_json = baseline::to_json(&self.route_table());
+        let routes_hash = routes_hash::routes_hash(&routes);
+        let variant_patterns = self.routes.iter().map(|route| {
+            let variant = route.route_name.to_string();
+            let pattern = &route.route;
+            quote! { #variant => Some(#pattern), }
+        });
+
+        quote! {
+            impl #name {
+                /// The pattern of the variant called `name`, like the pattern of a route a server handles
+                /// separately. Returns `None` if no variant has that name.
+                pub fn pattern_of_variant(name: &str) -> Option<&'static str> {
+                    match name {
+                        #(#variant_patterns)*
+                        _ => None,
+                    }
+                }
+
+                /// Find the pattern of the route a path corresponds to without parsing any dynamic segments.
+                ///
+                /// Dynamic segments match any value here, so a path can resolve to a pattern even if its segments
+                /// would fail to parse as the route's fields.
+                #[allow(unused_variables)]
+                pub fn match_pattern(path: &str) -> Option<&'static str> {
+                    #split_path
+
+                    if let Some(segment) = segments.next() {
+                        #(#pattern_matcher)*
+                    }
+
+                    None
+                }
+
+                /// Build a route from one of the patterns in [`Self::ROUTE_PATTERNS`] and the values of its
+                /// dynamic segments, like routes stored as data in a CMS.
+                ///
+                /// Parameters are looked up by the name of their segment and are parsed as they are, without
+                /// percent-decoding. A catch-all parameter is split on `/`. Parameters the pattern doesn't use
+                /// are ignored.
+                pub fn from_pattern_and_params(
+                    pattern: &str,
+                    params: &[(&str, &str)],
+                ) -> Result<Self, BuildError> {
+                    #[allow(unused_variables)]
+                    let param = |name: &'static str| {
+                        params
+                            .iter()
+                            .find(|(key, _)| *key == name)
+                            .map(|(_, value)| *value)
+                            .ok_or(BuildError::MissingParam(name))
+                    };
+
+                    match pattern {
+                        #(#pattern_params_match)*
+                        _ => Err(BuildError::UnknownPattern(pattern.to_string())),
+                    }
+                }
+
+                /// The route pattern of every variant in the order the parser tries them.
+                ///
+                /// Only patterns that parse into a variant are listed here. Patterns that are never
+                /// matched directly (like redirects or fallbacks) belong in their own constants. Routes
+                /// with `feature = "..."` are listed in every build; check `gated_routes` for the ones
+                /// that are compiled out.
+                pub const ROUTE_PATTERNS: &'static [&'static str] = &[#(#patterns),*];
+
+                /// The patterns in [`Self::ROUTE_PATTERNS`] of routes that run an action instead of rendering a
+                /// page. Tools that list pages, like site maps, should leave these out.
+                pub const ACTION_ROUTE_PATTERNS: &'static [&'static str] = &[#(#action_patterns),*];
+
+                /// Every route with the name and type of its segments as JSON, and the locale of localized
+                /// patterns as `hreflang`. Save it as the `#[routable(baseline = "...")]` file to check later
+                /// versions of the routes against this one.
+                pub const ROUTE_TABLE_JSON: &'static str = #route_table_json;
+
+                /// A hash of the routes that changes when a pattern, the type of a segment or the metadata of a route
+                /// changes. Put it in asset URLs to invalidate cached pages when the routes change; the precache
+                /// manifest uses it as the revision of every page.
+                pub const ROUTES_HASH: &'static str = #routes_hash;
+
+                /// Split the pages into the paths a service worker can cache ahead of time and the patterns that
+                /// must always be fetched from the network.
+                ///
+                /// Only routes without dynamic segments whose `cache = "..."` hint allows shared caching are
+                /// precached. Every other page is network only, with dynamic segments written as `*` and
+                /// catch-alls as `**`. Action routes are in neither list.
+                pub fn precache_manifest() -> PrecacheManifest {
+                    PrecacheManifest {
+                        precache: vec![#(#precache.to_string()),*],
+                        revision: Self::ROUTES_HASH,
+                        network_only_patterns: vec![#(#network_only),*],
                     }
-                    Ok(())
                 }
             }
         }
     }
 
-    fn routable_impl(&self) -> TokenStream2 {
-        let mut routable_match = Vec::new();
+    /// A module with the path of every route without dynamic segments as a constant, for `const` contexts like
+    /// default redirect targets. The module is named after the type, like `route_paths` for `Route`, so the
+    /// constants of several routable types in one module don't collide.
+    fn paths_module(&self) -> TokenStream2 {
+        let name = &self.route_name;
+        let module = format_ident!("{}_paths", route::to_snake_case(&name.to_string()));
+        let doc = format!(
+            "The paths of the routes of [`{name}`] without dynamic segments, like `{name}::Variant` as `VARIANT_PATH`"
+        );
+        let consts = self.routes.iter().filter_map(|route| {
+            let path = route.static_path()?;
+            let variant = route.route_name.to_string();
+            let doc = format!("The path of [`{name}::{variant}`]");
+            let ident = format_ident!("{}_PATH", route::to_snake_case(&variant).to_uppercase());
+            Some(quote! {
+                #[doc = #doc]
+                pub const #ident: &str = #path;
+            })
+        });
 
-        for route in &self.routes {
-            routable_match.push(route.routable_match());
+        quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            pub mod #module {
+                #(#consts)*
+            }
         }
+    }
+
+    /// The `SITE_MAP` of the type, with the pattern and segments of every variant, and `static_routes`, which builds
+    /// the variants without dynamic segments.
+    fn site_map_impl(&self) -> TokenStream2 {
+        let name = &self.route_name;
+        let listed = self.routes.iter().filter(|route| route.not_found.is_none());
+        let entries = listed.map(|route| {
+            let variant = route.route_name.to_string();
+            let pattern = &route.route;
+            let segments = route.route_segments.iter().map(|segment| {
+                let (kind, ident, ty) = match segment {
+                    RouteSegment::Static(segment) => {
+                        return quote! { SegmentInfo::Static(#segment) }
+                    }
+                    RouteSegment::Dynamic(ident, ty, _) => (quote! { Dynamic }, ident, ty),
+                    RouteSegment::CatchAll(ident, ty) => (quote! { CatchAll }, ident, ty),
+                    RouteSegment::Optional(ident, ty) => (quote! { Optional }, ident, ty),
+                    RouteSegment::Matrix(ident, ty) => (quote! { Matrix }, ident, ty),
+                    RouteSegment::Query(ident, ty) => (quote! { Query }, ident, ty),
+                    RouteSegment::Fragment(ident, ty) => (quote! { Fragment }, ident, ty),
+                };
+                let ident = ident.to_string();
+                let ty = baseline::type_name(ty);
+                quote! { SegmentInfo::#kind(#ident, #ty) }
+            });
+            quote! {
+                RouteInfo {
+                    variant: #variant,
+                    pattern: #pattern,
+                    segments: &[#(#segments),*],
+                }
+            }
+        });
+        let static_routes = self
+            .routes
+            .iter()
+            .filter(|route| route.static_path().is_some())
+            .map(|route| {
+                let construct = route.construct(name.clone());
+                route.gate(quote! { routes.push(#construct); })
+            });
 
         quote! {
-            impl Routable for Route {
-                fn render<'a>(self, cx: &'a ScopeState) -> Element<'a> {
+            impl #name {
+                /// Every variant with its pattern and the segments of it, in the order they are declared. Routes
+                /// behind a feature are listed even when the feature is off, like in [`Self::ROUTE_PATTERNS`].
+                pub const SITE_MAP: &'static [RouteInfo] = &[#(#entries),*];
+
+                /// Every route without dynamic segments, like the pages a static site generator can render ahead of
+                /// time. Routes with a dynamic or catch-all segment, a query or a fragment are left out.
+                #[allow(unused_mut, clippy::vec_init_then_push)]
+                pub fn static_routes() -> Vec<Self> {
+                    let mut routes = Vec::new();
+                    #(#static_routes)*
+                    routes
+                }
+            }
+        }
+    }
+
+    fn comparison_impl(&self) -> TokenStream2 {
+        let name = &self.route_name;
+        let diff_match = self.routes.iter().map(|route| route.params_diff_match());
+        let depth_match = self.routes.iter().map(|route| route.depth_match());
+        let section_match = self.routes.iter().map(|route| route.section_match());
+
+        quote! {
+            impl #name {
+                /// Check if two routes are the same variant, ignoring the values of their parameters.
+                pub fn same_variant(&self, other: &Self) -> bool {
+                    ::std::mem::discriminant(self) == ::std::mem::discriminant(other)
+                }
+
+                /// List the parameters whose serialized values differ between two routes of the same
+                /// variant as `(name, self_value, other_value)`. Routes of different variants have no
+                /// comparable parameters and produce an empty list.
+                pub fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+                    let mut diff = Vec::new();
+                    #[allow(unreachable_patterns)]
+                    match (self, other) {
+                        #(#diff_match)*
+                        _ => {}
+                    }
+                    diff
+                }
+
+                /// The number of segments in the path of this route. A catch-all counts every segment it holds.
+                pub fn depth(&self) -> usize {
                     match self {
-                        #(#routable_match)*
+                        #(#depth_match)*
+                    }
+                }
+
+                /// The first static segment in the pattern of this route, if it has one.
+                pub fn section(&self) -> Option<&'static str> {
+                    match self {
+                        #(#section_match)*
                     }
                 }
             }
         }
     }
-}
 
-impl ToTokens for RouteEnum {
-    fn to_tokens(&self, tokens: &mut quote::__private::TokenStream) {
-        let routes = &self.routes;
+    fn lenient_impl(&self) -> TokenStream2 {
+        if !self.options.lenient {
+            return quote! {};
+        }
 
-        tokens.extend(quote!(
-            #[path = "pages"]
-            mod pages {
-                #(#routes)*
+        let name = &self.route_name;
+        let parse_error = self.parse_error_type();
+        let box_parse_error = self.box_parse_error();
+        let mut routes = Vec::new();
+        for segment in RouteTreeSegment::build(self.patterns()) {
+            segment.routes_in_match_order(&mut routes);
+        }
+        // Filled routes are normalized like the routes `from_str` parses
+        let found = match self.routes.iter().any(|route| route.normalize.is_some()) {
+            true => quote! { (found.0.normalized(), found.1) },
+            false => quote! { found },
+        };
+        let matchers = routes.iter().map(|route| {
+            let matcher = route.lenient_match(name);
+            route.gate(quote! {
+                if let Some(found) = #matcher {
+                    return Ok(#found);
+                }
+            })
+        });
+        // Routes with a `#(fragment)` and a `?(query)` parse them apart from the segments
+        let split_fragment = self.parses_fragment().then(|| {
+            quote! {
+                let (path, __router_fragment) = match path.split_once('#') {
+                    Some((rest, fragment)) => (rest, Some((rest.len(), fragment))),
+                    None => (path, None),
+                };
             }
-            pub use pages::*;
-        ));
-    }
+        });
+        let split_query = self.routes.iter().any(Route::has_query).then(|| {
+            quote! {
+                let (path, __router_query) = match path.split_once('?') {
+                    Some((path, query)) => (path, Some(query)),
+                    None => (path, None),
+                };
+            }
+        });
+
+        quote! {
+            impl #name {
+                /// Parse a route, filling dynamic segments missing from the end of the path with their default
+                /// values. The filled segments are returned with the route.
+                ///
+                /// Paths that parse with [`std::str::FromStr`] parse the same way here. Otherwise routes are
+                /// tried in the same order, and static and catch-all segments are never filled.
+                pub fn from_str_lenient(
+                    path: &str,
+                ) -> Result<(Self, Vec<FilledSegment>), #parse_error> {
+                    let err = match Self::parse_path(path) {
+                        Ok(route) => return Ok((route, Vec::new())),
+                        Err(err) if err.limit.is_some() => return Err(err) #box_parse_error,
+                        Err(err) => err,
+                    };
+
+                    #split_fragment
+                    #split_query
+                    let mut segments: Vec<&str> = path.strip_prefix('/').unwrap_or(path).split('/').collect();
+                    // Ignore a trailing slash, like the strict parser does
+                    if segments.len() > 1 && segments.last() == Some(&"") {
+                        segments.pop();
+                    }
+
+                    #(#matchers)*
+
+                    Err(err) #box_parse_error
+                }
+            }
+        }
+    }
+
+    /// Generate the conversion from [`http::Uri`] when the `http` feature is enabled.
+    fn http_impl(&self) -> TokenStream2 {
+        if !cfg!(feature = "http") {
+            return quote! {};
+        }
+        let name = &self.route_name;
+
+        quote! {
+            impl<'a> TryFrom<&'a http::Uri> for #name {
+                type Error = <Self as ::std::str::FromStr>::Err;
+
+                /// Parse a route from the path of a request. The query is ignored, like when the router parses the
+                /// current location, and the still percent-encoded path is parsed exactly like a string path.
+                fn try_from(uri: &'a http::Uri) -> Result<Self, Self::Error> {
+                    uri.path().parse()
+                }
+            }
+        }
+    }
+
+    /// Generate `try_to_path` for `#[routable(strict_display)]`.
+    fn strict_display_impl(&self) -> TokenStream2 {
+        if !self.options.strict_display {
+            return quote! {};
+        }
+        let name = &self.route_name;
+        let check_match = self.routes.iter().map(|route| route.display_check_match());
+
+        quote! {
+            impl #name {
+                /// The path of this route, or an error if it wouldn't parse back into the same route.
+                ///
+                /// `Display` writes any value, so a route built with a value its segments reject displays as a path
+                /// that doesn't match it. This checks the `#[route_segment(...)]` constraints before writing the
+                /// path, then parses the path and compares the pattern and segments of the result with this route.
+                pub fn try_to_path(&self) -> Result<String, DisplayError> {
+                    match self {
+                        #(#check_match)*
+                    }
+
+                    let mut path = String::new();
+                    // Writing to a string only fails if a segment's `Display` impl fails
+                    Routable::write_path(self, &mut path).expect("a segment failed to display");
+                    match <Self as ::std::str::FromStr>::from_str(&path) {
+                        Ok(parsed)
+                            if parsed.pattern() == self.pattern()
+                                && parsed.matched_params() == self.matched_params() =>
+                        {
+                            Ok(path)
+                        }
+                        _ => Err(DisplayError::NoRoundTrip {
+                            path,
+                            pattern: self.pattern(),
+                        }),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generate a getter for every dynamic segment shared by all variants.
+    fn shared_impl(&self) -> TokenStream2 {
+        if self.shared_fields.is_empty() {
+            return quote! {};
+        }
+        let name = &self.route_name;
+        let getters = self.shared_fields.iter().map(|(field, ty)| {
+            let variants = self.routes.iter().map(|route| &route.route_name);
+            let doc = format!(" The `{field}` segment, which every route starts with");
+            quote! {
+                #[doc = #doc]
+                pub fn #field(&self) -> &#ty {
+                    match self {
+                        #(Self::#variants { #field, .. } => #field,)*
+                    }
+                }
+            }
+        });
+
+        quote! {
+            impl #name {
+                #(#getters)*
+            }
+        }
+    }
+
+    fn versioned_impl(&self) -> TokenStream2 {
+        let version = match &self.options.version {
+            Some(version) => version,
+            None => return quote! {},
+        };
+        let name = &self.route_name;
+        // Apply the migrations from the oldest version up
+        let mut migrations: Vec<_> = self.options.migrations.iter().collect();
+        migrations.sort_by_key(|(from, _)| from.base10_parse::<u32>().unwrap_or_default());
+        let migrations = migrations.into_iter().map(|(from, migrate)| {
+            quote! {
+                if version <= #from {
+                    path = #migrate(&path);
+                }
+            }
+        });
+
+        quote! {
+            impl #name {
+                /// The current version of the serialized route format.
+                pub const VERSION: u32 = #version;
+
+                /// Serialize the route with a version marker, like `v3:/blog/1`, so it can still be parsed after
+                /// the route patterns change. Use [`std::fmt::Display`] for URLs.
+                pub fn to_versioned_string(&self) -> String {
+                    format!("v{}:{}", Self::VERSION, self)
+                }
+
+                /// Parse a route serialized by [`Self::to_versioned_string`]. Routes from older versions are
+                /// passed through the `migrate(...)` functions first, and strings without a version marker are
+                /// parsed as plain paths.
+                pub fn from_versioned_str(s: &str) -> Result<Self, <Self as ::std::str::FromStr>::Err> {
+                    let versioned = s
+                        .strip_prefix('v')
+                        .and_then(|rest| rest.split_once(':'))
+                        .and_then(|(version, path)| Some((version.parse::<u32>().ok()?, path)));
+                    match versioned {
+                        Some((version, path)) if version <= Self::VERSION => {
+                            let mut path = path.to_string();
+                            #(#migrations)*
+                            path.parse()
+                        }
+                        _ => s.parse(),
+                    }
+                }
+            }
+        }
+    }
+
+    fn builder_impl(&self) -> TokenStream2 {
+        let name = &self.route_name;
+        let builders = self.routes.iter().map(|route| route.builder_impl(name));
+
+        quote! {
+            #(#builders)*
+        }
+    }
+
+    fn attribute_value_impl(&self) -> TokenStream2 {
+        let name = &self.route_name;
+
+        quote! {
+            /// Lets a route be used directly as an attribute value, like `a { href: &route }`.
+            ///
+            /// The route is written straight into the scope's bump allocator instead of an intermediate `String`.
+            impl<'a> IntoAttributeValue<'a> for &#name {
+                fn into_value(
+                    self,
+                    bump: &'a dioxus::core::exports::bumpalo::Bump,
+                ) -> dioxus::core::AttributeValue<'a> {
+                    format_args!("{}", self).into_value(bump)
+                }
+            }
+        }
+    }
+
+    fn conflict_test(&self) -> TokenStream2 {
+        if !self.options.conflict_test {
+            return quote! {};
+        }
+
+        let name = &self.route_name;
+        // Named after the type, so several route enums with the check can share a module
+        let module = format_ident!("{}_conflicts", route::to_snake_case(&name.to_string()));
+        let checks = self.patterns().map(|route| {
+            let example_path = route.example_path();
+            let pattern = route.variant_path(name);
+            let route_str = &route.route;
+
+            route.gate(quote! {
+                match <#name as ::std::str::FromStr>::from_str(#example_path) {
+                    Ok(route) => assert!(
+                        matches!(route, #pattern { .. }),
+                        "The route '{}' can never be reached: its example path '{}' was parsed as '{}'",
+                        #route_str,
+                        #example_path,
+                        route
+                    ),
+                    Err(err) => panic!(
+                        "The example path '{}' of the route '{}' did not parse:\n{}",
+                        #example_path,
+                        #route_str,
+                        err
+                    ),
+                }
+            })
+        });
+
+        quote! {
+            #[cfg(test)]
+            mod #module {
+                use super::*;
+
+                #[test]
+                fn every_route_is_reachable() {
+                    #(#checks)*
+                }
+            }
+        }
+    }
+
+    /// A module with a constructor for every route that fills its segments with `FixtureValue::fixture()`, and
+    /// `all` with every route. It only exists in tests and with the `fixtures` feature of the crate.
+    fn fixtures_module(&self) -> TokenStream2 {
+        if !self.options.fixtures {
+            return quote! {};
+        }
+
+        let name = &self.route_name;
+        let module = format_ident!("{}_fixtures", route::to_snake_case(&name.to_string()));
+        let doc =
+            format!("A [`{name}`] of every route, with placeholder values for their segments");
+        let mut constructors = Vec::new();
+        let mut pushes = Vec::new();
+        for route in &self.routes {
+            let variant = route.route_name.to_string();
+            let ident = format_ident!("{}", route::to_snake_case(&variant));
+            if ident == "all" {
+                return syn::Error::new_spanned(
+                    &route.route_name,
+                    "`all` is the fixture of every route, so a route with fixtures can't be named `All`",
+                )
+                .to_compile_error();
+            }
+            let path = route.variant_path(name);
+            let fields = route.route_segments.iter().filter_map(|segment| {
+                let member = route.member(&segment.name()?);
+                Some(quote! { #member: FixtureValue::fixture(), })
+            });
+            let locale = route.locale_init();
+            let cfg = route.cfg_attr();
+            let doc = format!("[`{name}::{variant}`] with placeholder values");
+            constructors.push(quote! {
+                #[doc = #doc]
+                #cfg
+                pub fn #ident() -> #name {
+                    #path {
+                        #(#fields)*
+                        #locale
+                    }
+                }
+            });
+            pushes.push(route.gate(quote! { routes.push(#ident()); }));
+        }
+
+        quote! {
+            #[doc = #doc]
+            #[cfg(any(test, feature = "fixtures"))]
+            #[allow(dead_code)]
+            pub mod #module {
+                use super::*;
+
+                #(#constructors)*
+
+                /// Every route, in the order they are declared
+                #[allow(clippy::vec_init_then_push)]
+                pub fn all() -> Vec<#name> {
+                    let mut routes = Vec::new();
+                    #(#pushes)*
+                    routes
+                }
+            }
+        }
+    }
+
+    /// Parse and write the routes as the rest of the path of a route in another type, for `#[routable(nested)]`
+    fn nested_impl(&self) -> TokenStream2 {
+        if !self.options.nested {
+            return quote! {};
+        }
+
+        let name = &self.route_name;
+        let parse_error = self.parse_error_type();
+
+        quote! {
+            /// The routes nested in a `child` catch-all of another routable type, like `/(workspace)/(...child)`
+            impl FromRouteSegments for #name {
+                type Err = #parse_error;
+
+                fn from_route_segments<'a, I>(segments: I) -> Result<Self, Self::Err>
+                where
+                    I: Iterator<Item = &'a str> + Clone,
+                {
+                    Self::from_segments(segments)
+                }
+            }
+
+            /// The path of the route without its leading slash, which the route it is nested in writes
+            impl ToRouteSegments for #name {
+                fn display_route_segments(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    let path = ::std::string::ToString::to_string(self);
+                    f.write_str(path.strip_prefix('/').unwrap_or(&path))
+                }
+            }
+        }
+    }
+
+    /// Convert between the enum and the routes nested in its `child` catch-alls: `From` the child routes for routes
+    /// without other segments, a `from_<child type>` constructor that takes the other segments for the rest, and
+    /// `as_child` back.
+    fn child_impl(&self) -> TokenStream2 {
+        let name = &self.route_name;
+        let mut impls = Vec::new();
+        for (i, route) in self.routes.iter().enumerate() {
+            let Some((child, ty)) = route.child() else {
+                continue;
+            };
+            let Some(type_name) = (match ty {
+                syn::Type::Path(path) => path.path.segments.last().map(|segment| &segment.ident),
+                _ => None,
+            }) else {
+                return syn::Error::new_spanned(
+                    ty,
+                    "A child is a routable type, like `ProjectRoute`",
+                )
+                .to_compile_error();
+            };
+            // A child route converts back into a single parent route
+            if self.routes[..i]
+                .iter()
+                .any(|other| other.child().is_some_and(|(_, other)| other == ty))
+            {
+                return syn::Error::new_spanned(
+                    ty,
+                    format!("Another route of {name} already holds the routes of {type_name} as its child"),
+                )
+                .to_compile_error();
+            }
+
+            let path = route.variant_path(name);
+            let fields: Vec<_> = route
+                .route_segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    RouteSegment::Dynamic(ident, ty, _)
+                    | RouteSegment::CatchAll(ident, ty)
+                    | RouteSegment::Optional(ident, ty)
+                    | RouteSegment::Matrix(ident, ty)
+                        if ident != child =>
+                    {
+                        Some((ident, ty))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let names = fields.iter().map(|(ident, _)| route.field(ident));
+            let child_member = route.member(child);
+            let child_field = route.field(child);
+            let locale = route.locale_init();
+            let constructor = match fields.is_empty() {
+                true => quote! {
+                    impl From<#ty> for #name {
+                        fn from(child: #ty) -> Self {
+                            #path { #child_member: child, #locale }
+                        }
+                    }
+                },
+                false => {
+                    let constructor =
+                        format_ident!("from_{}", route::to_snake_case(&type_name.to_string()));
+                    let params = fields.iter().map(|(ident, ty)| quote! { #ident: #ty });
+                    let doc = format!(
+                        "[`{}`] with these routes of [`{type_name}`] and its other segments",
+                        path.to_string().replace(' ', "")
+                    );
+                    quote! {
+                        impl #name {
+                            #[doc = #doc]
+                            pub fn #constructor(child: #ty, #(#params),*) -> Self {
+                                #path { #child_member: child, #(#names,)* #locale }
+                            }
+                        }
+                    }
+                }
+            };
+            impls.push(quote! {
+                #constructor
+
+                impl HasChild<#ty> for #name {
+                    fn child(&self) -> Option<&#ty> {
+                        #[allow(unreachable_patterns)]
+                        match self {
+                            #path { #child_field, .. } => Some(#child),
+                            _ => None,
+                        }
+                    }
+                }
+            });
+        }
+        if impls.is_empty() {
+            return quote! {};
+        }
+
+        quote! {
+            #(#impls)*
+
+            impl #name {
+                /// The routes nested in this route, if it holds routes of `C`
+                pub fn as_child<C>(&self) -> Option<&C>
+                where
+                    Self: HasChild<C>,
+                {
+                    HasChild::child(self)
+                }
+            }
+        }
+    }
+
+    /// The `macro_rules!` `link!(Type, "/path")` calls for the patterns and field types of every route, in the order
+    /// they are declared
+    fn link_macro(&self) -> TokenStream2 {
+        if !self.options.link_macro {
+            return quote! {};
+        }
+
+        let name = &self.route_name;
+        let macro_name = link::table_macro_name(name);
+        let entries = self.patterns().map(|route| {
+            let variant = route.variant_path(name);
+            let pattern = &route.route;
+            let fields = route
+                .route_segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    RouteSegment::Dynamic(ident, ty, _)
+                    | RouteSegment::CatchAll(ident, ty)
+                    | RouteSegment::Optional(ident, ty)
+                    | RouteSegment::Matrix(ident, ty)
+                    | RouteSegment::Query(ident, ty)
+                    | RouteSegment::Fragment(ident, ty) => {
+                        let member = route.member(ident);
+                        Some(quote! { #member: #ty, })
+                    }
+                    RouteSegment::Static(_) => None,
+                });
+            let locale = route
+                .locale
+                .as_ref()
+                .map(|locale| quote! { locale = #locale });
+            let feature = route
+                .feature
+                .as_ref()
+                .map(|feature| quote! { feature = #feature });
+            let normalize = route.normalize.as_ref().map(|normalize| {
+                let normalize =
+                    LitStr::new(&normalize.to_token_stream().to_string(), normalize.span());
+                quote! { normalize = #normalize }
+            });
+            quote! { #variant #pattern { #(#fields)* } #locale #feature #normalize; }
+        });
+        // Every path is parsed while the program runs if the shared segments have a guard
+        let runtime = self
+            .options
+            .shared_guard
+            .as_ref()
+            .map(|_| quote! { runtime });
+        let link = match &self.options.runtime {
+            Some(runtime) => quote! { #runtime::link },
+            None => quote! { link },
+        };
+
+        quote! {
+            #[doc(hidden)]
+            #[allow(unused_macros)]
+            macro_rules! #macro_name {
+                ($path:literal) => {
+                    #link!(@routes #name #runtime [ #(#entries)* ] $path)
+                };
+            }
+        }
+    }
+
+    /// Check that the type of every dynamic segment can be parsed and written, so a missing impl is reported once
+    /// on the field's type with a hint to derive `RouteSegment`, next to the errors from the parser.
+    fn segment_type_checks(&self) -> TokenStream2 {
+        let mut types: Vec<&syn::Type> = Vec::new();
+        for route in &self.routes {
+            for segment in &route.route_segments {
+                let ty = match segment {
+                    RouteSegment::Dynamic(_, ty, _) => ty,
+                    RouteSegment::Optional(_, ty) => route::option_inner(ty).unwrap_or(ty),
+                    _ => continue,
+                };
+                if !route::is_shared_str(ty) && !types.contains(&ty) {
+                    types.push(ty);
+                }
+            }
+        }
+        let checks = types.iter().map(|ty| {
+            quote_spanned! {ty.span()=> __router_segment_value::<#ty>(); }
+        });
+
+        quote! {
+            const _: () = {
+                fn __router_segment_value<T: SegmentValue>() {}
+                fn __router_check_segments() {
+                    #(#checks)*
+                }
+            };
+        }
+    }
+
+    /// The routes in the order they are declared, as they are written to the route table. Localized patterns follow
+    /// the route's own pattern with their locale as `hreflang`, and aliases follow them without one, since a sitemap
+    /// only links the patterns routes display with.
+    fn route_table(&self) -> Vec<baseline::TableRoute> {
+        self.patterns()
+            .map(|route| baseline::TableRoute {
+                variant: route.route_name.to_string(),
+                pattern: route.route.value(),
+                hreflang: route
+                    .locale
+                    .as_ref()
+                    .filter(|_| !route.is_alias)
+                    .map(LitStr::value),
+                deprecated: route.deprecated.as_ref().map(LitStr::value),
+                owner: route.owner.as_ref().map(LitStr::value),
+                segments: route
+                    .route_segments
+                    .iter()
+                    .filter_map(|segment| match segment {
+                        RouteSegment::Dynamic(ident, ty, _)
+                        | RouteSegment::CatchAll(ident, ty)
+                        | RouteSegment::Optional(ident, ty)
+                        | RouteSegment::Matrix(ident, ty)
+                        | RouteSegment::Query(ident, ty)
+                        | RouteSegment::Fragment(ident, ty) => {
+                            Some((ident.to_string(), baseline::type_name(ty)))
+                        }
+                        RouteSegment::Static(_) => None,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Compare the routes to the `#[routable(baseline = "...")]` file. Changes that break old URLs are errors, and
+    /// the others are warnings from a deprecated constant, since proc macros can't emit warnings directly.
+    fn baseline_check(&self) -> TokenStream2 {
+        let Some(path) = &self.options.baseline else {
+            return quote! {};
+        };
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let full_path = std::path::Path::new(&manifest_dir).join(path.value());
+        let source = match std::fs::read_to_string(&full_path) {
+            Ok(source) => source,
+            Err(err) => {
+                return syn::Error::new_spanned(
+                    path,
+                    format!(
+                        "Could not read the baseline {}: {}. Save {}::ROUTE_TABLE_JSON to it to start checking changes",
+                        full_path.display(),
+                        err,
+                        self.route_name
+                    ),
+                )
+                .to_compile_error()
+            }
+        };
+        let old = match baseline::parse_json(&source) {
+            Ok(old) => old,
+            Err(err) => {
+                return syn::Error::new_spanned(
+                    path,
+                    format!("The baseline is not a route table: {err}"),
+                )
+                .to_compile_error()
+            }
+        };
+        let redirects: Vec<_> = self
+            .options
+            .redirects
+            .iter()
+            .map(|(from, to)| (from.value(), to.value()))
+            .collect();
+
+        let checks = baseline::compare(&old, &self.route_table(), &redirects)
+            .into_iter()
+            .map(|change| {
+                let message = change.message();
+                match change.is_error() {
+                    true => syn::Error::new_spanned(path, message).to_compile_error(),
+                    // The use is spanned to the attribute, since lints are not reported in code spanned to the
+                    // derive
+                    false => {
+                        let warning = format_ident!("ROUTE_BASELINE_CHANGED", span = path.span());
+                        quote! {
+                            const _: () = {
+                                #[deprecated(note = #message)]
+                                const ROUTE_BASELINE_CHANGED: () = ();
+                                #warning
+                            };
+                        }
+                    }
+                }
+            });
+        let full_path = full_path.display().to_string();
+
+        quote! {
+            // Rebuild when the baseline changes
+            const _: &str = include_str!(#full_path);
+
+            #(#checks)*
+        }
+    }
+
+    fn error_name(&self) -> Ident {
+        Ident::new(
+            &(self.route_name.to_string() + "MatchError"),
+            Span::call_site(),
+        )
+    }
+
+    /// The visibility of the generated error types. With `boxed_errors` they only appear behind the boxed error,
+    /// so they stay private to the crate.
+    fn error_visibility(&self) -> TokenStream2 {
+        match self.options.boxed_errors {
+            true => quote! { pub(crate) },
+            false => quote! { pub },
+        }
+    }
+
+    /// The error type of the public parsing functions
+    fn parse_error_type(&self) -> TokenStream2 {
+        let error_name = self.error_name();
+        match self.options.boxed_errors {
+            true => quote! { Box<dyn ::std::error::Error + Send + Sync> },
+            false => quote! { RouteParseError<#error_name> },
+        }
+    }
+
+    /// Convert a `RouteParseError` result into the result of the public parsing functions
+    fn box_parse_error(&self) -> TokenStream2 {
+        match self.options.boxed_errors {
+            true => quote! {
+                .map_err(|err| -> Box<dyn ::std::error::Error + Send + Sync> { Box::new(err) })
+            },
+            false => quote! {},
+        }
+    }
+
+    fn error_type(&self) -> TokenStream2 {
+        let match_error_name = self.error_name();
+        let vis = self.error_visibility();
+
+        let mut type_defs = Vec::new();
+        let mut error_variants = Vec::new();
+        let mut display_match = Vec::new();
+        let mut span_match = Vec::new();
+        let mut source_match = Vec::new();
+        let mut decode_match = Vec::new();
+
+        // The parser never tries the not_found route, so it never fails to match
+        for route in self.routes.iter().filter(|route| route.not_found.is_none()) {
+            let route_name = &route.route_name;
+
+            let error_name = Ident::new(&format!("{}ParseError", route_name), Span::call_site());
+            let route_str = &route.route;
+
+            error_variants.push(quote! {
+                #route_name {
+                    error: #error_name,
+                    span: ::std::ops::Range<usize>,
+                }
+            });
+            display_match.push(quote! { Self::#route_name { error, .. } => write!(f, "Route '{}' ('{}') did not match:\n{}", stringify!(#route_name), #route_str, error)? });
+            span_match.push(quote! { Self::#route_name { span, .. } => span.clone() });
+            source_match.push(quote! { Self::#route_name { error, .. } => Some(error) });
+            decode_match
+                .push(quote! { Self::#route_name { error, .. } => error.is_decode_error() });
+            type_defs.push(route.error_type(&vis));
+        }
+
+        quote! {
+            #(#type_defs)*
+
+            #[derive(Debug, PartialEq)]
+            #vis enum #match_error_name {
+                #(#error_variants),*
+            }
+
+            impl ::std::error::Error for #match_error_name {
+                fn source(&self) -> Option<&(dyn ::std::error::Error + 'static)> {
+                    match self {
+                        #(#source_match),*
+                    }
+                }
+            }
+
+            impl ::std::fmt::Display for #match_error_name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        #(#display_match),*
+                    }
+                    Ok(())
+                }
+            }
+
+            impl #match_error_name {
+                /// The byte range of the parsed input that failed to match. The range refers to the original
+                /// input, before any segment was percent-decoded.
+                pub fn span(&self) -> ::std::ops::Range<usize> {
+                    match self {
+                        #(#span_match),*
+                    }
+                }
+
+                /// Render the input with a line of carets under the part that failed to match.
+                pub fn highlight(&self, input: &str) -> String {
+                    let span = self.span();
+                    let start = input.get(..span.start).map_or(0, |before| before.chars().count());
+                    let len = input.get(span).map_or(0, |failed| failed.chars().count());
+                    format!("{}\n{}{}", input, " ".repeat(start), "^".repeat(len.max(1)))
+                }
+            }
+
+            impl AttemptedRoute for #match_error_name {
+                fn span(&self) -> ::std::ops::Range<usize> {
+                    Self::span(self)
+                }
+
+                fn is_decode_error(&self) -> bool {
+                    match self {
+                        #(#decode_match),*
+                    }
+                }
+            }
+        }
+    }
+
+    fn routable_impl(&self) -> TokenStream2 {
+        let mut routable_match = Vec::new();
+
+        for route in &self.routes {
+            routable_match.push(route.routable_match());
+        }
+
+        let display_match = self.routes.iter().map(|route| route.display_match());
+        let provide_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.provide_match())
+            .collect();
+        // Contexts are provided in the scope that renders the route, so the route's whole component tree sees them
+        let provide_contexts = (!provide_match.is_empty()).then(|| {
+            quote! {
+                #[allow(unreachable_patterns)]
+                match &self {
+                    #(#provide_match)*
+                    _ => {}
+                }
+            }
+        });
+        let matched_params_match = self.routes.iter().map(|route| route.matched_params_match());
+        let differing_fields_match = self
+            .routes
+            .iter()
+            .map(|route| route.differing_fields_match());
+        let pattern_match = self.routes.iter().map(|route| route.pattern_match());
+        let action_match = self.routes.iter().filter_map(|route| route.action_match());
+        let status_match = self.routes.iter().filter_map(|route| route.status_match());
+        let cache_match = self.routes.iter().filter_map(|route| route.cache_match());
+        let set_fragment = self.parses_fragment().then(|| {
+            let set_fragment_match = self
+                .routes
+                .iter()
+                .filter_map(|route| route.set_fragment_match());
+            let fragment_id_match = self
+                .routes
+                .iter()
+                .filter_map(|route| route.fragment_id_match());
+            quote! {
+                fn parses_fragment() -> bool {
+                    true
+                }
+
+                // The fields of the routes are in scope, so the parameter uses the reserved prefix
+                fn set_fragment(&mut self, __router_fragment: Option<&str>) {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#set_fragment_match)*
+                        _ => {}
+                    }
+                }
+
+                fn fragment_id(&self) -> Option<String> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#fragment_id_match)*
+                        _ => None,
+                    }
+                }
+            }
+        });
+        let head_match = self.routes.iter().filter_map(|route| route.head_match());
+        let parses_query = self.parses_query().then(|| {
+            let includes_query = self
+                .routes
+                .iter()
+                .filter(|route| route.includes_query() || route.has_query())
+                .map(|route| route.variant_path(quote! { Self }));
+            quote! {
+                fn parses_query() -> bool {
+                    true
+                }
+
+                fn includes_query(&self) -> bool {
+                    matches!(self, #(#includes_query { .. })|*)
+                }
+            }
+        });
+
+        let gated_routes: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| {
+                let feature = route.feature.as_ref()?;
+                let pattern = &route.route;
+                let variant = route.route_name.to_string();
+                Some(quote! {
+                    GatedRoute {
+                        pattern: #pattern,
+                        name: #variant,
+                        feature: #feature,
+                        enabled: cfg!(feature = #feature),
+                    }
+                })
+            })
+            .collect();
+        let gated_routes = (!gated_routes.is_empty()).then(|| {
+            quote! {
+                fn gated_routes() -> &'static [GatedRoute] {
+                    &[#(#gated_routes),*]
+                }
+            }
+        });
+
+        let deprecated_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.deprecated_match())
+            .collect();
+        let deprecated = (!deprecated_match.is_empty()).then(|| {
+            // Localized patterns are deprecated with their route
+            let deprecated_routes = self.patterns().filter_map(|route| {
+                let note = route.deprecated.as_ref()?;
+                let pattern = &route.route;
+                Some(quote! { (#pattern, #note) })
+            });
+            quote! {
+                fn is_deprecated(&self) -> Option<&'static str> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#deprecated_match)*
+                        _ => None,
+                    }
+                }
+
+                fn deprecated_routes() -> &'static [(&'static str, &'static str)] {
+                    &[#(#deprecated_routes),*]
+                }
+            }
+        });
+
+        let owner_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.owner_match())
+            .collect();
+        let owner = (!owner_match.is_empty()).then(|| {
+            quote! {
+                fn owner(&self) -> Option<&'static str> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#owner_match)*
+                        _ => None,
+                    }
+                }
+            }
+        });
+
+        let shortcuts: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| {
+                let keys = route.shortcut.as_ref()?;
+                let variant = route.construct(format_ident!("Self"));
+                Some(route.gate(quote! { shortcuts.push((#keys, #variant)); }))
+            })
+            .collect();
+        let shortcuts = (!shortcuts.is_empty()).then(|| {
+            quote! {
+                fn shortcuts() -> Vec<(&'static str, Self)> {
+                    let mut shortcuts = Vec::new();
+                    #(#shortcuts)*
+                    shortcuts
+                }
+            }
+        });
+        let palette_routes: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.palette_routes())
+            .collect();
+        let palette_routes = (!palette_routes.is_empty()).then(|| {
+            quote! {
+                fn palette_routes() -> Vec<Self> {
+                    let mut routes = Vec::new();
+                    #(#palette_routes)*
+                    routes
+                }
+            }
+        });
+        let icon_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.icon_match())
+            .collect();
+        let palette_icon = (!icon_match.is_empty()).then(|| {
+            quote! {
+                fn palette_icon(&self) -> Option<&'static str> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#icon_match)*
+                        _ => None,
+                    }
+                }
+            }
+        });
+        let nav_hidden_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.nav_hidden_match())
+            .collect();
+        let nav_hidden = (!nav_hidden_match.is_empty()).then(|| {
+            quote! {
+                fn nav_hidden(&self) -> bool {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#nav_hidden_match)*
+                        _ => false,
+                    }
+                }
+            }
+        });
+        let nav_order_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.nav_order_match())
+            .collect();
+        let nav_order = (!nav_order_match.is_empty()).then(|| {
+            quote! {
+                fn nav_order(&self) -> i32 {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#nav_order_match)*
+                        _ => 0,
+                    }
+                }
+            }
+        });
+        let dry_run_safe_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.dry_run_safe_match())
+            .collect();
+        let action_is_dry_run_safe = (!dry_run_safe_match.is_empty()).then(|| {
+            quote! {
+                fn action_is_dry_run_safe(&self) -> bool {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#dry_run_safe_match)*
+                        _ => false,
+                    }
+                }
+            }
+        });
+        let guard_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.guard_match())
+            .collect();
+        let guard = (!guard_match.is_empty()).then(|| {
+            quote! {
+                fn guard(&self) -> Option<Guard<Self>> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#guard_match)*
+                        _ => None,
+                    }
+                }
+            }
+        });
+        let soft_guard_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.soft_guard_match())
+            .collect();
+        let guard_on_soft_navigation = (!soft_guard_match.is_empty()).then(|| {
+            quote! {
+                fn guard_on_soft_navigation(&self) -> bool {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#soft_guard_match)*
+                        _ => true,
+                    }
+                }
+            }
+        });
+        let hard_navigation_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.hard_navigation_match())
+            .collect();
+        // Hard navigation routes key their component with the path
+        let route_key = (!hard_navigation_match.is_empty()).then(|| {
+            quote! { let route_key = self.to_string(); }
+        });
+        let hard_navigation = (!hard_navigation_match.is_empty()).then(|| {
+            quote! {
+                fn hard_navigation(&self) -> bool {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#hard_navigation_match)*
+                        _ => false,
+                    }
+                }
+            }
+        });
+        let is_not_found = self
+            .routes
+            .iter()
+            .find(|route| route.not_found.is_some())
+            .map(|route| {
+                let pattern = route.variant_path(quote! { Self });
+                quote! {
+                    fn is_not_found(&self) -> bool {
+                        matches!(self, #pattern { .. })
+                    }
+                }
+            });
+        let lang_match: Vec<_> = self
+            .routes
+            .iter()
+            .filter_map(|route| route.lang_match())
+            .collect();
+        let lang = (!lang_match.is_empty()).then(|| {
+            let directions: Vec<_> = self
+                .options
+                .locales
+                .iter()
+                .filter_map(|(locale, dir)| {
+                    let locale = locale.to_string();
+                    let dir = dir.as_ref()?;
+                    Some(quote! { #locale => Some(#dir), })
+                })
+                .collect();
+            let dir = match directions.is_empty() {
+                true => quote! { |_: &str| None },
+                false => quote! {
+                    |locale: &str| match locale {
+                        #(#directions)*
+                        _ => None,
+                    }
+                },
+            };
+            quote! {
+                fn lang(&self) -> Option<DocumentLang> {
+                    let dir = #dir;
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#lang_match)*
+                        _ => None,
+                    }
+                }
+            }
+        });
+        let head = quote! {
+            #[allow(unreachable_patterns)]
+            match self {
+                #(#head_match)*
+                _ => HeadMeta::default(),
+            }
+        };
+        // The `<html>` attributes of localized routes come with their head
+        let head = match lang.is_some() {
+            true => quote! { HeadMeta { lang: self.lang(), ..{ #head } } },
+            false => head,
+        };
+        let max_path_len = Literal::usize_unsuffixed(self.options.max_path_len());
+        let max_segments = Literal::usize_unsuffixed(self.options.max_segments());
+
+        let name = &self.route_name;
+        let error_name = self.error_name();
+
+        quote! {
+            impl Routable for #name {
+                fn render<'a>(self, cx: &'a ScopeState) -> Element<'a> {
+                    #provide_contexts
+                    #route_key
+
+                    match self {
+                        #(#routable_match)*
+                    }
+                }
+
+                fn write_path(&self, f: &mut impl ::std::fmt::Write) -> ::std::fmt::Result {
+                    match self {
+                        #(#display_match)*
+                    }
+                    Ok(())
+                }
+
+                fn matched_params(&self) -> Vec<(&'static str, String)> {
+                    match self {
+                        #(#matched_params_match)*
+                    }
+                }
+
+                // The inherent `params_diff` of the enum, which takes precedence over this method in the path
+                fn params_diff(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+                    Self::params_diff(self, other)
+                }
+
+                fn differing_fields(&self, other: &Self) -> Option<Vec<&'static str>> {
+                    #[allow(unreachable_patterns)]
+                    match (self, other) {
+                        #(#differing_fields_match)*
+                        _ => None,
+                    }
+                }
+
+                // The inherent `static_routes` from the site map
+                fn static_routes() -> Vec<Self> {
+                    Self::static_routes()
+                }
+
+                fn pattern(&self) -> &'static str {
+                    match self {
+                        #(#pattern_match)*
+                    }
+                }
+
+                fn route_patterns() -> &'static [&'static str] {
+                    Self::ROUTE_PATTERNS
+                }
+
+                fn from_pattern_and_params(
+                    pattern: &str,
+                    params: &[(&str, &str)],
+                ) -> Result<Self, BuildError> {
+                    Self::from_pattern_and_params(pattern, params)
+                }
+
+                fn action(&self) -> Option<fn(&mut Router<Self>)> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#action_match)*
+                        _ => None,
+                    }
+                }
+
+                fn status_hint(&self) -> u16 {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#status_match)*
+                        _ => 200,
+                    }
+                }
+
+                fn cache_hint(&self) -> Option<&'static str> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(#cache_match)*
+                        _ => None,
+                    }
+                }
+
+                #parses_query
+
+                #set_fragment
+
+                #gated_routes
+
+                #deprecated
+
+                #owner
+
+                #shortcuts
+
+                #palette_routes
+
+                #palette_icon
+
+                #nav_hidden
+
+                #nav_order
+
+                #action_is_dry_run_safe
+
+                #guard
+
+                #guard_on_soft_navigation
+
+                #hard_navigation
+
+                #is_not_found
+
+                fn parse_limits() -> ParseLimits {
+                    ParseLimits {
+                        max_path_len: #max_path_len,
+                        max_segments: #max_segments,
+                    }
+                }
+
+                fn rejected(limit: ParseLimit) -> <Self as ::std::str::FromStr>::Err {
+                    RouteParseError::<#error_name>::limit(limit).into()
+                }
+
+                fn head(&self) -> HeadMeta {
+                    #head
+                }
+
+                #lang
+            }
+        }
+    }
+}
+
+impl ToTokens for RouteEnum {
+    fn to_tokens(&self, tokens: &mut quote::__private::TokenStream) {
+        let routes = &self.routes;
+
+        if !routes.iter().any(|route| route.file_based) {
+            return;
+        }
+
+        tokens.extend(quote!(
+            #[path = "pages"]
+            mod pages {
+                #(#routes)*
+            }
+            pub use pages::*;
+        ));
+    }
+}
+
+#[test]
+fn shared_segment_errors() {
+    for (input, error) in [
+        (
+            r#"#[routable(shared = "/(workspace)")] enum Route {
+                #[route("/" Home)] Home { workspace: u32 },
+                #[route("/about" About)] About { workspace: String },
+            }"#,
+            "is a String, but it is a u32 in the other variants",
+        ),
+        (
+            r#"#[routable(shared = "/(workspace)")] enum Route {
+                #[route("/" Home)] Home {},
+            }"#,
+            "Could not find a field with the name 'workspace' in the variant 'Home'",
+        ),
+        (
+            r#"#[routable(shared = "/(...rest)")] enum Route {
+                #[route("/" Home)] Home { rest: Vec<String> },
+            }"#,
+            "Shared segments can't contain a catch-all segment",
+        ),
+        (
+            r#"#[routable(shared = "(workspace)/")] enum Route {
+                #[route("/" Home)] Home { workspace: u32 },
+            }"#,
+            "Shared segments should start with /",
+        ),
+        (
+            r#"#[routable(shared = "/(workspace)")] #[route("/" Home)] struct Home { workspace: u32 }"#,
+            "Shared segments are only supported on enums",
+        ),
+        (
+            r#"#[routable(shared_guard = check_version)] enum Route {
+                #[route("/" Home)] Home {},
+            }"#,
+            "A shared_guard checks the shared segments",
+        ),
+        (
+            r#"enum Route {
+                #[route("/docs/v(...path)" Docs)] Docs { path: Vec<String> },
+            }"#,
+            "Catch-all segments can't have a prefix",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn shortcut_errors() {
+    for (input, error) in [
+        (
+            r#"enum Route {
+                #[route("/inbox" Inbox, shortcut = "g i")] Inbox {},
+                #[route("/issues" Issues, shortcut = "g  i")] Issues {},
+            }"#,
+            "The shortcut 'g i' of Issues conflicts with 'g i' of Inbox",
+        ),
+        (
+            r#"enum Route {
+                #[route("/inbox" Inbox, shortcut = "g i")] Inbox {},
+                #[route("/" Home, shortcut = "g")] Home {},
+            }"#,
+            "The shortcut 'g' of Home conflicts with 'g i' of Inbox",
+        ),
+        (
+            r#"enum Route {
+                #[route("/users/(id)" User, shortcut = "g u")] User { id: u32 },
+            }"#,
+            "so the route '/users/(id)' can't have one because of its dynamic segments",
+        ),
+        (
+            r#"enum Route {
+                #[route("/" Home, shortcut = " ")] Home {},
+            }"#,
+            "A shortcut needs at least one key",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn parse_limit_errors() {
+    for input in [
+        r#"#[routable(max_path_len = 0)] enum Route { #[route("/" Home)] Home {} }"#,
+        r#"#[routable(max_segments = 0)] enum Route { #[route("/" Home)] Home {} }"#,
+    ] {
+        let expanded = expand_str(input);
+        assert!(
+            expanded.contains("A parse limit of 0 would reject every path"),
+            "{expanded}"
+        );
+    }
+}
+
+#[test]
+fn localized_route_errors() {
+    for (input, error) in [
+        (
+            r#"enum Route {
+                #[route("/blog/(id)" Post, locale(de = "/de/beitraege/(post)"))] Post { id: u32, post: u32, locale: String },
+            }"#,
+            "The localized pattern '/de/beitraege/(post)' has the dynamic segments [(post)], but '/blog/(id)' has [(id)]",
+        ),
+        (
+            r#"enum Route {
+                #[route("/blog/(id)/(slug)" Post, locale(de = "/beitraege/(slug)/(id)"))] Post { id: u32, slug: String, locale: String },
+            }"#,
+            "has the dynamic segments [(slug), (id)], but '/blog/(id)/(slug)' has [(id), (slug)]",
+        ),
+        (
+            r#"enum Route {
+                #[route("/about" About, locale(de = "/ueber-uns"))] About {},
+            }"#,
+            "Routes with localized patterns need a `locale` field",
+        ),
+        (
+            r#"enum Route {
+                #[route("/about" About, locale(de = "/ueber-uns", de = "/info"))] About { locale: String },
+            }"#,
+            "There is already a pattern for the locale 'de'",
+        ),
+        (
+            r#"enum Route {
+                #[route("/about" About, locale(en = "/about-us"))] About { locale: String },
+            }"#,
+            "'en' is the default locale",
+        ),
+        (
+            r#"enum Route {
+                #[route("/(locale)/about" About, locale(de = "/(locale)/ueber-uns"))] About { locale: String },
+            }"#,
+            "so it can't be a segment",
+        ),
+        (
+            r#"#[routable(locales(en, de(dir = "rtl")))] enum Route {
+                #[route("/about" About, locale(de = "/ueber-uns", ar = "/hawl"))] About { locale: String },
+            }"#,
+            "The locale 'ar' is missing from #[routable(locales(...))]",
+        ),
+        (
+            r#"#[routable(locales(ar(dir = "right")))] enum Route {}"#,
+            r#"Unknown direction. Expected \"ltr\", \"rtl\" or \"auto\""#,
+        ),
+        (
+            r#"#[routable(locales(ar(direction = "rtl")))] enum Route {}"#,
+            "Unknown locale option",
+        ),
+        (
+            r#"#[routable(locales(ar, ar(dir = "rtl")))] enum Route {}"#,
+            "This locale is already listed",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn route_alias_errors() {
+    for (input, error) in [
+        (
+            r#"enum Route {
+                #[route("/blog/(id)" Post)] #[route("/posts/(post)")] Post { id: u32, post: u32 },
+            }"#,
+            "The alias '/posts/(post)' has the dynamic segments [(post)], but '/blog/(id)' has [(id)]",
+        ),
+        (
+            r#"enum Route {
+                #[route("/files/(...path)" Files)] #[route("/f/(path)")] Files { path: Vec<String> },
+            }"#,
+            "The alias '/f/(path)' has the dynamic segments [(path)], but '/files/(...path)' has [(...path)]",
+        ),
+        (
+            r#"enum Route {
+                #[route("/blog/(id)" Post)] #[route("/posts/(id)" OldPost)] Post { id: u32 },
+            }"#,
+            "Only the first #[route(...)] of a variant has a component and options",
+        ),
+        (
+            r#"enum Route {
+                #[route("/blog/(id)" Post)] #[route("/posts/(id)")] #[route("/posts/(id)")] Post { id: u32 },
+            }"#,
+            "The route already has the pattern '/posts/(id)'",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn unreachable_route_errors() {
+    for (input, error) in [
+        (
+            r#"enum Route {
+                #[route("/users/(id)" User)] User { id: u32 },
+                #[route("/users/(user)" OtherUser)] OtherUser { user: u32 },
+            }"#,
+            "The route '/users/(user)' can never match: '/users/(id)' of User is tried before it and matches every path it does",
+        ),
+        (
+            r#"enum Route {
+                #[route("/items/(slug)" Slug)] Slug { slug: String },
+                #[route("/items/(id)" Item)] Item { id: u64 },
+            }"#,
+            "The route '/items/(id)' can never match: '/items/(slug)' of Slug",
+        ),
+        (
+            r#"enum Route {
+                #[route("/files/(...path)" Files)] Files { path: Vec<String> },
+                #[route("/files/(...rest)" Rest)] Rest { rest: Vec<String> },
+            }"#,
+            "The route '/files/(...rest)' can never match",
+        ),
+        (
+            r#"enum Route {
+                #[route("/about" About, locale(de = "/about"))] About { locale: String },
+            }"#,
+            "'/about' is declared here",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+
+    // Numbers go to the first route and everything else to the second, and only one of two routes behind
+    // features is compiled in
+    for input in [
+        r#"enum Route {
+            #[route("/items/(id)" Item)] Item { id: u64 },
+            #[route("/items/(slug)" Slug)] Slug { slug: String },
+        }"#,
+        r#"enum Route {
+            #[route("/pricing" Pricing, feature = "pro")] Pricing {},
+            #[route("/pricing" FreePricing, feature = "free")] FreePricing {},
+        }"#,
+        r#"enum Route {
+            #[route("/files/(...path)" Files)] Files { #[route_segment(non_empty)] path: Vec<String> },
+            #[route("/files/(...rest)" Rest)] Rest { rest: Vec<String> },
+        }"#,
+    ] {
+        let expanded = expand_str(input);
+        assert!(!expanded.contains("can never match"), "{expanded}");
+    }
+}
+
+#[test]
+fn palette_errors() {
+    for (input, error) in [
+        (
+            r#"enum Route {
+                #[route("/inbox" Inbox, palette_provider = inboxes)] Inbox {},
+            }"#,
+            "The route '/inbox' has no dynamic segments, so it is listed in the palette without a palette_provider",
+        ),
+        (
+            r#"enum Route {
+                #[route("/projects/(id)" Project, palette = false, palette_provider = recent_projects)] Project { id: u32 },
+            }"#,
+            "Routes with `palette = false` aren't listed in the palette",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn nav_errors() {
+    for input in [
+        r#"enum Route {
+            #[route("/projects/(id)" Project, order = 1)] Project { id: u32 },
+        }"#,
+        r#"enum Route {
+            #[route("/logout" Logout, nav = false, order = 1)] Logout {},
+        }"#,
+    ] {
+        let expanded = expand_str(input);
+        assert!(
+            expanded.contains("isn't listed in navigation menus, so it can't have an order"),
+            "{expanded}"
+        );
+    }
+
+    let expanded = expand_str(
+        r#"enum Route {
+            #[route("/about" About, order = 99999999999)] About {},
+        }"#,
+    );
+    assert!(expanded.contains("number too large"), "{expanded}");
+}
+
+#[test]
+fn normalize_errors() {
+    let input = r#"enum Route {
+        #[route("/about" About, normalize = normalize_about)] About {},
+    }"#;
+    let expanded = expand_str(input);
+    assert!(
+        expanded.contains(
+            "The route '/about' has no dynamic segments, so there is nothing to normalize"
+        ),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn dry_run_safe_errors() {
+    let input = r#"enum Route {
+        #[route("/" Home, dry_run_safe = true)] Home {},
+    }"#;
+    let expanded = expand_str(input);
+    assert!(
+        expanded.contains("Only routes with an action can be dry_run_safe"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn deprecated_errors() {
+    let input = r#"enum Route {
+        #[route("/old-dashboard" OldDashboard, deprecated = 2)] OldDashboard {},
+    }"#;
+    let expanded = expand_str(input);
+    assert!(
+        expanded.contains("The deprecation note must be a string literal"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn owner_errors() {
+    let input = r#"enum Route {
+        #[route("/checkout" Checkout, owner = 1)] Checkout {},
+    }"#;
+    let expanded = expand_str(input);
+    assert!(
+        expanded.contains("The owner must be a string literal"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn route_groups() {
+    // The variants of a group expand as if they had its arguments, unless they set them themselves
+    let grouped = expand_str(
+        r#"enum Route {
+            #[route("/" Home)] Home {},
+            #[group(guard = require_admin, meta(section = "admin", robots = "noindex"), owner = "admin-team")]
+            #[route("/admin" Admin, title = "Admin")] Admin {},
+            #[route("/admin/users/(id)" AdminUser, guard = require_owner, meta(robots = "none"))]
+            AdminUser { id: u32 },
+            #[end_group]
+            #[route("/admin/audit" Audit, owner = "security-team")] Audit {},
+            #[route("/about" About)] About {},
+        }"#,
+    );
+    let written_out = expand_str(
+        r#"enum Route {
+            #[route("/" Home)] Home {},
+            #[route("/admin" Admin, title = "Admin", guard = require_admin, meta(section = "admin", robots = "noindex"), owner = "admin-team")]
+            Admin {},
+            #[route("/admin/users/(id)" AdminUser, guard = require_owner, meta(section = "admin", robots = "none"), owner = "admin-team")]
+            AdminUser { id: u32 },
+            #[route("/admin/audit" Audit, guard = require_admin, meta(section = "admin", robots = "noindex"), owner = "security-team")]
+            Audit {},
+            #[route("/about" About)] About {},
+        }"#,
+    );
+    assert!(!grouped.contains("compile_error"), "{grouped}");
+    assert_eq!(grouped, written_out);
+}
+
+#[test]
+fn route_group_errors() {
+    for (input, error) in [
+        (
+            r#"enum Route {
+                #[group(owner = "admin-team")]
+                #[route("/admin" Admin)] Admin {},
+            }"#,
+            "This group isn't ended. Add #[end_group] to its last variant",
+        ),
+        (
+            r#"enum Route {
+                #[end_group]
+                #[route("/admin" Admin)] Admin {},
+            }"#,
+            "#[end_group] ends the #[group(...)] of this or an earlier variant, but there is none",
+        ),
+        (
+            r#"enum Route {
+                #[group(owner = "admin-team")]
+                #[route("/admin" Admin)] Admin {},
+                #[group(owner = "billing-team")]
+                #[end_group]
+                #[route("/billing" Billing)] Billing {},
+            }"#,
+            "Groups can't be nested",
+        ),
+        (
+            r#"enum Route {
+                #[group(status = 403)]
+                #[end_group]
+                #[route("/admin" Admin)] Admin {},
+            }"#,
+            "Unknown group argument 'status'. Groups can set a guard, title, meta and owner",
+        ),
+        (
+            r#"enum Route {
+                #[group(title = "User {id}")]
+                #[route("/users/(id)" User)] User { id: u32 },
+                #[end_group]
+                #[route("/admin" Admin)] Admin {},
+            }"#,
+            // The title of the group is checked against the segments of each variant in it
+            "The route has no segment named 'id'",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains("compile_error"), "{expanded}");
+        assert!(expanded.contains(error), "{error}: {expanded}");
+    }
+}
+
+#[test]
+fn runtime_paths() {
+    let expand_route = |options: &str| {
+        let input =
+            format!("#[routable({options})] enum Route {{ #[route(\"/\" Home)] Home {{}}, }}");
+        expand_str(&input)
+    };
+
+    let expanded = expand_route("crate = \"facade::router\"");
+    assert!(
+        expanded.contains("use facade :: router :: * ;"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("impl :: std :: str :: FromStr for Route"),
+        "{expanded}"
+    );
+    assert!(!expand_route("lenient").contains("use facade"));
+
+    let expanded = expand_route("crate = \"facade router\"");
+    assert!(expanded.contains("The crate must be a path"), "{expanded}");
+}
+
+#[test]
+fn matrix_errors() {
+    let expand_route = |route: &str| {
+        let input = format!(
+            "enum Route {{ #[route(\"{route}\" Items)] Items {{ filters: MatrixParams, rest: Vec<String> }}, }}"
+        );
+        expand_str(&input)
+    };
+
+    let expanded = expand_route("/items/(...rest);(filters)");
+    assert!(
+        expanded.contains("Catch-all segments can't have matrix params"),
+        "{expanded}"
+    );
+    let expanded = expand_route("/items;filters/(...rest)");
+    assert!(
+        expanded.contains("Matrix params are written as ';(name)' at the end of a segment"),
+        "{expanded}"
+    );
+    let expanded = expand_route("/items;(missing)/(...rest)");
+    assert!(
+        expanded.contains("Could not find a field with the name 'missing'"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn segment_role_errors() {
+    let input = r#"enum Route {
+        #[route("/files/(...tags)" Files)] Files { tags: Vec<String> },
+        #[route("/search/(...tags)" Search)] Search {
+            #[route_segment(include_query)]
+            tags: Vec<String>,
+        },
+    }"#;
+    let expanded = expand_str(input);
+    assert!(
+        expanded.contains(
+            "`Vec < String >` is a catch-all with the query in Search but a catch-all in Files"
+        ),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("`Vec < String >` is a catch-all here"),
+        "{expanded}"
+    );
+
+    let input = r#"enum Route {
+        #[route("/tag/(tag)" Tag)] Tag { tag: Tags },
+        #[route("/tags/(...tags)" AllTags)] AllTags { tags: Tags },
+    }"#;
+    let expanded = expand_str(input);
+    assert!(
+        expanded.contains("`Tags` is a catch-all in AllTags but a dynamic segment in Tag"),
+        "{expanded}"
+    );
+
+    // Types used in one role, or declared role aware, are fine
+    for input in [
+        r#"enum Route {
+            #[route("/a/(id)" A)] A { id: u32 },
+            #[route("/b/(id)/(...rest)" B)] B { id: u32, rest: Vec<String> },
+        }"#,
+        r#"enum Route {
+            #[route("/tag/(tag)" Tag)] Tag {
+                #[route_segment(role_aware)]
+                tag: Tags,
+            },
+            #[route("/tags/(...tags)" AllTags)] AllTags {
+                #[route_segment(role_aware)]
+                tags: Tags,
+            },
+        }"#,
+    ] {
+        let expanded = expand_str(input);
+        assert!(!expanded.contains("compile_error"), "{expanded}");
+    }
+}
+
+#[test]
+fn fixture_errors() {
+    let input = r#"#[routable(fixtures)]
+    enum Route {
+        #[route("/" Home)] Home {},
+        #[route("/all" All)] All {},
+    }"#;
+    let expanded = expand_str(input);
+    assert!(
+        expanded.contains("a route with fixtures can't be named `All`"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn conflict_test_modules() {
+    let expand = |name: &str| {
+        expand_str(&format!(
+            r#"#[routable(conflict_test)]
+            enum {name} {{
+                #[route("/" Home)] Home {{}},
+            }}"#
+        ))
+    };
+    let (app, admin) = (expand("AppRoute"), expand("AdminRoute"));
+    assert!(app.contains("mod app_route_conflicts"), "{app}");
+    assert!(admin.contains("mod admin_route_conflicts"), "{admin}");
+    assert!(!app.contains("admin_route_conflicts"), "{app}");
+}
+
+#[test]
+fn routes_hash() {
+    fn hash(input: &str) -> String {
+        let expanded = expand_str(input);
+        let start = expanded
+            .find("ROUTES_HASH : & 'static str = \"")
+            .expect("the hash is generated")
+            + 31;
+        expanded[start..start + 16].to_string()
+    }
+
+    let routes = r#"enum Route {
+        #[route("/" Home)] Home {},
+        /// A user
+        #[route("/users/(id)" User, title = "User {id}", status = 200)] User { id: u32 },
+    }"#;
+    assert_eq!(hash(routes), hash(routes));
+    // Documentation and the order of the arguments are not part of the hash
+    assert_eq!(
+        hash(routes),
+        hash(
+            r#"enum Route {
+                #[route("/" Home)] Home {},
+                #[route("/users/(id)" User, status = 200, title = "User {id}")] User { id: u32 },
+            }"#
+        )
+    );
+    for changed in [
+        r#"enum Route {
+            #[route("/" Home)] Home {},
+            #[route("/people/(id)" User, title = "User {id}", status = 200)] User { id: u32 },
+        }"#,
+        r#"enum Route {
+            #[route("/" Home)] Home {},
+            #[route("/users/(id)" User, title = "User {id}", status = 200)] User { id: String },
+        }"#,
+        r#"enum Route {
+            #[route("/" Home)] Home {},
+            #[route("/users/(id)" User, title = "Profile {id}", status = 200)] User { id: u32 },
+        }"#,
+    ] {
+        assert_ne!(hash(routes), hash(changed), "{changed}");
+    }
+}
+
+#[test]
+fn link_checks() {
+    let link = |path: &str| {
+        let input = format!(
+            "@routes Route [ \
+                Route::Home \"/\" {{}}; \
+                Route::Post \"/blog/(id)\" {{ id: u8, }}; \
+                Route::Draft \"/blog/(id)/draft/(visible)\" {{ id: u8, visible: bool, }} feature = \"drafts\"; \
+                Route::Tag \"/tags/tag-(name)\" {{ name: String, }}; \
+                Route::Docs \"/docs/(...rest)\" {{ rest: Vec<String>, }}; \
+                Route::User \"/users/(id)\" {{ id: UserId, }}; \
+                Route::About \"/a-propos\" {{}} locale = \"fr\"; \
+            ] \"{path}\""
+        );
+        link::expand(input.parse().unwrap()).to_string()
+    };
+
+    assert_eq!(link("/"), "Route :: Home { }");
+    assert_eq!(link("/blog/42/"), "Route :: Post { id : 42 , }");
+    assert_eq!(
+        link("/tags/tag-rust"),
+        "Route :: Tag { name : :: std :: string :: String :: from (\"rust\") , }"
+    );
+    assert_eq!(
+        link("/a-propos"),
+        "Route :: About { locale : :: std :: convert :: From :: from (\"fr\") , }"
+    );
+
+    // Values the type of the field rejects don't match
+    let expanded = link("/blog/256");
+    assert!(
+        expanded.contains("'/blog/256' doesn't match any route of Route"),
+        "{expanded}"
+    );
+    let expanded = link("/tags/rust");
+    assert!(expanded.contains("doesn't match any route"), "{expanded}");
+    let expanded = link("blog/1");
+    assert!(
+        expanded.contains("Links are absolute paths, like '/blog/1'"),
+        "{expanded}"
+    );
+
+    // Catch-alls, other types, features and query strings are parsed when the program runs
+    for path in [
+        "/docs/a/b",
+        "/users/7",
+        "/blog/1/draft/true",
+        "/blog/1?ref=home",
+    ] {
+        let expanded = link(path);
+        assert!(
+            expanded.contains("< Route as :: std :: str :: FromStr > :: from_str"),
+            "{expanded}"
+        );
+    }
+
+    let expanded = expand_str(
+        "#[routable(link_macro)] enum Route { #[route(\"/blog/(id)\" Post)] Post { id: u8 }, }",
+    );
+    assert!(
+        expanded.contains("macro_rules ! __router_link_Route"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains(
+            "link ! (@ routes Route [Route :: Post \"/blog/(id)\" { id : u8 , } ;] $ path)"
+        ),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn child_routes() {
+    let expand_route = |variants: &str| {
+        let input = format!("enum Route {{ {variants} }}");
+        expand_str(&input)
+    };
+
+    let expanded = expand_route(
+        "#[route(\"/settings/(...child)\" Settings)] Settings { #[route_segment(child)] child: SettingsRoute }, \
+         #[route(\"/(workspace)/(...child)\" Projects)] Projects { workspace: u32, #[route_segment(child)] child: crate::ProjectRoute },",
+    );
+    assert!(
+        expanded.contains("impl From < SettingsRoute > for Route { fn from (child : SettingsRoute) -> Self { Route :: Settings { child : child , } } }"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("pub fn from_project_route (child : crate :: ProjectRoute , workspace : u32) -> Self { Route :: Projects { child : child , workspace , } }"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("impl HasChild < crate :: ProjectRoute > for Route"),
+        "{expanded}"
+    );
+    assert!(expanded.contains("pub fn as_child < C >"), "{expanded}");
+    assert!(!expand_route("#[route(\"/\" Home)] Home {},").contains("as_child"));
+
+    let expanded = expand_route(
+        "#[route(\"/a/(...child)\" A)] A { #[route_segment(child)] child: SettingsRoute }, \
+         #[route(\"/b/(...child)\" B)] B { #[route_segment(child)] child: SettingsRoute },",
+    );
+    assert!(
+        expanded.contains(
+            "Another route of Route already holds the routes of SettingsRoute as its child"
+        ),
+        "{expanded}"
+    );
+
+    let input = "#[routable(nested)] enum SettingsRoute { #[route(\"/\" Home)] Home {}, }";
+    let expanded = expand_str(input);
+    assert!(
+        expanded.contains("impl FromRouteSegments for SettingsRoute"),
+        "{expanded}"
+    );
+    assert!(
+        expanded.contains("impl ToRouteSegments for SettingsRoute"),
+        "{expanded}"
+    );
+}
+
+#[test]
+fn path_option_errors() {
+    for (input, error) in [
+        (
+            r#"#[routable(trailing_slash = "strip")] enum Route {
+                #[route("/" Home)] Home {},
+            }"#,
+            "Unknown trailing slash mode. Expected \\\"accept\\\" or \\\"require\\\"",
+        ),
+        (
+            r#"#[routable(trailing_slash = "require")] enum Route {
+                #[route("/files/(...path)" Files)] Files { #[route_segment(include_query)] path: Vec<String> },
+            }"#,
+            "A catch-all with #[route_segment(include_query)] writes the query where",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn tuple_variant_errors() {
+    for (input, error) in [
+        (
+            r#"enum Route {
+                #[route("/users/(id)" User)] User(u32),
+            }"#,
+            "so its segments refer to them by position, like '(0)' instead of '(id)'",
+        ),
+        (
+            r#"enum Route {
+                #[route("/users/(1)" User)] User(u32),
+            }"#,
+            "The variant 'User' has 1 field(s), so it has no field at the position (1)",
+        ),
+        (
+            r#"enum Route {
+                #[route("/users/(0)" User)] User { id: u32 },
+            }"#,
+            "so its segments refer to them by name instead of the position (0)",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn optional_segment_errors() {
+    for (input, error) in [
+        (
+            r#"enum Route {
+                #[route("/posts/(?page)/comments" Posts)] Posts { page: Option<u32> },
+            }"#,
+            "The optional segment '(?page)' is followed by 'comments'",
+        ),
+        (
+            r#"enum Route {
+                #[route("/posts/page-(?page)" Posts)] Posts { page: Option<u32> },
+            }"#,
+            "Optional segments take the whole segment, so '(?page)' can't have a prefix or suffix",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn not_found_errors() {
+    for (input, error) in [
+        (
+            r#"enum Route {
+                #[route("/" Home)] Home {},
+                #[route(not_found Missing)] Missing {},
+                #[route(not_found Gone)] Gone {},
+            }"#,
+            "Only one variant can be #[route(not_found)], but Missing already is, so Gone can't be",
+        ),
+        (
+            r#"enum Route {
+                #[route(not_found Missing)] Missing { path: Vec<String> },
+            }"#,
+            "so the enum needs at least one other route",
+        ),
+        (
+            r#"enum Route {
+                #[route("/" Home)] Home {},
+                #[route(not_found Missing)] Missing { path: Vec<String>, query: String },
+            }"#,
+            "The not_found variant can only have one field",
+        ),
+        (
+            r#"#[routable(shared = "/(lang)")] enum Route {
+                #[route("/" Home)] Home { lang: String },
+                #[route(not_found Missing)] Missing {},
+            }"#,
+            "so it can't be combined with #[routable(shared",
+        ),
+    ] {
+        let expanded = expand_str(input);
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn collected_errors() {
+    let expanded = expand_str(
+        r#"enum Route {
+            #[route("/users/(idd)/(tabb)" User)] User { id: u32, tab: String },
+            #[route("/posts/(id)" Post)] Post { id: u32, draft: bool },
+            #[route("/files/(0)" File)] File(String, u8),
+        }"#,
+    );
+    for error in [
+        "Could not find a field with the name 'idd' in the variant 'User'",
+        "Could not find a field with the name 'tabb' in the variant 'User'",
+        "The field 'draft' of the variant 'Post' isn't a segment of the route '/posts/(id)'",
+        "The field '1' of the variant 'File' isn't a segment of the route '/files/(0)'",
+    ] {
+        assert!(expanded.contains(error), "{expanded}");
+    }
+}
+
+#[test]
+fn foreign_attributes() {
+    let input = r#"#[serde(rename_all = "kebab-case")]
+    /// The routes of the app
+    enum Route {
+        /// The landing page
+        #[strum(serialize = "home")]
+        #[route("/" Home)]
+        #[serde(rename = "index")]
+        Home {},
+        #[route("/users/(id)" User)]
+        User {
+            /// The id of the user
+            #[serde(default)]
+            id: u32,
+        },
+    }"#;
+    let expanded = expand_str(input);
+    assert!(!expanded.contains("compile_error"), "{expanded}");
+    // Attributes of other derives are left to them
+    assert!(!expanded.contains("serde"), "{expanded}");
+    assert!(!expanded.contains("strum"), "{expanded}");
+    assert_eq!(
+        expanded,
+        expand_str(
+            r#"enum Route {
+                #[route("/" Home)] Home {},
+                #[route("/users/(id)" User)] User { id: u32 },
+            }"#
+        )
+    );
 }
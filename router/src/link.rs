@@ -0,0 +1,350 @@
+//! `link!(Route, "/blog/42")`: check a literal path against a routable type while the crate is built, and
+//! evaluate to the route it parses as.
+//!
+//! A proc macro can't see the variants of another type, so `#[routable(link_macro)]` makes the derive write them
+//! into a `macro_rules!` named after the type, like `__router_link_Route`. `link!` calls it with the path, and it
+//! calls `link!` again with the patterns and field types of every route. Like every `macro_rules!`, it can only be
+//! used after the type in the same module and in the modules declared after it.
+//!
+//! The path is matched against the routes in the order the parser tries them. A route whose segments are all
+//! integers, `bool`s, `char`s and `String`s is decided while expanding: the link becomes the variant with its fields
+//! written as literals, or a build error if no route matches. A route that may match but has a segment of another
+//! type can only be decided by parsing it, so the link falls back to parsing the path when the program runs and
+//! panics with the path if it doesn't parse. The same goes for routes with a `feature` or `normalize`, for paths with
+//! a query, a fragment or percent-encoding, and for every path of a type with a `shared_guard`.
+
+use crate::route::{parse_pattern, Route, RouteSegment};
+use crate::route_tree::RouteTreeSegment;
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitStr, Token, Type};
+
+/// The name of the `macro_rules!` that holds the routes of a type
+pub fn table_macro_name(name: &Ident) -> Ident {
+    format_ident!("__router_link_{}", name)
+}
+
+pub fn expand(input: TokenStream2) -> TokenStream2 {
+    let result = match syn::parse2::<LinkInput>(input) {
+        Ok(LinkInput::Call { ty, path }) => Ok(call_table(ty, path)),
+        Ok(LinkInput::Table(table)) => table.link(),
+        Err(err) => Err(err),
+    };
+    result.unwrap_or_else(|err| err.to_compile_error())
+}
+
+/// `link!(Route, "/blog/42")` calls the table of `Route` next to it
+fn call_table(mut ty: syn::Path, path: LitStr) -> TokenStream2 {
+    let last = ty
+        .segments
+        .last_mut()
+        .expect("paths have at least one segment");
+    last.ident = table_macro_name(&last.ident);
+    quote! { #ty!(#path) }
+}
+
+enum LinkInput {
+    Call { ty: syn::Path, path: LitStr },
+    Table(Table),
+}
+
+impl Parse for LinkInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        if input.peek(Token![@]) {
+            return Ok(Self::Table(input.parse()?));
+        }
+        let ty = input.parse::<syn::Path>()?;
+        input.parse::<Token![,]>()?;
+        let path = input.parse::<LitStr>()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self::Call { ty, path })
+    }
+}
+
+/// The routes of a type, as the table macro writes them: `@routes Route runtime? [ entries ] "/path"`
+struct Table {
+    name: Ident,
+    /// The type checks paths while the program runs, with a `shared_guard`, so every link is parsed then
+    runtime: bool,
+    entries: Vec<Entry>,
+    path: LitStr,
+}
+
+/// `Route::BlogPost "/blog/(id)" { id: u64, } locale = "fr" feature = "blog";`
+struct Entry {
+    variant: syn::Path,
+    pattern: LitStr,
+    /// The field of every segment, named or by position like `0: u64` for a tuple variant
+    fields: Vec<(syn::Member, Type)>,
+    locale: Option<LitStr>,
+    /// The route has a `feature` or a `normalize` function, so only the program knows what a match parses as
+    at_runtime: bool,
+}
+
+impl Parse for Table {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        input.parse::<Token![@]>()?;
+        input.parse::<Ident>()?;
+        let name = input.parse::<Ident>()?;
+        let runtime = input.peek(Ident);
+        if runtime {
+            input.parse::<Ident>()?;
+        }
+        let content;
+        syn::bracketed!(content in input);
+        let mut entries = Vec::new();
+        while !content.is_empty() {
+            let variant = content.parse::<syn::Path>()?;
+            let pattern = content.parse::<LitStr>()?;
+            let fields_content;
+            syn::braced!(fields_content in content);
+            let mut fields = Vec::new();
+            while !fields_content.is_empty() {
+                let member = fields_content.parse::<syn::Member>()?;
+                fields_content.parse::<Token![:]>()?;
+                fields.push((member, fields_content.parse::<Type>()?));
+                fields_content.parse::<Option<Token![,]>>()?;
+            }
+            let mut locale = None;
+            let mut at_runtime = false;
+            while !content.peek(Token![;]) {
+                let key = content.parse::<Ident>()?;
+                content.parse::<Token![=]>()?;
+                let value = content.parse::<LitStr>()?;
+                match key == "locale" {
+                    true => locale = Some(value),
+                    false => at_runtime = true,
+                }
+            }
+            content.parse::<Token![;]>()?;
+            entries.push(Entry {
+                variant,
+                pattern,
+                fields,
+                locale,
+                at_runtime,
+            });
+        }
+        let path = input.parse::<LitStr>()?;
+        Ok(Self {
+            name,
+            runtime,
+            entries,
+            path,
+        })
+    }
+}
+
+/// The name of a field in a pattern: its name, or its position like `0` for a field of a tuple variant
+fn member_name(member: &syn::Member) -> String {
+    match member {
+        syn::Member::Named(ident) => ident.to_string(),
+        syn::Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+/// The name a pattern binds the field to, like `field_0` for the field `0` of a tuple variant
+fn binding_name(member: &syn::Member) -> String {
+    match member {
+        syn::Member::Named(ident) => ident.to_string(),
+        syn::Member::Unnamed(index) => format!("field_{}", index.index),
+    }
+}
+
+/// How a route fits a path
+enum Fit {
+    /// The route doesn't match the path
+    No,
+    /// The route matches, and these are the values of its fields
+    Fields(Vec<(Ident, TokenStream2)>),
+    /// The route may match, but it can only be told by parsing the path
+    Unknown,
+}
+
+impl Table {
+    fn link(&self) -> syn::Result<TokenStream2> {
+        let path = self.path.value();
+        let Some(rest) = path.strip_prefix('/') else {
+            return Err(syn::Error::new_spanned(
+                &self.path,
+                format!("Links are absolute paths, like '/{path}'"),
+            ));
+        };
+        if self.runtime || path.contains(['?', '#', '%']) {
+            return Ok(self.parse_at_runtime());
+        }
+
+        let mut routes = Vec::new();
+        for entry in &self.entries {
+            let segments = parse_pattern(&entry.pattern, &entry.pattern, |ident, _| {
+                entry
+                    .fields
+                    .iter()
+                    .find_map(|(field, ty)| (member_name(field) == ident).then(|| ty.clone()))
+                    .ok_or_else(|| syn::Error::new_spanned(&entry.pattern, "missing field"))
+            })?;
+            let route_name = Ident::new("Link", Span::call_site());
+            routes.push(Route::from_segments(
+                route_name,
+                entry.pattern.clone(),
+                segments,
+            ));
+        }
+        let mut ordered = Vec::new();
+        for segment in RouteTreeSegment::build(&routes) {
+            segment.routes_in_match_order(&mut ordered);
+        }
+
+        let segments: Vec<&str> = rest.split('/').collect();
+        for route in ordered {
+            let index = routes
+                .iter()
+                .position(|other| std::ptr::eq(other, route))
+                .expect("the tree only has routes of the table");
+            let entry = &self.entries[index];
+            match fit(route, &segments) {
+                Fit::No => continue,
+                Fit::Unknown => return Ok(self.parse_at_runtime()),
+                Fit::Fields(_) if entry.at_runtime => return Ok(self.parse_at_runtime()),
+                Fit::Fields(fields) => {
+                    let variant = &entry.variant;
+                    // Positional segments are bound as `field_0`, but the variant is built with its member `0`
+                    let fields = fields.iter().map(|(ident, value)| {
+                        let member = entry
+                            .fields
+                            .iter()
+                            .map(|(member, _)| member)
+                            .find(|member| *ident == binding_name(member));
+                        quote! { #member: #value, }
+                    });
+                    let locale = entry.locale.as_ref().map(|locale| {
+                        quote! { locale: ::std::convert::From::from(#locale), }
+                    });
+                    return Ok(quote! { #variant { #(#fields)* #locale } });
+                }
+            }
+        }
+
+        Err(syn::Error::new_spanned(
+            &self.path,
+            format!("'{}' doesn't match any route of {}", path, self.name),
+        ))
+    }
+
+    /// Parse the path when the program runs, panicking with the span of the literal if it doesn't parse
+    fn parse_at_runtime(&self) -> TokenStream2 {
+        let name = &self.name;
+        let path = &self.path;
+        quote_spanned! {path.span()=>
+            <#name as ::std::str::FromStr>::from_str(#path).unwrap_or_else(|err| {
+                panic!("The link '{}' doesn't parse as a {}: {}", #path, stringify!(#name), err)
+            })
+        }
+    }
+}
+
+/// Match the segments of a path against a route
+fn fit(route: &Route, segments: &[&str]) -> Fit {
+    let mut fields = Vec::new();
+    let mut unknown = false;
+    let mut rest = segments.iter();
+    for (i, segment) in route.route_segments.iter().enumerate() {
+        match segment {
+            RouteSegment::Static(text) => {
+                let Some(value) = rest.next() else {
+                    return Fit::No;
+                };
+                let value = match route.has_matrix(i) {
+                    true => value.split_once(';').map_or(*value, |(value, _)| value),
+                    false => value,
+                };
+                if value != text {
+                    return Fit::No;
+                }
+            }
+            RouteSegment::Dynamic(ident, ty, affix) => {
+                let Some(value) = rest.next() else {
+                    return Fit::No;
+                };
+                let value = match route.has_matrix(i) {
+                    true => value.split_once(';').map_or(*value, |(value, _)| value),
+                    false => value,
+                };
+                let value = match affix {
+                    Some(affix) => {
+                        let stripped = value
+                            .strip_prefix(affix.prefix.as_str())
+                            .and_then(|value| value.strip_suffix(affix.suffix.as_str()));
+                        match stripped {
+                            Some(value) => value,
+                            None => return Fit::No,
+                        }
+                    }
+                    None => value,
+                };
+                // Empty values depend on `allow_empty`, which the table doesn't have
+                if value.is_empty() {
+                    unknown = true;
+                    continue;
+                }
+                match literal(ty, value) {
+                    Some(Some(literal)) => fields.push((ident.clone(), literal)),
+                    Some(None) => return Fit::No,
+                    None => unknown = true,
+                }
+            }
+            // Catch-alls, optional segments, matrix params, queries and fragments are parsed by the traits of their
+            // type
+            RouteSegment::CatchAll(..) => {
+                rest.by_ref().for_each(drop);
+                unknown = true;
+            }
+            RouteSegment::Optional(..) => {
+                rest.next();
+                unknown = true;
+            }
+            RouteSegment::Matrix(..) | RouteSegment::Query(..) | RouteSegment::Fragment(..) => {
+                unknown = true
+            }
+        }
+    }
+    // A single trailing slash is ignored, like by the parser
+    match (rest.next(), rest.next()) {
+        (None, _) | (Some(&""), None) if unknown => Fit::Unknown,
+        (None, _) | (Some(&""), None) => Fit::Fields(fields),
+        _ => Fit::No,
+    }
+}
+
+/// The value of a segment as a literal of its type: `Some(None)` if the type rejects it and `None` if the type
+/// can't be checked while expanding
+fn literal(ty: &Type, value: &str) -> Option<Option<TokenStream2>> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let ident = path.path.get_ident()?.to_string();
+    let valid = match ident.as_str() {
+        "u8" => value.parse::<u8>().is_ok(),
+        "u16" => value.parse::<u16>().is_ok(),
+        "u32" => value.parse::<u32>().is_ok(),
+        "u64" => value.parse::<u64>().is_ok(),
+        "u128" => value.parse::<u128>().is_ok(),
+        "usize" => value.parse::<usize>().is_ok(),
+        "i8" => value.parse::<i8>().is_ok(),
+        "i16" => value.parse::<i16>().is_ok(),
+        "i32" => value.parse::<i32>().is_ok(),
+        "i64" => value.parse::<i64>().is_ok(),
+        "i128" => value.parse::<i128>().is_ok(),
+        "isize" => value.parse::<isize>().is_ok(),
+        "bool" => return Some(value.parse::<bool>().ok().map(|value| quote! { #value })),
+        "char" => return Some(value.parse::<char>().ok().map(|value| quote! { #value })),
+        "String" => return Some(Some(quote! { ::std::string::String::from(#value) })),
+        _ => return None,
+    };
+    // The range was checked for the type of the field, so the literal is written without a suffix
+    Some(valid.then(|| match ident.starts_with('i') {
+        true => Literal::i128_unsuffixed(value.parse().unwrap()).into_token_stream(),
+        false => Literal::u128_unsuffixed(value.parse().unwrap()).into_token_stream(),
+    }))
+}
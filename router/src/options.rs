@@ -0,0 +1,465 @@
+use syn::ext::IdentExt;
+use syn::parse::ParseStream;
+use syn::{Attribute, Ident, Lit, LitInt, LitStr, Meta, NestedMeta, Token};
+
+/// The longest path the parser accepts without `max_path_len`. Browsers and servers commonly cap URLs at 8 KiB.
+pub const DEFAULT_MAX_PATH_LEN: usize = 8 * 1024;
+
+/// The most segments a path can have without `max_segments`
+pub const DEFAULT_MAX_SEGMENTS: usize = 256;
+
+/// Options set on the routable type with `#[routable(...)]`
+#[derive(Debug, Default)]
+pub struct RoutableOptions {
+    /// Generate a test that checks every route can be reached by its example path
+    pub conflict_test: bool,
+    /// Generate a module of fixture constructors, one per route, for tests and the `fixtures` feature
+    pub fixtures: bool,
+    /// Generate `from_str_lenient`, which fills missing trailing segments with their default values
+    pub lenient: bool,
+    /// Skip the inherent `to_path` and `from_path` methods, for types that define methods with those names
+    pub no_inherent: bool,
+    /// Print the number of tokens generated for each route while the type is expanded
+    pub size_report: bool,
+    /// Return parse errors as `Box<dyn std::error::Error + Send + Sync>` and keep the generated error types
+    /// crate-private, so they don't become part of a library's public API
+    pub boxed_errors: bool,
+    /// Generate `try_to_path`, which checks that a route displays as a path that parses back into it
+    pub strict_display: bool,
+    /// Generate the table of routes `link!(Type, "/path")` checks literal paths against
+    pub link_macro: bool,
+    /// Implement `FromRouteSegments` and `ToRouteSegments`, so the type can be the `child` catch-all of a route in
+    /// another routable type
+    pub nested: bool,
+    /// The version of the serialized route format, set with `version = 3`
+    pub version: Option<LitInt>,
+    /// Leading segments shared by every variant, set with `shared = "/(workspace)"`. Each variant must have a field
+    /// for every dynamic segment in it, with the same type in every variant.
+    pub shared: Option<LitStr>,
+    /// A function that checks the values of the shared segments before any route is tried, set with
+    /// `shared_guard = check_version`. It gets a reference to each of them and returns a `Result<(), E>`
+    pub shared_guard: Option<syn::Path>,
+    /// Functions that migrate a serialized route from one version to the next, set with `migrate(2 => migrate_v2)`
+    pub migrations: Vec<(LitInt, syn::Path)>,
+    /// A route table exported from an earlier version of the type, set with `baseline = "routes_baseline.json"`.
+    /// The path is relative to the crate root.
+    pub baseline: Option<LitStr>,
+    /// Patterns of the baseline that moved, set with `redirect("/old" => "/new")`
+    pub redirects: Vec<(LitStr, LitStr)>,
+    /// The longest path in bytes the parser accepts, set with `max_path_len = 4096`
+    pub max_path_len: Option<LitInt>,
+    /// The most segments a path can have for the parser to try its routes, set with `max_segments = 64`
+    pub max_segments: Option<LitInt>,
+    /// The locale of the pattern in `#[route("...")]` itself on routes with `locale(...)` patterns, set with
+    /// `default_locale = "en-GB"`
+    pub default_locale: Option<LitStr>,
+    /// The locales of the localized routes and the direction their text is written in, set with
+    /// `locales(en(dir = "ltr"), ar(dir = "rtl"))`. Once it is set, every locale of a route must be listed.
+    pub locales: Vec<(Ident, Option<LitStr>)>,
+    /// The module the generated code imports the router's items from, set with `crate = "my_facade::router"`.
+    /// Without it they must be in scope where the type is derived.
+    pub runtime: Option<syn::Path>,
+    /// How the parser treats a slash at the end of a path, set with `trailing_slash = "require"`
+    pub trailing_slash: TrailingSlash,
+    /// Compare static segments ignoring the case of ASCII letters, set with `case_insensitive`. Routes still
+    /// display with the casing of their pattern.
+    pub case_insensitive: bool,
+}
+
+/// The modes of `#[routable(trailing_slash = "...")]`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// Paths parse with or without a slash at the end, and routes display without one
+    #[default]
+    Accept,
+    /// Only paths with a slash at the end parse, and routes display with one. The root is `/` either way. Segments
+    /// passed to `from_segments` are already split from their path, so they aren't checked.
+    Require,
+}
+
+impl RoutableOptions {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        // The migrations use `=>`, which isn't valid meta syntax, so the options are parsed by hand
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("routable")) {
+            attr.parse_args_with(|input: ParseStream<'_>| {
+                while !input.is_empty() {
+                    // `crate` is a keyword, so it isn't parsed by `Ident`'s own parser
+                    let key = input.call(Ident::parse_any)?;
+                    if key == "conflict_test" {
+                        options.conflict_test = true;
+                    } else if key == "fixtures" {
+                        options.fixtures = true;
+                    } else if key == "link_macro" {
+                        options.link_macro = true;
+                    } else if key == "lenient" {
+                        options.lenient = true;
+                    } else if key == "nested" {
+                        options.nested = true;
+                    } else if key == "no_inherent" {
+                        options.no_inherent = true;
+                    } else if key == "size_report" {
+                        options.size_report = true;
+                    } else if key == "boxed_errors" {
+                        options.boxed_errors = true;
+                    } else if key == "strict_display" {
+                        options.strict_display = true;
+                    } else if key == "case_insensitive" {
+                        options.case_insensitive = true;
+                    } else if key == "trailing_slash" {
+                        input.parse::<Token![=]>()?;
+                        let mode = input.parse::<LitStr>()?;
+                        options.trailing_slash = match mode.value().as_str() {
+                            "accept" => TrailingSlash::Accept,
+                            "require" => TrailingSlash::Require,
+                            _ => {
+                                return Err(syn::Error::new_spanned(
+                                    mode,
+                                    r#"Unknown trailing slash mode. Expected "accept" or "require""#,
+                                ))
+                            }
+                        };
+                    } else if key == "version" {
+                        input.parse::<Token![=]>()?;
+                        options.version = Some(input.parse()?);
+                    } else if key == "shared" {
+                        input.parse::<Token![=]>()?;
+                        options.shared = Some(input.parse()?);
+                    } else if key == "shared_guard" {
+                        input.parse::<Token![=]>()?;
+                        options.shared_guard = Some(input.parse()?);
+                    } else if key == "migrate" {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        while !content.is_empty() {
+                            let version = content.parse::<LitInt>()?;
+                            content.parse::<Token![=>]>()?;
+                            options.migrations.push((version, content.parse()?));
+                            if !content.is_empty() {
+                                content.parse::<Token![,]>()?;
+                            }
+                        }
+                    } else if key == "baseline" {
+                        input.parse::<Token![=]>()?;
+                        options.baseline = Some(input.parse()?);
+                    } else if key == "max_path_len" {
+                        input.parse::<Token![=]>()?;
+                        options.max_path_len = Some(input.parse()?);
+                    } else if key == "max_segments" {
+                        input.parse::<Token![=]>()?;
+                        options.max_segments = Some(input.parse()?);
+                    } else if key == "crate" {
+                        input.parse::<Token![=]>()?;
+                        let path = input.parse::<LitStr>()?;
+                        options.runtime = Some(path.parse().map_err(|_| {
+                            syn::Error::new_spanned(
+                                &path,
+                                "The crate must be a path, like `crate = \"my_facade::router\"`",
+                            )
+                        })?);
+                    } else if key == "default_locale" {
+                        input.parse::<Token![=]>()?;
+                        options.default_locale = Some(input.parse()?);
+                    } else if key == "locales" {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        while !content.is_empty() {
+                            let locale = content.parse::<Ident>()?;
+                            let dir = match content.peek(syn::token::Paren) {
+                                true => Some(parse_locale_dir(&content)?),
+                                false => None,
+                            };
+                            if options.locales.iter().any(|(other, _)| *other == locale) {
+                                return Err(syn::Error::new_spanned(
+                                    locale,
+                                    "This locale is already listed",
+                                ));
+                            }
+                            options.locales.push((locale, dir));
+                            if !content.is_empty() {
+                                content.parse::<Token![,]>()?;
+                            }
+                        }
+                    } else if key == "redirect" {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        while !content.is_empty() {
+                            let from = content.parse::<LitStr>()?;
+                            content.parse::<Token![=>]>()?;
+                            options.redirects.push((from, content.parse()?));
+                            if !content.is_empty() {
+                                content.parse::<Token![,]>()?;
+                            }
+                        }
+                    } else {
+                        return Err(syn::Error::new_spanned(key, "Unknown routable option"));
+                    }
+                    if !input.is_empty() {
+                        input.parse::<Token![,]>()?;
+                    }
+                }
+                Ok(())
+            })?;
+        }
+
+        options.validate_migrations()?;
+        options.validate_shared()?;
+        for limit in [&options.max_path_len, &options.max_segments]
+            .into_iter()
+            .flatten()
+        {
+            if limit.base10_parse::<usize>()? == 0 {
+                return Err(syn::Error::new_spanned(
+                    limit,
+                    "A parse limit of 0 would reject every path",
+                ));
+            }
+        }
+        if let (None, Some((from, _))) = (&options.baseline, options.redirects.first()) {
+            return Err(syn::Error::new_spanned(
+                from,
+                "Redirects describe changes since the baseline: add `baseline = \"...\"` to #[routable(...)]",
+            ));
+        }
+
+        Ok(options)
+    }
+
+    /// The longest path the parser accepts, in bytes
+    pub fn max_path_len(&self) -> usize {
+        self.max_path_len
+            .as_ref()
+            .and_then(|limit| limit.base10_parse().ok())
+            .unwrap_or(DEFAULT_MAX_PATH_LEN)
+    }
+
+    /// The most segments a path the parser accepts can have
+    pub fn max_segments(&self) -> usize {
+        self.max_segments
+            .as_ref()
+            .and_then(|limit| limit.base10_parse().ok())
+            .unwrap_or(DEFAULT_MAX_SEGMENTS)
+    }
+
+    /// The locale of the default pattern of localized routes, `en` unless it is set
+    pub fn default_locale(&self) -> String {
+        self.default_locale
+            .as_ref()
+            .map_or_else(|| "en".to_string(), LitStr::value)
+    }
+
+    /// Check that the shared segments can be put in front of every route.
+    fn validate_shared(&self) -> syn::Result<()> {
+        let Some(shared) = &self.shared else {
+            return match &self.shared_guard {
+                Some(guard) => Err(syn::Error::new_spanned(
+                    guard,
+                    "A shared_guard checks the shared segments, add them with `shared = \"...\"`",
+                )),
+                None => Ok(()),
+            };
+        };
+        let value = shared.value();
+        if !value.starts_with('/') || value == "/" || value.ends_with('/') {
+            return Err(syn::Error::new_spanned(
+                shared,
+                "Shared segments should start with / and not end with one, like \"/(workspace)\"",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that a serialized route of every older version can be migrated to the current version.
+    fn validate_migrations(&self) -> syn::Result<()> {
+        let version = match &self.version {
+            Some(version) => version,
+            None => {
+                return match self.migrations.first() {
+                    Some((version, _)) => Err(syn::Error::new_spanned(
+                        version,
+                        "Migrations need a current version: add `version = ...` to #[routable(...)]",
+                    )),
+                    None => Ok(()),
+                };
+            }
+        };
+        let current = version.base10_parse::<u32>()?;
+        if current == 0 {
+            return Err(syn::Error::new_spanned(version, "Versions start at 1"));
+        }
+
+        let mut migrated = Vec::new();
+        for (from, _) in &self.migrations {
+            let from_version = from.base10_parse::<u32>()?;
+            if from_version == 0 || from_version >= current {
+                return Err(syn::Error::new_spanned(
+                    from,
+                    format!(
+                        "Migrations must start from a version between 1 and {}",
+                        current - 1
+                    ),
+                ));
+            }
+            if migrated.contains(&from_version) {
+                return Err(syn::Error::new_spanned(
+                    from,
+                    format!("There is already a migration from version {}", from_version),
+                ));
+            }
+            migrated.push(from_version);
+        }
+        if let Some(missing) = (1..current).find(|v| !migrated.contains(v)) {
+            return Err(syn::Error::new_spanned(
+                version,
+                format!(
+                    "Missing a migration from version {}: add `migrate({} => ...)` to #[routable(...)]",
+                    missing, missing
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse the `(dir = "rtl")` of a locale in `locales(...)`
+fn parse_locale_dir(input: ParseStream<'_>) -> syn::Result<LitStr> {
+    let content;
+    syn::parenthesized!(content in input);
+    let key = content.parse::<Ident>()?;
+    if key != "dir" {
+        return Err(syn::Error::new_spanned(
+            key,
+            "Unknown locale option. Expected `dir = \"...\"`",
+        ));
+    }
+    content.parse::<Token![=]>()?;
+    let dir = content.parse::<LitStr>()?;
+    if !matches!(dir.value().as_str(), "ltr" | "rtl" | "auto") {
+        return Err(syn::Error::new_spanned(
+            dir,
+            r#"Unknown direction. Expected "ltr", "rtl" or "auto""#,
+        ));
+    }
+    Ok(dir)
+}
+
+/// Options set on a field of a route with `#[route_segment(...)]`
+#[derive(Debug, Default, Clone)]
+pub struct SegmentOptions {
+    /// The value used for this segment when building an example path for the route
+    pub example: Option<LitStr>,
+    /// Accept an empty value for this segment, like the `name` in `/user//settings`, set with `allow_empty`
+    pub allow_empty: bool,
+    /// Require at least one segment for this catch-all, set with `non_empty`. Catch-alls match zero
+    /// segments otherwise.
+    pub non_empty: bool,
+    /// Append `?` and the raw query to the value of this catch-all, set with `include_query`. Display writes the
+    /// value back verbatim, query included.
+    pub include_query: bool,
+    /// The characters of a dynamic segment that are written without percent-encoding, set with
+    /// `encode = "unreserved"`
+    pub encode: SegmentEncoding,
+    /// The type of this field is parsed the same way whether it is a dynamic segment, a catch-all or a catch-all
+    /// with the query, so it may be used in another of those roles by another variant. Set with `role_aware`
+    pub role_aware: bool,
+    /// This catch-all holds the routes of another routable type with `#[routable(nested)]`, and the enum converts
+    /// from and into them. Set with `child`
+    pub child: bool,
+}
+
+/// The encoding sets of `#[route_segment(encode = "...")]`. The runtime's `EncodeSet` has a variant for each.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentEncoding {
+    /// Every character RFC 3986 allows in a path segment
+    #[default]
+    Pchar,
+    /// Only letters, digits and `-._~`
+    Unreserved,
+    /// Nothing is encoded
+    None,
+}
+
+impl SegmentOptions {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for meta in nested_meta(attrs, "route_segment")? {
+            match &meta {
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("example") =>
+                {
+                    match &name_value.lit {
+                        Lit::Str(example) => options.example = Some(example.clone()),
+                        lit => {
+                            return Err(syn::Error::new_spanned(
+                                lit,
+                                "The example value must be a string literal",
+                            ))
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("allow_empty") => {
+                    options.allow_empty = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("non_empty") => {
+                    options.non_empty = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("include_query") => {
+                    options.include_query = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("role_aware") => {
+                    options.role_aware = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("child") => {
+                    options.child = true;
+                }
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("encode") =>
+                {
+                    options.encode = match &name_value.lit {
+                        Lit::Str(encode) if encode.value() == "pchar" => SegmentEncoding::Pchar,
+                        Lit::Str(encode) if encode.value() == "unreserved" => {
+                            SegmentEncoding::Unreserved
+                        }
+                        Lit::Str(encode) if encode.value() == "none" => SegmentEncoding::None,
+                        lit => {
+                            return Err(syn::Error::new_spanned(
+                                lit,
+                                r#"Unknown encoding. Expected "pchar", "unreserved" or "none""#,
+                            ))
+                        }
+                    };
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "Unknown route segment option",
+                    ))
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Collect the items of every `#[name(...)]` attribute
+fn nested_meta(attrs: &[Attribute], name: &str) -> syn::Result<Vec<NestedMeta>> {
+    let mut nested = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(name)) {
+        match attr.parse_meta()? {
+            Meta::List(list) => nested.extend(list.nested),
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    format!("Expected #[{}(...)]", name),
+                ))
+            }
+        }
+    }
+
+    Ok(nested)
+}
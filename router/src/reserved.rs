@@ -0,0 +1,97 @@
+//! Names a dynamic or catch-all segment can't have. Every way of declaring a segment checks its name here, so new
+//! kinds of segments get the same checks.
+
+use crate::route::to_snake_case;
+
+/// Rust keywords, including the reserved ones. Fields can only use them as raw identifiers, which the segment
+/// syntax doesn't support.
+const KEYWORDS: &[&str] = &[
+    "_", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Names the generated code binds while the fields of a route are in scope. A field with one of these names would
+/// shadow the generated binding or be shadowed by it.
+const GENERATED_BINDINGS: &[&str] = &[
+    "cx",
+    "errors",
+    "f",
+    "next_segment",
+    "param",
+    "parsed",
+    "remaining_segments",
+    "segment",
+    "segment_after_next",
+    "segments",
+    "segments_clone",
+];
+
+/// Generated code may use any name with this prefix in the future
+const RESERVED_PREFIX: &str = "__router";
+
+/// Check the name of a segment, returning an error message with a suggested name if it can't be used.
+pub fn check_segment_name(name: &str) -> Result<(), String> {
+    let (reason, suggestion) = if KEYWORDS.contains(&name) {
+        ("is a Rust keyword", format!("{name}_"))
+    } else if GENERATED_BINDINGS.contains(&name) {
+        ("is used by the generated code", format!("{name}_value"))
+    } else if let Some(rest) = name.strip_prefix(RESERVED_PREFIX) {
+        (
+            "starts with `__router`, which is reserved for the generated code",
+            rest.trim_start_matches('_').to_string(),
+        )
+    } else if name.ends_with("Props")
+        || name.ends_with("ParseError")
+        || is_static_segment_error(name)
+    {
+        (
+            "collides with the names of generated types",
+            to_snake_case(name),
+        )
+    } else {
+        return Ok(());
+    };
+
+    let suggestion = match suggestion.is_empty() {
+        true => String::new(),
+        false => format!(". Try '{suggestion}' instead"),
+    };
+    Err(format!("The segment name '{name}' {reason}{suggestion}"))
+}
+
+/// Check if a segment would generate the same error variant as a static segment, like `StaticSegment0`
+fn is_static_segment_error(name: &str) -> bool {
+    name.strip_prefix("StaticSegment")
+        .is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[test]
+fn reserved_names() {
+    assert_eq!(check_segment_name("id"), Ok(()));
+    assert_eq!(check_segment_name("segment_id"), Ok(()));
+    assert_eq!(
+        check_segment_name("type"),
+        Err("The segment name 'type' is a Rust keyword. Try 'type_' instead".to_string())
+    );
+    assert_eq!(
+        check_segment_name("cx"),
+        Err(
+            "The segment name 'cx' is used by the generated code. Try 'cx_value' instead"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        check_segment_name("__router_state"),
+        Err("The segment name '__router_state' starts with `__router`, which is reserved for the generated code. Try 'state' instead".to_string())
+    );
+    assert!(check_segment_name("__router").is_err());
+    assert_eq!(
+        check_segment_name("UserProps"),
+        Err("The segment name 'UserProps' collides with the names of generated types. Try 'user_props' instead".to_string())
+    );
+    assert!(check_segment_name("StaticSegment0").is_err());
+    assert_eq!(check_segment_name("StaticSegment"), Ok(()));
+}
@@ -1,117 +1,2421 @@
-use quote::{__private::Span, format_ident, quote, ToTokens};
-use syn::{Ident, LitStr, Type, Variant};
-use syn::parse::ParseStream;
+use quote::{__private::Span, format_ident, quote, quote_spanned, ToTokens};
+use syn::parse::discouraged::Speculative;
 use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::spanned::Spanned;
+use syn::{Fields, Ident, LitStr, Token, Type};
+
+use proc_macro2::TokenStream as TokenStream2;
+
+use crate::edit_distance::edit_distance;
+use crate::options::{RoutableOptions, SegmentEncoding, SegmentOptions, TrailingSlash};
+use crate::reserved;
+
+struct RouteArgs {
+    route: LitStr,
+    /// The `not_found` of `#[route(not_found)]`, which is written instead of the pattern
+    not_found: Option<Ident>,
+    comp_name: Option<syn::Path>,
+    props_name: Option<Ident>,
+    builder: bool,
+    action: Option<syn::Path>,
+    dry_run_safe: Option<syn::LitBool>,
+    normalize: Option<syn::Path>,
+    guard: Option<syn::Path>,
+    guard_on_soft_navigation: bool,
+    hard_navigation: bool,
+    status: Option<u16>,
+    cache: Option<LitStr>,
+    provide: Option<syn::Path>,
+    title: Option<LitStr>,
+    meta: Vec<(LitStr, LitStr)>,
+    feature: Option<LitStr>,
+    shortcut: Option<LitStr>,
+    locale: Vec<(LitStr, LitStr)>,
+    icon: Option<LitStr>,
+    palette: bool,
+    palette_provider: Option<syn::Path>,
+    nav: bool,
+    order: Option<syn::LitInt>,
+    deprecated: Option<LitStr>,
+    owner: Option<LitStr>,
+}
+
+/// A name in `meta(...)` or `locale(...)`: a string like `"og:type"` or an identifier like `description`
+fn parse_list_name(input: ParseStream<'_>) -> syn::Result<LitStr> {
+    match input.parse::<LitStr>() {
+        Ok(name) => Ok(name),
+        Err(_) => {
+            let name = input.parse::<Ident>()?;
+            Ok(LitStr::new(&name.to_string(), name.span()))
+        }
+    }
+}
+
+/// The parenthesized form of `guard(require_owner, params_only = false)`: the guard, and if it runs on soft
+/// navigations too
+fn parse_guard_options(input: ParseStream<'_>) -> syn::Result<(syn::Path, bool)> {
+    let content;
+    syn::parenthesized!(content in input);
+    let guard = content.parse()?;
+    let mut on_soft_navigation = true;
+    while !content.is_empty() {
+        content.parse::<Token![,]>()?;
+        let option = content.parse::<Ident>()?;
+        if option != "params_only" {
+            return Err(syn::Error::new_spanned(
+                &option,
+                format!("Unknown guard option '{}'", option),
+            ));
+        }
+        content.parse::<Token![=]>()?;
+        on_soft_navigation = content.parse::<syn::LitBool>()?.value;
+    }
+    Ok((guard, on_soft_navigation))
+}
+
+/// The component written right after the pattern, like `BlogPost` or `crate::pages::BlogPost`
+fn parse_component(input: ParseStream<'_>) -> Option<syn::Path> {
+    let fork = input.fork();
+    let path = fork.call(syn::Path::parse_mod_style).ok()?;
+    input.advance_to(&fork);
+    Some(path)
+}
+
+/// The component as the first argument after the pattern, like `#[route("/blog/(id)", BlogPost)]`. A path that is
+/// followed by `=` or `(` is a named argument instead, and so is the flag `hard_navigation`.
+fn parse_component_arg(input: ParseStream<'_>) -> syn::Result<Option<syn::Path>> {
+    let fork = input.fork();
+    fork.parse::<Token![,]>()?;
+    let Some(path) = parse_component(&fork) else {
+        return Ok(None);
+    };
+    if path.is_ident("hard_navigation") || !(fork.is_empty() || fork.peek(Token![,])) {
+        return Ok(None);
+    }
+    input.advance_to(&fork);
+    Ok(Some(path))
+}
+
+/// The name of a component without the module it is in
+pub fn component_ident(path: &syn::Path) -> &Ident {
+    &path.segments.last().expect("paths have a segment").ident
+}
+
+/// How to avoid repeating a pattern, for the errors on patterns that aren't literals
+const PATTERN_LITERAL_HINT: &str = "Write the pattern as a string literal. To share the start of a pattern between routes, use #[routable(shared = \"...\")], or declare the routes in a file with routes_from_file!";
+
+/// Parse a route pattern. Besides a string literal, this accepts `concat!` of literals, which is resolved here so the
+/// pattern is parsed exactly like the literal it joins to. Anything else, like a const, gets an error that explains
+/// why it can't work instead of the parser's "expected string literal".
+pub fn parse_pattern_literal(input: ParseStream<'_>) -> syn::Result<LitStr> {
+    if input.peek(LitStr) {
+        return input.parse();
+    }
+    if input.fork().parse::<syn::Macro>().is_ok() {
+        let mac = input.parse::<syn::Macro>()?;
+        if !mac.path.is_ident("concat") {
+            return Err(syn::Error::new_spanned(
+                &mac,
+                format!(
+                    "Route patterns can't be generated by {}!, because the pattern is read before macros expand. {}",
+                    mac.path.to_token_stream(),
+                    PATTERN_LITERAL_HINT
+                ),
+            ));
+        }
+        return resolve_concat(&mac);
+    }
+    if let Ok(path) = input.fork().parse::<syn::Path>() {
+        return Err(syn::Error::new_spanned(
+            &path,
+            format!(
+                "Route patterns must be string literals, because the value of {} isn't known while the routes are derived. {}",
+                path.to_token_stream(),
+                PATTERN_LITERAL_HINT
+            ),
+        ));
+    }
+    Err(input.error(r#"Expected the route pattern as a string literal, like "/blog/(id)""#))
+}
+
+/// The route of the `#[route(not_found)]` variant: a catch-all of its field, like `/(...path)`, or `/` without a
+/// field. The variant only has the options of a route that make sense without a pattern of its own.
+fn not_found_pattern(
+    not_found: &Ident,
+    attrs: &[syn::Attribute],
+    fields: &Fields,
+    is_struct: bool,
+    shared: Option<&LitStr>,
+    args: &RouteArgs,
+) -> syn::Result<LitStr> {
+    if is_struct {
+        return Err(syn::Error::new_spanned(
+            not_found,
+            "Only a variant of an enum can be not_found, since a struct has no other routes to fall back from",
+        ));
+    }
+    if shared.is_some() {
+        return Err(syn::Error::new_spanned(
+            not_found,
+            "The not_found variant gets any path no route matches, so it can't be combined with #[routable(shared = \"...\")]",
+        ));
+    }
+    if let Some((locale, _)) = args.locale.first() {
+        return Err(syn::Error::new_spanned(
+            locale,
+            "The not_found variant has no pattern, so it can't have localized patterns",
+        ));
+    }
+    let mut route_attrs = attrs.iter().filter(|attr| attr.path.is_ident("route"));
+    if let Some(alias) = route_attrs.nth(1) {
+        return Err(syn::Error::new_spanned(
+            alias,
+            "The not_found variant has no pattern, so it can't have aliases",
+        ));
+    }
+    let mut fields = fields.iter();
+    let pattern = match fields.next() {
+        Some(field) => {
+            let capture = field
+                .ident
+                .as_ref()
+                .map_or_else(|| "0".to_string(), ToString::to_string);
+            format!("/(...{capture})")
+        }
+        None => "/".to_string(),
+    };
+    if let Some(field) = fields.next() {
+        return Err(syn::Error::new_spanned(
+            field,
+            "The not_found variant can only have one field, which gets the path no route matched, like `path: Vec<String>`",
+        ));
+    }
+    Ok(LitStr::new(&pattern, not_found.span()))
+}
+
+/// Join the literals of `concat!(...)` into one pattern. Its span covers the arguments if the compiler can join
+/// spans, and is the span of the first argument otherwise.
+fn resolve_concat(mac: &syn::Macro) -> syn::Result<LitStr> {
+    let args =
+        mac.parse_body_with(syn::punctuated::Punctuated::<syn::Expr, Token![,]>::parse_terminated)?;
+    let mut pattern = String::new();
+    for arg in &args {
+        match arg {
+            syn::Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+                syn::Lit::Str(lit) => pattern.push_str(&lit.value()),
+                syn::Lit::Char(lit) => pattern.push(lit.value()),
+                syn::Lit::Int(lit) => pattern.push_str(lit.base10_digits()),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "concat! in a route pattern can only join strings, characters and integers",
+                    ))
+                }
+            },
+            arg => {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    format!(
+                        "concat! in a route pattern can only join literals, because the value of {} isn't known while the routes are derived. {}",
+                        arg.to_token_stream(),
+                        PATTERN_LITERAL_HINT
+                    ),
+                ))
+            }
+        }
+    }
+
+    let span = match (args.first(), args.last()) {
+        (Some(first), Some(last)) => {
+            let first = first.span();
+            first.join(last.span()).unwrap_or(first)
+        }
+        _ => mac.path.span(),
+    };
+    Ok(LitStr::new(&pattern, span))
+}
+
+impl Parse for RouteArgs {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        // The fallback variant has no pattern. Its route is filled in from its field once the variant is parsed
+        let not_found = match input.fork().parse::<Ident>() {
+            Ok(ident) if ident == "not_found" => Some(input.parse::<Ident>()?),
+            _ => None,
+        };
+        let route = match &not_found {
+            Some(not_found) => LitStr::new("/", not_found.span()),
+            None => parse_pattern_literal(input)?,
+        };
+        let comp_name = match input.peek(Token![,]) {
+            true => parse_component_arg(input)?,
+            false => parse_component(input),
+        };
+        let mut props_name = input.parse().ok();
+        let mut builder = true;
+        let mut action = None;
+        let mut dry_run_safe = None;
+        let mut normalize = None;
+        let mut guard = None;
+        let mut guard_on_soft_navigation = true;
+        let mut hard_navigation = false;
+        let mut status = None;
+        let mut cache = None;
+        let mut provide = None;
+        let mut title = None;
+        let mut meta = Vec::new();
+        let mut feature = None;
+        let mut shortcut = None;
+        let mut locale = Vec::new();
+        let mut icon = None;
+        let mut palette = true;
+        let mut palette_provider = None;
+        let mut nav = true;
+        let mut order = None;
+        let mut deprecated = None;
+        let mut owner = None;
+        let mut seen: Vec<Ident> = Vec::new();
+
+        // Named arguments follow the route and optional component: `, props = AboutProps`
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            let key = input.parse::<Ident>()?;
+            // The second of two arguments with the same name is underlined, rather than silently winning
+            if seen.contains(&key) {
+                return Err(syn::Error::new_spanned(
+                    &key,
+                    format!("Duplicate argument '{}'", key),
+                ));
+            }
+            seen.push(key.clone());
+            // `meta(description = "...", "og:type" = "article")` is a list of names and values instead of one value,
+            // and so is `locale(de = "/ueber-uns", "pt-BR" = "/sobre")`
+            if key == "meta" || key == "locale" {
+                let content;
+                syn::parenthesized!(content in input);
+                while !content.is_empty() {
+                    let name = parse_list_name(&content)?;
+                    content.parse::<Token![=]>()?;
+                    if key == "meta" {
+                        meta.push((name, content.parse()?));
+                    } else {
+                        locale.push((name, parse_pattern_literal(&content)?));
+                    }
+                    if !content.is_empty() {
+                        content.parse::<Token![,]>()?;
+                    }
+                }
+                continue;
+            }
+            if key == "hard_navigation" {
+                hard_navigation = true;
+                continue;
+            }
+            // `guard(require_owner, params_only = false)` is a guard with options
+            if key == "guard" && input.peek(syn::token::Paren) {
+                let (path, on_soft_navigation) = parse_guard_options(input)?;
+                guard = Some(path);
+                guard_on_soft_navigation = on_soft_navigation;
+                continue;
+            }
+            input.parse::<Token![=]>()?;
+            if key == "props" {
+                props_name = Some(input.parse()?);
+            } else if key == "builder" {
+                builder = input.parse::<syn::LitBool>()?.value;
+            } else if key == "action" {
+                action = Some(input.parse()?);
+            } else if key == "dry_run_safe" {
+                dry_run_safe = Some(input.parse()?);
+            } else if key == "normalize" {
+                normalize = Some(input.parse()?);
+            } else if key == "guard" {
+                guard = Some(input.parse()?);
+            } else if key == "cache" {
+                cache = Some(input.parse()?);
+            } else if key == "provide" {
+                provide = Some(input.parse()?);
+            } else if key == "title" {
+                title = Some(input.parse()?);
+            } else if key == "feature" {
+                feature = Some(input.parse()?);
+            } else if key == "shortcut" {
+                shortcut = Some(input.parse()?);
+            } else if key == "icon" {
+                icon = Some(input.parse()?);
+            } else if key == "palette" {
+                palette = input.parse::<syn::LitBool>()?.value;
+            } else if key == "palette_provider" {
+                palette_provider = Some(input.parse()?);
+            } else if key == "nav" {
+                nav = input.parse::<syn::LitBool>()?.value;
+            } else if key == "order" {
+                order = Some(input.parse()?);
+            } else if key == "deprecated" {
+                deprecated = match input.parse::<syn::Lit>()? {
+                    syn::Lit::Str(note) => Some(note),
+                    lit => {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            r#"The deprecation note must be a string literal, like `deprecated = "use /dashboard"`"#,
+                        ))
+                    }
+                };
+            } else if key == "owner" {
+                owner = match input.parse::<syn::Lit>()? {
+                    syn::Lit::Str(owner) => Some(owner),
+                    lit => {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            r#"The owner must be a string literal, like `owner = "payments-team"`"#,
+                        ))
+                    }
+                };
+            } else if key == "status" {
+                let lit = input.parse::<syn::LitInt>()?;
+                match lit.base10_parse::<u16>() {
+                    Ok(code @ 100..=599) => status = Some(code),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "HTTP status codes must be between 100 and 599",
+                        ))
+                    }
+                }
+            } else {
+                return Err(syn::Error::new_spanned(
+                    &key,
+                    format!("Unknown route argument '{}'", key),
+                ));
+            }
+        }
+
+        Ok(RouteArgs {
+            route,
+            not_found,
+            comp_name,
+            props_name,
+            builder,
+            action,
+            dry_run_safe,
+            normalize,
+            guard,
+            guard_on_soft_navigation,
+            hard_navigation,
+            status,
+            cache,
+            provide,
+            title,
+            meta,
+            feature,
+            shortcut,
+            locale,
+            icon,
+            palette,
+            palette_provider,
+            nav,
+            order,
+            deprecated,
+            owner,
+        })
+    }
+}
+
+/// The arguments of `#[group(guard = require_admin, meta(section = "admin"))]`, which every variant from the one it
+/// is on through the one with `#[end_group]` gets as if it had them in its `#[route(...)]`.
+///
+/// A variant's own arguments take precedence: its `guard`, `title` and `owner` replace the group's, and its `meta`
+/// values replace the group's with the same name. The other meta values of the group come first.
+#[derive(Default)]
+pub struct RouteGroup {
+    guard: Option<(syn::Path, bool)>,
+    title: Option<LitStr>,
+    meta: Vec<(LitStr, LitStr)>,
+    owner: Option<LitStr>,
+}
+
+impl Parse for RouteGroup {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut group = RouteGroup::default();
+        let mut seen: Vec<Ident> = Vec::new();
+        while !input.is_empty() {
+            let key = input.parse::<Ident>()?;
+            if seen.contains(&key) {
+                return Err(syn::Error::new_spanned(
+                    &key,
+                    format!("Duplicate argument '{}'", key),
+                ));
+            }
+            seen.push(key.clone());
+            if key == "meta" {
+                let content;
+                syn::parenthesized!(content in input);
+                while !content.is_empty() {
+                    let name = parse_list_name(&content)?;
+                    content.parse::<Token![=]>()?;
+                    group.meta.push((name, content.parse()?));
+                    if !content.is_empty() {
+                        content.parse::<Token![,]>()?;
+                    }
+                }
+            } else if key == "guard" && input.peek(syn::token::Paren) {
+                group.guard = Some(parse_guard_options(input)?);
+            } else {
+                input.parse::<Token![=]>()?;
+                if key == "guard" {
+                    group.guard = Some((input.parse()?, true));
+                } else if key == "title" {
+                    group.title = Some(input.parse()?);
+                } else if key == "owner" {
+                    group.owner = Some(input.parse()?);
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        &key,
+                        format!(
+                            "Unknown group argument '{}'. Groups can set a guard, title, meta and owner",
+                            key
+                        ),
+                    ));
+                }
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(group)
+    }
+}
+
+impl RouteGroup {
+    /// Add the arguments of the group a variant doesn't set itself
+    fn merge_into(&self, args: &mut RouteArgs) {
+        if let (None, Some((guard, on_soft_navigation))) = (&args.guard, &self.guard) {
+            args.guard = Some(guard.clone());
+            args.guard_on_soft_navigation = *on_soft_navigation;
+        }
+        if args.title.is_none() {
+            args.title = self.title.clone();
+        }
+        if args.owner.is_none() {
+            args.owner = self.owner.clone();
+        }
+        let mut meta: Vec<_> = self
+            .meta
+            .iter()
+            .filter(|(name, _)| {
+                !args
+                    .meta
+                    .iter()
+                    .any(|(other, _)| other.value() == name.value())
+            })
+            .cloned()
+            .collect();
+        meta.append(&mut args.meta);
+        args.meta = meta;
+    }
+}
+
+#[derive(Debug)]
+pub struct Route {
+    pub file_based: bool,
+    pub route_name: Ident,
+    /// The component the route renders, named after the pattern like `BlogPost` or `crate::pages::BlogPost`. Routes
+    /// that don't name one render the component of their page file, which is named after the variant.
+    pub comp_name: syn::Path,
+    /// The props struct of the component. Routes without any dynamic segments render
+    /// their component with unit props unless a props struct is named explicitly.
+    pub props_name: Option<Ident>,
+    pub route: LitStr,
+    pub route_segments: Vec<RouteSegment>,
+    /// If this route was derived from a struct instead of an enum variant
+    pub is_struct: bool,
+    /// If the fields of the variant are unnamed, like `User(u32)`. Its segments refer to them by position, like
+    /// `(0)`, and bind them as `field_0`, which is also the name of the prop the component gets.
+    pub tuple_fields: bool,
+    /// The `#[route_segment(...)]` options of each named field
+    pub segment_options: Vec<(Ident, SegmentOptions)>,
+    /// If a builder should be generated for this route. Disabled with `builder = false`
+    pub builder: bool,
+    /// The function the router runs instead of rendering a component, set with `action = path`
+    pub action: Option<syn::Path>,
+    /// If the action only navigates, so [`Router::resolve`] can run it without navigating. Set with
+    /// `dry_run_safe = true`
+    pub dry_run_safe: bool,
+    /// A function the parser passes the dynamic segments to by mutable reference after a path parsed, to
+    /// canonicalize their values like lowercasing a slug. Set with `normalize = normalize_post`
+    pub normalize: Option<syn::Path>,
+    /// A function that checks navigations to this route, after the guards of the router. Set with
+    /// `guard = require_admin`
+    pub guard: Option<syn::Path>,
+    /// If the guard runs on soft navigations, which only change the dynamic segments of the route. Disabled with
+    /// `guard(require_owner, params_only = false)`
+    pub guard_on_soft_navigation: bool,
+    /// If navigating between two paths of this route remounts its component instead of updating its props, set
+    /// with `hard_navigation`
+    pub hard_navigation: bool,
+    /// The HTTP status code a server should respond with for this route, set with `status = 410`
+    pub status: Option<u16>,
+    /// The `Cache-Control` header a server should respond with for this route, set with
+    /// `cache = "public, max-age=3600"`. The value is not validated.
+    pub cache: Option<LitStr>,
+    /// A function from the route's dynamic segments to a context value provided while the route renders, set
+    /// with `provide = ProjectContext::from_route`. The value is replaced every time the route renders; like any
+    /// context, components that don't re-render keep the value they read last.
+    pub provide: Option<syn::Path>,
+    /// The title of the page, set with `title = "Results for {query}"`. `{name}` is replaced with the value of
+    /// the segment `name`; `{{` and `}}` are literal braces.
+    pub title: Option<Template>,
+    /// `<meta>` tags of the page, set with `meta(description = "...")`. Values are templates like the title.
+    pub meta: Vec<(LitStr, Template)>,
+    /// The cargo feature the route is compiled in with, set with `feature = "pro"`. Without the feature the
+    /// variant still exists, but it is never parsed, rendered or built from a pattern.
+    pub feature: Option<LitStr>,
+    /// The keys that navigate to the route, separated by spaces, set with `shortcut = "g i"`. Only routes without
+    /// dynamic segments can have one, since a shortcut has no values to fill them with.
+    pub shortcut: Option<LitStr>,
+    /// The locale this pattern is for, on routes with `locale(...)` patterns. The route's own pattern is for the
+    /// `#[routable(default_locale = "...")]`.
+    pub locale: Option<LitStr>,
+    /// A route for each pattern of `locale(de = "/ueber-uns")`. They parse into this variant with their locale, and
+    /// the variant displays with the pattern of the value in its `locale` field.
+    pub localized: Vec<Route>,
+    /// A route for each `#[route("...")]` after the first, like an old pattern that should keep working. They parse
+    /// into this variant, and the variant always displays with its own pattern.
+    pub aliases: Vec<Route>,
+    /// If this is the route of an alias
+    pub is_alias: bool,
+    /// How a slash at the end of the path is parsed and displayed, from `#[routable(trailing_slash = "...")]`
+    pub trailing_slash: TrailingSlash,
+    /// If static segments are compared ignoring the case of ASCII letters, from `#[routable(case_insensitive)]`
+    pub case_insensitive: bool,
+    /// The name of the icon a command palette shows next to the route, set with `icon = "inbox"`
+    pub icon: Option<LitStr>,
+    /// If the route is listed in the command palette. Routes without dynamic segments are unless they have
+    /// `palette = false`; other routes are listed with the values from their `palette_provider`.
+    pub palette: bool,
+    /// A function that returns the routes with dynamic segments a command palette lists, like recent projects,
+    /// set with `palette_provider = recent_projects`
+    pub palette_provider: Option<syn::Path>,
+    /// If a navigation menu lists the route. Routes without dynamic segments are unless they have `nav = false`.
+    pub nav: bool,
+    /// Where a navigation menu lists the route, set with `order = 2`. Lower orders come first, and routes without
+    /// one have order 0.
+    pub order: Option<i32>,
+    /// Why the route is deprecated and what replaces it, set with `deprecated = "use /dashboard"`. The route still
+    /// works, but the router warns when it matches and site maps leave it out.
+    pub deprecated: Option<LitStr>,
+    /// The team that owns the route, set with `owner = "payments-team"`. Failures of the route name it, and the
+    /// route table lists it.
+    pub owner: Option<LitStr>,
+    /// The `not_found` of `#[route(not_found)]` on the variant `FromStr` returns for locations no route matches. Its
+    /// route is a catch-all of its field, which gets the path, and is never tried by the parser.
+    pub not_found: Option<Ident>,
+}
+
+impl Route {
+    /// Parse a variant. Its route starts with the segments `shared` by every variant of the enum, if there are any.
+    pub fn parse(input: syn::Variant, shared: Option<&LitStr>) -> syn::Result<Self> {
+        Self::parse_in_group(input, shared, None)
+    }
+
+    /// Parse a variant that is in a [`RouteGroup`], with the arguments of the group it doesn't set itself.
+    pub fn parse_in_group(
+        input: syn::Variant,
+        shared: Option<&LitStr>,
+        group: Option<&RouteGroup>,
+    ) -> syn::Result<Self> {
+        Self::parse_item(
+            &input.ident,
+            &input.attrs,
+            &input.fields,
+            &input,
+            false,
+            shared,
+            group,
+        )
+    }
+
+    /// Parse a struct with a single `#[route(...)]` attribute. The struct's fields play the role of the variant's fields.
+    pub fn parse_struct(input: &syn::DeriveInput, fields: &Fields) -> syn::Result<Self> {
+        Self::parse_item(&input.ident, &input.attrs, fields, input, true, None, None)
+    }
+
+    fn parse_item(
+        route_name: &Ident,
+        attrs: &[syn::Attribute],
+        fields: &Fields,
+        item: &impl ToTokens,
+        is_struct: bool,
+        shared: Option<&LitStr>,
+        group: Option<&RouteGroup>,
+    ) -> syn::Result<Self> {
+        let mut route_attrs = attrs.iter().filter(|attr| attr.path.is_ident("route"));
+        let route_attr = route_attrs.next().ok_or_else(|| {
+            syn::Error::new_spanned(
+                item,
+                "Routable variants must have a #[route(...)] attribute",
+            )
+        })?;
+
+        let route_name = route_name.clone();
+        let mut args = route_attr.parse_args::<RouteArgs>()?;
+        if let Some(group) = group {
+            group.merge_into(&mut args);
+        }
+        if let Some(not_found) = &args.not_found {
+            args.route = not_found_pattern(not_found, attrs, fields, is_struct, shared, &args)?;
+        }
+        // The shared segments become part of the route, so they are displayed and parsed like the variant's own
+        let with_shared = |route: &LitStr| match shared {
+            Some(shared) if route.value() == "/" => LitStr::new(&shared.value(), route.span()),
+            Some(shared) => LitStr::new(
+                &format!("{}{}", shared.value(), route.value()),
+                route.span(),
+            ),
+            None => route.clone(),
+        };
+        let route = with_shared(&args.route);
+        if let (Some(comp_name), Some(_)) = (&args.comp_name, &args.action) {
+            return Err(syn::Error::new_spanned(
+                comp_name,
+                "Routes with an action never render, so they can't have a component",
+            ));
+        }
+        if let (Some(provide), Some(_)) = (&args.provide, &args.action) {
+            return Err(syn::Error::new_spanned(
+                provide,
+                "Routes with an action never render, so they can't provide a context",
+            ));
+        }
+        if let (Some(dry_run_safe), None) = (&args.dry_run_safe, &args.action) {
+            return Err(syn::Error::new_spanned(
+                dry_run_safe,
+                "Only routes with an action can be dry_run_safe, since there is nothing else to run",
+            ));
+        }
+        let file_based = args.comp_name.is_none() && args.action.is_none();
+        let comp_name = args.comp_name.unwrap_or_else(|| route_name.clone().into());
+
+        let route_segments = parse_route_segments(&route_name, fields, &args.route, &route)?;
+        if let Some(shortcut) = &args.shortcut {
+            if shortcut.value().split_whitespace().next().is_none() {
+                return Err(syn::Error::new_spanned(
+                    shortcut,
+                    "A shortcut needs at least one key, like \"g i\"",
+                ));
+            }
+            if route_segments
+                .iter()
+                .any(|segment| !matches!(segment, RouteSegment::Static(_)))
+            {
+                return Err(syn::Error::new_spanned(
+                    shortcut,
+                    format!(
+                        "Shortcuts navigate to a fixed path, so the route '{}' can't have one because of its dynamic segments",
+                        route.value()
+                    ),
+                ));
+            }
+        }
+        if let Some(normalize) = &args.normalize {
+            if route_segments
+                .iter()
+                .all(|segment| segment.name().is_none())
+            {
+                return Err(syn::Error::new_spanned(
+                    normalize,
+                    format!(
+                        "The route '{}' has no dynamic segments, so there is nothing to normalize",
+                        route.value()
+                    ),
+                ));
+            }
+        }
+
+        let mut localized = Vec::new();
+        for (i, (locale, written)) in args.locale.iter().enumerate() {
+            if args.locale[..i]
+                .iter()
+                .any(|(other, _)| other.value() == locale.value())
+            {
+                return Err(syn::Error::new_spanned(
+                    locale,
+                    format!(
+                        "There is already a pattern for the locale '{}'",
+                        locale.value()
+                    ),
+                ));
+            }
+            let pattern = with_shared(written);
+            let segments = parse_route_segments(&route_name, fields, written, &pattern)?;
+            check_localized_segments(&route, &route_segments, &pattern, &segments)?;
+            localized.push((locale.clone(), pattern, segments));
+        }
+        if let Some((locale, _)) = args.locale.first() {
+            let has_locale_field = fields
+                .iter()
+                .any(|field| field.ident.as_ref().is_some_and(|ident| ident == "locale"));
+            if !has_locale_field {
+                return Err(syn::Error::new_spanned(
+                    locale,
+                    format!(
+                        "Routes with localized patterns need a `locale` field, which picks the pattern '{}' is displayed with",
+                        route_name
+                    ),
+                ));
+            }
+            if route_segments
+                .iter()
+                .any(|segment| segment.name().is_some_and(|ident| ident == "locale"))
+            {
+                return Err(syn::Error::new_spanned(
+                    &route,
+                    "The `locale` field of a route with localized patterns holds the locale of the pattern, so it can't be a segment",
+                ));
+            }
+        }
+
+        // Every `#[route(...)]` after the first is an alias, which parses into the variant but is never displayed
+        let mut aliases: Vec<(LitStr, Vec<RouteSegment>)> = Vec::new();
+        for attr in route_attrs {
+            let written = attr.parse_args_with(parse_alias)?;
+            let pattern = with_shared(&written);
+            if pattern.value() == route.value()
+                || aliases
+                    .iter()
+                    .any(|(other, _)| other.value() == pattern.value())
+            {
+                return Err(syn::Error::new_spanned(
+                    &pattern,
+                    format!("The route already has the pattern '{}'", pattern.value()),
+                ));
+            }
+            let segments = parse_route_segments(&route_name, fields, &written, &pattern)?;
+            check_alias_segments(&route, &route_segments, &pattern, &segments)?;
+            aliases.push((pattern, segments));
+        }
+
+        if let Some(provider) = &args.palette_provider {
+            if !args.palette {
+                return Err(syn::Error::new_spanned(
+                    provider,
+                    "Routes with `palette = false` aren't listed in the palette, so they can't have a palette_provider",
+                ));
+            }
+            if route_segments
+                .iter()
+                .all(|segment| matches!(segment, RouteSegment::Static(_)))
+            {
+                return Err(syn::Error::new_spanned(
+                    provider,
+                    format!(
+                        "The route '{}' has no dynamic segments, so it is listed in the palette without a palette_provider",
+                        route.value()
+                    ),
+                ));
+            }
+        }
+
+        // Navigation menus only list routes without dynamic segments, since they have no values to fill them with
+        let mut order = None;
+        if let Some(lit) = &args.order {
+            if !args.nav
+                || !route_segments
+                    .iter()
+                    .all(|segment| matches!(segment, RouteSegment::Static(_)))
+            {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    format!(
+                        "The route '{}' isn't listed in navigation menus, so it can't have an order",
+                        route.value()
+                    ),
+                ));
+            }
+            order = Some(lit.base10_parse()?);
+        }
+
+        // A field without a segment can't be parsed from a path, so every one of them is reported together
+        let mut unused = None;
+        for (i, field) in fields.iter().enumerate() {
+            let ident = field
+                .ident
+                .clone()
+                .unwrap_or_else(|| format_ident!("field_{}", i));
+            let is_locale = !args.locale.is_empty() && ident == "locale";
+            if is_locale
+                || route_segments
+                    .iter()
+                    .any(|segment| segment.name().is_some_and(|name| name == ident))
+            {
+                continue;
+            }
+            let capture = field
+                .ident
+                .as_ref()
+                .map_or_else(|| i.to_string(), ToString::to_string);
+            push_error(
+                &mut unused,
+                syn::Error::new(
+                    field.ident.as_ref().map_or_else(|| field.ty.span(), Ident::span),
+                    format!(
+                        "The field '{}' of the variant '{}' isn't a segment of the route '{}', so it can't be parsed from a path. Add '({})' to the route or remove the field",
+                        capture,
+                        route_name,
+                        route.value(),
+                        capture
+                    ),
+                ),
+            );
+        }
+        if let Some(unused) = unused {
+            return Err(unused);
+        }
+
+        let mut segment_options = Vec::new();
+        for (i, field) in fields.iter().enumerate() {
+            // The fields of a tuple variant are bound like their positional segments
+            let ident = field
+                .ident
+                .clone()
+                .unwrap_or_else(|| format_ident!("field_{}", i));
+            let options = SegmentOptions::parse(&field.attrs)?;
+            let is_catch_all = route_segments
+                .iter()
+                .any(|segment| matches!(segment, RouteSegment::CatchAll(catch_all, _) if *catch_all == ident));
+            if options.include_query && !is_catch_all {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "include_query is only supported on catch-all segments",
+                ));
+            }
+            if options.child && !is_catch_all {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "child is only supported on catch-all segments, like '/(...child)'",
+                ));
+            }
+            // Catch-alls are written by their type's `ToRouteSegments`, which does its own encoding
+            if options.encode != SegmentEncoding::Pchar && is_catch_all {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "encode is only supported on dynamic segments. Catch-alls are encoded by their type's ToRouteSegments",
+                ));
+            }
+            segment_options.push((ident, options));
+        }
+
+        let title = args
+            .title
+            .map(|title| Template::parse(title, &route_segments))
+            .transpose()?;
+        let meta = args
+            .meta
+            .into_iter()
+            .map(|(name, value)| Ok((name, Template::parse(value, &route_segments)?)))
+            .collect::<syn::Result<_>>()?;
+
+        let has_props = route_segments.iter().any(|s| s.name().is_some());
+        let props_name = args
+            .props_name
+            .or_else(|| has_props.then(|| format_ident!("{}Props", component_ident(&comp_name))));
+
+        let mut route = Self {
+            comp_name,
+            props_name,
+            route_name,
+            route_segments,
+            route,
+            file_based,
+            is_struct,
+            tuple_fields: matches!(fields, Fields::Unnamed(_)),
+            segment_options,
+            builder: args.builder,
+            action: args.action,
+            dry_run_safe: args.dry_run_safe.is_some_and(|lit| lit.value),
+            normalize: args.normalize,
+            guard: args.guard,
+            guard_on_soft_navigation: args.guard_on_soft_navigation,
+            hard_navigation: args.hard_navigation,
+            // Servers respond to the fallback with a 404 unless it has a status of its own
+            status: args.status.or(args.not_found.is_some().then_some(404)),
+            cache: args.cache,
+            provide: args.provide,
+            title,
+            meta,
+            feature: args.feature,
+            shortcut: args.shortcut,
+            locale: None,
+            localized: Vec::new(),
+            aliases: Vec::new(),
+            is_alias: false,
+            trailing_slash: TrailingSlash::Accept,
+            case_insensitive: false,
+            icon: args.icon,
+            palette: args.palette,
+            palette_provider: args.palette_provider,
+            nav: args.nav,
+            order,
+            deprecated: args.deprecated,
+            owner: args.owner,
+            not_found: args.not_found,
+        };
+        route.localized = localized
+            .into_iter()
+            .map(|(locale, pattern, segments)| route.alternate(Some(locale), pattern, segments))
+            .collect();
+        route.aliases = aliases
+            .into_iter()
+            .map(|(pattern, segments)| Self {
+                is_alias: true,
+                ..route.alternate(None, pattern, segments)
+            })
+            .collect();
+        Ok(route)
+    }
+
+    /// The route of another pattern of this route: one of its `locale(...)` patterns, or an alias. It shares every
+    /// option that doesn't depend on the pattern, and everything that is only generated once per variant is left to
+    /// this route.
+    fn alternate(
+        &self,
+        locale: Option<LitStr>,
+        route: LitStr,
+        route_segments: Vec<RouteSegment>,
+    ) -> Self {
+        Self {
+            file_based: false,
+            route_name: self.route_name.clone(),
+            comp_name: self.comp_name.clone(),
+            props_name: self.props_name.clone(),
+            route,
+            route_segments,
+            is_struct: self.is_struct,
+            tuple_fields: self.tuple_fields,
+            segment_options: self.segment_options.clone(),
+            builder: false,
+            action: self.action.clone(),
+            dry_run_safe: self.dry_run_safe,
+            normalize: self.normalize.clone(),
+            guard: self.guard.clone(),
+            guard_on_soft_navigation: self.guard_on_soft_navigation,
+            hard_navigation: self.hard_navigation,
+            status: self.status,
+            cache: self.cache.clone(),
+            provide: None,
+            title: None,
+            meta: Vec::new(),
+            feature: self.feature.clone(),
+            shortcut: None,
+            locale,
+            localized: Vec::new(),
+            aliases: Vec::new(),
+            is_alias: false,
+            trailing_slash: self.trailing_slash,
+            case_insensitive: self.case_insensitive,
+            icon: None,
+            palette: false,
+            palette_provider: None,
+            nav: false,
+            order: None,
+            deprecated: self.deprecated.clone(),
+            owner: self.owner.clone(),
+            not_found: None,
+        }
+    }
+
+    /// Give the route's own pattern the `default` locale if it has localized patterns.
+    pub fn set_default_locale(&mut self, default: &str) -> syn::Result<()> {
+        if self.localized.is_empty() {
+            return Ok(());
+        }
+        if let Some(locale) = self
+            .localized
+            .iter()
+            .filter_map(|route| route.locale.as_ref())
+            .find(|locale| locale.value() == default)
+        {
+            return Err(syn::Error::new_spanned(
+                locale,
+                format!(
+                    "'{}' is the default locale, whose pattern is the route's own. Set another one with #[routable(default_locale = \"...\")]",
+                    default
+                ),
+            ));
+        }
+        self.locale = Some(LitStr::new(default, self.route.span()));
+        // Aliases parse with the default locale, like the pattern they stand in for
+        for alias in &mut self.aliases {
+            alias.locale = self.locale.clone();
+        }
+        Ok(())
+    }
+
+    /// Parse and display the route and its other patterns with the `trailing_slash` and `case_insensitive` options
+    /// of the type.
+    pub fn set_path_options(&mut self, options: &RoutableOptions) -> syn::Result<()> {
+        if options.trailing_slash == TrailingSlash::Require && self.includes_query() {
+            return Err(syn::Error::new_spanned(
+                &self.route,
+                "A catch-all with #[route_segment(include_query)] writes the query where trailing_slash = \"require\" writes the slash",
+            ));
+        }
+        self.trailing_slash = options.trailing_slash;
+        self.case_insensitive = options.case_insensitive;
+        for route in self.localized.iter_mut().chain(&mut self.aliases) {
+            route.trailing_slash = options.trailing_slash;
+            route.case_insensitive = options.case_insensitive;
+        }
+        Ok(())
+    }
+
+    /// `/` if the route displays with a slash at the end of its path. The root is `/` already
+    pub fn trailing_slash(&self) -> &'static str {
+        let is_root =
+            matches!(self.path_segments(), [RouteSegment::Static(segment)] if segment.is_empty());
+        match self.trailing_slash {
+            TrailingSlash::Require if !is_root => "/",
+            _ => "",
+        }
+    }
+
+    /// An expression that compares a raw segment with the text of a static segment, ignoring the case of ASCII
+    /// letters if the route is `case_insensitive`
+    pub fn static_eq(&self, left: impl ToTokens, right: impl ToTokens) -> TokenStream2 {
+        match self.case_insensitive {
+            true => quote! { #left.eq_ignore_ascii_case(#right) },
+            false => quote! { #left == #right },
+        }
+    }
+
+    /// The negation of [`Route::static_eq`]
+    pub fn static_ne(&self, left: impl ToTokens, right: impl ToTokens) -> TokenStream2 {
+        match self.case_insensitive {
+            true => quote! { !#left.eq_ignore_ascii_case(#right) },
+            false => quote! { #left != #right },
+        }
+    }
+
+    /// This route followed by the routes of its localized patterns and its aliases. The not_found route has no
+    /// patterns, since the parser never tries it.
+    pub fn with_patterns(&self) -> impl Iterator<Item = &Route> {
+        std::iter::once(self)
+            .filter(|route| route.not_found.is_none())
+            .chain(&self.localized)
+            .chain(&self.aliases)
+    }
+
+    /// A route for the segments of a `route_segments!` pattern: a struct route that never renders, without any
+    /// options. Its segments use the defaults of the derive, so dynamic segments can't be empty.
+    pub fn from_segments(
+        route_name: Ident,
+        route: LitStr,
+        route_segments: Vec<RouteSegment>,
+    ) -> Self {
+        Self {
+            file_based: false,
+            comp_name: route_name.clone().into(),
+            props_name: None,
+            route_name,
+            route,
+            route_segments,
+            is_struct: true,
+            tuple_fields: false,
+            segment_options: Vec::new(),
+            builder: false,
+            action: None,
+            dry_run_safe: false,
+            normalize: None,
+            guard: None,
+            guard_on_soft_navigation: true,
+            hard_navigation: false,
+            status: None,
+            cache: None,
+            provide: None,
+            title: None,
+            meta: Vec::new(),
+            feature: None,
+            shortcut: None,
+            locale: None,
+            localized: Vec::new(),
+            aliases: Vec::new(),
+            is_alias: false,
+            trailing_slash: TrailingSlash::Accept,
+            case_insensitive: false,
+            icon: None,
+            palette: false,
+            palette_provider: None,
+            nav: false,
+            order: None,
+            deprecated: None,
+            owner: None,
+            not_found: None,
+        }
+    }
+
+    pub fn segment_options(&self, ident: &Ident) -> Option<&SegmentOptions> {
+        self.segment_options
+            .iter()
+            .find_map(|(field, options)| (field == ident).then_some(options))
+    }
+
+    /// If the segment bound to `ident` accepts an empty value
+    pub fn allows_empty(&self, ident: &Ident) -> bool {
+        self.segment_options(ident)
+            .is_some_and(|options| options.allow_empty)
+    }
+
+    /// If the catch-all of this route takes the query as well, with `#[route_segment(include_query)]`
+    pub fn includes_query(&self) -> bool {
+        self.segment_options
+            .iter()
+            .any(|(_, options)| options.include_query)
+    }
+
+    /// The catch-all of this route with `#[route_segment(child)]`, which holds the routes of another type
+    pub fn child(&self) -> Option<(&Ident, &Type)> {
+        self.route_segments
+            .iter()
+            .find_map(|segment| match segment {
+                RouteSegment::CatchAll(ident, ty)
+                    if self
+                        .segment_options(ident)
+                        .is_some_and(|options| options.child) =>
+                {
+                    Some((ident, ty))
+                }
+                _ => None,
+            })
+    }
+
+    /// The `#[cfg(...)]` attribute of a route with `feature = "..."`
+    pub fn cfg_attr(&self) -> Option<TokenStream2> {
+        let feature = self.feature.as_ref()?;
+        Some(quote! { #[cfg(feature = #feature)] })
+    }
+
+    /// Wrap statements that only exist for this route in a block that is compiled out with its feature
+    pub fn gate(&self, tokens: TokenStream2) -> TokenStream2 {
+        match self.cfg_attr() {
+            Some(cfg) => quote! { #cfg { #tokens } },
+            None => tokens,
+        }
+    }
+
+    /// The `#[route_segment(encode = "...")]` of a dynamic segment
+    pub fn encoding(&self, segment: &RouteSegment) -> SegmentEncoding {
+        segment
+            .name()
+            .and_then(|ident| self.segment_options(&ident))
+            .map_or(SegmentEncoding::Pchar, |options| options.encode)
+    }
+
+    /// If a segment of this route accepts an empty value: an empty dynamic segment with `allow_empty`, or zero
+    /// segments for a catch-all without `non_empty`. Static segments never do.
+    pub fn allows_segment_empty(&self, segment: &RouteSegment) -> bool {
+        match segment {
+            RouteSegment::Static(_) => false,
+            RouteSegment::Dynamic(ident, ..) | RouteSegment::Optional(ident, _) => {
+                self.allows_empty(ident)
+            }
+            RouteSegment::CatchAll(ident, _) => !self
+                .segment_options(ident)
+                .is_some_and(|options| options.non_empty),
+            // An empty fragment is the same as none
+            RouteSegment::Matrix(..) | RouteSegment::Query(..) | RouteSegment::Fragment(..) => true,
+        }
+    }
+
+    /// The segments of the path, which are every segment but the query and the fragment after it
+    pub fn path_segments(&self) -> &[RouteSegment] {
+        let trailing = self
+            .route_segments
+            .iter()
+            .rev()
+            .take_while(|seg| matches!(seg, RouteSegment::Query(..) | RouteSegment::Fragment(..)))
+            .count();
+        &self.route_segments[..self.route_segments.len() - trailing]
+    }
+
+    /// The number of segments of the path, which is every segment but the query and the fragment
+    pub fn path_segment_count(&self) -> usize {
+        self.path_segments().len()
+    }
+
+    /// If the route parses the query after its path, like `/search?(query)`
+    pub fn has_query(&self) -> bool {
+        self.route_segments
+            .iter()
+            .any(|seg| matches!(seg, RouteSegment::Query(..)))
+    }
+
+    /// If the route parses the fragment at the end of its location, like `/docs/(page)#(section)`
+    pub fn has_fragment(&self) -> bool {
+        matches!(self.route_segments.last(), Some(RouteSegment::Fragment(..)))
+    }
+
+    /// If the segment at `idx` is followed by matrix params, which are split off its path segment before it is
+    /// matched
+    pub fn has_matrix(&self, idx: usize) -> bool {
+        matches!(
+            self.route_segments.get(idx + 1),
+            Some(RouteSegment::Matrix(..))
+        )
+    }
+
+    /// Build a path that should be parsed as this route.
+    ///
+    /// Dynamic and catch-all segments use the `#[route_segment(example = "...")]` value of their field, or `1` if it
+    /// isn't set. `1` parses as every number type and as every string type, so most routes don't need an override.
+    pub fn example_path(&self) -> String {
+        let mut path = String::new();
+        for (i, segment) in self.route_segments.iter().enumerate() {
+            // The trailing slash ends the path, before its query and fragment
+            if i == self.path_segment_count() {
+                path.push_str(self.trailing_slash());
+            }
+            // Matrix params are only written with an example, since no params parse for most types
+            if let RouteSegment::Matrix(ident, _) = segment {
+                if let Some(example) = self.segment_options(ident).and_then(|o| o.example.as_ref())
+                {
+                    path.push(';');
+                    path.push_str(&example.value());
+                }
+                continue;
+            }
+            // Without an example the path has no query, which parses as an empty one
+            if let RouteSegment::Query(ident, _) = segment {
+                if let Some(example) = self.segment_options(ident).and_then(|o| o.example.as_ref())
+                {
+                    path.push('?');
+                    path.push_str(&example.value());
+                }
+                continue;
+            }
+            // A fragment is written like a dynamic segment, since most types don't default without one
+            if let RouteSegment::Fragment(ident, _) = segment {
+                path.push('#');
+                match self.segment_options(ident).and_then(|o| o.example.as_ref()) {
+                    Some(example) => path.push_str(&example.value()),
+                    None => path.push('1'),
+                }
+                continue;
+            }
+            path.push('/');
+            let affix = match segment {
+                RouteSegment::Dynamic(_, _, affix) => affix.as_ref(),
+                _ => None,
+            };
+            match segment {
+                RouteSegment::Static(segment) => path.push_str(segment),
+                RouteSegment::Dynamic(ident, ..)
+                | RouteSegment::CatchAll(ident, _)
+                | RouteSegment::Optional(ident, _) => {
+                    path.push_str(affix.map_or("", |affix| &affix.prefix));
+                    match self.segment_options(ident).and_then(|o| o.example.as_ref()) {
+                        Some(example) => path.push_str(&example.value()),
+                        None => path.push('1'),
+                    }
+                    path.push_str(affix.map_or("", |affix| &affix.suffix));
+                }
+                RouteSegment::Matrix(..) | RouteSegment::Query(..) | RouteSegment::Fragment(..) => {
+                }
+            }
+        }
+        if self.path_segment_count() == self.route_segments.len() {
+            path.push_str(self.trailing_slash());
+        }
+        path
+    }
+
+    /// The path of the route if it has no dynamic or catch-all segments, which is the same for every value of it. The
+    /// not_found route has none, since it stands for any path no route matches
+    pub fn static_path(&self) -> Option<String> {
+        if self.not_found.is_some() {
+            return None;
+        }
+        let mut path = String::new();
+        for segment in &self.route_segments {
+            match segment {
+                RouteSegment::Static(segment) => {
+                    path.push('/');
+                    path.push_str(segment);
+                }
+                RouteSegment::Dynamic(..)
+                | RouteSegment::CatchAll(..)
+                | RouteSegment::Optional(..)
+                | RouteSegment::Matrix(..)
+                | RouteSegment::Query(..)
+                | RouteSegment::Fragment(..) => return None,
+            }
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(self.trailing_slash());
+        Some(path)
+    }
+
+    /// The pattern with every dynamic segment replaced by `*` and a catch-all by `**`, like `/blog/*`. Matrix params
+    /// are a `*` at the end of their segment. Optional segments end the glob with `**`, which also matches the path
+    /// without them.
+    pub fn glob_pattern(&self) -> String {
+        let mut glob = String::new();
+        for segment in &self.route_segments {
+            match segment {
+                RouteSegment::Optional(..) => {
+                    glob.push_str("/**");
+                    break;
+                }
+                RouteSegment::Matrix(..) => {
+                    glob.push('*');
+                    continue;
+                }
+                // Globs only match the path
+                RouteSegment::Query(..) | RouteSegment::Fragment(..) => continue,
+                _ => glob.push('/'),
+            }
+            match segment {
+                RouteSegment::Static(segment) => glob.push_str(segment),
+                RouteSegment::Dynamic(_, _, affix) => {
+                    glob.push_str(affix.as_ref().map_or("", |affix| &affix.prefix));
+                    glob.push('*');
+                    glob.push_str(affix.as_ref().map_or("", |affix| &affix.suffix));
+                }
+                RouteSegment::CatchAll(..) => glob.push_str("**"),
+                RouteSegment::Optional(..)
+                | RouteSegment::Matrix(..)
+                | RouteSegment::Query(..)
+                | RouteSegment::Fragment(..) => {}
+            }
+        }
+        if glob.is_empty() {
+            glob.push('/');
+        }
+        glob
+    }
+
+    /// If a service worker can cache this route ahead of time: a page without dynamic segments whose cache hint
+    /// allows shared caching
+    pub fn is_precacheable(&self) -> bool {
+        let is_static = self
+            .route_segments
+            .iter()
+            .all(|seg| matches!(seg, RouteSegment::Static(_)));
+        let cacheable = self.cache.as_ref().is_some_and(|cache| {
+            let cache = cache.value().to_ascii_lowercase();
+            !["no-store", "no-cache", "private"]
+                .iter()
+                .any(|directive| cache.contains(directive))
+        });
+        is_static && cacheable && self.action.is_none()
+    }
+
+    /// The path used to construct or match this route, relative to the routable type: `ty::Variant` for enums and `ty` for structs
+    pub fn variant_path(&self, ty: impl ToTokens) -> TokenStream2 {
+        let name = &self.route_name;
+        if self.is_struct {
+            quote! { #ty }
+        } else {
+            quote! { #ty::#name }
+        }
+    }
+
+    /// The field a segment binds, as it is written in a struct expression or pattern: `0` for `field_0` if the
+    /// fields are unnamed, and the name otherwise
+    pub fn member(&self, ident: &Ident) -> TokenStream2 {
+        match ident.to_string().strip_prefix("field_") {
+            Some(index) if self.tuple_fields => {
+                let index = syn::Index::from(index.parse::<usize>().unwrap_or_default());
+                quote! { #index }
+            }
+            _ => quote! { #ident },
+        }
+    }
+
+    /// A field in a pattern or constructor of the variant that binds the value of the segment `ident` to its name,
+    /// like `id` or `0: field_0`
+    pub fn field(&self, ident: &Ident) -> TokenStream2 {
+        match self.tuple_fields {
+            true => {
+                let member = self.member(ident);
+                quote! { #member: #ident }
+            }
+            false => quote! { #ident },
+        }
+    }
+
+    /// The fields of every dynamic segment, like [`Route::field`]
+    pub fn segment_fields(&self) -> Vec<TokenStream2> {
+        self.route_segments
+            .iter()
+            .filter_map(|segment| segment.name())
+            .map(|ident| self.field(&ident))
+            .collect()
+    }
+
+    /// `locale,` in a pattern of a route with localized patterns, which binds the field `match_locale` reads
+    fn locale_binding(&self) -> Option<TokenStream2> {
+        (!self.localized.is_empty()).then(|| quote! { locale, })
+    }
+
+    /// `..` in a pattern that binds every dynamic segment, since routes with localized patterns also have a locale
+    fn locale_rest(&self) -> Option<TokenStream2> {
+        (!self.localized.is_empty()).then(|| quote! { .. })
+    }
+
+    /// The `locale` field of a variant built from this pattern
+    pub fn locale_init(&self) -> Option<TokenStream2> {
+        let locale = self.locale.as_ref()?;
+        Some(quote! { locale: ::std::convert::From::from(#locale), })
+    }
+
+    /// Pick the `tokens` of the pattern for the bound `locale` field. Locales without a pattern of their own use the
+    /// route's pattern.
+    fn match_locale(&self, tokens: impl Fn(&Route) -> TokenStream2) -> TokenStream2 {
+        if self.localized.is_empty() {
+            return tokens(self);
+        }
+        let locales = self.localized.iter().map(|route| &route.locale);
+        let localized = self.localized.iter().map(&tokens);
+        let default = tokens(self);
+
+        quote! {
+            match ::std::convert::AsRef::<str>::as_ref(locale) {
+                #(#locales => { #localized })*
+                _ => { #default }
+            }
+        }
+    }
+
+    pub fn display_match(&self) -> TokenStream2 {
+        let pattern = self.variant_path(quote! { Self });
+        let dynamic_segments = self.segment_fields();
+        let locale = self.locale_binding();
+        let write_segments = self.match_locale(Route::write_segments);
+
+        quote! {
+            #pattern { #(#dynamic_segments,)* #locale } => {
+                #write_segments
+            }
+        }
+    }
+
+    /// Statements that write the path of the route to `f`, with a reference to the value of every dynamic segment
+    /// bound to its name
+    pub fn write_segments(&self) -> TokenStream2 {
+        let mut write_segments: Vec<_> = self
+            .route_segments
+            .iter()
+            .enumerate()
+            .map(|(i, s)| match s {
+                // The value starts with the query if the path ended before the catch-all
+                RouteSegment::CatchAll(ident, _) if self.includes_query() => quote! {
+                    let __router_value = ToRouteSegments::to_route_segments_string(#ident);
+                    if !__router_value.is_empty() && !__router_value.starts_with('?') {
+                        write!(f, "/")?;
+                    }
+                    write!(f, "{}", __router_value)?;
+                },
+                // The value of a segment with matrix params can't contain an unencoded `;`, which would start them
+                s if self.has_matrix(i) && self.encoding(s) == SegmentEncoding::Pchar => {
+                    s.write_segment(self.allows_segment_empty(s), SegmentEncoding::Unreserved)
+                }
+                s => s.write_segment(self.allows_segment_empty(s), self.encoding(s)),
+            })
+            .collect();
+        // The trailing slash goes after the path and before its query. A route that is only an empty catch-all is
+        // written as the root, which already is a slash
+        let trailing_slash = self.trailing_slash();
+        if !trailing_slash.is_empty() {
+            let write = match self.path_segments() {
+                [RouteSegment::CatchAll(ident, _)] => quote! {
+                    if ToRouteSegments::has_route_segments(#ident) {
+                        write!(f, "/")?;
+                    }
+                },
+                _ => quote! { write!(f, "/")?; },
+            };
+            write_segments.insert(self.path_segment_count(), write);
+        }
+        // An empty catch-all writes nothing, so a route that is only a catch-all still needs the root slash, before
+        // its fragment
+        if let [RouteSegment::CatchAll(ident, _)] = self.path_segments() {
+            write_segments.insert(
+                1,
+                quote! {
+                    if !ToRouteSegments::has_route_segments(#ident) {
+                        write!(f, "/")?;
+                    }
+                },
+            );
+        }
+
+        quote! { #(#write_segments)* }
+    }
+
+    /// A match arm that returns an error if a segment of the route breaks one of its `#[route_segment(...)]`
+    /// constraints, which the `debug_assert!`s in `display_match` would panic on.
+    pub fn display_check_match(&self) -> TokenStream2 {
+        let pattern = self.variant_path(quote! { Self });
+        let dynamic_segments = self.segment_fields();
+        let checks = self.route_segments.iter().filter_map(|segment| {
+            if self.allows_segment_empty(segment) {
+                return None;
+            }
+            match segment {
+                RouteSegment::Static(_)
+                | RouteSegment::Matrix(..)
+                | RouteSegment::Query(..)
+                | RouteSegment::Fragment(..) => None,
+                RouteSegment::Dynamic(ident, ..) => Some(quote! {
+                    if DisplayRouteSegment(#ident).to_string().is_empty() {
+                        return Err(DisplayError::EmptySegment(stringify!(#ident)));
+                    }
+                }),
+                // `None` isn't written, so only a value that is there can be empty
+                RouteSegment::Optional(ident, ty) if option_inner(ty).is_some() => Some(quote! {
+                    if #ident.as_ref().is_some_and(|value| DisplayRouteSegment(value).to_string().is_empty()) {
+                        return Err(DisplayError::EmptySegment(stringify!(#ident)));
+                    }
+                }),
+                // Other types are left out when they are empty, which parses back to their default
+                RouteSegment::Optional(..) => None,
+                RouteSegment::CatchAll(ident, _) => Some(quote! {
+                    if !ToRouteSegments::has_route_segments(#ident) {
+                        return Err(DisplayError::EmptySegment(stringify!(#ident)));
+                    }
+                }),
+            }
+        });
+
+        let rest = self.locale_rest();
+
+        quote! {
+            #pattern { #(#dynamic_segments,)* #rest } => {
+                #(#checks)*
+            }
+        }
+    }
+
+    pub fn routable_match(&self) -> TokenStream2 {
+        let pattern = self.variant_path(quote! { Self });
+        let dynamic_segments: Vec<_> = self
+            .route_segments
+            .iter()
+            .filter_map(|s| s.name())
+            .collect();
+        let fields = self.segment_fields();
+        let comp_name = &self.comp_name;
+
+        // The router runs the action and navigates away before an action route is rendered
+        if self.action.is_some() {
+            return quote! {
+                #pattern { .. } => None,
+            };
+        }
+
+        // A route that is compiled out renders nothing, since its component may not exist
+        let disabled = self.feature.as_ref().map(|feature| {
+            quote! {
+                #[cfg(not(feature = #feature))]
+                #pattern { .. } => None,
+            }
+        });
+        let cfg = self.cfg_attr();
+        let rest = self.locale_rest();
+
+        // A key that changes with the path makes the runtime replace the component instead of diffing its props.
+        // Keys are only compared between the children of a list, so the component is rendered as a list of one
+        if self.hard_navigation {
+            return quote! {
+                #cfg
+                #pattern { #(#fields,)* #rest } => {
+                    render! {
+                        ::std::iter::once(rsx! {
+                            #comp_name { key: "{route_key}", #(#dynamic_segments: #dynamic_segments,)* }
+                        })
+                    }
+                }
+                #disabled
+            };
+        }
+
+        // Let the runtime create the component's scope through the props builder instead of
+        // allocating the props ourselves
+        quote! {
+            #cfg
+            #pattern { #(#fields,)* #rest } => {
+                render! {
+                    #comp_name { #(#dynamic_segments: #dynamic_segments,)* }
+                }
+            }
+            #disabled
+        }
+    }
+
+    /// Generate a match arm that provides this route's context value, passing the function a reference to each
+    /// dynamic segment in the order they appear in the route
+    pub fn provide_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let provide = self.provide.as_ref()?;
+        let dynamic_segments: Vec<_> = self
+            .route_segments
+            .iter()
+            .filter_map(|s| s.name())
+            .collect();
+        // Errors in the function call point at the `provide = ...` argument
+        let value = quote_spanned! {provide.span()=> #provide(#(#dynamic_segments),*) };
+        let fields = self.segment_fields();
+
+        let cfg = self.cfg_attr();
+        let rest = self.locale_rest();
+
+        Some(quote! {
+            #cfg
+            #pattern { #(#fields,)* #rest } => {
+                cx.provide_context(#value);
+            }
+        })
+    }
+
+    pub fn normalize_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let normalize = self.normalize.as_ref()?;
+        let dynamic_segments: Vec<_> = self
+            .route_segments
+            .iter()
+            .filter_map(|s| s.name())
+            .collect();
+        // Errors in the function call point at the `normalize = ...` argument
+        let call = quote_spanned! {normalize.span()=> #normalize(#(#dynamic_segments),*) };
+        let fields = self.segment_fields();
+
+        let cfg = self.cfg_attr();
+        let rest = self.locale_rest();
+
+        Some(quote! {
+            #cfg
+            #pattern { #(#fields,)* #rest } => #call,
+        })
+    }
+
+    pub fn action_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let action = self.action.as_ref()?;
+        let cfg = self.cfg_attr();
+
+        Some(quote! {
+            #cfg
+            #pattern { .. } => Some(#action),
+        })
+    }
+
+    pub fn guard_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let guard = self.guard.as_ref()?;
+        let cfg = self.cfg_attr();
+
+        Some(quote! {
+            #cfg
+            #pattern { .. } => Some(#guard),
+        })
+    }
+
+    /// The language of a localized route is its locale, written in the direction `dir` looks up for it
+    pub fn lang_match(&self) -> Option<TokenStream2> {
+        if self.localized.is_empty() {
+            return None;
+        }
+        let pattern = self.variant_path(quote! { Self });
+        let cfg = self.cfg_attr();
+
+        Some(quote! {
+            #cfg
+            #pattern { locale, .. } => Some(DocumentLang {
+                lang: locale.to_string(),
+                dir: dir(locale),
+            }),
+        })
+    }
+
+    pub fn hard_navigation_match(&self) -> Option<TokenStream2> {
+        if !self.hard_navigation {
+            return None;
+        }
+        let pattern = self.variant_path(quote! { Self });
+        let cfg = self.cfg_attr();
+
+        Some(quote! {
+            #cfg
+            #pattern { .. } => true,
+        })
+    }
+
+    pub fn soft_guard_match(&self) -> Option<TokenStream2> {
+        if self.guard_on_soft_navigation {
+            return None;
+        }
+        let pattern = self.variant_path(quote! { Self });
+        let cfg = self.cfg_attr();
+
+        Some(quote! {
+            #cfg
+            #pattern { .. } => false,
+        })
+    }
+
+    pub fn dry_run_safe_match(&self) -> Option<TokenStream2> {
+        if !self.dry_run_safe {
+            return None;
+        }
+        let pattern = self.variant_path(quote! { Self });
+        let cfg = self.cfg_attr();
+
+        Some(quote! {
+            #cfg
+            #pattern { .. } => true,
+        })
+    }
+
+    /// Generate a closure that matches this route against the collected `segments`, allowing the trailing dynamic
+    /// segments to be missing. Missing segments are filled with their default value and recorded in the result.
+    pub fn lenient_match(&self, enum_name: &Ident) -> TokenStream2 {
+        // Only dynamic segments after the last static or catch-all segment can be filled
+        let fillable_from = self
+            .route_segments
+            .iter()
+            .rposition(|seg| {
+                !matches!(
+                    seg,
+                    RouteSegment::Dynamic(..)
+                        | RouteSegment::Optional(..)
+                        | RouteSegment::Query(..)
+                        | RouteSegment::Fragment(..)
+                )
+            })
+            .map_or(0, |i| i + 1);
+
+        let matchers = self.route_segments.iter().enumerate().map(|(i, seg)| match seg {
+            RouteSegment::Static(segment) if self.has_matrix(i) => {
+                let ne = self.static_ne(quote! { segment }, segment);
+                quote! {
+                    let (segment, __router_matrix) = split_matrix(segments.next()?);
+                    if #ne {
+                        return None;
+                    }
+                }
+            }
+            RouteSegment::Static(segment) => {
+                let ne = self.static_ne(quote! { segments.next()? }, segment);
+                quote! {
+                    if #ne {
+                        return None;
+                    }
+                }
+            }
+            RouteSegment::Dynamic(ident, ty, _) => {
+                let parse = seg.parse_value(quote! { segment }, self.allows_empty(ident));
+                if i >= fillable_from {
+                    let default = quote_spanned! {ty.span()=> <#ty as Default>::default() };
+                    quote! {
+                        let #ident = match segments.next() {
+                            Some(segment) => #parse?,
+                            None => {
+                                filled.push(FilledSegment { name: stringify!(#ident), index: #i });
+                                #default
+                            }
+                        };
+                    }
+                } else {
+                    let split = self.has_matrix(i).then(|| {
+                        quote! { let (segment, __router_matrix) = split_matrix(segment); }
+                    });
+                    quote! {
+                        let segment = segments.next()?;
+                        #split
+                        let #ident = #parse?;
+                    }
+                }
+            }
+            // A missing optional segment is part of the route, so it isn't a filled segment
+            RouteSegment::Optional(ident, _) => {
+                let parse = seg.parse_value(quote! { segment }, self.allows_empty(ident));
+                let missing = seg.missing_optional();
+                quote! {
+                    let #ident = match segments.next() {
+                        Some("") if segments.clone().next().is_none() => #missing,
+                        Some(segment) => #parse?,
+                        None => #missing,
+                    };
+                }
+            }
+            RouteSegment::Matrix(ident, ty) => quote! {
+                let #ident = parse_matrix::<#ty>(__router_matrix).ok()?;
+            },
+            RouteSegment::Query(ident, ty) => quote! {
+                let #ident = <#ty as FromQuery>::from_query(__router_query.unwrap_or_default()).ok()?;
+            },
+            RouteSegment::Fragment(ident, _) => {
+                let parse = seg.parse_fragment(quote! { &decoded });
+                let missing = seg.missing_fragment();
+                quote! {
+                    let #ident = match __router_fragment {
+                        Some((_, fragment)) if !fragment.is_empty() => {
+                            decode_path_segment(fragment).and_then(|decoded| #parse.ok())?
+                        }
+                        _ => #missing?,
+                    };
+                }
+            }
+            RouteSegment::CatchAll(ident, ty) => {
+                let require_segment = (!self.allows_segment_empty(seg)).then(|| {
+                    quote! { segments.clone().next()?; }
+                });
+                quote! {
+                    #require_segment
+                    let #ident = <#ty as FromRouteSegments>::from_route_segments(segments.clone()).ok()?;
+                    segments.by_ref().for_each(drop);
+                }
+            }
+        });
+        let construct = self.construct(enum_name.clone());
+
+        quote! {
+            (|| {
+                let mut filled = Vec::new();
+                let mut segments = segments.iter().copied();
+                #(#matchers)*
+                if segments.next().is_some() {
+                    return None;
+                }
+                Some((#construct, filled))
+            })()
+        }
+    }
+
+    /// Generate a match arm that builds this route from its pattern and a list of raw, not percent-encoded,
+    /// parameter values.
+    pub fn pattern_params_match(&self, enum_name: &Ident) -> TokenStream2 {
+        let pattern = &self.route;
+        let params = self.route_segments.iter().filter_map(|seg| {
+            let parse = match seg {
+                RouteSegment::Static(_) => return None,
+                RouteSegment::Dynamic(_, ty, _) if is_shared_str(ty) => quote! {
+                    Ok::<#ty, ::std::convert::Infallible>(<#ty>::from(value.to_string()))
+                },
+                RouteSegment::Dynamic(_, ty, _) => quote! {
+                    <#ty as FromRouteSegment>::from_route_segment(value)
+                },
+                // An empty value is a catch-all with zero segments
+                RouteSegment::CatchAll(_, ty) => quote! {
+                    <#ty as FromRouteSegments>::from_route_segments(
+                        value.split('/').take(if value.is_empty() { 0 } else { usize::MAX })
+                    )
+                },
+                // Matrix params are given as they are written in the path, like `sort=price;dir=asc`
+                RouteSegment::Matrix(_, ty) => quote! { parse_matrix::<#ty>(value) },
+                // The query is given without the `?`, like `q=milk&page=2`
+                RouteSegment::Query(_, ty) => quote! { <#ty as FromQuery>::from_query(value) },
+                // An optional segment can be left out like in a path
+                RouteSegment::Optional(ident, _) => {
+                    let parse = seg.parse_optional(quote! { value });
+                    let missing = seg.missing_optional();
+                    return Some(quote! {
+                        let #ident = match param(stringify!(#ident)) {
+                            Ok(value) if !value.is_empty() => #parse.map_err(|err| BuildError::InvalidParam {
+                                name: stringify!(#ident),
+                                error: err.to_string(),
+                            })?,
+                            _ => #missing,
+                        };
+                    });
+                }
+                // A fragment can be left out like in a location, and is given without the `#`
+                RouteSegment::Fragment(ident, _) => {
+                    let parse = seg.parse_fragment(quote! { value });
+                    let missing = seg.missing_fragment();
+                    return Some(quote! {
+                        let #ident = match param(stringify!(#ident)) {
+                            Ok(value) if !value.is_empty() => #parse.map_err(|err| BuildError::InvalidParam {
+                                name: stringify!(#ident),
+                                error: err.to_string(),
+                            })?,
+                            _ => #missing.ok_or(BuildError::MissingParam(stringify!(#ident)))?,
+                        };
+                    });
+                }
+            };
+            let ident = seg.name()?;
+            Some(quote! {
+                let #ident = {
+                    let value = param(stringify!(#ident))?;
+                    #parse.map_err(|err| BuildError::InvalidParam {
+                        name: stringify!(#ident),
+                        error: err.to_string(),
+                    })?
+                };
+            })
+        });
+        let construct = self.construct(enum_name.clone());
+        let cfg = self.cfg_attr();
+
+        quote! {
+            #cfg
+            #pattern => {
+                #(#params)*
+                Ok(#construct)
+            }
+        }
+    }
+
+    pub fn depth_match(&self) -> TokenStream2 {
+        let pattern = self.variant_path(quote! { Self });
+        // A catch-all counts the segments of the value it holds, and an optional `Option` counts if it is `Some`
+        let locale = self.locale_binding();
+        let depth = self.match_locale(Route::depth);
+        let fields = self.path_segments().iter().filter_map(|seg| match seg {
+            RouteSegment::CatchAll(ident, _) => Some(self.field(ident)),
+            RouteSegment::Optional(ident, ty) if option_inner(ty).is_some() => {
+                Some(self.field(ident))
+            }
+            _ => None,
+        });
+        quote! {
+            #pattern { #(#fields,)* #locale .. } => #depth,
+        }
+    }
+
+    /// The depth of a route with this pattern, with its catch-all bound
+    fn depth(&self) -> TokenStream2 {
+        let fixed = self
+            .route_segments
+            .iter()
+            .filter(|seg| match seg {
+                RouteSegment::Static(segment) => !segment.is_empty(),
+                RouteSegment::Dynamic(..) => true,
+                RouteSegment::Optional(..)
+                | RouteSegment::CatchAll(..)
+                | RouteSegment::Matrix(..)
+                | RouteSegment::Query(..)
+                | RouteSegment::Fragment(..) => false,
+            })
+            .count();
+        let optional = self.route_segments.iter().filter_map(|seg| match seg {
+            RouteSegment::Optional(ident, ty) if option_inner(ty).is_some() => {
+                Some(quote! { + usize::from(#ident.is_some()) })
+            }
+            RouteSegment::Optional(ident, _) => Some(quote! {
+                + usize::from(!DisplayRouteSegment(#ident).to_string().is_empty())
+            }),
+            _ => None,
+        });
+
+        match self.path_segments().last() {
+            Some(RouteSegment::CatchAll(ident, _)) => quote! {
+                #fixed + ToRouteSegments::route_segment_count(#ident)
+            },
+            _ => quote! { #fixed #(#optional)* },
+        }
+    }
+
+    pub fn section_match(&self) -> TokenStream2 {
+        let pattern = self.variant_path(quote! { Self });
+        let locale = self.locale_binding();
+        let section = self.match_locale(|route| {
+            let section = route.route_segments.iter().find_map(|seg| match seg {
+                RouteSegment::Static(segment) if !segment.is_empty() => Some(segment),
+                _ => None,
+            });
+            match section {
+                Some(section) => quote! { Some(#section) },
+                None => quote! { None },
+            }
+        });
+
+        quote! { #pattern { #locale .. } => #section, }
+    }
+
+    pub fn pattern_match(&self) -> TokenStream2 {
+        let pattern = self.variant_path(quote! { Self });
+        let locale = self.locale_binding();
+        let route = self.match_locale(|route| route.route.to_token_stream());
 
-use proc_macro2::TokenStream as TokenStream2;
+        quote! { #pattern { #locale .. } => #route, }
+    }
 
-struct RouteArgs{
-    route: LitStr,
-    comp_name: Option<Ident>,
-    props_name: Option<Ident>,
-}
+    pub fn status_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let status = self.status?;
 
-impl Parse for RouteArgs{
-    fn parse(input: ParseStream<'_>) -> syn::Result<Self>{
-        let route = input.parse::<LitStr>()?;
+        Some(quote! {
+            #pattern { .. } => #status,
+        })
+    }
 
-        Ok(RouteArgs {
-            route,
-            comp_name:input.parse().ok(),
-            props_name: input.parse().ok(),
+    pub fn cache_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let cache = self.cache.as_ref()?;
+
+        Some(quote! {
+            #pattern { .. } => Some(#cache),
         })
     }
-}
 
-#[derive(Debug)]
-pub struct Route {
-    pub file_based: bool,
-    pub route_name: Ident,
-    pub comp_name: Ident,
-    pub props_name: Ident,
-    pub route: LitStr,
-    pub route_segments: Vec<RouteSegment>,
-}
+    /// A match arm that parses a new fragment into the `#(fragment)` field of this route. A fragment that doesn't
+    /// parse leaves the field as it is
+    pub fn set_fragment_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let fragment = self.route_segments.last().filter(|_| self.has_fragment())?;
+        let ident = fragment.name()?;
+        let field = self.field(&ident);
+        let parse = fragment.parse_fragment(quote! { &decoded });
+        let missing = fragment.missing_fragment();
+        let cfg = self.cfg_attr();
 
-impl Route {
-    pub fn parse(input: syn::Variant) -> syn::Result<Self> {
-        let route_attr = input
-            .attrs
-            .iter()
-            .find(|attr| attr.path.is_ident("route"))
-            .ok_or_else(|| {
-                syn::Error::new_spanned(
-                    input.clone(),
-                    "Routable variants must have a #[route(...)] attribute",
-                )
-            })?;
+        Some(quote! {
+            #cfg
+            #pattern { #field, .. } => {
+                let value = match __router_fragment {
+                    Some(fragment) if !fragment.is_empty() => {
+                        decode_path_segment(fragment).and_then(|decoded| #parse.ok())
+                    }
+                    _ => #missing,
+                };
+                if let Some(value) = value {
+                    *#ident = value;
+                }
+            }
+        })
+    }
 
-        let route_name = input.ident.clone();
-        let args = route_attr.parse_args::<RouteArgs>()?;
-        let route = args.route;
-        let file_based= args.comp_name.is_none();
-        let comp_name = args.comp_name.unwrap_or_else(|| format_ident!("{}", route_name));
-        let props_name = args.props_name.unwrap_or_else(|| format_ident!("{}Props", comp_name));
+    /// An arm for `fragment_id`: the fragment field as it is displayed, without percent-encoding
+    pub fn fragment_id_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let fragment = self.route_segments.last().filter(|_| self.has_fragment())?;
+        let RouteSegment::Fragment(ident, ty) = fragment else {
+            return None;
+        };
+        let field = self.field(ident);
+        let id = match option_inner(ty) {
+            Some(_) => quote! { #ident.as_ref().map(ToString::to_string) },
+            None => quote! { Some(#ident.to_string()) },
+        };
+        let cfg = self.cfg_attr();
 
-        let route_segments = parse_route_segments(&input, &route)?;
+        Some(quote! {
+            #cfg
+            #pattern { #field, .. } => #id,
+        })
+    }
 
-        Ok(Self {
-            comp_name,
-            props_name,
-            route_name,
-            route_segments,
-            route,
-            file_based,
+    pub fn deprecated_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let note = self.deprecated.as_ref()?;
+        let cfg = self.cfg_attr();
+
+        Some(quote! {
+            #cfg
+            #pattern { .. } => Some(#note),
         })
     }
 
-    pub fn display_match(&self) -> TokenStream2 {
-        let name = &self.route_name;
-        let dynamic_segments = self.route_segments.iter().filter_map(|s| s.name());
-        let write_segments = self.route_segments.iter().map(|s| s.write_segment());
+    pub fn owner_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let owner = self.owner.as_ref()?;
+        let cfg = self.cfg_attr();
+
+        Some(quote! {
+            #cfg
+            #pattern { .. } => Some(#owner),
+        })
+    }
+
+    pub fn icon_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let icon = self.icon.as_ref()?;
+
+        Some(quote! {
+            #pattern { .. } => Some(#icon),
+        })
+    }
+
+    /// An arm for `nav_hidden` if the route isn't listed in navigation menus
+    pub fn nav_hidden_match(&self) -> Option<TokenStream2> {
+        if self.nav {
+            return None;
+        }
+        let pattern = self.variant_path(quote! { Self });
+
+        Some(quote! {
+            #pattern { .. } => true,
+        })
+    }
+
+    pub fn nav_order_match(&self) -> Option<TokenStream2> {
+        let pattern = self.variant_path(quote! { Self });
+        let order = self.order?;
+
+        Some(quote! {
+            #pattern { .. } => #order,
+        })
+    }
+
+    /// Statements that add this route to the `routes` of the command palette: the route itself if it has no dynamic
+    /// segments, or every route from its `palette_provider`
+    pub fn palette_routes(&self) -> Option<TokenStream2> {
+        if !self.palette {
+            return None;
+        }
+        let push = match &self.palette_provider {
+            Some(provider) => {
+                // Errors in the function call point at the `palette_provider = ...` argument
+                let provided = quote_spanned! {provider.span()=> #provider() };
+                quote! { routes.extend(#provided); }
+            }
+            None if self
+                .route_segments
+                .iter()
+                .all(|segment| matches!(segment, RouteSegment::Static(_))) =>
+            {
+                let route = self.construct(format_ident!("Self"));
+                quote! { routes.push(#route); }
+            }
+            None => return None,
+        };
+        Some(self.gate(push))
+    }
+
+    pub fn head_match(&self) -> Option<TokenStream2> {
+        if self.title.is_none() && self.meta.is_empty() {
+            return None;
+        }
+        let pattern = self.variant_path(quote! { Self });
+        let fields = self.segment_fields();
+        let title = match &self.title {
+            Some(title) => {
+                let title = title.format(&self.route_segments);
+                quote! { Some(#title) }
+            }
+            None => quote! { None },
+        };
+        let meta = self.meta.iter().map(|(name, value)| {
+            let value = value.format(&self.route_segments);
+            quote! { (#name, #value) }
+        });
+
+        Some(quote! {
+            #[allow(unused_variables)]
+            #pattern { #(#fields,)* .. } => HeadMeta {
+                title: #title,
+                meta: vec![#(#meta),*],
+                ..HeadMeta::default()
+            },
+        })
+    }
+
+    pub fn params_diff_match(&self) -> TokenStream2 {
+        let pattern = self.variant_path(quote! { Self });
+        let fields: Vec<_> = self
+            .route_segments
+            .iter()
+            .filter_map(|s| s.name())
+            .collect();
+        let self_fields: Vec<_> = fields.iter().map(|f| format_ident!("self_{}", f)).collect();
+        let rest = self.locale_rest();
+        let other_fields: Vec<_> = fields
+            .iter()
+            .map(|f| format_ident!("other_{}", f))
+            .collect();
+        let members: Vec<_> = fields.iter().map(|f| self.member(f)).collect();
+        let diffs = self.route_segments.iter().filter_map(|seg| {
+            let field = seg.name()?;
+            let self_field = format_ident!("self_{}", field);
+            let other_field = format_ident!("other_{}", field);
+            let self_value = seg.serialize(&self_field);
+            let other_value = seg.serialize(&other_field);
+            Some(quote! {
+                let self_value = #self_value;
+                let other_value = #other_value;
+                if self_value != other_value {
+                    diff.push((stringify!(#field), self_value, other_value));
+                }
+            })
+        });
 
         quote! {
-            Self::#name { #(#dynamic_segments,)* } => {
-                #(#write_segments)*
+            (#pattern { #(#members: #self_fields,)* #rest }, #pattern { #(#members: #other_fields,)* #rest }) => {
+                #(#diffs)*
             }
         }
     }
 
-    pub fn routable_match(&self) -> TokenStream2 {
-        let name = &self.route_name;
-        let dynamic_segments: Vec<_> = self
+    /// Compare the fields of two routes of this variant with `PartialEq`, unlike `params_diff_match` which
+    /// compares their serialized values
+    pub fn differing_fields_match(&self) -> TokenStream2 {
+        let pattern = self.variant_path(quote! { Self });
+        let fields: Vec<_> = self
             .route_segments
             .iter()
             .filter_map(|s| s.name())
             .collect();
-        let props_name = &self.props_name;
-        let comp_name = &self.comp_name;
+        let self_fields: Vec<_> = fields.iter().map(|f| format_ident!("self_{}", f)).collect();
+        let other_fields: Vec<_> = fields
+            .iter()
+            .map(|f| format_ident!("other_{}", f))
+            .collect();
+        let members: Vec<_> = fields.iter().map(|f| self.member(f)).collect();
+        let rest = self.locale_rest();
 
         quote! {
-            Self::#name { #(#dynamic_segments,)* } => {
-                let comp = #props_name { #(#dynamic_segments,)* };
-                let cx = cx.bump().alloc(Scoped {
-                    props: cx.bump().alloc(comp),
-                    scope: cx,
-                });
-                #comp_name(cx)
+            (#pattern { #(#members: #self_fields,)* #rest }, #pattern { #(#members: #other_fields,)* #rest }) => {
+                #[allow(unused_mut)]
+                let mut fields = Vec::new();
+                #(
+                    if #self_fields != #other_fields {
+                        fields.push(stringify!(#fields));
+                    }
+                )*
+                Some(fields)
             }
         }
     }
 
+    pub fn matched_params_match(&self) -> TokenStream2 {
+        let pattern = self.variant_path(quote! { Self });
+        let fields = self.segment_fields();
+        let params = self.route_segments.iter().filter_map(|seg| {
+            let field = seg.name()?;
+            let value = seg.serialize(&field);
+            Some(quote! { (stringify!(#field), #value) })
+        });
+        let rest = self.locale_rest();
+
+        quote! {
+            #pattern { #(#fields,)* #rest } => vec![#(#params),*],
+        }
+    }
+
     pub fn construct(&self, enum_name: Ident) -> TokenStream2 {
-        let segments = self.route_segments.iter().filter_map(|seg| {
-            seg.name().map(|name| {
-                quote! {
-                    #name
-                }
+        let segments = self.segment_fields();
+        let path = self.variant_path(enum_name);
+        let locale = self.locale_init();
+
+        quote! {
+            #path {
+                #(#segments,)*
+                #locale
+            }
+        }
+    }
+
+    /// Generate a typestate builder for routes with three or more dynamic segments.
+    ///
+    /// Each segment has a type parameter on the builder that is `()` until the segment is set, so `build` only
+    /// exists once every segment has a value. Setters are generated in the order the fields are declared.
+    pub fn builder_impl(&self, enum_name: &Ident) -> TokenStream2 {
+        let fields: Vec<_> = self
+            .route_segments
+            .iter()
+            .filter_map(|seg| match seg {
+                RouteSegment::Dynamic(ident, ty, _)
+                | RouteSegment::CatchAll(ident, ty)
+                | RouteSegment::Optional(ident, ty)
+                | RouteSegment::Matrix(ident, ty)
+                | RouteSegment::Query(ident, ty)
+                | RouteSegment::Fragment(ident, ty) => Some((ident, ty)),
+                RouteSegment::Static(_) => None,
             })
+            .collect();
+        if !self.builder || fields.len() < 3 {
+            return quote! {};
+        }
+
+        let (builder_name, constructor) = if self.is_struct {
+            (
+                format_ident!("{}Builder", enum_name),
+                format_ident!("builder"),
+            )
+        } else {
+            (
+                format_ident!("{}{}Builder", enum_name, self.route_name),
+                format_ident!("{}", to_snake_case(&self.route_name.to_string())),
+            )
+        };
+        let path = self.variant_path(enum_name);
+        let names: Vec<_> = fields.iter().map(|(ident, _)| *ident).collect();
+        let types: Vec<_> = fields.iter().map(|(_, ty)| *ty).collect();
+        let states: Vec<_> = names
+            .iter()
+            .map(|ident| format_ident!("{}State", to_camel_case(&ident.to_string())))
+            .collect();
+        let members = names.iter().map(|ident| self.member(ident));
+        let unset = names.iter().map(|_| quote! { () });
+        let locale = self.locale_init();
+        let doc = format!(
+            "A builder for [`{}`]. Every segment must be set before the route can be built.",
+            path.to_string().replace(' ', "")
+        );
+
+        let setters = fields.iter().enumerate().map(|(i, (ident, ty))| {
+            let other_states = states
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, state)| state);
+            let input_states = states.iter().enumerate().map(|(j, state)| {
+                if j == i {
+                    quote! { () }
+                } else {
+                    quote! { #state }
+                }
+            });
+            let output_states = states.iter().enumerate().map(|(j, state)| {
+                if j == i {
+                    quote! { #ty }
+                } else {
+                    quote! { #state }
+                }
+            });
+            let other_names = names.iter().filter(|name| **name != *ident);
+
+            quote! {
+                impl<#(#other_states),*> #builder_name<#(#input_states),*> {
+                    pub fn #ident(self, #ident: #ty) -> #builder_name<#(#output_states),*> {
+                        #builder_name {
+                            #ident,
+                            #(#other_names: self.#other_names,)*
+                        }
+                    }
+                }
+            }
         });
-        let name = &self.route_name;
 
         quote! {
-            #enum_name::#name {
-                #(#segments,)*
+            #[doc = #doc]
+            #[must_use]
+            pub struct #builder_name<#(#states),*> {
+                #(#names: #states,)*
+            }
+
+            impl #enum_name {
+                /// Start building this route one segment at a time.
+                pub fn #constructor() -> #builder_name<#(#unset),*> {
+                    #builder_name {
+                        #(#names: (),)*
+                    }
+                }
+            }
+
+            #(#setters)*
+
+            impl #builder_name<#(#types),*> {
+                pub fn build(self) -> #enum_name {
+                    #path {
+                        #(#members: self.#names,)*
+                        #locale
+                    }
+                }
             }
         }
     }
@@ -120,96 +2424,607 @@ impl Route {
         format_ident!("{}ParseError", self.route_name)
     }
 
-    pub fn error_type(&self) -> TokenStream2 {
+    /// The error enum of this route, declared with the visibility `vis`.
+    pub fn error_type(&self, vis: &TokenStream2) -> TokenStream2 {
         let error_name = self.error_ident();
 
         let mut error_variants = Vec::new();
         let mut display_match = Vec::new();
 
-        for (i, segment) in self.route_segments.iter().enumerate() {
+        let mut decode_errors = Vec::new();
+
+        // Localized patterns and aliases fail to parse with the same error type, so it has the errors of every pattern
+        let mut error_names = Vec::new();
+        let segments = self
+            .with_patterns()
+            .flat_map(|route| route.route_segments.iter().enumerate());
+        for (i, segment) in segments {
             let error_name = segment.error_name(i);
+            if error_names.contains(&error_name) {
+                continue;
+            }
+            error_names.push(error_name.clone());
             match segment {
-                RouteSegment::Static(index) => {
+                RouteSegment::Static(_) => {
+                    let mut index = Vec::new();
+                    for route in self.with_patterns() {
+                        if let Some(RouteSegment::Static(segment)) = route.route_segments.get(i) {
+                            if !index.contains(segment) {
+                                index.push(segment.clone());
+                            }
+                        }
+                    }
+                    let index = index.join("' or '");
                     error_variants.push(quote! { #error_name });
                     display_match.push(quote! { Self::#error_name => write!(f, "Static segment '{}' did not match", #index)? });
                 }
-                RouteSegment::Dynamic(ident, ty) => {
-                    error_variants.push(quote! { #error_name(<#ty as std::str::FromStr>::Err) });
-                    display_match.push(quote! { Self::#error_name(err) => write!(f, "Dynamic segment '({}:{})' did not match: {}", stringify!(#ident), stringify!(#ty), err)? });
+                RouteSegment::Dynamic(ident, ty, _) => {
+                    let err = if is_shared_str(ty) {
+                        quote! { ::std::convert::Infallible }
+                    } else {
+                        quote! { <#ty as FromRouteSegment>::Err }
+                    };
+                    error_variants.push(quote! { #error_name(SegmentError<#err>) });
+                    display_match.push(quote! { Self::#error_name(err) => write!(f, "Dynamic segment '({}:{})' {}", stringify!(#ident), stringify!(#ty), err)? });
+                    decode_errors.push(quote! { Self::#error_name(SegmentError::DecodeError(_)) });
+                }
+                RouteSegment::Optional(ident, ty) => {
+                    let value_ty = option_inner(ty).unwrap_or(ty);
+                    let err = if is_shared_str(value_ty) {
+                        quote! { ::std::convert::Infallible }
+                    } else {
+                        quote! { <#value_ty as FromRouteSegment>::Err }
+                    };
+                    error_variants.push(quote! { #error_name(SegmentError<#err>) });
+                    display_match.push(quote! { Self::#error_name(err) => write!(f, "Optional segment '(?{}:{})' {}", stringify!(#ident), stringify!(#ty), err)? });
+                    decode_errors.push(quote! { Self::#error_name(SegmentError::DecodeError(_)) });
                 }
                 RouteSegment::CatchAll(ident, ty) => {
-                    error_variants.push(quote! { #error_name(<#ty as std::str::FromStr>::Err) });
+                    error_variants.push(quote! { #error_name(<#ty as FromRouteSegments>::Err) });
                     display_match.push(quote! { Self::#error_name(err) => write!(f, "Catch-all segment '({}:{})' did not match: {}", stringify!(#ident), stringify!(#ty), err)? });
                 }
+                RouteSegment::Matrix(ident, ty) => {
+                    error_variants
+                        .push(quote! { #error_name(SegmentError<<#ty as FromMatrix>::Err>) });
+                    display_match.push(quote! { Self::#error_name(err) => write!(f, "Matrix params ';({}:{})' {}", stringify!(#ident), stringify!(#ty), err)? });
+                    decode_errors.push(quote! { Self::#error_name(SegmentError::DecodeError(_)) });
+                }
+                RouteSegment::Query(ident, ty) => {
+                    error_variants.push(quote! { #error_name(<#ty as FromQuery>::Err) });
+                    display_match.push(quote! { Self::#error_name(err) => write!(f, "Query '?({}:{})' did not match: {}", stringify!(#ident), stringify!(#ty), err)? });
+                }
+                // A route has at most one fragment, so it is the only one that can be missing
+                RouteSegment::Fragment(ident, ty) => {
+                    let value_ty = option_inner(ty).unwrap_or(ty);
+                    error_variants.push(quote! { #error_name(SegmentError<<#value_ty as ::std::str::FromStr>::Err>) });
+                    error_variants.push(quote! { MissingFragment });
+                    display_match.push(quote! { Self::#error_name(err) => write!(f, "Fragment '#({}:{})' {}", stringify!(#ident), stringify!(#ty), err)? });
+                    display_match.push(quote! { Self::MissingFragment => write!(f, "Fragment '#({})' is missing", stringify!(#ident))? });
+                    decode_errors.push(quote! { Self::#error_name(SegmentError::DecodeError(_)) });
+                }
             }
         }
 
+        let is_decode_error = match decode_errors.is_empty() {
+            true => quote! { false },
+            false => quote! { matches!(self, #(#decode_errors)|*) },
+        };
+
         quote! {
             #[allow(non_camel_case_types)]
             #[derive(Debug, PartialEq)]
-            pub enum #error_name {
+            #vis enum #error_name {
                 ExtraSegments(String),
+                EmptySegment(&'static str),
+                MissingPrefix(&'static str),
+                MissingSuffix(&'static str),
                 #(#error_variants,)*
             }
 
-            impl std::fmt::Display for #error_name {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            impl ::std::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
                     match self {
                         Self::ExtraSegments(segments) => {
                             write!(f, "Found additional trailing segments: {segments}")?
                         }
+                        Self::EmptySegment(name) => {
+                            write!(f, "Dynamic segment '({name})' is empty")?
+                        }
+                        Self::MissingPrefix(prefix) => {
+                            write!(f, "Segment does not start with '{prefix}'")?
+                        }
+                        Self::MissingSuffix(suffix) => {
+                            write!(f, "Segment does not end with '{suffix}'")?
+                        }
                         #(#display_match,)*
                     }
                     Ok(())
                 }
             }
+
+            impl ::std::error::Error for #error_name {}
+
+            impl #error_name {
+                /// If a dynamic segment couldn't be percent-decoded, rather than parsed
+                pub fn is_decode_error(&self) -> bool {
+                    #is_decode_error
+                }
+            }
+        }
+    }
+}
+
+impl ToTokens for Route {
+    fn to_tokens(&self, tokens: &mut quote::__private::TokenStream) {
+        if !self.file_based {
+            return;
+        }
+
+        let route = self.route.value();
+        let without_leading_slash = route.strip_prefix('/').unwrap_or(&route);
+        let route_path = std::path::Path::new(without_leading_slash);
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let pages = std::path::Path::new(&dir).join("src").join("pages");
+
+        let route = match find_page(&pages, route_path) {
+            Ok(route) => route,
+            Err(message) => {
+                tokens.extend(syn::Error::new_spanned(&self.route, message).to_compile_error());
+                return;
+            }
+        };
+
+        let route_name: Ident = self.route_name.clone();
+        let exports = match &self.props_name {
+            Some(prop_name) => quote! { {#prop_name, #route_name} },
+            None => quote! { #route_name },
+        };
+
+        let cfg = self.cfg_attr();
+        tokens.extend(quote!(
+            #cfg
+            #[path = #route]
+            #[allow(non_snake_case)]
+            mod #route_name;
+            #cfg
+            pub use #route_name::#exports;
+        ));
+    }
+}
+
+/// Find the page file of a file-based route relative to `pages`: `route.rs`, or `route/index.rs` for the root and
+/// for routes without their own file.
+///
+/// The pages directory is assumed to be `src/pages`, next to the file that derives the routes. If it doesn't exist
+/// the index path is returned unchecked, and the compiler reports a missing file like it would without this check.
+/// Otherwise a missing page is an error that names the expected files and the closest existing ones.
+fn find_page(pages: &std::path::Path, route_path: &std::path::Path) -> Result<String, String> {
+    let with_extension = route_path.with_extension("rs");
+    let index = route_path.join("index.rs");
+    if !route_path.as_os_str().is_empty() && pages.join(&with_extension).exists() {
+        return Ok(with_extension.to_str().unwrap().to_string());
+    }
+    if !pages.is_dir() || pages.join(&index).exists() {
+        return Ok(index.to_str().unwrap().to_string());
+    }
+
+    let display = |path: &std::path::Path| format!("src/pages/{}", path.display());
+    let expected = match route_path.as_os_str().is_empty() {
+        true => display(&index),
+        false => format!("{} or {}", display(&with_extension), display(&index)),
+    };
+    let mut message = format!("No page file for this route. Expected {expected}");
+
+    let parent = route_path.parent().unwrap_or(route_path);
+    let name = route_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Pages in the same directory, as a file or a directory with an index
+    let mut similar: Vec<(usize, std::path::PathBuf)> = std::fs::read_dir(pages.join(parent))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let page = if path.is_dir() {
+                path.join("index.rs")
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                path.clone()
+            } else {
+                return None;
+            };
+            let stem = path.file_stem()?.to_string_lossy().into_owned();
+            page.exists().then(|| {
+                let relative = page.strip_prefix(pages).unwrap_or(&page).to_path_buf();
+                (edit_distance(&name, &stem), relative)
+            })
+        })
+        .collect();
+    similar.sort();
+    if !similar.is_empty() {
+        let similar: Vec<_> = similar
+            .iter()
+            .take(2)
+            .map(|(_, path)| display(path))
+            .collect();
+        message.push_str(&format!(". Similar files: {}", similar.join(", ")));
+    }
+
+    Err(message)
+}
+
+/// A title or meta value with `{name}` placeholders for the segments of its route
+#[derive(Debug)]
+pub struct Template {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug)]
+enum TemplatePart {
+    Text(String),
+    Segment(Ident),
+}
+
+impl Template {
+    fn parse(template: LitStr, segments: &[RouteSegment]) -> syn::Result<Self> {
+        let value = template.value();
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let Some(end) = rest.find('}') else {
+                        return Err(syn::Error::new_spanned(
+                            &template,
+                            "Unclosed '{' in the template. Use '{{' for a literal brace",
+                        ));
+                    };
+                    let name = &rest[..end];
+                    let Some(ident) = segments
+                        .iter()
+                        .filter_map(|segment| segment.name())
+                        .find(|ident| *ident == name || *ident == format!("field_{name}"))
+                    else {
+                        return Err(syn::Error::new_spanned(
+                            &template,
+                            format!("The route has no segment named '{name}'"),
+                        ));
+                    };
+                    chars = rest[end + 1..].chars();
+                    parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    parts.push(TemplatePart::Segment(ident));
+                }
+                '}' => {
+                    return Err(syn::Error::new_spanned(
+                        &template,
+                        "Unmatched '}' in the template. Use '}}' for a literal brace",
+                    ));
+                }
+                c => text.push(c),
+            }
+        }
+        parts.push(TemplatePart::Text(text));
+
+        Ok(Self { parts })
+    }
+
+    /// The template as it was written, with literal braces escaped again
+    pub fn source(&self) -> String {
+        let mut source = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Text(text) => {
+                    source.push_str(&text.replace('{', "{{").replace('}', "}}"))
+                }
+                TemplatePart::Segment(ident) => {
+                    source.push('{');
+                    source.push_str(&ident.to_string());
+                    source.push('}');
+                }
+            }
+        }
+        source
+    }
+
+    /// An expression that builds the string with the serialized values of the segments, the same values as
+    /// `matched_params`
+    fn format(&self, segments: &[RouteSegment]) -> TokenStream2 {
+        let parts = self.parts.iter().map(|part| match part {
+            TemplatePart::Text(text) => quote! { out.push_str(#text); },
+            TemplatePart::Segment(ident) => {
+                let segment = segments
+                    .iter()
+                    .find(|segment| segment.name().as_ref() == Some(ident))
+                    .expect("templates only name segments of their route");
+                let value = segment.serialize(ident);
+                quote! { out.push_str(&#value); }
+            }
+        });
+
+        quote! {
+            {
+                let mut out = String::new();
+                #(#parts)*
+                out
+            }
+        }
+    }
+}
+
+/// Parse the segments of the pattern `route` of a variant, with the types of the variant's fields.
+///
+/// `written` is the pattern as it is written in the attribute, without the shared segments, which errors point
+/// into. Every segment keeps the span of its field, so errors in the code generated for it point at the field.
+fn parse_route_segments(
+    name: &Ident,
+    fields: &Fields,
+    written: &LitStr,
+    route: &LitStr,
+) -> syn::Result<Vec<RouteSegment>> {
+    // Every segment without a field is reported, not only the first one
+    let mut errors = None;
+    let segments = parse_pattern(route, written, |ident, annotation| {
+        match resolve_field(name, fields, written, ident, annotation) {
+            Ok(field) => Ok(field.ty.clone()),
+            Err(error) => {
+                push_error(&mut errors, error);
+                Ok(syn::parse_quote!(()))
+            }
         }
+    });
+    let mut segments = match (segments, errors) {
+        (Ok(segments), None) => segments,
+        (Ok(_), Some(errors)) => return Err(errors),
+        (Err(error), Some(mut errors)) => {
+            errors.combine(error);
+            return Err(errors);
+        }
+        (Err(error), None) => return Err(error),
+    };
+    for segment in &mut segments {
+        let Some(ident) = segment.name_mut() else {
+            continue;
+        };
+        let field = fields
+            .iter()
+            .enumerate()
+            .find(|(i, field)| match &field.ident {
+                Some(field_ident) => field_ident == ident,
+                None => *ident == format!("field_{i}"),
+            });
+        if let Some((_, field)) = field {
+            match &field.ident {
+                Some(field_ident) => ident.set_span(field_ident.span()),
+                None => ident.set_span(field.ty.span()),
+            }
+        }
+    }
+    Ok(segments)
+}
+
+/// The field the segment `ident` of a pattern binds: the field of that name, or at that position for a tuple
+/// variant
+fn resolve_field<'a>(
+    name: &Ident,
+    fields: &'a Fields,
+    written: &LitStr,
+    ident: &str,
+    annotation: Option<&str>,
+) -> syn::Result<&'a syn::Field> {
+    let span = capture_span(written, ident);
+    if let Some(annotation) = annotation {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "The type of the segment '{}' comes from its field. Remove ': {}' from the route",
+                ident, annotation
+            ),
+        ));
+    }
+    let position = ident.parse::<usize>().ok();
+    if let Fields::Unnamed(unnamed) = fields {
+        let Some(position) = position else {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "The fields of the variant '{}' have no names, so its segments refer to them by position, like '(0)' instead of '({})'",
+                    name, ident
+                ),
+            ));
+        };
+        return unnamed.unnamed.iter().nth(position).ok_or_else(|| {
+            syn::Error::new(
+                span,
+                format!(
+                    "The variant '{}' has {} field(s), so it has no field at the position ({})",
+                    name,
+                    unnamed.unnamed.len(),
+                    position
+                ),
+            )
+        });
+    }
+    if position.is_some() {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "The fields of the variant '{}' have names, so its segments refer to them by name instead of the position ({})",
+                name, ident
+            ),
+        ));
+    }
+    fields
+        .iter()
+        .find(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|field_ident| field_ident == ident)
+        })
+        .ok_or_else(|| {
+            syn::Error::new(
+                span,
+                format!(
+                    "Could not find a field with the name '{}' in the variant '{}'",
+                    ident, name
+                ),
+            )
+        })
+}
+
+/// Add `error` to the errors collected in `errors`, so every problem is reported in the same compile
+pub fn push_error(errors: &mut Option<syn::Error>, error: syn::Error) {
+    match errors {
+        Some(errors) => errors.combine(error),
+        None => *errors = Some(error),
     }
 }
 
-impl ToTokens for Route {
-    fn to_tokens(&self, tokens: &mut quote::__private::TokenStream) {
-        if !self.file_based {
-            return;
-       }
-
-        let without_leading_slash = &self.route.value()[1..];
-        let route_path = std::path::Path::new(without_leading_slash);
-        let with_extension = route_path.with_extension("rs");
-        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
-        let dir = std::path::Path::new(&dir);
-        let route = dir.join("src").join("pages").join(with_extension.clone());
-
-        // check if the route exists or if not use the index route
-        let route = if route.exists() && without_leading_slash != "" {
-            with_extension.to_str().unwrap().to_string()
-        } else {
-            route_path.join("index.rs").to_str().unwrap().to_string()
+/// The span of the capture of the segment `name` in the pattern, like `(idd)` in `"/users/(idd)"`. The compiler
+/// can't always point into a literal, so this is the span of the whole pattern if it can't or there is no such
+/// capture.
+fn capture_span(route: &LitStr, name: &str) -> Span {
+    let token = route.token();
+    let source = token.to_string();
+    for (start, _) in source.match_indices('(') {
+        let Some(len) = source[start..].find(')') else {
+            break;
         };
+        let capture = &source[start + 1..start + len];
+        let capture = capture.strip_prefix("...").unwrap_or(capture);
+        let capture = capture.split_once(':').map_or(capture, |(ident, _)| ident);
+        if capture.trim() == name {
+            return token
+                .subspan(start..=start + len)
+                .unwrap_or_else(|| route.span());
+        }
+    }
+    route.span()
+}
 
-        let route_name: Ident = self.route_name.clone();
-        let prop_name = &self.props_name;
+/// Check that a localized pattern has the same dynamic segments as the route's own pattern, in the same order, so
+/// the variant can be displayed with either pattern.
+fn check_localized_segments(
+    route: &LitStr,
+    route_segments: &[RouteSegment],
+    pattern: &LitStr,
+    segments: &[RouteSegment],
+) -> syn::Result<()> {
+    let expected = dynamic_segments(route_segments);
+    let found = dynamic_segments(segments);
+    if found != expected {
+        return Err(syn::Error::new_spanned(
+            pattern,
+            format!(
+                "The localized pattern '{}' has the dynamic segments [{}], but '{}' has [{}]. Every pattern of a route needs the same dynamic segments in the same order",
+                pattern.value(),
+                found.join(", "),
+                route.value(),
+                expected.join(", ")
+            ),
+        ));
+    }
+    Ok(())
+}
 
-        tokens.extend(quote!(
-            #[path = #route]
-            #[allow(non_snake_case)]
-            mod #route_name;
-            pub use #route_name::{#prop_name, #route_name};
+/// The pattern of a `#[route(...)]` after the first one of a variant. The first one has the options of the route,
+/// so an alias only has a pattern.
+fn parse_alias(input: ParseStream<'_>) -> syn::Result<LitStr> {
+    let pattern = parse_pattern_literal(input)?;
+    if !input.is_empty() {
+        return Err(input.error(
+            "Only the first #[route(...)] of a variant has a component and options. The others are aliases, which only have a pattern",
+        ));
+    }
+    Ok(pattern)
+}
+
+/// Check that an alias has the same dynamic segments as the route's own pattern. They can be in another order,
+/// since the alias is only parsed and every segment binds its field by name.
+fn check_alias_segments(
+    route: &LitStr,
+    route_segments: &[RouteSegment],
+    pattern: &LitStr,
+    segments: &[RouteSegment],
+) -> syn::Result<()> {
+    let mut expected = dynamic_segments(route_segments);
+    let mut found = dynamic_segments(segments);
+    expected.sort();
+    found.sort();
+    if found != expected {
+        return Err(syn::Error::new_spanned(
+            pattern,
+            format!(
+                "The alias '{}' has the dynamic segments [{}], but '{}' has [{}]. An alias parses into the same variant, so it needs the same dynamic segments",
+                pattern.value(),
+                dynamic_segments(segments).join(", "),
+                route.value(),
+                dynamic_segments(route_segments).join(", ")
+            ),
         ));
     }
+    Ok(())
+}
+
+/// The dynamic segments of a pattern as they are written in it, like `(id)` or `?(query)`
+fn dynamic_segments(segments: &[RouteSegment]) -> Vec<String> {
+    segments
+        .iter()
+        .filter_map(|segment| match segment {
+            RouteSegment::Static(_) => None,
+            RouteSegment::Dynamic(ident, ..) => Some(format!("({})", ident)),
+            RouteSegment::CatchAll(ident, _) => Some(format!("(...{})", ident)),
+            RouteSegment::Optional(ident, _) => Some(format!("(?{})", ident)),
+            RouteSegment::Matrix(ident, _) => Some(format!(";({})", ident)),
+            RouteSegment::Query(ident, _) => Some(format!("?({})", ident)),
+            RouteSegment::Fragment(ident, _) => Some(format!("#({})", ident)),
+        })
+        .collect()
 }
 
-fn parse_route_segments(varient: &Variant, route: &LitStr) -> syn::Result<Vec<RouteSegment>> {
+/// Parse the segments of a route pattern. This is the grammar of both `#[route(...)]` and `route_segments!`, which
+/// only differ in where the type of a dynamic segment comes from: `resolve_type` gets the name of the segment and the
+/// type written after it in the pattern, like `u64` in `(id: u64)`.
+pub fn parse_pattern(
+    route: &LitStr,
+    item: &impl ToTokens,
+    mut resolve_type: impl FnMut(&str, Option<&str>) -> syn::Result<Type>,
+) -> syn::Result<Vec<RouteSegment>> {
     let mut route_segments = Vec::new();
 
     let route_string = route.value();
+    // The fragment and then the query are split off before the path, so a `/` in them doesn't start a segment
+    let (route_string, fragment) = match route_string.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (route_string.as_str(), None),
+    };
+    // The `?` of an optional segment `(?name)` is part of the path
+    let query_start = route_string
+        .match_indices('?')
+        .map(|(i, _)| i)
+        .find(|&i| !route_string[..i].ends_with('('));
+    let (route_string, query) = match query_start {
+        Some(i) => (&route_string[..i], Some(&route_string[i + 1..])),
+        None => (route_string, None),
+    };
     let mut iterator = route_string.split('/');
 
     // skip the first empty segment
     let first = iterator.next();
     if first != Some("") {
         return Err(syn::Error::new_spanned(
-            varient,
+            item,
             format!(
                 "Routes should start with /. Error found in the route '{}'",
                 route.value()
@@ -217,37 +3032,110 @@ fn parse_route_segments(varient: &Variant, route: &LitStr) -> syn::Result<Vec<Ro
         ));
     }
 
+    // Only more optional segments can come after an optional one, so a path without them is only ever missing its end
+    let mut optional: Option<&str> = None;
     while let Some(segment) = iterator.next() {
-        if segment.starts_with('(') && segment.ends_with(')') {
-            let spread = segment.starts_with("(...");
+        // Matrix params share the path segment they follow, like `(filters)` in `items;(filters)`
+        let (segment, matrix) = match segment.split_once(';') {
+            Some((segment, matrix)) => (segment, Some(matrix)),
+            None => (segment, None),
+        };
+        if let Some(optional) = optional.filter(|_| !segment.starts_with("(?")) {
+            return Err(syn::Error::new_spanned(
+                route,
+                format!(
+                    "The optional segment '({})' is followed by '{}'. Optional segments can only end the path, or a path without them would be ambiguous",
+                    optional, segment
+                ),
+            ));
+        }
+        // A dynamic segment can be surrounded by literal text in the same segment, like `(id).png` or `v(version)`
+        if let Some((prefix, (capture, suffix))) = segment
+            .split_once('(')
+            .and_then(|(prefix, rest)| Some((prefix, rest.split_once(')')?)))
+        {
+            let spread = capture.starts_with("...");
+            let is_optional = capture.starts_with('?');
 
-            let ident = if spread {
-                segment[3..segment.len() - 1].to_string()
-            } else {
-                segment[1..segment.len() - 1].to_string()
+            let (ident, annotation) = match capture.split_once(':') {
+                Some((ident, annotation)) => (ident.trim(), Some(annotation.trim())),
+                None => (capture, None),
             };
+            let ident = if spread { &ident[3..] } else { ident };
+            let ident = if is_optional { &ident[1..] } else { ident };
+            reserved::check_segment_name(ident)
+                .map_err(|message| syn::Error::new_spanned(route, message))?;
+            if spread && !prefix.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    route,
+                    format!(
+                        "Catch-all segments can't have a prefix. Remove '{}' before '({})'",
+                        prefix, capture
+                    ),
+                ));
+            }
+            if prefix.contains(')') {
+                return Err(syn::Error::new_spanned(
+                    route,
+                    format!("Unmatched ')' in the segment '{}'", segment),
+                ));
+            }
+            if spread && !suffix.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    route,
+                    format!(
+                        "Catch-all segments can't have a suffix. Remove '{}' after '({})'",
+                        suffix, capture
+                    ),
+                ));
+            }
+            if suffix.contains(['(', ')']) {
+                return Err(syn::Error::new_spanned(
+                    route,
+                    format!(
+                        "A segment can only capture one dynamic value. Found '{}' after '({})'",
+                        suffix, capture
+                    ),
+                ));
+            }
 
-            let field = varient.fields.iter().find(|field| match field.ident {
-                Some(ref field_ident) => field_ident.to_string() == ident,
-                None => false,
-            });
-
-            let ty = if let Some(field) = field {
-                field.ty.clone()
-            } else {
+            if is_optional && !(prefix.is_empty() && suffix.is_empty()) {
                 return Err(syn::Error::new_spanned(
-                    varient,
+                    route,
                     format!(
-                        "Could not find a field with the name '{}' in the variant '{}'",
-                        ident, varient.ident
+                        "Optional segments take the whole segment, so '({})' can't have a prefix or suffix",
+                        capture
                     ),
                 ));
-            };
-            if spread {
-                route_segments.push(RouteSegment::CatchAll(
-                    Ident::new(&ident, Span::call_site()),
-                    ty,
+            }
+            if is_optional && matrix.is_some() {
+                return Err(syn::Error::new_spanned(
+                    route,
+                    format!(
+                        "Optional segments can't have matrix params. Remove ';{}' after '({})'",
+                        matrix.unwrap_or_default(),
+                        capture
+                    ),
+                ));
+            }
+
+            if spread && matrix.is_some() {
+                return Err(syn::Error::new_spanned(
+                    route,
+                    format!(
+                        "Catch-all segments can't have matrix params. Remove ';{}' after '({})'",
+                        matrix.unwrap_or_default(),
+                        capture
+                    ),
                 ));
+            }
+
+            let ty = resolve_type(ident, annotation)?;
+            if is_optional {
+                route_segments.push(RouteSegment::Optional(segment_ident(ident), ty));
+                optional = Some(capture);
+            } else if spread {
+                route_segments.push(RouteSegment::CatchAll(segment_ident(ident), ty));
 
                 if iterator.next().is_some() {
                     return Err(syn::Error::new_spanned(
@@ -258,48 +3146,416 @@ fn parse_route_segments(varient: &Variant, route: &LitStr) -> syn::Result<Vec<Ro
                     break;
                 }
             } else {
+                let affix = Affix {
+                    prefix: prefix.to_string(),
+                    suffix: suffix.to_string(),
+                };
                 route_segments.push(RouteSegment::Dynamic(
-                    Ident::new(&ident, Span::call_site()),
+                    segment_ident(ident),
                     ty,
+                    (!affix.is_empty()).then_some(affix),
                 ));
             }
         } else {
             route_segments.push(RouteSegment::Static(segment.to_string()));
         }
+
+        if let Some(matrix) = matrix {
+            let Some(capture) = matrix
+                .strip_prefix('(')
+                .and_then(|matrix| matrix.strip_suffix(')'))
+                .filter(|capture| !capture.contains(['(', ')', ';']))
+            else {
+                return Err(syn::Error::new_spanned(
+                    route,
+                    format!(
+                        "Matrix params are written as ';(name)' at the end of a segment, like '/items;(filters)'. Found ';{}'",
+                        matrix
+                    ),
+                ));
+            };
+            let (ident, annotation) = match capture.split_once(':') {
+                Some((ident, annotation)) => (ident.trim(), Some(annotation.trim())),
+                None => (capture, None),
+            };
+            reserved::check_segment_name(ident)
+                .map_err(|message| syn::Error::new_spanned(route, message))?;
+            let ty = resolve_type(ident, annotation)?;
+            route_segments.push(RouteSegment::Matrix(segment_ident(ident), ty));
+        }
+    }
+
+    if let Some(query) = query {
+        let Some(capture) = trailing_capture(query) else {
+            return Err(syn::Error::new_spanned(
+                route,
+                format!(
+                    "The query is written as '?(name)' after the path, like '/search?(query)'. Found '?{}'",
+                    query
+                ),
+            ));
+        };
+        if let Some(RouteSegment::CatchAll(ident, _)) = route_segments.last() {
+            return Err(syn::Error::new_spanned(
+                route,
+                format!(
+                    "A catch-all takes the query with #[route_segment(include_query)] on '{}', instead of '?({})'",
+                    ident, capture
+                ),
+            ));
+        }
+        let (ident, ty) = trailing_field(route, capture, &mut resolve_type)?;
+        route_segments.push(RouteSegment::Query(ident, ty));
+    }
+
+    // A fragment followed by anything else, like a query or more segments, doesn't end in a capture
+    if let Some(fragment) = fragment {
+        let Some(capture) = trailing_capture(fragment) else {
+            return Err(syn::Error::new_spanned(
+                route,
+                format!(
+                    "The fragment is written as '#(name)' at the end of the pattern, like '/docs/(page)#(section)'. Found '#{}'",
+                    fragment
+                ),
+            ));
+        };
+        let (ident, ty) = trailing_field(route, capture, &mut resolve_type)?;
+        route_segments.push(RouteSegment::Fragment(ident, ty));
     }
 
     Ok(route_segments)
 }
 
+/// The binding of a segment: its name, or `field_0` for the positional segment `(0)` of a tuple variant
+fn segment_ident(name: &str) -> Ident {
+    match !name.is_empty() && name.bytes().all(|byte| byte.is_ascii_digit()) {
+        true => format_ident!("field_{}", name),
+        false => Ident::new(name, Span::call_site()),
+    }
+}
+
+/// The name and type inside the parentheses of a query or fragment, like `query` in `(query)`
+fn trailing_capture(part: &str) -> Option<&str> {
+    part.strip_prefix('(')
+        .and_then(|part| part.strip_suffix(')'))
+        .filter(|capture| !capture.contains(['(', ')']) && !capture.starts_with("..."))
+}
+
+fn trailing_field(
+    route: &LitStr,
+    capture: &str,
+    resolve_type: &mut impl FnMut(&str, Option<&str>) -> syn::Result<Type>,
+) -> syn::Result<(Ident, Type)> {
+    let (ident, annotation) = match capture.split_once(':') {
+        Some((ident, annotation)) => (ident.trim(), Some(annotation.trim())),
+        None => (capture, None),
+    };
+    reserved::check_segment_name(ident)
+        .map_err(|message| syn::Error::new_spanned(route, message))?;
+    let ty = resolve_type(ident, annotation)?;
+    Ok((segment_ident(ident), ty))
+}
+
 #[derive(Debug)]
 pub enum RouteSegment {
     Static(String),
-    Dynamic(Ident, Type),
+    /// A dynamic segment and the literal text around it in the same segment, if there is any
+    Dynamic(Ident, Type, Option<Affix>),
     CatchAll(Ident, Type),
+    /// A dynamic segment that can be missing from the end of the path, like `page` in `/posts/(?page)`. Its field is
+    /// an `Option`, which is `None` without the segment, or a type that defaults without it and is left out when it
+    /// displays as empty
+    Optional(Ident, Type),
+    /// The matrix params at the end of the segment before it, like `filters` in `/items;(filters)`. It shares the
+    /// path segment of the segment before it
+    Matrix(Ident, Type),
+    /// The query after the path, like `query` in `/search?(query)`. Only a fragment can come after it
+    Query(Ident, Type),
+    /// The fragment after the path and query, like `section` in `/docs/(page)#(section)`. It is always the last
+    /// segment. An `Option` is `None` without a fragment, other types default without one if they implement `Default`
+    Fragment(Ident, Type),
+}
+
+/// The literal text before and after the value of a dynamic segment, like `v` in `v(version)` and `.png` in
+/// `(id).png`. Either can be empty.
+#[derive(Debug)]
+pub struct Affix {
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl Affix {
+    fn is_empty(&self) -> bool {
+        self.prefix.is_empty() && self.suffix.is_empty()
+    }
+
+    /// An expression that strips the affix from `decoded`, as an `Option<&str>` of the value between them
+    pub fn strip(&self, decoded: TokenStream2) -> TokenStream2 {
+        let Self { prefix, suffix } = self;
+        match (prefix.is_empty(), suffix.is_empty()) {
+            (false, false) => quote! {
+                #decoded.strip_prefix(#prefix).and_then(|value| value.strip_suffix(#suffix))
+            },
+            (false, true) => quote! { #decoded.strip_prefix(#prefix) },
+            (true, _) => quote! { #decoded.strip_suffix(#suffix) },
+        }
+    }
+
+    /// Like [`Affix::strip`], but as a `Result` with the error for the part that is missing
+    fn strip_or_error(&self, decoded: TokenStream2, inner_parse_enum: &Ident) -> TokenStream2 {
+        let Self { prefix, suffix } = self;
+        let strip_prefix = quote! {
+            #decoded.strip_prefix(#prefix).ok_or(#inner_parse_enum::MissingPrefix(#prefix))
+        };
+        let strip_suffix = |value: TokenStream2| {
+            quote! { #value.strip_suffix(#suffix).ok_or(#inner_parse_enum::MissingSuffix(#suffix)) }
+        };
+        match (prefix.is_empty(), suffix.is_empty()) {
+            (false, false) => {
+                let strip_suffix = strip_suffix(quote! { value });
+                quote! { #strip_prefix.and_then(|value| #strip_suffix) }
+            }
+            (false, true) => strip_prefix,
+            (true, _) => strip_suffix(decoded),
+        }
+    }
 }
 
 impl RouteSegment {
     pub fn name(&self) -> Option<Ident> {
         match self {
             Self::Static(_) => None,
-            Self::Dynamic(ident, _) => Some(ident.clone()),
+            Self::Dynamic(ident, ..) => Some(ident.clone()),
             Self::CatchAll(ident, _) => Some(ident.clone()),
+            Self::Optional(ident, _) => Some(ident.clone()),
+            Self::Matrix(ident, _) => Some(ident.clone()),
+            Self::Query(ident, _) => Some(ident.clone()),
+            Self::Fragment(ident, _) => Some(ident.clone()),
+        }
+    }
+
+    /// The binding of a dynamic segment, to give it the span of its field
+    fn name_mut(&mut self) -> Option<&mut Ident> {
+        match self {
+            Self::Static(_) => None,
+            Self::Dynamic(ident, ..)
+            | Self::CatchAll(ident, _)
+            | Self::Optional(ident, _)
+            | Self::Matrix(ident, _)
+            | Self::Query(ident, _)
+            | Self::Fragment(ident, _) => Some(ident),
         }
     }
 
-    pub fn write_segment(&self) -> TokenStream2 {
+    pub fn write_segment(&self, allow_empty: bool, encoding: SegmentEncoding) -> TokenStream2 {
         match self {
             Self::Static(segment) => quote! { write!(f, "/{}", #segment)?; },
-            Self::Dynamic(ident, _) => quote! { write!(f, "/{}", #ident)?; },
-            Self::CatchAll(ident, _) => quote! { write!(f, "/{}", #ident)?; },
+            Self::Dynamic(ident, _, affix) => {
+                let value = quote! { &DisplayRouteSegment(#ident) };
+                let encoded = match encoding {
+                    SegmentEncoding::Pchar => quote! { EncodedSegment(#value) },
+                    SegmentEncoding::Unreserved => {
+                        quote! { EncodedSegmentWith(#value, EncodeSet::Unreserved) }
+                    }
+                    SegmentEncoding::None => quote! { EncodedSegmentWith(#value, EncodeSet::None) },
+                };
+                let write = match affix {
+                    Some(Affix { prefix, suffix }) => {
+                        quote! { write!(f, "/{}{}{}", #prefix, #encoded, #suffix)?; }
+                    }
+                    None => quote! { write!(f, "/{}", #encoded)?; },
+                };
+                if allow_empty {
+                    return write;
+                }
+                // An empty value would display as a path that doesn't parse back into the route
+                quote! {
+                    debug_assert!(
+                        !DisplayRouteSegment(#ident).to_string().is_empty(),
+                        "The dynamic segment '{}' is empty. Add #[route_segment(allow_empty)] to the field if it accepts empty values",
+                        stringify!(#ident)
+                    );
+                    #write
+                }
+            }
+            // `None` isn't written at all, and any other value is written like a dynamic segment. Types that aren't an
+            // `Option` are left out when they are empty instead, which parses back to their default
+            Self::Optional(ident, ty) => match option_inner(ty) {
+                Some(_) => {
+                    let write = Self::Dynamic(ident.clone(), ty.clone(), None)
+                        .write_segment(allow_empty, encoding);
+                    quote! {
+                        if let Some(#ident) = #ident {
+                            #write
+                        }
+                    }
+                }
+                None => {
+                    let write = Self::Dynamic(ident.clone(), ty.clone(), None)
+                        .write_segment(true, encoding);
+                    quote! {
+                        if !DisplayRouteSegment(#ident).to_string().is_empty() {
+                            #write
+                        }
+                    }
+                }
+            },
+            // An empty catch-all is written without a trailing slash
+            Self::CatchAll(ident, _) if allow_empty => quote! {
+                if ToRouteSegments::has_route_segments(#ident) {
+                    write!(f, "/")?;
+                    write!(f, "{}", DisplayRouteSegments(#ident))?;
+                }
+            },
+            Self::CatchAll(ident, _) => quote! {
+                debug_assert!(
+                    ToRouteSegments::has_route_segments(#ident),
+                    "The catch-all segment '{}' is empty, but it is marked #[route_segment(non_empty)]",
+                    stringify!(#ident)
+                );
+                write!(f, "/")?;
+                write!(f, "{}", DisplayRouteSegments(#ident))?;
+            },
+            // Written at the end of the segment before it, and not at all without params
+            Self::Matrix(ident, _) => quote! { write!(f, "{}", DisplayMatrix(#ident))?; },
+            // An empty query is written without the `?`
+            Self::Query(ident, _) => quote! {
+                if ToQuery::has_query(#ident) {
+                    write!(f, "?{}", DisplayQuery(#ident))?;
+                }
+            },
+            // `None` is written without the `#`
+            Self::Fragment(ident, ty) if option_inner(ty).is_some() => quote! {
+                if let Some(fragment) = #ident {
+                    write!(f, "#{}", EncodedSegment(fragment))?;
+                }
+            },
+            Self::Fragment(ident, _) => quote! { write!(f, "#{}", EncodedSegment(#ident))?; },
+        }
+    }
+
+    /// An expression that serializes the value of this segment, without percent-encoding. Matrix params are
+    /// written as they are in the path, so a `;` in a value can't be mistaken for the start of the next param
+    fn serialize(&self, value: &Ident) -> TokenStream2 {
+        match self {
+            Self::CatchAll(..) => quote! { ToRouteSegments::to_route_segments_string(#value) },
+            Self::Matrix(..) => quote! { matrix_string(#value) },
+            Self::Query(..) => quote! { ToQuery::to_query_string(#value) },
+            Self::Fragment(_, ty) if option_inner(ty).is_some() => {
+                quote! { #value.as_ref().map(ToString::to_string).unwrap_or_default() }
+            }
+            Self::Dynamic(..) => quote! { DisplayRouteSegment(#value).to_string() },
+            // A missing optional segment is an empty value, like a missing fragment
+            Self::Optional(_, ty) if option_inner(ty).is_some() => quote! {
+                #value.as_ref().map(|value| DisplayRouteSegment(value).to_string()).unwrap_or_default()
+            },
+            Self::Optional(..) => quote! { DisplayRouteSegment(#value).to_string() },
+            _ => quote! { #value.to_string() },
+        }
+    }
+
+    /// An expression that decodes and parses the segment into the type of this dynamic segment, or `None` if it
+    /// doesn't parse or is empty without `allow_empty`
+    pub fn parse_value(&self, segment: TokenStream2, allow_empty: bool) -> TokenStream2 {
+        if let Self::Dynamic(_, ty, Some(affix)) = self {
+            let parse = if is_shared_str(ty) {
+                quote! { Some(<#ty>::from(value.to_string())) }
+            } else {
+                quote! { <#ty as FromRouteSegment>::from_route_segment(value).ok() }
+            };
+            let empty = (!allow_empty).then(|| quote! { Some("") => None, });
+            let strip = affix.strip(quote! { decoded });
+            // The affix is checked after decoding, like the rest of the segment
+            return quote! {
+                decode_path_segment(#segment).and_then(|decoded| match #strip {
+                    #empty
+                    Some(value) => #parse,
+                    None => None,
+                })
+            };
+        }
+        let parse = match self {
+            Self::Dynamic(_, ty, _) if is_shared_str(ty) => quote! {
+                decode_path_segment(#segment).map(|decoded| <#ty>::from(decoded.into_owned()))
+            },
+            Self::Dynamic(_, ty, _) => quote! {
+                decode_path_segment(#segment)
+                    .and_then(|decoded| <#ty as FromRouteSegment>::from_route_segment(&decoded).ok())
+            },
+            Self::Optional(..) => {
+                let parse = self.parse_optional(quote! { &decoded });
+                quote! { decode_path_segment(#segment).and_then(|decoded| #parse.ok()) }
+            }
+            _ => return quote! { None },
+        };
+        if allow_empty {
+            parse
+        } else {
+            quote! {
+                if #segment.is_empty() { None } else { #parse }
+            }
         }
     }
 
     fn error_name(&self, idx: usize) -> Ident {
         match self {
             Self::Static(_) => static_segment_idx(idx),
-            Self::Dynamic(ident, _) => format_ident!("{}ParseError", ident),
+            Self::Dynamic(ident, ..) => format_ident!("{}ParseError", ident),
             Self::CatchAll(ident, _) => format_ident!("{}ParseError", ident),
+            Self::Optional(ident, _) => format_ident!("{}ParseError", ident),
+            Self::Matrix(ident, _) => format_ident!("{}ParseError", ident),
+            Self::Query(ident, _) => format_ident!("{}ParseError", ident),
+            Self::Fragment(ident, _) => format_ident!("{}ParseError", ident),
+        }
+    }
+
+    /// An expression that parses the decoded fragment `value` into the field of this fragment segment with
+    /// `FromStr`. An `Option` parses the type inside it
+    pub fn parse_fragment(&self, value: TokenStream2) -> TokenStream2 {
+        let Self::Fragment(_, ty) = self else {
+            unreachable!("only fragments are parsed as fragments");
+        };
+        match option_inner(ty) {
+            Some(inner) => quote! { <#inner as ::std::str::FromStr>::from_str(#value).map(Some) },
+            None => quote! { <#ty as ::std::str::FromStr>::from_str(#value) },
+        }
+    }
+
+    /// An expression for the field of this fragment segment in a location without a fragment: `Some` default value
+    /// if the type implements `Default`, which includes `None` for an `Option`, and `None` otherwise
+    pub fn missing_fragment(&self) -> TokenStream2 {
+        let Self::Fragment(_, ty) = self else {
+            unreachable!("only fragments can be missing");
+        };
+        quote! { (&MissingFragment::<#ty>::new()).missing_fragment() }
+    }
+
+    /// An expression that parses the decoded `value` into the field of this optional segment with
+    /// `FromRouteSegment`. An `Option` parses the type inside it
+    pub fn parse_optional(&self, value: TokenStream2) -> TokenStream2 {
+        let Self::Optional(_, ty) = self else {
+            unreachable!("only optional segments are parsed as optional");
+        };
+        let (value_ty, wrap) = match option_inner(ty) {
+            Some(inner) => (inner, quote! { .map(Some) }),
+            None => (ty, quote! {}),
+        };
+        if is_shared_str(value_ty) {
+            quote! { Ok::<#value_ty, ::std::convert::Infallible>(<#value_ty>::from(#value.to_string()))#wrap }
+        } else {
+            quote! { <#value_ty as FromRouteSegment>::from_route_segment(#value)#wrap }
+        }
+    }
+
+    /// An expression for the field of this optional segment in a path without it: `None` for an `Option`, and the
+    /// default value of any other type
+    pub fn missing_optional(&self) -> TokenStream2 {
+        let Self::Optional(_, ty) = self else {
+            unreachable!("only optional segments can be missing");
+        };
+        match option_inner(ty) {
+            Some(_) => quote! { None },
+            None => quote_spanned! {ty.span()=> <#ty as ::std::default::Default>::default() },
         }
     }
 
@@ -307,32 +3563,538 @@ impl RouteSegment {
         &self,
         idx: usize,
         error_enum_name: &Ident,
-        error_enum_varient: &Ident,
-        inner_parse_enum: &Ident,
+        route: &Route,
+        allow_empty: bool,
+        include_query: bool,
     ) -> TokenStream2 {
         let error_name = self.error_name(idx);
+        let error_enum_varient = &route.route_name;
+        let inner_parse_enum = &route.error_ident();
         match self {
             Self::Static(segment) => {
+                let eq = route.static_eq(quote! { segment }, segment);
                 quote! {
-                    let parsed = if segment == #segment {
+                    let parsed = if #eq {
                         Ok(())
                     } else {
-                        Err(#error_enum_name::#error_enum_varient(#inner_parse_enum::#error_name))
+                        Err(#error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name, span: segments.span() })
+                    };
+                }
+            }
+            Self::Dynamic(ident, ty, Some(affix)) => {
+                let parse = if is_shared_str(ty) {
+                    quote! { Ok::<#ty, ::std::convert::Infallible>(<#ty>::from(value.to_string())) }
+                } else {
+                    quote! { <#ty as FromRouteSegment>::from_route_segment(value) }
+                };
+                let empty = (!allow_empty).then(|| {
+                    quote! {
+                        Ok("") => Err(#error_enum_name::#error_enum_varient { error: #inner_parse_enum::EmptySegment(stringify!(#ident)), span: segments.span() }),
+                    }
+                });
+                let strip = affix.strip_or_error(quote! { decoded }, inner_parse_enum);
+                // The affix is checked after percent-decoding, so `%2Epng` matches `.png` like every other encoded
+                // character matches its decoded form
+                quote! {
+                    let parsed = match decode_path_segment(segment) {
+                        Some(decoded) => match #strip {
+                            #empty
+                            Ok(value) => #parse.map_err(|err| #error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(SegmentError::from_parse_error(segment, err)), span: segments.span() }),
+                            Err(error) => Err(#error_enum_name::#error_enum_varient { error, span: segments.span() }),
+                        },
+                        None => Err(#error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(SegmentError::DecodeError(DecodeError::InvalidUtf8)), span: segments.span() }),
+                    };
+                }
+            }
+            Self::Dynamic(ident, ty, None) => {
+                let parse = if is_shared_str(ty) {
+                    quote! { Ok::<#ty, ::std::convert::Infallible>(<#ty>::from(decoded.into_owned())) }
+                } else {
+                    quote! { <#ty as FromRouteSegment>::from_route_segment(&decoded) }
+                };
+                let empty = (!allow_empty).then(|| {
+                    quote! {
+                        _ if segment.is_empty() => Err(#error_enum_name::#error_enum_varient { error: #inner_parse_enum::EmptySegment(stringify!(#ident)), span: segments.span() }),
+                    }
+                });
+                // Segments are percent-decoded before they are parsed, so encoded and raw UTF-8 paths both match
+                quote! {
+                    let parsed = match decode_path_segment(segment) {
+                        #empty
+                        Some(decoded) => #parse.map_err(|err| #error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(SegmentError::from_parse_error(segment, err)), span: segments.span() }),
+                        None => Err(#error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(SegmentError::DecodeError(DecodeError::InvalidUtf8)), span: segments.span() }),
+                    };
+                }
+            }
+            // A trailing slash without a value after it is the same as no segment. The segments without a value at all
+            // are filled in by `parse_missing_optional`
+            Self::Optional(ident, _) => {
+                let parse = self.parse_optional(quote! { &decoded });
+                let missing = self.missing_optional();
+                let empty = (!allow_empty).then(|| {
+                    quote! {
+                        _ if segment.is_empty() => Err(#error_enum_name::#error_enum_varient { error: #inner_parse_enum::EmptySegment(stringify!(#ident)), span: segments.span() }),
+                    }
+                });
+                quote! {
+                    let parsed = match decode_path_segment(segment) {
+                        _ if segment.is_empty() && segments.clone().next().is_none() => Ok(#missing),
+                        #empty
+                        Some(decoded) => #parse.map_err(|err| #error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(SegmentError::from_parse_error(segment, err)), span: segments.span() }),
+                        None => Err(#error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(SegmentError::DecodeError(DecodeError::InvalidUtf8)), span: segments.span() }),
                     };
                 }
             }
-            Self::Dynamic(_, ty) => {
+            Self::CatchAll(_, ty) => {
+                // A single empty segment is a trailing slash, which is zero segments if the catch-all allows it
+                let rest = if allow_empty {
+                    quote! {
+                        let __router_trailing_slash = segment.is_empty() && segments.clone().next().is_none();
+                        let __router_rest = ::std::iter::once(segment)
+                            .chain(segments.clone())
+                            .take(if __router_trailing_slash { 0 } else { usize::MAX });
+                    }
+                } else {
+                    quote! {
+                        let __router_rest = ::std::iter::once(segment).chain(segments.clone());
+                    }
+                };
+                let query = include_query.then(|| {
+                    quote! {
+                        let __router_rest = with_query(__router_rest, __router_query);
+                        let __router_rest = __router_rest.iter().map(String::as_str);
+                    }
+                });
+                // The catch-all segment consumes the current segment and everything after it. The fields of the
+                // segments before it are in scope, so the bindings use the reserved prefix
+                quote! {
+                    #rest
+                    #query
+                    let parsed = <#ty as FromRouteSegments>::from_route_segments(__router_rest).map_err(|err| #error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(err), span: segments.span().start..segments.end() });
+                }
+            }
+            // The segment before it bound the raw params it split off
+            Self::Matrix(_, ty) => quote! {
+                let parsed = parse_matrix::<#ty>(__router_matrix).map_err(|err| #error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(err), span: segments.span() });
+            },
+            // A location without a query parses as an empty one. The span covers the `?` and the query after the path
+            Self::Query(_, ty) => quote! {
+                let parsed = <#ty as FromQuery>::from_query(__router_query.unwrap_or_default()).map_err(|err| #error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(err), span: segments.end()..segments.end() + __router_query.map_or(0, |query| query.len() + 1) });
+            },
+            // The fragment comes with the offset of its `#` in the location, and its span covers the `#`. A missing
+            // fragment is reported at the end of the path
+            Self::Fragment(..) => {
+                let parse = self.parse_fragment(quote! { &decoded });
+                let missing = self.missing_fragment();
                 quote! {
-                    let parsed = <#ty as std::str::FromStr>::from_str(segment).map_err(|err| #error_enum_name::#error_enum_varient(#inner_parse_enum::#error_name(err)));
+                    let parsed = match __router_fragment {
+                        Some((start, fragment)) if !fragment.is_empty() => match decode_path_segment(fragment) {
+                            Some(decoded) => #parse.map_err(|err| #error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(SegmentError::from_parse_error(fragment, err)), span: start..start + fragment.len() + 1 }),
+                            None => Err(#error_enum_name::#error_enum_varient { error: #inner_parse_enum::#error_name(SegmentError::DecodeError(DecodeError::InvalidUtf8)), span: start..start + fragment.len() + 1 }),
+                        },
+                        _ => #missing.ok_or(#error_enum_name::#error_enum_varient { error: #inner_parse_enum::MissingFragment, span: segments.end()..segments.end() }),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Generate a match for a catch-all that is reached with no segments left, if it accepts zero segments
+pub fn parse_empty_catch_all(
+    route: &Route,
+    enum_name: &Ident,
+    error_enum_name: &Ident,
+) -> Option<TokenStream2> {
+    let idx = route.path_segment_count().checked_sub(1)?;
+    let segment = &route.route_segments[idx];
+    let (ident, ty) = match segment {
+        RouteSegment::CatchAll(ident, ty) => (ident, ty),
+        _ => return None,
+    };
+    if !route.allows_segment_empty(segment) {
+        return None;
+    }
+    let error_name = segment.error_name(idx);
+    let variant = &route.route_name;
+    let inner_parse_enum = route.error_ident();
+    let construct = route.construct(enum_name.clone());
+    // A catch-all can't be followed by a query, but the fragment after it still has to parse
+    let finish = match route.has_fragment() {
+        true => {
+            let parsed = return_parsed(route, construct, error_enum_name);
+            quote! { { #parsed } }
+        }
+        false => quote! { return Ok(#construct) },
+    };
+
+    let segments = match route.includes_query() {
+        true => {
+            quote! { with_query(::std::iter::empty(), __router_query).iter().map(String::as_str) }
+        }
+        false => quote! { ::std::iter::empty() },
+    };
+
+    Some(quote! {
+        match <#ty as FromRouteSegments>::from_route_segments(#segments) {
+            Ok(#ident) => #finish,
+            Err(err) => errors.push(#error_enum_name::#variant { error: #inner_parse_enum::#error_name(err), span: segments.end()..segments.end() }),
+        }
+    })
+}
+
+/// The type inside an `Option`, like `String` in `Option<String>`
+pub fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last().filter(|_| path.qself.is_none())?;
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args)
+            if segment.ident == "Option" && args.args.len() == 1 =>
+        {
+            match args.args.first() {
+                Some(syn::GenericArgument::Type(inner)) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Generate the match of a route whose path ends before its optional segment `idx`: every optional segment from it
+/// on is missing, and the query and fragment after the path are parsed like with the segments
+pub fn parse_missing_optional(
+    route: &Route,
+    idx: usize,
+    enum_name: &Ident,
+    error_enum_name: &Ident,
+) -> TokenStream2 {
+    let missing = route.route_segments[idx..]
+        .iter()
+        .filter_map(|segment| match segment {
+            RouteSegment::Optional(ident, _) => {
+                let missing = segment.missing_optional();
+                Some(quote! { let #ident = #missing; })
+            }
+            _ => None,
+        });
+    let parsed = return_parsed(route, route.construct(enum_name.clone()), error_enum_name);
+    quote! {
+        {
+            #(#missing)*
+            #parsed
+        }
+    }
+}
+
+/// Return the route once its path matched, parsing the query and then the fragment after the path first if it has
+/// them
+pub fn return_parsed(
+    route: &Route,
+    construct: TokenStream2,
+    error_enum_name: &Ident,
+) -> TokenStream2 {
+    let path_len = route.path_segment_count();
+    let mut tokens = quote! { return Ok(#construct); };
+    for (i, segment) in route.route_segments.iter().enumerate().skip(path_len).rev() {
+        let ident = segment.name();
+        let parse = segment.try_parse(i, error_enum_name, route, true, false);
+        tokens = quote! {
+            #parse
+            match parsed {
+                Ok(#ident) => {
+                    #tokens
                 }
+                Err(err) => errors.push(err),
             }
-            Self::CatchAll(_, _) => {
-                todo!()
+        };
+    }
+    tokens
+}
+
+/// Check if a type is `Cow<'static, str>`, `Rc<str>` or `Arc<str>`. These don't implement `FromStr`, so segments
+/// of these types are converted from the decoded `String` instead.
+pub fn is_shared_str(ty: &Type) -> bool {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => match path.path.segments.last() {
+            Some(segment) => segment,
+            None => return false,
+        },
+        _ => return false,
+    };
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => &args.args,
+        _ => return false,
+    };
+    let is_str = |arg: Option<&syn::GenericArgument>| matches!(arg, Some(syn::GenericArgument::Type(Type::Path(path))) if path.path.is_ident("str"));
+
+    if segment.ident == "Rc" || segment.ident == "Arc" {
+        args.len() == 1 && is_str(args.first())
+    } else if segment.ident == "Cow" {
+        let is_static = matches!(
+            args.first(),
+            Some(syn::GenericArgument::Lifetime(lifetime)) if lifetime.ident == "static"
+        );
+        args.len() == 2 && is_static && is_str(args.iter().nth(1))
+    } else {
+        false
+    }
+}
+
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                snake.push('_');
             }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
         }
     }
+    snake
+}
+
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
 }
 
 pub fn static_segment_idx(idx: usize) -> Ident {
     format_ident!("StaticSegment{}ParseError", idx)
 }
+
+#[test]
+fn missing_pages() {
+    let pages = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/pages");
+    let page = |route: &str| find_page(&pages, std::path::Path::new(route));
+
+    assert_eq!(page(""), Ok("index.rs".to_string()));
+    assert_eq!(page("hello_world"), Ok("hello_world/index.rs".to_string()));
+    assert_eq!(
+        page("hello_world/(dynamic)"),
+        Ok("hello_world/(dynamic).rs".to_string())
+    );
+    assert_eq!(
+        page("hello_wrld"),
+        Err("No page file for this route. Expected src/pages/hello_wrld.rs or src/pages/hello_wrld/index.rs. Similar files: src/pages/hello_world/index.rs, src/pages/(dynamic).rs".to_string())
+    );
+    assert_eq!(
+        page("hello_world/(dynamc)"),
+        Err("No page file for this route. Expected src/pages/hello_world/(dynamc).rs or src/pages/hello_world/(dynamc)/index.rs. Similar files: src/pages/hello_world/(dynamic).rs, src/pages/hello_world/index.rs".to_string())
+    );
+
+    // Without a pages directory the route is left for the compiler to check
+    let missing = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("no_pages");
+    assert_eq!(
+        find_page(&missing, std::path::Path::new("anything")),
+        Ok("anything/index.rs".to_string())
+    );
+}
+
+#[test]
+fn head_templates() {
+    let parse = |attr: &str| {
+        let variant = syn::parse_str::<syn::Variant>(&format!(
+            r#"#[route("/search/(query)" Search, {attr})] Search {{ query: String }}"#
+        ))
+        .unwrap();
+        Route::parse(variant, None).map_err(|err| err.to_string())
+    };
+
+    let route =
+        parse(r#"title = "{{ {query} }}", meta("og:title" = "{query}", robots = "noindex")"#)
+            .unwrap();
+    let title = route.title.unwrap();
+    assert!(matches!(
+        title.parts.as_slice(),
+        [TemplatePart::Text(open), TemplatePart::Segment(query), TemplatePart::Text(close)]
+            if open == "{ " && query == "query" && close == " }"
+    ));
+    let names: Vec<_> = route.meta.iter().map(|(name, _)| name.value()).collect();
+    assert_eq!(names, ["og:title", "robots"]);
+
+    assert_eq!(
+        parse(r#"title = "{page}""#).err().unwrap(),
+        "The route has no segment named 'page'"
+    );
+    assert_eq!(
+        parse(r#"title = "{query""#).err().unwrap(),
+        "Unclosed '{' in the template. Use '{{' for a literal brace"
+    );
+    assert_eq!(
+        parse(r#"meta(description = "a } b")"#).err().unwrap(),
+        "Unmatched '}' in the template. Use '}}' for a literal brace"
+    );
+}
+
+#[test]
+fn segment_encodings() {
+    let variant = syn::parse_str::<syn::Variant>(
+        r#"#[route("/files/(...path)" Files)] Files { #[route_segment(encode = "none")] path: Vec<String> }"#,
+    )
+    .unwrap();
+    assert!(Route::parse(variant, None)
+        .err()
+        .unwrap()
+        .to_string()
+        .starts_with("encode is only supported on dynamic segments"));
+    let variant = syn::parse_str::<syn::Variant>(
+        r#"#[route("/users/(id)" User)] User { #[route_segment(encode = "raw")] id: u32 }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        Route::parse(variant, None).err().unwrap().to_string(),
+        r#"Unknown encoding. Expected "pchar", "unreserved" or "none""#
+    );
+}
+
+#[test]
+fn include_query_only_on_catch_alls() {
+    let variant = syn::parse_str::<syn::Variant>(
+        r#"#[route("/users/(id)" User)] User { #[route_segment(include_query)] id: u32 }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        Route::parse(variant, None).err().unwrap().to_string(),
+        "include_query is only supported on catch-all segments"
+    );
+}
+
+#[test]
+fn query_patterns() {
+    let error = |pattern: &str| {
+        let variant = syn::parse_str::<syn::Variant>(&format!(
+            r#"#[route("{pattern}" Search)] Search {{ query: String, rest: Vec<String> }}"#
+        ))
+        .unwrap();
+        Route::parse(variant, None).err().unwrap().to_string()
+    };
+    assert_eq!(
+        error("/search?query"),
+        "The query is written as '?(name)' after the path, like '/search?(query)'. Found '?query'"
+    );
+    assert_eq!(
+        error("/search/(...rest)?(query)"),
+        "A catch-all takes the query with #[route_segment(include_query)] on 'rest', instead of '?(query)'"
+    );
+
+    let variant = syn::parse_str::<syn::Variant>(
+        r#"#[route("/search/(page)?(query)" Search)] Search { page: u32, query: String }"#,
+    )
+    .unwrap();
+    let route = Route::parse(variant, None).unwrap();
+    assert!(route.has_query());
+    assert_eq!(route.path_segment_count(), 2);
+    assert_eq!(route.glob_pattern(), "/search/*");
+}
+
+#[test]
+fn fragment_patterns() {
+    let error = |pattern: &str| {
+        let variant = syn::parse_str::<syn::Variant>(&format!(
+            r#"#[route("{pattern}" Docs)] Docs {{ page: String, section: String, query: String }}"#
+        ))
+        .unwrap();
+        Route::parse(variant, None).err().unwrap().to_string()
+    };
+    assert_eq!(
+        error("/docs#(section)/(page)"),
+        "The fragment is written as '#(name)' at the end of the pattern, like '/docs/(page)#(section)'. Found '#(section)/(page)'"
+    );
+    assert_eq!(
+        error("/docs#(section)?(query)"),
+        "The fragment is written as '#(name)' at the end of the pattern, like '/docs/(page)#(section)'. Found '#(section)?(query)'"
+    );
+
+    let variant = syn::parse_str::<syn::Variant>(
+        r#"#[route("/docs/(page)?(query)#(section)" Docs)] Docs { page: String, query: String, section: Option<String> }"#,
+    )
+    .unwrap();
+    let route = Route::parse(variant, None).unwrap();
+    assert!(route.has_query());
+    assert!(route.has_fragment());
+    assert_eq!(route.path_segment_count(), 2);
+    assert_eq!(route.example_path(), "/docs/1#1");
+    assert_eq!(route.glob_pattern(), "/docs/*");
+}
+
+#[test]
+fn child_only_on_catch_alls() {
+    let variant = syn::parse_str::<syn::Variant>(
+        r#"#[route("/projects/(project)" Project)] Project { #[route_segment(child)] project: ProjectRoute }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        Route::parse(variant, None).err().unwrap().to_string(),
+        "child is only supported on catch-all segments, like '/(...child)'"
+    );
+}
+
+#[test]
+fn pattern_literals() {
+    let parse = |variant: &str| {
+        Route::parse(syn::parse_str::<syn::Variant>(variant).unwrap(), None)
+            .map(|route| route.route.value())
+            .map_err(|err| err.to_string())
+    };
+    assert_eq!(
+        parse(r#"#[route(concat!("/api/", "v", 2, "/(id)") Api)] Api { id: u32 }"#),
+        Ok("/api/v2/(id)".to_string())
+    );
+    // The joined pattern goes through the same checks as a literal
+    assert_eq!(
+        parse(r#"#[route(concat!("/api/", "(user)") Api)] Api { id: u32 }"#),
+        Err("Could not find a field with the name 'user' in the variant 'Api'".to_string())
+    );
+    assert!(parse(r#"#[route(concat!("/api/", VERSION) Api)] Api {}"#)
+        .unwrap_err()
+        .starts_with("concat! in a route pattern can only join literals, because the value of VERSION isn't known"));
+    assert!(parse(r#"#[route(PATH_CONST Api)] Api {}"#)
+        .unwrap_err()
+        .starts_with(
+            "Route patterns must be string literals, because the value of PATH_CONST isn't known"
+        ));
+    assert!(parse(r#"#[route(format!("/{}", 1) Api)] Api {}"#)
+        .unwrap_err()
+        .starts_with("Route patterns can't be generated by format!"));
+}
+
+#[test]
+fn error_spans() {
+    let parse =
+        |variant: &str| Route::parse(syn::parse_str::<syn::Variant>(variant).unwrap(), None);
+
+    // The error underlines the capture inside the pattern
+    let error = parse(r#"#[route("/users/(idd)" User)] User { id: u32 }"#).unwrap_err();
+    let span = error.span();
+    assert_eq!((span.start().column, span.end().column), (16, 21));
+
+    // A field without a segment is underlined where it is declared
+    let error = parse(r#"#[route("/users/(id)" User)] User { id: u32, tab: u8 }"#).unwrap_err();
+    assert_eq!(error.span().start().column, 45);
+
+    // Segments have the span of their field, so errors in the code generated for them point at it
+    let route = parse(r#"#[route("/users/(id)" User)] User { id: u32 }"#).unwrap();
+    let ident = route.route_segments[1].name().unwrap();
+    assert_eq!(ident.span().start().column, 36);
+
+    // A repeated argument is underlined where it is repeated
+    let error =
+        parse(r#"#[route("/" Home, title = "Home", title = "Start")] Home {}"#).unwrap_err();
+    assert_eq!(error.to_string(), "Duplicate argument 'title'");
+    let span = error.span();
+    assert_eq!((span.start().column, span.end().column), (34, 39));
+    let error = parse(r#"#[route("/" Home, meta(a = "1"), meta(b = "2"))] Home {}"#).unwrap_err();
+    assert_eq!(error.span().start().column, 33);
+}
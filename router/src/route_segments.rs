@@ -0,0 +1,194 @@
+//! `route_segments!("/blog/(id: u64)")`, which parses and writes a single route pattern for types that implement
+//! `Routable` by hand.
+//!
+//! The pattern has the grammar of `#[route(...)]`, except that every dynamic and catch-all segment is followed by
+//! its type. The segments become the fields of a hidden struct route, so the parser, the error types and the
+//! percent-encoding are generated by the same code as the derive's. The macro evaluates to a value that implements
+//! `SegmentPattern`, with the values of the segments as a tuple in the order they appear in the pattern.
+
+use crate::options::RoutableOptions;
+use crate::route::{parse_pattern, Route, RouteSegment};
+use crate::RouteEnum;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{LitStr, Type};
+
+pub fn expand(pattern: LitStr) -> TokenStream2 {
+    match to_tokens(pattern) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn to_tokens(pattern: LitStr) -> syn::Result<TokenStream2> {
+    let route_segments = parse_pattern(&pattern, &pattern, |ident, annotation| {
+        let annotation = annotation.ok_or_else(|| {
+            syn::Error::new_spanned(
+                &pattern,
+                format!("The segment '{ident}' needs a type, like '({ident}: String)'"),
+            )
+        })?;
+        syn::parse_str::<Type>(annotation).map_err(|err| {
+            syn::Error::new_spanned(
+                &pattern,
+                format!("The type of the segment '{ident}' is not a valid type: {err}"),
+            )
+        })
+    })?;
+
+    // Hand-written routes get the path without the query or fragment, so the pattern has nothing to parse them from
+    for segment in &route_segments {
+        let message = match segment {
+            RouteSegment::Query(ident, _) => {
+                format!("route_segments! only matches the path. Parse the query '?({ident})' with FromQuery instead")
+            }
+            RouteSegment::Fragment(ident, _) => {
+                format!("route_segments! only matches the path. Parse the fragment '#({ident})' with FromStr instead")
+            }
+            _ => continue,
+        };
+        return Err(syn::Error::new_spanned(&pattern, message));
+    }
+
+    // The pattern without the types is the one errors and `SegmentPattern::pattern` refer to
+    let untyped: String = route_segments
+        .iter()
+        .map(|segment| match segment {
+            RouteSegment::Static(segment) => format!("/{segment}"),
+            RouteSegment::Dynamic(ident, _, None) => format!("/({ident})"),
+            RouteSegment::Dynamic(ident, _, Some(affix)) => {
+                format!("/{}({ident}){}", affix.prefix, affix.suffix)
+            }
+            RouteSegment::CatchAll(ident, _) => format!("/(...{ident})"),
+            RouteSegment::Optional(ident, _) => format!("/(?{ident})"),
+            RouteSegment::Matrix(ident, _) => format!(";({ident})"),
+            RouteSegment::Query(..) | RouteSegment::Fragment(..) => {
+                unreachable!("queries and fragments are rejected above")
+            }
+        })
+        .collect();
+    let untyped = LitStr::new(&untyped, pattern.span());
+
+    let struct_name = format_ident!("Segments");
+    let route = Route::from_segments(struct_name.clone(), untyped.clone(), route_segments);
+
+    let fields: Vec<_> = route
+        .route_segments
+        .iter()
+        .filter_map(|segment| match segment {
+            RouteSegment::Dynamic(ident, ty, _)
+            | RouteSegment::CatchAll(ident, ty)
+            | RouteSegment::Optional(ident, ty)
+            | RouteSegment::Matrix(ident, ty)
+            | RouteSegment::Query(ident, ty)
+            | RouteSegment::Fragment(ident, ty) => Some((ident.clone(), ty.clone())),
+            RouteSegment::Static(_) => None,
+        })
+        .collect();
+    let names: Vec<_> = fields.iter().map(|(ident, _)| ident).collect();
+    let types: Vec<_> = fields.iter().map(|(_, ty)| ty).collect();
+
+    let write_segments = route.write_segments();
+    let params_arm = route.pattern_params_match(&struct_name);
+
+    let route_enum = RouteEnum {
+        route_name: struct_name.clone(),
+        routes: vec![route],
+        options: RoutableOptions::default(),
+        shared_fields: Vec::new(),
+    };
+    let match_error = format_ident!("{}MatchError", struct_name);
+    let error_type = route_enum.error_type();
+    let parse_impl = route_enum.parse_impl();
+
+    // The generated items live in a module so the names of the hidden route can't collide with the caller's, and
+    // unused parts of the parser don't warn
+    Ok(quote! {
+        {
+            #[allow(dead_code, non_snake_case, unused_imports)]
+            mod __router_segments {
+                use super::*;
+
+                #[derive(Debug, Clone, PartialEq)]
+                pub(super) struct #struct_name {
+                    #(pub(super) #names: #types,)*
+                }
+
+                #error_type
+
+                #parse_impl
+
+                pub(super) struct Pattern;
+
+                impl SegmentPattern for Pattern {
+                    type Params = (#(#types,)*);
+                    type Err = RouteParseError<#match_error>;
+
+                    fn pattern(&self) -> &'static str {
+                        #untyped
+                    }
+
+                    fn parse(&self, path: &str) -> Result<Self::Params, Self::Err> {
+                        let #struct_name { #(#names,)* } = path.parse()?;
+                        Ok((#(#names,)*))
+                    }
+
+                    fn write_path(
+                        &self,
+                        (#(#names,)*): &Self::Params,
+                        f: &mut impl ::std::fmt::Write,
+                    ) -> ::std::fmt::Result {
+                        #write_segments
+                        Ok(())
+                    }
+
+                    fn from_params(&self, params: &[(&str, &str)]) -> Result<Self::Params, BuildError> {
+                        let param = |name: &'static str| {
+                            params
+                                .iter()
+                                .find(|(key, _)| *key == name)
+                                .map(|(_, value)| *value)
+                                .ok_or(BuildError::MissingParam(name))
+                        };
+                        let #struct_name { #(#names,)* } = match self.pattern() {
+                            #params_arm
+                            _ => unreachable!(),
+                        }?;
+                        Ok((#(#names,)*))
+                    }
+                }
+            }
+
+            __router_segments::Pattern
+        }
+    })
+}
+
+#[test]
+fn typed_patterns() {
+    let error = |pattern: &str| {
+        to_tokens(LitStr::new(pattern, proc_macro2::Span::call_site()))
+            .err()
+            .map(|err| err.to_string())
+    };
+    assert_eq!(error("/blog/(id: u64)/(...rest: Vec<String>)"), None);
+    assert_eq!(
+        error("/blog/(id)").unwrap(),
+        "The segment 'id' needs a type, like '(id: String)'"
+    );
+    // The grammar is the derive's, so it rejects the same patterns
+    assert_eq!(
+        error("/(type: String)").unwrap(),
+        "The segment name 'type' is a Rust keyword. Try 'type_' instead"
+    );
+    assert!(error("blog/(id: u64)").is_some());
+    assert!(error("/(...rest: Vec<String>)/more").is_some());
+
+    let variant =
+        syn::parse_str::<syn::Variant>(r#"#[route("/users/(id: u32)" User)] User { id: u32 }"#)
+            .unwrap();
+    assert_eq!(
+        Route::parse(variant, None).err().unwrap().to_string(),
+        "The type of the segment 'id' comes from its field. Remove ': u32' from the route"
+    );
+}
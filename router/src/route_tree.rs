@@ -1,8 +1,144 @@
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::Ident;
+use quote::{quote, ToTokens};
+use syn::{Ident, Type};
 
-use crate::route::{static_segment_idx, Route, RouteSegment};
+use crate::route::{
+    is_shared_str, parse_empty_catch_all, parse_missing_optional, return_parsed,
+    static_segment_idx, Affix, Route, RouteSegment,
+};
+
+/// The most nodes the route tree of one routable type can have. Every node becomes a branch in the generated
+/// parser and pattern matcher, so the size of the expansion grows with the number of nodes.
+pub const MAX_MATCHER_NODES: usize = 10_000;
+
+/// The most segments one route pattern can have. Code generation recurses once for every segment of a route, so
+/// this bounds how deep it recurses. The limits bound the shape of the generated code, not how long it takes to
+/// compile.
+pub const MAX_ROUTE_SEGMENTS: usize = 64;
+
+/// Check that the routes generate a matcher of reasonable size before any code is generated
+pub fn check_limits(name: &Ident, routes: &[Route]) -> syn::Result<()> {
+    for route in routes.iter().flat_map(Route::with_patterns) {
+        if route.route_segments.len() > MAX_ROUTE_SEGMENTS {
+            return Err(syn::Error::new_spanned(
+                &route.route,
+                format!(
+                    "This route has {} segments, but routes can have at most {}",
+                    route.route_segments.len(),
+                    MAX_ROUTE_SEGMENTS
+                ),
+            ));
+        }
+    }
+
+    let nodes = node_count(&RouteTreeSegment::build(
+        routes.iter().flat_map(Route::with_patterns),
+    ));
+    if nodes > MAX_MATCHER_NODES {
+        return Err(syn::Error::new_spanned(
+            name,
+            format!(
+                "This enum generates a matcher with {} nodes, but the limit is {}. Consider splitting it into nested child routers",
+                nodes, MAX_MATCHER_NODES
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that every route can match a path the routes before it don't. A route can never match if a route the
+/// parser tries before it has the same static segments and takes every path it does: the same pattern, or a
+/// pattern whose dynamic segments take any value where the route's have a type. Routes behind a feature are left
+/// out, since two routes with the same pattern can be compiled in by different features.
+pub fn check_reachable(routes: &[Route]) -> syn::Result<()> {
+    let mut ordered = Vec::new();
+    for segment in RouteTreeSegment::build(routes.iter().flat_map(Route::with_patterns)) {
+        segment.routes_in_match_order(&mut ordered);
+    }
+    for (i, route) in ordered.iter().enumerate() {
+        let Some(earlier) = ordered[..i].iter().find(|earlier| covers(earlier, route)) else {
+            continue;
+        };
+        let mut err = syn::Error::new_spanned(
+            &route.route,
+            format!(
+                "The route '{}' can never match: '{}' of {} is tried before it and matches every path it does",
+                route.route.value(),
+                earlier.route.value(),
+                earlier.route_name
+            ),
+        );
+        err.combine(syn::Error::new_spanned(
+            &earlier.route,
+            format!("'{}' is declared here", earlier.route.value()),
+        ));
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// If `earlier` matches every path `route` matches
+fn covers(earlier: &Route, route: &Route) -> bool {
+    if earlier.feature.is_some()
+        || route.feature.is_some()
+        || earlier.includes_query() != route.includes_query()
+        || earlier.route_segments.len() != route.route_segments.len()
+    {
+        return false;
+    }
+    let same_type =
+        |a: &Type, b: &Type| a.to_token_stream().to_string() == b.to_token_stream().to_string();
+    // A segment that can't be empty doesn't take the empty segments the other one does
+    let takes_empty = |a, b| earlier.allows_segment_empty(a) || !route.allows_segment_empty(b);
+    earlier
+        .route_segments
+        .iter()
+        .zip(&route.route_segments)
+        .all(|(a, b)| match (a, b) {
+            (RouteSegment::Static(a), RouteSegment::Static(b)) => match earlier.case_insensitive {
+                true => a.eq_ignore_ascii_case(b),
+                false => a == b,
+            },
+            (RouteSegment::Dynamic(_, a_ty, a_affix), RouteSegment::Dynamic(_, b_ty, b_affix)) => {
+                let affix = |affix: &Option<Affix>| {
+                    affix
+                        .as_ref()
+                        .map(|affix| (affix.prefix.clone(), affix.suffix.clone()))
+                };
+                affix(a_affix) == affix(b_affix)
+                    && (same_type(a_ty, b_ty) || takes_any_value(a_ty))
+                    && takes_empty(a, b)
+            }
+            (RouteSegment::CatchAll(_, a_ty), RouteSegment::CatchAll(_, b_ty))
+            | (RouteSegment::Optional(_, a_ty), RouteSegment::Optional(_, b_ty)) => {
+                same_type(a_ty, b_ty) && takes_empty(a, b)
+            }
+            (RouteSegment::Matrix(_, a_ty), RouteSegment::Matrix(_, b_ty))
+            | (RouteSegment::Query(_, a_ty), RouteSegment::Query(_, b_ty))
+            | (RouteSegment::Fragment(_, a_ty), RouteSegment::Fragment(_, b_ty)) => {
+                same_type(a_ty, b_ty)
+            }
+            _ => false,
+        })
+}
+
+/// If a dynamic segment of this type parses every segment that decodes
+fn takes_any_value(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.is_ident("String")) || is_shared_str(ty)
+}
+
+/// Count the nodes in a route tree without recursing
+fn node_count(segments: &[RouteTreeSegment]) -> usize {
+    let mut count = 0;
+    let mut stack: Vec<_> = segments.iter().collect();
+    while let Some(segment) = stack.pop() {
+        count += 1;
+        if let RouteTreeSegment::Static { children, .. } = segment {
+            stack.extend(children);
+        }
+    }
+    count
+}
 
 // First deduplicate the routes by the static part of the route
 #[derive(Debug)]
@@ -18,7 +154,7 @@ pub enum RouteTreeSegment<'a> {
 }
 
 impl<'a> RouteTreeSegment<'a> {
-    pub fn build(routes: &'a Vec<Route>) -> Vec<RouteTreeSegment<'a>> {
+    pub fn build(routes: impl IntoIterator<Item = &'a Route>) -> Vec<RouteTreeSegment<'a>> {
         let routes = routes.into_iter().map(PartialRoute::new).collect();
         Self::construct(routes)
     }
@@ -37,14 +173,15 @@ impl<'a> RouteTreeSegment<'a> {
                             segment: s,
                             children,
                             ..
-                        } => (s == &segment).then(|| children),
+                        } => (s == &segment).then_some(children),
                         _ => None,
                     });
 
                     match found {
                         Some(children) => {
                             // If it does, add the route to the children of the segment
-                            children.append(&mut RouteTreeSegment::construct(vec![route]))
+                            children.append(&mut RouteTreeSegment::construct(vec![route]));
+                            sort_by_precedence(children);
                         }
                         None => {
                             // If it doesn't, add the route as a new segment
@@ -59,8 +196,8 @@ impl<'a> RouteTreeSegment<'a> {
                 }
                 // If there is no static segment, add the route to the dynamic routes
                 None => {
-                    // This route is entirely static
-                    if route.route.route_segments.len() == route.static_segment_index {
+                    // This route is entirely static, apart from its query
+                    if route.route.path_segment_count() == route.static_segment_index {
                         static_segments.push(RouteTreeSegment::StaticEnd(route.route));
                     } else {
                         dyn_segments.push(RouteTreeSegment::Dynamic(route.route));
@@ -69,11 +206,29 @@ impl<'a> RouteTreeSegment<'a> {
             }
         }
 
-        // All static routes are checked before dynamic routes
         static_segments.append(&mut dyn_segments);
+        sort_by_precedence(&mut static_segments);
 
         static_segments
     }
+
+    /// Collect the routes in this segment in the order the generated parser tries them
+    pub fn routes_in_match_order(&self, routes: &mut Vec<&'a Route>) {
+        match self {
+            RouteTreeSegment::Static { children, .. } => {
+                // Routes that end at this segment are checked before the children that need more segments
+                let (ends, rest): (Vec<_>, Vec<_>) = children
+                    .iter()
+                    .partition(|child| matches!(child, RouteTreeSegment::StaticEnd(_)));
+                for child in ends.into_iter().chain(rest) {
+                    child.routes_in_match_order(routes);
+                }
+            }
+            RouteTreeSegment::Dynamic(route) | RouteTreeSegment::StaticEnd(route) => {
+                routes.push(route)
+            }
+        }
+    }
 }
 
 impl<'a> RouteTreeSegment<'a> {
@@ -98,11 +253,35 @@ impl<'a> RouteTreeSegment<'a> {
                         RouteTreeSegment::StaticEnd { .. } => {
                             Some(child.to_tokens(enum_name.clone(), error_enum_name.clone()))
                         }
+                        // A route that only has a catch-all left can match with no segments left
+                        RouteTreeSegment::Dynamic(route) if only_catch_all_left(route) => {
+                            let parse = parse_empty_catch_all(route, &enum_name, &error_enum_name)?;
+                            Some(route.gate(quote! {
+                                if segments.clone().next().is_none() {
+                                    #parse
+                                }
+                            }))
+                        }
+                        // A route with only optional segments left matches without any of them
+                        RouteTreeSegment::Dynamic(route) if only_optional_left(route) => {
+                            let parse = parse_missing_optional(
+                                route,
+                                static_prefix_len(route),
+                                &enum_name,
+                                &error_enum_name,
+                            );
+                            Some(route.gate(quote! {
+                                if segments.clone().next().is_none() {
+                                    #parse
+                                }
+                            }))
+                        }
                         _ => None,
                     });
 
+                let eq = from_route.static_eq(segment, quote! { segment });
                 quote! {
-                    if #segment == segment {
+                    if #eq {
                         let mut segments = segments.clone();
                         #(#children_without_next_segment)*
                         if let Some(segment) = segments.next() {
@@ -110,7 +289,7 @@ impl<'a> RouteTreeSegment<'a> {
                         }
                     }
                     else {
-                        errors.push(#error_enum_name::#enum_varient(#varient_parse_error::#error_ident))
+                        errors.push(#error_enum_name::#enum_varient { error: #varient_parse_error::#error_ident, span: segments.span() })
                     }
                 }
             }
@@ -119,38 +298,90 @@ impl<'a> RouteTreeSegment<'a> {
                 let varient_parse_error = route.error_ident();
                 let enum_varient = &route.route_name;
 
+                let try_parse = |i: usize, seg: &RouteSegment| {
+                    seg.try_parse(
+                        i,
+                        &error_enum_name,
+                        route,
+                        route.allows_segment_empty(seg),
+                        route.includes_query(),
+                    )
+                };
                 let route_segments = route
                     .route_segments
                     .iter()
                     .enumerate()
-                    .skip_while(|(_, seg)| match seg {
-                        RouteSegment::Static(_) => true,
-                        _ => false,
+                    .skip(static_prefix_len(route))
+                    .filter(|(_, seg)| {
+                        !matches!(
+                            seg,
+                            RouteSegment::Matrix(..)
+                                | RouteSegment::Query(..)
+                                | RouteSegment::Fragment(..)
+                        )
                     })
                     .map(|(i, seg)| {
-                        (
-                            seg.name(),
-                            seg.try_parse(i, &error_enum_name, enum_varient, &varient_parse_error),
-                        )
+                        let empty = match seg {
+                            RouteSegment::CatchAll(..) => {
+                                parse_empty_catch_all(route, &enum_name, &error_enum_name)
+                            }
+                            RouteSegment::Optional(..) => Some(parse_missing_optional(
+                                route,
+                                i,
+                                &enum_name,
+                                &error_enum_name,
+                            )),
+                            _ => None,
+                        };
+                        let name = seg
+                            .name()
+                            .map(|name| quote! {#name})
+                            .unwrap_or_else(|| quote! {_});
+                        let parse = try_parse(i, seg);
+                        match route.route_segments.get(i + 1) {
+                            // Matrix params are split off the path segment before the rest of it is parsed, and
+                            // parsed together with it
+                            Some(matrix @ RouteSegment::Matrix(ident, _)) => {
+                                let parse_matrix = try_parse(i + 1, matrix);
+                                (
+                                    quote! { (#name, #ident) },
+                                    empty,
+                                    // In a block, so the routes after this one still see the whole segment
+                                    quote! {
+                                        let parsed = {
+                                            let (segment, __router_matrix) = split_matrix(segment);
+                                            #parse
+                                            parsed.and_then(|value| {
+                                                #parse_matrix
+                                                parsed.map(|matrix| (value, matrix))
+                                            })
+                                        };
+                                    },
+                                )
+                            }
+                            _ => (name, empty, parse),
+                        }
                     });
 
-                fn print_route_segment<I: Iterator<Item = (Option<Ident>, TokenStream)>>(
+                #[allow(clippy::type_complexity)]
+                fn print_route_segment<
+                    I: Iterator<Item = (TokenStream, Option<TokenStream>, TokenStream)>,
+                >(
                     mut s: std::iter::Peekable<I>,
                     sucess_tokens: TokenStream,
                 ) -> TokenStream {
-                    if let Some((name, first)) = s.next() {
-                        let has_next = s.peek().is_some();
+                    if let Some((name, _, first)) = s.next() {
+                        let next = s.peek().map(|(_, empty, _)| empty.clone());
                         let children = print_route_segment(s, sucess_tokens);
-                        let name = name
-                            .map(|name| quote! {#name})
-                            .unwrap_or_else(|| quote! {_});
 
-                        let sucess = if has_next {
+                        let sucess = if let Some(empty) = next {
+                            // A catch-all that accepts zero segments also matches when no segments are left
+                            let empty = empty.map(|empty| quote! { else { #empty } });
                             quote! {
                                 let mut segments = segments.clone();
                                 if let Some(segment) = segments.next() {
                                     #children
-                                }
+                                } #empty
                             }
                         } else {
                             children
@@ -174,36 +405,240 @@ impl<'a> RouteTreeSegment<'a> {
                     }
                 }
 
-                let construct_variant = route.construct(enum_name);
+                let construct_variant = route.construct(enum_name.clone());
 
-                print_route_segment(
-                    route_segments.peekable(),
+                // A catch-all segment consumes all remaining segments, so there can't be any extra segments
+                let ends_with_catch_all = matches!(
+                    route.path_segments().last(),
+                    Some(RouteSegment::CatchAll(..))
+                );
+                let sucess_tokens = if ends_with_catch_all {
+                    return_parsed(route, construct_variant, &error_enum_name)
+                } else {
                     return_constructed(
-                        construct_variant,
+                        return_parsed(route, construct_variant, &error_enum_name),
                         &error_enum_name,
                         enum_varient,
                         &varient_parse_error,
-                    ),
-                )
+                    )
+                };
+
+                route.gate(print_route_segment(
+                    route_segments.peekable(),
+                    sucess_tokens,
+                ))
             }
             Self::StaticEnd(route) => {
                 let varient_parse_error = route.error_ident();
                 let enum_varient = &route.route_name;
                 let construct_variant = route.construct(enum_name);
 
-                return_constructed(
-                    construct_variant,
+                route.gate(return_constructed(
+                    return_parsed(route, construct_variant, &error_enum_name),
                     &error_enum_name,
                     enum_varient,
                     &varient_parse_error,
-                )
+                ))
+            }
+        }
+    }
+}
+
+impl<'a> RouteTreeSegment<'a> {
+    /// Generate a matcher that returns the pattern of the route a path corresponds to without parsing any of
+    /// the dynamic segments. It walks the same tree as the full parser, treating every dynamic segment as a
+    /// wildcard, so the first route whose static segments and segment count fit the path wins.
+    pub fn to_pattern_tokens(&self) -> TokenStream {
+        match self {
+            RouteTreeSegment::Static {
+                segment,
+                children,
+                from_route,
+                ..
+            } => {
+                let children_with_next_segment = children
+                    .iter()
+                    .filter(|child| !matches!(child, RouteTreeSegment::StaticEnd(_)))
+                    .map(|child| child.to_pattern_tokens());
+                let children_without_next_segment =
+                    children.iter().filter_map(|child| match child {
+                        RouteTreeSegment::StaticEnd(_) => Some(child.to_pattern_tokens()),
+                        RouteTreeSegment::Dynamic(route)
+                            if (only_catch_all_left(route) && catch_all_accepts_zero(route))
+                                || only_optional_left(route) =>
+                        {
+                            let pattern = &route.route;
+                            Some(route.gate(quote! {
+                                if segments.clone().next().is_none() {
+                                    return Some(#pattern);
+                                }
+                            }))
+                        }
+                        _ => None,
+                    });
+
+                let eq = from_route.static_eq(segment, quote! { segment });
+                quote! {
+                    if #eq {
+                        let mut segments = segments.clone();
+                        #(#children_without_next_segment)*
+                        if let Some(segment) = segments.next() {
+                            #(#children_with_next_segment)*
+                        }
+                    }
+                }
             }
+            RouteTreeSegment::Dynamic(route) => {
+                let pattern = &route.route;
+                let remaining: Vec<_> = route
+                    .route_segments
+                    .iter()
+                    .enumerate()
+                    .skip(static_prefix_len(route))
+                    .filter(|(_, seg)| {
+                        !matches!(
+                            seg,
+                            RouteSegment::Matrix(..)
+                                | RouteSegment::Query(..)
+                                | RouteSegment::Fragment(..)
+                        )
+                    })
+                    .collect();
+
+                // Build the checks from the last segment outwards, so each check wraps the ones after it
+                let mut tokens = return_pattern(pattern);
+                let mut following: Option<&RouteSegment> = None;
+                for (i, (index, seg)) in remaining.into_iter().rev().enumerate() {
+                    // The matrix params aren't checked, only the part of the segment a pattern matches
+                    let segment = match route.has_matrix(index) {
+                        true => quote! { split_matrix(segment).0 },
+                        false => quote! { segment },
+                    };
+                    let next = if i == 0 {
+                        tokens
+                    } else {
+                        // The segment after this one is the catch-all if it was the first one built. A path can also
+                        // end before an optional segment
+                        let empty = ((i == 1 && catch_all_accepts_zero(route))
+                            || matches!(following, Some(RouteSegment::Optional(..))))
+                        .then(|| quote! { else { return Some(#pattern); } });
+                        quote! {
+                            let mut segments = segments.clone();
+                            if let Some(segment) = segments.next() {
+                                #tokens
+                            } #empty
+                        }
+                    };
+                    tokens = match seg {
+                        RouteSegment::Static(text) => {
+                            let eq = route.static_eq(segment, text);
+                            quote! {
+                                if #eq {
+                                    #next
+                                }
+                            }
+                        }
+                        RouteSegment::Dynamic(_, _, Some(affix)) => {
+                            let strip = affix.strip(quote! { decoded });
+                            quote! {
+                                if decode_path_segment(#segment).is_some_and(|decoded| #strip.is_some()) {
+                                    #next
+                                }
+                            }
+                        }
+                        RouteSegment::Dynamic(..) | RouteSegment::Optional(..) => next,
+                        // A catch-all is always the last segment and consumes everything after it
+                        RouteSegment::CatchAll(..) => quote! { return Some(#pattern); },
+                        RouteSegment::Matrix(..) => {
+                            unreachable!("matrix params are checked with their segment")
+                        }
+                        RouteSegment::Query(..) | RouteSegment::Fragment(..) => {
+                            unreachable!("the matcher only checks the path")
+                        }
+                    };
+                    following = Some(seg);
+                }
+                route.gate(tokens)
+            }
+            RouteTreeSegment::StaticEnd(route) => route.gate(return_pattern(&route.route)),
+        }
+    }
+}
+
+/// Order the segments the way the parser should try them: static segments first, then dynamic segments with a
+/// prefix or suffix, then dynamic segments that match anything, then catch-alls. Routes of the same kind keep the order they
+/// were declared in. A route that can never match because of the routes before it is an error, see
+/// [`check_reachable`].
+///
+/// The generated parser backtracks: if a dynamic segment fails to parse, or a later segment of the same route
+/// doesn't match, the error is recorded and the next sibling is tried with the same segments. `/items/(id)` with
+/// `id: u64` declared before `/items/(slug)` with `slug: String` sends numbers to the first route and everything
+/// else to the second. Each sibling re-parses the segments it shares with the ones before it, so a path costs at
+/// most one attempt per route below the static prefix it matched.
+fn sort_by_precedence(segments: &mut [RouteTreeSegment]) {
+    segments.sort_by_key(|seg| match seg {
+        RouteTreeSegment::Static { .. } | RouteTreeSegment::StaticEnd(_) => 0,
+        RouteTreeSegment::Dynamic(route) => match next_dynamic(route) {
+            // Static text with matrix params only matches some segments, like a dynamic segment with an affix
+            Some(RouteSegment::Dynamic(_, _, Some(_)) | RouteSegment::Static(_)) => 1,
+            Some(RouteSegment::CatchAll(..)) => 3,
+            _ => 2,
+        },
+    });
+}
+
+/// The first segment of the route after its static prefix
+fn next_dynamic(route: &Route) -> Option<&RouteSegment> {
+    route.route_segments.get(static_prefix_len(route))
+}
+
+/// The number of static segments at the start of the route that the tree matches. A static segment with matrix
+/// params ends the prefix, since it is split before it is compared.
+fn static_prefix_len(route: &Route) -> usize {
+    route
+        .route_segments
+        .iter()
+        .enumerate()
+        .take_while(|(i, seg)| matches!(seg, RouteSegment::Static(_)) && !route.has_matrix(*i))
+        .count()
+}
+
+/// Check if every segment of the route after its static prefix is a single catch-all
+fn only_catch_all_left(route: &Route) -> bool {
+    let mut remaining = route.path_segments().iter().skip(static_prefix_len(route));
+    matches!(
+        (remaining.next(), remaining.next()),
+        (Some(RouteSegment::CatchAll(..)), None)
+    )
+}
+
+/// Check if every segment of the route after its static prefix is optional
+fn only_optional_left(route: &Route) -> bool {
+    matches!(
+        route.path_segments().get(static_prefix_len(route)),
+        Some(RouteSegment::Optional(..))
+    )
+}
+
+/// Check if the path of the route ends with a catch-all that matches zero segments
+fn catch_all_accepts_zero(route: &Route) -> bool {
+    match route.path_segments().last() {
+        Some(seg @ RouteSegment::CatchAll(..)) => route.allows_segment_empty(seg),
+        _ => false,
+    }
+}
+
+fn return_pattern(pattern: &syn::LitStr) -> TokenStream {
+    quote! {
+        let mut segments_clone = segments.clone();
+        if let (None, _) | (Some(""), None) = (segments_clone.next(), segments_clone.next()) {
+            return Some(#pattern);
         }
     }
 }
 
 fn return_constructed(
-    construct_variant: TokenStream,
+    finish: TokenStream,
     error_enum_name: &Ident,
     enum_varient: &Ident,
     varient_parse_error: &Ident,
@@ -216,16 +651,17 @@ fn return_constructed(
         match (next_segment, segment_after_next) {
             // This is the last segment, return the parsed route
             (None, _) | (Some(""), None) => {
-                return Ok(#construct_variant);
+                #finish
             }
             _ => {
+                let span = remaining_segments.rest_span();
                 let mut trailing = String::new();
                 for seg in remaining_segments {
                     trailing += seg;
                     trailing += "/";
                 }
                 trailing.pop();
-                errors.push(#error_enum_name::#enum_varient(#varient_parse_error::ExtraSegments(trailing)))
+                errors.push(#error_enum_name::#enum_varient { error: #varient_parse_error::ExtraSegments(trailing), span })
             }
         }
     }
@@ -246,6 +682,9 @@ impl<'a> PartialRoute<'a> {
 
     fn next_static_segment(&mut self) -> Option<(usize, &'a str)> {
         let idx = self.static_segment_index;
+        if idx >= static_prefix_len(self.route) {
+            return None;
+        }
         let segment = self.route.route_segments.get(idx)?;
         match segment {
             RouteSegment::Static(segment) => {
@@ -256,3 +695,87 @@ impl<'a> PartialRoute<'a> {
         }
     }
 }
+
+/// The depth of a route tree, which is how deep code generation recurses for it
+#[cfg(test)]
+fn tree_depth(segments: &[RouteTreeSegment]) -> usize {
+    let mut depth = 0;
+    let mut stack: Vec<_> = segments.iter().map(|segment| (segment, 1)).collect();
+    while let Some((segment, level)) = stack.pop() {
+        depth = depth.max(level);
+        if let RouteTreeSegment::Static { children, .. } = segment {
+            stack.extend(children.iter().map(|child| (child, level + 1)));
+        }
+    }
+    depth
+}
+
+/// Expand an enum with 1,000 variants. Its route tree has a node for the static and the dynamic segment of every
+/// variant and is only two levels deep, and the expansion stays under 8KB per variant.
+#[test]
+fn large_enums_stay_within_limits() {
+    let variants: String = (0..1000)
+        .map(|i| format!("#[route(\"/section{i}/(id)\" Page)] Page{i} {{ id: u32 }},"))
+        .collect();
+    let input =
+        syn::parse_str::<syn::DeriveInput>(&format!("enum Route {{ {variants} }}")).unwrap();
+    let syn::Data::Enum(data) = &input.data else {
+        unreachable!()
+    };
+    let routes: Vec<_> = data
+        .variants
+        .iter()
+        .map(|variant| Route::parse(variant.clone(), None).unwrap())
+        .collect();
+    let tree = RouteTreeSegment::build(&routes);
+    assert_eq!(node_count(&tree), 2000);
+    assert_eq!(tree_depth(&tree), 2);
+
+    let expanded = crate::expand(input).to_string();
+    assert!(!expanded.contains("compile_error"));
+    assert!(
+        expanded.len() < 1000 * 8 * 1024,
+        "expanded to {} bytes",
+        expanded.len()
+    );
+}
+
+/// Code generation recurses once per level of the route tree, and the longest route allowed makes the deepest tree
+#[test]
+fn deep_routes_stay_within_limits() {
+    let segments = "/a".repeat(MAX_ROUTE_SEGMENTS);
+    let input = syn::parse_str::<syn::DeriveInput>(&format!(
+        "enum Route {{ #[route(\"{segments}\" Page)] Page {{}} }}"
+    ))
+    .unwrap();
+    let syn::Data::Enum(data) = &input.data else {
+        unreachable!()
+    };
+    let route = Route::parse(data.variants[0].clone(), None).unwrap();
+    assert_eq!(
+        tree_depth(&RouteTreeSegment::build([&route])),
+        MAX_ROUTE_SEGMENTS + 1
+    );
+    assert!(!crate::expand(input).to_string().contains("compile_error"));
+}
+
+#[test]
+fn matcher_limits() {
+    let segments = "/a".repeat(MAX_ROUTE_SEGMENTS + 1);
+    let input = syn::parse_str::<syn::DeriveInput>(&format!(
+        "enum Route {{ #[route(\"{segments}\" Page)] Page {{}} }}"
+    ))
+    .unwrap();
+    assert!(crate::expand(input)
+        .to_string()
+        .contains("routes can have at most 64"));
+
+    let variants: String = (0..MAX_MATCHER_NODES / 2 + 1)
+        .map(|i| format!("#[route(\"/section{i}/(id)\" Page)] Page{i} {{ id: u32 }},"))
+        .collect();
+    let input =
+        syn::parse_str::<syn::DeriveInput>(&format!("enum Route {{ {variants} }}")).unwrap();
+    assert!(crate::expand(input)
+        .to_string()
+        .contains("This enum generates a matcher with 10002 nodes"));
+}
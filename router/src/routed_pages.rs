@@ -0,0 +1,212 @@
+//! `#[routed_pages(crate::pages)]`, which fails the build if a page component in a module has no route.
+//!
+//! The check is a heuristic: the module is found the way rustc finds it by default, as `src/pages.rs` or
+//! `src/pages/mod.rs` under `CARGO_MANIFEST_DIR`, and every file in the module's directory is read. Public functions
+//! marked `#[component]` or `#[inline_props]` are pages. A page is routed if a variant renders it, by its name or by
+//! the component named in its `#[route(...)]`. If the module can't be found or a file doesn't parse, nothing is
+//! checked, since the layout of the crate may be something the scan doesn't understand.
+//!
+//! Pages that are rendered some other way, like inside another page, are listed with `#[allow_unrouted(A, B)]`.
+
+use crate::route::{component_ident, Route};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::ToTokens;
+use std::path::{Path, PathBuf};
+use syn::punctuated::Punctuated;
+use syn::{DeriveInput, Ident, Token};
+
+pub fn expand(attr: TokenStream2, item: TokenStream2) -> TokenStream2 {
+    let module = match syn::parse2::<syn::Path>(attr) {
+        Ok(module) => module,
+        Err(err) => return err.to_compile_error(),
+    };
+    // Anything that isn't a routable type is left for the derive to report
+    let Ok(mut input) = syn::parse2::<DeriveInput>(item.clone()) else {
+        return item;
+    };
+
+    let mut allowed = Vec::new();
+    let mut errors = Vec::new();
+    input.attrs.retain(|attr| {
+        if !attr.path.is_ident("allow_unrouted") {
+            return true;
+        }
+        match attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated) {
+            Ok(names) => allowed.extend(names.into_iter().map(|name| name.to_string())),
+            Err(err) => errors.push(err.to_compile_error()),
+        }
+        false
+    });
+
+    let routed = routed_components(&input);
+    let pages = std::env::var("CARGO_MANIFEST_DIR")
+        .ok()
+        .and_then(|dir| find_pages(Path::new(&dir), &module))
+        .unwrap_or_default();
+    let orphans: Vec<_> = pages
+        .into_iter()
+        .filter(|page| !routed.contains(page) && !allowed.contains(page))
+        .collect();
+    if !orphans.is_empty() {
+        errors.push(
+            syn::Error::new_spanned(
+                &module,
+                format!(
+                    "These pages in {} have no route: {}. Add a variant that renders each of them, or list the ones that are rendered some other way in #[allow_unrouted(...)]",
+                    module.to_token_stream().to_string().replace(' ', ""),
+                    orphans.join(", ")
+                ),
+            )
+            .to_compile_error(),
+        );
+    }
+
+    let mut tokens = input.into_token_stream();
+    tokens.extend(errors);
+    tokens
+}
+
+/// The names of the components the routes of the type render. Routes that don't parse are skipped here; the derive
+/// reports them.
+fn routed_components(input: &DeriveInput) -> Vec<String> {
+    let routes: Vec<Route> = match &input.data {
+        syn::Data::Enum(data) => data
+            .variants
+            .iter()
+            .filter_map(|variant| Route::parse(variant.clone(), None).ok())
+            .collect(),
+        syn::Data::Struct(data) => Route::parse_struct(input, &data.fields)
+            .into_iter()
+            .collect(),
+        syn::Data::Union(_) => Vec::new(),
+    };
+    routes
+        .into_iter()
+        .filter(|route| route.action.is_none())
+        .map(|route| component_ident(&route.comp_name).to_string())
+        .collect()
+}
+
+/// Find the public components of a module, or `None` if the module's files can't be found or read.
+fn find_pages(manifest_dir: &Path, module: &syn::Path) -> Option<Vec<String>> {
+    let mut segments = module
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string());
+    if segments.next()? != "crate" {
+        return None;
+    }
+    let segments: Vec<String> = segments.collect();
+    let (last, parents) = segments.split_last()?;
+
+    let mut dir = manifest_dir.join("src");
+    dir.extend(parents);
+    let file = [
+        dir.join(format!("{last}.rs")),
+        dir.join(last).join("mod.rs"),
+    ]
+    .into_iter()
+    .find(|file| file.is_file())?;
+
+    // A module's submodules live in the directory named after it
+    let mut files = vec![file];
+    collect_files(&dir.join(last), &mut files).ok()?;
+
+    let mut pages = Vec::new();
+    for file in files {
+        let source = std::fs::read_to_string(&file).ok()?;
+        let parsed = syn::parse_file(&source).ok()?;
+        collect_pages(&parsed.items, &mut pages);
+    }
+    pages.sort();
+    pages.dedup();
+    Some(pages)
+}
+
+/// Every `.rs` file below `dir`, in a stable order. A missing directory has no files.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") && !files.contains(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn collect_pages(items: &[syn::Item], pages: &mut Vec<String>) {
+    for item in items {
+        match item {
+            syn::Item::Fn(function) => {
+                let is_component = function.attrs.iter().any(|attr| {
+                    attr.path.segments.last().is_some_and(|segment| {
+                        segment.ident == "component" || segment.ident == "inline_props"
+                    })
+                });
+                if is_component && !matches!(function.vis, syn::Visibility::Inherited) {
+                    pages.push(function.sig.ident.to_string());
+                }
+            }
+            syn::Item::Mod(syn::ItemMod {
+                content: Some((_, items)),
+                ..
+            }) => collect_pages(items, pages),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn unrouted_pages() {
+    let dir = std::env::temp_dir().join(format!("routed_pages_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src/pages/admin")).unwrap();
+    std::fs::write(
+        dir.join("src/pages/mod.rs"),
+        "mod admin;\n#[component] pub fn Home() {}\n#[inline_props] pub fn About() {}\n#[component] fn Private() {}\npub fn Helper() {}",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("src/pages/admin/users.rs"),
+        "#[dioxus::component] pub fn Users() {}\npub mod nested { #[component] pub(crate) fn Audit() {} }",
+    )
+    .unwrap();
+
+    let module = syn::parse_str("crate::pages").unwrap();
+    assert_eq!(
+        find_pages(&dir, &module),
+        Some(vec![
+            "About".to_string(),
+            "Audit".to_string(),
+            "Home".to_string(),
+            "Users".to_string()
+        ])
+    );
+    assert_eq!(
+        find_pages(&dir, &syn::parse_str("crate::missing").unwrap()),
+        None
+    );
+    assert_eq!(
+        find_pages(&dir, &syn::parse_str("super::pages").unwrap()),
+        None
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let input = syn::parse_str::<DeriveInput>(
+        r#"enum Route {
+            #[route("/")] Home {},
+            #[route("/about" About)] AboutPage {},
+            #[route("/logout", action = logout)] Users {},
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(routed_components(&input), ["Home", "About"]);
+}
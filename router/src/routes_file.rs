@@ -0,0 +1,432 @@
+//! `routes_from_file!`: generate a routable enum from a routes file.
+//!
+//! The file is a small subset of TOML. An optional top-level `enum` key names the enum, and every `[[route]]`
+//! table is one variant:
+//!
+//! ```toml
+//! enum = "CatalogRoute"
+//!
+//! [[route]]
+//! name = "Product"
+//! pattern = "/catalog/(id)"
+//! component = "ProductPage"
+//! type.id = "u32"
+//! ```
+//!
+//! `component` defaults to a file based route like in the derive. Segments without a `type.<segment>` entry are
+//! `String`s, or `Vec<String>` for catch-alls.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::LitStr;
+
+/// The types a segment can have in a routes file
+const ALLOWED_TYPES: &[&str] = &[
+    "String",
+    "bool",
+    "char",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "f32",
+    "f64",
+    "Vec<String>",
+    "UrlPath",
+];
+
+#[derive(Debug, PartialEq)]
+pub struct RoutesFile {
+    pub enum_name: String,
+    pub routes: Vec<FileRoute>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FileRoute {
+    pub name: String,
+    pub pattern: String,
+    pub component: Option<String>,
+    /// The fields of the variant in the order they appear in the pattern
+    pub fields: Vec<(String, String)>,
+}
+
+/// An error in a routes file, with the line it was found on
+#[derive(Debug, PartialEq)]
+pub struct FileError {
+    pub line: usize,
+    pub message: String,
+}
+
+fn error(line: usize, message: impl Into<String>) -> FileError {
+    FileError {
+        line,
+        message: message.into(),
+    }
+}
+
+/// A `[[route]]` table while it is being read, with the line each key was set on
+#[derive(Default)]
+struct PartialRoute {
+    line: usize,
+    name: Option<String>,
+    pattern: Option<(usize, String)>,
+    component: Option<(usize, String)>,
+    types: Vec<(usize, String, String)>,
+}
+
+impl PartialRoute {
+    fn finish(self) -> Result<FileRoute, FileError> {
+        let name = self
+            .name
+            .ok_or_else(|| error(self.line, "This route is missing a `name`"))?;
+        if syn::parse_str::<syn::Ident>(&name).is_err() {
+            return Err(error(
+                self.line,
+                format!("'{}' is not a valid variant name", name),
+            ));
+        }
+        let (pattern_line, pattern) = self.pattern.ok_or_else(|| {
+            error(
+                self.line,
+                format!("The route '{}' is missing a `pattern`", name),
+            )
+        })?;
+        if let Some((line, component)) = &self.component {
+            if syn::parse_str::<syn::Ident>(component).is_err() {
+                return Err(error(
+                    *line,
+                    format!("'{}' is not a valid component name", component),
+                ));
+            }
+        }
+
+        let mut fields = Vec::new();
+        for segment in pattern.split('/') {
+            if let Some(field) = segment
+                .strip_prefix("(...")
+                .and_then(|s| s.strip_suffix(')'))
+            {
+                fields.push((field.to_string(), "Vec<String>".to_string()));
+            } else if let Some((field, _suffix)) =
+                segment.strip_prefix('(').and_then(|s| s.split_once(')'))
+            {
+                fields.push((field.to_string(), "String".to_string()));
+            }
+        }
+        for (field, _) in &fields {
+            crate::reserved::check_segment_name(field)
+                .map_err(|message| error(pattern_line, message))?;
+        }
+        for (line, segment, ty) in self.types {
+            if !ALLOWED_TYPES.contains(&ty.as_str()) {
+                return Err(error(
+                    line,
+                    format!(
+                        "'{}' is not a supported segment type. Use one of: {}",
+                        ty,
+                        ALLOWED_TYPES.join(", ")
+                    ),
+                ));
+            }
+            match fields.iter_mut().find(|(field, _)| *field == segment) {
+                Some((_, field_ty)) => *field_ty = ty,
+                None => {
+                    return Err(error(
+                        line,
+                        format!(
+                            "The pattern '{}' on line {} has no segment named '{}'",
+                            pattern, pattern_line, segment
+                        ),
+                    ))
+                }
+            }
+        }
+
+        Ok(FileRoute {
+            name,
+            pattern,
+            component: self.component.map(|(_, component)| component),
+            fields,
+        })
+    }
+}
+
+impl RoutesFile {
+    pub fn parse(source: &str) -> Result<Self, FileError> {
+        let mut enum_name = "Route".to_string();
+        let mut routes = Vec::new();
+        let mut current: Option<PartialRoute> = None;
+
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == "[[route]]" {
+                if let Some(route) = current.take() {
+                    routes.push(route.finish()?);
+                }
+                current = Some(PartialRoute {
+                    line: line_number,
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(line_number, "Expected `[[route]]` or `key = \"value\"`"))?;
+            let key = key.trim();
+            let value = parse_string(value.trim()).ok_or_else(|| {
+                error(
+                    line_number,
+                    format!("The value of `{}` must be a string", key),
+                )
+            })?;
+
+            let route = match &mut current {
+                Some(route) => route,
+                None if key == "enum" => {
+                    if syn::parse_str::<syn::Ident>(&value).is_err() {
+                        return Err(error(
+                            line_number,
+                            format!("'{}' is not a valid enum name", value),
+                        ));
+                    }
+                    enum_name = value;
+                    continue;
+                }
+                None => {
+                    return Err(error(
+                        line_number,
+                        format!("Unknown top-level key `{}`", key),
+                    ))
+                }
+            };
+            let duplicate = match key {
+                "name" => route.name.replace(value).is_some(),
+                "pattern" => route.pattern.replace((line_number, value)).is_some(),
+                "component" => route.component.replace((line_number, value)).is_some(),
+                _ => match key.strip_prefix("type.") {
+                    Some(segment) => {
+                        let duplicate = route.types.iter().any(|(_, s, _)| s == segment);
+                        route.types.push((line_number, segment.to_string(), value));
+                        duplicate
+                    }
+                    None => return Err(error(line_number, format!("Unknown route key `{}`", key))),
+                },
+            };
+            if duplicate {
+                return Err(error(
+                    line_number,
+                    format!("`{}` is set twice for this route", key),
+                ));
+            }
+        }
+
+        if let Some(route) = current.take() {
+            routes.push(route.finish()?);
+        }
+        Ok(Self { enum_name, routes })
+    }
+
+    /// The enum the file describes, with the attributes the derive reads
+    pub fn to_derive_input(&self) -> syn::DeriveInput {
+        let name = format_ident!("{}", self.enum_name);
+        let variants = self.routes.iter().map(|route| {
+            let variant = format_ident!("{}", route.name);
+            let pattern = LitStr::new(&route.pattern, Span::call_site());
+            let component = route
+                .component
+                .as_ref()
+                .map(|component| format_ident!("{}", component));
+            let fields = route.fields.iter().map(|(field, ty)| {
+                let field = format_ident!("{}", field);
+                let ty: syn::Type = syn::parse_str(ty).unwrap();
+                quote! { #field: #ty }
+            });
+            quote! {
+                #[route(#pattern #component)]
+                #variant { #(#fields,)* }
+            }
+        });
+
+        syn::parse_quote! {
+            enum #name {
+                #(#variants,)*
+            }
+        }
+    }
+}
+
+/// Remove a `#` comment, ignoring `#` inside strings
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parse a basic TOML string, supporting the `\"` and `\\` escapes
+fn parse_string(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut parsed = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                c @ ('"' | '\\') => parsed.push(c),
+                _ => return None,
+            },
+            '"' => return None,
+            c => parsed.push(c),
+        }
+    }
+    Some(parsed)
+}
+
+/// Expand `routes_from_file!("path")`. The path is relative to the crate root.
+pub fn expand(path: LitStr) -> TokenStream2 {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let full_path = std::path::Path::new(&manifest_dir).join(path.value());
+    let source = match std::fs::read_to_string(&full_path) {
+        Ok(source) => source,
+        Err(err) => {
+            return syn::Error::new_spanned(
+                &path,
+                format!("Could not read {}: {}", full_path.display(), err),
+            )
+            .to_compile_error()
+        }
+    };
+    let file = match RoutesFile::parse(&source) {
+        Ok(file) => file,
+        Err(err) => {
+            return syn::Error::new_spanned(
+                &path,
+                format!("{}:{}: {}", path.value(), err.line, err.message),
+            )
+            .to_compile_error()
+        }
+    };
+
+    let input = file.to_derive_input();
+    // The derive reads the route attributes, but they aren't valid on the enum itself without the derive
+    let mut item = input.clone();
+    if let syn::Data::Enum(data) = &mut item.data {
+        for variant in &mut data.variants {
+            variant.attrs.clear();
+        }
+    }
+    let routable = crate::expand(input.clone());
+    let full_path = full_path.display().to_string();
+
+    quote! {
+        #[derive(Clone, Debug, PartialEq)]
+        #item
+
+        // Rebuild when the routes file changes
+        const _: &str = include_str!(#full_path);
+
+        #routable
+    }
+}
+
+#[test]
+fn parse_routes_file() {
+    let file = RoutesFile::parse(
+        r#"
+        enum = "CatalogRoute" # the name of the enum
+
+        [[route]]
+        name = "Catalog"
+        pattern = "/catalog"
+        component = "CatalogPage"
+
+        [[route]]
+        name = "Product"
+        pattern = "/catalog/(id)/(...rest)"
+        type.id = "u32"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(file.enum_name, "CatalogRoute");
+    assert_eq!(
+        file.routes,
+        [
+            FileRoute {
+                name: "Catalog".to_string(),
+                pattern: "/catalog".to_string(),
+                component: Some("CatalogPage".to_string()),
+                fields: Vec::new(),
+            },
+            FileRoute {
+                name: "Product".to_string(),
+                pattern: "/catalog/(id)/(...rest)".to_string(),
+                component: None,
+                fields: vec![
+                    ("id".to_string(), "u32".to_string()),
+                    ("rest".to_string(), "Vec<String>".to_string())
+                ],
+            },
+        ]
+    );
+    let file =
+        RoutesFile::parse("[[route]]\nname = \"Avatar\"\npattern = \"/avatar/(id).png\"").unwrap();
+    assert_eq!(
+        file.routes[0].fields,
+        [("id".to_string(), "String".to_string())]
+    );
+}
+
+#[test]
+fn routes_file_errors() {
+    let line = |source: &str| RoutesFile::parse(source).unwrap_err().line;
+
+    assert_eq!(line("[[route]]\npattern = \"/\""), 1);
+    assert_eq!(
+        line("[[route]]\nname = \"Home\"\npattern = \"/\"\ntype.id = \"u32\""),
+        4
+    );
+    assert_eq!(
+        RoutesFile::parse(
+            "[[route]]\nname = \"User\"\npattern = \"/(id)\"\ntype.id = \"HashMap<u32>\""
+        )
+        .unwrap_err(),
+        error(
+            4,
+            format!(
+                "'HashMap<u32>' is not a supported segment type. Use one of: {}",
+                ALLOWED_TYPES.join(", ")
+            )
+        )
+    );
+    assert_eq!(line("[[route]]\nname = Home"), 2);
+    assert_eq!(line("\n\nroute = \"/\""), 3);
+    assert_eq!(line("[[route]]\nname = \"Home\"\nname = \"Index\""), 3);
+    assert_eq!(
+        RoutesFile::parse("[[route]]\nname = \"Kind\"\n\npattern = \"/kinds/(type)\"").unwrap_err(),
+        error(
+            4,
+            "The segment name 'type' is a Rust keyword. Try 'type_' instead"
+        )
+    );
+}
@@ -0,0 +1,89 @@
+//! The `ROUTES_HASH` of a routable type, so caches of rendered pages can be invalidated when the routes change.
+//!
+//! The hash covers what decides which page a URL renders and how it is served, in the order the parser tries the
+//! routes, since that order decides between routes that match the same path. Everything else, like documentation
+//! or the order of the arguments of `#[route(...)]`, doesn't change it.
+
+use crate::baseline;
+use crate::route::{Route, RouteSegment};
+use std::fmt::Write;
+use syn::LitStr;
+
+/// The hash of the routes as 16 hex digits
+pub fn routes_hash(routes: &[&Route]) -> String {
+    let mut description = String::new();
+    for route in routes {
+        describe(route, &mut description);
+    }
+    format!("{:016x}", fnv1a(description.as_bytes()))
+}
+
+/// One line per property of the route that is part of the hash. Values are written with `Debug` so a value can't
+/// run into the next property.
+fn describe(route: &Route, out: &mut String) {
+    let _ = writeln!(out, "route {:?}", route.route.value());
+    if let Some(locale) = &route.locale {
+        let _ = writeln!(out, "locale {:?}", locale.value());
+    }
+    for segment in &route.route_segments {
+        match segment {
+            RouteSegment::Static(_) => {}
+            RouteSegment::Dynamic(ident, ty, _) => {
+                let _ = writeln!(out, "segment {ident}: {:?}", baseline::type_name(ty));
+            }
+            RouteSegment::CatchAll(ident, ty) => {
+                let _ = writeln!(out, "catch_all {ident}: {:?}", baseline::type_name(ty));
+            }
+            RouteSegment::Optional(ident, ty) => {
+                let _ = writeln!(out, "optional {ident}: {:?}", baseline::type_name(ty));
+            }
+            RouteSegment::Matrix(ident, ty) => {
+                let _ = writeln!(out, "matrix {ident}: {:?}", baseline::type_name(ty));
+            }
+            RouteSegment::Query(ident, ty) => {
+                let _ = writeln!(out, "query {ident}: {:?}", baseline::type_name(ty));
+            }
+            RouteSegment::Fragment(ident, ty) => {
+                let _ = writeln!(out, "fragment {ident}: {:?}", baseline::type_name(ty));
+            }
+        }
+    }
+    if let Some(action) = &route.action {
+        let action = quote::quote!(#action).to_string().replace(' ', "");
+        let _ = writeln!(out, "action {action}");
+    }
+    if let Some(status) = route.status {
+        let _ = writeln!(out, "status {status}");
+    }
+    if let Some(cache) = &route.cache {
+        let _ = writeln!(out, "cache {:?}", cache.value());
+    }
+    if let Some(feature) = &route.feature {
+        let _ = writeln!(out, "feature {:?}", feature.value());
+    }
+    if let Some(title) = &route.title {
+        let _ = writeln!(out, "title {:?}", title.source());
+    }
+    let mut meta: Vec<_> = route
+        .meta
+        .iter()
+        .map(|(name, value)| (LitStr::value(name), value.source()))
+        .collect();
+    meta.sort();
+    for (name, value) in meta {
+        let _ = writeln!(out, "meta {name:?} {value:?}");
+    }
+}
+
+/// The 64-bit FNV-1a hash, which is stable across compilers and platforms unlike the hasher of the standard library
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[test]
+fn fnv1a_known_values() {
+    assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+}
@@ -0,0 +1,177 @@
+//! `#[derive(RouteSegmentEnum)]`, which lets a fieldless enum be used as a dynamic segment.
+//!
+//! Each variant is written as its kebab-case name, `MostViewed` as `most-viewed`, unless it is renamed with
+//! `#[segment(rename = "...")]`. The derive implements `Display` and `FromStr` on the enum, which is what the
+//! generated route parsers use for dynamic segments.
+
+use crate::route::to_snake_case;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, Lit, LitStr, Meta, NestedMeta};
+
+pub fn expand(input: DeriveInput) -> TokenStream2 {
+    match SegmentEnum::parse(input) {
+        Ok(segment_enum) => segment_enum.to_tokens(),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+struct SegmentEnum {
+    name: Ident,
+    variants: Vec<(Ident, String)>,
+}
+
+impl SegmentEnum {
+    fn parse(input: DeriveInput) -> syn::Result<Self> {
+        let Data::Enum(data) = input.data else {
+            return Err(syn::Error::new_spanned(
+                input.ident,
+                "RouteSegmentEnum can only be derived for enums",
+            ));
+        };
+
+        let mut variants: Vec<(Ident, String)> = Vec::new();
+        for variant in data.variants {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    variant.fields,
+                    "RouteSegmentEnum variants can't have fields",
+                ));
+            }
+            let rename = parse_rename(&variant.attrs)?;
+            let value = match &rename {
+                Some(rename) => rename.value(),
+                None => to_snake_case(&variant.ident.to_string()).replace('_', "-"),
+            };
+            if value.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    rename,
+                    "A segment value can't be empty",
+                ));
+            }
+            if let Some((other, _)) = variants.iter().find(|(_, other)| *other == value) {
+                return Err(syn::Error::new_spanned(
+                    &variant.ident,
+                    format!("'{value}' is already the segment value of {other}"),
+                ));
+            }
+            variants.push((variant.ident, value));
+        }
+
+        Ok(Self {
+            name: input.ident,
+            variants,
+        })
+    }
+
+    fn to_tokens(&self) -> TokenStream2 {
+        let name = &self.name;
+        let idents: Vec<_> = self.variants.iter().map(|(ident, _)| ident).collect();
+        let values: Vec<_> = self.variants.iter().map(|(_, value)| value).collect();
+
+        quote! {
+            impl #name {
+                /// Every variant, in declaration order
+                pub const ALL: &'static [Self] = &[#(Self::#idents),*];
+
+                /// The segment value of every variant, in declaration order
+                pub const SEGMENT_VALUES: &'static [&'static str] = &[#(#values),*];
+            }
+
+            impl ::std::fmt::Display for #name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.write_str(match self {
+                        #(Self::#idents => #values,)*
+                    })
+                }
+            }
+
+            impl ::std::str::FromStr for #name {
+                type Err = UnknownSegmentValue;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    match s {
+                        #(#values => Ok(Self::#idents),)*
+                        _ => Err(UnknownSegmentValue {
+                            value: s.to_string(),
+                            allowed: Self::SEGMENT_VALUES,
+                        }),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn parse_rename(attrs: &[syn::Attribute]) -> syn::Result<Option<LitStr>> {
+    let mut rename = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("segment")) {
+        let Meta::List(list) = attr.parse_meta()? else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "Expected #[segment(rename = \"...\")]",
+            ));
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident("rename") => {
+                    match pair.lit {
+                        Lit::Str(value) => rename = Some(value),
+                        lit => {
+                            return Err(syn::Error::new_spanned(lit, "Expected a string"));
+                        }
+                    }
+                }
+                nested => {
+                    return Err(syn::Error::new_spanned(nested, "Unknown segment option"));
+                }
+            }
+        }
+    }
+    Ok(rename)
+}
+
+#[test]
+fn segment_values() {
+    let input = syn::parse_str::<DeriveInput>(
+        r#"
+        enum SortOrder {
+            Newest,
+            MostViewed,
+            #[segment(rename = "a-z")]
+            Alphabetical,
+        }
+        "#,
+    )
+    .unwrap();
+    let parsed = SegmentEnum::parse(input).unwrap();
+    let values: Vec<_> = parsed
+        .variants
+        .iter()
+        .map(|(_, value)| value.as_str())
+        .collect();
+    assert_eq!(values, ["newest", "most-viewed", "a-z"]);
+
+    for (input, error) in [
+        (
+            "enum A { B { c: u32 } }",
+            "RouteSegmentEnum variants can't have fields",
+        ),
+        (
+            r#"enum A { First, #[segment(rename = "first")] Second }"#,
+            "'first' is already the segment value of First",
+        ),
+        (
+            r#"enum A { #[segment(rename = "")] Empty }"#,
+            "A segment value can't be empty",
+        ),
+        (
+            "struct A;",
+            "RouteSegmentEnum can only be derived for enums",
+        ),
+    ] {
+        let input = syn::parse_str::<DeriveInput>(input).unwrap();
+        let err = SegmentEnum::parse(input).err().unwrap();
+        assert_eq!(err.to_string(), error);
+    }
+}
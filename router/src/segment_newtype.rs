@@ -0,0 +1,115 @@
+//! `#[derive(RouteSegment)]`, which lets a newtype like `struct UserId(u64)` be used as a dynamic segment.
+//!
+//! The derive implements `Display` and `FromStr` by delegating to the wrapped type, so the segment is written and
+//! parsed like the inner value and the `#[route_segment(...)]` options of the route's field work the same way.
+//! Parse errors are wrapped in a generated `<Name>SegmentError` that displays the inner error, so a message still
+//! says what was wrong with the value, like "invalid digit found in string".
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Ident, Type, Visibility};
+
+pub fn expand(input: DeriveInput) -> TokenStream2 {
+    match SegmentNewtype::parse(input) {
+        Ok(newtype) => newtype.to_tokens(),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+struct SegmentNewtype {
+    vis: Visibility,
+    name: Ident,
+    inner: Type,
+}
+
+impl SegmentNewtype {
+    fn parse(input: DeriveInput) -> syn::Result<Self> {
+        if !input.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                input.generics,
+                "RouteSegment can't be derived for generic types",
+            ));
+        }
+        let inner = match input.data {
+            Data::Struct(data) => match data.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    fields.unnamed.into_iter().next().map(|field| field.ty)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(inner) = inner else {
+            return Err(syn::Error::new_spanned(
+                input.ident,
+                "RouteSegment can only be derived for tuple structs with one field, like `struct UserId(u64)`",
+            ));
+        };
+
+        Ok(Self {
+            vis: input.vis,
+            name: input.ident,
+            inner,
+        })
+    }
+
+    fn to_tokens(&self) -> TokenStream2 {
+        let Self { vis, name, inner } = self;
+        let error = format_ident!("{}SegmentError", name);
+        let error_doc = format!("The {name} in a segment did not parse as the value it wraps");
+
+        quote! {
+            #[doc = #error_doc]
+            #[derive(Debug, PartialEq)]
+            #vis struct #error(pub <#inner as ::std::str::FromStr>::Err);
+
+            impl ::std::fmt::Display for #error {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    ::std::fmt::Display::fmt(&self.0, f)
+                }
+            }
+
+            impl ::std::fmt::Display for #name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    ::std::fmt::Display::fmt(&self.0, f)
+                }
+            }
+
+            impl ::std::str::FromStr for #name {
+                type Err = #error;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    <#inner as ::std::str::FromStr>::from_str(s)
+                        .map(Self)
+                        .map_err(#error)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn newtype_shapes() {
+    let parse = |input: &str| {
+        SegmentNewtype::parse(syn::parse_str::<DeriveInput>(input).unwrap())
+            .err()
+            .map(|err| err.to_string())
+    };
+    assert_eq!(parse("pub struct UserId(u64);"), None);
+    assert_eq!(parse("struct Slug(pub String);"), None);
+
+    let tuple_error =
+        "RouteSegment can only be derived for tuple structs with one field, like `struct UserId(u64)`";
+    for input in [
+        "struct Pair(u32, u32);",
+        "struct Named { id: u64 }",
+        "struct Unit;",
+        "enum Id { A(u64) }",
+    ] {
+        assert_eq!(parse(input).as_deref(), Some(tuple_error), "{input}");
+    }
+    assert_eq!(
+        parse("struct Id<T>(T);").as_deref(),
+        Some("RouteSegment can't be derived for generic types")
+    );
+}
@@ -0,0 +1,106 @@
+//! Measure how much code the derive generates for each route, enabled with `#[routable(size_report)]`.
+
+use crate::route::Route;
+use crate::route_tree::RouteTreeSegment;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::Ident;
+
+/// The number of tokens generated for one route
+#[derive(Debug, PartialEq)]
+pub struct RouteSize {
+    pub route_name: String,
+    pub display: usize,
+    pub parser: usize,
+    pub errors: usize,
+    pub render: usize,
+}
+
+impl RouteSize {
+    pub fn measure(route: &Route, enum_name: &Ident) -> Self {
+        let error_name = quote::format_ident!("{}MatchError", enum_name);
+        // Measure the matcher of the route on its own. Routes share static prefixes in the real matcher, so
+        // this counts the shared prefix once for every route that uses it
+        let parser = RouteTreeSegment::build(route.with_patterns())
+            .into_iter()
+            .map(|segment| count_tokens(&segment.to_tokens(enum_name.clone(), error_name.clone())))
+            .sum();
+
+        Self {
+            route_name: route.route_name.to_string(),
+            display: count_tokens(&route.display_match()),
+            parser,
+            errors: count_tokens(&route.error_type(&quote! { pub })),
+            render: count_tokens(&route.routable_match()),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.display + self.parser + self.errors + self.render
+    }
+}
+
+/// Format the sizes as a table with the largest routes first
+pub fn format_report(enum_name: &Ident, mut sizes: Vec<RouteSize>) -> String {
+    sizes.sort_by_key(|size| std::cmp::Reverse(size.total()));
+    let width = sizes
+        .iter()
+        .map(|size| size.route_name.len())
+        .max()
+        .unwrap_or(0)
+        .max("route".len());
+
+    let mut report = format!("note: generated tokens per route of `{}`\n", enum_name);
+    report += &format!(
+        "  {:width$}  {:>7}  {:>7}  {:>7}  {:>7}  {:>7}\n",
+        "route", "display", "parser", "errors", "render", "total"
+    );
+    for size in &sizes {
+        report += &format!(
+            "  {:width$}  {:>7}  {:>7}  {:>7}  {:>7}  {:>7}\n",
+            size.route_name,
+            size.display,
+            size.parser,
+            size.errors,
+            size.render,
+            size.total()
+        );
+    }
+    report
+}
+
+/// Count the tokens in a stream, including the tokens inside groups
+fn count_tokens(tokens: &TokenStream2) -> usize {
+    tokens
+        .clone()
+        .into_iter()
+        .map(|tree| match tree {
+            TokenTree::Group(group) => 1 + count_tokens(&group.stream()),
+            _ => 1,
+        })
+        .sum()
+}
+
+#[test]
+fn largest_routes_first() {
+    let input: syn::ItemEnum = syn::parse_quote! {
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/users/(id)/posts/(post)" Post)]
+            Post { id: u32, post: String },
+        }
+    };
+    let name = input.ident.clone();
+    let sizes: Vec<_> = input
+        .variants
+        .into_iter()
+        .map(|variant| RouteSize::measure(&Route::parse(variant, None).unwrap(), &name))
+        .collect();
+    assert!(sizes[1].total() > sizes[0].total());
+
+    let report = format_report(&name, sizes);
+    let rows: Vec<_> = report.lines().skip(2).collect();
+    assert!(rows[0].trim_start().starts_with("Post"));
+    assert!(rows[1].trim_start().starts_with("Home"));
+}
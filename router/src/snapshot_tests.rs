@@ -0,0 +1,505 @@
+//! Golden-file tests for the generated code.
+//!
+//! Each test expands a routable type and compares the formatted output with `snapshots/<name>.rs`. Run the tests
+//! with `UPDATE_SNAPSHOTS=1` to write the current output instead, then review the diff of the snapshot files.
+
+use std::path::Path;
+
+fn assert_snapshot(name: &str, input: &str) {
+    let input = syn::parse_str::<syn::DeriveInput>(input).unwrap();
+    let expanded = crate::expand(input);
+    let file = syn::parse2::<syn::File>(expanded).unwrap();
+    let formatted = prettyplease::unparse(&file);
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(format!("{}.rs", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &formatted).unwrap();
+        return;
+    }
+
+    let snapshot = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        snapshot == formatted,
+        "The expansion of '{}' changed. Run the tests with UPDATE_SNAPSHOTS=1 to update {} and review the diff.",
+        name,
+        path.display()
+    );
+}
+
+/// The expansion of a routable type as a string, for tests that only check if it contains an error or some code
+pub fn expand_str(input: &str) -> String {
+    let input = syn::parse_str::<syn::DeriveInput>(input).unwrap();
+    crate::expand(input).to_string()
+}
+
+#[test]
+fn static_routes() {
+    assert_snapshot(
+        "static_routes",
+        r#"
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/about/team" Team)]
+            Team {},
+        }
+        "#,
+    );
+}
+
+#[test]
+fn dynamic_routes() {
+    assert_snapshot(
+        "dynamic_routes",
+        r#"
+        enum Route {
+            #[route("/users/(id)" User)]
+            User { id: u32 },
+            #[route("/users/(id)/posts/(post)" Post)]
+            Post { id: u32, post: String },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn suffix_routes() {
+    assert_snapshot(
+        "suffix_routes",
+        r#"
+        enum Route {
+            #[route("/avatar/(file)" File)]
+            File { file: String },
+            #[route("/avatar/(user_id).png" Avatar)]
+            Avatar { user_id: u32 },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn catch_all_routes() {
+    assert_snapshot(
+        "catch_all_routes",
+        r#"
+        enum Route {
+            #[route("/files/(...path)" Files)]
+            Files { path: Vec<String> },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn struct_routes() {
+    assert_snapshot(
+        "struct_routes",
+        r#"
+        #[routable(version = 2, migrate(1 => migrate_v1))]
+        #[route("/search/(query)/(page)/(sort)" Search, status = 200)]
+        struct SearchRoute {
+            query: String,
+            page: u32,
+            sort: String,
+        }
+        "#,
+    );
+}
+
+#[test]
+fn component_paths() {
+    assert_snapshot(
+        "component_paths",
+        r#"
+        enum Route {
+            #[route("/", Home)]
+            Home {},
+            #[route("/blog/(id)", crate::pages::BlogPost, title = "Post {id}")]
+            BlogPost { id: u64 },
+            #[route("/settings" settings::Page)]
+            Settings {},
+            #[route("/live/(id)", Live, hard_navigation)]
+            Live { id: u64 },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn shared_routes() {
+    assert_snapshot(
+        "shared_routes",
+        r#"
+        #[routable(shared = "/(workspace)")]
+        enum Route {
+            #[route("/" Overview)]
+            Overview { workspace: u32 },
+            #[route("/projects/(project)" Project)]
+            Project { workspace: u32, project: String },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn boxed_errors() {
+    assert_snapshot(
+        "boxed_errors",
+        r#"
+        #[routable(boxed_errors, lenient)]
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/users/(id)" User)]
+            User { id: u32 },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn localized_routes() {
+    assert_snapshot(
+        "localized_routes",
+        r#"
+        #[routable(lenient)]
+        enum Route {
+            #[route("/about" About, locale(de = "/ueber-uns", fr = "/a-propos"))]
+            About { locale: String },
+            #[route("/blog/(id)" Post, locale(de = "/de/beitraege/(id)"))]
+            Post { id: u32, locale: String },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn route_aliases() {
+    assert_snapshot(
+        "route_aliases",
+        r#"
+        enum Route {
+            #[route("/blog/(id)" Post)]
+            #[route("/posts/(id)")]
+            #[route("/p/(id)")]
+            Post { id: u32 },
+            #[route("/users/(user)/repos/(repo)" Repo)]
+            #[route("/(repo)/by/(user)")]
+            Repo { user: String, repo: String },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn document_lang_routes() {
+    assert_snapshot(
+        "document_lang_routes",
+        r#"
+        #[routable(locales(en(dir = "ltr"), ar(dir = "rtl"), de))]
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/about" About, title = "About", locale(ar = "/ar/about", de = "/de/ueber-uns"))]
+            About { locale: String },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn deprecated_routes() {
+    assert_snapshot(
+        "deprecated_routes",
+        r#"
+        enum Route {
+            #[route("/dashboard" Dashboard)]
+            Dashboard {},
+            #[route("/old-dashboard" OldDashboard, deprecated = "use /dashboard")]
+            OldDashboard {},
+            #[route("/about" About, deprecated = "use /company", locale(de = "/ueber-uns"))]
+            About { locale: String },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn palette_routes() {
+    assert_snapshot(
+        "palette_routes",
+        r#"
+        enum Route {
+            #[route("/" Home, icon = "home")]
+            Home {},
+            #[route("/settings" Settings, palette = false)]
+            Settings {},
+            #[route("/projects/(id)" Project, icon = "folder", palette_provider = recent_projects)]
+            Project { id: u32 },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn nav_routes() {
+    assert_snapshot(
+        "nav_routes",
+        r#"
+        enum Route {
+            #[route("/" Home, title = "Home")]
+            Home {},
+            #[route("/docs" Docs, order = -1)]
+            Docs {},
+            #[route("/settings" Settings, nav = false)]
+            Settings {},
+            #[route("/projects/(id)" Project)]
+            Project { id: u32 },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn action_routes() {
+    assert_snapshot(
+        "action_routes",
+        r#"
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/old-home", action = to_home, dry_run_safe = true)]
+            OldHome {},
+            #[route("/logout", action = logout)]
+            Logout {},
+        }
+        "#,
+    );
+}
+
+#[test]
+fn normalized_routes() {
+    assert_snapshot(
+        "normalized_routes",
+        r#"
+        #[routable(lenient)]
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/(category)/(id)" Post, normalize = normalize_post)]
+            Post { category: String, id: u32 },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn guarded_routes() {
+    assert_snapshot(
+        "guarded_routes",
+        r#"
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/admin/(section)" Admin, guard = require_admin)]
+            Admin { section: String },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn soft_navigation_routes() {
+    assert_snapshot(
+        "soft_navigation_routes",
+        r#"
+        enum Route {
+            #[route("/photos/(id)" Photo, guard(require_viewer, params_only = false))]
+            Photo { id: u32 },
+            #[route("/albums/(id)" Album, hard_navigation)]
+            Album { id: u32 },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn shared_guard_routes() {
+    assert_snapshot(
+        "shared_guard_routes",
+        r#"
+        #[routable(shared = "/v(version)", shared_guard = check_version)]
+        enum Route {
+            #[route("/users/(id)" User)]
+            User { version: u8, id: u32 },
+            #[route("/avatars/(id).png" Avatar)]
+            Avatar { version: u8, id: u32 },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn fixture_routes() {
+    assert_snapshot(
+        "fixture_routes",
+        r#"
+        #[routable(fixtures)]
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/blog/(id)/(slug)" BlogPost)]
+            BlogPost { id: u64, slug: String },
+            #[route("/files/(...path)" Files)]
+            Files { path: Vec<String> },
+            #[route("/debug" Debug, feature = "devtools")]
+            Debug {},
+        }
+        "#,
+    );
+}
+
+#[test]
+fn matrix_routes() {
+    assert_snapshot(
+        "matrix_routes",
+        r#"
+        enum Route {
+            #[route("/items/(id)" Item)]
+            Item { id: u64 },
+            #[route("/items;(filters)/(id)" FilteredItem)]
+            FilteredItem { filters: MatrixParams, id: u64 },
+            #[route("/variants/(color);(options)" Variant)]
+            Variant { color: String, options: MatrixParams },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn query_routes() {
+    assert_snapshot(
+        "query_routes",
+        r#"
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/search?(query)" Search)]
+            Search { query: SearchQuery },
+            #[route("/users/(id)?(tab)" User)]
+            User { id: u32, tab: String },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn fragment_routes() {
+    assert_snapshot(
+        "fragment_routes",
+        r#"
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/docs/(page)#(section)" Docs)]
+            Docs { page: String, section: Option<String> },
+            #[route("/search?(query)#(result)" Search)]
+            Search { query: String, result: u32 },
+            #[route("/files/(...path)#(line)" File)]
+            File { path: Vec<String>, line: Option<u32> },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn path_options() {
+    assert_snapshot(
+        "path_options",
+        r#"
+        #[routable(trailing_slash = "require", case_insensitive)]
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/About" About)]
+            About {},
+            #[route("/users/(id)?(tab)" User)]
+            User { id: u32, tab: String },
+            #[route("/(...rest)" NotFound)]
+            NotFound { rest: Vec<String> },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn site_map_routes() {
+    assert_snapshot(
+        "site_map_routes",
+        r#"
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/blog" Blog)]
+            Blog {},
+            #[route("/blog/(id)" Post)]
+            Post { id: u32 },
+            #[route("/(...path)" NotFound)]
+            NotFound { path: Vec<String> },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn tuple_routes() {
+    assert_snapshot(
+        "tuple_routes",
+        r#"
+        enum Route {
+            #[route("/" Home)]
+            Home(),
+            #[route("/users/(0)" User)]
+            User(u32),
+            #[route("/posts/(1)/(0)" Post)]
+            Post(String, u32),
+        }
+        "#,
+    );
+}
+
+#[test]
+fn optional_routes() {
+    assert_snapshot(
+        "optional_routes",
+        r#"
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/posts/(?page)" Posts)]
+            Posts { page: Option<u32> },
+            #[route("/tags/(tag)/(?sort)" Tag)]
+            Tag { tag: String, sort: String },
+        }
+        "#,
+    );
+}
+
+#[test]
+fn not_found_routes() {
+    assert_snapshot(
+        "not_found_routes",
+        r#"
+        enum Route {
+            #[route("/" Home)]
+            Home {},
+            #[route("/blog/(id)" BlogPost)]
+            BlogPost { id: u32 },
+            #[route(not_found PageNotFound)]
+            PageNotFound { path: Vec<String> },
+        }
+        "#,
+    );
+}
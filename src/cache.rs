@@ -0,0 +1,292 @@
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A small least-recently-used cache from path strings to parsed routes.
+///
+/// Parsing is pure, so entries are never invalidated; they are only evicted when the cache is full.
+pub struct RouteCache<R> {
+    capacity: usize,
+    // The most recently used entry is at the back
+    entries: VecDeque<(String, R)>,
+    // Asked on a miss before parsing, and filled with the parses of this cache
+    shared: Option<SharedRouteCache<R>>,
+    #[cfg(feature = "devtools")]
+    stats: CacheStats,
+}
+
+/// Hit statistics for a [`RouteCache`].
+#[cfg(feature = "devtools")]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl<R: FromStr + Clone> RouteCache<R> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+            shared: None,
+            #[cfg(feature = "devtools")]
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Ask a cache shared with other routers on a miss, before parsing
+    pub fn with_shared(mut self, shared: Option<SharedRouteCache<R>>) -> Self {
+        self.shared = shared;
+        self
+    }
+
+    /// Parse a path, reusing the result of an earlier parse of the same path if it is cached.
+    /// Failed parses are not cached.
+    pub fn parse(&mut self, path: &str) -> Result<R, R::Err> {
+        if let Some(idx) = self.entries.iter().position(|(p, _)| p == path) {
+            #[cfg(feature = "devtools")]
+            {
+                self.stats.hits += 1;
+            }
+            let entry = self.entries.remove(idx).unwrap();
+            let route = entry.1.clone();
+            self.entries.push_back(entry);
+            return Ok(route);
+        }
+
+        #[cfg(feature = "devtools")]
+        {
+            self.stats.misses += 1;
+        }
+        let route = match &self.shared {
+            Some(shared) => shared.parse(path)?,
+            None => R::from_str(path)?,
+        };
+        if self.capacity > 0 {
+            if self.entries.len() == self.capacity {
+                self.entries.pop_front();
+            }
+            self.entries.push_back((path.to_string(), route.clone()));
+        }
+        Ok(route)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[cfg(feature = "devtools")]
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+/// A parse cache shared by the routers it is given to with [`RouterConfiguration::shared_parse_cache`], like the
+/// routers of the requests a pool of server threads renders.
+///
+/// Each router still has its own [`RouteCache`], which only lives as long as the router; a server creates a router
+/// per request, so that one never hits. The shared cache is keyed by the raw location, path and query, and holds at
+/// most `capacity` routes, evicting the least recently used. It only pays off if a few locations make up most of the
+/// requests, like landing pages. Locations with ids in them are each requested a few times at most, so they push
+/// the popular routes out instead, and parsing a route is already cheap; measure the hit rate, which the `metrics`
+/// feature reports as [`SHARED_CACHE_HITS`](crate::route_metrics::SHARED_CACHE_HITS) and
+/// [`SHARED_CACHE_MISSES`](crate::route_metrics::SHARED_CACHE_MISSES), before relying on it.
+///
+/// [`RouterConfiguration::shared_parse_cache`]: crate::RouterConfiguration
+pub struct SharedRouteCache<R> {
+    inner: Arc<SharedEntries<R>>,
+}
+
+struct SharedEntries<R> {
+    capacity: usize,
+    // Each route with the tick it was last used at
+    entries: Mutex<(HashMap<String, (R, u64)>, u64)>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<R> Clone for SharedRouteCache<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<R: FromStr + Clone + Send + Sync> SharedRouteCache<R> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(SharedEntries {
+                capacity,
+                entries: Mutex::new((HashMap::with_capacity(capacity), 0)),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            }),
+        }
+    }
+}
+
+impl<R: FromStr + Clone> SharedRouteCache<R> {
+    /// Parse a location, reusing the route another router parsed from it if it is cached. Failed parses are not
+    /// cached. The location is parsed without holding the lock.
+    pub fn parse(&self, location: &str) -> Result<R, R::Err> {
+        let cached = {
+            let mut entries = self
+                .inner
+                .entries
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let (entries, tick) = &mut *entries;
+            *tick += 1;
+            entries.get_mut(location).map(|(route, used)| {
+                *used = *tick;
+                route.clone()
+            })
+        };
+        #[cfg(feature = "metrics")]
+        crate::route_metrics::record_shared_cache(cached.is_some());
+        if let Some(route) = cached {
+            self.inner.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(route);
+        }
+
+        self.inner.misses.fetch_add(1, Ordering::Relaxed);
+        let route = R::from_str(location)?;
+        if self.inner.capacity > 0 {
+            let mut entries = self
+                .inner
+                .entries
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let (entries, tick) = &mut *entries;
+            if entries.len() == self.inner.capacity && !entries.contains_key(location) {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, (_, used))| *used)
+                    .map(|(location, _)| location.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+            entries.insert(location.to_string(), (route.clone(), *tick));
+        }
+        Ok(route)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner
+            .entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .0
+            .len()
+    }
+
+    /// How many parses were answered from the cache, by every router sharing it
+    pub fn hits(&self) -> u64 {
+        self.inner.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.inner.misses.load(Ordering::Relaxed)
+    }
+}
+
+#[test]
+fn cache_evicts_least_recently_used() {
+    let mut cache = RouteCache::<u32>::new(2);
+    assert_eq!(cache.parse("1"), Ok(1));
+    assert_eq!(cache.parse("2"), Ok(2));
+    // Touch 1 so that 2 is evicted next
+    assert_eq!(cache.parse("1"), Ok(1));
+    assert_eq!(cache.parse("3"), Ok(3));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.entries.iter().all(|(path, _)| path != "2"));
+
+    // Errors are returned but never cached
+    assert!(cache.parse("-1").is_err());
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn shared_cache() {
+    use crate::{Route, Router, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+
+    let shared = SharedRouteCache::<u32>::new(2);
+    assert_eq!(shared.parse("1"), Ok(1));
+    assert_eq!(shared.parse("2"), Ok(2));
+    assert_eq!(shared.parse("1"), Ok(1));
+    assert_eq!(shared.parse("3"), Ok(3));
+    assert_eq!(shared.len(), 2);
+    assert!(shared.parse("-1").is_err());
+    assert_eq!((shared.hits(), shared.misses()), (1, 4));
+
+    // Threads share the routes they parsed
+    let shared = SharedRouteCache::<u32>::new(8);
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            let shared = shared.clone();
+            scope.spawn(move || assert_eq!(shared.parse("7"), Ok(7)));
+        }
+    });
+    assert_eq!(shared.hits() + shared.misses(), 4);
+    assert_eq!(shared.len(), 1);
+
+    // A router per request, like on a server, parses the first location from the shared cache
+    let shared = SharedRouteCache::<Route>::new(16);
+    for _ in 0..3 {
+        let history = MemoryHistory::with_initial_path("/hello_world/1").unwrap();
+        let config = RouterConfiguration {
+            shared_parse_cache: Some(shared.clone()),
+            ..Default::default()
+        };
+        let router = Router::with_configuration(history, config).unwrap();
+        assert_eq!(router.route, Route::Route3 { dynamic: 1 });
+    }
+    assert_eq!((shared.hits(), shared.misses()), (2, 1));
+}
+
+/// Parse a skewed mix of locations, where a few landing pages make up most of the requests, with and without a
+/// shared cache. Run with `cargo test --release shared_cache_benchmark -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn shared_cache_benchmark() {
+    use crate::Route;
+    use std::time::Instant;
+
+    const LANDING_PAGES: &[&str] = &[
+        "/",
+        "/hello_world",
+        "/search/rust/2/stars",
+        "/docs/getting-started",
+    ];
+    // Nine in ten requests are for a landing page, the rest for articles by id
+    let locations: Vec<String> = (0..200_000u32)
+        .map(|i| match i % 10 {
+            9 => format!("/hello_world/{i}"),
+            n => LANDING_PAGES[n as usize % LANDING_PAGES.len()].to_string(),
+        })
+        .collect();
+
+    let start = Instant::now();
+    for location in &locations {
+        let _ = std::hint::black_box(Route::from_str(location));
+    }
+    let uncached = start.elapsed();
+
+    let shared = SharedRouteCache::<Route>::new(64);
+    let start = Instant::now();
+    for location in &locations {
+        let _ = std::hint::black_box(shared.parse(location));
+    }
+    let cached = start.elapsed();
+
+    let hit_rate = shared.hits() as f64 / locations.len() as f64;
+    println!(
+        "{} locations: {uncached:?} parsing each, {cached:?} with a shared cache ({:.0}% hits)",
+        locations.len(),
+        hit_rate * 100.0
+    );
+}
@@ -0,0 +1,454 @@
+use crate::encoding::decode_query_component;
+use crate::navigation::RouteChange;
+use crate::{Routable, Router};
+use dioxus::prelude::*;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+
+type Subscriber<R> = Box<dyn FnMut(RouteChange<R>)>;
+
+/// Callbacks that run after every navigation of a router, so components can decide if they need to re-render.
+pub struct Subscribers<R> {
+    next_id: usize,
+    subscribers: Vec<(usize, Subscriber<R>)>,
+}
+
+impl<R> Default for Subscribers<R> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            subscribers: Vec::new(),
+        }
+    }
+}
+
+impl<R> Subscribers<R> {
+    pub fn subscribe(&mut self, subscriber: impl FnMut(RouteChange<R>) + 'static) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.subscribers.push((id, Box::new(subscriber)));
+        id
+    }
+
+    fn unsubscribe(&mut self, id: usize) {
+        self.subscribers.retain(|(subscriber, _)| *subscriber != id);
+    }
+
+    /// Tell every subscriber about a navigation of the router.
+    pub fn notify(&mut self, change: RouteChange<R>) {
+        for (_, subscriber) in &mut self.subscribers {
+            subscriber(change.clone());
+        }
+    }
+}
+
+/// Identifies a router so it can still be found when another router with the same route type is nested inside it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RouterId(pub &'static str);
+
+/// A handle to a router, shared with every component below the component that provided it.
+///
+/// Contexts are looked up by type, so routers with different route types never see each other. When routers
+/// with the same route type are nested, the innermost router takes precedence in [`use_router`] and
+/// [`use_route`]. Outer routers of the same type can only be reached by their id with [`use_router_with_id`].
+pub struct RouterContext<R: Routable>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    id: RouterId,
+    router: Rc<RefCell<Router<R>>>,
+    // The router with the same route type that this router shadows
+    parent: Option<Box<RouterContext<R>>>,
+}
+
+impl<R: Routable> Clone for RouterContext<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            router: self.router.clone(),
+            parent: self.parent.clone(),
+        }
+    }
+}
+
+impl<R: Routable> RouterContext<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    pub fn id(&self) -> RouterId {
+        self.id
+    }
+
+    /// The current route of the router.
+    pub fn route(&self) -> R {
+        self.router.borrow().route.clone()
+    }
+
+    pub fn router(&self) -> &Rc<RefCell<Router<R>>> {
+        &self.router
+    }
+
+    /// How many routers with the same route type this router is nested in.
+    pub fn depth(&self) -> usize {
+        std::iter::successors(self.parent.as_deref(), |parent| parent.parent.as_deref()).count()
+    }
+}
+
+/// Routers that outlive the components that provide them, so a provider that is unmounted and mounted again, like
+/// below a suspense or error boundary, reattaches to its history, route and caches instead of starting over.
+///
+/// Provide it with [`use_router_store`] in a component above every router that should survive a remount. Routers
+/// are stored by their id and route type.
+#[derive(Clone, Default)]
+pub struct RouterStore {
+    routers: Rc<RefCell<Vec<(RouterId, TypeId, Rc<dyn Any>)>>>,
+}
+
+impl RouterStore {
+    /// The stored router with the id and the route type `R`.
+    pub fn router<R: Routable + 'static>(&self, id: RouterId) -> Option<Rc<RefCell<Router<R>>>>
+    where
+        <R as FromStr>::Err: std::fmt::Display,
+    {
+        self.routers
+            .borrow()
+            .iter()
+            .find(|(stored, ty, _)| *stored == id && *ty == TypeId::of::<R>())
+            .and_then(|(_, _, router)| router.clone().downcast().ok())
+    }
+
+    /// The stored router with the id, or `router` if there is none or it was made with [`Router::fresh`].
+    fn attach<R: Routable + 'static>(
+        &self,
+        id: RouterId,
+        router: Router<R>,
+    ) -> Rc<RefCell<Router<R>>>
+    where
+        <R as FromStr>::Err: std::fmt::Display,
+    {
+        if !router.fresh {
+            if let Some(stored) = self.router(id) {
+                return stored;
+            }
+        }
+        let router = Rc::new(RefCell::new(router));
+        let mut routers = self.routers.borrow_mut();
+        routers.retain(|(stored, ty, _)| !(*stored == id && *ty == TypeId::of::<R>()));
+        routers.push((id, TypeId::of::<R>(), router.clone()));
+        router
+    }
+}
+
+/// Keep the routers provided below the current component alive when their providers are unmounted.
+pub fn use_router_store(cx: &ScopeState) -> &RouterStore {
+    use_context_provider(cx, RouterStore::default)
+}
+
+/// Provide a router to every component below the current one.
+///
+/// The router is created when the component first renders. If a [`RouterStore`] is provided above it and already
+/// has a router with this id, that router is used instead and the new one is dropped, unless it was made with
+/// [`Router::fresh`].
+pub fn use_router_provider<R: Routable + 'static>(
+    cx: &ScopeState,
+    id: RouterId,
+    init: impl FnOnce() -> Router<R>,
+) -> &RouterContext<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_context_provider(cx, || {
+        let router = init();
+        let router = match cx.consume_context::<RouterStore>() {
+            Some(store) => store.attach(id, router),
+            None => Rc::new(RefCell::new(router)),
+        };
+        RouterContext {
+            id,
+            router,
+            parent: cx.consume_context::<RouterContext<R>>().map(Box::new),
+        }
+    })
+}
+
+/// Provide a router that is also owned outside of the component tree, like by a test harness.
+pub fn use_shared_router_provider<'a, R: Routable + 'static>(
+    cx: &'a ScopeState,
+    id: RouterId,
+    router: &Rc<RefCell<Router<R>>>,
+) -> &'a RouterContext<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_context_provider(cx, || RouterContext {
+        id,
+        router: router.clone(),
+        parent: cx.consume_context::<RouterContext<R>>().map(Box::new),
+    })
+}
+
+/// Get the nearest router with the route type `R`.
+pub fn use_router<R: Routable + 'static>(cx: &ScopeState) -> Option<&RouterContext<R>>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_context(cx)
+}
+
+/// Get the nearest router with the route type `R` and the given id, skipping any routers with a different id.
+pub fn use_router_with_id<R: Routable + 'static>(
+    cx: &ScopeState,
+    id: RouterId,
+) -> Option<&RouterContext<R>>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let mut router = use_router::<R>(cx)?;
+    while router.id != id {
+        router = router.parent.as_deref()?;
+    }
+    Some(router)
+}
+
+/// Get the current route of the nearest router with the route type `R`.
+pub fn use_route<R: Routable + 'static>(cx: &ScopeState) -> Option<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_router::<R>(cx).map(RouterContext::route)
+}
+
+/// Get the route the nearest router with the route type `R` was at before its last navigation.
+///
+/// This is `None` on the first load and after a reload, so it can decide whether to show an in-app back link.
+pub fn use_previous_route<R: Routable + 'static>(cx: &ScopeState) -> Option<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_router::<R>(cx)?.router.borrow().previous().cloned()
+}
+
+/// Get the location the current route of the nearest router with the route type `R` was parsed from, including
+/// query parameters the route doesn't know about.
+pub fn use_current_url<R: Routable + 'static>(cx: &ScopeState) -> Option<Rc<str>>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_router::<R>(cx).map(|router| router.router.borrow().current_url())
+}
+
+/// The value selected by [`use_location_selector`]. Unsubscribes from the router when the component is dropped.
+struct RouteSelection<R: Routable, T>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    id: usize,
+    router: Rc<RefCell<Router<R>>>,
+    selected: Rc<RefCell<T>>,
+}
+
+impl<R: Routable, T> Drop for RouteSelection<R, T>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    fn drop(&mut self) {
+        // The router is only borrowed while it navigates, and components are not dropped during a navigation
+        if let Ok(mut router) = self.router.try_borrow_mut() {
+            router.subscribers.unsubscribe(self.id);
+        }
+    }
+}
+
+/// Select a value from the current route and location of the nearest router with the route type `R`, re-rendering
+/// only when the selected value changes. The location is the one returned by [`Router::current_url`].
+fn use_location_selector<R, T>(
+    cx: &ScopeState,
+    selector: impl Fn(&R, &str) -> T + 'static,
+) -> Option<T>
+where
+    R: Routable + 'static,
+    <R as FromStr>::Err: std::fmt::Display,
+    T: PartialEq + Clone + 'static,
+{
+    let router = use_router::<R>(cx)?.router.clone();
+    let selection = cx.use_hook(|| {
+        let selected = {
+            let router = router.borrow();
+            Rc::new(RefCell::new(selector(&router.route, &router.current_url())))
+        };
+        let update = cx.schedule_update();
+        let id = router.borrow_mut().subscribers.subscribe({
+            let selected = selected.clone();
+            move |change: RouteChange<R>| {
+                let value = selector(change.route, change.url);
+                if *selected.borrow() != value {
+                    *selected.borrow_mut() = value;
+                    update();
+                }
+            }
+        });
+        RouteSelection {
+            id,
+            router,
+            selected,
+        }
+    });
+    let selected = selection.selected.borrow().clone();
+    Some(selected)
+}
+
+/// Select a value from the current route of the nearest router with the route type `R`.
+///
+/// The component only re-renders after a navigation if the selected value changed, so a component that only
+/// needs one segment of the route is not rendered again when the rest of the route changes. The selector is
+/// the one passed on the first render.
+pub fn use_route_selector<R, T>(cx: &ScopeState, selector: impl Fn(&R) -> T + 'static) -> Option<T>
+where
+    R: Routable + 'static,
+    <R as FromStr>::Err: std::fmt::Display,
+    T: PartialEq + Clone + 'static,
+{
+    use_location_selector(cx, move |route: &R, _: &str| selector(route))
+}
+
+/// Get the serialized value of the dynamic segment `name` of the current route, re-rendering only when that value
+/// changes. This is `None` if the current route has no segment with that name.
+pub fn use_segment<R: Routable + 'static>(cx: &ScopeState, name: &'static str) -> Option<String>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_route_selector(cx, move |route: &R| {
+        route
+            .matched_params()
+            .into_iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| value)
+    })
+    .flatten()
+}
+
+/// The query of a location, without the `?`
+fn query_of(url: &str) -> Option<&str> {
+    url.split_once('?').map(|(_, query)| query)
+}
+
+/// The decoded values of every pair in the query with the decoded key `key`, in the order they appear
+fn query_values(url: &str, key: &str) -> Vec<String> {
+    let Some(query) = query_of(url) else {
+        return Vec::new();
+    };
+    query
+        .split('&')
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .filter(|(name, _)| decode_query_component(name).as_deref() == Some(key))
+        .filter_map(|(_, value)| decode_query_component(value))
+        .collect()
+}
+
+/// Get the raw, still encoded query of the current location of the nearest router with the route type `R`, or
+/// `None` if the location has no query.
+///
+/// The component re-renders when the query changes, but not when only the path changes.
+pub fn use_query_raw<R: Routable + 'static>(cx: &ScopeState) -> Option<&str>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let query = use_location_selector(cx, |_: &R, url| query_of(url).map(str::to_string)).flatten();
+    // Keep the query of this render in the scope so it can be borrowed
+    let current = cx.use_hook(|| None);
+    *current = query;
+    current.as_deref()
+}
+
+/// Get every value of the query parameter `key` of the current location of the nearest router with the route type
+/// `R`, decoded with form encoding. For `?tag=a&tag=b` the values of `tag` are `["a", "b"]`. Values that aren't
+/// valid percent-encoding are left out.
+///
+/// The component re-renders when the values of `key` change, but not when the path or other parameters change.
+pub fn use_query_values<R: Routable + 'static>(cx: &ScopeState, key: &'static str) -> Vec<String>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_location_selector(cx, move |_: &R, url| query_values(url, key)).unwrap_or_default()
+}
+
+/// Parse the first value of the query parameter `key` of the current location of the nearest router with the
+/// route type `R`, like `?debug=1`. This is `None` if the parameter is missing.
+///
+/// The component re-renders when the values of `key` change, but not when the path or other parameters change.
+pub fn use_query_value<R, T>(cx: &ScopeState, key: &'static str) -> Option<Result<T, T::Err>>
+where
+    R: Routable + 'static,
+    <R as FromStr>::Err: std::fmt::Display,
+    T: FromStr,
+{
+    use_query_values::<R>(cx, key)
+        .into_iter()
+        .next()
+        .map(|value| value.parse())
+}
+
+#[test]
+fn query_lookup() {
+    let url = "/search?tag=a%20b&debug&tag=c+d&other=1&tag=%FF";
+    assert_eq!(
+        query_of(url),
+        Some("tag=a%20b&debug&tag=c+d&other=1&tag=%FF")
+    );
+    assert_eq!(query_values(url, "tag"), ["a b", "c d"]);
+    assert_eq!(query_values(url, "debug"), [""]);
+    assert!(query_values(url, "missing").is_empty());
+    assert!(query_values("/search", "tag").is_empty());
+}
+
+#[test]
+fn nested_routers() {
+    use crate::{Route, SettingsRoute};
+    use dioxus_router_core::history::MemoryHistory;
+
+    fn new_router<R: Routable>(path: &str) -> Router<R>
+    where
+        <R as FromStr>::Err: std::fmt::Display + std::fmt::Debug,
+    {
+        Router::new(MemoryHistory::with_initial_path(path).unwrap()).unwrap()
+    }
+
+    #[allow(non_snake_case)]
+    fn Outer(cx: Scope) -> Element {
+        use_router_provider(cx, RouterId("outer"), || {
+            new_router::<Route>("/hello_world/1")
+        });
+        render! { Inner {} }
+    }
+
+    #[allow(non_snake_case)]
+    fn Inner(cx: Scope) -> Element {
+        use_router_provider(cx, RouterId("inner"), || new_router::<Route>("/1/2"));
+        use_router_provider(cx, RouterId("settings"), || {
+            new_router::<SettingsRoute>("/settings/profile")
+        });
+        render! { Leaf {} }
+    }
+
+    #[allow(non_snake_case)]
+    fn Leaf(cx: Scope) -> Element {
+        let nearest = use_route::<Route>(cx).unwrap();
+        let outer = use_router_with_id::<Route>(cx, RouterId("outer"))
+            .unwrap()
+            .route();
+        let settings = use_route::<SettingsRoute>(cx).unwrap();
+        render! { "{nearest} {outer} {settings}" }
+    }
+
+    let mut vdom = VirtualDom::new(Outer);
+    let _ = vdom.rebuild();
+    assert_eq!(
+        dioxus_ssr::render(&vdom),
+        "/1/2 /hello_world/1 /settings/profile"
+    );
+}
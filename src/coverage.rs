@@ -0,0 +1,247 @@
+//! Which routes a router has matched, to find the routes nothing navigates to anymore.
+//!
+//! With the `devtools` feature every router keeps a bit for each pattern of [`Routable::route_patterns`], set the
+//! first time a route with that pattern becomes the current route. Dry runs don't count. The bits are indexed by
+//! the generated route table, so they follow the routes as they are added and removed.
+//!
+//! In a debug build with the `web` feature the bits are kept in `localStorage`, so the coverage adds up over reloads.
+//! They are stored under a key with a hash of the patterns, so a changed route table starts over instead of reading
+//! the bits of other routes. [`coverage_panel`] lists the routes that were never matched. Test suites collect the
+//! coverage of every [`TestRouter`](crate::router_test::TestRouter) into one [`RouteCoverage`] with
+//! [`RouteCoverage::merge`] and check it at the end.
+
+use crate::context::RouterContext;
+use crate::Routable;
+use dioxus::prelude::*;
+use std::str::FromStr;
+
+/// The class of the list [`coverage_panel`] renders
+pub const COVERAGE_CLASS: &str = "router-coverage";
+
+/// Where the router keeps the coverage between runs of the app, `localStorage` in a browser. Anything else, like a
+/// stub in tests, can stand in for it.
+pub trait CoverageStorage {
+    fn load(&self, key: &str) -> Option<String>;
+    fn save(&mut self, key: &str, value: &str);
+}
+
+#[cfg(feature = "web")]
+impl CoverageStorage for web_sys::Storage {
+    fn load(&self, key: &str) -> Option<String> {
+        self.get_item(key).ok().flatten()
+    }
+
+    fn save(&mut self, key: &str, value: &str) {
+        let _ = self.set_item(key, value);
+    }
+}
+
+/// The `localStorage` of the page in debug builds. Release builds don't keep the coverage.
+#[cfg(feature = "web")]
+pub fn browser_coverage_storage() -> Option<Box<dyn CoverageStorage>> {
+    if !cfg!(debug_assertions) {
+        return None;
+    }
+    let storage = web_sys::window()?.local_storage().ok()??;
+    Some(Box::new(storage))
+}
+
+/// A bit for every pattern of a route type, set once a route with that pattern was matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteCoverage {
+    patterns: &'static [&'static str],
+    visited: Vec<u64>,
+}
+
+impl RouteCoverage {
+    /// The coverage of `R` with no route matched yet
+    pub fn new<R: Routable>() -> Self
+    where
+        <R as FromStr>::Err: std::fmt::Display,
+    {
+        let patterns = R::route_patterns();
+        Self {
+            patterns,
+            visited: vec![0; patterns.len().div_ceil(64)],
+        }
+    }
+
+    /// Set the bit of a pattern. Returns if it wasn't set before; patterns that aren't in the table are ignored.
+    pub fn record(&mut self, pattern: &str) -> bool {
+        let Some(index) = self.patterns.iter().position(|other| *other == pattern) else {
+            return false;
+        };
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let new = self.visited[word] & bit == 0;
+        self.visited[word] |= bit;
+        new
+    }
+
+    /// Every pattern in the order the parser tries them, and if a route with it was matched
+    pub fn coverage(&self) -> Vec<(&'static str, bool)> {
+        self.patterns
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| {
+                (
+                    *pattern,
+                    self.visited[index / 64] & (1 << (index % 64)) != 0,
+                )
+            })
+            .collect()
+    }
+
+    /// The patterns no route was matched with
+    pub fn unvisited(&self) -> Vec<&'static str> {
+        self.coverage()
+            .into_iter()
+            .filter_map(|(pattern, visited)| (!visited).then_some(pattern))
+            .collect()
+    }
+
+    /// Add the routes another router of the same type matched
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.patterns, other.patterns,
+            "Only the coverage of the same route type can be merged"
+        );
+        for (word, other) in self.visited.iter_mut().zip(&other.visited) {
+            *word |= other;
+        }
+    }
+
+    /// The key the coverage is stored under, with a hash of the patterns
+    pub fn storage_key(&self) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in self.patterns.join("\n").bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        format!("router-coverage-{hash:016x}")
+    }
+
+    /// The bits as hex words separated by commas
+    pub fn to_storage(&self) -> String {
+        let words: Vec<_> = self
+            .visited
+            .iter()
+            .map(|word| format!("{word:x}"))
+            .collect();
+        words.join(",")
+    }
+
+    /// Add the bits of [`RouteCoverage::to_storage`]. A value with a different number of words is ignored.
+    pub fn load(&mut self, value: &str) {
+        let words: Result<Vec<u64>, _> = value
+            .split(',')
+            .map(|word| u64::from_str_radix(word, 16))
+            .collect();
+        match words {
+            Ok(words) if words.len() == self.visited.len() => {
+                for (word, stored) in self.visited.iter_mut().zip(words) {
+                    *word |= stored;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A devtools panel with the patterns the nearest router with the route type `R` never matched.
+pub fn coverage_panel<R: Routable + 'static>(cx: Scope) -> Element
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let context = cx.consume_context::<RouterContext<R>>()?;
+    let unvisited = context.router().borrow().route_coverage().unvisited();
+    render! {
+        ul { class: COVERAGE_CLASS,
+            unvisited.into_iter().map(|pattern| rsx! { li { "{pattern}" } })
+        }
+    }
+}
+
+#[test]
+fn route_coverage() {
+    use crate::router_test::TestRouter;
+    use crate::{Route, Router, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let mut coverage = RouteCoverage::new::<Route>();
+    assert_eq!(coverage.coverage().len(), Route::ROUTE_PATTERNS.len());
+    assert!(coverage.record("/hello_world"));
+    assert!(!coverage.record("/hello_world"));
+    assert!(!coverage.record("/not/a/pattern"));
+    assert!(!coverage.unvisited().contains(&"/hello_world"));
+    assert_eq!(coverage.unvisited().len(), Route::ROUTE_PATTERNS.len() - 1);
+
+    // The suite collects the coverage of every test router
+    let mut suite = RouteCoverage::new::<Route>();
+    let mut router = TestRouter::<Route>::new("/hello_world/1");
+    router.navigate(Route::Route5 {});
+    suite.merge(&router.coverage());
+    let visited: Vec<_> = suite
+        .coverage()
+        .into_iter()
+        .filter_map(|(pattern, visited)| visited.then_some(pattern))
+        .collect();
+    assert_eq!(visited.len(), 2);
+    assert!(visited.contains(&"/hello_world/(dynamic)"), "{visited:?}");
+    assert!(visited.contains(&"/"), "{visited:?}");
+
+    let mut stored = RouteCoverage::new::<Route>();
+    stored.load(&suite.to_storage());
+    assert_eq!(stored, suite);
+    stored.load("not hex");
+    assert_eq!(stored, suite);
+
+    // The router keeps the coverage in its storage and picks it up again on the next start
+    #[derive(Clone, Default)]
+    struct MemoryStorage(Rc<RefCell<Vec<(String, String)>>>);
+
+    impl CoverageStorage for MemoryStorage {
+        fn load(&self, key: &str) -> Option<String> {
+            let entries = self.0.borrow();
+            entries
+                .iter()
+                .find(|(other, _)| other == key)
+                .map(|(_, value)| value.clone())
+        }
+
+        fn save(&mut self, key: &str, value: &str) {
+            let mut entries = self.0.borrow_mut();
+            entries.retain(|(other, _)| other != key);
+            entries.push((key.to_string(), value.to_string()));
+        }
+    }
+
+    let storage = MemoryStorage::default();
+    let router = |path: &str| {
+        let history = MemoryHistory::with_initial_path(path).unwrap();
+        let config = RouterConfiguration {
+            coverage_storage: Some(Box::new(storage.clone())),
+            ..Default::default()
+        };
+        Router::<Route>::with_configuration(history, config).unwrap()
+    };
+    router("/hello_world/1");
+    let coverage = router("/").coverage();
+    assert!(
+        coverage.contains(&("/hello_world/(dynamic)", true)),
+        "{coverage:?}"
+    );
+    assert!(coverage.contains(&("/", true)), "{coverage:?}");
+    assert!(storage.load(&suite.storage_key()).is_some());
+
+    let dry_run = Router::<Route>::with_configuration(
+        MemoryHistory::with_initial_path("/hello_world").unwrap(),
+        RouterConfiguration {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(dry_run.coverage().iter().all(|(_, visited)| !visited));
+}
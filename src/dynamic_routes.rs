@@ -0,0 +1,157 @@
+//! Routes registered at runtime next to the derived routes, for plugins the app doesn't know about when it is
+//! compiled.
+//!
+//! The router component tries the derived routes first. A path that none of them parse is matched against the
+//! dynamic routes in registration order before the not found page is rendered.
+
+use crate::pattern::{Params, RoutePattern};
+use crate::Routable;
+use dioxus::prelude::*;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// A route registered at runtime.
+#[derive(Clone)]
+pub struct DynamicRoute {
+    pub pattern: RoutePattern,
+    pub render: Component<DynamicRouteProps>,
+}
+
+/// The props of the component of a [`DynamicRoute`].
+#[derive(Props, PartialEq)]
+pub struct DynamicRouteProps {
+    /// The values of the dynamic segments of the path
+    pub params: Params,
+}
+
+/// The reason a dynamic route was not registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegisterError {
+    /// The pattern matches the same paths as a derived route with this pattern, which is always tried first
+    ConflictsWithRoute(&'static str),
+    /// The pattern matches the same paths as a dynamic route registered before, at this index
+    ConflictsWithDynamicRoute(usize),
+}
+
+impl std::fmt::Display for RegisterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConflictsWithRoute(pattern) => {
+                write!(
+                    f,
+                    "The pattern matches the same paths as the route '{pattern}'"
+                )
+            }
+            Self::ConflictsWithDynamicRoute(index) => write!(
+                f,
+                "The pattern matches the same paths as dynamic route {index}"
+            ),
+        }
+    }
+}
+
+/// The dynamic routes of a router. Cloning is cheap, and clones compare equal until one of them registers a route.
+#[derive(Clone, Default)]
+pub struct DynamicRoutes {
+    routes: Rc<Vec<DynamicRoute>>,
+}
+
+impl PartialEq for DynamicRoutes {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.routes, &other.routes)
+    }
+}
+
+impl DynamicRoutes {
+    /// Register a route next to the derived routes of `R`. Routes whose pattern matches the same paths as a derived
+    /// route or an earlier dynamic route are rejected, since they could never be reached.
+    pub fn register<R: Routable>(&mut self, route: DynamicRoute) -> Result<(), RegisterError>
+    where
+        <R as FromStr>::Err: std::fmt::Display,
+    {
+        if let Some(pattern) = R::route_patterns().iter().find(|pattern| {
+            RoutePattern::parse(pattern).is_ok_and(|pattern| pattern.conflicts_with(&route.pattern))
+        }) {
+            return Err(RegisterError::ConflictsWithRoute(pattern));
+        }
+        if let Some(index) = self
+            .routes
+            .iter()
+            .position(|other| other.pattern.conflicts_with(&route.pattern))
+        {
+            return Err(RegisterError::ConflictsWithDynamicRoute(index));
+        }
+        Rc::make_mut(&mut self.routes).push(route);
+        Ok(())
+    }
+
+    /// The first dynamic route that matches the path, and the values of its segments
+    pub fn match_path(&self, path: &str) -> Option<(&DynamicRoute, Params)> {
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        self.routes
+            .iter()
+            .find_map(|route| Some((route, route.pattern.matches(path)?)))
+    }
+
+    /// The patterns of every dynamic route, in registration order
+    pub fn patterns(&self) -> impl Iterator<Item = &RoutePattern> {
+        self.routes.iter().map(|route| &route.pattern)
+    }
+}
+
+#[test]
+fn dynamic_routes() {
+    use crate::pattern::ParamValue;
+    use crate::{Route, RouterProps};
+
+    #[allow(non_snake_case)]
+    fn PluginStats(cx: Scope<DynamicRouteProps>) -> Element {
+        let Some(ParamValue::Segment(name)) = cx.props.params.get("name") else {
+            return None;
+        };
+        render! { "stats for {name}" }
+    }
+
+    let route = |pattern| DynamicRoute {
+        pattern: RoutePattern::parse(pattern).unwrap(),
+        render: PluginStats,
+    };
+    let mut dynamic_routes = DynamicRoutes::default();
+    dynamic_routes
+        .register::<Route>(route("/plugins/stats/(name)"))
+        .unwrap();
+    assert_eq!(
+        dynamic_routes.register::<Route>(route("/hello_world/(id)")),
+        Err(RegisterError::ConflictsWithRoute("/hello_world/(dynamic)"))
+    );
+    assert_eq!(
+        dynamic_routes.register::<Route>(route("/plugins/stats/(other)")),
+        Err(RegisterError::ConflictsWithDynamicRoute(0))
+    );
+    let patterns: Vec<_> = dynamic_routes.patterns().collect();
+    assert_eq!(
+        patterns,
+        [&RoutePattern::parse("/plugins/stats/(name)").unwrap()]
+    );
+
+    let href = patterns[0].fill(&[("name", "disk usage")]).unwrap();
+    assert_eq!(href, "/plugins/stats/disk%20usage");
+
+    let render = |current_route: &str| {
+        let mut vdom = VirtualDom::new_with_props(
+            Route::comp,
+            RouterProps {
+                current_route: current_route.to_string(),
+                max_suggestions: 3,
+                dynamic_routes: dynamic_routes.clone(),
+                legacy_fallback: None,
+            },
+        );
+        let _ = vdom.rebuild();
+        dioxus_ssr::render(&vdom)
+    };
+    assert_eq!(render(&href), "stats for disk usage");
+    // Derived routes are tried first
+    assert!(render("/hello_world/1").contains("dynamic: 1"));
+    assert!(render("/plugins/other/cpu").contains("Route did not match"));
+}
@@ -0,0 +1,318 @@
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+/// The characters a dynamic segment is written with unencoded, set per field with
+/// `#[route_segment(encode = "...")]`. Segments are decoded the same way whatever the set, so every set parses back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeSet {
+    /// Every character RFC 3986 allows in a path segment: letters, digits, `-._~`, `!$&'()*+,;=`, `:` and `@`. The
+    /// default.
+    Pchar,
+    /// Only letters, digits and `-._~`
+    Unreserved,
+    /// Nothing is encoded, for values that must reach the server exactly as they are. The value must not contain
+    /// `/`, `?` or `#`, which would end the segment, and a `%` in it is decoded when the path is parsed.
+    None,
+}
+
+impl EncodeSet {
+    /// If the character can appear unencoded. Everything else, including non-ASCII characters, is percent-encoded
+    /// as UTF-8.
+    fn keeps(self, c: char) -> bool {
+        match self {
+            Self::Pchar => c.is_ascii_alphanumeric() || "-._~!$&'()*+,;=:@".contains(c),
+            Self::Unreserved => c.is_ascii_alphanumeric() || "-._~".contains(c),
+            Self::None => true,
+        }
+    }
+}
+
+/// Displays a value as a percent-encoded path segment without allocating an intermediate string.
+pub struct EncodedSegment<'a, T: ?Sized>(pub &'a T);
+
+impl<T: fmt::Display + ?Sized> fmt::Display for EncodedSegment<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        EncodedSegmentWith(self.0, EncodeSet::Pchar).fmt(f)
+    }
+}
+
+/// Displays a value as a path segment, percent-encoding the characters outside of an [`EncodeSet`].
+pub struct EncodedSegmentWith<'a, T: ?Sized>(pub &'a T, pub EncodeSet);
+
+impl<T: fmt::Display + ?Sized> fmt::Display for EncodedSegmentWith<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Encoder<'a, 'b>(&'a mut fmt::Formatter<'b>, EncodeSet);
+
+        impl Write for Encoder<'_, '_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                debug_assert!(
+                    self.1 != EncodeSet::None || !s.contains(['/', '?', '#']),
+                    "The segment '{s}' is written without encoding, but it contains '/', '?' or '#'"
+                );
+                let mut rest = s;
+                while let Some((i, c)) = rest.char_indices().find(|&(_, c)| !self.1.keeps(c)) {
+                    self.0.write_str(&rest[..i])?;
+                    for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                        write!(self.0, "%{:02X}", byte)?;
+                    }
+                    rest = &rest[i + c.len_utf8()..];
+                }
+                self.0.write_str(rest)
+            }
+        }
+
+        write!(Encoder(f, self.1), "{}", self.0)
+    }
+}
+
+/// Percent-decode a path segment. Unlike [`decode_query_component`], `+` is an ordinary character in a path.
+///
+/// Segments from clients that send raw UTF-8 pass through unchanged, and `%` signs that don't start a valid escape
+/// are kept as they are. Returns `None` if the decoded bytes are not valid UTF-8.
+pub fn decode_path_segment(segment: &str) -> Option<Cow<'_, str>> {
+    if !segment.contains('%') {
+        return Some(Cow::Borrowed(segment));
+    }
+
+    let mut bytes = Vec::with_capacity(segment.len());
+    let raw = segment.as_bytes();
+    let mut i = 0;
+    while i < raw.len() {
+        match escaped_byte(raw, i) {
+            Some(byte) => {
+                bytes.push(byte);
+                i += 3;
+            }
+            None => {
+                bytes.push(raw[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok().map(Cow::Owned)
+}
+
+/// The byte escaped at `i`, if a `%` followed by two hex digits starts there
+fn escaped_byte(raw: &[u8], i: usize) -> Option<u8> {
+    // Both characters must be hex digits. `u8::from_str_radix` would also accept a sign, like in `%+1`
+    let hex_digit = |i: usize| (*raw.get(i)? as char).to_digit(16);
+    if raw[i] != b'%' {
+        return None;
+    }
+    Some((hex_digit(i + 1)? * 16 + hex_digit(i + 2)?) as u8)
+}
+
+/// If the segment has a `%` that doesn't start an escape, like in `%GG`. The decoder keeps those as they are.
+pub fn has_invalid_escape(segment: &str) -> bool {
+    let raw = segment.as_bytes();
+    (0..raw.len()).any(|i| raw[i] == b'%' && escaped_byte(raw, i).is_none())
+}
+
+/// Why a dynamic segment couldn't be decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A `%` that isn't followed by two hex digits, like in `%GG`
+    InvalidPercentEncoding,
+    /// The decoded bytes aren't valid UTF-8, like `%E0%A4`
+    InvalidUtf8,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPercentEncoding => write!(f, "a '%' is not followed by two hex digits"),
+            Self::InvalidUtf8 => write!(f, "it is not valid UTF-8 after percent-decoding"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Why a dynamic segment failed to parse: its value couldn't be decoded, which usually means the client sent a
+/// malformed path, or the decoded value isn't a valid `E`, which usually means the path is for another route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentError<E> {
+    DecodeError(DecodeError),
+    ParseError(E),
+}
+
+impl<E> SegmentError<E> {
+    /// The error for a decoded `segment` that its type failed to parse. Invalid escapes are kept by the decoder, so
+    /// a segment with one that doesn't parse is reported as malformed rather than as a parse error.
+    pub fn from_parse_error(segment: &str, err: E) -> Self {
+        match has_invalid_escape(segment) {
+            true => Self::DecodeError(DecodeError::InvalidPercentEncoding),
+            false => Self::ParseError(err),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for SegmentError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DecodeError(err) => write!(f, "could not be decoded: {err}"),
+            Self::ParseError(err) => write!(f, "did not match: {err}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for SegmentError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::DecodeError(err) => Some(err),
+            Self::ParseError(err) => Some(err),
+        }
+    }
+}
+
+/// Characters that can appear unencoded in a query value. `&`, `=`, `+` and `#` are always encoded so the value
+/// can't be confused with the structure of the query.
+fn is_query_value_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b"-._~/:@!$'()*,;?".contains(&c)
+}
+
+/// Percent-encode a value so it can be used as a key or value in a query string.
+///
+/// Spaces are encoded as `%20`, never as `+`, so the output decodes to the same value with the path decoder as
+/// well. A literal `+` is encoded as `%2B`.
+pub fn encode_query_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if is_query_value_char(byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Decode a key or value from a query string. `+` decodes to a space, following form encoding.
+///
+/// Returns `None` if the value contains an invalid percent-encoding or does not decode to valid UTF-8.
+pub fn decode_query_component(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut iter = value.bytes();
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'%' => {
+                let high = (iter.next()? as char).to_digit(16)?;
+                let low = (iter.next()? as char).to_digit(16)?;
+                bytes.push((high * 16 + low) as u8);
+            }
+            b'+' => bytes.push(b' '),
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Sort the parameters of a query by their decoded key, so the same parameters always make the same query, like for
+/// a CDN that caches `?a=1&b=2` and `?b=2&a=1` separately. Parameters with the same key keep their order, since the
+/// page may read them as a list, and empty parameters are dropped.
+pub fn sort_query(query: &str) -> String {
+    let mut pairs: Vec<&str> = query.split('&').filter(|pair| !pair.is_empty()).collect();
+    pairs.sort_by_cached_key(|pair| {
+        let key = pair.split('=').next().unwrap_or_default();
+        decode_query_component(key).unwrap_or_else(|| key.to_string())
+    });
+    pairs.join("&")
+}
+
+#[test]
+fn sorted_queries() {
+    assert_eq!(sort_query("b=2&a=1"), "a=1&b=2");
+    assert_eq!(sort_query("tag=x&%61=1&tag=a&&b"), "%61=1&b&tag=x&tag=a");
+    let sorted = sort_query("utm_source=x&gclid=1&page=2");
+    assert_eq!(sort_query(&sorted), sorted);
+    assert_eq!(sort_query(""), "");
+}
+
+#[test]
+fn query_component_round_trip() {
+    for value in ["/settings/profile", "a b&c=d", "100%+", "ünïcödé"] {
+        assert_eq!(
+            decode_query_component(&encode_query_component(value)).as_deref(),
+            Some(value)
+        );
+    }
+    assert_eq!(encode_query_component("/a b"), "/a%20b");
+    assert_eq!(decode_query_component("a+b").as_deref(), Some("a b"));
+    assert_eq!(decode_query_component("%E0%A4"), None);
+    assert_eq!(decode_query_component("%G0"), None);
+}
+
+#[test]
+fn path_segment_round_trip() {
+    for value in ["你好世界", "🦀🚀", "a b/c%d", "plain"] {
+        let encoded = EncodedSegment(value).to_string();
+        assert!(encoded.is_ascii());
+        assert_eq!(decode_path_segment(&encoded).as_deref(), Some(value));
+    }
+    assert_eq!(EncodedSegment("ü").to_string(), "%C3%BC");
+    assert_eq!(decode_path_segment("你好").as_deref(), Some("你好"));
+    assert_eq!(decode_path_segment("100%").as_deref(), Some("100%"));
+    assert_eq!(decode_path_segment("%E0%A4"), None);
+}
+
+#[test]
+fn plus_in_path_and_query() {
+    assert_eq!(decode_path_segment("a+b").as_deref(), Some("a+b"));
+    assert_eq!(decode_path_segment("a%20b").as_deref(), Some("a b"));
+    assert_eq!(decode_path_segment("a%2Bb").as_deref(), Some("a+b"));
+    assert_eq!(EncodedSegment("a+b c").to_string(), "a+b%20c");
+
+    assert_eq!(decode_query_component("a+b").as_deref(), Some("a b"));
+    assert_eq!(decode_query_component("a%20b").as_deref(), Some("a b"));
+    assert_eq!(decode_query_component("a%2Bb").as_deref(), Some("a+b"));
+    assert_eq!(encode_query_component("a+b c"), "a%2Bb%20c");
+}
+
+#[test]
+fn malformed_path_escapes() {
+    assert_eq!(decode_path_segment("%+1%-1").as_deref(), Some("%+1%-1"));
+    assert_eq!(decode_path_segment("100%").as_deref(), Some("100%"));
+    assert_eq!(decode_path_segment("%4").as_deref(), Some("%4"));
+    assert_eq!(decode_path_segment("%é1").as_deref(), Some("%é1"));
+    assert_eq!(decode_path_segment("a%00b").as_deref(), Some("a\0b"));
+    assert_eq!(decode_path_segment("%252525").as_deref(), Some("%2525"));
+    assert_eq!(decode_path_segment("%FF%FE"), None);
+
+    assert!(has_invalid_escape("%GG"));
+    assert!(has_invalid_escape("100%"));
+    assert!(has_invalid_escape("%2%41"));
+    assert!(!has_invalid_escape("%41%2f"));
+    assert_eq!(
+        SegmentError::from_parse_error("%GG", ()),
+        SegmentError::DecodeError(DecodeError::InvalidPercentEncoding)
+    );
+    assert_eq!(
+        SegmentError::from_parse_error("abc", ()),
+        SegmentError::ParseError(())
+    );
+}
+
+#[test]
+fn encode_sets() {
+    let value = "a:b,c d";
+    assert_eq!(EncodedSegment(value).to_string(), "a:b,c%20d");
+    assert_eq!(
+        EncodedSegmentWith(value, EncodeSet::Unreserved).to_string(),
+        "a%3Ab%2Cc%20d"
+    );
+    assert_eq!(
+        EncodedSegmentWith(value, EncodeSet::None).to_string(),
+        value
+    );
+    for set in [EncodeSet::Pchar, EncodeSet::Unreserved, EncodeSet::None] {
+        let encoded = EncodedSegmentWith(value, set).to_string();
+        assert_eq!(decode_path_segment(&encoded).as_deref(), Some(value));
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "contains '/', '?' or '#'")]
+fn unencoded_segment_with_slash() {
+    EncodedSegmentWith("a/b", EncodeSet::None).to_string();
+}
@@ -0,0 +1,274 @@
+//! Explain what the router would do with a path, step by step, for support tools that look at the urls users report.
+//!
+//! Enabled with the `diagnostics` feature. An [`Explanation`] keeps the outcome of every route pattern that was
+//! tried, which a browser build never needs.
+
+use crate::not_found::NotFoundContext;
+use crate::pattern::RoutePattern;
+use crate::{link, Routable, Router};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// What the router would do with a path, returned by [`Router::explain`].
+///
+/// `Display` writes the explanation as plain text, one step per line, and `Serialize` as JSON for tools.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Explanation {
+    /// The path that was explained
+    pub path: String,
+    pub steps: Vec<ExplanationStep>,
+    pub outcome: ExplanationOutcome,
+}
+
+/// One step of an [`Explanation`], in the order the router takes them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum ExplanationStep {
+    /// A rewrite rule was checked. The router stops at the first rule that fires, so later rules aren't listed
+    Rewrite {
+        rule: String,
+        rewritten: Option<String>,
+    },
+    /// The path is under an excluded prefix, so no route was tried
+    Excluded { prefix: String },
+    /// A route pattern was matched against the path. `diverged_at` is the index of the segment of the pattern where
+    /// the path stopped matching, without the root `/`
+    Candidate {
+        pattern: &'static str,
+        matched: bool,
+        diverged_at: Option<usize>,
+        reason: Option<String>,
+    },
+}
+
+/// Where the router would end up.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum ExplanationOutcome {
+    /// The path parsed as the route with this pattern. `canonical` is the path of the route, which the router
+    /// replaces the location with if [`RouterConfiguration::canonicalize`](crate::RouterConfiguration) is set and
+    /// it differs
+    Matched {
+        pattern: &'static str,
+        params: Vec<(&'static str, String)>,
+        canonical: String,
+    },
+    /// No route parsed the path. `closest` holds the patterns closest to it, closest first
+    Failed {
+        error: String,
+        closest: Vec<&'static str>,
+    },
+}
+
+impl<R: Routable> Router<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Explain how the router would parse `path` when the history navigated to it: the rewrite rules it checks, the
+    /// route patterns it tries and the route it ends up at, or why it fails.
+    ///
+    /// The patterns are matched like a [`RoutePattern`], with every segment as a string, and the route comes from the
+    /// parser of `R`. A path can match a pattern and still fail to parse, like a number segment with letters.
+    /// Guards and actions are not run; see [`Router::resolve`] for where a navigation would end.
+    pub fn explain(&self, path: &str) -> Explanation {
+        let mut steps = Vec::new();
+        let (location, query) = match path.split('#').next().unwrap_or_default().split_once('?') {
+            Some((location, query)) => (location, Some(query)),
+            None => (path.split('#').next().unwrap_or_default(), None),
+        };
+
+        let mut rewritten = location.to_string();
+        for rule in &self.rewrites {
+            let applied = rule.apply(location);
+            steps.push(ExplanationStep::Rewrite {
+                rule: rule.to_string(),
+                rewritten: applied.clone(),
+            });
+            if let Some(applied) = applied {
+                rewritten = applied;
+                break;
+            }
+        }
+
+        let excluded = link::excluded_prefix(&self.excluded_prefixes, &rewritten);
+        match excluded {
+            Some(prefix) => steps.push(ExplanationStep::Excluded {
+                prefix: prefix.to_string(),
+            }),
+            None => {
+                for pattern in R::route_patterns() {
+                    let Ok(parsed) = RoutePattern::parse(pattern) else {
+                        continue;
+                    };
+                    let result = parsed.try_match(&rewritten);
+                    let divergence = result.as_ref().err();
+                    steps.push(ExplanationStep::Candidate {
+                        pattern,
+                        matched: result.is_ok(),
+                        diverged_at: divergence.map(|divergence| divergence.segment),
+                        reason: divergence.map(|divergence| divergence.reason.to_string()),
+                    });
+                }
+            }
+        }
+
+        let input = match query {
+            Some(query) if R::parses_query() => format!("{rewritten}?{query}"),
+            _ => rewritten.clone(),
+        };
+        let parsed = match excluded {
+            Some(prefix) => Err(R::rejected(crate::ParseLimit::ExcludedPrefix {
+                prefix: prefix.to_string(),
+            })),
+            None => R::from_str(&input),
+        };
+        let outcome = match parsed {
+            Ok(route) => ExplanationOutcome::Matched {
+                pattern: route.pattern(),
+                params: route.matched_params(),
+                canonical: route.to_string(),
+            },
+            Err(err) => ExplanationOutcome::Failed {
+                error: err.to_string(),
+                closest: NotFoundContext::<R>::new(&rewritten, 3).closest_patterns,
+            },
+        };
+
+        Explanation {
+            path: path.to_string(),
+            steps,
+            outcome,
+        }
+    }
+}
+
+impl std::fmt::Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Explaining {}", self.path)?;
+        for step in &self.steps {
+            match step {
+                ExplanationStep::Rewrite {
+                    rule,
+                    rewritten: Some(rewritten),
+                } => writeln!(f, "  rewrite {rule}: rewritten to {rewritten}")?,
+                ExplanationStep::Rewrite {
+                    rule,
+                    rewritten: None,
+                } => writeln!(f, "  rewrite {rule}: doesn't match")?,
+                ExplanationStep::Excluded { prefix } => {
+                    writeln!(f, "  excluded: {prefix} is served by something else")?
+                }
+                ExplanationStep::Candidate {
+                    pattern,
+                    matched: true,
+                    ..
+                } => writeln!(f, "  tried {pattern}: matches")?,
+                ExplanationStep::Candidate {
+                    pattern,
+                    diverged_at,
+                    reason,
+                    ..
+                } => writeln!(
+                    f,
+                    "  tried {pattern}: diverged at segment {}, {}",
+                    diverged_at.unwrap_or_default(),
+                    reason.as_deref().unwrap_or_default()
+                )?,
+            }
+        }
+        match &self.outcome {
+            ExplanationOutcome::Matched {
+                pattern,
+                params,
+                canonical,
+            } => {
+                write!(f, "Matched {pattern}")?;
+                for (index, (name, value)) in params.iter().enumerate() {
+                    let separator = match index {
+                        0 => " with",
+                        _ => ",",
+                    };
+                    write!(f, "{separator} {name} = {value}")?;
+                }
+                match canonical == &self.path {
+                    true => Ok(()),
+                    false => write!(f, "\nThe canonical path is {canonical}"),
+                }
+            }
+            ExplanationOutcome::Failed { error, closest } => {
+                write!(f, "Failed: {error}")?;
+                match closest.is_empty() {
+                    true => Ok(()),
+                    false => write!(f, "\nClosest routes: {}", closest.join(", ")),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn explain() {
+    use crate::rewrite::RewriteRule;
+    use crate::{Route, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+
+    let config = RouterConfiguration {
+        rewrites: vec![RewriteRule::new("/legacy/(id)", "/hello_world/(id)").unwrap()],
+        excluded_prefixes: vec!["/static".to_string()],
+        ..Default::default()
+    };
+    let router = Router::<Route>::with_configuration(MemoryHistory::default(), config).unwrap();
+
+    let explanation = router.explain("/legacy/7/");
+    assert_eq!(
+        explanation.steps[0],
+        ExplanationStep::Rewrite {
+            rule: "/legacy/(id) -> /hello_world/(id)".to_string(),
+            rewritten: Some("/hello_world/7".to_string()),
+        }
+    );
+    assert!(explanation.steps.contains(&ExplanationStep::Candidate {
+        pattern: "/hello_world",
+        matched: false,
+        diverged_at: Some(1),
+        reason: Some("the path has 1 more segments".to_string()),
+    }));
+    assert_eq!(
+        explanation.outcome,
+        ExplanationOutcome::Matched {
+            pattern: "/hello_world/(dynamic)",
+            params: vec![("dynamic", "7".to_string())],
+            canonical: "/hello_world/7".to_string(),
+        }
+    );
+    let text = explanation.to_string();
+    assert!(text.contains("  tried /hello_world/(dynamic): matches\n"));
+    assert!(text.ends_with(
+        "Matched /hello_world/(dynamic) with dynamic = 7\nThe canonical path is /hello_world/7"
+    ));
+
+    // The pattern matches, but the id isn't a number
+    let explanation = router.explain("/gone/x");
+    assert!(explanation.steps.contains(&ExplanationStep::Candidate {
+        pattern: "/gone/(id)",
+        matched: true,
+        diverged_at: None,
+        reason: None,
+    }));
+    assert!(matches!(
+        &explanation.outcome,
+        ExplanationOutcome::Failed { closest, .. } if closest.contains(&"/gone/(id)")
+    ));
+
+    let explanation = router.explain("/static/app.js");
+    assert_eq!(
+        explanation.steps.last(),
+        Some(&ExplanationStep::Excluded {
+            prefix: "/static".to_string()
+        })
+    );
+    assert!(matches!(
+        explanation.outcome,
+        ExplanationOutcome::Failed { .. }
+    ));
+}
@@ -0,0 +1,357 @@
+//! Map navigations that fail to a typed error route, like `/error/500`, so the page the app shows and the status
+//! the server responds with come from one place.
+//!
+//! The router navigates to the route [`RouterConfiguration::error_route`](crate::RouterConfiguration) returns for
+//! the failure, replacing the current entry, and a server responds with the `status_hint` of that route. If the
+//! navigation to the error route fails too, the router doesn't map that failure again: it stays where it is and
+//! renders [`render_fallback`] instead, with status 500.
+
+#[cfg(test)]
+use super::*;
+use crate::context::{use_shared_router_provider, RouterId};
+use crate::hydration::HydrationMarker;
+use crate::{Routable, Router};
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// Why a navigation failed
+#[derive(Debug)]
+pub enum NavigationFailure<R: FromStr> {
+    /// No route matched the path
+    NoMatch(R::Err),
+    /// The data of the route couldn't be loaded, reported with [`Router::fail`]
+    LoaderFailed(Box<dyn std::error::Error>),
+    /// A guard of the route with this pattern cancelled the navigation
+    GuardCancelled(&'static str),
+    /// Actions or guards redirected in a loop. `chain` holds the patterns of the routes that redirected
+    TooManyRedirects { chain: Vec<&'static str> },
+    /// A guard panicked while checking a navigation to the route with this pattern and owner
+    GuardPanicked {
+        pattern: &'static str,
+        owner: Option<&'static str>,
+        message: String,
+    },
+}
+
+impl<R: FromStr> std::fmt::Display for NavigationFailure<R>
+where
+    R::Err: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoMatch(err) => write!(f, "{err}"),
+            Self::LoaderFailed(err) => write!(f, "Loading the page failed: {err}"),
+            Self::GuardCancelled(pattern) => {
+                write!(f, "The guard of {pattern} cancelled the navigation")
+            }
+            Self::TooManyRedirects { chain } => {
+                write!(
+                    f,
+                    "Stopped after too many redirects: {}",
+                    chain.join(" -> ")
+                )
+            }
+            Self::GuardPanicked {
+                pattern,
+                owner,
+                message,
+            } => {
+                write!(f, "The guard of {pattern} panicked: {message}")?;
+                write_owner(f, *owner)
+            }
+        }
+    }
+}
+
+/// Write ` (owned by payments-team)` after a failure of a route declared with `owner = "..."`.
+pub fn write_owner(f: &mut std::fmt::Formatter<'_>, owner: Option<&str>) -> std::fmt::Result {
+    match owner {
+        Some(owner) => write!(f, " (owned by {owner})"),
+        None => Ok(()),
+    }
+}
+
+/// The message a panic was started with, for the payload `catch_unwind` returns.
+pub fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "the panic has no message".to_string(),
+    }
+}
+
+/// A component of the route with this pattern and owner panicked while it rendered.
+struct RenderPanic {
+    pattern: &'static str,
+    owner: Option<&'static str>,
+    message: String,
+}
+
+impl std::fmt::Display for RenderPanic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The page of {} panicked: {}", self.pattern, self.message)?;
+        write_owner(f, self.owner)
+    }
+}
+
+/// The route to show for a failed navigation.
+pub type ErrorRoute<R> = fn(NavigationFailure<R>) -> R;
+
+/// The page the router renders when the navigation to the error route failed as well.
+pub fn render_fallback<'a>(cx: &'a ScopeState, message: &str) -> Element<'a> {
+    render! {
+        h1 { "Something went wrong" }
+        p { "{message}" }
+    }
+}
+
+/// A page rendered on the server and the status to respond with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerPage {
+    pub html: String,
+    pub status: u16,
+    /// Embed [`HydrationMarker::to_html`] in the page so the client router doesn't check the route again
+    pub hydration: HydrationMarker,
+}
+
+struct ServerRootProps<R: Routable + 'static>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    router: Rc<RefCell<Router<R>>>,
+}
+
+fn server_root<R: Routable + 'static>(cx: Scope<ServerRootProps<R>>) -> Element
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_shared_router_provider(cx, RouterId("server"), &cx.props.router);
+    let router = cx.props.router.borrow();
+    match router.fallback() {
+        Some(message) => render_fallback(cx, message),
+        None => router.route.clone().render(cx),
+    }
+}
+
+/// Render the current route of a router to HTML, with the status of [`Router::status`].
+///
+/// If a component panics, the page is the [`render_fallback`] page with status 500, saying which route panicked and
+/// the team that owns it.
+pub fn server_render<R: Routable + 'static>(router: Router<R>) -> ServerPage
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let status = router.status();
+    let hydration = HydrationMarker {
+        location: router.current_url().to_string(),
+    };
+    let (pattern, owner) = (router.route.pattern(), router.route.owner());
+    let router = Rc::new(RefCell::new(router));
+    let rendered = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let mut vdom = VirtualDom::new_with_props(server_root::<R>, ServerRootProps { router });
+        let _ = vdom.rebuild();
+        dioxus_ssr::render(&vdom)
+    }));
+    match rendered {
+        Ok(html) => ServerPage {
+            html,
+            status,
+            hydration,
+        },
+        Err(payload) => {
+            let panic = RenderPanic {
+                pattern,
+                owner,
+                message: panic_message(&*payload),
+            };
+            #[cfg(feature = "tracing")]
+            tracing::error!(
+                pattern,
+                owner = owner.unwrap_or_default(),
+                panic = %panic.message,
+                "a route panicked while it rendered"
+            );
+            ServerPage {
+                html: format!(
+                    "<h1>Something went wrong</h1><p>{}</p>",
+                    crate::head::escape_html(&panic.to_string())
+                ),
+                status: 500,
+                hydration,
+            }
+        }
+    }
+}
+
+/// An app with error pages for failed navigations, and a page its guard never lets anyone see
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum FailingRoute {
+    #[route("/" FailingHome)]
+    Home {},
+    #[route("/private" FailingHome, guard = cancel_private)]
+    Private {},
+    #[route("/error/404" FailingNotFound, status = 404)]
+    NotFound {},
+    #[route("/error/500" FailingServerError, status = 500)]
+    ServerError {},
+    #[route("/checkout" FailingHome, guard = panic_checkout, owner = "payments-team")]
+    Checkout {},
+    #[route("/invoices" FailingInvoices, owner = "billing-team")]
+    Invoices {},
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn FailingHome(cx: Scope) -> Element {
+    render! { "home" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn FailingNotFound(cx: Scope) -> Element {
+    render! { "There is no such page" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn FailingServerError(cx: Scope) -> Element {
+    render! { "Try again later" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn FailingInvoices(_cx: Scope) -> Element {
+    panic!("the invoice is missing its total");
+}
+
+#[cfg(test)]
+fn cancel_private(_: &GuardContext<FailingRoute>) -> GuardResult<FailingRoute> {
+    GuardResult::Cancel
+}
+
+#[cfg(test)]
+fn panic_checkout(_: &GuardContext<FailingRoute>) -> GuardResult<FailingRoute> {
+    panic!("the payment provider is down");
+}
+
+#[cfg(test)]
+thread_local! {
+    static LAST_FAILURE: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+#[cfg(test)]
+fn failing_error_route(failure: NavigationFailure<FailingRoute>) -> FailingRoute {
+    LAST_FAILURE.with(|last| *last.borrow_mut() = Some(failure.to_string()));
+    match failure {
+        NavigationFailure::NoMatch(_) => FailingRoute::NotFound {},
+        _ => FailingRoute::ServerError {},
+    }
+}
+
+#[test]
+fn error_route() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    fn new_router(path: &str) -> Router<FailingRoute> {
+        let config = RouterConfiguration {
+            error_route: Some(failing_error_route),
+            ..Default::default()
+        };
+        let history = MemoryHistory::with_initial_path(path).unwrap();
+        Router::with_configuration(history, config).unwrap()
+    }
+    fn database_down() -> NavigationFailure<FailingRoute> {
+        let err = std::io::Error::new(std::io::ErrorKind::Other, "the database is down");
+        NavigationFailure::LoaderFailed(Box::new(err))
+    }
+
+    let router = new_router("/nope");
+    assert_eq!(router.route, FailingRoute::NotFound {});
+    assert_eq!(&*router.current_url(), "/error/404");
+    let page = server_render(router);
+    assert_eq!(page.status, 404);
+    assert!(page.html.contains("There is no such page"), "{}", page.html);
+
+    let mut router = new_router("/");
+    router.fail(database_down());
+    assert_eq!(router.route, FailingRoute::ServerError {});
+    assert_eq!(&*router.current_url(), "/error/500");
+    assert_eq!(router.navigation_source(), &NavigationSource::Failure);
+    assert_eq!(router.status(), 500);
+    assert_eq!(router.fallback(), None);
+
+    // A failure on the error page isn't mapped again, the built-in page shows it instead
+    router.fail(database_down());
+    assert_eq!(router.route, FailingRoute::ServerError {});
+    assert!(router.fallback().is_some());
+    let page = server_render(router);
+    assert_eq!(page.status, 500);
+    assert!(
+        page.html
+            .contains("Loading the page failed: the database is down"),
+        "{}",
+        page.html
+    );
+
+    let mut router = new_router("/");
+    router.push(FailingRoute::Private {});
+    assert_eq!(router.route, FailingRoute::ServerError {});
+    assert_eq!(
+        router.navigation_error(),
+        Some(&NavigationError::GuardCancelled {
+            pattern: "/private"
+        })
+    );
+}
+
+#[test]
+fn route_owners() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    fn new_router(path: &str) -> Router<FailingRoute> {
+        let config = RouterConfiguration {
+            error_route: Some(failing_error_route),
+            ..Default::default()
+        };
+        let history = MemoryHistory::with_initial_path(path).unwrap();
+        Router::with_configuration(history, config).unwrap()
+    }
+
+    assert_eq!(FailingRoute::Checkout {}.owner(), Some("payments-team"));
+    assert_eq!(FailingRoute::Home {}.owner(), None);
+    assert!(FailingRoute::ROUTE_TABLE_JSON
+        .contains(r#""pattern": "/invoices", "owner": "billing-team""#));
+
+    let mut router = new_router("/");
+    router.push(FailingRoute::Checkout {});
+    assert_eq!(router.route, FailingRoute::ServerError {});
+    assert_eq!(
+        router.navigation_error(),
+        Some(&NavigationError::GuardPanicked {
+            pattern: "/checkout",
+            owner: Some("payments-team"),
+            message: "the payment provider is down".to_string(),
+        })
+    );
+    assert_eq!(
+        LAST_FAILURE.with(|last| last.take()).as_deref(),
+        Some("The guard of /checkout panicked: the payment provider is down (owned by payments-team)")
+    );
+
+    // A page that panics while it renders on the server
+    let page = server_render(new_router("/invoices"));
+    assert_eq!(page.status, 500);
+    assert!(
+        page.html.contains(
+            "The page of /invoices panicked: the invoice is missing its total (owned by billing-team)"
+        ),
+        "{}",
+        page.html
+    );
+}
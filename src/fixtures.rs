@@ -0,0 +1,95 @@
+//! Placeholder values for routes in tests, used by the modules `#[routable(fixtures)]` generates.
+//!
+//! A route type with the `fixtures` option gets a module named after it, like `route_fixtures` for `Route`, with a
+//! function for every route that fills its segments with [`FixtureValue::fixture`], and `all` with every route. The
+//! module only exists in tests and with the `fixtures` feature, so shipped builds don't carry it. Segment types of
+//! the app implement [`FixtureValue`] to take part.
+
+use crate::matrix::MatrixParams;
+use crate::url_path::{UrlPath, UrlPathRules};
+
+/// The value a segment of this type has in a fixture route.
+pub trait FixtureValue {
+    fn fixture() -> Self;
+}
+
+macro_rules! fixture_numbers {
+    ($($ty:ty => $value:expr),* $(,)?) => {
+        $(
+            impl FixtureValue for $ty {
+                fn fixture() -> Self {
+                    $value
+                }
+            }
+        )*
+    };
+}
+
+fixture_numbers! {
+    u8 => 1, u16 => 1, u32 => 1, u64 => 1, u128 => 1, usize => 1,
+    i8 => 1, i16 => 1, i32 => 1, i64 => 1, i128 => 1, isize => 1,
+    f32 => 1.0, f64 => 1.0,
+}
+
+impl FixtureValue for bool {
+    fn fixture() -> Self {
+        true
+    }
+}
+
+impl FixtureValue for String {
+    fn fixture() -> Self {
+        "example".to_string()
+    }
+}
+
+/// A catch-all of one segment
+impl<T: FixtureValue> FixtureValue for Vec<T> {
+    fn fixture() -> Self {
+        vec![T::fixture()]
+    }
+}
+
+/// No params
+impl FixtureValue for MatrixParams {
+    fn fixture() -> Self {
+        MatrixParams::default()
+    }
+}
+
+impl FixtureValue for UrlPath {
+    fn fixture() -> Self {
+        UrlPath::parse(std::iter::once("example"), UrlPathRules::default())
+            .expect("`example` is a valid path")
+    }
+}
+
+#[test]
+fn fixtures() {
+    use crate::{route_fixtures, Routable, Route};
+
+    assert_eq!(route_fixtures::route3(), Route::Route3 { dynamic: 1 });
+    assert_eq!(
+        route_fixtures::search(),
+        Route::Search {
+            query: "example".to_string(),
+            page: 1,
+            sort: "example".to_string(),
+        }
+    );
+    assert_eq!(route_fixtures::docs().to_string(), "/docs/example");
+
+    let all = route_fixtures::all();
+    assert_eq!(all.first(), Some(&route_fixtures::route1()));
+    let mut patterns: Vec<_> = all.iter().map(Route::pattern).collect();
+    patterns.sort();
+    patterns.dedup();
+    assert_eq!(patterns.len(), all.len());
+    assert!(patterns
+        .iter()
+        .all(|pattern| Route::route_patterns().contains(pattern)));
+    // Every fixture has a path, even if it parses as an earlier route
+    for route in all {
+        assert!(route.to_string().parse::<Route>().is_ok(), "{route}");
+    }
+}
@@ -0,0 +1,767 @@
+//! Links to a section of a page, like `#pricing`, that go through the router instead of around it.
+//!
+//! The router can't scroll by itself, so it leaves a [`ScrollRequest`] after every navigation that should move the
+//! page. The renderer takes it with [`Router::take_scroll_request`] once the new route rendered, and reports where
+//! the page is scrolled to with [`Router::save_scroll_position`] so going back can restore it.
+//!
+//! Routes that keep the fragment in a field declare it at the end of their pattern, like `section` in
+//! `/docs/(page)#(section)`. It is parsed with `FromStr` and displayed after the path and query. A location without
+//! a fragment still matches if the field is an `Option`, which is `None`, or implements `Default`.
+//!
+//! The router scrolls to the element whose `id` is the field as it is displayed, so `#v02` and `#v2` both scroll to
+//! `id="v2"` for a field that displays as `v2`, and a route whose fragment is `None` scrolls to the top. Moving
+//! between fragments of the current location only replaces the history entry: the guards don't run again and the
+//! route isn't parsed again.
+
+#[cfg(test)]
+use super::*;
+use crate::context::use_router;
+use crate::encoding::{decode_query_component, encode_query_component};
+use crate::link::LinkClick;
+use crate::navigation::{NavigationKind, NavigationSource, RouteChange};
+use crate::{Routable, Router};
+use dioxus::prelude::*;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// Split a location into everything before the fragment and the fragment without its `#`.
+pub fn split_fragment(location: &str) -> (&str, Option<&str>) {
+    match location.split_once('#') {
+        Some((location, fragment)) => (location, Some(fragment)),
+        None => (location, None),
+    }
+}
+
+/// The value of a `#(fragment)` field for a location without a fragment.
+///
+/// The derive doesn't know which types implement `Default`, so it calls `missing_fragment` on a reference to this
+/// and lets method resolution pick [`DefaultFragment`] for the types that do, and [`RequiredFragment`] otherwise.
+pub struct MissingFragment<T>(PhantomData<T>);
+
+impl<T> MissingFragment<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for MissingFragment<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The default value of a fragment field whose type implements `Default`, like `None` for an `Option`.
+pub trait DefaultFragment<T> {
+    fn missing_fragment(&self) -> Option<T>;
+}
+
+impl<T: Default> DefaultFragment<T> for MissingFragment<T> {
+    fn missing_fragment(&self) -> Option<T> {
+        Some(T::default())
+    }
+}
+
+/// No value for a fragment field whose type doesn't implement `Default`, so the location doesn't match.
+pub trait RequiredFragment<T> {
+    fn missing_fragment(&self) -> Option<T>;
+}
+
+impl<T> RequiredFragment<T> for &MissingFragment<T> {
+    fn missing_fragment(&self) -> Option<T> {
+        None
+    }
+}
+
+/// Where the renderer should scroll after a navigation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScrollRequest {
+    /// Scroll the element with this id into view
+    Fragment(String),
+    /// Scroll back to a position the renderer saved for the entry, in pixels from the top
+    Position(f64),
+    /// Scroll to the top of the page
+    Top,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ScrollEntry {
+    fragment: Option<String>,
+    position: Option<f64>,
+    state: Option<String>,
+}
+
+/// The fragment, the last saved scroll position and the state of the history entries the router navigated through.
+///
+/// Like `TrackedHistory`, the entries are only precise as long as every navigation goes through the router.
+///
+/// At most `capacity` entries are kept: pushing drops the oldest entry, and going back or forward past the kept
+/// entries drops one from the other end. The current and the previous entry are always
+/// kept. Entries that were dropped come back empty, so going back to them scrolls to the top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollHistory {
+    entries: VecDeque<ScrollEntry>,
+    // The index of the current entry in `entries`
+    index: usize,
+    capacity: usize,
+    // How many entries were dropped before and after the kept ones
+    dropped_before: usize,
+    dropped_after: usize,
+}
+
+impl ScrollHistory {
+    pub fn new(fragment: Option<&str>, capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::from([ScrollEntry {
+                fragment: fragment.map(String::from),
+                ..Default::default()
+            }]),
+            index: 0,
+            capacity: capacity.max(2),
+            dropped_before: 0,
+            dropped_after: 0,
+        }
+    }
+
+    fn current(&mut self) -> &mut ScrollEntry {
+        &mut self.entries[self.index]
+    }
+
+    /// Add an entry after the current one, dropping every entry after it like a browser does.
+    pub fn push(&mut self, fragment: Option<&str>) {
+        self.entries.truncate(self.index + 1);
+        self.dropped_after = 0;
+        self.entries.push_back(ScrollEntry {
+            fragment: fragment.map(String::from),
+            ..Default::default()
+        });
+        self.index = self.entries.len() - 1;
+        if self.entries.len() > self.capacity {
+            self.entries.pop_front();
+            self.dropped_before += 1;
+            self.index -= 1;
+        }
+    }
+
+    /// Replace the current entry. A position or state saved for it was for another location, so it is forgotten.
+    pub fn replace(&mut self, fragment: Option<&str>) {
+        *self.current() = ScrollEntry {
+            fragment: fragment.map(String::from),
+            ..Default::default()
+        };
+    }
+
+    pub fn go_back(&mut self) {
+        if self.index > 0 {
+            self.index -= 1;
+        } else if self.dropped_before > 0 {
+            self.dropped_before -= 1;
+            self.entries.push_front(ScrollEntry::default());
+            if self.entries.len() > self.capacity {
+                self.entries.pop_back();
+                self.dropped_after += 1;
+            }
+        }
+    }
+
+    pub fn go_forward(&mut self) {
+        if self.index + 1 < self.entries.len() {
+            self.index += 1;
+        } else if self.dropped_after > 0 {
+            self.dropped_after -= 1;
+            self.entries.push_back(ScrollEntry::default());
+            self.index += 1;
+            if self.entries.len() > self.capacity {
+                self.entries.pop_front();
+                self.dropped_before += 1;
+                self.index -= 1;
+            }
+        }
+    }
+
+    pub fn save_position(&mut self, position: f64) {
+        self.current().position = Some(position);
+    }
+
+    /// The fragment of the current entry.
+    pub fn fragment(&self) -> Option<&str> {
+        self.entries[self.index].fragment.as_deref()
+    }
+
+    pub fn set_state(&mut self, state: Option<String>) {
+        self.current().state = state;
+    }
+
+    /// The state of the current entry, see [`Router::transaction`].
+    pub fn state(&self) -> Option<&str> {
+        self.entries[self.index].state.as_deref()
+    }
+
+    /// The number of entries kept, at most the capacity.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Where to scroll after going back or forward to the current entry: to the saved position if there is one,
+    /// otherwise to its fragment or the top.
+    pub fn restore(&self) -> ScrollRequest {
+        let entry = &self.entries[self.index];
+        match (entry.position, &entry.fragment) {
+            (Some(position), _) => ScrollRequest::Position(position),
+            (None, Some(fragment)) => ScrollRequest::Fragment(fragment.clone()),
+            (None, None) => ScrollRequest::Top,
+        }
+    }
+
+    /// The entries as lines of query strings, after a line with the index and the dropped counts. See
+    /// [`session`](crate::session).
+    pub fn to_storage(&self) -> String {
+        let mut lines = vec![format!(
+            "index={}&dropped_before={}&dropped_after={}",
+            self.index, self.dropped_before, self.dropped_after
+        )];
+        for entry in &self.entries {
+            let mut params = Vec::new();
+            if let Some(position) = entry.position {
+                params.push(format!("position={position}"));
+            }
+            if let Some(fragment) = &entry.fragment {
+                params.push(format!("fragment={}", encode_query_component(fragment)));
+            }
+            if let Some(state) = &entry.state {
+                params.push(format!("state={}", encode_query_component(state)));
+            }
+            lines.push(params.join("&"));
+        }
+        lines.join("\n")
+    }
+
+    /// Read the entries of [`ScrollHistory::to_storage`], or `None` if the value is malformed or has more entries
+    /// than `capacity`.
+    pub fn from_storage(value: &str, capacity: usize) -> Option<Self> {
+        let mut lines = value.split('\n');
+        let (mut index, mut dropped_before, mut dropped_after) = (None, None, None);
+        for (key, value) in query_pairs(lines.next()?)? {
+            let count = Some(value.parse::<usize>().ok()?);
+            match key.as_str() {
+                "index" => index = count,
+                "dropped_before" => dropped_before = count,
+                "dropped_after" => dropped_after = count,
+                _ => return None,
+            }
+        }
+
+        let mut entries = VecDeque::new();
+        for line in lines {
+            let mut entry = ScrollEntry::default();
+            for (key, value) in query_pairs(line)? {
+                match key.as_str() {
+                    "position" => {
+                        entry.position = Some(value.parse::<f64>().ok().filter(|p| p.is_finite())?)
+                    }
+                    "fragment" => entry.fragment = Some(value),
+                    "state" => entry.state = Some(value),
+                    _ => return None,
+                }
+            }
+            entries.push_back(entry);
+        }
+
+        let capacity = capacity.max(2);
+        let index = index?;
+        if index >= entries.len() || entries.len() > capacity {
+            return None;
+        }
+        Some(Self {
+            entries,
+            index,
+            capacity,
+            dropped_before: dropped_before?,
+            dropped_after: dropped_after?,
+        })
+    }
+}
+
+/// The decoded keys and values of a query string, or `None` if one doesn't decode
+fn query_pairs(query: &str) -> Option<Vec<(String, String)>> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            Some((decode_query_component(key)?, decode_query_component(value)?))
+        })
+        .collect()
+}
+
+#[derive(Props)]
+pub struct FragmentLinkProps<'a> {
+    /// `#pricing` for a section of the current route, or a path with a fragment like `/docs#install`
+    to: &'a str,
+    /// The `id` of the link, reported as the [`NavigationSource::LinkClick`] of the navigation
+    id: Option<&'a str>,
+    /// Runs before the router handles a click. Call [`LinkClickEvent::prevent_default`] to keep the router from
+    /// navigating
+    onclick: Option<EventHandler<'a, LinkClickEvent>>,
+    children: Element<'a>,
+}
+
+/// A click on a link, passed to its `onclick` handler before the router handles it.
+pub struct LinkClickEvent {
+    click: LinkClick,
+    prevented: Rc<Cell<bool>>,
+}
+
+impl LinkClickEvent {
+    pub fn click(&self) -> &LinkClick {
+        &self.click
+    }
+
+    /// Handle the click in the handler: the router won't navigate.
+    pub fn prevent_default(&self) {
+        self.prevented.set(true);
+    }
+
+    /// Run the `onclick` handler of a link on a click before the router handles it. The click that is returned is
+    /// [`LinkClick::default_prevented`] if the handler called [`LinkClickEvent::prevent_default`].
+    pub fn dispatch(mut click: LinkClick, handler: impl FnOnce(Self)) -> LinkClick {
+        let prevented = Rc::new(Cell::new(false));
+        handler(Self {
+            click,
+            prevented: prevented.clone(),
+        });
+        click.default_prevented = prevented.get();
+        click
+    }
+}
+
+/// A link to a section of a page that navigates with the nearest router with the route type `R`.
+/// See [`Router::follow_fragment_link`].
+///
+/// The link is a genuine `<a>` with the absolute url of [`Router::href`], so it is focusable, Enter follows it and
+/// copying the link address works without a `role` or `tabindex`. Only clicks [`LinkClick::intercepted`] accepts
+/// navigate with the router: middle clicks and clicks with a modifier, like ctrl-click to open a new tab, are left
+/// alone, as are clicks the `onclick` handler prevented.
+#[allow(non_snake_case)]
+pub fn FragmentLink<'a, R: Routable + 'static>(cx: Scope<'a, FragmentLinkProps<'a>>) -> Element<'a>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    // The press that starts a click decides if its default is prevented, see `LinkClick::prevent_default_attribute`.
+    // Clicks without one, like pressing Enter, are primary clicks
+    let press = use_state(cx, LinkClick::primary);
+    let router = use_router::<R>(cx)?.router().clone();
+    let to = cx.props.to;
+    let id = cx.props.id;
+    let href = router.borrow().href(to);
+    let prevent_default = press.prevent_default_attribute();
+    render! {
+        a {
+            href: "{href}",
+            id: id,
+            prevent_default: "{prevent_default}",
+            onmousedown: move |event: MouseEvent| press.set(LinkClick::from_mouse(&event)),
+            onclick: move |event: MouseEvent| {
+                press.set(LinkClick::primary());
+                let mut click = LinkClick::from_mouse(&event);
+                if let Some(handler) = &cx.props.onclick {
+                    click = LinkClickEvent::dispatch(click, |event| handler.call(event));
+                }
+                if click.intercepted() {
+                    // A path that doesn't parse has no route to show, so the router stays where it is
+                    let _ = router.borrow_mut().follow_fragment_link(to, id);
+                }
+            },
+            &cx.props.children
+        }
+    }
+}
+
+impl<R: Routable> Router<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Follow a link that may have a fragment, like `#pricing` or `/docs#install`.
+    ///
+    /// A link to the current location only changes the fragment: the current history entry is replaced and the
+    /// route's fragment is updated with [`Routable::set_fragment`], so the route isn't parsed again. Links to other
+    /// locations push a new entry like [`Router::push`]. Either way the router asks to scroll to the fragment, or to
+    /// the [`Routable::fragment_id`] of a route that keeps it in a field.
+    ///
+    /// The navigation is reported as a [`NavigationSource::LinkClick`] of the link with the id `element_id`.
+    pub fn follow_fragment_link(
+        &mut self,
+        href: &str,
+        element_id: Option<&str>,
+    ) -> Result<(), R::Err> {
+        let source = NavigationSource::LinkClick {
+            element_id: element_id.map(String::from),
+        };
+        let (location, fragment) = split_fragment(href);
+        if location.is_empty() || location == &*self.url {
+            let Some(fragment) = fragment else {
+                return Ok(());
+            };
+            self.history.replace(format!("{}#{fragment}", self.url));
+            let from = self.route.clone();
+            self.route.set_fragment(Some(fragment));
+            (self.same_variant, self.params_diff) = Self::compare_routes(Some(&from), &self.route);
+            self.scroll.replace(Some(fragment));
+            self.save_session();
+            let id = self
+                .route
+                .fragment_id()
+                .unwrap_or_else(|| fragment.to_string());
+            self.scroll_request = Some(ScrollRequest::Fragment(id));
+            self.source = source;
+            self.kind = Some(NavigationKind::Replace);
+            self.subscribers.notify(RouteChange {
+                route: &self.route,
+                url: &self.url,
+                source: &self.source,
+                kind: self.kind,
+                same_variant: self.same_variant,
+                params_diff: self.params_diff.clone(),
+                state: self.scroll.state(),
+            });
+            return Ok(());
+        }
+
+        let path = location.split('?').next().unwrap_or_default();
+        let mut route = match fragment {
+            Some(fragment) if R::parses_fragment() => self.parse(&format!("{path}#{fragment}"))?,
+            _ => self.parse(path)?,
+        };
+        route.set_fragment(fragment);
+        self.push_location(route, href.to_string(), source);
+        Ok(())
+    }
+
+    /// The fragment of the current location, without its `#`.
+    pub fn current_fragment(&self) -> Option<&str> {
+        self.scroll.fragment()
+    }
+
+    /// Remember where the page is scrolled to, so going back or forward to the current entry scrolls back there.
+    pub fn save_scroll_position(&mut self, position: f64) {
+        self.scroll.save_position(position);
+        self.save_session();
+    }
+
+    /// Where to scroll after the last navigation, if anywhere. Each request is only returned once.
+    pub fn take_scroll_request(&mut self) -> Option<ScrollRequest> {
+        self.scroll_request.take()
+    }
+}
+
+#[test]
+fn fragment_links() {
+    use crate::Route;
+    use dioxus_router_core::history::MemoryHistory;
+
+    let history = MemoryHistory::with_initial_path("/hello_world").unwrap();
+    let mut router = Router::<Route>::new(history).unwrap();
+    router.save_scroll_position(120.0);
+
+    // Only the fragment changes on the current route
+    router.follow_fragment_link("#pricing", None).unwrap();
+    assert_eq!(router.route, Route::Route2 {});
+    assert_eq!(router.current_fragment(), Some("pricing"));
+    assert_eq!(
+        router.take_scroll_request(),
+        Some(ScrollRequest::Fragment("pricing".to_string()))
+    );
+    assert_eq!(router.take_scroll_request(), None);
+    assert!(!router.history.can_go_back());
+    router.save_scroll_position(900.0);
+
+    // Other routes are pushed, then scrolled to the fragment
+    router
+        .follow_fragment_link("/hello_world/7#comments", None)
+        .unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 7 });
+    assert_eq!(router.current_fragment(), Some("comments"));
+    assert_eq!(
+        router.take_scroll_request(),
+        Some(ScrollRequest::Fragment("comments".to_string()))
+    );
+    assert!(router
+        .follow_fragment_link("/hello_world/x/y/z#a", None)
+        .is_err());
+
+    router.push(Route::Route5 {});
+    assert_eq!(router.take_scroll_request(), Some(ScrollRequest::Top));
+    assert_eq!(router.current_fragment(), None);
+
+    // Going back restores the saved position, or the fragment if none was saved
+    router.go_back().unwrap();
+    assert_eq!(
+        router.take_scroll_request(),
+        Some(ScrollRequest::Fragment("comments".to_string()))
+    );
+    router.go_back().unwrap();
+    assert_eq!(router.route, Route::Route2 {});
+    assert_eq!(
+        router.take_scroll_request(),
+        Some(ScrollRequest::Position(900.0))
+    );
+    router.go_forward().unwrap();
+    assert_eq!(router.current_fragment(), Some("comments"));
+}
+
+#[test]
+fn bounded_scroll_history() {
+    let mut scroll = ScrollHistory::new(None, 3);
+    for position in 0..5 {
+        scroll.save_position(position as f64);
+        scroll.push(None);
+    }
+    assert_eq!(scroll.len(), 3);
+
+    // The previous entry is kept, the dropped ones come back empty
+    scroll.go_back();
+    assert_eq!(scroll.restore(), ScrollRequest::Position(4.0));
+    scroll.go_back();
+    scroll.go_back();
+    assert_eq!(scroll.restore(), ScrollRequest::Top);
+    assert_eq!(scroll.len(), 3);
+
+    // Going forward again reaches the entry that was current
+    for _ in 0..3 {
+        scroll.go_forward();
+    }
+    assert_eq!(scroll.restore(), ScrollRequest::Top);
+    scroll.go_back();
+    assert_eq!(scroll.restore(), ScrollRequest::Position(4.0));
+}
+
+#[test]
+fn typed_fragments() {
+    use crate::guard::{GuardContext, GuardResult};
+    use crate::RouterConfiguration;
+    use dioxus_router_core::history::MemoryHistory;
+
+    thread_local! {
+        static RUNS: Cell<usize> = const { Cell::new(0) };
+    }
+    fn count(_: &GuardContext<DocsRoute>) -> GuardResult<DocsRoute> {
+        RUNS.with(|runs| runs.set(runs.get() + 1));
+        GuardResult::Allow
+    }
+    let runs = || RUNS.with(Cell::get);
+    let fragment = |id: &str| Some(ScrollRequest::Fragment(id.to_string()));
+
+    let config = RouterConfiguration {
+        guards: vec![count],
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/changes#v1").unwrap();
+    let mut router = Router::<DocsRoute>::with_configuration(history, config).unwrap();
+    let ran = runs();
+
+    // The page scrolls to the id the release displays as, not to the fragment of the link
+    router.follow_fragment_link("#v02", None).unwrap();
+    let changelog = |release| DocsRoute::Changelog {
+        release: Release(release),
+    };
+    assert_eq!(router.route, changelog(2));
+    assert_eq!(router.take_scroll_request(), fragment("v2"));
+
+    // Moving between fragments of the same path doesn't run the guards again or add an entry
+    router.follow_fragment_link("#v3", None).unwrap();
+    assert_eq!(router.take_scroll_request(), fragment("v3"));
+    assert_eq!(runs(), ran);
+    assert!(!router.history.can_go_back());
+
+    // An absent fragment scrolls to the top, and a present one to its decoded id
+    let page = |section: Option<&str>| DocsRoute::DocsPage {
+        page: "intro".to_string(),
+        section: section.map(String::from),
+    };
+    router.push(page(None));
+    assert_eq!(router.take_scroll_request(), Some(ScrollRequest::Top));
+    router.push(page(Some("café")));
+    assert_eq!(router.current_fragment(), Some("caf%C3%A9"));
+    assert_eq!(router.take_scroll_request(), fragment("café"));
+
+    // Going back and forward restores the fragment of the entry the same way
+    router.go_back().unwrap();
+    assert_eq!(router.take_scroll_request(), Some(ScrollRequest::Top));
+    router.go_forward().unwrap();
+    assert_eq!(router.take_scroll_request(), fragment("café"));
+}
+
+/// The documentation keeps the section of a page and the result of a search in the fragment
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum DocsRoute {
+    #[route("/" DocsHome)]
+    DocsHome {},
+    #[route("/docs/(page)#(section)" DocsPage)]
+    DocsPage {
+        page: String,
+        section: Option<String>,
+    },
+    #[route("/find?(query)#(result)" DocsSearch)]
+    DocsSearch { query: String, result: u32 },
+    #[route("/changes#(release)" Changelog)]
+    Changelog { release: Release },
+}
+
+/// A release like `v2`. It has no default, so the changelog needs a fragment
+#[cfg(test)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Release(u32);
+
+#[cfg(test)]
+impl std::fmt::Display for Release {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+#[cfg(test)]
+impl FromStr for Release {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix('v')
+            .and_then(|release| release.parse().ok())
+            .map(Self)
+            .ok_or_else(|| format!("'{s}' is not a release"))
+    }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn DocsHome(cx: Scope) -> Element {
+    render! { "Docs" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn DocsPage(cx: Scope, page: String, section: Option<String>) -> Element {
+    render! { "Page {page} section {section:?}" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn DocsSearch(cx: Scope, query: String, result: u32) -> Element {
+    render! { "Result {result} for {query}" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn Changelog(cx: Scope, release: Release) -> Element {
+    render! { "Changes in {release}" }
+}
+
+#[test]
+fn fragment_patterns() {
+    let page = |section: Option<&str>| DocsRoute::DocsPage {
+        page: "intro".to_string(),
+        section: section.map(String::from),
+    };
+    assert_eq!(
+        DocsRoute::from_str("/docs/intro#install"),
+        Ok(page(Some("install")))
+    );
+    assert_eq!(page(Some("install")).to_string(), "/docs/intro#install");
+    // No fragment and an empty one are both `None`, which is written without the `#`
+    assert_eq!(DocsRoute::from_str("/docs/intro"), Ok(page(None)));
+    assert_eq!(DocsRoute::from_str("/docs/intro#"), Ok(page(None)));
+    assert_eq!(page(None).to_string(), "/docs/intro");
+    assert_eq!(
+        DocsRoute::from_str("/docs/intro#getting%20started"),
+        Ok(page(Some("getting started")))
+    );
+    assert_eq!(
+        page(Some("getting started")).to_string(),
+        "/docs/intro#getting%20started"
+    );
+
+    // The fragment comes after the query, and a type with a default doesn't need one
+    let search = |result| DocsRoute::DocsSearch {
+        query: "milk".to_string(),
+        result,
+    };
+    assert_eq!(DocsRoute::from_str("/find?milk#3"), Ok(search(3)));
+    assert_eq!(DocsRoute::from_str("/find?milk"), Ok(search(0)));
+    assert_eq!(search(3).to_string(), "/find?milk#3");
+    let err = DocsRoute::from_str("/find#x").unwrap_err();
+    assert!(err
+        .attempted_routes
+        .contains(&DocsRouteMatchError::DocsSearch {
+            error: DocsSearchParseError::resultParseError(SegmentError::ParseError(
+                "x".parse::<u32>().unwrap_err()
+            )),
+            span: 5..7,
+        }));
+
+    // Without a default the fragment is required
+    let changelog = |release| DocsRoute::Changelog {
+        release: Release(release),
+    };
+    assert_eq!(DocsRoute::from_str("/changes#v2"), Ok(changelog(2)));
+    let err = DocsRoute::from_str("/changes").unwrap_err();
+    assert!(err
+        .attempted_routes
+        .contains(&DocsRouteMatchError::Changelog {
+            error: ChangelogParseError::MissingFragment,
+            span: 8..8,
+        }));
+
+    // Links parse with their fragment, and a link to a section of the current route updates its field
+    use dioxus_router_core::history::MemoryHistory;
+    let mut router = Router::<DocsRoute>::new(MemoryHistory::default()).unwrap();
+    router.follow_fragment_link("/changes#v3", None).unwrap();
+    assert_eq!(router.route, changelog(3));
+    router.follow_fragment_link("#v4", None).unwrap();
+    assert_eq!(router.route, changelog(4));
+    assert_eq!(router.current_fragment(), Some("v4"));
+}
+
+#[test]
+fn return_locations_with_query_and_fragment() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    // The parameter joins the query of the route, before its fragment
+    let mut router = Router::<DocsRoute>::new(MemoryHistory::default()).unwrap();
+    let search = DocsRoute::DocsSearch {
+        query: "rust".to_string(),
+        result: 2,
+    };
+    router.push_with_return(search.clone(), "next");
+    assert_eq!(router.route, search);
+    assert_eq!(&*router.current_url(), "/find?rust&next=/");
+    assert_eq!(router.current_fragment(), Some("2"));
+
+    let page = DocsRoute::DocsPage {
+        page: "intro".to_string(),
+        section: Some("install".to_string()),
+    };
+    router.push_with_return(page.clone(), "next");
+    assert_eq!(router.route, page);
+    assert_eq!(
+        &*router.current_url(),
+        "/docs/intro?next=/find?rust%26next%3D/"
+    );
+    assert_eq!(router.current_fragment(), Some("install"));
+
+    // The route is parsed with the query it returns to
+    router.pop_return("next", DocsRoute::DocsHome {});
+    assert_eq!(&*router.current_url(), "/find?rust&next=/");
+    assert_eq!(
+        router.route,
+        DocsRoute::DocsSearch {
+            query: "rust&next=/".to_string(),
+            result: 0
+        }
+    );
+}
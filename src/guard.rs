@@ -0,0 +1,279 @@
+//! Guards decide if a navigation may go where it is going, like an auth check that sends signed out users to the
+//! login page.
+//!
+//! Guards run before the route changes. The guards of [`RouterConfiguration::guards`](crate::RouterConfiguration)
+//! are the outermost and run first, in the order they are listed, then the guard of the target route declared with
+//! `#[route(..., guard = path)]`. The first guard that doesn't allow the navigation wins: the guards after it don't
+//! run, and the navigation either starts over at the route it redirected to or is cancelled.
+
+#[cfg(test)]
+use super::*;
+use crate::navigation::NavigationSource;
+use std::any::{Any, TypeId};
+use std::rc::Rc;
+
+/// A guard of the routes `R`.
+pub type Guard<R> = fn(&GuardContext<R>) -> GuardResult<R>;
+
+/// What a guard decided about a navigation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuardResult<R> {
+    /// Let the navigation continue to the next guard, or to its target after the last one
+    Allow,
+    /// Navigate to this route instead
+    Redirect(R),
+    /// Stop the navigation. The router stays where it is, or goes to its error route if it has one. Navigations
+    /// that already happened, like the browser going back, can't be undone, so they only report the error
+    Cancel,
+}
+
+/// What a guard knows about the navigation it checks.
+pub struct GuardContext<'a, R> {
+    /// The route the router is leaving, or `None` for the first route of the router
+    pub from: Option<&'a R>,
+    /// The route the navigation goes to
+    pub to: &'a R,
+    pub source: &'a NavigationSource,
+    /// The location the navigation goes to, with its query
+    pub url: &'a str,
+    contexts: &'a RouterContexts,
+}
+
+impl<'a, R> GuardContext<'a, R> {
+    pub fn new(
+        from: Option<&'a R>,
+        to: &'a R,
+        source: &'a NavigationSource,
+        url: &'a str,
+        contexts: &'a RouterContexts,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            source,
+            url,
+            contexts,
+        }
+    }
+
+    /// A value the router provides with [`RouterContexts::provide`], like the current session.
+    pub fn context<T: 'static>(&self) -> Option<&T> {
+        self.contexts.get()
+    }
+}
+
+/// Values the router provides to its guards, at most one of each type.
+#[derive(Clone, Default)]
+pub struct RouterContexts {
+    values: Vec<(TypeId, Rc<dyn Any>)>,
+}
+
+impl RouterContexts {
+    /// Provide a value, replacing the value of the same type if there is one.
+    pub fn provide<T: 'static>(&mut self, value: T) {
+        self.values.retain(|(ty, _)| *ty != TypeId::of::<T>());
+        self.values.push((TypeId::of::<T>(), Rc::new(value)));
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values
+            .iter()
+            .find(|(ty, _)| *ty == TypeId::of::<T>())
+            .and_then(|(_, value)| value.downcast_ref())
+    }
+}
+
+/// An admin area behind a sign in, checked by a global guard, and an admin guard on the admin routes
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum GuardedRoute {
+    #[route("/" GuardedHome)]
+    Home {},
+    #[route("/login" GuardedLogin)]
+    Login {},
+    #[route("/admin/(section)" GuardedAdmin, guard = require_admin)]
+    Admin { section: String },
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn GuardedHome(cx: Scope) -> Element {
+    render! { "home" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn GuardedLogin(cx: Scope) -> Element {
+    render! { "login" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn GuardedAdmin(cx: Scope, section: String) -> Element {
+    render! { "admin {section}" }
+}
+
+/// The signed in user, provided to the guards
+#[cfg(test)]
+struct Session {
+    admin: bool,
+}
+
+#[cfg(test)]
+thread_local! {
+    // The route the admin guard was left from and the location it checked, every time it ran
+    static ADMIN_CHECKS: std::cell::RefCell<Vec<(Option<GuardedRoute>, String)>> = const {
+        std::cell::RefCell::new(Vec::new())
+    };
+}
+
+#[cfg(test)]
+fn require_session(context: &GuardContext<GuardedRoute>) -> GuardResult<GuardedRoute> {
+    match (context.to, context.context::<Session>()) {
+        (GuardedRoute::Login {}, _) | (_, Some(_)) => GuardResult::Allow,
+        _ => GuardResult::Redirect(GuardedRoute::Login {}),
+    }
+}
+
+#[cfg(test)]
+fn require_admin(context: &GuardContext<GuardedRoute>) -> GuardResult<GuardedRoute> {
+    ADMIN_CHECKS.with(|checks| {
+        checks
+            .borrow_mut()
+            .push((context.from.cloned(), context.url.to_string()))
+    });
+    match context.context::<Session>() {
+        Some(Session { admin: true }) => GuardResult::Allow,
+        _ => GuardResult::Redirect(GuardedRoute::Home {}),
+    }
+}
+
+/// A photo viewer that keeps its state between photos, and albums that start over for every album
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum GalleryRoute {
+    #[route("/" GalleryHome)]
+    Home {},
+    #[route("/photos/(id)" GalleryPhoto, guard(count_photo_checks, params_only = false))]
+    Photo { id: u32 },
+    #[route("/albums/(id)" GalleryAlbum, hard_navigation)]
+    Album { id: u32 },
+}
+
+#[cfg(test)]
+thread_local! {
+    static PHOTO_CHECKS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+#[cfg(test)]
+fn count_photo_checks(_: &GuardContext<GalleryRoute>) -> GuardResult<GalleryRoute> {
+    PHOTO_CHECKS.with(|checks| checks.set(checks.get() + 1));
+    GuardResult::Allow
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn GalleryHome(cx: Scope) -> Element {
+    render! { "home" }
+}
+
+/// Counts its renders in a hook, which only lives as long as the component
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn GalleryPhoto(cx: Scope, id: u32) -> Element {
+    let renders = cx.use_hook(|| std::cell::Cell::new(0));
+    renders.set(renders.get() + 1);
+    render! { "photo {id}, render {renders.get()}" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn GalleryAlbum(cx: Scope, id: u32) -> Element {
+    let renders = cx.use_hook(|| std::cell::Cell::new(0));
+    renders.set(renders.get() + 1);
+    render! { "album {id}, render {renders.get()}" }
+}
+
+#[test]
+fn guards() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    fn admin_checks() -> Vec<(Option<GuardedRoute>, String)> {
+        ADMIN_CHECKS.with(|checks| checks.take())
+    }
+
+    let config = RouterConfiguration {
+        guards: vec![require_session],
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/admin/users").unwrap();
+    let mut router = Router::<GuardedRoute>::with_configuration(history, config).unwrap();
+
+    // Signed out, the global guard redirects before the guard of the admin route runs
+    assert_eq!(router.route, GuardedRoute::Login {});
+    assert_eq!(&*router.current_url(), "/login");
+    assert_eq!(
+        router.navigation_source(),
+        &NavigationSource::Guard("/admin/(section)")
+    );
+    assert_eq!(admin_checks(), []);
+
+    // Signed in, the admin guard runs next and sees the session, where the navigation comes from and where it goes
+    router.contexts_mut().provide(Session { admin: false });
+    router.push(GuardedRoute::Admin {
+        section: "users".to_string(),
+    });
+    assert_eq!(router.route, GuardedRoute::Home {});
+    assert_eq!(
+        admin_checks(),
+        [(Some(GuardedRoute::Login {}), "/admin/users".to_string())]
+    );
+    router.go_back().unwrap();
+    assert_eq!(router.route, GuardedRoute::Login {});
+
+    router.contexts_mut().provide(Session { admin: true });
+    router.push(GuardedRoute::Admin {
+        section: "users".to_string(),
+    });
+    assert_eq!(
+        router.route,
+        GuardedRoute::Admin {
+            section: "users".to_string()
+        }
+    );
+    assert_eq!(router.navigation_source(), &NavigationSource::Programmatic);
+    assert_eq!(admin_checks().len(), 1);
+}
+
+#[test]
+fn soft_navigation() {
+    use crate::router_test::TestRouter;
+
+    let mut gallery = TestRouter::<GalleryRoute>::new("/photos/1");
+    assert!(gallery.rendered_html().contains("photo 1, render 1"));
+    assert_eq!(PHOTO_CHECKS.with(|checks| checks.take()), 1);
+
+    // Another photo keeps the state of the viewer, and the guard that doesn't check the id is skipped
+    gallery.navigate(GalleryRoute::Photo { id: 2 });
+    assert!(gallery.rendered_html().contains("photo 2, render 2"));
+    assert!(gallery.is_soft_navigation());
+    assert_eq!(PHOTO_CHECKS.with(|checks| checks.take()), 0);
+    gallery.back();
+    assert!(gallery.rendered_html().contains("photo 1, render 3"));
+
+    // Another variant mounts the viewer again
+    gallery.navigate(GalleryRoute::Home {});
+    assert!(!gallery.is_soft_navigation());
+    gallery.navigate(GalleryRoute::Photo { id: 3 });
+    assert!(gallery.rendered_html().contains("photo 3, render 1"));
+    assert_eq!(PHOTO_CHECKS.with(|checks| checks.take()), 1);
+
+    // Albums are declared with `hard_navigation`, so every album starts over
+    gallery.navigate(GalleryRoute::Album { id: 1 });
+    gallery.navigate(GalleryRoute::Album { id: 2 });
+    assert!(gallery.rendered_html().contains("album 2, render 1"));
+    assert!(!gallery.is_soft_navigation());
+}
@@ -0,0 +1,213 @@
+//! The title and `<meta>` tags of a route, for the `<head>` of a server rendered page, and the `lang` and `dir` of
+//! its `<html>` element.
+
+#[cfg(test)]
+use super::*;
+
+/// What a route puts in the `<head>` of its page, declared with `title = "..."` and `meta(...)` on the route.
+///
+/// Values are plain text with the segment values filled in. [`HeadMeta::to_html`] escapes them, so a segment like
+/// `<script>` can't inject markup.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeadMeta {
+    pub title: Option<String>,
+    /// The name and content of every `<meta>` tag, in declaration order
+    pub meta: Vec<(&'static str, String)>,
+    /// The language of localized routes, see [`Routable::lang`](crate::Routable::lang)
+    pub lang: Option<DocumentLang>,
+}
+
+/// The language of a page and the direction its text is written in, for the `lang` and `dir` attributes of
+/// `<html>`. Screen readers pick their pronunciation from them.
+///
+/// The language is the locale of the route. The direction is the one `#[routable(locales(ar(dir = "rtl")))]`
+/// declares for it, or `None` to leave it to the browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentLang {
+    pub lang: String,
+    pub dir: Option<&'static str>,
+}
+
+impl DocumentLang {
+    /// The attributes for the `<html>` tag of a server template, like ` lang="ar" dir="rtl"`.
+    pub fn to_attributes(&self) -> String {
+        let mut attributes = format!(" lang=\"{}\"", escape_html(&self.lang));
+        if let Some(dir) = self.dir {
+            attributes.push_str(&format!(" dir=\"{dir}\""));
+        }
+        attributes
+    }
+}
+
+/// The element the router sets the `lang` and `dir` of the current route on, `document.documentElement` in a
+/// browser. Anything else, like a stub in tests, can stand in for it.
+pub trait DocumentRoot {
+    fn set_attribute(&mut self, name: &str, value: &str);
+    fn remove_attribute(&mut self, name: &str);
+}
+
+#[cfg(feature = "web")]
+impl DocumentRoot for web_sys::Element {
+    fn set_attribute(&mut self, name: &str, value: &str) {
+        let _ = web_sys::Element::set_attribute(self, name, value);
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        let _ = web_sys::Element::remove_attribute(self, name);
+    }
+}
+
+/// The `<html>` element of the page, if there is one.
+#[cfg(feature = "web")]
+pub fn browser_document_root() -> Option<Box<dyn DocumentRoot>> {
+    let root = web_sys::window()?.document()?.document_element()?;
+    Some(Box::new(root))
+}
+
+/// Update the attributes of `root` from the language of the last route to the one of the current route. Routes
+/// without a language remove the attributes an earlier route set, and leave them alone otherwise, so the `lang` a
+/// server rendered stays.
+pub fn update_document_root(
+    root: &mut dyn DocumentRoot,
+    last: Option<&DocumentLang>,
+    current: Option<&DocumentLang>,
+) {
+    if last == current {
+        return;
+    }
+    match current {
+        Some(current) => {
+            root.set_attribute("lang", &current.lang);
+            match current.dir {
+                Some(dir) => root.set_attribute("dir", dir),
+                None => root.remove_attribute("dir"),
+            }
+        }
+        None => {
+            root.remove_attribute("lang");
+            root.remove_attribute("dir");
+        }
+    }
+}
+
+impl HeadMeta {
+    /// The `<title>` and `<meta>` tags, for a server template to splice into its `<head>`.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        if let Some(title) = &self.title {
+            html.push_str(&format!("<title>{}</title>", escape_html(title)));
+        }
+        for (name, content) in &self.meta {
+            html.push_str(&format!(
+                "<meta name=\"{}\" content=\"{}\">",
+                escape_html(name),
+                escape_html(content)
+            ));
+        }
+        html
+    }
+}
+
+/// Escape text for HTML, in element content or in a quoted attribute value
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A site in English and in Arabic, which is written from right to left
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(locales(en(dir = "ltr"), ar(dir = "rtl")))]
+enum BilingualRoute {
+    #[route("/" BilingualHome)]
+    Home {},
+    #[route("/about" BilingualAbout, locale(ar = "/ar/about"))]
+    About { locale: String },
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn BilingualHome(cx: Scope) -> Element {
+    render! { "home" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn BilingualAbout(cx: Scope) -> Element {
+    render! { "about" }
+}
+
+/// Stands in for the `<html>` element, sharing its attributes with the test
+#[cfg(test)]
+#[derive(Default, Clone)]
+struct DocumentStub(Rc<std::cell::RefCell<Vec<(String, String)>>>);
+
+#[cfg(test)]
+impl DocumentStub {
+    fn attribute(&self, name: &str) -> Option<String> {
+        let attributes = self.0.borrow();
+        attributes
+            .iter()
+            .find(|(other, _)| other == name)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+#[cfg(test)]
+impl DocumentRoot for DocumentStub {
+    fn set_attribute(&mut self, name: &str, value: &str) {
+        self.remove_attribute(name);
+        self.0
+            .borrow_mut()
+            .push((name.to_string(), value.to_string()));
+    }
+
+    fn remove_attribute(&mut self, name: &str) {
+        self.0.borrow_mut().retain(|(other, _)| other != name);
+    }
+}
+
+#[test]
+fn document_lang() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let document = DocumentStub::default();
+    let config = RouterConfiguration {
+        document_root: Some(Box::new(document.clone())),
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/about").unwrap();
+    let mut router = Router::<BilingualRoute>::with_configuration(history, config).unwrap();
+    assert_eq!(document.attribute("lang").as_deref(), Some("en"));
+    assert_eq!(document.attribute("dir").as_deref(), Some("ltr"));
+
+    // Only the locale changes, so the page stays mounted
+    router.push(BilingualRoute::About {
+        locale: "ar".to_string(),
+    });
+    assert_eq!(router.history.current_path(), "/ar/about");
+    assert!(router.is_soft_navigation());
+    assert_eq!(document.attribute("lang").as_deref(), Some("ar"));
+    assert_eq!(document.attribute("dir").as_deref(), Some("rtl"));
+
+    let head = router.route.head();
+    assert_eq!(
+        head.lang.unwrap().to_attributes(),
+        r#" lang="ar" dir="rtl""#
+    );
+
+    router.push(BilingualRoute::Home {});
+    assert_eq!(document.attribute("lang"), None);
+    assert_eq!(document.attribute("dir"), None);
+    assert_eq!(router.route.head().lang, None);
+}
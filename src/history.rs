@@ -0,0 +1,623 @@
+use crate::encoding::{decode_query_component, encode_query_component};
+use dioxus_router_core::history::HistoryProvider;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// A history that stores the current path of a router in a single query parameter of another history.
+///
+/// This lets a small widget router live in the URL of the page (`/page?panel=/settings/profile`) without touching
+/// the page's path or any other query parameters. Navigating with the widget pushes or replaces entries in the
+/// inner history, so back and forward drive the widget as well.
+pub struct QueryParamHistory<H: HistoryProvider> {
+    inner: H,
+    key: &'static str,
+}
+
+impl<H: HistoryProvider> QueryParamHistory<H> {
+    pub fn new(inner: H, key: &'static str) -> Self {
+        Self { inner, key }
+    }
+
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    /// Build the path of the inner history with the query parameter set to the given path.
+    fn inner_path_with(&self, path: &str) -> String {
+        let mut query: Vec<String> = self
+            .inner
+            .current_query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|pair| !pair.is_empty() && !self.is_own_pair(pair))
+            .map(String::from)
+            .collect();
+        query.push(format!(
+            "{}={}",
+            encode_query_component(self.key),
+            encode_query_component(path)
+        ));
+
+        format!("{}?{}", self.inner.current_path(), query.join("&"))
+    }
+
+    fn is_own_pair(&self, pair: &str) -> bool {
+        let key = pair.split('=').next().unwrap_or_default();
+        decode_query_component(key).as_deref() == Some(self.key)
+    }
+}
+
+impl<H: HistoryProvider> HistoryProvider for QueryParamHistory<H> {
+    fn current_path(&self) -> String {
+        self.inner
+            .current_query()
+            .unwrap_or_default()
+            .split('&')
+            .find(|pair| self.is_own_pair(pair))
+            .and_then(|pair| pair.split_once('='))
+            .and_then(|(_, value)| decode_query_component(value))
+            .unwrap_or_else(|| "/".to_string())
+    }
+
+    fn can_go_back(&self) -> bool {
+        self.inner.can_go_back()
+    }
+
+    fn go_back(&mut self) {
+        self.inner.go_back()
+    }
+
+    fn can_go_forward(&self) -> bool {
+        self.inner.can_go_forward()
+    }
+
+    fn go_forward(&mut self) {
+        self.inner.go_forward()
+    }
+
+    fn push(&mut self, path: String) {
+        let path = self.inner_path_with(&path);
+        self.inner.push(path)
+    }
+
+    fn replace(&mut self, path: String) {
+        let path = self.inner_path_with(&path);
+        self.inner.replace(path)
+    }
+
+    fn updater(&mut self, callback: Arc<dyn Fn() + Send + Sync>) {
+        // Any change to the outer location (like a popstate) may change the parameter
+        self.inner.updater(callback)
+    }
+}
+
+/// A history that records the stack of entries it navigated through, so tests can assert on the exact back-stack.
+///
+/// The recorded entries are precise as long as every navigation goes through this history, which is always the case
+/// for a wrapped `MemoryHistory`. Backends that can also be navigated from the outside (like the browser's back
+/// button with a web history) only report best-effort values, because those navigations are never seen here.
+pub struct TrackedHistory<H: HistoryProvider> {
+    inner: H,
+    entries: Vec<String>,
+    index: usize,
+}
+
+impl<H: HistoryProvider> TrackedHistory<H> {
+    pub fn new(inner: H) -> Self {
+        let entries = vec![inner.current_path()];
+        Self {
+            inner,
+            entries,
+            index: 0,
+        }
+    }
+
+    /// The number of entries in the history stack, including entries after the current one.
+    pub fn entries_len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The index of the current entry in the history stack.
+    pub fn current_index(&self) -> usize {
+        self.index
+    }
+
+    /// Every path in the history stack, oldest first.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+}
+
+impl<H: HistoryProvider> HistoryProvider for TrackedHistory<H> {
+    fn current_path(&self) -> String {
+        self.inner.current_path()
+    }
+
+    fn current_query(&self) -> Option<String> {
+        self.inner.current_query()
+    }
+
+    fn can_go_back(&self) -> bool {
+        self.inner.can_go_back()
+    }
+
+    fn go_back(&mut self) {
+        if self.inner.can_go_back() {
+            self.index = self.index.saturating_sub(1);
+        }
+        self.inner.go_back()
+    }
+
+    fn can_go_forward(&self) -> bool {
+        self.inner.can_go_forward()
+    }
+
+    fn go_forward(&mut self) {
+        if self.inner.can_go_forward() {
+            self.index = (self.index + 1).min(self.entries.len() - 1);
+        }
+        self.inner.go_forward()
+    }
+
+    fn push(&mut self, path: String) {
+        // Pushing drops every entry after the current one, like a browser does
+        self.entries.truncate(self.index + 1);
+        self.entries.push(path.clone());
+        self.index = self.entries.len() - 1;
+        self.inner.push(path)
+    }
+
+    fn replace(&mut self, path: String) {
+        self.entries[self.index] = path.clone();
+        self.inner.replace(path)
+    }
+
+    fn updater(&mut self, callback: Arc<dyn Fn() + Send + Sync>) {
+        self.inner.updater(callback)
+    }
+}
+
+/// An in-memory history like `MemoryHistory` that keeps at most `capacity` entries, for apps that navigate for a long
+/// time without reloading, like kiosks.
+///
+/// Pushing past the capacity drops the oldest entry, so going back stops earlier than it would in a browser. The
+/// current and the previous entry are always kept.
+pub struct BoundedMemoryHistory {
+    // Locations with their query and fragment, oldest first
+    entries: VecDeque<String>,
+    index: usize,
+    capacity: usize,
+}
+
+impl BoundedMemoryHistory {
+    pub fn new(initial: &str, capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::from([initial.to_string()]),
+            index: 0,
+            capacity: capacity.max(2),
+        }
+    }
+
+    /// The number of entries in the history stack, at most the capacity.
+    pub fn entries_len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn current(&self) -> &str {
+        &self.entries[self.index]
+    }
+}
+
+impl HistoryProvider for BoundedMemoryHistory {
+    fn current_path(&self) -> String {
+        location_path(self.current())
+    }
+
+    fn current_query(&self) -> Option<String> {
+        location_query(self.current())
+    }
+
+    fn can_go_back(&self) -> bool {
+        self.index > 0
+    }
+
+    fn go_back(&mut self) {
+        if self.can_go_back() {
+            self.index -= 1;
+        }
+    }
+
+    fn can_go_forward(&self) -> bool {
+        self.index + 1 < self.entries.len()
+    }
+
+    fn go_forward(&mut self) {
+        if self.can_go_forward() {
+            self.index += 1;
+        }
+    }
+
+    fn push(&mut self, path: String) {
+        self.entries.truncate(self.index + 1);
+        self.entries.push_back(path);
+        if self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+        self.index = self.entries.len() - 1;
+    }
+
+    fn replace(&mut self, path: String) {
+        self.entries[self.index] = path;
+    }
+
+    fn updater(&mut self, _callback: Arc<dyn Fn() + Send + Sync>) {
+        // Every navigation of an in-memory history comes from the router, which updates itself
+    }
+}
+
+/// The path of a location with its query and fragment
+fn location_path(location: &str) -> String {
+    location
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The query of a location with its query and fragment
+fn location_query(location: &str) -> Option<String> {
+    let (_, query) = location.split('#').next()?.split_once('?')?;
+    Some(query.to_string())
+}
+
+/// Why a [`FallibleHistory`] couldn't read or change the location, like the exception a browser throws in private
+/// browsing mode or when the storage quota is exceeded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryError(pub String);
+
+impl std::fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A history backend whose calls can fail, wrapped in a [`DegradingHistory`] to give it to a router.
+///
+/// Locations are paths with their query and fragment.
+pub trait FallibleHistory {
+    fn current_location(&self) -> Result<String, HistoryError>;
+
+    fn can_go_back(&self) -> bool;
+
+    fn go_back(&mut self) -> Result<(), HistoryError>;
+
+    fn can_go_forward(&self) -> bool;
+
+    fn go_forward(&mut self) -> Result<(), HistoryError>;
+
+    fn push(&mut self, location: &str) -> Result<(), HistoryError>;
+
+    fn replace(&mut self, location: &str) -> Result<(), HistoryError>;
+
+    /// Call `callback` when the location changes from outside of the router, like with the back button.
+    fn updater(&mut self, _callback: Arc<dyn Fn() + Send + Sync>) {}
+}
+
+/// If the backend of a [`DegradingHistory`] failed, shared with whatever reports it, like a devtools panel.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryHealth(Arc<Mutex<HealthState>>);
+
+#[derive(Debug, Default)]
+struct HealthState {
+    failures: usize,
+    first_error: Option<HistoryError>,
+}
+
+impl HistoryHealth {
+    /// If the backend failed, so the history navigates in memory.
+    pub fn degraded(&self) -> bool {
+        self.failures() > 0
+    }
+
+    /// How many calls to the backend failed. After a failure every navigation stays in memory, so only reads of
+    /// the location fail again.
+    pub fn failures(&self) -> usize {
+        self.state().failures
+    }
+
+    pub fn first_error(&self) -> Option<HistoryError> {
+        self.state().first_error.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, HealthState> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn record(&self, error: HistoryError) {
+        let mut state = self.state();
+        state.failures += 1;
+        if state.first_error.is_none() {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(%error, "the history failed, navigating in memory from now on");
+            state.first_error = Some(error);
+        }
+    }
+}
+
+/// A history over a backend that can fail, like the browser's history in Safari's private mode or in some
+/// embedded webviews. A failing backend never fails a navigation.
+///
+/// After the first failure the history stops touching the backend and navigates in memory from the last location
+/// it knew, so the url stops changing but the app keeps working. Going back stops at the location of the failure.
+/// The router keeps the state and scroll position of its entries itself, so those keep working as well.
+/// [`DegradingHistory::health`] reports the failures.
+pub struct DegradingHistory<H: FallibleHistory> {
+    inner: H,
+    // The last location the backend reported or was navigated to
+    location: String,
+    // The history the router navigates in once the backend failed
+    fallback: Option<BoundedMemoryHistory>,
+    health: HistoryHealth,
+}
+
+impl<H: FallibleHistory> DegradingHistory<H> {
+    /// How many entries the history keeps in memory after the backend failed.
+    pub const FALLBACK_ENTRIES: usize = 100;
+
+    pub fn new(inner: H) -> Self {
+        let health = HistoryHealth::default();
+        let location = inner.current_location().unwrap_or_else(|err| {
+            health.record(err);
+            "/".to_string()
+        });
+        Self {
+            inner,
+            location,
+            fallback: None,
+            health,
+        }
+    }
+
+    pub fn health(&self) -> HistoryHealth {
+        self.health.clone()
+    }
+
+    fn current_location(&self) -> String {
+        if let Some(fallback) = &self.fallback {
+            return fallback.current().to_string();
+        }
+        self.inner.current_location().unwrap_or_else(|err| {
+            self.health.record(err);
+            self.location.clone()
+        })
+    }
+
+    /// Run a navigation on the backend, or on the memory history if the backend failed before or fails now.
+    fn navigate(
+        &mut self,
+        backend: impl FnOnce(&mut H) -> Result<(), HistoryError>,
+        memory: impl FnOnce(&mut BoundedMemoryHistory),
+    ) {
+        if self.fallback.is_none() && !self.health.degraded() {
+            match backend(&mut self.inner) {
+                Ok(()) => {
+                    self.location = self.current_location();
+                    return;
+                }
+                Err(err) => self.health.record(err),
+            }
+        }
+        let location = &self.location;
+        let fallback = self
+            .fallback
+            .get_or_insert_with(|| BoundedMemoryHistory::new(location, Self::FALLBACK_ENTRIES));
+        memory(fallback);
+    }
+}
+
+impl<H: FallibleHistory> HistoryProvider for DegradingHistory<H> {
+    fn current_path(&self) -> String {
+        location_path(&self.current_location())
+    }
+
+    fn current_query(&self) -> Option<String> {
+        location_query(&self.current_location())
+    }
+
+    fn can_go_back(&self) -> bool {
+        match &self.fallback {
+            Some(fallback) => fallback.can_go_back(),
+            None => self.inner.can_go_back(),
+        }
+    }
+
+    fn go_back(&mut self) {
+        self.navigate(H::go_back, BoundedMemoryHistory::go_back)
+    }
+
+    fn can_go_forward(&self) -> bool {
+        match &self.fallback {
+            Some(fallback) => fallback.can_go_forward(),
+            None => self.inner.can_go_forward(),
+        }
+    }
+
+    fn go_forward(&mut self) {
+        self.navigate(H::go_forward, BoundedMemoryHistory::go_forward)
+    }
+
+    fn push(&mut self, path: String) {
+        self.navigate(
+            |inner| inner.push(&path),
+            |fallback| fallback.push(path.clone()),
+        )
+    }
+
+    fn replace(&mut self, path: String) {
+        self.navigate(
+            |inner| inner.replace(&path),
+            |fallback| fallback.replace(path.clone()),
+        )
+    }
+
+    fn updater(&mut self, callback: Arc<dyn Fn() + Send + Sync>) {
+        self.inner.updater(callback)
+    }
+}
+
+#[test]
+fn tracked_history() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let mut history = TrackedHistory::new(MemoryHistory::default());
+    history.push("/a".to_string());
+    history.push("/b".to_string());
+    history.replace("/c".to_string());
+    assert_eq!(history.entries(), ["/", "/a", "/c"]);
+    assert_eq!(history.current_index(), 2);
+
+    history.go_back();
+    history.go_back();
+    history.go_back();
+    assert_eq!(history.current_index(), 0);
+    assert_eq!(history.current_path(), "/");
+
+    history.go_forward();
+    history.push("/d".to_string());
+    assert_eq!(history.entries(), ["/", "/a", "/d"]);
+    assert_eq!(history.entries_len(), 3);
+    assert_eq!(history.current_path(), "/d");
+}
+
+#[test]
+fn query_param_history() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let inner = MemoryHistory::with_initial_path("/page?theme=dark").unwrap();
+    let mut history = QueryParamHistory::new(inner, "panel");
+    assert_eq!(history.current_path(), "/");
+
+    history.push("/settings/profile".to_string());
+    assert_eq!(history.current_path(), "/settings/profile");
+    assert_eq!(history.inner().current_path(), "/page");
+    assert_eq!(
+        history.inner().current_query().as_deref(),
+        Some("theme=dark&panel=/settings/profile")
+    );
+
+    history.push("/search&a b".to_string());
+    assert_eq!(history.current_path(), "/search&a b");
+    assert_eq!(
+        history.inner().current_query().as_deref(),
+        Some("theme=dark&panel=/search%26a%20b")
+    );
+
+    history.go_back();
+    assert_eq!(history.current_path(), "/settings/profile");
+}
+
+#[test]
+fn bounded_memory_history() {
+    let mut history = BoundedMemoryHistory::new("/", 3);
+    for page in 0..10_000 {
+        history.push(format!("/page/{page}?from=kiosk#top"));
+    }
+    assert_eq!(history.entries_len(), 3);
+    assert_eq!(history.current_path(), "/page/9999");
+    assert_eq!(history.current_query().as_deref(), Some("from=kiosk"));
+
+    history.go_back();
+    assert_eq!(history.current_path(), "/page/9998");
+    history.go_back();
+    history.go_back();
+    assert!(!history.can_go_back());
+    assert_eq!(history.current_path(), "/page/9997");
+
+    history.push("/".to_string());
+    assert!(!history.can_go_forward());
+    assert_eq!(history.current_query(), None);
+}
+
+/// A backend that stops working after a number of pushes, like a browser that runs out of storage quota
+#[cfg(test)]
+struct FaultyHistory {
+    inner: BoundedMemoryHistory,
+    pushes_left: usize,
+}
+
+#[cfg(test)]
+impl FallibleHistory for FaultyHistory {
+    fn current_location(&self) -> Result<String, HistoryError> {
+        Ok(self.inner.current().to_string())
+    }
+
+    fn can_go_back(&self) -> bool {
+        self.inner.can_go_back()
+    }
+
+    fn go_back(&mut self) -> Result<(), HistoryError> {
+        self.inner.go_back();
+        Ok(())
+    }
+
+    fn can_go_forward(&self) -> bool {
+        self.inner.can_go_forward()
+    }
+
+    fn go_forward(&mut self) -> Result<(), HistoryError> {
+        self.inner.go_forward();
+        Ok(())
+    }
+
+    fn push(&mut self, location: &str) -> Result<(), HistoryError> {
+        self.pushes_left = self
+            .pushes_left
+            .checked_sub(1)
+            .ok_or_else(|| HistoryError("QuotaExceededError".to_string()))?;
+        self.inner.push(location.to_string());
+        Ok(())
+    }
+
+    fn replace(&mut self, _location: &str) -> Result<(), HistoryError> {
+        Err(HistoryError("SecurityError".to_string()))
+    }
+}
+
+#[test]
+fn degrading_history() {
+    use crate::{Route, Router};
+
+    let history = DegradingHistory::new(FaultyHistory {
+        inner: BoundedMemoryHistory::new("/", 10),
+        pushes_left: 1,
+    });
+    let health = history.health();
+    let mut router = Router::<Route>::new(history).unwrap();
+
+    router.push(Route::Route3 { dynamic: 1 });
+    assert!(!health.degraded());
+
+    // The backend fails, but the navigation still happens in memory
+    router.push(Route::Route4 {
+        number1: 1,
+        number2: 2,
+    });
+    assert_eq!(&*router.current_url(), "/1/2");
+    assert_eq!(health.failures(), 1);
+    assert_eq!(
+        health.first_error(),
+        Some(HistoryError("QuotaExceededError".to_string()))
+    );
+
+    // Later navigations don't touch the backend again
+    router.replace(Route::Route5 {});
+    router.push(Route::Route3 { dynamic: 2 });
+    assert_eq!(health.failures(), 1);
+    router.go_back().unwrap();
+    assert_eq!(router.route, Route::Route5 {});
+    router.go_back().unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 1 });
+    assert!(!router.history.can_go_back());
+}
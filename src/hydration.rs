@@ -0,0 +1,107 @@
+//! Start a client router on a page the server already rendered, without checking its first route again.
+//!
+//! [`server_render`](crate::failure::server_render) returns a [`HydrationMarker`] with the page, to embed next to
+//! the html. The client reads it back, with [`HydrationMarker::from_document`] in the browser, and passes it as
+//! [`RouterConfiguration::hydration`](crate::RouterConfiguration). If the location of the history is the one the
+//! server rendered, the router takes the first route as it is: its guards don't run again and the navigation is
+//! reported as [`NavigationSource::Hydration`](crate::NavigationSource). Every later navigation is checked as usual.
+
+use crate::encoding::{decode_query_component, encode_query_component};
+
+/// The `id` of the element the marker is embedded in
+pub const MARKER_ID: &str = "router-hydration";
+
+/// The location a server rendered a page for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HydrationMarker {
+    pub location: String,
+}
+
+impl HydrationMarker {
+    /// The element to embed in the page. The location is percent-encoded, so it never closes the element early.
+    pub fn to_html(&self) -> String {
+        format!(
+            r#"<template id="{MARKER_ID}" data-location="{}"></template>"#,
+            encode_query_component(&self.location)
+        )
+    }
+
+    /// Read a marker from the `data-location` attribute of the embedded element.
+    pub fn from_attribute(value: &str) -> Option<Self> {
+        Some(Self {
+            location: decode_query_component(value)?,
+        })
+    }
+
+    /// The marker embedded in the current page, if the server rendered it.
+    #[cfg(feature = "web")]
+    pub fn from_document() -> Option<Self> {
+        let element = web_sys::window()?
+            .document()?
+            .get_element_by_id(MARKER_ID)?;
+        Self::from_attribute(&element.get_attribute("data-location")?)
+    }
+}
+
+#[test]
+fn hydration_markers() {
+    let marker = HydrationMarker {
+        location: "/search/a\"><script>/1/stars?ref=\"x\"".to_string(),
+    };
+    let html = marker.to_html();
+    assert!(!html.contains("<script>"), "{html}");
+    let value = html
+        .split("data-location=\"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap();
+    assert_eq!(HydrationMarker::from_attribute(value), Some(marker));
+}
+
+#[test]
+fn hydration() {
+    use crate::failure::server_render;
+    use crate::guard::{GuardContext, GuardResult};
+    use crate::navigation::NavigationSource;
+    use crate::{Route, Router, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+
+    thread_local! {
+        static GUARD_RUNS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    // A guard that only counts how often it ran
+    fn count_guard(_: &GuardContext<Route>) -> GuardResult<Route> {
+        GUARD_RUNS.with(|runs| runs.set(runs.get() + 1));
+        GuardResult::Allow
+    }
+
+    let runs = || GUARD_RUNS.with(std::cell::Cell::get);
+    let config = |hydration| RouterConfiguration {
+        guards: vec![count_guard],
+        hydration,
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/hello_world/1").unwrap();
+    let server = Router::<Route>::with_configuration(history, config(None)).unwrap();
+    let page = server_render(server);
+    assert_eq!(runs(), 1);
+    assert_eq!(page.hydration.location, "/hello_world/1");
+
+    let history = MemoryHistory::with_initial_path("/hello_world/1").unwrap();
+    let mut client =
+        Router::<Route>::with_configuration(history, config(Some(page.hydration.clone()))).unwrap();
+    assert_eq!(runs(), 1);
+    assert_eq!(client.navigation_source(), &NavigationSource::Hydration);
+
+    // Later navigations are checked as usual
+    client.push(Route::Route2 {});
+    assert_eq!(runs(), 2);
+
+    // A page rendered for another location is checked again
+    let history = MemoryHistory::with_initial_path("/hello_world/2").unwrap();
+    let client =
+        Router::<Route>::with_configuration(history, config(Some(page.hydration))).unwrap();
+    assert_eq!(runs(), 3);
+    assert_eq!(client.navigation_source(), &NavigationSource::Browser);
+}
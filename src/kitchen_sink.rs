@@ -0,0 +1,214 @@
+//! A small shop that uses the features of the router together, as a reference for how they compose. The tests at
+//! the bottom parse, navigate and server-render it, so the reference breaks the build instead of going stale.
+//!
+//! It lives in the app rather than in its own crate because the router runtime is part of the app.
+
+use super::*;
+use crate::context::{use_query_value, use_query_values};
+use crate::router_test::TestRouter;
+use dioxus_router_core::history::MemoryHistory;
+use std::cell::Cell;
+
+/// How a product listing is sorted
+#[derive(RouteSegmentEnum, Clone, Copy, Debug, PartialEq)]
+enum ShopOrder {
+    Newest,
+    #[segment(rename = "price")]
+    Cheapest,
+}
+
+/// The id of a customer
+#[derive(RouteSegment, Clone, Copy, Debug, PartialEq)]
+struct CustomerId(u32);
+
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum ShopRoute {
+    #[route("/" ShopHome, title = "Shop")]
+    Home {},
+    /// A static prefix and an enum segment
+    #[route("/products/(order)" ShopListing)]
+    Listing { order: ShopOrder },
+    /// Readable product urls, whose slug is lowercased when they are parsed
+    #[route("/products/(id)/(slug)" ShopProduct, title = "Product {id}", normalize = lowercase_slug)]
+    Product { id: u32, slug: String },
+    /// A dynamic segment with a suffix
+    #[route("/images/(id).png" ShopImage)]
+    Image { id: u32 },
+    /// A newtype segment
+    #[route("/customers/(customer)" ShopCustomer)]
+    Customer { customer: CustomerId },
+    /// The search terms are in the query, like `/search?q=chair&page=2`
+    #[route("/search" ShopSearch)]
+    Search {},
+    /// A catch-all docs viewer
+    #[route("/docs/(...path)" ShopDocs)]
+    Docs { path: UrlPath },
+    /// Only signed in users get to the admin dashboard, the others sign in first
+    #[route("/admin", action = admin_guard)]
+    Admin {},
+    #[route("/admin/dashboard" ShopDashboard)]
+    Dashboard {},
+    #[route("/login" ShopLogin)]
+    Login {},
+}
+
+thread_local! {
+    static SIGNED_IN: Cell<bool> = const { Cell::new(false) };
+}
+
+fn admin_guard(router: &mut Router<ShopRoute>) {
+    match SIGNED_IN.with(Cell::get) {
+        true => router.replace(ShopRoute::Dashboard {}),
+        false => router.push_with_return(ShopRoute::Login {}, "next"),
+    }
+}
+
+fn lowercase_slug(_id: &mut u32, slug: &mut String) {
+    slug.make_ascii_lowercase();
+}
+
+#[allow(non_snake_case)]
+fn ShopHome(cx: Scope) -> Element {
+    render! { "Welcome to the shop" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ShopListing(cx: Scope, order: ShopOrder) -> Element {
+    render! { "Products by {order}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ShopProduct(cx: Scope, id: u32, slug: String) -> Element {
+    render! { "Product {id}: {slug}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ShopImage(cx: Scope, id: u32) -> Element {
+    render! { "Image {id}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ShopCustomer(cx: Scope, customer: CustomerId) -> Element {
+    render! { "Customer {customer}" }
+}
+
+#[allow(non_snake_case)]
+fn ShopSearch(cx: Scope) -> Element {
+    let terms = use_query_values::<ShopRoute>(cx, "q").join(" ");
+    let page = match use_query_value::<ShopRoute, u32>(cx, "page") {
+        Some(Ok(page)) => page,
+        _ => 1,
+    };
+    render! { "Results for {terms}, page {page}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ShopDocs(cx: Scope, path: UrlPath) -> Element {
+    let page = path.iter().collect::<Vec<_>>().join("/");
+    render! { "Docs at {page}" }
+}
+
+#[allow(non_snake_case)]
+fn ShopDashboard(cx: Scope) -> Element {
+    render! { "Dashboard" }
+}
+
+#[allow(non_snake_case)]
+fn ShopLogin(cx: Scope) -> Element {
+    render! { "Sign in" }
+}
+
+/// Render a request on the server. A not found page suggests the routes closest to the path.
+fn render_page(path: &str) -> String {
+    let mut vdom = VirtualDom::new_with_props(
+        ShopRoute::comp,
+        RouterProps {
+            current_route: path.to_string(),
+            max_suggestions: 3,
+            dynamic_routes: DynamicRoutes::default(),
+            legacy_fallback: None,
+        },
+    );
+    let _ = vdom.rebuild();
+    dioxus_ssr::render(&vdom)
+}
+
+#[test]
+fn shop_round_trips() {
+    let routes = [
+        ShopRoute::Home {},
+        ShopRoute::Listing {
+            order: ShopOrder::Cheapest,
+        },
+        ShopRoute::Product {
+            id: 7,
+            slug: "oak-chair".to_string(),
+        },
+        ShopRoute::Image { id: 7 },
+        ShopRoute::Customer {
+            customer: CustomerId(12),
+        },
+        ShopRoute::Search {},
+        ShopRoute::Docs {
+            path: UrlPath::from_route_segments("shipping/returns".split('/')).unwrap(),
+        },
+        ShopRoute::Dashboard {},
+        ShopRoute::Login {},
+    ];
+    for route in routes {
+        assert_eq!(ShopRoute::from_str(&route.to_string()), Ok(route));
+    }
+    assert_eq!(
+        ShopRoute::from_str("/products/7/Oak-Chair")
+            .unwrap()
+            .to_string(),
+        "/products/7/oak-chair"
+    );
+    assert_eq!(
+        ShopRoute::from_str("/images/7.png"),
+        Ok(ShopRoute::Image { id: 7 })
+    );
+    assert!(ShopRoute::from_str("/products/cheap").is_err());
+}
+
+#[test]
+fn shop_navigation() {
+    let mut shop = TestRouter::<ShopRoute>::new("/search?q=oak+chair&page=2");
+    assert!(shop
+        .rendered_html()
+        .contains("Results for oak chair, page 2"));
+
+    shop.navigate(ShopRoute::Product {
+        id: 7,
+        slug: "oak-chair".to_string(),
+    });
+    assert!(shop.rendered_html().contains("Product 7: oak-chair"));
+    shop.back();
+    assert_eq!(shop.current_route(), ShopRoute::Search {});
+
+    // The guard sends signed out users to the login page and brings them back after they signed in
+    let mut router = Router::<ShopRoute>::new(MemoryHistory::default()).unwrap();
+    router.push(ShopRoute::Admin {});
+    assert_eq!(router.route, ShopRoute::Login {});
+    assert_eq!(&*router.current_url(), "/login?next=/admin");
+    SIGNED_IN.with(|signed_in| signed_in.set(true));
+    router.pop_return("next", ShopRoute::Home {});
+    assert_eq!(router.route, ShopRoute::Dashboard {});
+    assert_eq!(&*router.current_url(), "/admin/dashboard");
+    SIGNED_IN.with(|signed_in| signed_in.set(false));
+}
+
+#[test]
+fn shop_server_render() {
+    assert!(render_page("/products/price").contains("Products by price"));
+    assert!(render_page("/docs/shipping/returns").contains("Docs at shipping/returns"));
+
+    let not_found = render_page("/prodcts/7/oak-chair");
+    assert!(not_found.contains("Did you mean:"), "{not_found}");
+    assert!(not_found.contains("/products/7/oak-chair"), "{not_found}");
+}
@@ -0,0 +1,104 @@
+//! A legacy route table that answers some paths before the derived routes, for apps migrating from another router.
+//!
+//! The router component checks the [`LegacyFallback`] first for paths under one of its prefixes, and renders the
+//! component of the [`LegacyMatch`] if the legacy table has one. Every other path, and every path the table doesn't
+//! match, goes to the derived routes as before. Navigating, links and hrefs only know the derived routes, so legacy
+//! pages can't be linked to by route.
+//!
+//! With the `metrics` feature, every legacy match is counted by prefix, so a prefix can be removed once it stops
+//! being hit.
+
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+/// A page of the legacy route table.
+#[derive(Clone)]
+pub struct LegacyMatch {
+    pub render: Component,
+    /// Whatever the legacy table parsed from the path, read with [`use_legacy_params`]
+    pub params: HashMap<String, String>,
+}
+
+/// The legacy route table, consulted before the derived routes.
+#[derive(Clone)]
+pub struct LegacyFallback {
+    /// Only paths under these prefixes go to the legacy table. Prefixes match whole segments, like the excluded
+    /// prefixes of the router
+    pub prefixes: Vec<String>,
+    /// Match a path, with its query, against the legacy table
+    pub matcher: fn(&str) -> Option<LegacyMatch>,
+}
+
+impl PartialEq for LegacyFallback {
+    fn eq(&self, other: &Self) -> bool {
+        self.prefixes == other.prefixes && std::ptr::fn_addr_eq(self.matcher, other.matcher)
+    }
+}
+
+impl LegacyFallback {
+    /// The legacy page for a path and the prefix it is under, if the path is under a prefix and the table matches it
+    pub fn match_path(&self, path: &str) -> Option<(&str, LegacyMatch)> {
+        let prefix = crate::link::excluded_prefix(&self.prefixes, path)?;
+        Some((prefix, (self.matcher)(path)?))
+    }
+}
+
+/// The params of the current legacy page.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LegacyParams(pub HashMap<String, String>);
+
+/// The params the legacy table parsed for the current page, or `None` outside of a legacy page.
+pub fn use_legacy_params(cx: &ScopeState) -> Option<LegacyParams> {
+    cx.consume_context()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn LegacyArticle(cx: Scope) -> Element {
+    let params = use_legacy_params(cx).unwrap_or_default();
+    let id = params.0.get("id").cloned().unwrap_or_default();
+    render! { "legacy article {id}" }
+}
+
+/// A legacy table that still serves the first two articles, which the derived routes parse as well
+#[cfg(test)]
+pub fn legacy_articles(path: &str) -> Option<LegacyMatch> {
+    let id = path.strip_prefix("/hello_world/")?;
+    matches!(id, "1" | "2").then(|| LegacyMatch {
+        render: LegacyArticle,
+        params: HashMap::from([("id".to_string(), id.to_string())]),
+    })
+}
+
+#[test]
+fn legacy_fallback() {
+    use crate::{DynamicRoutes, Route, RouterProps};
+
+    let fallback = LegacyFallback {
+        prefixes: vec!["/hello_world".to_string()],
+        matcher: legacy_articles,
+    };
+    assert_eq!(
+        fallback.match_path("/hello_world/1").unwrap().0,
+        "/hello_world"
+    );
+    assert!(fallback.match_path("/hello_world/3").is_none());
+
+    let render = |current_route: &str| {
+        let mut vdom = VirtualDom::new_with_props(
+            Route::comp,
+            RouterProps {
+                current_route: current_route.to_string(),
+                max_suggestions: 3,
+                dynamic_routes: DynamicRoutes::default(),
+                legacy_fallback: Some(fallback.clone()),
+            },
+        );
+        let _ = vdom.rebuild();
+        dioxus_ssr::render(&vdom)
+    };
+    // The legacy table comes first for the paths it still matches
+    assert_eq!(render("/hello_world/1"), "legacy article 1");
+    assert!(render("/hello_world/3").contains("dynamic: 3"));
+    assert!(render("/12/34").contains("number2: 34"));
+}
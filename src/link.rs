@@ -0,0 +1,414 @@
+use dioxus::html::input_data::keyboard_types::Modifiers;
+use dioxus::html::input_data::MouseButton;
+use dioxus::prelude::MouseData;
+
+/// The `rel` attribute external links get unless it is overridden.
+pub const EXTERNAL_REL: &str = "noopener noreferrer";
+
+/// The location the app is served from, like `https://example.com/app`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseUrl {
+    scheme: String,
+    host: String,
+    port: Option<u16>,
+    path: String,
+    excluded_prefixes: Vec<String>,
+}
+
+/// Where a link points, relative to the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkTarget<'a> {
+    /// A path inside the app, without the base path. The router should handle the navigation.
+    Internal(&'a str),
+    /// A link that leaves the app. It should open in a new tab with [`EXTERNAL_REL`] and the browser should
+    /// handle the click.
+    External(&'a str),
+}
+
+impl BaseUrl {
+    /// Parse an absolute base url. Returns `None` if the url has no scheme or host.
+    pub fn parse(url: &str) -> Option<Self> {
+        let (scheme, host, port, path) = split_url(url)?;
+        Some(Self {
+            scheme: scheme.to_ascii_lowercase(),
+            host: host.to_ascii_lowercase(),
+            port: port.or_else(|| default_port(scheme)),
+            path: path.trim_end_matches('/').to_string(),
+            excluded_prefixes: Vec::new(),
+        })
+    }
+
+    /// Treat links to paths under these prefixes, like `/static` or `/api`, as external even though they are on the
+    /// same origin, so the browser loads them instead of the router. See [`excluded_prefix`].
+    pub fn with_excluded_prefixes(mut self, prefixes: Vec<String>) -> Self {
+        self.excluded_prefixes = prefixes;
+        self
+    }
+
+    /// The path the app is served under, like `/app`, without a slash at the end. Empty if the app is served from
+    /// the root. Parse locations under it with the generated `parse_with_base`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Decide if a link stays inside the app.
+    ///
+    /// Relative links are always internal. Absolute urls are internal if their scheme, host and port match the
+    /// base url and their path is inside the base path; the internal path is the rest of the path after the base.
+    /// Internal paths under an excluded prefix are external after all.
+    pub fn classify<'a>(&self, href: &'a str) -> LinkTarget<'a> {
+        match self.classify_origin(href) {
+            LinkTarget::Internal(path)
+                if excluded_prefix(&self.excluded_prefixes, path).is_some() =>
+            {
+                LinkTarget::External(href)
+            }
+            target => target,
+        }
+    }
+
+    /// The absolute url of a location inside the app, like `https://example.com/app/settings` for `/settings`.
+    /// This is the `href` links render, so copying the link address gives a url that works outside the app.
+    pub fn href(&self, location: &str) -> String {
+        let port = match self.port.is_some() && self.port != default_port(&self.scheme) {
+            true => format!(":{}", self.port.unwrap_or_default()),
+            false => String::new(),
+        };
+        let location = match location {
+            "/" if !self.path.is_empty() => "",
+            location => location,
+        };
+        format!(
+            "{}://{}{port}{}{location}",
+            self.scheme, self.host, self.path
+        )
+    }
+
+    fn classify_origin<'a>(&self, href: &'a str) -> LinkTarget<'a> {
+        if href.starts_with('/') && !href.starts_with("//") {
+            return LinkTarget::Internal(href);
+        }
+
+        let internal_path = split_url(href).and_then(|(scheme, host, port, path)| {
+            let same_origin = scheme.eq_ignore_ascii_case(&self.scheme)
+                && host.eq_ignore_ascii_case(&self.host)
+                && port.or_else(|| default_port(scheme)) == self.port;
+            if !same_origin {
+                return None;
+            }
+            strip_base_path(&self.path, path)
+        });
+
+        match internal_path {
+            Some(path) => LinkTarget::Internal(path),
+            None => LinkTarget::External(href),
+        }
+    }
+}
+
+/// The parts of a click on a link that decide if the router handles it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkClick {
+    /// If the primary button was pressed. Pressing Enter on a focused link is a click of the primary button too
+    pub primary_button: bool,
+    pub ctrl: bool,
+    /// The command key on macOS, the windows key elsewhere
+    pub meta: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// A handler that ran before the router prevented the default of the click
+    pub default_prevented: bool,
+}
+
+impl LinkClick {
+    /// Read a click from a mouse event of the renderer. The event doesn't tell if the default was prevented, so
+    /// set [`LinkClick::default_prevented`] afterwards.
+    pub fn from_mouse(event: &MouseData) -> Self {
+        let modifiers = event.modifiers();
+        Self {
+            primary_button: matches!(event.trigger_button(), None | Some(MouseButton::Primary)),
+            ctrl: modifiers.contains(Modifiers::CONTROL),
+            meta: modifiers.contains(Modifiers::META),
+            shift: modifiers.contains(Modifiers::SHIFT),
+            alt: modifiers.contains(Modifiers::ALT),
+            default_prevented: false,
+        }
+    }
+
+    /// If the router should navigate instead of the browser. Clicks with another button or with a modifier open
+    /// the link in a new tab or window or download it, so they are left to the browser, like clicks an earlier
+    /// handler already handled.
+    pub fn intercepted(&self) -> bool {
+        self.primary_button
+            && !(self.ctrl || self.meta || self.shift || self.alt || self.default_prevented)
+    }
+
+    /// A click of the primary button without modifiers, like pressing Enter on a focused link.
+    pub fn primary() -> Self {
+        Self {
+            primary_button: true,
+            ..Self::default()
+        }
+    }
+
+    /// The `prevent_default` attribute of a link for the click that follows this press of a button. The renderer
+    /// prevents the default of a click from the attribute before any handler runs, so links set it when the button
+    /// goes down: clicks the router handles are kept from the browser, and the others, like ctrl-click to open a
+    /// new tab, reach it untouched.
+    pub fn prevent_default_attribute(&self) -> &'static str {
+        match self.intercepted() {
+            true => "onclick",
+            false => "",
+        }
+    }
+}
+
+/// The location inside the app of a `location` under the `base` path, like `/settings` for `/app/settings` under
+/// `/app`, and `None` if it isn't under the base. A slash at the end of the base is ignored.
+pub fn strip_base_path<'a>(base: &str, location: &'a str) -> Option<&'a str> {
+    match location.strip_prefix(base.trim_end_matches('/'))? {
+        "" => Some("/"),
+        rest if rest.starts_with(['/', '?', '#']) => Some(rest),
+        // `/application` is not inside the base path `/app`
+        _ => None,
+    }
+}
+
+/// Where the path of a location starts: after the scheme and host of a full url, like `/blog/3?x=1` in
+/// `https://example.com/blog/3?x=1`, and at the start of anything else. Only a scheme of letters, digits, `+`, `-` and
+/// `.` counts, so a path like `/go/https://example.com` starts at 0.
+pub fn url_path_start(location: &str) -> usize {
+    let Some((scheme, _)) = location.split_once("://") else {
+        return 0;
+    };
+    let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    match split_url(location) {
+        Some((.., path)) if valid_scheme => location.len() - path.len(),
+        _ => 0,
+    }
+}
+
+/// The first of `prefixes` that `path` is under, if any. Prefixes match whole segments, so `/static` excludes
+/// `/static` and `/static/app.css` but not `/staticfiles`. A trailing slash on a prefix is ignored.
+pub fn excluded_prefix<'a>(prefixes: &'a [String], path: &str) -> Option<&'a str> {
+    prefixes.iter().map(String::as_str).find(|prefix| {
+        let prefix = prefix.trim_end_matches('/');
+        match path.strip_prefix(prefix) {
+            Some(rest) => rest.is_empty() || rest.starts_with(['/', '?', '#']),
+            None => false,
+        }
+    })
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme.to_ascii_lowercase().as_str() {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        _ => None,
+    }
+}
+
+/// Split an absolute url into its scheme, host, port and the rest of the url starting at the path.
+fn split_url(url: &str) -> Option<(&str, &str, Option<u16>, &str)> {
+    let (scheme, rest) = url.split_once("://")?;
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(authority_end);
+    // Ignore any user info before the host
+    let authority = authority.rsplit('@').next().unwrap_or(authority);
+    let (host, port) = match authority.rsplit_once(':') {
+        // Only split on the last colon if it isn't part of an IPv6 address
+        Some((host, port)) if !port.contains(']') => (host, Some(port.parse().ok()?)),
+        _ => (authority, None),
+    };
+    if scheme.is_empty() || host.is_empty() {
+        return None;
+    }
+    Some((scheme, host, port, path))
+}
+
+#[test]
+fn classify_links() {
+    let base = BaseUrl::parse("https://example.com/app/").unwrap();
+
+    assert_eq!(
+        base.classify("/settings"),
+        LinkTarget::Internal("/settings")
+    );
+    assert_eq!(
+        base.classify("https://example.com/app/settings?tab=1"),
+        LinkTarget::Internal("/settings?tab=1")
+    );
+    assert_eq!(
+        base.classify("https://EXAMPLE.com:443/app"),
+        LinkTarget::Internal("/")
+    );
+    assert_eq!(
+        base.classify("https://example.com/application"),
+        LinkTarget::External("https://example.com/application")
+    );
+    assert_eq!(
+        base.classify("https://example.com:8443/app/settings"),
+        LinkTarget::External("https://example.com:8443/app/settings")
+    );
+    assert_eq!(
+        base.classify("http://example.com/app/settings"),
+        LinkTarget::External("http://example.com/app/settings")
+    );
+    assert_eq!(
+        base.classify("//example.org/app"),
+        LinkTarget::External("//example.org/app")
+    );
+    assert_eq!(
+        base.classify("mailto:someone@example.com"),
+        LinkTarget::External("mailto:someone@example.com")
+    );
+
+    let local = BaseUrl::parse("http://localhost:8080").unwrap();
+    assert_eq!(
+        local.classify("http://localhost:8080/settings"),
+        LinkTarget::Internal("/settings")
+    );
+    assert_eq!(
+        local.classify("http://localhost/settings"),
+        LinkTarget::External("http://localhost/settings")
+    );
+}
+
+#[test]
+fn absolute_hrefs() {
+    let base = BaseUrl::parse("https://example.com/app/").unwrap();
+    assert_eq!(
+        base.href("/settings?tab=1"),
+        "https://example.com/app/settings?tab=1"
+    );
+    assert_eq!(base.href("/"), "https://example.com/app");
+    assert_eq!(
+        base.href("/docs#install"),
+        "https://example.com/app/docs#install"
+    );
+    assert_eq!(
+        base.classify(&base.href("/settings")),
+        LinkTarget::Internal("/settings")
+    );
+
+    let local = BaseUrl::parse("http://localhost:8080").unwrap();
+    assert_eq!(local.href("/"), "http://localhost:8080/");
+    let default_port = BaseUrl::parse("https://example.com:443").unwrap();
+    assert_eq!(
+        default_port.href("/settings"),
+        "https://example.com/settings"
+    );
+}
+
+#[test]
+fn intercepted_clicks() {
+    let click = LinkClick {
+        primary_button: true,
+        ..Default::default()
+    };
+    // Also what pressing Enter on a focused link looks like
+    assert!(click.intercepted());
+
+    let middle = LinkClick {
+        primary_button: false,
+        ..click
+    };
+    assert!(!middle.intercepted());
+    for modified in [
+        LinkClick {
+            ctrl: true,
+            ..click
+        },
+        LinkClick {
+            meta: true,
+            ..click
+        },
+        LinkClick {
+            shift: true,
+            ..click
+        },
+        LinkClick { alt: true, ..click },
+    ] {
+        assert!(!modified.intercepted(), "{modified:?}");
+    }
+    let prevented = LinkClick {
+        default_prevented: true,
+        ..click
+    };
+    assert!(!prevented.intercepted());
+}
+
+#[test]
+fn excluded_prefixes() {
+    let prefixes = vec!["/static".to_string(), "/api/".to_string()];
+    assert_eq!(excluded_prefix(&prefixes, "/static"), Some("/static"));
+    assert_eq!(
+        excluded_prefix(&prefixes, "/static/app.css"),
+        Some("/static")
+    );
+    assert_eq!(excluded_prefix(&prefixes, "/api?v=2"), Some("/api/"));
+    assert_eq!(excluded_prefix(&prefixes, "/api/users/1"), Some("/api/"));
+    assert_eq!(excluded_prefix(&prefixes, "/staticfiles"), None);
+    assert_eq!(excluded_prefix(&prefixes, "/settings/api"), None);
+
+    let base = BaseUrl::parse("https://example.com/app")
+        .unwrap()
+        .with_excluded_prefixes(prefixes);
+    assert_eq!(
+        base.classify("/static/logo.png"),
+        LinkTarget::External("/static/logo.png")
+    );
+    assert_eq!(
+        base.classify("https://example.com/app/api/users"),
+        LinkTarget::External("https://example.com/app/api/users")
+    );
+    assert_eq!(
+        base.classify("/staticfiles"),
+        LinkTarget::Internal("/staticfiles")
+    );
+}
+
+#[test]
+fn url_paths() {
+    let url = "https://example.com:8080/blog/3?x=1";
+    assert_eq!(&url[url_path_start(url)..], "/blog/3?x=1");
+    assert_eq!(
+        url_path_start("https://example.com"),
+        "https://example.com".len()
+    );
+    assert_eq!(url_path_start("/blog/3"), 0);
+    assert_eq!(url_path_start("/go/https://example.com"), 0);
+    assert_eq!(url_path_start("1http://example.com/"), 0);
+}
+
+#[test]
+fn excluded_locations() {
+    use crate::{ParseLimit, Route, Router, RouterConfiguration};
+    use dioxus_router_core::history::{HistoryProvider, MemoryHistory};
+
+    let config = || RouterConfiguration {
+        excluded_prefixes: vec!["/static".to_string(), "/hello_world/7".to_string()],
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/static/app.css").unwrap();
+    let err = Router::<Route>::with_configuration(history, config())
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.limit,
+        Some(ParseLimit::ExcludedPrefix {
+            prefix: "/static".to_string()
+        })
+    );
+    assert!(err.attempted_routes.is_empty());
+
+    let history = MemoryHistory::with_initial_path("/hello_world/70").unwrap();
+    let mut router = Router::<Route>::with_configuration(history, config()).unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 70 });
+    router.history.push("/hello_world/7".to_string());
+    assert!(router.sync_route().unwrap_err().limit.is_some());
+    assert_eq!(router.route, Route::Route3 { dynamic: 70 });
+}
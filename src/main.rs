@@ -1,166 +1,4260 @@
+// Built as the fuzz target in `fuzz/`, which only runs `fuzz/fuzz_targets/from_path.rs`
+#![cfg_attr(fuzzing, no_main, allow(dead_code))]
+
+use cache::{RouteCache, SharedRouteCache};
+use context::Subscribers;
+#[cfg(feature = "devtools")]
+use coverage::{CoverageStorage, RouteCoverage};
 use dioxus::prelude::*;
 use dioxus_router_core::history::HistoryProvider;
-use router::Routable;
+use dynamic_routes::{DynamicRouteProps, DynamicRoutes};
+use encoding::{
+    decode_path_segment, decode_query_component, encode_query_component, sort_query, DecodeError,
+    EncodeSet, EncodedSegment, EncodedSegmentWith, SegmentError,
+};
+use failure::{ErrorRoute, NavigationFailure};
+#[cfg(any(test, feature = "fixtures"))]
+use fixtures::FixtureValue;
+use fragment::{
+    split_fragment, DefaultFragment, MissingFragment, RequiredFragment, ScrollHistory,
+    ScrollRequest,
+};
+use guard::{Guard, GuardContext, GuardResult, RouterContexts};
+use head::{DocumentLang, DocumentRoot, HeadMeta};
+use hydration::HydrationMarker;
+use legacy::{LegacyFallback, LegacyParams};
+use link::{strip_base_path, url_path_start, BaseUrl};
+use matrix::{matrix_string, parse_matrix, split_matrix, DisplayMatrix, FromMatrix, MatrixParams};
+use navigation::{NavigationKind, NavigationSource, RouteChange};
+#[cfg(feature = "devtools")]
+use navigation_log::NavigationLog;
+use nested::{ChildOutlet, HasChild};
+use not_found::NotFoundContext;
+use palette::PaletteEntry;
+use precache::PrecacheManifest;
+use query::{DisplayQuery, FromQuery, QueryParams, ToQuery};
+use rewrite::{rewrite_location, RewriteRule};
+use router::{link, route_segments, routes_from_file, Routable, RouteSegment, RouteSegmentEnum};
+use segments::{
+    with_query, DisplayRouteSegment, DisplayRouteSegments, FilledSegment, FromRouteSegment,
+    FromRouteSegments, SegmentCursor, SegmentPattern, SegmentValue, ToRouteSegment,
+    ToRouteSegments, UnknownSegmentValue,
+};
+use session::RouterSession;
+use site_map::{RouteInfo, SegmentInfo};
+use std::rc::Rc;
 use std::str::FromStr;
+use sub_router::{render_sub_router, MountedSubRouter};
+use url_path::UrlPath;
+
+mod cache;
+mod context;
+#[cfg(feature = "devtools")]
+mod coverage;
+mod dynamic_routes;
+#[path = "../router/src/edit_distance.rs"]
+mod edit_distance;
+mod encoding;
+#[cfg(feature = "diagnostics")]
+mod explain;
+mod failure;
+#[cfg(any(test, feature = "fixtures"))]
+mod fixtures;
+mod fragment;
+#[cfg(fuzzing)]
+#[path = "../fuzz/fuzz_targets/from_path.rs"]
+mod fuzz_target;
+mod guard;
+mod head;
+mod history;
+mod hydration;
+#[cfg(test)]
+mod kitchen_sink;
+mod legacy;
+mod link;
+mod matrix;
+mod nav_menu;
+mod navigation;
+#[cfg(feature = "devtools")]
+mod navigation_log;
+mod navigator;
+mod nested;
+mod not_found;
+mod palette;
+mod pattern;
+mod precache;
+mod query;
+mod query_form;
+mod resolve;
+mod rewrite;
+mod round_trip;
+#[cfg(feature = "metrics")]
+mod route_metrics;
+#[cfg(test)]
+mod router_test;
+mod segments;
+#[cfg(feature = "http")]
+mod server_actions;
+mod session;
+#[cfg(feature = "web")]
+mod shortcuts;
+mod site_map;
+mod sub_router;
+mod transaction;
+mod url_path;
+mod validate;
+#[cfg(feature = "devtools")]
+mod watermark;
+
+#[derive(Debug, PartialEq)]
+struct RouteParseError<E: std::fmt::Display> {
+    attempted_routes: Vec<E>,
+    /// The limit the path broke. No route was tried, so `attempted_routes` is empty.
+    limit: Option<ParseLimit>,
+}
+
+impl<E: std::fmt::Display> RouteParseError<E> {
+    /// The error for a path the parser rejected without trying any route
+    fn limit(limit: ParseLimit) -> Self {
+        Self {
+            attempted_routes: Vec::new(),
+            limit: Some(limit),
+        }
+    }
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for RouteParseError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(limit) = &self.limit {
+            return write!(f, "Route did not match: {limit}");
+        }
+        write!(f, "Route did not match:\nAttempted Matches:\n")?;
+        for (i, route) in self.attempted_routes.iter().enumerate() {
+            writeln!(f, "{}) {route}", i + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// The source is the error of the first route that was tried.
+impl<E: std::error::Error + 'static> std::error::Error for RouteParseError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.attempted_routes.first().map(|error| error as _)
+    }
+}
+
+impl std::error::Error for ParseLimit {}
+
+/// The error of one route the parser tried. Implemented by the match error enums the routes derive.
+trait AttemptedRoute {
+    /// The byte range of the input that failed to match
+    fn span(&self) -> std::ops::Range<usize>;
+
+    /// If a dynamic segment couldn't be percent-decoded, which means the path is malformed rather than for another
+    /// route
+    fn is_decode_error(&self) -> bool;
+}
+
+impl<E: AttemptedRoute + std::fmt::Display> RouteParseError<E> {
+    /// The error to report to a client, like in the body of a 404 response. A segment that couldn't be decoded is
+    /// reported first, since the client most likely sent a malformed path. Otherwise it is the route that matched
+    /// the furthest into the path, and the first of those in the order the routes were tried.
+    fn best_match(&self) -> Option<&E> {
+        self.attempted_routes
+            .iter()
+            .find(|error| error.is_decode_error())
+            .or_else(|| {
+                self.attempted_routes
+                    .iter()
+                    .rev()
+                    .max_by_key(|error| error.span().start)
+            })
+    }
+}
+
+/// A limit of the generated parser that a path broke, see [`Routable::parse_limits`], or another reason to reject
+/// a path without trying any route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParseLimit {
+    /// The path is `len` bytes long, but the parser accepts at most `max`
+    PathTooLong { len: usize, max: usize },
+    /// The path has more than `max` segments
+    TooManySegments { max: usize },
+    /// The path is under one of the [`RouterConfiguration::excluded_prefixes`], so it isn't a route of the app
+    ExcludedPrefix { prefix: String },
+    /// The `shared_guard = ...` of the routes rejected the values of the shared segments
+    SharedGuard { reason: String },
+    /// The routes are `trailing_slash = "require"`, but the path doesn't end with a slash
+    MissingTrailingSlash,
+    /// The location passed to `parse_with_base` isn't under the `base` path
+    OutsideBase { base: String },
+}
+
+impl std::fmt::Display for ParseLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PathTooLong { len, max } => {
+                write!(f, "The path is {len} bytes long, the limit is {max}")
+            }
+            Self::TooManySegments { max } => {
+                write!(f, "The path has more than {max} segments")
+            }
+            Self::ExcludedPrefix { prefix } => {
+                write!(f, "Paths under {prefix} are not handled by the router")
+            }
+            Self::SharedGuard { reason } => {
+                write!(f, "The shared segments were rejected: {reason}")
+            }
+            Self::MissingTrailingSlash => write!(f, "The path doesn't end with a slash"),
+            Self::OutsideBase { base } => {
+                write!(f, "The location is not under the base path {base}")
+            }
+        }
+    }
+}
+
+/// The largest paths the generated parser accepts, set with `#[routable(max_path_len = ..., max_segments = ...)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ParseLimits {
+    /// The most bytes a path can have, query included. 8 KiB by default.
+    pub max_path_len: usize,
+    /// The most segments a path can have. 256 by default.
+    pub max_segments: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_path_len: 8 * 1024,
+            max_segments: 256,
+        }
+    }
+}
+
+/// The reason a route could not be built from a pattern and its parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BuildError {
+    /// No route has this pattern
+    UnknownPattern(String),
+    /// A dynamic segment of the pattern has no parameter
+    MissingParam(&'static str),
+    /// A parameter did not parse as the type of its segment
+    InvalidParam { name: &'static str, error: String },
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownPattern(pattern) => write!(f, "No route has the pattern '{pattern}'"),
+            Self::MissingParam(name) => write!(f, "Missing a value for the segment '{name}'"),
+            Self::InvalidParam { name, error } => {
+                write!(
+                    f,
+                    "The value of the segment '{name}' did not parse: {error}"
+                )
+            }
+        }
+    }
+}
+
+/// The reason a route can't be written as a path that parses back into it, returned by the `try_to_path` method
+/// of routes with `#[routable(strict_display)]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DisplayError {
+    /// A segment is empty, but its field doesn't accept empty values
+    EmptySegment(&'static str),
+    /// The path parses as a different route, or as the same route with different segments, or not at all
+    NoRoundTrip { path: String, pattern: &'static str },
+}
+
+impl std::fmt::Display for DisplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptySegment(name) => write!(f, "The segment '{name}' is empty"),
+            Self::NoRoundTrip { path, pattern } => {
+                write!(f, "'{path}' doesn't parse back into the route '{pattern}'")
+            }
+        }
+    }
+}
+
+/// A route declared with `feature = "..."`, listed by [`Routable::gated_routes`] whether or not it was compiled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GatedRoute {
+    pub pattern: &'static str,
+    /// The name of the variant
+    pub name: &'static str,
+    /// The cargo feature the route needs
+    pub feature: &'static str,
+    /// If the feature was enabled when the routes were compiled
+    pub enabled: bool,
+}
+
+/// A navigation the router gave up on.
+#[derive(Debug, Clone, PartialEq)]
+enum NavigationError {
+    /// Action routes kept navigating to other action routes, or guards kept redirecting. `chain` holds the patterns
+    /// of the routes in the order they redirected, ending with the one that was not run or that its guard stopped at.
+    TooManyRedirects { chain: Vec<&'static str> },
+    /// A guard of the route with this pattern cancelled the navigation
+    GuardCancelled { pattern: &'static str },
+    /// A guard panicked while checking a navigation to the route with this pattern and owner
+    GuardPanicked {
+        pattern: &'static str,
+        owner: Option<&'static str>,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for NavigationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooManyRedirects { chain } => {
+                write!(
+                    f,
+                    "Stopped after too many redirects: {}",
+                    chain.join(" -> ")
+                )
+            }
+            Self::GuardCancelled { pattern } => {
+                write!(f, "The guard of {pattern} cancelled the navigation")
+            }
+            Self::GuardPanicked {
+                pattern,
+                owner,
+                message,
+            } => {
+                write!(f, "The guard of {pattern} panicked: {message}")?;
+                failure::write_owner(f, *owner)
+            }
+        }
+    }
+}
+
+struct RouterConfiguration<R: FromStr> {
+    /// How many parsed paths the router remembers. Set to 0 to disable the cache.
+    parse_cache_capacity: usize,
+    /// How many history entries the router remembers the fragment and scroll position of, at least 2. Going back to an
+    /// older entry scrolls to the top. Apps that navigate for a long time without reloading, like kiosks, should
+    /// bound the history itself as well, see [`BoundedMemoryHistory`](history::BoundedMemoryHistory).
+    max_history_entries: usize,
+    /// Replace the current history entry with the canonical path of the route when the location differs from
+    /// it, for example by a trailing slash, by percent-encoding characters that don't need it, or by a value the
+    /// route's `normalize = ...` function changed.
+    canonicalize: bool,
+    /// Drop the query parameters the route doesn't parse, like `utm_source`, when the router replaces the location
+    /// with the canonical path or redirects to the same page. By default they are kept, sorted by key with
+    /// [`encoding::sort_query`] so the same parameters always make the same url. Parsing doesn't depend on the order.
+    strip_unknown_query: bool,
+    /// Rules that rewrite legacy paths before they are parsed. The first matching rule replaces the current history
+    /// entry with its rewritten path.
+    rewrites: Vec<RewriteRule>,
+    /// How many actions can run in a row before the router assumes they redirect in a loop and stops. The same limit
+    /// applies to guards that redirect in a row.
+    max_redirects: usize,
+    /// Guards that check every navigation before the guard of its target route, in order. See [`guard`].
+    guards: Vec<Guard<R>>,
+    /// Values the guards can read, like the current session. Update them later with [`Router::contexts_mut`].
+    contexts: RouterContexts,
+    /// Paths under these prefixes, like `/static` or `/api`, are served by something else than the app. The router
+    /// rejects them with a [`ParseLimit::ExcludedPrefix`] error without trying any route, and links to them should be
+    /// left to the browser, see [`BaseUrl::with_excluded_prefixes`](link::BaseUrl::with_excluded_prefixes). Prefixes
+    /// match whole segments, so `/static` doesn't exclude `/staticfiles`.
+    excluded_prefixes: Vec<String>,
+    /// Where the app is served from, like `https://example.com/app`. Links render the absolute url of their
+    /// location under it as their `href`, see [`Router::href`].
+    base_url: Option<BaseUrl>,
+    /// The route to show when a navigation fails, like a path that doesn't parse or a redirect loop. Without it,
+    /// paths that don't parse are errors and the router stays where it is for the other failures. See [`failure`].
+    error_route: Option<ErrorRoute<R>>,
+    /// Only resolve navigations without making them: actions that aren't declared `dry_run_safe = true` are skipped
+    /// instead of run. Used by [`Router::resolve`].
+    dry_run: bool,
+    /// Check the configuration against the routes with [`RouterConfiguration::validate`] when the router starts,
+    /// and panic if it doesn't fit them. On by default in debug builds.
+    validate_on_start: bool,
+    /// The element the router sets the `lang` and `dir` of localized routes on after every navigation. With the
+    /// `web` feature this is the `<html>` element of the page.
+    document_root: Option<Box<dyn DocumentRoot>>,
+    /// The location the server rendered the page for, read from the page with [`HydrationMarker::from_document`].
+    /// If the history starts there, the guards of the first route don't run again. See [`hydration`].
+    hydration: Option<HydrationMarker>,
+    /// A parse cache shared with other routers, asked before parsing a location the router's own cache doesn't
+    /// have. Off by default; see [`SharedRouteCache`] for when it helps.
+    shared_parse_cache: Option<SharedRouteCache<R>>,
+    /// Guests that render the paths under their prefix, each behind a route of `R` with a catch-all at the prefix.
+    /// See [`sub_router`].
+    sub_routers: Vec<MountedSubRouter>,
+    /// Panic when a route the app pushes or replaces doesn't parse back from its path, instead of the warning of
+    /// debug builds. Meant for tests. See [`round_trip`].
+    strict_roundtrip: bool,
+    /// Where the router keeps the scroll positions and states of the history entries, so they survive a reload,
+    /// like the `sessionStorage` of [`RouterSession::browser`]. Off by default, since it writes on every
+    /// navigation. See [`session`].
+    session: Option<RouterSession>,
+    /// How many navigations the navigation log keeps for devtools.
+    #[cfg(feature = "devtools")]
+    navigation_log_capacity: usize,
+    /// Record navigations into the navigation log from the start. Recording can also be turned on later with
+    /// [`Router::set_navigation_recording`].
+    #[cfg(feature = "devtools")]
+    record_navigations: bool,
+    /// Render a watermark with the pattern of the route next to every route the router renders, for screenshots
+    /// taken while testing. See [`watermark`].
+    #[cfg(feature = "devtools")]
+    route_watermarks: bool,
+    /// Where the router keeps which routes it matched between runs of the app. In debug builds with the `web`
+    /// feature this is the `localStorage` of the page. See [`coverage`].
+    #[cfg(feature = "devtools")]
+    coverage_storage: Option<Box<dyn CoverageStorage>>,
+}
+
+impl<R: FromStr> Default for RouterConfiguration<R> {
+    fn default() -> Self {
+        Self {
+            parse_cache_capacity: 64,
+            max_history_entries: 100,
+            canonicalize: false,
+            strip_unknown_query: false,
+            rewrites: Vec::new(),
+            max_redirects: 8,
+            guards: Vec::new(),
+            contexts: RouterContexts::default(),
+            excluded_prefixes: Vec::new(),
+            base_url: None,
+            error_route: None,
+            dry_run: false,
+            validate_on_start: cfg!(debug_assertions),
+            #[cfg(feature = "web")]
+            document_root: head::browser_document_root(),
+            #[cfg(not(feature = "web"))]
+            document_root: None,
+            hydration: None,
+            shared_parse_cache: None,
+            sub_routers: Vec::new(),
+            strict_roundtrip: false,
+            session: None,
+            #[cfg(feature = "devtools")]
+            navigation_log_capacity: 100,
+            #[cfg(feature = "devtools")]
+            record_navigations: false,
+            #[cfg(feature = "devtools")]
+            route_watermarks: false,
+            #[cfg(all(feature = "devtools", feature = "web"))]
+            coverage_storage: coverage::browser_coverage_storage(),
+            #[cfg(all(feature = "devtools", not(feature = "web")))]
+            coverage_storage: None,
+        }
+    }
+}
+
+struct Router<R: Routable>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    // The history is boxed so the router can be shared through the context without naming its history type
+    history: Box<dyn HistoryProvider>,
+    route: R,
+    // The route before the last navigation within the app. Lost on reload, since the router is recreated
+    previous: Option<R>,
+    cache: RouteCache<R>,
+    canonicalize: bool,
+    strip_unknown_query: bool,
+    rewrites: Vec<RewriteRule>,
+    max_redirects: usize,
+    guards: Vec<Guard<R>>,
+    contexts: RouterContexts,
+    excluded_prefixes: Vec<String>,
+    base_url: Option<BaseUrl>,
+    error_route: Option<ErrorRoute<R>>,
+    // If the current route is the error route of a failed navigation
+    error_page: bool,
+    // If the router is navigating to the error route. Failures on the way are not mapped again
+    mapping_failure: bool,
+    // The message of a failure the error route couldn't show, rendered with `failure::render_fallback`
+    fallback: Option<String>,
+    // The patterns of the actions that ran since the last navigation that didn't come from an action
+    redirect_chain: Vec<&'static str>,
+    redirecting: bool,
+    navigation_error: Option<NavigationError>,
+    dry_run: bool,
+    // The pattern of the action a dry run skipped, since it isn't safe to run
+    unevaluated_action: Option<&'static str>,
+    // What started the navigation to the current route
+    source: NavigationSource,
+    // How the navigation to the current route changed the history, if the router changed it
+    kind: Option<NavigationKind>,
+    // If the navigation to the current route only changed its values, see `Router::is_soft_navigation`
+    soft: bool,
+    // If the navigation stayed on the same variant, and the parameters it changed, as subscribers get them
+    same_variant: bool,
+    params_diff: Vec<(&'static str, String, String)>,
+    // The location as the history reported it when the route was last updated
+    url: Rc<str>,
+    subscribers: Subscribers<R>,
+    // Replace the router in a `RouterStore` instead of reattaching to it
+    fresh: bool,
+    scroll: ScrollHistory,
+    scroll_request: Option<ScrollRequest>,
+    document_root: Option<Box<dyn DocumentRoot>>,
+    // The language the router last set on the document root
+    document_lang: Option<DocumentLang>,
+    // The patterns of the deprecated routes the router warned about, so it only warns once per route
+    deprecation_warnings: Vec<&'static str>,
+    sub_routers: Vec<MountedSubRouter>,
+    strict_roundtrip: bool,
+    session: Option<RouterSession>,
+    #[cfg(feature = "devtools")]
+    navigation_log: NavigationLog,
+    #[cfg(feature = "devtools")]
+    route_watermarks: bool,
+    #[cfg(feature = "devtools")]
+    coverage: RouteCoverage,
+    #[cfg(feature = "devtools")]
+    coverage_storage: Option<Box<dyn CoverageStorage>>,
+}
+
+impl<R: Routable> Router<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    fn new(history: impl HistoryProvider + 'static) -> Result<Self, R::Err> {
+        Self::with_configuration(history, RouterConfiguration::default())
+    }
+
+    fn with_configuration(
+        mut history: impl HistoryProvider + 'static,
+        config: RouterConfiguration<R>,
+    ) -> Result<Self, R::Err> {
+        if config.validate_on_start {
+            if let Err(errors) = config.validate() {
+                let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+                panic!(
+                    "The router configuration doesn't fit the routes:\n{}",
+                    errors.join("\n")
+                );
+            }
+        }
+        let mut cache =
+            RouteCache::new(config.parse_cache_capacity).with_shared(config.shared_parse_cache);
+        let (path, source) = Self::rewrite_with(&mut history, &config.rewrites);
+        let path = Self::parse_input(&history, path);
+        let (route, error_page) =
+            match Self::parse_with(&mut cache, &config.excluded_prefixes, &path) {
+                Ok(route) => (route, false),
+                Err(err) => {
+                    let Some(error_route) = config.error_route else {
+                        return Err(err);
+                    };
+                    let route = error_route(NavigationFailure::NoMatch(err));
+                    history.replace(route.to_string());
+                    (route, true)
+                }
+            };
+        let mut router = Self {
+            history: Box::new(history),
+            route,
+            previous: None,
+            cache,
+            canonicalize: config.canonicalize,
+            strip_unknown_query: config.strip_unknown_query,
+            rewrites: config.rewrites,
+            max_redirects: config.max_redirects,
+            guards: config.guards,
+            contexts: config.contexts,
+            excluded_prefixes: config.excluded_prefixes,
+            base_url: config.base_url,
+            error_route: config.error_route,
+            error_page,
+            mapping_failure: error_page,
+            fallback: None,
+            redirect_chain: Vec::new(),
+            redirecting: false,
+            navigation_error: None,
+            dry_run: config.dry_run,
+            unevaluated_action: None,
+            source,
+            kind: None,
+            soft: false,
+            same_variant: false,
+            params_diff: Vec::new(),
+            url: Rc::from(""),
+            subscribers: Subscribers::default(),
+            fresh: false,
+            scroll: ScrollHistory::new(None, config.max_history_entries),
+            scroll_request: None,
+            document_root: config.document_root,
+            document_lang: None,
+            deprecation_warnings: Vec::new(),
+            sub_routers: config.sub_routers,
+            strict_roundtrip: config.strict_roundtrip,
+            session: config.session,
+            #[cfg(feature = "devtools")]
+            navigation_log: NavigationLog::new(
+                config.navigation_log_capacity,
+                config.record_navigations,
+            ),
+            #[cfg(feature = "devtools")]
+            route_watermarks: config.route_watermarks,
+            #[cfg(feature = "devtools")]
+            coverage: RouteCoverage::new::<R>(),
+            #[cfg(feature = "devtools")]
+            coverage_storage: config.coverage_storage,
+        };
+        #[cfg(feature = "devtools")]
+        if let Some(stored) = router
+            .coverage_storage
+            .as_ref()
+            .and_then(|storage| storage.load(&router.coverage.storage_key()))
+        {
+            router.coverage.load(&stored);
+        }
+        router.url = router.read_url();
+        // A reload of the last stored location gets its entries back and scrolls to where the page was
+        if let Some(scroll) = router
+            .session
+            .as_ref()
+            .and_then(|session| session.restore(&router.url, config.max_history_entries))
+        {
+            router.scroll_request = Some(scroll.restore());
+            router.scroll = scroll;
+        }
+        // The server already ran the guards of the page it rendered
+        let hydrated = config
+            .hydration
+            .is_some_and(|marker| *marker.location == *router.url);
+        let guarded = match hydrated {
+            true => {
+                router.source = NavigationSource::Hydration;
+                Ok(false)
+            }
+            false => router.guard_current(),
+        };
+        if matches!(guarded, Ok(false)) {
+            router.canonicalize_location(&path);
+        }
+        router.route_changed();
+        router.mapping_failure = false;
+        if let Err(err) = guarded {
+            router.stop_navigation(err);
+        }
+        Ok(router)
+    }
+
+    /// Start over when the provider mounts, even if a `RouterStore` has a router with the same id, like after a
+    /// logout.
+    fn fresh(mut self) -> Self {
+        self.fresh = true;
+        self
+    }
+
+    /// Parse a path into a route, consulting the parse cache first. Paths under an excluded prefix are rejected
+    /// before either.
+    fn parse(&mut self, path: &str) -> Result<R, R::Err> {
+        Self::parse_with(&mut self.cache, &self.excluded_prefixes, path)
+    }
+
+    fn parse_with(
+        cache: &mut RouteCache<R>,
+        excluded_prefixes: &[String],
+        path: &str,
+    ) -> Result<R, R::Err> {
+        if let Some(prefix) = link::excluded_prefix(excluded_prefixes, path) {
+            return Err(R::rejected(ParseLimit::ExcludedPrefix {
+                prefix: prefix.to_string(),
+            }));
+        }
+        #[cfg(feature = "metrics")]
+        let start = std::time::Instant::now();
+        let result = cache.parse(path);
+        #[cfg(feature = "metrics")]
+        route_metrics::record_parse(&result, start.elapsed());
+        result
+    }
+
+    /// Navigate to a route, adding a new entry to the history stack. Routes nested in a route of `R` are pushed as
+    /// that route.
+    fn push(&mut self, route: impl Into<R>) {
+        let route = route.into();
+        let location = self.programmatic_location(&route);
+        let source = self.programmatic_source();
+        self.push_location(route, location, source);
+    }
+
+    /// Navigate to a route, like a login page, and remember the current location in the query parameter `key`
+    /// of the new entry. [`Router::pop_return`] navigates back to it. The parameter is added to the query of the
+    /// route if it has one, before the fragment.
+    fn push_with_return(&mut self, route: R, key: &str) {
+        let location = self.programmatic_location(&route);
+        let param = format!(
+            "{}={}",
+            encode_query_component(key),
+            encode_query_component(&self.url)
+        );
+        let (before_fragment, fragment) = split_fragment(&location);
+        let separator = match before_fragment.contains('?') {
+            true => '&',
+            false => '?',
+        };
+        let location = match fragment {
+            Some(fragment) => format!("{before_fragment}{separator}{param}#{fragment}"),
+            None => format!("{before_fragment}{separator}{param}"),
+        };
+        let source = self.programmatic_source();
+        self.push_location(route, location, source);
+    }
+
+    /// Navigate to the location stored by [`Router::push_with_return`] in the query parameter `key`, or to
+    /// `default` if there is none. The current entry is replaced, so going back skips the page that stored it.
+    ///
+    /// The query parameter can be set by anyone who links to the page, so the location must be a path inside the app
+    /// that parses as a route other than the `not_found` variant. Anything else, like `https://evil.example` or
+    /// `//evil.example`, navigates to `default` instead.
+    fn pop_return(&mut self, key: &str, default: R) {
+        match self.return_location(key) {
+            Some((route, location)) => {
+                let source = self.programmatic_source();
+                self.replace_location(route, location, source)
+            }
+            None => self.replace(default),
+        }
+    }
+
+    fn return_location(&mut self, key: &str) -> Option<(R, String)> {
+        let query = self.history.current_query()?;
+        let location = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(name, _)| decode_query_component(name).as_deref() == Some(key))
+            .and_then(|(_, value)| decode_query_component(value))?;
+
+        // Only follow paths of this app. `//host` and `/\host` are treated as other hosts by browsers
+        if !location.starts_with('/') || location.starts_with("//") || location.contains('\\') {
+            return None;
+        }
+        // Parsed like the current location: with the query only if a route of `R` parses it
+        let path = match R::parses_query() {
+            true => location.as_str(),
+            false => location.split(['?', '#']).next().unwrap_or_default(),
+        };
+        // The fallback variant matches every path, but nothing is there to return to
+        let route = self
+            .parse(path)
+            .ok()
+            .filter(|route| !route.is_not_found())?;
+        Some((route, location))
+    }
+
+    fn push_location(&mut self, route: R, location: String, source: NavigationSource) {
+        self.push_location_with(route, location, source, None, None);
+    }
+
+    /// Push a new entry with a state and where to scroll, instead of the fragment of the location or the top.
+    fn push_location_with(
+        &mut self,
+        route: R,
+        location: String,
+        source: NavigationSource,
+        state: Option<String>,
+        scroll: Option<ScrollRequest>,
+    ) {
+        let Some((route, location, source)) = self.guard_navigation(route, location, source) else {
+            return;
+        };
+        let fragment = split_fragment(&location).1;
+        self.scroll.push(fragment);
+        self.scroll.set_state(state);
+        self.scroll_request = Some(scroll.unwrap_or_else(|| Self::scroll_to(&route, fragment)));
+        self.history.push(location);
+        self.url = self.read_url();
+        self.soft = Self::soft_navigation(Some(&self.route), &route);
+        (self.same_variant, self.params_diff) = Self::compare_routes(Some(&self.route), &route);
+        self.previous = Some(std::mem::replace(&mut self.route, route));
+        self.source = source;
+        self.kind = Some(NavigationKind::Push);
+        #[cfg(feature = "devtools")]
+        self.record_navigation(NavigationKind::Push);
+        self.route_changed();
+    }
+
+    /// Navigate to a route, replacing the current entry in the history stack.
+    ///
+    /// Going back after a replace skips the replaced route. The previous route is kept as it was, since the
+    /// replaced route is no longer part of the history.
+    fn replace(&mut self, route: impl Into<R>) {
+        let route = route.into();
+        let location = self.programmatic_location(&route);
+        let source = self.programmatic_source();
+        self.replace_location(route, location, source);
+    }
+
+    fn replace_location(&mut self, route: R, location: String, source: NavigationSource) {
+        self.replace_location_with(route, location, source, None, None);
+    }
+
+    /// Replace the current entry with a state and where to scroll, like [`Router::push_location_with`].
+    fn replace_location_with(
+        &mut self,
+        route: R,
+        location: String,
+        source: NavigationSource,
+        state: Option<String>,
+        scroll: Option<ScrollRequest>,
+    ) {
+        let Some((route, location, source)) = self.guard_navigation(route, location, source) else {
+            return;
+        };
+        let fragment = split_fragment(&location).1;
+        self.scroll.replace(fragment);
+        self.scroll.set_state(state);
+        self.scroll_request = Some(scroll.unwrap_or_else(|| Self::scroll_to(&route, fragment)));
+        self.history.replace(location);
+        self.url = self.read_url();
+        self.soft = Self::soft_navigation(Some(&self.route), &route);
+        (self.same_variant, self.params_diff) = Self::compare_routes(Some(&self.route), &route);
+        self.route = route;
+        self.source = source;
+        self.kind = Some(NavigationKind::Replace);
+        #[cfg(feature = "devtools")]
+        self.record_navigation(NavigationKind::Replace);
+        self.route_changed();
+    }
+
+    /// The location of a navigation to `route` the app asked for. An action that redirects stands in for the page it
+    /// redirects to, so the redirect keeps the query parameters of the action's location that `route` doesn't parse.
+    fn programmatic_location(&self, route: &R) -> String {
+        self.check_round_trip(route);
+        let location = route.to_string();
+        match self.redirecting {
+            true => self.keep_unknown_query(location, route, &self.url),
+            false => location,
+        }
+    }
+
+    /// Add the query of `from`, the location the router is redirecting from, to `location`, the location of `to`.
+    /// Routes that parse the query display it themselves, so nothing is added for them or with
+    /// [`RouterConfiguration::strip_unknown_query`].
+    fn keep_unknown_query(&self, location: String, to: &R, from: &str) -> String {
+        if self.strip_unknown_query || to.includes_query() {
+            return location;
+        }
+        match split_fragment(from)
+            .0
+            .split_once('?')
+            .map(|(_, query)| sort_query(query))
+        {
+            Some(query) if !query.is_empty() => match split_fragment(&location) {
+                (path, Some(fragment)) => format!("{path}?{query}#{fragment}"),
+                (path, None) => format!("{path}?{query}"),
+            },
+            _ => location,
+        }
+    }
+
+    /// The source of a navigation the app asked for: the action that is running if it came from one.
+    fn programmatic_source(&self) -> NavigationSource {
+        match self.redirect_chain.last() {
+            Some(pattern) if self.redirecting => NavigationSource::Action(pattern),
+            _ => NavigationSource::Programmatic,
+        }
+    }
+
+    /// What started the navigation to the current route. Navigations superseded by a later one, like a route
+    /// whose action redirected, are not reported.
+    fn navigation_source(&self) -> &NavigationSource {
+        &self.source
+    }
+
+    /// Where to scroll after navigating to a new entry. Routes with a typed fragment scroll to its
+    /// [`Routable::fragment_id`] instead of the fragment of the location, which is missing when the fragment is an
+    /// absent `Option`.
+    fn scroll_to(route: &R, fragment: Option<&str>) -> ScrollRequest {
+        match route.fragment_id().or_else(|| fragment.map(String::from)) {
+            Some(id) => ScrollRequest::Fragment(id),
+            None => ScrollRequest::Top,
+        }
+    }
+
+    /// Restore the scroll of an entry the history moved to, with the fragment id of the route it parsed to.
+    fn restore_fragment_scroll(&mut self) {
+        if let Some(ScrollRequest::Fragment(fragment)) = &mut self.scroll_request {
+            if let Some(id) = self.route.fragment_id() {
+                *fragment = id;
+            }
+        }
+    }
+
+    fn go_back(&mut self) -> Result<(), R::Err> {
+        if self.history.can_go_back() {
+            self.scroll.go_back();
+            self.scroll_request = Some(self.scroll.restore());
+        }
+        self.history.go_back();
+        self.sync_route_with(Some(NavigationKind::Back))?;
+        self.restore_fragment_scroll();
+        #[cfg(feature = "devtools")]
+        self.record_navigation(NavigationKind::Back);
+        Ok(())
+    }
+
+    fn go_forward(&mut self) -> Result<(), R::Err> {
+        if self.history.can_go_forward() {
+            self.scroll.go_forward();
+            self.scroll_request = Some(self.scroll.restore());
+        }
+        self.history.go_forward();
+        self.sync_route_with(Some(NavigationKind::Forward))?;
+        self.restore_fragment_scroll();
+        #[cfg(feature = "devtools")]
+        self.record_navigation(NavigationKind::Forward);
+        Ok(())
+    }
+
+    /// Record the navigation to the current route in the navigation log, if recording is on.
+    #[cfg(feature = "devtools")]
+    fn record_navigation(&mut self, kind: NavigationKind) {
+        self.navigation_log
+            .record(self.route.to_string(), kind, self.source.clone());
+    }
+
+    /// Set the bit of the current route in the coverage, and store the coverage if the bit is new
+    #[cfg(feature = "devtools")]
+    fn record_coverage(&mut self) {
+        if !self.coverage.record(self.route.pattern()) {
+            return;
+        }
+        if let Some(storage) = &mut self.coverage_storage {
+            storage.save(&self.coverage.storage_key(), &self.coverage.to_storage());
+        }
+    }
+
+    /// Store the scroll history for the current location, if the router keeps a session. Dry runs don't store it.
+    fn save_session(&mut self) {
+        if self.dry_run {
+            return;
+        }
+        if let Some(session) = &mut self.session {
+            session.save(&self.url, &self.scroll);
+        }
+    }
+
+    /// Every pattern of the routes and if the router ever matched a route with it, including in earlier runs of
+    /// the app if the coverage is stored. See [`coverage`].
+    #[cfg(feature = "devtools")]
+    fn coverage(&self) -> Vec<(&'static str, bool)> {
+        self.coverage.coverage()
+    }
+
+    #[cfg(feature = "devtools")]
+    fn route_coverage(&self) -> &RouteCoverage {
+        &self.coverage
+    }
+
+    /// Turn recording navigations into the navigation log on or off.
+    #[cfg(feature = "devtools")]
+    fn set_navigation_recording(&mut self, recording: bool) {
+        self.navigation_log.set_recording(recording);
+    }
+
+    #[cfg(feature = "devtools")]
+    fn navigation_log(&self) -> &NavigationLog {
+        &self.navigation_log
+    }
+
+    /// Go back to the route of entry `index` of the navigation log by replacing the current history entry. The jump
+    /// is recorded as a replace from devtools. Does nothing if there is no such entry.
+    #[cfg(feature = "devtools")]
+    fn jump_to_navigation(&mut self, index: usize) -> Result<(), R::Err> {
+        let Some(entry) = self.navigation_log.get(index) else {
+            return Ok(());
+        };
+        let path = entry.path.clone();
+        let route = self.parse(&path)?;
+        self.scroll.replace(split_fragment(&path).1);
+        self.history.replace(path);
+        self.url = self.read_url();
+        self.soft = Self::soft_navigation(Some(&self.route), &route);
+        (self.same_variant, self.params_diff) = Self::compare_routes(Some(&self.route), &route);
+        self.route = route;
+        self.source = NavigationSource::Devtools;
+        self.kind = Some(NavigationKind::Replace);
+        self.record_navigation(NavigationKind::Replace);
+        self.route_changed();
+        Ok(())
+    }
+
+    /// Reparse the route after the history changed the current path, like when the browser went back.
+    fn sync_route(&mut self) -> Result<(), R::Err> {
+        self.sync_route_with(None)
+    }
+
+    /// Reparse the route after the history moved, reporting the navigation as `kind` if the router moved it
+    fn sync_route_with(&mut self, kind: Option<NavigationKind>) -> Result<(), R::Err> {
+        let (path, source) = Self::rewrite_with(&mut *self.history, &self.rewrites);
+        let path = Self::parse_input(&*self.history, path);
+        let route = match self.parse(&path) {
+            Ok(route) => route,
+            Err(err) if self.error_route.is_some() => {
+                self.fail(NavigationFailure::NoMatch(err));
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
+        self.url = self.read_url();
+        self.soft = Self::soft_navigation(Some(&self.route), &route);
+        (self.same_variant, self.params_diff) = Self::compare_routes(Some(&self.route), &route);
+        self.previous = Some(std::mem::replace(&mut self.route, route));
+        self.source = source;
+        self.kind = kind;
+        let guarded = self.guard_current();
+        if matches!(guarded, Ok(false)) {
+            self.canonicalize_location(&path);
+        }
+        self.route_changed();
+        if let Err(err) = guarded {
+            self.stop_navigation(err);
+        }
+        Ok(())
+    }
+
+    /// If the navigation to the current route was soft: it stayed on the same variant, like from `/photos/1` to
+    /// `/photos/2`, so the component of the route kept its state and only got new props. Variants declared with
+    /// `hard_navigation` are never navigated to softly.
+    fn is_soft_navigation(&self) -> bool {
+        self.soft
+    }
+
+    fn soft_navigation(from: Option<&R>, to: &R) -> bool {
+        from.is_some_and(|from| from.same_variant(to)) && !to.hard_navigation()
+    }
+
+    /// If a navigation from `from` to `to` stays on the same variant, and the parameters that differ between them as
+    /// `(name, from_value, to_value)`, for the [`RouteChange`] subscribers get.
+    fn compare_routes(from: Option<&R>, to: &R) -> (bool, Vec<(&'static str, String, String)>) {
+        match from {
+            Some(from) if from.same_variant(to) => (true, from.params_diff(to)),
+            _ => (false, Vec::new()),
+        }
+    }
+
+    /// Report a failed navigation, like the loader of the current route failing. The router replaces the current
+    /// entry with the route [`RouterConfiguration::error_route`] returns for the failure. If there is no error route,
+    /// or the failure happened on the way to it or on it, the router stays where it is and [`Router::fallback`]
+    /// returns the message of the failure instead.
+    fn fail(&mut self, failure: NavigationFailure<R>) {
+        match self.error_route {
+            Some(error_route) if !self.mapping_failure && !self.error_page => {
+                let route = error_route(failure);
+                let location = route.to_string();
+                self.mapping_failure = true;
+                self.replace_location(route, location, NavigationSource::Failure);
+                self.mapping_failure = false;
+            }
+            _ => self.fallback = Some(failure.to_string()),
+        }
+    }
+
+    /// Stop a navigation the router gave up on, and show the error route for it if there is one.
+    fn stop_navigation(&mut self, err: NavigationError) {
+        if self.error_route.is_some() {
+            self.fail(match &err {
+                NavigationError::TooManyRedirects { chain } => {
+                    NavigationFailure::TooManyRedirects {
+                        chain: chain.clone(),
+                    }
+                }
+                NavigationError::GuardCancelled { pattern } => {
+                    NavigationFailure::GuardCancelled(pattern)
+                }
+                NavigationError::GuardPanicked {
+                    pattern,
+                    owner,
+                    message,
+                } => NavigationFailure::GuardPanicked {
+                    pattern: *pattern,
+                    owner: *owner,
+                    message: message.clone(),
+                },
+            });
+        }
+        self.navigation_error = Some(err);
+    }
+
+    /// The message of a failure the error route couldn't show, if the last navigation had one. Render it with
+    /// [`failure::render_fallback`] instead of the current route.
+    fn fallback(&self) -> Option<&str> {
+        self.fallback.as_deref()
+    }
+
+    /// The HTTP status a server should respond with for the current page: the `status_hint` of the current route, or
+    /// 500 for the fallback page.
+    fn status(&self) -> u16 {
+        match self.fallback {
+            Some(_) => 500,
+            None => self.route.status_hint(),
+        }
+    }
+
+    /// Values provided to the guards, like the current session.
+    fn contexts_mut(&mut self) -> &mut RouterContexts {
+        &mut self.contexts
+    }
+
+    /// Run the guards of a navigation the app asked for before it happens. Returns where the navigation goes after
+    /// the guards, or `None` if they redirected in a loop, in which case the router stays where it is and reports
+    /// [`NavigationError::TooManyRedirects`].
+    fn guard_navigation(
+        &mut self,
+        route: R,
+        location: String,
+        source: NavigationSource,
+    ) -> Option<(R, String, NavigationSource)> {
+        match self.run_guards(Some(&self.route), route, location, source) {
+            Ok(navigation) => Some(navigation),
+            Err(err) => {
+                self.stop_navigation(err);
+                None
+            }
+        }
+    }
+
+    /// Run the guards of the current route after the history already moved to it, like when the browser went back.
+    /// A guard that redirects replaces the current entry. Returns if a guard redirected.
+    fn guard_current(&mut self) -> Result<bool, NavigationError> {
+        let (route, location, source) = self.run_guards(
+            self.previous.as_ref(),
+            self.route.clone(),
+            self.url.to_string(),
+            self.source.clone(),
+        )?;
+        // Only a redirect makes a guard the source of the navigation
+        if !matches!(source, NavigationSource::Guard(_)) {
+            return Ok(false);
+        }
+        self.scroll.replace(split_fragment(&location).1);
+        self.history.replace(location);
+        self.url = self.read_url();
+        self.soft = Self::soft_navigation(self.previous.as_ref(), &route);
+        (self.same_variant, self.params_diff) =
+            Self::compare_routes(self.previous.as_ref(), &route);
+        self.route = route;
+        self.source = source;
+        Ok(true)
+    }
+
+    /// Run the guards of a navigation from `from` to `to`, in the order described in the [`guard`](crate::guard)
+    /// module. A redirect starts the guards over for the route it redirects to, until every guard allows the
+    /// navigation or the redirects exceed [`RouterConfiguration::max_redirects`].
+    ///
+    /// Soft navigations skip the guard of their route if it is declared with `params_only = false`. The global
+    /// guards always run. A guard that panics stops the navigation with [`NavigationError::GuardPanicked`], with the
+    /// owner of the route it was checking.
+    fn run_guards(
+        &self,
+        from: Option<&R>,
+        mut to: R,
+        mut location: String,
+        mut source: NavigationSource,
+    ) -> Result<(R, String, NavigationSource), NavigationError> {
+        let mut chain = Vec::new();
+        'navigation: loop {
+            let route_guard =
+                match Self::soft_navigation(from, &to) && !to.guard_on_soft_navigation() {
+                    true => None,
+                    false => to.guard(),
+                };
+            for guard in self.guards.iter().copied().chain(route_guard) {
+                let context = GuardContext::new(from, &to, &source, &location, &self.contexts);
+                let result =
+                    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| guard(&context)));
+                let result = match result {
+                    Ok(result) => result,
+                    Err(payload) => {
+                        let (pattern, owner) = (to.pattern(), to.owner());
+                        let message = failure::panic_message(&*payload);
+                        #[cfg(feature = "tracing")]
+                        tracing::error!(
+                            pattern,
+                            owner = owner.unwrap_or_default(),
+                            panic = %message,
+                            "a route guard panicked"
+                        );
+                        return Err(NavigationError::GuardPanicked {
+                            pattern,
+                            owner,
+                            message,
+                        });
+                    }
+                };
+                match result {
+                    GuardResult::Allow => {}
+                    GuardResult::Redirect(redirect) => {
+                        chain.push(to.pattern());
+                        if chain.len() > self.max_redirects {
+                            return Err(NavigationError::TooManyRedirects { chain });
+                        }
+                        source = NavigationSource::Guard(to.pattern());
+                        // A redirect to the same page, like a guard that corrects a value, keeps the query
+                        location = match to.same_variant(&redirect) {
+                            true => {
+                                self.keep_unknown_query(redirect.to_string(), &redirect, &location)
+                            }
+                            false => redirect.to_string(),
+                        };
+                        to = redirect;
+                        continue 'navigation;
+                    }
+                    GuardResult::Cancel => {
+                        return Err(NavigationError::GuardCancelled {
+                            pattern: to.pattern(),
+                        })
+                    }
+                }
+            }
+            return Ok((to, location, source));
+        }
+    }
+
+    /// Apply the rewrite rules to the current path of the history. The source is [`NavigationSource::Rewrite`] if a
+    /// rule replaced the path, otherwise the path came from the browser.
+    fn rewrite_with(
+        history: &mut dyn HistoryProvider,
+        rewrites: &[RewriteRule],
+    ) -> (String, NavigationSource) {
+        let current = history.current_path();
+        let path = rewrite_location(history, rewrites);
+        let source = match path == current {
+            true => NavigationSource::Browser,
+            false => NavigationSource::Rewrite,
+        };
+        (path, source)
+    }
+
+    /// The string the current route is parsed from: the path, followed by the query if a route of `R` parses it.
+    fn parse_input(history: &dyn HistoryProvider, path: String) -> String {
+        match history.current_query() {
+            Some(query) if R::parses_query() => format!("{path}?{query}"),
+            _ => path,
+        }
+    }
+
+    /// Notify everything that depends on the current route after it changed.
+    fn route_changed(&mut self) {
+        self.fallback = None;
+        self.error_page = self.mapping_failure;
+        let lang = self.route.lang();
+        if let Some(root) = &mut self.document_root {
+            head::update_document_root(&mut **root, self.document_lang.as_ref(), lang.as_ref());
+        }
+        self.document_lang = lang;
+        self.warn_if_deprecated();
+        self.subscribers.notify(RouteChange {
+            route: &self.route,
+            url: &self.url,
+            source: &self.source,
+            kind: self.kind,
+            same_variant: self.same_variant,
+            params_diff: self.params_diff.clone(),
+            state: self.scroll.state(),
+        });
+        #[cfg(feature = "metrics")]
+        if !self.dry_run {
+            route_metrics::record_match(&self.route, &self.source);
+        }
+        #[cfg(feature = "devtools")]
+        if !self.dry_run {
+            self.record_coverage();
+        }
+        self.save_session();
+        self.run_action();
+    }
+
+    /// Warn the first time the router matches a deprecated route, with the `tracing` feature. Dry runs don't count
+    /// as a match.
+    fn warn_if_deprecated(&mut self) {
+        let pattern = self.route.pattern();
+        if self.dry_run
+            || self.route.is_deprecated().is_none()
+            || self.deprecation_warnings.contains(&pattern)
+        {
+            return;
+        }
+        self.deprecation_warnings.push(pattern);
+        #[cfg(feature = "tracing")]
+        if let Some(note) = self.route.is_deprecated() {
+            tracing::warn!(pattern, note, "matched a deprecated route");
+        }
+    }
+
+    /// Run the action of the current route if it has one. Actions are expected to navigate away, since
+    /// action routes don't render anything.
+    ///
+    /// Navigations made by an action continue the chain of the navigation that ran it. If the chain grows past
+    /// [`RouterConfiguration::max_redirects`], the router stays on the current route without running its action and
+    /// reports [`NavigationError::TooManyRedirects`].
+    ///
+    /// In a dry run, actions that aren't [`Routable::action_is_dry_run_safe`] are skipped and the router stays on
+    /// their route.
+    fn run_action(&mut self) {
+        if !self.redirecting {
+            self.redirect_chain.clear();
+            self.navigation_error = None;
+            self.unevaluated_action = None;
+        }
+        let Some(action) = self.route.action() else {
+            return;
+        };
+        if self.dry_run && !self.route.action_is_dry_run_safe() {
+            self.unevaluated_action = Some(self.route.pattern());
+            return;
+        }
+
+        self.redirect_chain.push(self.route.pattern());
+        if self.redirect_chain.len() > self.max_redirects {
+            let chain = std::mem::take(&mut self.redirect_chain);
+            self.stop_navigation(NavigationError::TooManyRedirects { chain });
+            return;
+        }
+        let outer = std::mem::replace(&mut self.redirecting, true);
+        action(self);
+        self.redirecting = outer;
+    }
+
+    /// The reason the router stopped the last navigation, if it did.
+    fn navigation_error(&self) -> Option<&NavigationError> {
+        self.navigation_error.as_ref()
+    }
+
+    /// Replace the location with the canonical path of the current route if canonicalization is enabled.
+    ///
+    /// The canonical path already matches the current route, so the route is not parsed again and this replaces
+    /// the location at most once per navigation.
+    fn canonicalize_location(&mut self, path: &str) {
+        if !self.canonicalize {
+            return;
+        }
+        let canonical = self.route.to_string();
+        // Routes that include the query display it themselves. For the others the query is kept in a stable order,
+        // unless the router strips it
+        let includes_query = self.route.includes_query();
+        let path = match path.split_once('?') {
+            Some((path, _)) if !includes_query => path,
+            _ => path,
+        };
+        let query = match includes_query {
+            true => None,
+            false => self
+                .history
+                .current_query()
+                .filter(|query| !query.is_empty()),
+        };
+        let unknown_query = query
+            .as_deref()
+            .map(sort_query)
+            .filter(|query| !query.is_empty());
+        let strip = self.strip_unknown_query && unknown_query.is_some();
+        if canonical != path || strip || unknown_query != query {
+            let location = match unknown_query {
+                Some(query) if !self.strip_unknown_query => format!("{canonical}?{query}"),
+                _ => canonical,
+            };
+            self.history.replace(location);
+        }
+    }
+
+    /// The location the current route was parsed from, including the query. Unlike the typed route, this keeps
+    /// query parameters the route doesn't know about.
+    ///
+    /// This is the location as the history reported it, so with canonicalization enabled it may differ from
+    /// the route's `Display` output.
+    fn current_url(&self) -> Rc<str> {
+        self.url.clone()
+    }
+
+    /// The `href` links to `location` render: the absolute url under the [`RouterConfiguration::base_url`] if
+    /// there is one, so copying the link address works outside the app. Fragments like `#pricing` are links to the
+    /// current location.
+    fn href(&self, location: &str) -> String {
+        let location = match location.starts_with('#') {
+            true => format!("{}{location}", self.url),
+            false => location.to_string(),
+        };
+        match &self.base_url {
+            Some(base) => base.href(&location),
+            None => location,
+        }
+    }
+
+    fn read_url(&self) -> Rc<str> {
+        let path = self.history.current_path();
+        match self.history.current_query() {
+            Some(query) => Rc::from(format!("{path}?{query}")),
+            None => Rc::from(path),
+        }
+    }
+
+    /// The route the router was at before the last completed navigation, or `None` if the router has not
+    /// navigated yet.
+    fn previous(&self) -> Option<&R> {
+        self.previous.as_ref()
+    }
+}
+
+/// Write the paths of a site map, one per line like [`paths_of`]. Action routes aren't pages, so they are left out,
+/// and so are deprecated routes unless `include_deprecated` is set, so search engines find their replacements instead.
+fn sitemap_of<R: Routable>(routes: impl IntoIterator<Item = R>, include_deprecated: bool) -> String
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    paths_of(routes.into_iter().filter(|route| {
+        route.action().is_none() && (include_deprecated || route.is_deprecated().is_none())
+    }))
+}
+
+/// Write the path of every route on its own line, reusing one buffer. Useful for site maps and feeds.
+fn paths_of<R: Routable>(routes: impl IntoIterator<Item = R>) -> String
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let mut out = String::new();
+    for (i, route) in routes.into_iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        route
+            .write_path(&mut out)
+            .expect("a segment failed to display");
+    }
+    out
+}
+
+#[derive(Props, PartialEq)]
+struct RouterProps {
+    current_route: String,
+    /// How many "did you mean" links the not found page shows at most
+    #[props(default = 3)]
+    max_suggestions: usize,
+    /// Routes registered at runtime, tried when the path doesn't parse as a route
+    #[props(default)]
+    dynamic_routes: DynamicRoutes,
+    /// A legacy route table tried before the derived routes for the paths under its prefixes
+    #[props(default)]
+    legacy_fallback: Option<LegacyFallback>,
+}
+
+trait Routable: FromStr + std::fmt::Display + Clone
+where
+    <Self as FromStr>::Err: std::fmt::Display,
+{
+    fn render(self, cx: &ScopeState) -> Element;
+
+    /// Append the path of the route to `out`. `Display` and `to_path` are both built on this, so writing many paths
+    /// into one buffer avoids allocating a string per route.
+    fn write_path(&self, out: &mut impl std::fmt::Write) -> std::fmt::Result;
+
+    /// The name and serialized value of every dynamic segment of the route, in the order they appear in the path.
+    /// Values are not percent-encoded.
+    fn matched_params(&self) -> Vec<(&'static str, String)>;
+
+    /// The parameters whose serialized values differ between two routes of the same variant, as
+    /// `(name, self_value, other_value)`. Routes of different variants have no comparable parameters.
+    fn params_diff(&self, _other: &Self) -> Vec<(&'static str, String, String)> {
+        Vec::new()
+    }
+
+    /// If this route and `other` are the same variant with possibly different values, like `/photos/1` and
+    /// `/photos/2`.
+    fn same_variant(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// The fields that differ between two routes of the same variant, compared with `PartialEq`, or `None` if the
+    /// routes are different variants or can't be compared field by field.
+    fn differing_fields(&self, _other: &Self) -> Option<Vec<&'static str>> {
+        None
+    }
+
+    /// Every route without dynamic segments, in the order they were declared. See [`site_map`].
+    fn static_routes() -> Vec<Self> {
+        Vec::new()
+    }
+
+    /// The pattern the route was declared with, like `/hello_world/(dynamic)`.
+    fn pattern(&self) -> &'static str;
+
+    /// The patterns of every route in the order the parser tries them. The same as `ROUTE_PATTERNS`.
+    fn route_patterns() -> &'static [&'static str];
+
+    /// Build a route from a pattern and the values of its dynamic segments. The same as `from_pattern_and_params`.
+    fn from_pattern_and_params(pattern: &str, params: &[(&str, &str)]) -> Result<Self, BuildError>;
+
+    /// The function to run when the router navigates to this route, for routes declared with `action = ...`.
+    fn action(&self) -> Option<fn(&mut Router<Self>)> {
+        None
+    }
+
+    /// The guard that checks navigations to this route, for routes declared with `guard = ...`. It runs after the
+    /// guards of [`RouterConfiguration::guards`].
+    fn guard(&self) -> Option<Guard<Self>> {
+        None
+    }
+
+    /// If the guard of this route runs on soft navigations, false for routes declared with
+    /// `guard(..., params_only = false)`.
+    fn guard_on_soft_navigation(&self) -> bool {
+        true
+    }
+
+    /// If navigating between two paths of this route remounts its component, for routes declared with
+    /// `hard_navigation`. Otherwise the component keeps its state and only gets new props.
+    fn hard_navigation(&self) -> bool {
+        false
+    }
+
+    /// If this is the `#[route(not_found)]` variant, which `FromStr` returns for locations no other route matches.
+    fn is_not_found(&self) -> bool {
+        false
+    }
+
+    /// The HTTP status code a server should respond with when rendering this route, for routes declared with
+    /// `status = ...`.
+    fn status_hint(&self) -> u16 {
+        200
+    }
+
+    /// The `Cache-Control` header a server should respond with when rendering this route, for routes declared with
+    /// `cache = "..."`. Common values are `"public, max-age=3600"` for pages that are the same for every visitor,
+    /// `"private, max-age=0, must-revalidate"` for pages that depend on the user, and `"no-store"` for pages that
+    /// must never be cached.
+    fn cache_hint(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// If some routes parse the query along with the path, because they have a catch-all with
+    /// `#[route_segment(include_query)]` or a `?(name)`. The router hands those the whole location.
+    fn parses_query() -> bool {
+        false
+    }
+
+    /// If this route's catch-all has `#[route_segment(include_query)]` or its pattern has a `?(name)`, so its
+    /// path includes the query.
+    fn includes_query(&self) -> bool {
+        false
+    }
+
+    /// If some routes parse the fragment along with the path, because their pattern ends with `#(name)`. The router
+    /// parses links with a fragment to those with their fragment.
+    fn parses_fragment() -> bool {
+        false
+    }
+
+    /// The title and `<meta>` tags of the page, for routes declared with `title = "..."` or `meta(...)`.
+    fn head(&self) -> HeadMeta {
+        HeadMeta::default()
+    }
+
+    /// The language of localized routes and the direction their text is written in, for the `<html>` element.
+    /// Switching the locale of a page keeps its variant, so it is a soft navigation.
+    fn lang(&self) -> Option<DocumentLang> {
+        None
+    }
+
+    /// Every route declared with `feature = "..."`, in the order they were declared. The list is the same in
+    /// every build, so a menu can show a teaser for the routes of features that are turned off.
+    fn gated_routes() -> &'static [GatedRoute] {
+        &[]
+    }
+
+    /// The note of a route declared with `deprecated = "use /dashboard"`, for a banner that points to its
+    /// replacement.
+    fn is_deprecated(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The pattern and note of every deprecated route, localized patterns included, in the order they were declared.
+    fn deprecated_routes() -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// The team that owns the route, for routes declared with `owner = "payments-team"`. Failures of the route name
+    /// it, and `ROUTE_TABLE_JSON` lists it, so routes without an owner can be found.
+    fn owner(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The keys of every route declared with `shortcut = "..."` and the route they navigate to, in the order the
+    /// routes were declared.
+    fn shortcuts() -> Vec<(&'static str, Self)> {
+        Vec::new()
+    }
+
+    /// The routes a command palette lists, in the order they were declared: every route without dynamic segments
+    /// unless it has `palette = false`, and the routes from the `palette_provider = ...` of routes with dynamic
+    /// segments. Routes whose feature is turned off are left out.
+    fn palette_routes() -> Vec<Self> {
+        Vec::new()
+    }
+
+    /// If the action of the route only navigates, so a dry run can run it. Declared with `dry_run_safe = true`.
+    fn action_is_dry_run_safe(&self) -> bool {
+        false
+    }
+
+    /// The icon of the route in a command palette, for routes declared with `icon = "..."`.
+    fn palette_icon(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// If navigation menus leave the route out, for routes declared with `nav = false`.
+    fn nav_hidden(&self) -> bool {
+        false
+    }
+
+    /// Where navigation menus list the route, for routes declared with `order = ...`. Lower orders come first, and
+    /// routes without one have order 0.
+    fn nav_order(&self) -> i32 {
+        0
+    }
+
+    /// An entry with the title, icon and feature of every route of [`Routable::palette_routes`]. Search them with
+    /// [`palette::search`].
+    fn palette_entries() -> Vec<PaletteEntry<Self>> {
+        Self::palette_routes()
+            .into_iter()
+            .map(PaletteEntry::new)
+            .collect()
+    }
+
+    /// The limits of the generated parser. Paths from untrusted clients can be megabytes long or made of thousands
+    /// of slashes, so `from_str`, `from_segments` and `from_str_lenient` reject a path that is longer than
+    /// `max_path_len` bytes or has more than `max_segments` segments with a [`ParseLimit`] error, before trying
+    /// any route. Within the limits, parsing takes time proportional to the length of the path for each route that
+    /// is tried, and percent-encoding is decoded exactly once, so `%2525` parses as `%25`.
+    fn parse_limits() -> ParseLimits {
+        ParseLimits::default()
+    }
+
+    /// The error for a path that is rejected without trying any route, like the errors for paths over the
+    /// [`Routable::parse_limits`].
+    fn rejected(limit: ParseLimit) -> <Self as FromStr>::Err;
+
+    /// Update the fragment of a route that keeps it in a field, like the section of a documentation page. Called
+    /// when the router follows a link with a fragment, so a link to `#pricing` on the current route changes the
+    /// field without parsing the path again. Routes without such a field ignore it.
+    fn set_fragment(&mut self, _fragment: Option<&str>) {}
+
+    /// The id of the element to scroll to for a route that keeps its fragment in a field: the field as it is
+    /// displayed, without percent-encoding. `None` if the field is an absent `Option`, or for routes without one.
+    fn fragment_id(&self) -> Option<String> {
+        None
+    }
+
+    /// The routes whose feature was turned off. Their variants still exist and display as paths, but the paths
+    /// never parse and the routes render nothing.
+    fn disabled_routes() -> Vec<&'static GatedRoute> {
+        Self::gated_routes()
+            .iter()
+            .filter(|route| !route.enabled)
+            .collect()
+    }
+
+    /// The path of the route as a [`http::Uri`], like for the `Location` header of a redirect response.
+    ///
+    /// Fails if a segment written with `encode = "none"` contains a character a uri can't, like a space. Every
+    /// other segment is percent-encoded, so its path is always a valid uri.
+    #[cfg(feature = "http")]
+    fn to_uri(&self) -> Result<http::Uri, http::uri::InvalidUri> {
+        let mut path = String::new();
+        self.write_path(&mut path)
+            .expect("a segment failed to display");
+        http::Uri::try_from(path)
+    }
+
+    fn comp(cx: Scope<RouterProps>) -> Element
+    where
+        Self: 'static,
+    {
+        let legacy = cx
+            .props
+            .legacy_fallback
+            .as_ref()
+            .and_then(|fallback| fallback.match_path(&cx.props.current_route));
+        if let Some((prefix, legacy)) = legacy {
+            #[cfg(feature = "metrics")]
+            route_metrics::record_legacy_hit(prefix);
+            #[cfg(not(feature = "metrics"))]
+            let _ = prefix;
+            cx.provide_context(LegacyParams(legacy.params));
+            let node = cx.component(legacy.render, (), "LegacyRoute");
+            return render! { node };
+        }
+
+        #[cfg(feature = "metrics")]
+        let start = std::time::Instant::now();
+        let router = Self::from_str(&cx.props.current_route);
+        #[cfg(feature = "metrics")]
+        {
+            route_metrics::record_parse(&router, start.elapsed());
+            if let Ok(route) = &router {
+                route_metrics::record_match(route, &NavigationSource::Browser);
+            }
+        }
+        match router {
+            Ok(router) => {
+                // The not_found variant renders its own page, which gets the same suggestions as the page for a
+                // location that fails to parse
+                if router.is_not_found() {
+                    cx.provide_context(NotFoundContext::<Self>::new(
+                        &cx.props.current_route,
+                        cx.props.max_suggestions,
+                    ));
+                }
+                #[cfg(feature = "devtools")]
+                return watermark::render_route(cx, router);
+                #[cfg(not(feature = "devtools"))]
+                router.render(cx)
+            }
+            Err(err) => {
+                let dynamic = cx.props.dynamic_routes.match_path(&cx.props.current_route);
+                if let Some((route, params)) = dynamic {
+                    let props = DynamicRouteProps { params };
+                    let node = cx.component(route.render, props, "DynamicRoute");
+                    return render! { node };
+                }
+                let not_found = cx.provide_context(NotFoundContext::<Self>::new(
+                    &cx.props.current_route,
+                    cx.props.max_suggestions,
+                ));
+                render! {
+                    pre {
+                        "{err}"
+                    }
+                    if !not_found.suggestions.is_empty() {
+                        rsx! {
+                            "Did you mean:"
+                            ul {
+                                not_found.suggestions.iter().map(|route| rsx! {
+                                    li { a { href: "{route}", "{route}" } }
+                                })
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(conflict_test, fixtures)]
+enum Route {
+    #[route("/(dynamic)")]
+    Route1 { dynamic: String },
+    #[route("/hello_world", cache = "public, max-age=3600", shortcut = "g w")]
+    Route2 {},
+    #[route("/hello_world/(dynamic)")]
+    Route3 { dynamic: u32 },
+    #[route("/(number1)/(number2)")]
+    Route4 { number1: u32, number2: u32 },
+    #[route("/", shortcut = "g h", title = "Home", icon = "home")]
+    Route5 {},
+    #[route("/files/(...path)" Files)]
+    Route6 {
+        #[route_segment(non_empty)]
+        path: Vec<String>,
+    },
+    /// Documentation served from the docs directory
+    #[route("/docs/(...path)" Docs)]
+    Docs {
+        /// The path of the page inside the docs directory
+        path: UrlPath,
+    },
+    #[route("/gone/(id)" Gone, status = 410, cache = "public, max-age=3600")]
+    /// Content that was removed
+    Gone { id: u32 },
+    #[route("/logout", action = logout, palette = false)]
+    Logout {},
+    #[route(
+        "/search/(query)/(page)/(sort)" Search,
+        provide = SearchTerms::from_route,
+        title = "Results for {query}",
+        meta(description = "Page {page} of the results for {query}, sorted by {sort}"),
+        palette_provider = recent_searches
+    )]
+    Search {
+        query: String,
+        page: u32,
+        sort: String,
+    },
+    #[route("/debug/routes" RouteTable, feature = "devtools")]
+    RouteTable {},
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Docs(cx: Scope, path: UrlPath) -> Element {
+    let file = path.join(std::path::Path::new("docs"));
+    render! {div {
+        "Docs {{
+            file: {file:?}
+        }}"
+    }}
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Gone(cx: Scope, id: u32) -> Element {
+    render! {div {
+        "Gone {{
+            id: {id}
+        }}"
+    }}
+}
+
+/// The searches the command palette suggests
+fn recent_searches() -> Vec<Route> {
+    vec![Route::Search {
+        query: "rust".to_string(),
+        page: 1,
+        sort: "new".to_string(),
+    }]
+}
+
+fn logout(router: &mut Router<Route>) {
+    router.replace(Route::Route5 {});
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Files(cx: Scope, path: Vec<String>) -> Element {
+    render! {div {
+        "Files {{
+            path: {path:?}
+        }}"
+    }}
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Search(cx: Scope, query: String, page: u32, sort: String) -> Element {
+    render! {div {
+        "Search {{
+            query: {query},
+            page: {page},
+            sort: {sort}
+        }}"
+        SearchSummary {}
+    }}
+}
+
+#[cfg(feature = "devtools")]
+#[inline_props]
+#[allow(non_snake_case)]
+fn RouteTable(cx: Scope) -> Element {
+    render! {
+        ul {
+            Route::ROUTE_PATTERNS.iter().map(|pattern| {
+                let deprecated = Route::deprecated_routes().iter().find(|(other, _)| other == pattern);
+                match deprecated {
+                    Some((_, note)) => rsx! { li { "{pattern} " span { title: "{note}", "deprecated" } } },
+                    None => rsx! { li { "{pattern}" } },
+                }
+            })
+        }
+        ul {
+            Route::shortcuts().into_iter().map(|(keys, route)| rsx! { li { "{keys}: {route}" } })
+        }
+    }
+}
+
+/// The words of the current search, provided by the search route
+#[derive(Clone)]
+struct SearchTerms(Vec<String>);
+
+impl SearchTerms {
+    fn from_route(query: &String, _page: &u32, _sort: &String) -> Self {
+        Self(query.split_whitespace().map(String::from).collect())
+    }
+}
+
+#[allow(non_snake_case)]
+fn SearchSummary(cx: Scope) -> Element {
+    let terms = cx.consume_context::<SearchTerms>()?;
+    let count = terms.0.len();
+    render! { "{count} search terms" }
+}
+
+routes_from_file!("src/routes.toml");
+
+#[allow(non_snake_case)]
+fn CatalogPage(cx: Scope) -> Element {
+    render! { "Catalog" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ProductPage(cx: Scope, id: u32) -> Element {
+    render! { "Product {id}" }
+}
+
+/// The settings page, rendered by its own router
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[route("/settings/(tab)" Settings)]
+#[routable(version = 2, migrate(1 => migrate_settings_v1))]
+struct SettingsRoute {
+    tab: String,
+}
+
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[route("/articles/(section)/(slug)/(tag)" SharedArticle, builder = false)]
+struct SharedArticleRoute {
+    section: std::borrow::Cow<'static, str>,
+    slug: Rc<str>,
+    /// Empty for untagged articles
+    #[route_segment(allow_empty)]
+    tag: std::sync::Arc<str>,
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn SharedArticle(
+    cx: Scope,
+    section: std::borrow::Cow<'static, str>,
+    slug: Rc<str>,
+    tag: std::sync::Arc<str>,
+) -> Element {
+    render! {div {
+        "Article {{
+            section: {section},
+            slug: {slug},
+            tag: {tag}
+        }}"
+    }}
+}
+
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[route("/report/(year)/(month)" Report)]
+#[routable(lenient, max_path_len = 64, max_segments = 4)]
+struct ReportRoute {
+    year: u32,
+    month: u32,
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Report(cx: Scope, year: u32, month: u32) -> Element {
+    render! {div {
+        "Report {{
+            year: {year},
+            month: {month}
+        }}"
+    }}
+}
+
+/// Files served under a fixed extension
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(baseline = "src/routes_baseline.json", redirect("/rss.xml" => "/feed.xml"))]
+enum AssetRoute {
+    #[route("/feed.xml" Feed)]
+    Feed {},
+    #[route("/avatar/(file)" AvatarFile)]
+    AvatarFile { file: String },
+    #[route("/avatar/(user_id).png" Avatar)]
+    Avatar { user_id: u32 },
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Feed(cx: Scope) -> Element {
+    render! {div { "Feed {{}}" }}
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn AvatarFile(cx: Scope, file: String) -> Element {
+    render! {div { "AvatarFile {{ file: {file} }}" }}
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Avatar(cx: Scope, user_id: u32) -> Element {
+    render! {div { "Avatar {{ user_id: {user_id} }}" }}
+}
+
+/// Pages of a partner integration whose server compares paths byte for byte
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum PartnerRoute {
+    /// Matrix-like parameters, like `/partner/color:red,size:m`, which the partner requires unencoded
+    #[route("/partner/(filters)" PartnerFilters)]
+    PartnerFilters {
+        #[route_segment(encode = "none")]
+        filters: String,
+    },
+    #[route("/partner/signed/(token)" PartnerSigned)]
+    PartnerSigned {
+        #[route_segment(encode = "unreserved")]
+        token: String,
+    },
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn PartnerFilters(cx: Scope, filters: String) -> Element {
+    render! { "Filters {filters}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn PartnerSigned(cx: Scope, token: String) -> Element {
+    render! { "Signed {token}" }
+}
+
+/// A router written by hand, moving to the derive one route at a time. The blog route parses its segments with
+/// `route_segments!`, and the assets have already moved to the derived [`AssetRoute`], which is tried last.
+#[derive(Clone, Debug, PartialEq)]
+enum LegacyRoute {
+    Home,
+    BlogPost { id: u64, slug: String },
+    Assets(AssetRoute),
+}
+
+fn blog_post() -> impl SegmentPattern<Params = (u64, String)> {
+    route_segments!("/blog/(id: u64)/(slug: String)")
+}
+
+impl FromStr for LegacyRoute {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "/" {
+            return Ok(Self::Home);
+        }
+        let blog_error = match blog_post().parse(s) {
+            Ok((id, slug)) => return Ok(Self::BlogPost { id, slug }),
+            Err(err) => err.to_string(),
+        };
+        AssetRoute::from_str(s)
+            .map(Self::Assets)
+            .map_err(|err| format!("{blog_error}{err}"))
+    }
+}
+
+impl std::fmt::Display for LegacyRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_path(f)
+    }
+}
+
+impl Routable for LegacyRoute {
+    fn render(self, cx: &ScopeState) -> Element {
+        match self {
+            Self::Home => render! { "Home" },
+            Self::BlogPost { id, slug } => render! { "Post {id}: {slug}" },
+            Self::Assets(route) => route.render(cx),
+        }
+    }
+
+    fn write_path(&self, out: &mut impl std::fmt::Write) -> std::fmt::Result {
+        match self {
+            Self::Home => out.write_str("/"),
+            Self::BlogPost { id, slug } => blog_post().write_path(&(*id, slug.clone()), out),
+            Self::Assets(route) => route.write_path(out),
+        }
+    }
+
+    fn matched_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Home => Vec::new(),
+            Self::BlogPost { id, slug } => vec![("id", id.to_string()), ("slug", slug.clone())],
+            Self::Assets(route) => route.matched_params(),
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Home => "/",
+            Self::BlogPost { .. } => blog_post().pattern(),
+            Self::Assets(route) => route.pattern(),
+        }
+    }
+
+    fn route_patterns() -> &'static [&'static str] {
+        static PATTERNS: std::sync::OnceLock<Vec<&'static str>> = std::sync::OnceLock::new();
+        PATTERNS.get_or_init(|| {
+            let mut patterns = vec!["/", blog_post().pattern()];
+            patterns.extend(AssetRoute::route_patterns());
+            patterns
+        })
+    }
+
+    fn from_pattern_and_params(pattern: &str, params: &[(&str, &str)]) -> Result<Self, BuildError> {
+        if pattern == "/" {
+            Ok(Self::Home)
+        } else if pattern == blog_post().pattern() {
+            let (id, slug) = blog_post().from_params(params)?;
+            Ok(Self::BlogPost { id, slug })
+        } else {
+            AssetRoute::from_pattern_and_params(pattern, params).map(Self::Assets)
+        }
+    }
+
+    fn rejected(limit: ParseLimit) -> String {
+        limit.to_string()
+    }
+}
+
+/// Version 1 of the settings route was `/preferences/(tab)`
+fn migrate_settings_v1(path: &str) -> String {
+    path.replacen("/preferences/", "/settings/", 1)
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Settings(cx: Scope, tab: String) -> Element {
+    render! {div {
+        "Settings {{
+            tab: {tab}
+        }}"
+    }}
+}
+
+#[test]
+fn display_works() {
+    let route = Route::Route1 {
+        dynamic: "hello".to_string(),
+    };
+
+    assert_eq!(route.to_string(), "/hello");
+
+    let route = Route::Route3 { dynamic: 1234 };
+
+    assert_eq!(route.to_string(), "/hello_world/1234");
+
+    let route = Route::Route1 {
+        dynamic: "hello_world2".to_string(),
+    };
+
+    assert_eq!(route.to_string(), "/hello_world2");
+
+    let route = Route::Route6 {
+        path: vec!["a".to_string(), "b".to_string()],
+    };
+
+    assert_eq!(route.to_string(), "/files/a/b");
+}
+
+#[test]
+fn from_string_works() {
+    let w = "/hello";
+    assert_eq!(
+        Route::from_str(w),
+        Ok(Route::Route1 {
+            dynamic: "hello".to_string()
+        })
+    );
+    let w = "/hello/";
+    assert_eq!(
+        Route::from_str(w),
+        Ok(Route::Route1 {
+            dynamic: "hello".to_string()
+        })
+    );
+
+    let w = "/hello_world/1234";
+    assert_eq!(Route::from_str(w), Ok(Route::Route3 { dynamic: 1234 }));
+    let w = "/hello_world/1234/";
+    assert_eq!(Route::from_str(w), Ok(Route::Route3 { dynamic: 1234 }));
+
+    let w = "/hello_world2";
+    assert_eq!(
+        Route::from_str(w),
+        Ok(Route::Route1 {
+            dynamic: "hello_world2".to_string()
+        })
+    );
+
+    let w = "/files/a/b/c";
+    assert_eq!(
+        Route::from_str(w),
+        Ok(Route::Route6 {
+            path: vec!["a".to_string(), "b".to_string(), "c".to_string()]
+        })
+    );
+
+    let w = "/hello_world/-1";
+    match Route::from_str(w) {
+        Ok(r) => panic!("should not parse {r:?}"),
+        Err(err) => println!("{err}"),
+    }
+}
+
+#[test]
+fn from_segments_matches_from_str() {
+    for path in [
+        "/",
+        "/hello",
+        "/hello/",
+        "/hello_world",
+        "/hello_world/1234",
+        "/hello_world/-1",
+        "/1/2/3",
+        "/files/a/b/c",
+        "/search/rust/2/stars",
+        "/%E0%A4",
+    ] {
+        let segments = path[1..].split('/');
+        assert_eq!(
+            Route::from_segments(segments),
+            Route::from_str(path),
+            "{path}"
+        );
+    }
+
+    assert_eq!(Route::from_segments([].into_iter()), Ok(Route::Route5 {}));
+    let parts = vec!["files".to_string(), "a".to_string(), "b".to_string()];
+    assert_eq!(
+        Route::from_segments(parts.iter().map(String::as_str)),
+        Ok(Route::Route6 {
+            path: vec!["a".to_string(), "b".to_string()]
+        })
+    );
+}
+
+#[test]
+fn round_trip() {
+    // Route1
+    let string = "hello_world2";
+    let route = Route::Route1 {
+        dynamic: string.to_string(),
+    };
+    assert_eq!(Route::from_str(&route.to_string()), Ok(route));
+
+    // Route2
+    for num in 0..100 {
+        let route = Route::Route3 { dynamic: num };
+        assert_eq!(Route::from_str(&route.to_string()), Ok(route));
+    }
+
+    // Route3
+    for num1 in 0..100 {
+        for num2 in 0..100 {
+            let route = Route::Route4 {
+                number1: num1,
+                number2: num2,
+            };
+            assert_eq!(Route::from_str(&route.to_string()), Ok(route));
+        }
+    }
+}
+
+#[test]
+fn inherent_path_methods() {
+    let route = Route::Route3 { dynamic: 7 };
+    assert_eq!(route.to_path(), "/hello_world/7");
+    assert_eq!(Route::from_path("/hello_world/7"), Ok(route));
+    assert_eq!(
+        Route::from_path("/1/2/3").map_err(|err| err.to_string()),
+        Route::from_str("/1/2/3").map_err(|err| err.to_string())
+    );
+}
+
+#[test]
+fn empty_catch_all() {
+    let docs = Route::Docs {
+        path: UrlPath::default(),
+    };
+    assert_eq!(docs.to_string(), "/docs");
+    assert_eq!(Route::from_str("/docs"), Ok(docs.clone()));
+    assert_eq!(Route::from_str("/docs/"), Ok(docs.clone()));
+    assert_eq!(Route::from_path(&docs.to_string()), Ok(docs));
+
+    // The files catch-all is non_empty, so a bare `/files` is left to the dynamic route
+    assert_eq!(
+        Route::from_str("/files"),
+        Ok(Route::Route1 {
+            dynamic: "files".to_string()
+        })
+    );
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn uri_conversions() {
+    // Each uri and the string path it must parse exactly like
+    let vectors = [
+        ("/hello_world/1", "/hello_world/1"),
+        ("/hello_world/1?dynamic=2", "/hello_world/1"),
+        ("https://example.com/hello_world?x=1", "/hello_world"),
+        ("https://example.com", "/"),
+        ("/hello%20world", "/hello%20world"),
+        ("/a+b?q=a+b", "/a+b"),
+        ("/files/a%2Fb/c", "/files/a%2Fb/c"),
+        ("/docs/caf%C3%A9/", "/docs/caf%C3%A9/"),
+        ("/hello%FF", "/hello%FF"),
+        ("/hello_world/1/2/3", "/hello_world/1/2/3"),
+    ];
+    for (uri, path) in vectors {
+        let uri: http::Uri = uri.parse().unwrap();
+        assert_eq!(Route::try_from(&uri), Route::from_str(path), "{uri}");
+    }
+
+    let route = Route::Route1 {
+        dynamic: "hello world/é".to_string(),
+    };
+    let uri = route.to_uri().unwrap();
+    assert_eq!(uri, "/hello%20world%2F%C3%A9");
+    assert_eq!(Route::try_from(&uri), Ok(route));
+
+    // Segments without encoding are written as they are, even if a uri can't contain them
+    let partner = PartnerRoute::PartnerFilters {
+        filters: "color:red,size:m".to_string(),
+    };
+    assert_eq!(partner.to_uri().unwrap(), "/partner/color:red,size:m");
+    let partner = PartnerRoute::PartnerFilters {
+        filters: "color red".to_string(),
+    };
+    assert!(partner.to_uri().is_err());
+}
+
+#[test]
+fn write_paths() {
+    let mut out = String::from("routes: ");
+    Route::Route3 { dynamic: 1 }.write_path(&mut out).unwrap();
+    assert_eq!(out, "routes: /hello_world/1");
+
+    let routes = (1..=3).map(|dynamic| Route::Route3 { dynamic });
+    assert_eq!(
+        paths_of(routes),
+        "/hello_world/1\n/hello_world/2\n/hello_world/3"
+    );
+    assert_eq!(paths_of(Vec::<Route>::new()), "");
+}
+
+/// A dashboard that moved, kept alive until its links are updated
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum DashboardRoute {
+    #[route("/dashboard" Dashboard)]
+    Dashboard {},
+    #[route("/old-dashboard" Dashboard, deprecated = "use /dashboard")]
+    OldDashboard {},
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn Dashboard(cx: Scope) -> Element {
+    render! { "dashboard" }
+}
+
+#[test]
+fn deprecated_routes() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    assert_eq!(
+        DashboardRoute::OldDashboard {}.is_deprecated(),
+        Some("use /dashboard")
+    );
+    assert_eq!(DashboardRoute::Dashboard {}.is_deprecated(), None);
+    assert_eq!(
+        DashboardRoute::deprecated_routes(),
+        [("/old-dashboard", "use /dashboard")]
+    );
+    assert!(DashboardRoute::ROUTE_TABLE_JSON.contains(r#""deprecated": "use /dashboard""#));
+
+    let routes = || {
+        [
+            DashboardRoute::Dashboard {},
+            DashboardRoute::OldDashboard {},
+        ]
+    };
+    assert_eq!(sitemap_of(routes(), false), "/dashboard");
+    assert_eq!(sitemap_of(routes(), true), "/dashboard\n/old-dashboard");
+
+    // The router warns once per route
+    let history = MemoryHistory::with_initial_path("/old-dashboard").unwrap();
+    let mut router = Router::<DashboardRoute>::new(history).unwrap();
+    assert_eq!(router.deprecation_warnings, ["/old-dashboard"]);
+    router.push(DashboardRoute::Dashboard {});
+    router.push(DashboardRoute::OldDashboard {});
+    assert_eq!(router.deprecation_warnings, ["/old-dashboard"]);
+}
+
+#[test]
+fn bounded_memory() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let config = RouterConfiguration {
+        parse_cache_capacity: 16,
+        max_history_entries: 32,
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/hello_world").unwrap();
+    let mut router = Router::<Route>::with_configuration(history, config).unwrap();
+    for dynamic in 0..10_000 {
+        router.save_scroll_position(dynamic as f64);
+        router.push(Route::Route3 { dynamic });
+        if dynamic % 10 == 0 {
+            router.go_back().unwrap();
+            router.go_forward().unwrap();
+        }
+        assert!(router.scroll.len() <= 32);
+        assert!(router.cache.len() <= 16);
+    }
+
+    // The previous entry is never dropped
+    router.go_back().unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 9998 });
+    assert_eq!(
+        router.take_scroll_request(),
+        Some(ScrollRequest::Position(9999.0))
+    );
+}
+
+#[test]
+fn unicode_segments() {
+    for dynamic in ["你好世界", "🦀🚀"] {
+        let route = Route::Route1 {
+            dynamic: dynamic.to_string(),
+        };
+        let encoded = route.to_string();
+        assert!(encoded.is_ascii());
+        assert_eq!(Route::from_str(&encoded), Ok(route.clone()));
+        // Clients that send raw UTF-8 paths parse to the same route
+        assert_eq!(Route::from_str(&format!("/{dynamic}")), Ok(route));
+    }
+
+    let err = Route::from_str("/%E0%A4").unwrap_err();
+    assert!(err.attempted_routes.contains(&RouteMatchError::Route1 {
+        error: Route1ParseError::dynamicParseError(SegmentError::DecodeError(
+            DecodeError::InvalidUtf8
+        )),
+        span: 1..7,
+    }));
+}
+
+#[test]
+fn decode_and_parse_errors() {
+    let route3_error = |path: &str| {
+        Route::from_str(path)
+            .unwrap_err()
+            .attempted_routes
+            .into_iter()
+            .find_map(|error| match error {
+                RouteMatchError::Route3 { error, .. } => Some(error),
+                _ => None,
+            })
+            .unwrap()
+    };
+    let malformed = route3_error("/hello_world/%GG");
+    assert_eq!(
+        malformed,
+        Route3ParseError::dynamicParseError(SegmentError::DecodeError(
+            DecodeError::InvalidPercentEncoding
+        ))
+    );
+    assert!(malformed.is_decode_error());
+    assert_eq!(
+        malformed.to_string(),
+        "Dynamic segment '(dynamic:u32)' could not be decoded: a '%' is not followed by two hex digits"
+    );
+
+    let not_a_number = route3_error("/hello_world/abc");
+    assert!(matches!(
+        not_a_number,
+        Route3ParseError::dynamicParseError(SegmentError::ParseError(_))
+    ));
+    assert!(!not_a_number.is_decode_error());
+    assert!(not_a_number
+        .to_string()
+        .starts_with("Dynamic segment '(dynamic:u32)' did not match: "));
+
+    // String segments keep a stray `%` as it is, so the path still parses
+    assert_eq!(
+        Route::from_str("/100%").unwrap(),
+        Route::Route1 {
+            dynamic: "100%".to_string()
+        }
+    );
+
+    // The decode error is reported even though other routes got as far into the path
+    let err = Route::from_str("/hello_world/%GG").unwrap_err();
+    assert!(err.best_match().unwrap().is_decode_error());
+    let err = Route::from_str("/hello_world/abc/def").unwrap_err();
+    assert!(!err.best_match().unwrap().is_decode_error());
+}
+
+#[test]
+fn pathological_paths() {
+    let limits = Route::parse_limits();
+    assert_eq!(limits, ParseLimits::default());
+
+    let long = format!("/files/{}", "a".repeat(limits.max_path_len));
+    assert_eq!(
+        Route::from_str(&long).unwrap_err().limit,
+        Some(ParseLimit::PathTooLong {
+            len: long.len(),
+            max: limits.max_path_len
+        })
+    );
+    let slashes = "/".repeat(10_000);
+    assert_eq!(
+        Route::from_str(&slashes).unwrap_err().limit,
+        Some(ParseLimit::TooManySegments {
+            max: limits.max_segments
+        })
+    );
+    let segments = std::iter::repeat("a").take(100_000);
+    assert_eq!(
+        Route::from_segments(segments).unwrap_err().limit,
+        Some(ParseLimit::TooManySegments {
+            max: limits.max_segments
+        })
+    );
+    let segments = std::iter::repeat(&*long).take(2);
+    assert!(matches!(
+        Route::from_segments(segments).unwrap_err().limit,
+        Some(ParseLimit::PathTooLong { .. })
+    ));
+
+    // Paths within the limits still parse, whatever is in them
+    let deep = format!("/files{}", "/a".repeat(limits.max_segments - 1));
+    assert!(matches!(Route::from_str(&deep), Ok(Route::Route6 { .. })));
+    for path in [
+        "/\0",
+        "/hello_world/%00",
+        "/%FF%FE/%C0",
+        "/%%%",
+        "/%2525/%",
+        "//",
+        "?",
+        "/?#",
+    ] {
+        if let Err(err) = Route::from_str(path) {
+            assert_eq!(err.limit, None, "{path}");
+        }
+    }
+
+    // Types can lower the limits
+    assert_eq!(
+        ReportRoute::parse_limits(),
+        ParseLimits {
+            max_path_len: 64,
+            max_segments: 4
+        }
+    );
+    assert_eq!(
+        ReportRoute::from_str_lenient("/report/2024/5/1/2")
+            .unwrap_err()
+            .limit,
+        Some(ParseLimit::TooManySegments { max: 4 })
+    );
+    let err = ReportRoute::from_str(&format!("/report/{}", "9".repeat(64))).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Route did not match: The path is 72 bytes long, the limit is 64"
+    );
+}
+
+/// Routes of a component embedded in other crates, which shouldn't see the generated error types
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(boxed_errors)]
+enum EmbeddedRoute {
+    #[route("/")]
+    EmbeddedHome {},
+    #[route("/items/(id)")]
+    EmbeddedItem { id: u32 },
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn EmbeddedHome(cx: Scope) -> Element {
+    render! { "Home" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn EmbeddedItem(cx: Scope, id: u32) -> Element {
+    render! { "Item {id}" }
+}
+
+#[test]
+fn boxed_errors() {
+    let err: Box<dyn std::error::Error + Send + Sync> =
+        EmbeddedRoute::from_str("/items/x").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Dynamic segment '(id:u32)' did not match"),
+        "{err}"
+    );
+    let source = err.source().unwrap();
+    assert!(source.to_string().starts_with("Route '"), "{source}");
+
+    let parse_error = EmbeddedRoute::parse_error(&*err).unwrap();
+    let item_error = parse_error
+        .attempted_routes
+        .iter()
+        .find_map(|error| match error {
+            EmbeddedRouteMatchError::EmbeddedItem { error, .. } => Some(error),
+            _ => None,
+        })
+        .unwrap();
+    assert!(matches!(
+        item_error,
+        EmbeddedItemParseError::idParseError(_)
+    ));
+    assert!(err
+        .downcast_ref::<RouteParseError<EmbeddedRouteMatchError>>()
+        .is_some());
+
+    // Every public parsing function returns the boxed error
+    let err = EmbeddedRoute::from_segments(["items", "x"].into_iter()).unwrap_err();
+    assert!(EmbeddedRoute::parse_error(&*err).is_some());
+    let err = EmbeddedRoute::from_path(&"/".repeat(10_000)).unwrap_err();
+    assert_eq!(
+        EmbeddedRoute::parse_error(&*err).unwrap().limit,
+        Some(ParseLimit::PathTooLong {
+            len: 10_000,
+            max: 8192
+        })
+    );
+    assert_eq!(
+        EmbeddedRoute::from_path("/items/3").unwrap(),
+        EmbeddedRoute::EmbeddedItem { id: 3 }
+    );
+}
+
+/// Routes with a translated path for every language of the site
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum LocalizedRoute {
+    #[route("/about", locale(de = "/ueber-uns", fr = "/a-propos"))]
+    LocalizedAbout { locale: String },
+    #[route("/blog/(id)", locale(de = "/de/beitraege/(id)"))]
+    LocalizedPost { id: u32, locale: &'static str },
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn LocalizedAbout(cx: Scope) -> Element {
+    render! { "About" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn LocalizedPost(cx: Scope, id: u32) -> Element {
+    render! { "Post {id}" }
+}
+
+#[test]
+fn localized_patterns() {
+    let about = |locale: &str| LocalizedRoute::LocalizedAbout {
+        locale: locale.to_string(),
+    };
+    // Every pattern parses into the same variant and records its locale
+    assert_eq!(LocalizedRoute::from_str("/about").unwrap(), about("en"));
+    assert_eq!(LocalizedRoute::from_str("/ueber-uns").unwrap(), about("de"));
+    assert_eq!(LocalizedRoute::from_str("/a-propos").unwrap(), about("fr"));
+    assert_eq!(
+        LocalizedRoute::from_str("/de/beitraege/7").unwrap(),
+        LocalizedRoute::LocalizedPost {
+            id: 7,
+            locale: "de"
+        }
+    );
+
+    // Display uses the pattern of the locale, and the route's own pattern for other locales
+    assert_eq!(about("fr").to_string(), "/a-propos");
+    assert_eq!(about("es").to_string(), "/about");
+    let post = LocalizedRoute::LocalizedPost {
+        id: 7,
+        locale: "de",
+    };
+    assert_eq!(post.to_string(), "/de/beitraege/7");
+    assert_eq!(post.pattern(), "/de/beitraege/(id)");
+    assert_eq!(post.depth(), 3);
+    assert_eq!(post.section(), Some("de"));
+    assert_eq!(
+        LocalizedRoute::from_pattern_and_params("/ueber-uns", &[]).unwrap(),
+        about("de")
+    );
+
+    assert!(LocalizedRoute::ROUTE_TABLE_JSON.contains(
+        r#"{"variant": "LocalizedAbout", "pattern": "/ueber-uns", "hreflang": "de", "segments": []}"#
+    ));
+    assert!(LocalizedRoute::ROUTE_TABLE_JSON.contains(
+        r#"{"variant": "LocalizedPost", "pattern": "/blog/(id)", "hreflang": "en", "segments": [{"name": "id", "type": "u32"}]}"#
+    ));
+}
+
+#[test]
+fn error_spans() {
+    let input = "/hello_world/%C3%BC";
+    let err = Route::from_str(input).unwrap_err();
+    let dynamic_error = err
+        .attempted_routes
+        .iter()
+        .find(|err| matches!(err, RouteMatchError::Route3 { .. }))
+        .unwrap();
+    assert_eq!(dynamic_error.span(), 13..19);
+    assert_eq!(
+        dynamic_error.highlight(input),
+        "/hello_world/%C3%BC\n             ^^^^^^"
+    );
+
+    let input = "/1/2/3";
+    let err = Route::from_str(input).unwrap_err();
+    let extra_segments = err
+        .attempted_routes
+        .iter()
+        .find(|err| matches!(err, RouteMatchError::Route4 { .. }))
+        .unwrap();
+    assert_eq!(extra_segments.span(), 5..6);
+}
+
+#[test]
+fn route_patterns_are_in_match_order() {
+    assert_eq!(
+        Route::ROUTE_PATTERNS,
+        &[
+            "/hello_world",
+            "/hello_world/(dynamic)",
+            "/",
+            "/files/(...path)",
+            "/docs/(...path)",
+            "/gone/(id)",
+            "/logout",
+            "/search/(query)/(page)/(sort)",
+            "/debug/routes",
+            "/(dynamic)",
+            "/(number1)/(number2)",
+        ]
+    );
+}
+
+#[test]
+fn match_patterns() {
+    for (path, pattern) in [
+        ("/", "/"),
+        ("/hello", "/(dynamic)"),
+        ("/hello/", "/(dynamic)"),
+        ("/hello_world", "/hello_world"),
+        ("/hello_world/1234", "/hello_world/(dynamic)"),
+        ("/1/2", "/(number1)/(number2)"),
+        ("/files/a/b/c", "/files/(...path)"),
+        ("/files", "/(dynamic)"),
+        ("/docs", "/docs/(...path)"),
+        ("/search/rust/2/stars", "/search/(query)/(page)/(sort)"),
+    ] {
+        assert_eq!(Route::match_pattern(path), Some(pattern), "{path}");
+    }
+
+    // Dynamic segments are not parsed, so this matches even though -1 is not a valid u32
+    assert_eq!(
+        Route::match_pattern("/hello_world/-1"),
+        Some("/hello_world/(dynamic)")
+    );
+    assert_eq!(Route::match_pattern("/1/2/3"), None);
+}
+
+#[test]
+fn build_from_pattern_and_params() {
+    assert_eq!(
+        Route::from_pattern_and_params(
+            "/(number1)/(number2)",
+            &[("number2", "2"), ("number1", "1")]
+        ),
+        Ok(Route::Route4 {
+            number1: 1,
+            number2: 2
+        })
+    );
+    assert_eq!(
+        Route::from_pattern_and_params("/files/(...path)", &[("path", "a/b")]),
+        Ok(Route::Route6 {
+            path: vec!["a".to_string(), "b".to_string()]
+        })
+    );
+    assert_eq!(
+        Route::from_pattern_and_params("/", &[("unused", "1")]),
+        Ok(Route::Route5 {})
+    );
+
+    assert_eq!(
+        Route::from_pattern_and_params("/nope", &[]),
+        Err(BuildError::UnknownPattern("/nope".to_string()))
+    );
+    assert_eq!(
+        Route::from_pattern_and_params("/hello_world/(dynamic)", &[]),
+        Err(BuildError::MissingParam("dynamic"))
+    );
+    assert!(matches!(
+        Route::from_pattern_and_params("/hello_world/(dynamic)", &[("dynamic", "-1")]),
+        Err(BuildError::InvalidParam {
+            name: "dynamic",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn depth_and_section() {
+    assert_eq!(Route::Route5 {}.depth(), 0);
+    assert_eq!(Route::Route5 {}.section(), None);
+    assert_eq!(Route::Route2 {}.depth(), 1);
+    assert_eq!(Route::Route3 { dynamic: 1 }.depth(), 2);
+    assert_eq!(Route::Route3 { dynamic: 1 }.section(), Some("hello_world"));
+    assert_eq!(
+        Route::Route1 {
+            dynamic: "a".to_string()
+        }
+        .section(),
+        None
+    );
+
+    let route = Route::from_str("/files/a/b/c").unwrap();
+    assert_eq!(route.depth(), 4);
+    assert_eq!(route.section(), Some("files"));
+}
+
+#[test]
+fn compare_routes() {
+    let first = Route::Route4 {
+        number1: 1,
+        number2: 2,
+    };
+    let second = Route::Route4 {
+        number1: 1,
+        number2: 3,
+    };
+    assert!(first.same_variant(&second));
+    assert_eq!(
+        first.params_diff(&second),
+        vec![("number2", "2".to_string(), "3".to_string())]
+    );
+
+    let other_variant = Route::Route3 { dynamic: 2 };
+    assert!(!first.same_variant(&other_variant));
+    assert!(first.params_diff(&other_variant).is_empty());
+
+    assert!(first.same_variant(&first));
+    assert!(first.params_diff(&first).is_empty());
+}
+
+#[test]
+fn routes_from_file() {
+    assert_eq!(
+        CatalogRoute::from_str("/catalog"),
+        Ok(CatalogRoute::Catalog {})
+    );
+    let product = CatalogRoute::Product { id: 7 };
+    assert_eq!(product.to_string(), "/catalog/7");
+    assert_eq!(CatalogRoute::from_str("/catalog/7"), Ok(product));
+    assert!(CatalogRoute::from_str("/catalog/seven").is_err());
+}
+
+#[test]
+fn struct_routes() {
+    let route = SettingsRoute {
+        tab: "profile".to_string(),
+    };
+    assert_eq!(route.to_string(), "/settings/profile");
+    assert_eq!(SettingsRoute::from_str("/settings/profile"), Ok(route));
+    assert!(SettingsRoute::from_str("/profile/settings").is_err());
+}
+
+#[test]
+fn route_builders() {
+    let route = Route::search()
+        .page(2)
+        .query("rust".to_string())
+        .sort("stars".to_string())
+        .build();
+    assert_eq!(
+        route,
+        Route::Search {
+            query: "rust".to_string(),
+            page: 2,
+            sort: "stars".to_string(),
+        }
+    );
+    assert_eq!(route.to_string(), "/search/rust/2/stars");
+}
+
+#[test]
+fn shared_string_segments() {
+    let route = SharedArticleRoute {
+        section: "intro".into(),
+        slug: "hello world".into(),
+        tag: "你好".into(),
+    };
+    assert!(matches!(route.section, std::borrow::Cow::Borrowed(_)));
+    assert_eq!(
+        route.to_string(),
+        "/articles/intro/hello%20world/%E4%BD%A0%E5%A5%BD"
+    );
+    assert_eq!(SharedArticleRoute::from_str(&route.to_string()), Ok(route));
+}
+
+#[test]
+fn empty_segments() {
+    let err = Route::from_str("/search//2/stars").unwrap_err();
+    assert!(err.attempted_routes.iter().any(|err| matches!(
+        err,
+        RouteMatchError::Search {
+            error: SearchParseError::EmptySegment("query"),
+            span
+        } if *span == (8..8)
+    )));
+
+    let err = SharedArticleRoute::from_str("/articles//hello/tag").unwrap_err();
+    assert!(err.attempted_routes[0]
+        .to_string()
+        .ends_with("Dynamic segment '(section)' is empty"));
+
+    // The tag allows empty values
+    let untagged = SharedArticleRoute {
+        section: "intro".into(),
+        slug: "hello".into(),
+        tag: "".into(),
+    };
+    assert_eq!(untagged.to_string(), "/articles/intro/hello/");
+    assert_eq!(
+        SharedArticleRoute::from_segments(["articles", "intro", "hello", ""].into_iter()),
+        Ok(untagged)
+    );
+}
+
+#[test]
+fn segment_suffixes() {
+    assert_eq!(AssetRoute::from_str("/feed.xml"), Ok(AssetRoute::Feed {}));
+    assert_eq!(
+        AssetRoute::from_str("/avatar/42.png"),
+        Ok(AssetRoute::Avatar { user_id: 42 })
+    );
+    assert_eq!(
+        AssetRoute::Avatar { user_id: 42 }.to_string(),
+        "/avatar/42.png"
+    );
+    // The suffixed route is tried first even though it is declared after the plain dynamic route
+    assert_eq!(
+        AssetRoute::ROUTE_PATTERNS,
+        &["/feed.xml", "/avatar/(user_id).png", "/avatar/(file)"]
+    );
+    assert_eq!(
+        AssetRoute::from_str("/avatar/me.jpg"),
+        Ok(AssetRoute::AvatarFile {
+            file: "me.jpg".to_string()
+        })
+    );
+    // A prefix that doesn't parse falls through to the sibling route
+    assert_eq!(
+        AssetRoute::from_str("/avatar/me.png"),
+        Ok(AssetRoute::AvatarFile {
+            file: "me.png".to_string()
+        })
+    );
+    assert_eq!(
+        AssetRoute::match_pattern("/avatar/me.png"),
+        Some("/avatar/(user_id).png")
+    );
+    assert_eq!(
+        AssetRoute::match_pattern("/avatar/me.jpg"),
+        Some("/avatar/(file)")
+    );
+
+    // The suffix is checked after percent-decoding
+    assert_eq!(
+        AssetRoute::from_str("/avatar/42%2Epng"),
+        Ok(AssetRoute::Avatar { user_id: 42 })
+    );
+    assert_eq!(
+        AssetRoute::match_pattern("/avatar/42%2Epng"),
+        Some("/avatar/(user_id).png")
+    );
+
+    // An empty prefix isn't a value for the suffixed segment
+    assert_eq!(
+        AssetRoute::from_str("/avatar/.png"),
+        Ok(AssetRoute::AvatarFile {
+            file: ".png".to_string()
+        })
+    );
+}
+
+#[test]
+fn lenient_parsing() {
+    assert_eq!(
+        ReportRoute::from_str_lenient("/report/2024/5"),
+        Ok((
+            ReportRoute {
+                year: 2024,
+                month: 5
+            },
+            vec![]
+        ))
+    );
+    assert_eq!(
+        ReportRoute::from_str_lenient("/report/2024/"),
+        Ok((
+            ReportRoute {
+                year: 2024,
+                month: 0
+            },
+            vec![FilledSegment {
+                name: "month",
+                index: 2
+            }]
+        ))
+    );
+    assert!(ReportRoute::from_str("/report/2024").is_err());
+    assert!(ReportRoute::from_str_lenient("/report/june").is_err());
+    assert!(ReportRoute::from_str_lenient("/reports/2024").is_err());
+}
+
+#[test]
+fn versioned_routes() {
+    let route = SettingsRoute {
+        tab: "profile".to_string(),
+    };
+    assert_eq!(route.to_versioned_string(), "v2:/settings/profile");
+    assert_eq!(
+        SettingsRoute::from_versioned_str(&route.to_versioned_string()),
+        Ok(route.clone())
+    );
+    assert_eq!(
+        SettingsRoute::from_versioned_str("v1:/preferences/profile"),
+        Ok(route.clone())
+    );
+    assert_eq!(
+        SettingsRoute::from_versioned_str("/settings/profile"),
+        Ok(route)
+    );
+    assert!(SettingsRoute::from_versioned_str("v3:/settings/profile").is_err());
+}
+
+#[test]
+fn replace_skips_history_entry() {
+    let mut router =
+        Router::<Route>::new(dioxus_router_core::history::MemoryHistory::default()).unwrap();
+    assert_eq!(router.route, Route::Route5 {});
+
+    router.push(Route::Route3 { dynamic: 1 });
+    router.replace(Route::Route3 { dynamic: 2 });
+    router.push(Route::Route2 {});
+
+    router.go_back().unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 2 });
+    router.go_back().unwrap();
+    assert_eq!(router.route, Route::Route5 {});
+
+    router.go_forward().unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 2 });
+}
+
+#[test]
+fn login_redirect_flow() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let history = MemoryHistory::with_initial_path("/search/rust/2/stars?debug=1").unwrap();
+    let mut router = Router::<Route>::new(history).unwrap();
+
+    // `/hello_world` stands in for the login page
+    router.push_with_return(Route::Route2 {}, "next");
+    assert_eq!(
+        &*router.current_url(),
+        "/hello_world?next=/search/rust/2/stars?debug%3D1"
+    );
+
+    router.pop_return("next", Route::Route5 {});
+    assert_eq!(
+        router.route,
+        Route::Search {
+            query: "rust".to_string(),
+            page: 2,
+            sort: "stars".to_string()
+        }
+    );
+    assert_eq!(&*router.current_url(), "/search/rust/2/stars?debug=1");
+
+    for next in [
+        "https%3A%2F%2Fevil.example",
+        "//evil.example",
+        "/%5Cevil.example",
+        "/1/2/3",
+    ] {
+        let history =
+            MemoryHistory::with_initial_path(format!("/hello_world?next={next}")).unwrap();
+        let mut router = Router::<Route>::new(history).unwrap();
+        router.pop_return("next", Route::Route5 {});
+        assert_eq!(router.route, Route::Route5 {}, "{next}");
+        assert_eq!(&*router.current_url(), "/", "{next}");
+    }
+}
+
+#[test]
+fn canonicalize_location() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let config = || RouterConfiguration {
+        canonicalize: true,
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/hello_world/1234/").unwrap();
+    let mut router = Router::<Route>::with_configuration(history, config()).unwrap();
+    assert_eq!(router.history.current_path(), "/hello_world/1234");
+
+    router.history.push("/%68ello/".to_string());
+    router.sync_route().unwrap();
+    assert_eq!(router.history.current_path(), "/hello");
+    router.go_back().unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 1234 });
+
+    let history = MemoryHistory::with_initial_path("/hello/").unwrap();
+    let router = Router::<Route>::new(history).unwrap();
+    assert_eq!(router.history.current_path(), "/hello/");
+}
+
+/// Pages whose static segments are matched case-insensitively, so `/About/` is the about page
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(case_insensitive)]
+enum CaseRoute {
+    #[route("/" CaseHome)]
+    Home {},
+    #[route("/about" CaseAbout)]
+    About {},
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn CaseHome(cx: Scope) -> Element {
+    render! { "home" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn CaseAbout(cx: Scope) -> Element {
+    render! { "about" }
+}
+
+/// A `MemoryHistory` that counts how often its location is replaced
+#[cfg(test)]
+struct CountingHistory {
+    inner: dioxus_router_core::history::MemoryHistory,
+    replaces: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[cfg(test)]
+impl HistoryProvider for CountingHistory {
+    fn current_path(&self) -> String {
+        self.inner.current_path()
+    }
+
+    fn current_query(&self) -> Option<String> {
+        self.inner.current_query()
+    }
+
+    fn can_go_back(&self) -> bool {
+        self.inner.can_go_back()
+    }
+
+    fn go_back(&mut self) {
+        self.inner.go_back()
+    }
+
+    fn can_go_forward(&self) -> bool {
+        self.inner.can_go_forward()
+    }
+
+    fn go_forward(&mut self) {
+        self.inner.go_forward()
+    }
+
+    fn push(&mut self, path: String) {
+        self.inner.push(path)
+    }
+
+    fn replace(&mut self, path: String) {
+        self.replaces
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.inner.replace(path)
+    }
+
+    fn updater(&mut self, callback: std::sync::Arc<dyn Fn() + Send + Sync>) {
+        self.inner.updater(callback)
+    }
+}
+
+#[test]
+fn canonicalize_case_insensitive_location() {
+    use dioxus_router_core::history::MemoryHistory;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let replaces = Arc::new(AtomicUsize::new(0));
+    let history = CountingHistory {
+        inner: MemoryHistory::with_initial_path("/About/").unwrap(),
+        replaces: replaces.clone(),
+    };
+    let config = RouterConfiguration {
+        canonicalize: true,
+        ..Default::default()
+    };
+    let mut router = Router::<CaseRoute>::with_configuration(history, config).unwrap();
+    assert_eq!(router.route, CaseRoute::About {});
+    assert_eq!(router.history.current_path(), "/about");
+    assert_eq!(replaces.load(Ordering::Relaxed), 1);
+
+    // A location that is already canonical is left as it is
+    router.history.push("/about".to_string());
+    router.sync_route().unwrap();
+    assert_eq!(replaces.load(Ordering::Relaxed), 1);
+}
+
+/// Posts whose category is matched case-insensitively, and whose id is at least 1
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum NormalizedRoute {
+    #[route("/" NormalizedHome)]
+    Home {},
+    #[route("/(category)/(id)" CategoryPost, normalize = normalize_post)]
+    Post { category: String, id: u32 },
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn NormalizedHome(cx: Scope) -> Element {
+    render! { "home" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn CategoryPost(cx: Scope, category: String, id: u32) -> Element {
+    render! { "{category} {id}" }
+}
+
+#[cfg(test)]
+fn normalize_post(category: &mut String, id: &mut u32) {
+    category.make_ascii_lowercase();
+    *id = (*id).max(1);
+}
+
+#[test]
+fn normalized_routes() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let post = NormalizedRoute::from_str("/Blog/42").unwrap();
+    assert_eq!(post, NormalizedRoute::from_str("/blog/42").unwrap());
+    assert_eq!(post.to_string(), "/blog/42");
+    assert_eq!(
+        NormalizedRoute::from_segments(["News", "0"].into_iter())
+            .unwrap()
+            .to_string(),
+        "/news/1"
+    );
+
+    // Canonicalization replaces the location with the normalized route
+    let config = RouterConfiguration {
+        canonicalize: true,
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/Blog/42").unwrap();
+    let router = Router::<NormalizedRoute>::with_configuration(history, config).unwrap();
+    assert_eq!(router.route, post);
+    assert_eq!(router.history.current_path(), "/blog/42");
+}
+
+#[test]
+fn unknown_query() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let config = |strip_unknown_query| RouterConfiguration {
+        canonicalize: true,
+        strip_unknown_query,
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/Blog/42/?utm_source=x&gclid=1").unwrap();
+    let mut router = Router::<NormalizedRoute>::with_configuration(history, config(false)).unwrap();
+    assert_eq!(&*router.read_url(), "/blog/42?gclid=1&utm_source=x");
+
+    // The same parameters in any order make the same url, every time
+    for query in ["b=2&a=1&a=0", "a=1&b=2&a=0", "a=1&a=0&b=2"] {
+        router.history.push(format!("/blog/42?{query}"));
+        router.sync_route().unwrap();
+        assert_eq!(&*router.read_url(), "/blog/42?a=1&a=0&b=2");
+    }
+
+    let history = MemoryHistory::with_initial_path("/blog/42?utm_source=x").unwrap();
+    let router = Router::<NormalizedRoute>::with_configuration(history, config(true)).unwrap();
+    assert_eq!(&*router.read_url(), "/blog/42");
+
+    // Actions redirect to the page they stand in for, so they keep the query of their location
+    let history = MemoryHistory::with_initial_path("/v1?utm_source=x").unwrap();
+    let router = Router::<MovedRoute>::with_configuration(history, config(false)).unwrap();
+    assert_eq!(router.route, MovedRoute::Home {});
+    assert_eq!(&*router.read_url(), "/?utm_source=x");
+
+    let history = MemoryHistory::with_initial_path("/v1?utm_source=x").unwrap();
+    let router = Router::<MovedRoute>::with_configuration(history, config(true)).unwrap();
+    assert_eq!(&*router.read_url(), "/");
+}
+
+#[test]
+fn link_hrefs() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let history = MemoryHistory::with_initial_path("/hello_world/70?tab=1").unwrap();
+    let router = Router::<Route>::new(history).unwrap();
+    assert_eq!(router.href("/hello"), "/hello");
+    assert_eq!(router.href("#pricing"), "/hello_world/70?tab=1#pricing");
+
+    let config = RouterConfiguration {
+        base_url: BaseUrl::parse("https://example.com/app"),
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/hello_world/70").unwrap();
+    let router = Router::<Route>::with_configuration(history, config).unwrap();
+    assert_eq!(router.href("/hello"), "https://example.com/app/hello");
+    assert_eq!(
+        router.href("#pricing"),
+        "https://example.com/app/hello_world/70#pricing"
+    );
+}
+
+#[test]
+fn static_route_paths() {
+    // The paths are constants, so they work where a `const` is required
+    const HOME: &str = route_paths::ROUTE5_PATH;
+    assert_eq!(HOME, Route::Route5 {}.to_string());
+    assert_eq!(route_paths::ROUTE2_PATH, Route::Route2 {}.to_string());
+    assert_eq!(route_paths::LOGOUT_PATH, "/logout");
+    assert_eq!(asset_route_paths::FEED_PATH, "/feed.xml");
+}
+
+#[test]
+fn route_table_export() {
+    // The baseline is an export from before `/rss.xml` moved to `/feed.xml`
+    let baseline = include_str!("routes_baseline.json");
+    assert!(baseline.contains("\"/rss.xml\""));
+    assert!(!AssetRoute::ROUTE_TABLE_JSON.contains("\"/rss.xml\""));
+    assert!(AssetRoute::ROUTE_TABLE_JSON.contains(
+        r#"{"variant": "Avatar", "pattern": "/avatar/(user_id).png", "segments": [{"name": "user_id", "type": "u32"}]}"#
+    ));
+}
+
+#[test]
+fn status_hints() {
+    assert_eq!(Route::Gone { id: 1 }.status_hint(), 410);
+    assert_eq!(Route::Route5 {}.status_hint(), 200);
+    assert_eq!(
+        Route::Gone { id: 1 }.cache_hint(),
+        Some("public, max-age=3600")
+    );
+    assert_eq!(Route::Route5 {}.cache_hint(), None);
+}
+
+#[test]
+fn action_routes() {
+    let mut router =
+        Router::<Route>::new(dioxus_router_core::history::MemoryHistory::default()).unwrap();
+    router.push(Route::Route2 {});
+    router.push(Route::Logout {});
+    assert_eq!(router.route, Route::Route5 {});
+    assert_eq!(router.history.current_path(), "/");
+    assert_eq!(router.previous(), Some(&Route::Route2 {}));
+    assert_eq!(Route::ACTION_ROUTE_PATTERNS, &["/logout"]);
+}
+
+/// Two actions that redirect to each other
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum LoopRoute {
+    #[route("/" LoopHome)]
+    Home {},
+    #[route("/ping", action = to_pong)]
+    Ping {},
+    #[route("/pong", action = to_ping)]
+    Pong {},
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn LoopHome(cx: Scope) -> Element {
+    render! { "home" }
+}
+
+#[cfg(test)]
+fn to_ping(router: &mut Router<LoopRoute>) {
+    router.replace(LoopRoute::Ping {});
+}
+
+#[cfg(test)]
+fn to_pong(router: &mut Router<LoopRoute>) {
+    router.replace(LoopRoute::Pong {});
+}
+
+/// Pages that moved twice, and a logout that isn't safe to run in a dry run
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum MovedRoute {
+    #[route("/" MovedHome)]
+    Home {},
+    #[route("/v1", action = v1_to_v2, dry_run_safe = true)]
+    V1 {},
+    #[route("/v2", action = v2_to_home, dry_run_safe = true)]
+    V2 {},
+    #[route("/logout", action = moved_logout)]
+    Logout {},
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn MovedHome(cx: Scope) -> Element {
+    render! { "home" }
+}
+
+#[cfg(test)]
+fn v1_to_v2(router: &mut Router<MovedRoute>) {
+    router.replace(MovedRoute::V2 {});
+}
+
+#[cfg(test)]
+fn v2_to_home(router: &mut Router<MovedRoute>) {
+    router.replace(MovedRoute::Home {});
+}
+
+#[cfg(test)]
+fn moved_logout(router: &mut Router<MovedRoute>) {
+    router.replace(MovedRoute::Home {});
+}
+
+/// Items can be linked by their numeric id or by their slug
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(strict_display, link_macro)]
+enum ItemRoute {
+    // Declared first, but catch-alls are tried after the other routes at their depth
+    #[route("/items/(...rest)" ItemFiles)]
+    ItemFiles { rest: Vec<String> },
+    #[route("/items/(id)" ItemById)]
+    ItemById { id: u64 },
+    #[route("/items/(slug)" ItemBySlug)]
+    ItemBySlug { slug: String },
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ItemFiles(cx: Scope, rest: Vec<String>) -> Element {
+    render! { "Item files {rest:?}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ItemById(cx: Scope, id: u64) -> Element {
+    render! { "Item {id}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ItemBySlug(cx: Scope, slug: String) -> Element {
+    render! { "Item {slug}" }
+}
+
+#[test]
+fn dynamic_siblings_backtrack() {
+    assert_eq!(
+        ItemRoute::from_str("/items/12"),
+        Ok(ItemRoute::ItemById { id: 12 })
+    );
+    // `u64` fails to parse, so the parser falls through to the slug route
+    assert_eq!(
+        ItemRoute::from_str("/items/router"),
+        Ok(ItemRoute::ItemBySlug {
+            slug: "router".to_string()
+        })
+    );
+    assert_eq!(
+        ItemRoute::from_str("/items/12/notes.txt"),
+        Ok(ItemRoute::ItemFiles {
+            rest: vec!["12".to_string(), "notes.txt".to_string()]
+        })
+    );
+    assert_eq!(
+        ItemRoute::ROUTE_PATTERNS,
+        ["/items/(id)", "/items/(slug)", "/items/(...rest)"]
+    );
+
+    // A route that accepts every value shadows the routes declared after it
+    #[derive(Routable, Clone, Debug, PartialEq)]
+    enum SlugFirst {
+        #[route("/items/(slug)" SlugFirstSlug)]
+        Slug { slug: String },
+        #[route("/items/(id)" SlugFirstId)]
+        Id { id: u64 },
+    }
+
+    #[inline_props]
+    #[allow(non_snake_case)]
+    fn SlugFirstSlug(cx: Scope, slug: String) -> Element {
+        render! { "{slug}" }
+    }
+
+    #[inline_props]
+    #[allow(non_snake_case)]
+    fn SlugFirstId(cx: Scope, id: u64) -> Element {
+        render! { "{id}" }
+    }
+
+    assert_eq!(
+        SlugFirst::from_str("/items/12"),
+        Ok(SlugFirst::Slug {
+            slug: "12".to_string()
+        })
+    );
+}
+
+#[test]
+fn literal_links() {
+    // Checked while building: `link!(ItemRoute, "/users/12")` would fail to compile
+    assert_eq!(
+        link!(ItemRoute, "/items/12"),
+        ItemRoute::ItemById { id: 12 }
+    );
+    assert_eq!(
+        link!(ItemRoute, "/items/router"),
+        ItemRoute::ItemBySlug {
+            slug: "router".to_string()
+        }
+    );
+    // Catch-alls are parsed when the program runs
+    assert_eq!(
+        link!(ItemRoute, "/items/12/notes.txt"),
+        ItemRoute::ItemFiles {
+            rest: vec!["12".to_string(), "notes.txt".to_string()]
+        }
+    );
+}
+
+#[test]
+fn strict_display() {
+    assert_eq!(
+        ItemRoute::ItemBySlug {
+            slug: "router".to_string()
+        }
+        .try_to_path(),
+        Ok("/items/router".to_string())
+    );
+    // A numeric slug displays as the path of the id route
+    assert_eq!(
+        ItemRoute::ItemBySlug {
+            slug: "12".to_string()
+        }
+        .try_to_path(),
+        Err(DisplayError::NoRoundTrip {
+            path: "/items/12".to_string(),
+            pattern: "/items/(slug)",
+        })
+    );
+    assert_eq!(
+        ItemRoute::ItemBySlug {
+            slug: String::new()
+        }
+        .try_to_path(),
+        Err(DisplayError::EmptySegment("slug"))
+    );
+    assert_eq!(
+        ItemRoute::ItemFiles { rest: Vec::new() }.try_to_path(),
+        Ok("/items".to_string())
+    );
+}
+
+#[test]
+fn runtime_matcher_parity() {
+    use pattern::{ParamValue, RoutePattern};
+
+    fn first_match(patterns: &[&'static str], path: &str) -> Option<&'static str> {
+        patterns.iter().copied().find(|pattern| {
+            RoutePattern::parse(pattern)
+                .unwrap()
+                .matches(path)
+                .is_some()
+        })
+    }
 
-#[derive(Debug, PartialEq)]
-struct RouteParseError<E: std::fmt::Display> {
-    attempted_routes: Vec<E>,
-}
+    for path in [
+        "/items/12",
+        "/items/router/",
+        "/items",
+        "/items/12/notes.txt",
+        "/items/a%20b",
+        "/other",
+    ] {
+        assert_eq!(
+            first_match(ItemRoute::ROUTE_PATTERNS, path),
+            ItemRoute::match_pattern(path),
+            "{path}"
+        );
+    }
+    for path in [
+        "/feed.xml",
+        "/avatar/12.png",
+        "/avatar/12",
+        "/avatar",
+        "/avatar/12.png/more",
+    ] {
+        assert_eq!(
+            first_match(AssetRoute::ROUTE_PATTERNS, path),
+            AssetRoute::match_pattern(path),
+            "{path}"
+        );
+    }
 
-impl<E: std::fmt::Display> std::fmt::Display for RouteParseError<E> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Route did not match:\nAttempted Matches:\n")?;
-        for (i, route) in self.attempted_routes.iter().enumerate() {
-            writeln!(f, "{}) {route}", i + 1)?;
-        }
-        Ok(())
+    // The values of the segments are the ones the derived parser sees
+    let path = "/items/router%20experiment";
+    let params = RoutePattern::parse("/items/(slug)")
+        .unwrap()
+        .matches(path)
+        .unwrap();
+    let route = ItemRoute::from_str(path).unwrap();
+    for (name, value) in route.matched_params() {
+        assert_eq!(params.get(name), Some(&ParamValue::Segment(value)));
     }
 }
 
-struct Router<R: Routable, H: HistoryProvider>
-where
-    <R as FromStr>::Err: std::fmt::Display,
-{
-    history: H,
-    route: R,
+/// Requests under `/api` are forwarded to the backend with their query
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum ProxyRoute {
+    #[route("/" ProxyHome)]
+    ProxyHome {},
+    #[route("/api/(...rest)" ApiProxy)]
+    Api {
+        #[route_segment(include_query)]
+        rest: String,
+    },
 }
 
-impl<R: Routable, H: HistoryProvider> Router<R, H>
-where
-    <R as FromStr>::Err: std::fmt::Display,
-{
-    fn new(history: H) -> Result<Self, R::Err> {
-        let path = history.current_path();
-        Ok(Self {
-            history,
-            route: R::from_str(path.as_str())?,
-        })
-    }
+#[inline_props]
+#[allow(non_snake_case)]
+fn ProxyHome(cx: Scope) -> Element {
+    render! { "home" }
 }
 
-#[derive(Props, PartialEq)]
-struct RouterProps {
-    current_route: String,
+#[inline_props]
+#[allow(non_snake_case)]
+fn ApiProxy(cx: Scope, rest: String) -> Element {
+    render! { "{rest}" }
 }
 
-trait Routable: FromStr + std::fmt::Display + Clone
-where
-    <Self as FromStr>::Err: std::fmt::Display,
-{
-    fn render(self, cx: &ScopeState) -> Element;
+#[test]
+fn catch_all_includes_query() {
+    for (path, rest) in [
+        ("/api/users?id=1&sort=name", "users?id=1&sort=name"),
+        (
+            "/api/users/7?filter=a%3Db%26c&empty=",
+            "users/7?filter=a%3Db%26c&empty=",
+        ),
+        ("/api/search?q=%E2%9C%93+ok", "search?q=%E2%9C%93+ok"),
+        ("/api?token=a=b", "?token=a=b"),
+        ("/api/users", "users"),
+        ("/api", ""),
+    ] {
+        let route = ProxyRoute::from_str(path).unwrap();
+        assert_eq!(
+            route,
+            ProxyRoute::Api {
+                rest: rest.to_string()
+            },
+            "{path}"
+        );
+        assert_eq!(route.to_string(), path);
+    }
 
-    fn comp(cx: Scope<RouterProps>) -> Element
-    where
-        Self: 'static,
-    {
-        let router = Self::from_str(&cx.props.current_route);
-        match router {
-            Ok(router) => router.render(cx),
-            Err(err) => {
-                render! {pre {
-                    "{err}"
-                }}
-            }
+    // Other routes of the enum ignore the query
+    assert_eq!(
+        ProxyRoute::from_str("/?ref=mail"),
+        Ok(ProxyRoute::ProxyHome {})
+    );
+
+    use dioxus_router_core::history::MemoryHistory;
+    let history = MemoryHistory::with_initial_path("/api/users?id=1").unwrap();
+    let config = RouterConfiguration {
+        canonicalize: true,
+        ..Default::default()
+    };
+    let router = Router::<ProxyRoute>::with_configuration(history, config).unwrap();
+    assert_eq!(
+        router.route,
+        ProxyRoute::Api {
+            rest: "users?id=1".to_string()
         }
-    }
+    );
+    assert_eq!(router.current_url().as_ref(), "/api/users?id=1");
 }
 
+/// The blog moved from `/posts` to `/blog`, and the old links still work
 #[derive(Routable, Clone, Debug, PartialEq)]
-enum Route {
-    #[route("/(dynamic)")]
-    Route1 { dynamic: String },
-    #[route("/hello_world")]
-    Route2 {},
-    #[route("/hello_world/(dynamic)")]
-    Route3 { dynamic: u32 },
-    #[route("/(number1)/(number2)")]
-    Route4 { number1: u32, number2: u32 },
-    #[route("/")]
-    Route5 {},
+enum ArticleRoute {
+    #[route("/" ArticleHome)]
+    ArticleHome {},
+    #[route("/blog/(id)" Article)]
+    #[route("/posts/(id)")]
+    Article { id: u32 },
+    #[route("/users/(user)/articles/(slug)" UserArticle)]
+    #[route("/(slug)/by/(user)")]
+    UserArticle { user: String, slug: String },
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ArticleHome(cx: Scope) -> Element {
+    render! { "Articles" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Article(cx: Scope, id: u32) -> Element {
+    render! { "Article {id}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn UserArticle(cx: Scope, user: String, slug: String) -> Element {
+    render! { "{slug} by {user}" }
 }
 
 #[test]
-fn display_works() {
-    let route = Route::Route1 {
-        dynamic: "hello".to_string(),
+fn route_aliases() {
+    let article = ArticleRoute::Article { id: 7 };
+    assert_eq!(ArticleRoute::from_str("/blog/7"), Ok(article.clone()));
+    assert_eq!(ArticleRoute::from_str("/posts/7"), Ok(article.clone()));
+    // Aliases display with the route's own pattern
+    assert_eq!(article.to_string(), "/blog/7");
+    assert_eq!(article.pattern(), "/blog/(id)");
+
+    // The segments of an alias bind their fields by name, in any order
+    let user_article = ArticleRoute::UserArticle {
+        user: "ada".to_string(),
+        slug: "engines".to_string(),
     };
+    assert_eq!(
+        ArticleRoute::from_str("/engines/by/ada"),
+        Ok(user_article.clone())
+    );
+    assert_eq!(user_article.to_string(), "/users/ada/articles/engines");
 
-    assert_eq!(route.to_string(), "/hello");
+    let err = ArticleRoute::from_str("/posts/seven").unwrap_err();
+    assert!(err
+        .attempted_routes
+        .contains(&ArticleRouteMatchError::Article {
+            error: ArticleParseError::idParseError(SegmentError::ParseError(
+                "seven".parse::<u32>().unwrap_err()
+            )),
+            span: 7..12,
+        }));
+}
 
-    let route = Route::Route3 { dynamic: 1234 };
+/// A manual served from directories, where every page ends with a slash and `/Guide/` is `/guide/`
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(trailing_slash = "require", case_insensitive)]
+enum ManualRoute {
+    #[route("/" ManualIndex)]
+    ManualIndex {},
+    #[route("/guide/(page)" ManualPage)]
+    ManualPage { page: String },
+}
 
-    assert_eq!(route.to_string(), "/hello_world/1234");
+#[inline_props]
+#[allow(non_snake_case)]
+fn ManualIndex(cx: Scope) -> Element {
+    render! { "Manual" }
+}
 
-    let route = Route::Route1 {
-        dynamic: "hello_world2".to_string(),
+#[inline_props]
+#[allow(non_snake_case)]
+fn ManualPage(cx: Scope, page: String) -> Element {
+    render! { "Guide: {page}" }
+}
+
+#[test]
+fn path_options() {
+    let page = ManualRoute::ManualPage {
+        page: "Setup".to_string(),
     };
+    assert_eq!(page.to_string(), "/guide/Setup/");
+    assert_eq!(ManualRoute::from_str("/guide/Setup/"), Ok(page.clone()));
+    // Only static segments ignore case, the page keeps its own
+    assert_eq!(ManualRoute::from_str("/GUIDE/Setup/"), Ok(page));
+    assert_eq!(ManualRoute::ManualIndex {}.to_string(), "/");
+    assert_eq!(ManualRoute::from_str("/"), Ok(ManualRoute::ManualIndex {}));
 
-    assert_eq!(route.to_string(), "/hello_world2");
+    assert_eq!(
+        ManualRoute::from_str("/guide/Setup").unwrap_err().limit,
+        Some(ParseLimit::MissingTrailingSlash)
+    );
+    assert_eq!(
+        ManualRoute::match_pattern("/guide/Setup/"),
+        Some("/guide/(page)")
+    );
+    assert_eq!(ManualRoute::match_pattern("/guide/Setup"), None);
+}
+
+/// A shop whose variants hold their segments by position
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum ShopRoute {
+    #[route("/" ShopHome)]
+    ShopHome(),
+    #[route("/products/(0)" Product)]
+    Product(u32),
+    #[route("/reviews/(1)/(0)" Review)]
+    Review(String, u32),
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ShopHome(cx: Scope) -> Element {
+    render! { "Shop" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Product(cx: Scope, field_0: u32) -> Element {
+    render! { "Product {field_0}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Review(cx: Scope, field_1: u32, field_0: String) -> Element {
+    render! { "Review {field_0} of product {field_1}" }
 }
 
 #[test]
-fn from_string_works() {
-    let w = "/hello";
+fn tuple_routes() {
+    assert_eq!(ShopRoute::Product(7).to_string(), "/products/7");
     assert_eq!(
-        Route::from_str(w),
-        Ok(Route::Route1 {
-            dynamic: "hello".to_string()
-        })
+        ShopRoute::from_str("/products/7"),
+        Ok(ShopRoute::Product(7))
     );
-    let w = "/hello/";
+    let review = ShopRoute::Review("great".to_string(), 7);
+    assert_eq!(review.to_string(), "/reviews/7/great");
+    assert_eq!(ShopRoute::from_str("/reviews/7/great"), Ok(review));
+    assert_eq!(ShopRoute::from_str("/"), Ok(ShopRoute::ShopHome()));
     assert_eq!(
-        Route::from_str(w),
-        Ok(Route::Route1 {
-            dynamic: "hello".to_string()
-        })
+        ShopRoute::SITE_MAP[1].segments[1],
+        SegmentInfo::Dynamic("field_0", "u32")
     );
+}
 
-    let w = "/hello_world/1234";
-    assert_eq!(Route::from_str(w), Ok(Route::Route3 { dynamic: 1234 }));
-    let w = "/hello_world/1234/";
-    assert_eq!(Route::from_str(w), Ok(Route::Route3 { dynamic: 1234 }));
+/// A forum whose listings can leave out their last segment
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum ForumRoute {
+    #[route("/" ForumHome)]
+    ForumHome {},
+    #[route("/threads/(?page)" Threads)]
+    Threads { page: Option<u32> },
+    #[route("/boards/(board)/(?sort)" Board)]
+    Board { board: String, sort: String },
+}
 
-    let w = "/hello_world2";
+#[inline_props]
+#[allow(non_snake_case)]
+fn ForumHome(cx: Scope) -> Element {
+    render! { "Forum" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Threads(cx: Scope, page: Option<u32>) -> Element {
+    let page = page.unwrap_or(1);
+    render! { "Threads, page {page}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn Board(cx: Scope, board: String, sort: String) -> Element {
+    render! { "Board {board} sorted by {sort}" }
+}
+
+#[test]
+fn optional_segments() {
+    let first = ForumRoute::Threads { page: None };
+    assert_eq!(first.to_string(), "/threads");
+    assert_eq!(ForumRoute::from_str("/threads"), Ok(first.clone()));
+    assert_eq!(ForumRoute::from_str("/threads/"), Ok(first));
+    let second = ForumRoute::Threads { page: Some(2) };
+    assert_eq!(second.to_string(), "/threads/2");
+    assert_eq!(ForumRoute::from_str("/threads/2"), Ok(second));
+    assert!(ForumRoute::from_str("/threads/two").is_err());
+    assert!(ForumRoute::from_str("/threads/2/3").is_err());
+
+    let board = ForumRoute::Board {
+        board: "rust".to_string(),
+        sort: String::new(),
+    };
+    assert_eq!(board.to_string(), "/boards/rust");
+    assert_eq!(ForumRoute::from_str("/boards/rust"), Ok(board));
     assert_eq!(
-        Route::from_str(w),
-        Ok(Route::Route1 {
-            dynamic: "hello_world2".to_string()
+        ForumRoute::from_str("/boards/rust/new"),
+        Ok(ForumRoute::Board {
+            board: "rust".to_string(),
+            sort: "new".to_string(),
         })
     );
+    assert_eq!(
+        ForumRoute::match_pattern("/threads"),
+        Some("/threads/(?page)")
+    );
+}
 
-    let w = "/hello_world/-1";
-    match Route::from_str(w) {
-        Ok(r) => panic!("should not parse {r:?}"),
-        Err(err) => println!("{err}"),
+/// The pages of a workspace. Every route starts with the id of the workspace
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(shared = "/(workspace)")]
+enum WorkspaceRoute {
+    #[route("/" WorkspaceHome)]
+    Overview { workspace: u32 },
+    #[route("/projects/(project)" WorkspaceProject)]
+    Project { workspace: u32, project: String },
+}
+
+/// A versioned API. The version is glued to a `v` and checked before the rest of the path is parsed
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(shared = "/v(version)", shared_guard = check_api_version)]
+enum ApiRoute {
+    #[route("/users/(id)" ApiUser)]
+    User { version: u8, id: u32 },
+    #[route("/unsupported" ApiUnsupported)]
+    Unsupported { version: u8 },
+}
+
+fn check_api_version(version: &u8) -> Result<(), String> {
+    match version {
+        1 | 2 => Ok(()),
+        _ => Err(format!("API version {version} is not supported")),
     }
 }
 
+#[inline_props]
+#[allow(non_snake_case)]
+fn ApiUser(cx: Scope, version: u8, id: u32) -> Element {
+    render! { "User {id} of API v{version}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn ApiUnsupported(cx: Scope, version: u8) -> Element {
+    render! { "Upgrade to API v{version}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn WorkspaceHome(cx: Scope, workspace: u32) -> Element {
+    render! { "Workspace {workspace}" }
+}
+
+#[inline_props]
+#[allow(non_snake_case)]
+fn WorkspaceProject(cx: Scope, workspace: u32, project: String) -> Element {
+    render! { "Project {project} in workspace {workspace}" }
+}
+
 #[test]
-fn round_trip() {
-    // Route1
-    let string = "hello_world2";
-    let route = Route::Route1 {
-        dynamic: string.to_string(),
+fn shared_guard() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let user = ApiRoute::User { version: 2, id: 7 };
+    assert_eq!(user.to_string(), "/v2/users/7");
+    assert_eq!(ApiRoute::from_str("/v2/users/7"), Ok(user));
+
+    // The guard rejects the version before any route is tried
+    for path in ["/v9/users/7", "/v9/nope"] {
+        let err = ApiRoute::from_str(path).unwrap_err();
+        assert_eq!(
+            err.limit,
+            Some(ParseLimit::SharedGuard {
+                reason: "API version 9 is not supported".to_string()
+            })
+        );
+        assert!(err.attempted_routes.is_empty());
+    }
+    // Paths without a version are left to the routes, which report the missing prefix
+    let err = ApiRoute::from_str("/2/users/7").unwrap_err();
+    assert_eq!(err.limit, None);
+    assert!(
+        err.to_string().contains("Segment does not start with 'v'"),
+        "{err}"
+    );
+
+    // The rejection is a failure like any path that doesn't parse
+    let config = RouterConfiguration {
+        error_route: Some(|failure| match failure {
+            NavigationFailure::NoMatch(err)
+                if matches!(err.limit, Some(ParseLimit::SharedGuard { .. })) =>
+            {
+                ApiRoute::Unsupported { version: 2 }
+            }
+            _ => ApiRoute::User { version: 2, id: 0 },
+        }),
+        ..Default::default()
     };
-    assert_eq!(Route::from_str(&route.to_string()), Ok(route));
+    let history = MemoryHistory::with_initial_path("/v9/users/7").unwrap();
+    let router = Router::<ApiRoute>::with_configuration(history, config).unwrap();
+    assert_eq!(router.route, ApiRoute::Unsupported { version: 2 });
+    assert_eq!(&*router.current_url(), "/v2/unsupported");
+}
 
-    // Route2
-    for num in 0..100 {
-        let route = Route::Route3 { dynamic: num };
-        assert_eq!(Route::from_str(&route.to_string()), Ok(route));
+#[test]
+fn shared_segments() {
+    let project = WorkspaceRoute::Project {
+        workspace: 7,
+        project: "router".to_string(),
+    };
+    assert_eq!(project.to_string(), "/7/projects/router");
+    assert_eq!(
+        WorkspaceRoute::from_str("/7/projects/router"),
+        Ok(project.clone())
+    );
+    assert_eq!(project.workspace(), &7);
+    assert_eq!(project.pattern(), "/(workspace)/projects/(project)");
+
+    let overview = WorkspaceRoute::Overview { workspace: 7 };
+    assert_eq!(overview.to_string(), "/7");
+    assert_eq!(WorkspaceRoute::from_str("/7/"), Ok(overview));
+    assert!(WorkspaceRoute::from_str("/").is_err());
+    assert!(WorkspaceRoute::from_str("/projects/router").is_err());
+}
+
+#[test]
+fn redirect_loops_stop() {
+    let mut router =
+        Router::<LoopRoute>::new(dioxus_router_core::history::MemoryHistory::default()).unwrap();
+    router.push(LoopRoute::Ping {});
+    let chain: Vec<_> = ["/ping", "/pong"].into_iter().cycle().take(9).collect();
+    assert_eq!(
+        router.navigation_error(),
+        Some(&NavigationError::TooManyRedirects { chain })
+    );
+    assert_eq!(router.route, LoopRoute::Ping {});
+
+    // The next navigation starts a new chain
+    router.push(LoopRoute::Home {});
+    assert_eq!(router.navigation_error(), None);
+
+    let config = RouterConfiguration {
+        max_redirects: 2,
+        ..Default::default()
+    };
+    let history = dioxus_router_core::history::MemoryHistory::with_initial_path("/pong").unwrap();
+    let router = Router::<LoopRoute>::with_configuration(history, config).unwrap();
+    assert_eq!(
+        router
+            .navigation_error()
+            .map(ToString::to_string)
+            .as_deref(),
+        Some("Stopped after too many redirects: /pong -> /ping -> /pong")
+    );
+}
+
+#[test]
+fn current_url() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let config = RouterConfiguration {
+        canonicalize: true,
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/hello/?utm_source=mail").unwrap();
+    let mut router = Router::<Route>::with_configuration(history, config).unwrap();
+    assert_eq!(&*router.current_url(), "/hello/?utm_source=mail");
+    assert_eq!(router.route.to_string(), "/hello");
+
+    router.push(Route::Route2 {});
+    assert_eq!(&*router.current_url(), "/hello_world");
+}
+
+#[test]
+fn previous_route() {
+    let mut router =
+        Router::<Route>::new(dioxus_router_core::history::MemoryHistory::default()).unwrap();
+    assert_eq!(router.previous(), None);
+
+    router.push(Route::Route2 {});
+    assert_eq!(router.previous(), Some(&Route::Route5 {}));
+
+    // Replacing keeps the route before the replaced one
+    router.replace(Route::Route3 { dynamic: 1 });
+    assert_eq!(router.previous(), Some(&Route::Route5 {}));
+
+    router.go_back().unwrap();
+    assert_eq!(router.previous(), Some(&Route::Route3 { dynamic: 1 }));
+}
+
+#[test]
+fn render_works() {
+    /// Render the body of a route and the tags for its head, like a server would
+    fn render_route_with_head(current_route: &str) -> (String, String) {
+        let head = Route::from_str(current_route)
+            .map(|route| route.head().to_html())
+            .unwrap_or_default();
+        let mut vdom = VirtualDom::new_with_props(
+            Route::comp,
+            RouterProps {
+                current_route: current_route.to_string(),
+                max_suggestions: 3,
+                dynamic_routes: DynamicRoutes::default(),
+                legacy_fallback: None,
+            },
+        );
+        let _ = vdom.rebuild();
+        (head, dioxus_ssr::render(&vdom))
     }
 
-    // Route3
-    for num1 in 0..100 {
-        for num2 in 0..100 {
-            let route = Route::Route4 {
-                number1: num1,
-                number2: num2,
-            };
-            assert_eq!(Route::from_str(&route.to_string()), Ok(route));
+    fn render_route(current_route: &str) -> String {
+        render_route_with_head(current_route).1
+    }
+
+    assert!(render_route("/hello_world/1234").contains("dynamic: 1234"));
+    assert!(render_route("/12/34").contains("number2: 34"));
+    assert!(render_route("/").contains("Route5 {}"));
+
+    let not_found = render_route("/serch/rust/2/stars");
+    let suggestion = "<li><a href=\"/search/rust/2/stars\">/search/rust/2/stars</a></li>";
+    assert!(
+        not_found.contains(&format!("Did you mean:<ul>{suggestion}</ul>")),
+        "{not_found}"
+    );
+    assert!(!render_route("/nothing/like/any/route/at/all").contains("Did you mean"));
+
+    let (head, _) = render_route_with_head("/search/%3Cscript%3E/2/stars");
+    assert_eq!(
+        head,
+        "<title>Results for &lt;script&gt;</title><meta name=\"description\" \
+         content=\"Page 2 of the results for &lt;script&gt;, sorted by stars\">"
+    );
+    assert_eq!(render_route_with_head("/hello_world").0, "");
+}
+
+#[test]
+fn routes_are_attribute_values() {
+    #[allow(non_snake_case)]
+    fn Links(cx: Scope) -> Element {
+        let route = Route::Route3 { dynamic: 1234 };
+        let settings = SettingsRoute {
+            tab: "profile".to_string(),
+        };
+        render! {
+            a { href: &route }
+            a { href: &settings }
         }
     }
+
+    let mut vdom = VirtualDom::new(Links);
+    let _ = vdom.rebuild();
+    assert_eq!(
+        dioxus_ssr::render(&vdom),
+        "<a href=\"/hello_world/1234\"></a><a href=\"/settings/profile\"></a>"
+    );
+}
+
+#[test]
+fn feature_gated_routes() {
+    assert_eq!(
+        Route::gated_routes(),
+        [GatedRoute {
+            pattern: "/debug/routes",
+            name: "RouteTable",
+            feature: "devtools",
+            enabled: cfg!(feature = "devtools"),
+        }]
+    );
+    assert!(AssetRoute::gated_routes().is_empty());
+    // The variant displays in every build, even when its path doesn't parse
+    assert_eq!(Route::RouteTable {}.to_string(), "/debug/routes");
+
+    let parsed = "/debug/routes".parse::<Route>();
+    if cfg!(feature = "devtools") {
+        assert!(Route::disabled_routes().is_empty());
+        assert_eq!(parsed.ok(), Some(Route::RouteTable {}));
+    } else {
+        assert_eq!(Route::disabled_routes(), [&Route::gated_routes()[0]]);
+        assert!(parsed.is_err());
+        assert!(Route::from_pattern_and_params("/debug/routes", &[]).is_err());
+    }
+}
+
+#[test]
+fn segment_encode_sets() {
+    let filters = PartnerRoute::PartnerFilters {
+        filters: "color:red,size:m".to_string(),
+    };
+    assert_eq!(filters.to_string(), "/partner/color:red,size:m");
+    assert_eq!(
+        PartnerRoute::from_str("/partner/color%3Ared,size:m"),
+        Ok(filters)
+    );
+
+    let signed = PartnerRoute::PartnerSigned {
+        token: "a:b,c".to_string(),
+    };
+    assert_eq!(signed.to_string(), "/partner/signed/a%3Ab%2Cc");
+    assert_eq!(PartnerRoute::from_str(&signed.to_string()), Ok(signed));
+}
+
+#[test]
+fn manual_routable() {
+    let post = LegacyRoute::BlogPost {
+        id: 7,
+        slug: "hello world".to_string(),
+    };
+    assert_eq!(post.to_string(), "/blog/7/hello%20world");
+    assert_eq!(
+        LegacyRoute::from_str("/blog/7/hello%20world/"),
+        Ok(post.clone())
+    );
+    assert_eq!(post.pattern(), "/blog/(id)/(slug)");
+    assert_eq!(
+        LegacyRoute::from_pattern_and_params(
+            "/blog/(id)/(slug)",
+            &[("id", "7"), ("slug", "hello world")]
+        ),
+        Ok(post)
+    );
+    assert_eq!(
+        LegacyRoute::from_str("/avatar/3.png"),
+        Ok(LegacyRoute::Assets(AssetRoute::Avatar { user_id: 3 }))
+    );
+    assert_eq!(
+        LegacyRoute::route_patterns()[..3],
+        ["/", "/blog/(id)/(slug)", "/feed.xml"]
+    );
+
+    // Errors are the ones a derived route with the same fields reports
+    let err = blog_post()
+        .parse("/blog/seven/hello")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("Dynamic segment '(id:u64)' did not match"),
+        "{err}"
+    );
+    assert!(blog_post().parse("/blog/7/").is_err());
+    assert_eq!(
+        blog_post().from_params(&[("id", "7")]),
+        Err(BuildError::MissingParam("slug"))
+    );
+
+    let mut vdom = VirtualDom::new_with_props(
+        LegacyRoute::comp,
+        RouterProps {
+            current_route: "/blog/1/first".to_string(),
+            max_suggestions: 3,
+            dynamic_routes: DynamicRoutes::default(),
+            legacy_fallback: None,
+        },
+    );
+    let _ = vdom.rebuild();
+    assert_eq!(dioxus_ssr::render(&vdom), "Post 1: first");
 }
 
 fn root(cx: Scope) -> Element {
@@ -180,6 +4274,18 @@ fn root(cx: Scope) -> Element {
     }
 }
 
+#[cfg(feature = "desktop")]
 fn main() {
     dioxus_desktop::launch(root);
 }
+
+/// Without a window, parse each argument as a route, like a server that only uses the routes to match paths.
+#[cfg(not(feature = "desktop"))]
+fn main() {
+    for path in std::env::args().skip(1) {
+        match Route::from_str(&path) {
+            Ok(route) => println!("{path}: {}", route.pattern()),
+            Err(err) => println!("{path}: {err}"),
+        }
+    }
+}
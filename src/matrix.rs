@@ -0,0 +1,185 @@
+//! Matrix params: `;key=value` pairs at the end of a path segment, like `sort` and `dir` in
+//! `/items;sort=price;dir=asc/42`.
+//!
+//! A pattern declares them with `;(name)` after a segment, like `/items;(params)/(id)` or `/(id);(params)`. The
+//! segment is split at its first `;`: the part before it is matched like any other segment and the rest is
+//! decoded into `(key, value)` pairs for the [`FromMatrix`] of the field. Display writes the segment and then the
+//! [`ToMatrix`] pairs, and nothing after the segment if there are no pairs. Keys and values are written with only
+//! unreserved characters unencoded, so a `;` or `=` in a value is escaped and parses back as part of the value.
+
+#[cfg(test)]
+use super::*;
+use crate::encoding::{
+    decode_path_segment, DecodeError, EncodeSet, EncodedSegmentWith, SegmentError,
+};
+use std::fmt;
+
+/// A type that can be parsed from the matrix params of a segment.
+pub trait FromMatrix: Sized {
+    type Err: fmt::Display;
+
+    /// Parse the decoded params, in the order they appear in the segment
+    fn from_matrix(params: &[(String, String)]) -> Result<Self, Self::Err>;
+}
+
+/// A type that can be written as the matrix params of a segment.
+pub trait ToMatrix {
+    /// The params to write, in order. A param with an empty value is written as its key alone
+    fn to_matrix(&self) -> Vec<(String, String)>;
+}
+
+/// Matrix params as they appear in the path, for routes that read them by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MatrixParams(pub Vec<(String, String)>);
+
+impl MatrixParams {
+    /// The value of the first param with this key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find_map(|(k, value)| (k == key).then_some(value.as_str()))
+    }
+}
+
+impl FromMatrix for MatrixParams {
+    type Err = std::convert::Infallible;
+
+    fn from_matrix(params: &[(String, String)]) -> Result<Self, Self::Err> {
+        Ok(Self(params.to_vec()))
+    }
+}
+
+impl ToMatrix for MatrixParams {
+    fn to_matrix(&self) -> Vec<(String, String)> {
+        self.0.clone()
+    }
+}
+
+/// Split a raw segment into the part a pattern matches and its matrix params, without the `;` between them. A
+/// segment without params has an empty rest.
+pub fn split_matrix(segment: &str) -> (&str, &str) {
+    segment.split_once(';').unwrap_or((segment, ""))
+}
+
+/// Decode the raw matrix params of a segment, like `sort=price;dir=asc`, and parse them as `T`
+pub fn parse_matrix<T: FromMatrix>(raw: &str) -> Result<T, SegmentError<T::Err>> {
+    let mut params = Vec::new();
+    for param in raw.split(';').filter(|param| !param.is_empty()) {
+        let (key, value) = param.split_once('=').unwrap_or((param, ""));
+        let decode = |part: &str| {
+            decode_path_segment(part)
+                .map(|decoded| decoded.into_owned())
+                .ok_or(SegmentError::DecodeError(DecodeError::InvalidUtf8))
+        };
+        params.push((decode(key)?, decode(value)?));
+    }
+    T::from_matrix(&params).map_err(|err| SegmentError::from_parse_error(raw, err))
+}
+
+/// The matrix params of a value as they are written in the path, without the leading `;`
+pub fn matrix_string<T: ToMatrix>(value: &T) -> String {
+    let written = DisplayMatrix(value).to_string();
+    written.strip_prefix(';').unwrap_or(&written).to_string()
+}
+
+/// Displays the matrix params of a value, each with a leading `;`.
+pub struct DisplayMatrix<'a, T>(pub &'a T);
+
+impl<T: ToMatrix> fmt::Display for DisplayMatrix<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in self.0.to_matrix() {
+            write!(f, ";{}", EncodedSegmentWith(&key, EncodeSet::Unreserved))?;
+            if !value.is_empty() {
+                write!(f, "={}", EncodedSegmentWith(&value, EncodeSet::Unreserved))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn matrix_params() {
+    let params = |pairs: &[(&str, &str)]| {
+        MatrixParams(
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        )
+    };
+
+    let route: MatrixRoute = "/items;sort=price;dir=asc/42".parse().unwrap();
+    assert_eq!(
+        route,
+        MatrixRoute::Item {
+            filters: params(&[("sort", "price"), ("dir", "asc")]),
+            id: 42,
+        }
+    );
+    assert_eq!(route.to_string(), "/items;sort=price;dir=asc/42");
+
+    // A `;` or `=` in a value is escaped, so it stays part of the value
+    let route = MatrixRoute::Item {
+        filters: params(&[("q", "a;b=c"), ("flag", "")]),
+        id: 7,
+    };
+    assert_eq!(route.to_string(), "/items;q=a%3Bb%3Dc;flag/7");
+    assert_eq!(route.to_string().parse::<MatrixRoute>().unwrap(), route);
+
+    // No params is the static segment alone
+    let route = MatrixRoute::Item {
+        filters: MatrixParams::default(),
+        id: 1,
+    };
+    assert_eq!(route.to_string(), "/items/1");
+    assert_eq!("/items/1".parse::<MatrixRoute>().unwrap(), route);
+
+    // On a dynamic segment, the base is parsed as the field and the rest as the params
+    let route: MatrixRoute = "/variants/red;size=m;fit=slim".parse().unwrap();
+    assert_eq!(
+        route,
+        MatrixRoute::Variant {
+            color: "red".to_string(),
+            options: params(&[("size", "m"), ("fit", "slim")]),
+        }
+    );
+    assert_eq!(route.to_string(), "/variants/red;size=m;fit=slim");
+    let route = MatrixRoute::Variant {
+        color: "a;b".to_string(),
+        options: params(&[("size", "x;l")]),
+    };
+    assert_eq!(route.to_string(), "/variants/a%3Bb;size=x%3Bl");
+    assert_eq!(route.to_string().parse::<MatrixRoute>().unwrap(), route);
+
+    assert!("/items;sort=price/nope".parse::<MatrixRoute>().is_err());
+    assert_eq!(params(&[("sort", "price")]).get("sort"), Some("price"));
+    assert_eq!(matrix_string(&params(&[("a", "1"), ("b", "")])), "a=1;b");
+    assert_eq!(matrix_string(&MatrixParams::default()), "");
+}
+
+/// Matrix params on a static segment and on a dynamic one, like the paths of a partner API
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum MatrixRoute {
+    #[route("/items;(filters)/(id)" MatrixItem)]
+    Item { filters: MatrixParams, id: u64 },
+    #[route("/variants/(color);(options)" MatrixVariant)]
+    Variant {
+        color: String,
+        options: MatrixParams,
+    },
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn MatrixItem(cx: Scope, filters: MatrixParams, id: u64) -> Element {
+    render! { "Item {id} {filters:?}" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn MatrixVariant(cx: Scope, color: String, options: MatrixParams) -> Element {
+    render! { "Variant {color} {options:?}" }
+}
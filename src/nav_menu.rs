@@ -0,0 +1,267 @@
+//! A navigation menu built from the routes themselves, like a sidebar or the links of a header.
+//!
+//! [`NavMenu`] renders a `<nav>` with a [`Link`] for every route without dynamic segments, since those are the only
+//! routes a menu can build without values. Routes declared with `nav = false` are left out, and `order = ...` moves a
+//! route up or down the menu. An item shows the `title = "..."` and `icon = "..."` of its route, and the item of the
+//! current route gets the active class, decided by [`is_active`].
+
+#[cfg(test)]
+use super::*;
+use crate::context::use_route;
+use crate::navigator::Link;
+use crate::Routable;
+use dioxus::prelude::*;
+use std::str::FromStr;
+
+/// How a menu decides if its item for a route is the current page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ActiveMatch {
+    /// The current path is the path of the item
+    #[default]
+    Exact,
+    /// The current path starts with the segments of the item, so `/docs` is active on `/docs/install`. The item for
+    /// `/` is only active on `/`, or it would be active on every page.
+    Prefix,
+}
+
+/// The segments of the path of a location, without its query and fragment
+fn path_segments(location: &str) -> Vec<&str> {
+    let path = location.split(['?', '#']).next().unwrap_or_default();
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// If the menu item for `target` is active while the router is at `current`. The query and fragment of both are
+/// ignored, and so is a slash at the end of the path.
+pub fn is_active<R: Routable>(current: &R, target: &R, matching: ActiveMatch) -> bool
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let (current, target) = (current.to_string(), target.to_string());
+    let (current, target) = (path_segments(&current), path_segments(&target));
+    match matching {
+        ActiveMatch::Exact => current == target,
+        ActiveMatch::Prefix if target.is_empty() => current.is_empty(),
+        ActiveMatch::Prefix => current.starts_with(&target),
+    }
+}
+
+/// An item of a [`NavMenu`], which is also what a custom `item` renders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavItem<R> {
+    /// The route the item links to
+    pub route: R,
+    /// The title of the page from `title = "..."`, or its path for pages without one
+    pub title: String,
+    pub path: String,
+    /// The name of the icon from `icon = "..."`
+    pub icon: Option<&'static str>,
+    /// If the item is the current page
+    pub active: bool,
+}
+
+/// The items of a menu while the router is at `current`: every route without dynamic segments unless it has
+/// `nav = false`, sorted by their `order`. Routes with the same order stay in the order they were declared.
+pub fn nav_items<R: Routable>(current: &R, matching: ActiveMatch) -> Vec<NavItem<R>>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let mut routes: Vec<_> = R::static_routes()
+        .into_iter()
+        .filter(|route| !route.nav_hidden())
+        .collect();
+    routes.sort_by_key(|route| route.nav_order());
+    routes
+        .into_iter()
+        .map(|route| {
+            let path = route.to_string();
+            NavItem {
+                title: route.head().title.unwrap_or_else(|| path.clone()),
+                path,
+                icon: route.palette_icon(),
+                active: is_active(current, &route, matching),
+                route,
+            }
+        })
+        .collect()
+}
+
+#[derive(Props)]
+pub struct NavMenuProps<'a, R: 'static> {
+    /// The class of the `<li>` of the current page
+    #[props(default = "active")]
+    active_class: &'a str,
+    /// How the current page is found, see [`ActiveMatch`]
+    #[props(default)]
+    active_match: ActiveMatch,
+    /// Renders the content of the link of an item instead of its title
+    item: Option<&'a dyn Fn(NavItem<R>) -> LazyNodes<'a, 'a>>,
+}
+
+/// A `<nav>` with a list of links to the [`nav_items`] of the nearest router with the route type `R`.
+///
+/// Each item is a `<li>` with a [`Link`], so clicks navigate with the router like any other link, and the item of the
+/// current page has the `active_class` and `aria-current="page"`. By default the link shows the title of the item;
+/// pass `item` to render something else, like an icon next to the title.
+#[allow(non_snake_case)]
+pub fn NavMenu<'a, R: Routable + 'static>(cx: Scope<'a, NavMenuProps<'a, R>>) -> Element<'a>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let current = use_route::<R>(cx)?;
+    let items = nav_items(&current, cx.props.active_match);
+    render! {
+        nav {
+            ul {
+                items.into_iter().map(|item| {
+                    let class = if item.active { cx.props.active_class } else { "" };
+                    let current = item.active.then_some("page");
+                    let content = match cx.props.item {
+                        Some(render_item) => render_item(item.clone()),
+                        None => rsx! { "{item.title}" },
+                    };
+                    rsx! {
+                        li {
+                            key: "{item.path}",
+                            class: "{class}",
+                            aria_current: current,
+                            Link::<R> { to: item.route.clone(), content }
+                        }
+                    }
+                })
+            }
+        }
+    }
+}
+
+#[test]
+fn active_matching() {
+    let docs = MenuRoute::Docs {};
+    let page = MenuRoute::DocsPage {
+        page: "install".to_string(),
+    };
+    assert!(is_active(&docs, &docs, ActiveMatch::Exact));
+    assert!(!is_active(&page, &docs, ActiveMatch::Exact));
+    assert!(is_active(&page, &docs, ActiveMatch::Prefix));
+    // The home page would be a prefix of every page
+    assert!(!is_active(&page, &MenuRoute::Home {}, ActiveMatch::Prefix));
+    assert!(is_active(
+        &MenuRoute::Home {},
+        &MenuRoute::Home {},
+        ActiveMatch::Prefix
+    ));
+    // `/docs` is not a prefix of `/docsearch`
+    assert!(!is_active(
+        &MenuRoute::Search {},
+        &docs,
+        ActiveMatch::Prefix
+    ));
+}
+
+#[test]
+fn menu_items() {
+    let page = MenuRoute::DocsPage {
+        page: "install".to_string(),
+    };
+    let items = nav_items(&page, ActiveMatch::Prefix);
+    let listed: Vec<_> = items
+        .iter()
+        .map(|item| (item.title.as_str(), item.icon, item.active))
+        .collect();
+    // Docs has `order = -1`, Settings has `nav = false` and the docs pages have a dynamic segment
+    assert_eq!(
+        listed,
+        [
+            ("Docs", None, true),
+            ("Home", Some("home"), false),
+            ("/docsearch", None, false),
+        ]
+    );
+    let exact = nav_items(&page, ActiveMatch::Exact);
+    assert!(exact.iter().all(|item| !item.active));
+}
+
+#[test]
+fn render_menu() {
+    use crate::context::{use_router_provider, RouterId};
+    use crate::Router;
+    use dioxus_router_core::history::MemoryHistory;
+
+    #[allow(non_snake_case)]
+    fn Menu(cx: Scope) -> Element {
+        use_router_provider(cx, RouterId("menu"), || {
+            Router::<MenuRoute>::new(MemoryHistory::with_initial_path("/docs").unwrap()).unwrap()
+        });
+        render! {
+            NavMenu::<MenuRoute> {}
+            NavMenu::<MenuRoute> {
+                active_class: "current",
+                item: &|item: NavItem<MenuRoute>| {
+                    let icon = item.icon.unwrap_or("-");
+                    rsx! { "{icon} {item.title}" }
+                },
+            }
+        }
+    }
+
+    let mut vdom = VirtualDom::new(Menu);
+    let _ = vdom.rebuild();
+    let html = dioxus_ssr::render(&vdom);
+    let default_items =
+        "<li class=\"active\" aria-current=\"page\"><a href=\"/docs\">Docs</a></li>\
+        <li class=\"\"><a href=\"/\">Home</a></li>";
+    assert!(html.contains(default_items), "{html}");
+    let custom_item =
+        "<li class=\"current\" aria-current=\"page\"><a href=\"/docs\">- Docs</a></li>";
+    assert!(html.contains(custom_item), "{html}");
+    assert!(html.contains("<a href=\"/\">home Home</a>"), "{html}");
+    assert!(!html.contains("/settings"), "{html}");
+}
+
+/// The pages of a site with a navigation menu
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum MenuRoute {
+    #[route("/" MenuHome, title = "Home", icon = "home")]
+    Home {},
+    #[route("/docs" MenuDocs, title = "Docs", order = -1)]
+    Docs {},
+    #[route("/docs/(page)" MenuDocsPage)]
+    DocsPage { page: String },
+    #[route("/settings" MenuSettings, nav = false)]
+    Settings {},
+    #[route("/docsearch" MenuSearch)]
+    Search {},
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn MenuHome(cx: Scope) -> Element {
+    render! { "home" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn MenuDocs(cx: Scope) -> Element {
+    render! { "docs" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn MenuDocsPage(cx: Scope, page: String) -> Element {
+    render! { "docs for {page}" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn MenuSettings(cx: Scope) -> Element {
+    render! { "settings" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn MenuSearch(cx: Scope) -> Element {
+    render! { "search" }
+}
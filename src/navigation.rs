@@ -0,0 +1,176 @@
+//! Where navigations come from and how they changed the history, so subscribers like analytics can tell a link click
+//! from a redirect.
+
+/// What started a navigation.
+///
+/// A navigation that starts another one, like a route whose action redirects, is superseded by it: the router only
+/// ends up at the route of the last navigation, and reports the source of that one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationSource {
+    /// The router loaded the location of the history, or the browser went back or forward
+    Browser,
+    /// Code of the app called the router, like [`Router::push`](crate::Router::push)
+    Programmatic,
+    /// A link was clicked. The id is the `id` attribute of the link, if it has one
+    LinkClick { element_id: Option<String> },
+    /// The action of the route with this pattern ran
+    Action(&'static str),
+    /// A guard of the route with this pattern redirected the navigation away from it
+    Guard(&'static str),
+    /// A rewrite rule replaced a legacy path of the history
+    Rewrite,
+    /// Devtools jumped to an earlier entry of the navigation log
+    Devtools,
+    /// A navigation failed, and the router went to the route of
+    /// [`RouterConfiguration::error_route`](crate::RouterConfiguration) instead
+    Failure,
+    /// The router started on a page the server rendered, see [`hydration`](crate::hydration)
+    Hydration,
+}
+
+impl NavigationSource {
+    /// The name of the source without its data, like `link_click`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Browser => "browser",
+            Self::Programmatic => "programmatic",
+            Self::LinkClick { .. } => "link_click",
+            Self::Action(_) => "action",
+            Self::Guard(_) => "guard",
+            Self::Rewrite => "rewrite",
+            Self::Devtools => "devtools",
+            Self::Failure => "failure",
+            Self::Hydration => "hydration",
+        }
+    }
+}
+
+/// How a navigation changed the history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationKind {
+    /// A new entry was added after the current one
+    Push,
+    /// The current entry was replaced, so going back skips it
+    Replace,
+    Back,
+    Forward,
+}
+
+impl NavigationKind {
+    /// The name of the kind, like `replace`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Push => "push",
+            Self::Replace => "replace",
+            Self::Back => "back",
+            Self::Forward => "forward",
+        }
+    }
+}
+
+/// What subscribers of a router are told after every navigation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteChange<'a, R> {
+    /// The route the router navigated to
+    pub route: &'a R,
+    /// The location as the history reported it, see [`Router::current_url`](crate::Router::current_url)
+    pub url: &'a str,
+    pub source: &'a NavigationSource,
+    /// How the navigation changed the history, or `None` if the history moved on its own, like when the router
+    /// loaded the first location or the browser went back
+    pub kind: Option<NavigationKind>,
+    /// If the route is the same variant as the one the navigation came from, like `/photos/1` and `/photos/2`
+    pub same_variant: bool,
+    /// The parameters the navigation changed on the same variant as `(name, from_value, to_value)`, see
+    /// [`Routable::params_diff`](crate::Routable::params_diff). Empty when the variant changed.
+    pub params_diff: Vec<(&'static str, String, String)>,
+    /// The state of the current history entry, set with [`Router::transaction`](crate::Router::transaction)
+    pub state: Option<&'a str>,
+}
+
+#[test]
+fn route_change_params() {
+    use crate::{Route, Router};
+    use dioxus_router_core::history::MemoryHistory;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let history = MemoryHistory::with_initial_path("/1/2").unwrap();
+    let mut router = Router::<Route>::new(history).unwrap();
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    router.subscribers.subscribe({
+        let changes = changes.clone();
+        move |change: RouteChange<Route>| {
+            changes
+                .borrow_mut()
+                .push((change.kind, change.same_variant, change.params_diff));
+        }
+    });
+
+    router.push(Route::Route4 {
+        number1: 1,
+        number2: 3,
+    });
+    router.replace(Route::Route5 {});
+    // Going back skips the entry that was replaced
+    router.go_back().unwrap();
+    assert_eq!(
+        router.route,
+        Route::Route4 {
+            number1: 1,
+            number2: 2
+        }
+    );
+    assert_eq!(
+        *changes.borrow(),
+        [
+            (
+                Some(NavigationKind::Push),
+                true,
+                vec![("number2", "2".to_string(), "3".to_string())]
+            ),
+            (Some(NavigationKind::Replace), false, Vec::new()),
+            (Some(NavigationKind::Back), false, Vec::new()),
+        ]
+    );
+}
+
+#[test]
+fn navigation_sources() {
+    use crate::rewrite::RewriteRule;
+    use crate::{Route, Router, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+
+    let config = RouterConfiguration {
+        rewrites: vec![RewriteRule::new("/old/(id)", "/hello_world/(id)").unwrap()],
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/old/3").unwrap();
+    let mut router = Router::<Route>::with_configuration(history, config).unwrap();
+    assert_eq!(router.navigation_source(), &NavigationSource::Rewrite);
+
+    router.push(Route::Route2 {});
+    assert_eq!(router.navigation_source(), &NavigationSource::Programmatic);
+
+    // The action of the logout route redirects, and the redirect is the navigation that won
+    router.push(Route::Logout {});
+    assert_eq!(router.route, Route::Route5 {});
+    assert_eq!(
+        router.navigation_source(),
+        &NavigationSource::Action("/logout")
+    );
+
+    router
+        .follow_fragment_link("/hello_world#top", Some("nav-hello"))
+        .unwrap();
+    assert_eq!(
+        router.navigation_source(),
+        &NavigationSource::LinkClick {
+            element_id: Some("nav-hello".to_string())
+        }
+    );
+
+    router.go_back().unwrap();
+    assert_eq!(router.route, Route::Route5 {});
+    assert_eq!(router.navigation_source(), &NavigationSource::Browser);
+}
@@ -0,0 +1,211 @@
+//! A bounded log of the navigations a router made, for devtools to step back through and attach to bug reports.
+//!
+//! Routes are recorded as their paths, so the log holds no route values and can outlive the router that wrote it.
+
+use crate::navigation::{NavigationKind, NavigationSource};
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One recorded navigation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationRecord {
+    /// The path of the route the router navigated to
+    pub path: String,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub kind: NavigationKind,
+    pub source: NavigationSource,
+}
+
+/// The last `capacity` navigations, oldest first. Nothing is recorded until recording is enabled.
+pub struct NavigationLog {
+    capacity: usize,
+    recording: bool,
+    entries: VecDeque<NavigationRecord>,
+}
+
+impl NavigationLog {
+    pub fn new(capacity: usize, recording: bool) -> Self {
+        Self {
+            capacity,
+            recording,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+    }
+
+    /// Record a navigation, dropping the oldest entry if the log is full.
+    pub fn record(&mut self, path: String, kind: NavigationKind, source: NavigationSource) {
+        if !self.recording || self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        self.entries.push_back(NavigationRecord {
+            path,
+            timestamp,
+            kind,
+            source,
+        });
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &NavigationRecord> {
+        self.entries.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&NavigationRecord> {
+        self.entries.get(index)
+    }
+
+    /// The log as a JSON array, oldest entry first.
+    pub fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let source = match &entry.source {
+                    NavigationSource::LinkClick {
+                        element_id: Some(id),
+                    } => format!("{{\"link_click\":{}}}", json_string(id)),
+                    NavigationSource::Action(pattern) => {
+                        format!("{{\"action\":{}}}", json_string(pattern))
+                    }
+                    NavigationSource::Guard(pattern) => {
+                        format!("{{\"guard\":{}}}", json_string(pattern))
+                    }
+                    source => format!("\"{}\"", source.name()),
+                };
+                format!(
+                    "{{\"path\":{},\"timestamp\":{},\"kind\":\"{}\",\"source\":{source}}}",
+                    json_string(&entry.path),
+                    entry.timestamp,
+                    entry.kind.name(),
+                )
+            })
+            .collect();
+        format!("[{}]", entries.join(","))
+    }
+}
+
+/// Quote and escape a string for JSON
+fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[test]
+fn navigation_log_is_bounded() {
+    let mut log = NavigationLog::new(2, false);
+    log.record(
+        "/ignored".to_string(),
+        NavigationKind::Push,
+        NavigationSource::Programmatic,
+    );
+    assert_eq!(log.entries().count(), 0);
+
+    log.set_recording(true);
+    for path in ["/a", "/b", "/c"] {
+        log.record(
+            path.to_string(),
+            NavigationKind::Push,
+            NavigationSource::Programmatic,
+        );
+    }
+    let paths: Vec<_> = log.entries().map(|entry| entry.path.as_str()).collect();
+    assert_eq!(paths, ["/b", "/c"]);
+
+    let mut log = NavigationLog::new(1, true);
+    log.record(
+        "/say \"hi\"".to_string(),
+        NavigationKind::Replace,
+        NavigationSource::Action("/ping"),
+    );
+    let json = log.to_json();
+    let timestamp = log.get(0).unwrap().timestamp;
+    assert_eq!(
+        json,
+        format!(
+            r#"[{{"path":"/say \"hi\"","timestamp":{timestamp},"kind":"replace","source":{{"action":"/ping"}}}}]"#
+        )
+    );
+
+    log.record(
+        "/docs".to_string(),
+        NavigationKind::Push,
+        NavigationSource::LinkClick {
+            element_id: Some("nav-docs".to_string()),
+        },
+    );
+    assert!(log
+        .to_json()
+        .ends_with(r#""source":{"link_click":"nav-docs"}}]"#));
+    log.record(
+        "/".to_string(),
+        NavigationKind::Back,
+        NavigationSource::Browser,
+    );
+    assert!(log
+        .to_json()
+        .ends_with(r#""kind":"back","source":"browser"}]"#));
+}
+
+#[test]
+fn navigation_log() {
+    use crate::{Route, Router, RouterConfiguration};
+
+    let config = RouterConfiguration {
+        navigation_log_capacity: 3,
+        ..Default::default()
+    };
+    let history = dioxus_router_core::history::MemoryHistory::default();
+    let mut router = Router::<Route>::with_configuration(history, config).unwrap();
+    router.push(Route::Route2 {});
+    assert_eq!(router.navigation_log().entries().count(), 0);
+
+    router.set_navigation_recording(true);
+    let search = Route::Search {
+        query: "rust router".to_string(),
+        page: 2,
+        sort: "stars".to_string(),
+    };
+    router.push(search.clone());
+    router.push(Route::Route3 { dynamic: 7 });
+    router.go_back().unwrap();
+    router.replace(Route::Route2 {});
+    let kinds: Vec<_> = router
+        .navigation_log()
+        .entries()
+        .map(|entry| entry.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            NavigationKind::Push,
+            NavigationKind::Back,
+            NavigationKind::Replace
+        ]
+    );
+
+    router.jump_to_navigation(1).unwrap();
+    assert_eq!(router.route, search);
+    let last = router.navigation_log().entries().last().unwrap();
+    assert_eq!(last.source, NavigationSource::Devtools);
+    assert_eq!(last.path, search.to_string());
+}
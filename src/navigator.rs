@@ -0,0 +1,432 @@
+//! Navigation with typed routes instead of paths: [`Link`] renders a link to a route and [`use_navigator`] navigates
+//! from event handlers.
+//!
+//! Both go through the nearest router with the route type `R`, so links render the `href` of [`Router::href`],
+//! under the [`RouterConfiguration::base_url`](crate::RouterConfiguration) if there is one, and navigations run the
+//! guards and actions of the router like any other.
+
+use crate::context::use_router;
+use crate::fragment::LinkClickEvent;
+use crate::link::LinkClick;
+use crate::navigation::NavigationSource;
+use crate::{Routable, Router};
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// Navigates the router it was taken from, see [`use_navigator`].
+pub struct Navigator<R: Routable>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    router: Rc<RefCell<Router<R>>>,
+}
+
+impl<R: Routable> Clone for Navigator<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    fn clone(&self) -> Self {
+        Self {
+            router: self.router.clone(),
+        }
+    }
+}
+
+impl<R: Routable> Navigator<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Navigate to a route, adding a new entry to the history
+    pub fn push(&self, route: impl Into<R>) {
+        self.router.borrow_mut().push(route);
+    }
+
+    /// Navigate to a route, replacing the current entry of the history
+    pub fn replace(&self, route: impl Into<R>) {
+        self.router.borrow_mut().replace(route);
+    }
+
+    /// Go back to the previous entry of the history. Fails if its location doesn't parse as a route.
+    pub fn go_back(&self) -> Result<(), R::Err> {
+        self.router.borrow_mut().go_back()
+    }
+
+    /// Go forward to the next entry of the history, like [`Navigator::go_back`]
+    pub fn go_forward(&self) -> Result<(), R::Err> {
+        self.router.borrow_mut().go_forward()
+    }
+}
+
+/// A [`Navigator`] for the nearest router with the route type `R`, or `None` outside of one.
+pub fn use_navigator<R: Routable + 'static>(cx: &ScopeState) -> Option<Navigator<R>>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let router = use_router::<R>(cx)?.router().clone();
+    Some(Navigator { router })
+}
+
+#[derive(Props)]
+pub struct LinkProps<'a, R: 'static> {
+    /// The route the link navigates to
+    to: R,
+    /// The `id` of the link, reported as the [`NavigationSource::LinkClick`] of the navigation
+    id: Option<&'a str>,
+    /// Replace the current entry of the history instead of adding one, so going back skips it
+    #[props(default)]
+    replace: bool,
+    /// Download the page of the route instead of navigating to it, saved under this file name if it isn't empty
+    download: Option<&'a str>,
+    /// Space separated urls the browser notifies when it follows the link
+    ping: Option<&'a str>,
+    /// Runs before the router handles a click. Call [`LinkClickEvent::prevent_default`] to keep the router from
+    /// navigating
+    onclick: Option<EventHandler<'a, LinkClickEvent>>,
+    children: Element<'a>,
+}
+
+/// A link to a route that navigates with the nearest router with the route type `R`.
+///
+/// Like [`FragmentLink`](crate::fragment::FragmentLink), the link is an `<a>` with the `href` of the route, so it
+/// works without the router, and only clicks [`LinkClick::intercepted`] accepts navigate with the router. The others,
+/// like ctrl-click to open a new tab, are left to the browser.
+///
+/// The `onclick` handler runs first and can cancel the navigation; the router never runs before it. Links with
+/// `download` are always left to the browser, which saves the page instead of showing it. The browser only sends the
+/// `ping` of clicks it follows itself, not of the clicks the router navigates for.
+///
+/// Dioxus components can't take arbitrary attributes, so `Link` forwards the attributes it lists and nothing else.
+#[allow(non_snake_case)]
+pub fn Link<'a, R: Routable + 'static>(cx: Scope<'a, LinkProps<'a, R>>) -> Element<'a>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    // Like for `FragmentLink`, the press that starts a click decides if its default is prevented
+    let press = use_state(cx, LinkClick::primary);
+    let router = use_router::<R>(cx)?.router().clone();
+    let id = cx.props.id;
+    let replace = cx.props.replace;
+    let download = cx.props.download;
+    let href = router.borrow().link_href(&cx.props.to);
+    // The router can't save a page, so it doesn't prevent the download
+    let prevent_default = match download {
+        Some(_) => "",
+        None => press.prevent_default_attribute(),
+    };
+    render! {
+        a {
+            href: href,
+            id: id,
+            download: download,
+            ping: cx.props.ping,
+            prevent_default: "{prevent_default}",
+            onmousedown: move |event: MouseEvent| press.set(LinkClick::from_mouse(&event)),
+            onclick: move |event: MouseEvent| {
+                press.set(LinkClick::primary());
+                let mut click = LinkClick::from_mouse(&event);
+                if let Some(handler) = &cx.props.onclick {
+                    click = LinkClickEvent::dispatch(click, |event| handler.call(event));
+                }
+                if download.is_none() {
+                    router
+                        .borrow_mut()
+                        .click_link(click, cx.props.to.clone(), id, replace);
+                }
+            },
+            &cx.props.children
+        }
+    }
+}
+
+/// The `href` of a [`Link`]. Without a base url the route is its own `href`, so it is formatted straight into the
+/// scope's bump allocator instead of an intermediate `String`, like the `IntoAttributeValue` impl of the route.
+enum LinkHref<'a, R> {
+    Route(&'a R),
+    Url(String),
+}
+
+impl<'a, R: Routable> IntoAttributeValue<'a> for LinkHref<'a, R> {
+    fn into_value(
+        self,
+        bump: &'a dioxus::core::exports::bumpalo::Bump,
+    ) -> dioxus::core::AttributeValue<'a> {
+        match self {
+            LinkHref::Route(route) => format_args!("{route}").into_value(bump),
+            LinkHref::Url(url) => format_args!("{url}").into_value(bump),
+        }
+    }
+}
+
+impl<R: Routable> Router<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// The [`Router::href`] of a route, which only needs a `String` under a base url
+    fn link_href<'a>(&self, route: &'a R) -> LinkHref<'a, R> {
+        match &self.base_url {
+            Some(base) => LinkHref::Url(base.href(&route.to_string())),
+            None => LinkHref::Route(route),
+        }
+    }
+
+    /// Navigate to the route of a [`Link`], like [`Router::push`], or like [`Router::replace`] for a link with
+    /// `replace`. The navigation is reported as a [`NavigationSource::LinkClick`] of the link with the id
+    /// `element_id`.
+    pub fn follow_link(&mut self, route: R, element_id: Option<&str>, replace: bool) {
+        let location = self.programmatic_location(&route);
+        let source = NavigationSource::LinkClick {
+            element_id: element_id.map(String::from),
+        };
+        match replace {
+            true => self.replace_location(route, location, source),
+            false => self.push_location(route, location, source),
+        }
+    }
+
+    /// Handle a click on a [`Link`]: follow it if [`LinkClick::intercepted`] accepts the click, and leave the history
+    /// untouched otherwise. Returns if the router navigated.
+    pub fn click_link(
+        &mut self,
+        click: LinkClick,
+        route: R,
+        element_id: Option<&str>,
+        replace: bool,
+    ) -> bool {
+        let intercepted = click.intercepted();
+        if intercepted {
+            self.follow_link(route, element_id, replace);
+        }
+        intercepted
+    }
+}
+
+#[test]
+fn links_and_navigator() {
+    use crate::link::BaseUrl;
+    use crate::{ArticleRoute, ParseLimit, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+
+    let config = RouterConfiguration {
+        base_url: BaseUrl::parse("https://example.com/app"),
+        ..Default::default()
+    };
+    let router =
+        Router::<ArticleRoute>::with_configuration(MemoryHistory::default(), config).unwrap();
+    let navigator = Navigator {
+        router: Rc::new(RefCell::new(router)),
+    };
+    let article = ArticleRoute::Article { id: 7 };
+    assert_eq!(
+        navigator.router.borrow().href(&article.to_string()),
+        "https://example.com/app/blog/7"
+    );
+
+    navigator
+        .router
+        .borrow_mut()
+        .follow_link(article.clone(), Some("latest"), false);
+    assert_eq!(navigator.router.borrow().route, article);
+    assert_eq!(
+        navigator.router.borrow().navigation_source(),
+        &NavigationSource::LinkClick {
+            element_id: Some("latest".to_string())
+        }
+    );
+    navigator.push(ArticleRoute::Article { id: 8 });
+    navigator.replace(ArticleRoute::Article { id: 9 });
+    navigator.go_back().unwrap();
+    assert_eq!(navigator.router.borrow().route, article);
+
+    assert_eq!(
+        ArticleRoute::parse_with_base("/app/blog/7", "/app/"),
+        Ok(article)
+    );
+    assert_eq!(
+        ArticleRoute::parse_with_base("/app", "/app"),
+        Ok(ArticleRoute::ArticleHome {})
+    );
+    assert_eq!(
+        ArticleRoute::parse_with_base("/application", "/app")
+            .unwrap_err()
+            .limit,
+        Some(ParseLimit::OutsideBase {
+            base: "/app".to_string()
+        })
+    );
+}
+
+#[test]
+fn link_hrefs() {
+    use crate::link::BaseUrl;
+    use crate::{ArticleRoute, RouterConfiguration};
+    use dioxus::core::exports::bumpalo::Bump;
+    use dioxus::core::AttributeValue;
+    use dioxus_router_core::history::MemoryHistory;
+
+    let bump = Bump::new();
+    let article = ArticleRoute::Article { id: 7 };
+    let text = |href: LinkHref<ArticleRoute>| match href.into_value(&bump) {
+        AttributeValue::Text(text) => text.to_string(),
+        _ => panic!("the href is text"),
+    };
+
+    let router = Router::<ArticleRoute>::new(MemoryHistory::default()).unwrap();
+    assert!(matches!(router.link_href(&article), LinkHref::Route(_)));
+    assert_eq!(text(router.link_href(&article)), "/blog/7");
+
+    let config = RouterConfiguration {
+        base_url: BaseUrl::parse("https://example.com/app"),
+        ..Default::default()
+    };
+    let router =
+        Router::<ArticleRoute>::with_configuration(MemoryHistory::default(), config).unwrap();
+    assert_eq!(
+        text(router.link_href(&article)),
+        "https://example.com/app/blog/7"
+    );
+}
+
+/// Render the `href` of 5000 links into a bump allocator by formatting the route into a `String` first, like links
+/// did before, and with the `href` of [`Link`]. Run with
+/// `cargo test --release link_href_benchmark -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn link_href_benchmark() {
+    use crate::ArticleRoute;
+    use dioxus::core::exports::bumpalo::Bump;
+    use dioxus_router_core::history::MemoryHistory;
+    use std::time::Instant;
+
+    const RUNS: usize = 100;
+    let router = Router::<ArticleRoute>::new(MemoryHistory::default()).unwrap();
+    let links: Vec<_> = (0..5000).map(|id| ArticleRoute::Article { id }).collect();
+
+    let mut bump = Bump::new();
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        for route in &links {
+            let href = router.href(&route.to_string());
+            let _ = std::hint::black_box(format_args!("{href}").into_value(&bump));
+        }
+        bump.reset();
+    }
+    let formatted = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        for route in &links {
+            let _ = std::hint::black_box(router.link_href(route).into_value(&bump));
+        }
+        bump.reset();
+    }
+    let direct = start.elapsed();
+
+    println!(
+        "{RUNS} renders of {} links: {formatted:?} formatting each route into a String first, {direct:?} into the bump allocator",
+        links.len()
+    );
+}
+
+#[test]
+fn replacing_links() {
+    use crate::navigation::{NavigationKind, RouteChange};
+    use crate::ArticleRoute;
+    use dioxus_router_core::history::MemoryHistory;
+
+    let mut router = Router::<ArticleRoute>::new(MemoryHistory::default()).unwrap();
+    let kinds = Rc::new(RefCell::new(Vec::new()));
+    router.subscribers.subscribe({
+        let kinds = kinds.clone();
+        move |change: RouteChange<ArticleRoute>| kinds.borrow_mut().push(change.kind)
+    });
+    router.follow_link(ArticleRoute::Article { id: 1 }, None, false);
+    router.follow_link(ArticleRoute::Article { id: 2 }, None, true);
+    let navigator = Navigator {
+        router: Rc::new(RefCell::new(router)),
+    };
+    navigator.replace(ArticleRoute::Article { id: 3 });
+    assert_eq!(
+        navigator.router.borrow().route,
+        ArticleRoute::Article { id: 3 }
+    );
+
+    // Going back skips the entries that were replaced
+    navigator.go_back().unwrap();
+    assert_eq!(
+        navigator.router.borrow().route,
+        ArticleRoute::ArticleHome {}
+    );
+    assert_eq!(
+        *kinds.borrow(),
+        [
+            Some(NavigationKind::Push),
+            Some(NavigationKind::Replace),
+            Some(NavigationKind::Replace),
+            Some(NavigationKind::Back),
+        ]
+    );
+}
+
+#[test]
+fn cancelled_link_clicks() {
+    use crate::ArticleRoute;
+    use dioxus_router_core::history::MemoryHistory;
+
+    let mut router = Router::<ArticleRoute>::new(MemoryHistory::default()).unwrap();
+    let click = LinkClick {
+        primary_button: true,
+        ..Default::default()
+    };
+
+    // The handler runs before the router and can keep it from navigating
+    let mut ran = false;
+    let cancelled = LinkClickEvent::dispatch(click, |event| {
+        ran = event.click().primary_button;
+        event.prevent_default();
+    });
+    assert!(ran);
+    assert!(cancelled.default_prevented);
+    let article = ArticleRoute::Article { id: 1 };
+    assert!(!router.click_link(cancelled, article.clone(), None, false));
+    assert_eq!(router.route, ArticleRoute::ArticleHome {});
+    assert!(!router.history.can_go_back());
+    assert_eq!(router.navigation_source(), &NavigationSource::Browser);
+
+    // A handler that only watches the click leaves it to the router
+    let watched = LinkClickEvent::dispatch(click, |_| {});
+    assert!(router.click_link(watched, article.clone(), None, false));
+    assert_eq!(router.route, article);
+    assert!(router.history.can_go_back());
+}
+
+#[test]
+fn modified_link_clicks() {
+    use crate::ArticleRoute;
+    use dioxus_router_core::history::MemoryHistory;
+
+    let mut router = Router::<ArticleRoute>::new(MemoryHistory::default()).unwrap();
+    let article = ArticleRoute::Article { id: 1 };
+    for press in [
+        LinkClick {
+            ctrl: true,
+            ..LinkClick::primary()
+        },
+        LinkClick {
+            primary_button: false,
+            ..LinkClick::primary()
+        },
+    ] {
+        // The browser gets the click with its default, like opening a new tab, and the router stays where it is
+        assert_eq!(press.prevent_default_attribute(), "", "{press:?}");
+        assert!(!router.click_link(press, article.clone(), None, false));
+        assert_eq!(router.route, ArticleRoute::ArticleHome {});
+        assert!(!router.history.can_go_back());
+    }
+
+    let press = LinkClick::primary();
+    assert_eq!(press.prevent_default_attribute(), "onclick");
+    assert!(router.click_link(press, article.clone(), None, false));
+    assert_eq!(router.route, article);
+}
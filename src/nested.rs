@@ -0,0 +1,189 @@
+//! Routes of one type nested in a route of another, like the settings pages under `/settings/(...child)`.
+//!
+//! The nested type is derived with `#[routable(nested)]`, which lets it be the value of a catch-all: it parses the
+//! rest of the path and writes its path without the leading slash. The catch-all of the parent route is marked
+//! with `#[route_segment(child)]`. The parent then converts `From` the child routes if the route has no other
+//! segments, or with a constructor named after the child type that takes the other segments, like
+//! `AccountRoute::from_team_route(child, team)`. [`Router::push`](crate::Router) and `replace` take anything that
+//! converts into the route, so a child component can navigate with its own routes.
+//!
+//! The parent route renders its own component with the child routes as a prop, so the component is the layout
+//! around them. It renders the child route where it goes with [`ChildOutlet`]. The prefix before the child can have
+//! dynamic segments of its own, like `team` in `/teams/(team)/(...child)`, and a child path that doesn't parse is
+//! reported as the error of the child catch-all, with the errors of every child route that was tried.
+
+#[cfg(test)]
+use super::*;
+use crate::Routable;
+use dioxus::prelude::*;
+use std::str::FromStr;
+
+/// A routable type with a route that holds the routes of `C` in a `child` catch-all.
+pub trait HasChild<C> {
+    /// The child routes, if this is the route that holds them
+    fn child(&self) -> Option<&C>;
+}
+
+#[derive(Props, PartialEq)]
+pub struct ChildOutletProps<C: PartialEq + 'static> {
+    /// The child routes of the route whose component renders the outlet
+    route: C,
+}
+
+/// Renders the child routes of a route in the component of that route, like `ChildOutlet { route: child.clone() }`
+/// in the component of `/teams/(team)/(...child)`.
+#[allow(non_snake_case)]
+pub fn ChildOutlet<C: Routable + PartialEq + 'static>(cx: Scope<ChildOutletProps<C>>) -> Element
+where
+    <C as FromStr>::Err: std::fmt::Display,
+{
+    cx.props.route.clone().render(cx)
+}
+
+#[test]
+fn nested_routes() {
+    use crate::Router;
+    use dioxus_router_core::history::MemoryHistory;
+    use std::str::FromStr;
+
+    // A child route converts into the route it is nested in
+    let route = AccountRoute::from(PreferencesRoute::Security {});
+    assert_eq!(
+        route,
+        AccountRoute::Preferences {
+            child: PreferencesRoute::Security {}
+        }
+    );
+    assert_eq!(route.to_string(), "/preferences/security");
+    assert_eq!(
+        AccountRoute::from_str("/preferences/security"),
+        Ok(route.clone())
+    );
+    assert_eq!(
+        route.as_child::<PreferencesRoute>(),
+        Some(&PreferencesRoute::Security {})
+    );
+    assert_eq!(route.as_child::<TeamRoute>(), None);
+
+    // The root of the child is the parent's catch-all with no segments
+    let route: AccountRoute = PreferencesRoute::Profile {}.into();
+    assert_eq!(route.to_string(), "/preferences");
+    assert_eq!(AccountRoute::from_str("/preferences"), Ok(route));
+
+    // Routes with other segments take them next to the child
+    let route = AccountRoute::from_team_route(TeamRoute::Member { id: 7 }, "core".to_string());
+    assert_eq!(route.to_string(), "/teams/core/members/7");
+    assert_eq!(
+        AccountRoute::from_str("/teams/core/members/7"),
+        Ok(route.clone())
+    );
+    assert_eq!(
+        route.as_child::<TeamRoute>(),
+        Some(&TeamRoute::Member { id: 7 })
+    );
+    assert_eq!(AccountRoute::Home {}.as_child::<TeamRoute>(), None);
+
+    // A child path that doesn't parse is the error of the child catch-all, with the errors of the child routes
+    let err = AccountRoute::from_str("/teams/core/members/x").unwrap_err();
+    let child_err = TeamRoute::from_segments(["members", "x"].into_iter()).unwrap_err();
+    assert!(err
+        .attempted_routes
+        .contains(&AccountRouteMatchError::Team {
+            error: TeamParseError::childParseError(child_err),
+            span: 12..21,
+        }));
+
+    let history = MemoryHistory::with_initial_path("/").unwrap();
+    let mut router = Router::<AccountRoute>::new(history).unwrap();
+    router.push(PreferencesRoute::Security {});
+    assert_eq!(router.route.to_string(), "/preferences/security");
+    router.replace(AccountRoute::Home {});
+    assert_eq!(router.route, AccountRoute::Home {});
+}
+
+/// The account pages, with the preferences and the pages of each team nested under their own prefix
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum AccountRoute {
+    #[route("/" AccountHome)]
+    Home {},
+    #[route("/preferences/(...child)" AccountPreferences)]
+    Preferences {
+        #[route_segment(child)]
+        child: PreferencesRoute,
+    },
+    #[route("/teams/(team)/(...child)" AccountTeam)]
+    Team {
+        team: String,
+        #[route_segment(child)]
+        child: TeamRoute,
+    },
+}
+
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(nested)]
+enum PreferencesRoute {
+    #[route("/" PreferencesProfile)]
+    Profile {},
+    #[route("/security" PreferencesSecurity)]
+    Security {},
+}
+
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[routable(nested)]
+enum TeamRoute {
+    #[route("/" TeamMembers)]
+    Members {},
+    #[route("/members/(id)" TeamMember)]
+    Member { id: u32 },
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn AccountHome(cx: Scope) -> Element {
+    render! { "Account" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn AccountPreferences(cx: Scope, child: PreferencesRoute) -> Element {
+    render! { "Preferences {child}" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn AccountTeam(cx: Scope, team: String, child: TeamRoute) -> Element {
+    render! {
+        "Team {team}"
+        ChildOutlet { route: child.clone() }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn PreferencesProfile(cx: Scope) -> Element {
+    render! { "Profile" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn PreferencesSecurity(cx: Scope) -> Element {
+    render! { "Security" }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn TeamMembers(cx: Scope) -> Element {
+    render! { "Members" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn TeamMember(cx: Scope, id: u32) -> Element {
+    render! { "Member {id}" }
+}
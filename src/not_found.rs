@@ -0,0 +1,357 @@
+//! Suggestions for a path that didn't match any route, for "did you mean" links on a not found page.
+//!
+//! A location that fails to parse renders the not found page of the router, which gets them from
+//! [`use_not_found_context`]. Types with a `#[route(not_found)]` variant parse every location, so the page of that
+//! variant gets them from [`use_last_match_error`] instead.
+
+#[cfg(test)]
+use super::*;
+use crate::context::use_router;
+use crate::edit_distance::edit_distance;
+use crate::encoding::decode_path_segment;
+use crate::pattern::{PatternSegment, RoutePattern};
+use crate::Routable;
+use dioxus::prelude::*;
+use std::str::FromStr;
+
+/// What the not found page knows about the path that failed to parse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotFoundContext<R> {
+    /// The path that didn't match any route
+    pub path: String,
+    /// The patterns closest to the path, closest first. Patterns whose dynamic segments can be filled from the path
+    /// come before the ones that can't.
+    pub closest_patterns: Vec<&'static str>,
+    /// Routes built from the closest patterns with the segments of the path, closest first. Empty if no pattern is
+    /// close to the path.
+    pub suggestions: Vec<R>,
+}
+
+/// How far a pattern is from a path: the number of character edits to the static segments, and the values of the
+/// dynamic segments as they were taken from the path
+struct Candidate {
+    pattern: &'static str,
+    edits: usize,
+    params: Vec<(String, String)>,
+}
+
+impl<R: Routable> NotFoundContext<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Compare `path` with every route pattern, keeping at most `max_suggestions` patterns and suggestions.
+    ///
+    /// Only paths with the same number of segments as a pattern are compared, or at least its static segments for a
+    /// catch-all. Each static segment may be a few characters off, like `/serch` for `/search`. Suggestions are built
+    /// with `from_pattern_and_params`, so only routes that parse are suggested, and action routes are left out.
+    pub fn new(path: &str, max_suggestions: usize) -> Self {
+        let query_start = path.find(['?', '#']).unwrap_or(path.len());
+        let mut segments: Vec<&str> = path[..query_start]
+            .strip_prefix('/')
+            .unwrap_or(&path[..query_start])
+            .split('/')
+            .collect();
+        if segments.last() == Some(&"") {
+            segments.pop();
+        }
+
+        let mut candidates: Vec<_> = R::route_patterns()
+            .iter()
+            .filter_map(|pattern| compare(pattern, &segments))
+            .map(|candidate| {
+                let params: Vec<_> = candidate
+                    .params
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str()))
+                    .collect();
+                let route = R::from_pattern_and_params(candidate.pattern, &params)
+                    .ok()
+                    .filter(|route| route.action().is_none());
+                (candidate, route)
+            })
+            .collect();
+        // The sort is stable, so patterns that are equally close stay in match order
+        candidates.sort_by_key(|(candidate, route)| (route.is_none(), candidate.edits));
+        candidates.truncate(max_suggestions);
+
+        Self {
+            path: path.to_string(),
+            closest_patterns: candidates
+                .iter()
+                .map(|(candidate, _)| candidate.pattern)
+                .collect(),
+            suggestions: candidates
+                .into_iter()
+                .filter_map(|(_, route)| route)
+                .collect(),
+        }
+    }
+}
+
+/// Line up the segments of a path with a pattern, or return `None` if they are too different.
+fn compare(pattern: &'static str, segments: &[&str]) -> Option<Candidate> {
+    let parsed = RoutePattern::parse(pattern).ok()?;
+    let mut remaining = segments;
+    let mut edits = 0;
+    let mut params = Vec::new();
+
+    for pattern_segment in parsed.segments() {
+        if let PatternSegment::CatchAll(name) = pattern_segment {
+            let values: Option<Vec<_>> = remaining
+                .iter()
+                .filter(|segment| !segment.is_empty())
+                .map(|segment| decode_path_segment(segment))
+                .collect();
+            params.push((name.clone(), values?.join("/")));
+            remaining = &[];
+            continue;
+        }
+
+        let (segment, rest) = remaining.split_first()?;
+        remaining = rest;
+        match pattern_segment {
+            PatternSegment::Dynamic { name, suffix } => {
+                let decoded = decode_path_segment(segment)?;
+                let value = decoded.strip_suffix(suffix.as_str())?;
+                if value.is_empty() {
+                    return None;
+                }
+                params.push((name.clone(), value.to_string()));
+            }
+            PatternSegment::Static(expected) => {
+                let distance = edit_distance(expected, segment);
+                // Allow roughly one typo every three characters
+                if distance > (expected.chars().count() / 3).max(1) {
+                    return None;
+                }
+                edits += distance;
+            }
+            PatternSegment::CatchAll(_) => unreachable!("catch-alls are handled above"),
+        }
+    }
+
+    remaining.is_empty().then_some(Candidate {
+        pattern,
+        edits,
+        params,
+    })
+}
+
+/// Get the suggestions for the path that is rendered as a not found page, or `None` outside of one.
+pub fn use_not_found_context<R: Routable + 'static>(cx: &ScopeState) -> Option<NotFoundContext<R>>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    cx.consume_context()
+}
+
+/// Get the suggestions for the current location of the nearest router with the route type `R` while it is at the
+/// `#[route(not_found)]` variant, with at most three suggestions. Returns `None` at any other route and outside of a
+/// router.
+pub fn use_last_match_error<R: Routable + 'static>(cx: &ScopeState) -> Option<NotFoundContext<R>>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let router = use_router::<R>(cx)?.router().borrow();
+    router
+        .route
+        .is_not_found()
+        .then(|| NotFoundContext::new(&router.current_url(), 3))
+}
+
+#[test]
+fn suggestions() {
+    use crate::{AssetRoute, Route};
+
+    let not_found = NotFoundContext::<Route>::new("/serch/rust/2/stars", 3);
+    assert_eq!(
+        not_found.closest_patterns,
+        ["/search/(query)/(page)/(sort)"]
+    );
+    assert_eq!(
+        not_found.suggestions,
+        [Route::Search {
+            query: "rust".to_string(),
+            page: 2,
+            sort: "stars".to_string(),
+        }]
+    );
+
+    // One typo away from one route and a fit for the dynamic segments of another, but only the first can be built,
+    // since `hello_wrld` is not a number
+    let not_found = NotFoundContext::<Route>::new("/hello_wrld/3?ref=mail", 3);
+    assert_eq!(
+        not_found.closest_patterns,
+        ["/hello_world/(dynamic)", "/(number1)/(number2)"]
+    );
+    assert_eq!(not_found.suggestions, [Route::Route3 { dynamic: 3 }]);
+
+    let not_found = NotFoundContext::<Route>::new("/hello_wrld/3", 1);
+    assert_eq!(not_found.closest_patterns, ["/hello_world/(dynamic)"]);
+
+    // Action routes are never suggested
+    let not_found = NotFoundContext::<crate::LoopRoute>::new("/pimg", 3);
+    assert_eq!(not_found.closest_patterns, ["/ping"]);
+    assert!(not_found.suggestions.is_empty());
+
+    let not_found = NotFoundContext::<AssetRoute>::new("/avatr/12.png", 3);
+    assert_eq!(
+        not_found.suggestions,
+        [
+            AssetRoute::Avatar { user_id: 12 },
+            AssetRoute::AvatarFile {
+                file: "12.png".to_string()
+            }
+        ]
+    );
+
+    let not_found = NotFoundContext::<Route>::new("/nothing/like/any/route/at/all", 3);
+    assert!(not_found.closest_patterns.is_empty());
+    assert!(not_found.suggestions.is_empty());
+}
+
+#[test]
+fn last_match_error() {
+    use crate::context::{use_router_provider, RouterId};
+    use crate::Router;
+    use dioxus_router_core::history::MemoryHistory;
+
+    #[inline_props]
+    #[allow(non_snake_case)]
+    fn Site(cx: Scope, path: &'static str) -> Element {
+        use_router_provider(cx, RouterId("site"), || {
+            Router::<SiteRoute>::new(MemoryHistory::with_initial_path(path).unwrap()).unwrap()
+        });
+        render! { Suggestions {} }
+    }
+
+    #[allow(non_snake_case)]
+    fn Suggestions(cx: Scope) -> Element {
+        match use_last_match_error::<SiteRoute>(cx) {
+            Some(error) => {
+                let patterns = error.closest_patterns.join(" ");
+                let suggestions: Vec<_> = error
+                    .suggestions
+                    .iter()
+                    .map(|route| route.to_string())
+                    .collect();
+                let suggestions = suggestions.join(" ");
+                render! { "{error.path}: {patterns}, {suggestions}" }
+            }
+            None => render! { "matched" },
+        }
+    }
+
+    let render = |path| {
+        let mut vdom = VirtualDom::new_with_props(Site, SiteProps { path });
+        let _ = vdom.rebuild();
+        dioxus_ssr::render(&vdom)
+    };
+    assert_eq!(render("/blog/3"), "matched");
+    assert_eq!(
+        render("/blgo/4?ref=mail"),
+        "/blgo/4?ref=mail: /blog/(id), /blog/4"
+    );
+    assert_eq!(render("/blog/latest"), "/blog/latest: /blog/(id), ");
+}
+
+/// A site that renders a page for locations no route matches instead of failing to parse them
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum SiteRoute {
+    #[route("/" SiteHome)]
+    SiteHome {},
+    #[route("/blog/(id)" SitePost)]
+    SitePost { id: u32 },
+    #[route(not_found SiteNotFound)]
+    SiteNotFound { path: Vec<String> },
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn SiteHome(cx: Scope) -> Element {
+    render! { "Home" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn SitePost(cx: Scope, id: u32) -> Element {
+    render! { "Post {id}" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn SiteNotFound(cx: Scope, path: Vec<String>) -> Element {
+    let path = path.join("/");
+    render! { "Nothing at /{path}" }
+}
+
+#[test]
+fn not_found_routes() {
+    let post = SiteRoute::SitePost { id: 3 };
+    assert_eq!(SiteRoute::from_str("/blog/3"), Ok(post.clone()));
+    assert_eq!(
+        SiteRoute::from_str("https://example.com/blog/3?x=1"),
+        Ok(post.clone())
+    );
+    assert_eq!(SiteRoute::from_str("/blog/3#comments"), Ok(post));
+    assert_eq!(
+        SiteRoute::from_str("https://example.com"),
+        Ok(SiteRoute::SiteHome {})
+    );
+
+    let missing = SiteRoute::from_str("https://example.com/blog/latest?x=1").unwrap();
+    assert_eq!(
+        missing,
+        SiteRoute::SiteNotFound {
+            path: vec!["blog".to_string(), "latest".to_string()]
+        }
+    );
+    assert_eq!(missing.to_string(), "/blog/latest");
+    assert_eq!(missing.status_hint(), 404);
+    assert!(missing.is_not_found());
+    assert!(!SiteRoute::SiteHome {}.is_not_found());
+
+    // The fallback gets every segment of the path decoded, and displays them encoded again, so the path it shows
+    // can be copied back into the address bar
+    let decoded = SiteRoute::from_str("/blog/caf%C3%A9/2023%20notes?ref=mail").unwrap();
+    assert_eq!(
+        decoded,
+        SiteRoute::SiteNotFound {
+            path: vec![
+                "blog".to_string(),
+                "café".to_string(),
+                "2023 notes".to_string()
+            ]
+        }
+    );
+    assert_eq!(decoded.to_string(), "/blog/caf%C3%A9/2023%20notes");
+    assert_eq!(SiteRoute::from_str(&decoded.to_string()), Ok(decoded));
+    assert!(SiteRoute::try_parse("/blog/latest").is_err());
+    assert!(!SiteRoute::ROUTE_PATTERNS.contains(&"/(...path)"));
+    assert_eq!(SiteRoute::SITE_MAP.len(), 2);
+
+    // Spans of the detailed error still point into the whole url
+    let url = "https://example.com/blog/latest";
+    let err = SiteRoute::try_parse(url).unwrap_err();
+    let post_error = err
+        .attempted_routes
+        .iter()
+        .find(|err| matches!(err, SiteRouteMatchError::SitePost { .. }))
+        .unwrap();
+    assert_eq!(&url[post_error.span()], "latest");
+}
+
+#[test]
+fn no_return_to_not_found() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    // Every path parses as the fallback variant, so only the router's other routes are returned to
+    let history = MemoryHistory::with_initial_path("/?next=/nowhere").unwrap();
+    let mut router = Router::<SiteRoute>::new(history).unwrap();
+    router.pop_return("next", SiteRoute::SitePost { id: 1 });
+    assert_eq!(router.route, SiteRoute::SitePost { id: 1 });
+}
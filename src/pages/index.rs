@@ -1,6 +1,5 @@
 use dioxus::prelude::*;
 
-#[inline_props]
 pub fn Route5(cx: Scope) -> Element {
     render! {div {
         "Route5 {{}}"
@@ -0,0 +1,164 @@
+//! The entries of a command palette, like a ⌘K menu that jumps to any page.
+//!
+//! Every route without dynamic segments is listed unless it is declared with `palette = false`. Routes with dynamic
+//! segments are listed with the values their `palette_provider = ...` function returns, like recent projects. The
+//! UI only renders the list: [`use_command_palette`] searches the entries and navigates to the one that is picked.
+
+use crate::context::use_router;
+use crate::{Routable, Router};
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// A route listed in the command palette, with what the palette shows for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteEntry<R> {
+    /// The route the entry navigates to
+    pub route: R,
+    /// The title of the page from `title = "..."`, or its path for pages without one
+    pub title: String,
+    pub path: String,
+    pub pattern: &'static str,
+    /// The name of the icon from `icon = "..."`
+    pub icon: Option<&'static str>,
+    /// The cargo feature of a route declared with `feature = "..."`. Only routes whose feature is enabled are listed.
+    pub feature: Option<&'static str>,
+}
+
+impl<R: Routable> PaletteEntry<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    pub fn new(route: R) -> Self {
+        let path = route.to_string();
+        let pattern = route.pattern();
+        Self {
+            title: route.head().title.unwrap_or_else(|| path.clone()),
+            path,
+            pattern,
+            icon: route.palette_icon(),
+            feature: R::gated_routes()
+                .iter()
+                .find(|gated| gated.pattern == pattern)
+                .map(|gated| gated.feature),
+            route,
+        }
+    }
+}
+
+/// How well `query` matches `text`, or `None` if it doesn't. Every character of the query except spaces must appear
+/// in the text in the same order, ignoring case. Lower scores are better: the score is where the match starts plus
+/// the characters it skips.
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = next + text[next..].iter().position(|t| *t == c)?;
+        score += found - next;
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// The entries whose title or pattern matches `query`, best match first. Entries that match equally well stay in
+/// the order of `entries`, and an empty query matches every entry.
+pub fn search<'a, R>(entries: &'a [PaletteEntry<R>], query: &str) -> Vec<&'a PaletteEntry<R>> {
+    let mut matches: Vec<_> = entries
+        .iter()
+        .filter_map(|entry| {
+            let score = [entry.title.as_str(), entry.pattern]
+                .into_iter()
+                .filter_map(|text| fuzzy_score(query, text))
+                .min()?;
+            Some((score, entry))
+        })
+        .collect();
+    matches.sort_by_key(|(score, _)| *score);
+    matches.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// The command palette of the nearest router, returned by [`use_command_palette`].
+pub struct CommandPalette<R: Routable>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    router: Rc<RefCell<Router<R>>>,
+    entries: RefCell<Vec<PaletteEntry<R>>>,
+}
+
+impl<R: Routable> CommandPalette<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Every entry, in the order the routes were declared
+    pub fn entries(&self) -> Vec<PaletteEntry<R>> {
+        self.entries.borrow().clone()
+    }
+
+    /// The entries matching `query`, best match first. See [`search`].
+    pub fn search(&self, query: &str) -> Vec<PaletteEntry<R>> {
+        search(&self.entries.borrow(), query)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Navigate to the route of an entry.
+    pub fn select(&self, entry: &PaletteEntry<R>) {
+        self.router.borrow_mut().push(entry.route.clone());
+    }
+
+    /// Build the entries again, for palette providers whose routes changed since the palette was created, like a
+    /// list of recent projects.
+    pub fn refresh(&self) {
+        *self.entries.borrow_mut() = R::palette_entries();
+    }
+}
+
+/// A command palette for the nearest router with the route type `R`. The entries are built once, when the
+/// component is first rendered; call [`CommandPalette::refresh`] to build them again.
+pub fn use_command_palette<R: Routable + 'static>(cx: &ScopeState) -> Option<&CommandPalette<R>>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let router = use_router::<R>(cx)?.router().clone();
+    Some(cx.use_hook(|| CommandPalette {
+        router,
+        entries: RefCell::new(R::palette_entries()),
+    }))
+}
+
+#[test]
+fn palette_entries() {
+    use crate::Route;
+
+    let (gated, entries): (Vec<_>, Vec<_>) = Route::palette_entries()
+        .into_iter()
+        .partition(|entry| entry.feature.is_some());
+    // The route table is only listed in builds with its feature
+    assert_eq!(gated.len(), usize::from(cfg!(feature = "devtools")));
+    let paths: Vec<_> = entries.iter().map(|entry| entry.path.as_str()).collect();
+    // Logout has `palette = false`, and Search is listed with the routes of its provider
+    assert_eq!(paths, ["/hello_world", "/", "/search/rust/1/new"]);
+    assert_eq!(entries[0].title, "/hello_world");
+    assert_eq!(entries[1].title, "Home");
+    assert_eq!(entries[1].icon, Some("home"));
+    assert_eq!(entries[2].title, "Results for rust");
+    assert_eq!(entries[2].pattern, "/search/(query)/(page)/(sort)");
+
+    let titles = |query| -> Vec<_> {
+        search(&entries, query)
+            .into_iter()
+            .map(|entry| entry.title.as_str())
+            .collect()
+    };
+    assert_eq!(titles(""), ["/hello_world", "Home", "Results for rust"]);
+    assert_eq!(titles("hm"), ["Home"]);
+    assert_eq!(titles("HELLO"), ["/hello_world"]);
+    // Matches in the pattern count as well, and the closer match comes first
+    assert_eq!(titles("search"), ["Results for rust"]);
+    assert_eq!(titles("ho"), ["Home", "/hello_world", "Results for rust"]);
+    assert!(titles("xyz").is_empty());
+}
@@ -0,0 +1,399 @@
+//! Route patterns matched at runtime, for tools that check paths against a route table without deriving anything,
+//! like a CLI that validates a redirect map.
+//!
+//! A [`RoutePattern`] matches paths like a derived route whose fields are all strings: dynamic segments match any
+//! non-empty value that decodes to UTF-8, suffixes are checked after percent-decoding, a single trailing slash is
+//! ignored and catch-alls match zero or more segments. Segment types and `#[route_segment(...)]` options are not
+//! part of a pattern, so a path can match a pattern even if a route would fail to parse its segments.
+
+use crate::encoding::{decode_path_segment, EncodedSegment};
+
+/// A parsed route pattern, like `/blog/(slug)` or `/files/(...path)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutePattern {
+    segments: Vec<PatternSegment>,
+}
+
+/// One segment of a [`RoutePattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternSegment {
+    /// A segment that must be exactly this text
+    Static(String),
+    /// `(name)`, or `(name).ext` with a suffix the segment must end with
+    Dynamic { name: String, suffix: String },
+    /// `(...name)`, which matches the rest of the path
+    CatchAll(String),
+}
+
+/// The reason a pattern was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    /// The pattern does not start with `/`.
+    MissingLeadingSlash,
+    /// A catch-all segment is followed by more segments.
+    CatchAllNotLast,
+    /// A catch-all segment has a suffix.
+    CatchAllSuffix(String),
+    /// A segment captures more than one value, like `(a)-(b)`.
+    MultipleCaptures(String),
+    /// The same name is captured twice.
+    DuplicateSegment(String),
+}
+
+impl std::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingLeadingSlash => write!(f, "Routes should start with /"),
+            Self::CatchAllNotLast => {
+                write!(
+                    f,
+                    "Catch-all route segments must be the last segment in a route"
+                )
+            }
+            Self::CatchAllSuffix(segment) => {
+                write!(f, "Catch-all segments can't have a suffix: '{segment}'")
+            }
+            Self::MultipleCaptures(segment) => {
+                write!(
+                    f,
+                    "A segment can only capture one dynamic value: '{segment}'"
+                )
+            }
+            Self::DuplicateSegment(name) => write!(f, "The segment '{name}' is captured twice"),
+        }
+    }
+}
+
+/// The value of a dynamic or catch-all segment of a matched path, percent-decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamValue {
+    /// The value of a dynamic segment, without its suffix
+    Segment(String),
+    /// The segments matched by a catch-all
+    Segments(Vec<String>),
+}
+
+/// The values of the dynamic and catch-all segments of a matched path, in the order they appear in the pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Params {
+    params: Vec<(String, ParamValue)>,
+}
+
+impl Params {
+    /// The value of the segment `name`
+    pub fn get(&self, name: &str) -> Option<&ParamValue> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Every segment name and value, in the order they appear in the pattern
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ParamValue)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+}
+
+impl RoutePattern {
+    /// Parse a pattern with the syntax of `#[route(...)]`.
+    pub fn parse(pattern: &str) -> Result<Self, PatternError> {
+        let rest = pattern
+            .strip_prefix('/')
+            .ok_or(PatternError::MissingLeadingSlash)?;
+
+        let mut segments = Vec::new();
+        let mut names: Vec<String> = Vec::new();
+        for segment in rest.split('/') {
+            if matches!(segments.last(), Some(PatternSegment::CatchAll(_))) {
+                return Err(PatternError::CatchAllNotLast);
+            }
+            let parsed = match segment
+                .strip_prefix('(')
+                .and_then(|segment| segment.split_once(')'))
+            {
+                Some((capture, suffix)) => {
+                    if suffix.contains(['(', ')']) {
+                        return Err(PatternError::MultipleCaptures(segment.to_string()));
+                    }
+                    let (name, parsed) = match capture.strip_prefix("...") {
+                        Some(_) if !suffix.is_empty() => {
+                            return Err(PatternError::CatchAllSuffix(segment.to_string()));
+                        }
+                        Some(name) => (name, PatternSegment::CatchAll(name.to_string())),
+                        None => (
+                            capture,
+                            PatternSegment::Dynamic {
+                                name: capture.to_string(),
+                                suffix: suffix.to_string(),
+                            },
+                        ),
+                    };
+                    if names.iter().any(|other| other == name) {
+                        return Err(PatternError::DuplicateSegment(name.to_string()));
+                    }
+                    names.push(name.to_string());
+                    parsed
+                }
+                None => PatternSegment::Static(segment.to_string()),
+            };
+            segments.push(parsed);
+        }
+        // `/` is the root route, not a route with one empty segment
+        if segments == [PatternSegment::Static(String::new())] {
+            segments.clear();
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// The segments of the pattern, without the root `/`
+    pub fn segments(&self) -> &[PatternSegment] {
+        &self.segments
+    }
+
+    /// Build a path from the values of the dynamic segments, percent-encoding them like a derived route would. A
+    /// catch-all takes its segments joined with `/`. Returns `None` if a segment has no value.
+    pub fn fill(&self, params: &[(&str, &str)]) -> Option<String> {
+        let value = |name: &str| {
+            params
+                .iter()
+                .find(|(param, _)| *param == name)
+                .map(|(_, value)| *value)
+        };
+        let mut path = String::new();
+        for segment in &self.segments {
+            match segment {
+                PatternSegment::Static(segment) => path.push_str(&format!("/{segment}")),
+                PatternSegment::Dynamic { name, suffix } => {
+                    path.push_str(&format!("/{}{suffix}", EncodedSegment(value(name)?)));
+                }
+                PatternSegment::CatchAll(name) => {
+                    for segment in value(name)?
+                        .split('/')
+                        .filter(|segment| !segment.is_empty())
+                    {
+                        path.push_str(&format!("/{}", EncodedSegment(segment)));
+                    }
+                }
+            }
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        Some(path)
+    }
+
+    /// Check if two patterns match exactly the same paths: the same static segments, and dynamic segments with the
+    /// same suffixes and catch-alls in the same places. The names of the segments don't matter.
+    pub fn conflicts_with(&self, other: &RoutePattern) -> bool {
+        self.segments.len() == other.segments.len()
+            && self
+                .segments
+                .iter()
+                .zip(&other.segments)
+                .all(|pair| match pair {
+                    (PatternSegment::Static(a), PatternSegment::Static(b)) => a == b,
+                    (
+                        PatternSegment::Dynamic { suffix: a, .. },
+                        PatternSegment::Dynamic { suffix: b, .. },
+                    ) => a == b,
+                    (PatternSegment::CatchAll(_), PatternSegment::CatchAll(_)) => true,
+                    _ => false,
+                })
+    }
+
+    /// Match a path without its query, returning the values of its dynamic segments. Returns `None` if the path
+    /// doesn't match, a dynamic segment is empty or a segment isn't valid percent-encoding for UTF-8.
+    pub fn matches(&self, path: &str) -> Option<Params> {
+        self.try_match(path).ok()
+    }
+
+    /// Match a path like [`RoutePattern::matches`], returning the first segment of the path that didn't match if it
+    /// doesn't.
+    pub fn try_match(&self, path: &str) -> Result<Params, Divergence> {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let mut remaining: Vec<&str> = path.split('/').collect();
+        // A trailing slash is ignored, like in the generated parsers
+        if remaining.last() == Some(&"") {
+            remaining.pop();
+        }
+
+        let mut params = Params::default();
+        let mut remaining = remaining.as_slice();
+        for (index, segment) in self.segments.iter().enumerate() {
+            let diverged = |reason| Divergence {
+                segment: index,
+                reason,
+            };
+            if let PatternSegment::CatchAll(name) = segment {
+                let values = remaining
+                    .iter()
+                    .map(|segment| decode_path_segment(segment).map(|decoded| decoded.into_owned()))
+                    .collect::<Option<_>>()
+                    .ok_or(diverged(DivergenceReason::InvalidEncoding))?;
+                params
+                    .params
+                    .push((name.clone(), ParamValue::Segments(values)));
+                return Ok(params);
+            }
+
+            let (first, rest) = remaining
+                .split_first()
+                .ok_or(diverged(DivergenceReason::MissingSegment))?;
+            remaining = rest;
+            match segment {
+                PatternSegment::Static(expected) => {
+                    if first != expected {
+                        return Err(diverged(DivergenceReason::Static(first.to_string())));
+                    }
+                }
+                PatternSegment::Dynamic { name, suffix } => {
+                    let decoded = decode_path_segment(first)
+                        .ok_or(diverged(DivergenceReason::InvalidEncoding))?;
+                    let value = decoded.strip_suffix(suffix.as_str()).ok_or_else(|| {
+                        diverged(DivergenceReason::MissingSuffix(decoded.to_string()))
+                    })?;
+                    if value.is_empty() {
+                        return Err(diverged(DivergenceReason::EmptySegment));
+                    }
+                    params
+                        .params
+                        .push((name.clone(), ParamValue::Segment(value.to_string())));
+                }
+                PatternSegment::CatchAll(_) => unreachable!("catch-alls return above"),
+            }
+        }
+
+        match remaining.is_empty() {
+            true => Ok(params),
+            false => Err(Divergence {
+                segment: self.segments.len(),
+                reason: DivergenceReason::ExtraSegments(remaining.len()),
+            }),
+        }
+    }
+}
+
+/// Where a path stopped matching a [`RoutePattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// The index of the segment of the pattern, without the root `/`. For extra segments, the number of segments
+    /// of the pattern.
+    pub segment: usize,
+    pub reason: DivergenceReason,
+}
+
+/// Why a segment of a path didn't match a segment of a [`RoutePattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DivergenceReason {
+    /// The path has a different static segment, this one
+    Static(String),
+    /// The path ended before the pattern
+    MissingSegment,
+    /// The path has this many more segments than the pattern
+    ExtraSegments(usize),
+    /// The value of a dynamic segment doesn't end with its suffix. The value is percent-decoded
+    MissingSuffix(String),
+    /// A dynamic segment has no value besides its suffix
+    EmptySegment,
+    /// A segment isn't valid percent-encoding for UTF-8
+    InvalidEncoding,
+}
+
+impl std::fmt::Display for DivergenceReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Static(segment) => write!(f, "found '{segment}'"),
+            Self::MissingSegment => write!(f, "the path ends here"),
+            Self::ExtraSegments(count) => write!(f, "the path has {count} more segments"),
+            Self::MissingSuffix(value) => write!(f, "'{value}' is missing the suffix"),
+            Self::EmptySegment => write!(f, "the segment is empty"),
+            Self::InvalidEncoding => write!(f, "the segment isn't valid percent-encoding"),
+        }
+    }
+}
+
+#[test]
+fn runtime_patterns() {
+    let pattern = RoutePattern::parse("/avatar/(user_id).png").unwrap();
+    let params = pattern.matches("/avatar/ferris%20crab.png/").unwrap();
+    assert_eq!(
+        params.get("user_id"),
+        Some(&ParamValue::Segment("ferris crab".to_string()))
+    );
+    assert_eq!(pattern.matches("/avatar/ferris.jpg"), None);
+    assert_eq!(pattern.matches("/avatar/.png"), None);
+    assert_eq!(pattern.matches("/avatar/ferris.png/extra"), None);
+    assert_eq!(
+        pattern.try_match("/avatar/ferris.jpg"),
+        Err(Divergence {
+            segment: 1,
+            reason: DivergenceReason::MissingSuffix("ferris.jpg".to_string())
+        })
+    );
+    assert_eq!(
+        pattern
+            .try_match("/avatars/ferris.png/extra")
+            .unwrap_err()
+            .segment,
+        0
+    );
+    assert_eq!(
+        pattern
+            .try_match("/avatar/ferris.png/extra")
+            .unwrap_err()
+            .reason,
+        DivergenceReason::ExtraSegments(1)
+    );
+
+    let pattern = RoutePattern::parse("/files/(...path)").unwrap();
+    assert_eq!(
+        pattern.matches("/files/a/b%2Fc").unwrap().get("path"),
+        Some(&ParamValue::Segments(vec![
+            "a".to_string(),
+            "b/c".to_string()
+        ]))
+    );
+    assert_eq!(
+        pattern.matches("/files").unwrap().get("path"),
+        Some(&ParamValue::Segments(Vec::new()))
+    );
+
+    let pattern = RoutePattern::parse("/users/(id)/files/(...path)").unwrap();
+    assert_eq!(
+        pattern.fill(&[("id", "a b"), ("path", "docs/read me.md")]),
+        Some("/users/a%20b/files/docs/read%20me.md".to_string())
+    );
+    assert_eq!(pattern.fill(&[("id", "1")]), None);
+    assert!(pattern.conflicts_with(&RoutePattern::parse("/users/(user)/files/(...rest)").unwrap()));
+    assert!(!pattern.conflicts_with(&RoutePattern::parse("/users/(id)/files").unwrap()));
+
+    let root = RoutePattern::parse("/").unwrap();
+    assert_eq!(root.fill(&[]), Some("/".to_string()));
+    assert!(root.segments().is_empty());
+    assert!(root.matches("/").is_some());
+    assert!(root.matches("/a").is_none());
+
+    assert_eq!(
+        RoutePattern::parse("a/(b)"),
+        Err(PatternError::MissingLeadingSlash)
+    );
+    assert_eq!(
+        RoutePattern::parse("/(...a)/b"),
+        Err(PatternError::CatchAllNotLast)
+    );
+    assert_eq!(
+        RoutePattern::parse("/(...a).png"),
+        Err(PatternError::CatchAllSuffix("(...a).png".to_string()))
+    );
+    assert_eq!(
+        RoutePattern::parse("/(a)-(b)"),
+        Err(PatternError::MultipleCaptures("(a)-(b)".to_string()))
+    );
+    assert_eq!(
+        RoutePattern::parse("/(a)/(a)"),
+        Err(PatternError::DuplicateSegment("a".to_string()))
+    );
+}
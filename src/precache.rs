@@ -0,0 +1,99 @@
+use std::fmt::Write;
+
+/// The routes a service worker should cache ahead of time and the routes it should always fetch, generated by
+/// `precache_manifest` on every routable type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrecacheManifest {
+    /// The paths of static routes that can be cached
+    pub precache: Vec<String>,
+    /// The glob patterns of routes that must hit the network, like `/blog/*` or `/files/**`
+    pub network_only_patterns: Vec<&'static str>,
+    /// The `ROUTES_HASH` of the routes, which changes when the routes do
+    pub revision: &'static str,
+}
+
+impl PrecacheManifest {
+    /// Serialize the manifest as JSON in the shape Workbox expects: `precache` is a list of `{ "url", "revision" }`
+    /// entries for `precacheAndRoute`, and `networkOnly` lists the patterns to register with a `NetworkOnly` strategy.
+    ///
+    /// Every page has the manifest's revision, so Workbox refetches the precached pages when the routes change. Pages
+    /// whose content changed without the routes still need the service worker to be rebuilt.
+    pub fn to_workbox_json(&self) -> String {
+        let mut json = String::from("{\"precache\":[");
+        for (i, url) in self.precache.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"url\":");
+            write_json_string(&mut json, url);
+            json.push_str(",\"revision\":");
+            write_json_string(&mut json, self.revision);
+            json.push('}');
+        }
+        json.push_str("],\"networkOnly\":[");
+        for (i, pattern) in self.network_only_patterns.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write_json_string(&mut json, pattern);
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[test]
+fn workbox_json() {
+    let manifest = PrecacheManifest {
+        precache: vec!["/pricing".to_string(), "/say \"hi\"".to_string()],
+        network_only_patterns: vec!["/blog/*", "/files/**"],
+        revision: "5f0e2c8a9b1d3e47",
+    };
+    assert_eq!(
+        manifest.to_workbox_json(),
+        r#"{"precache":[{"url":"/pricing","revision":"5f0e2c8a9b1d3e47"},{"url":"/say \"hi\"","revision":"5f0e2c8a9b1d3e47"}],"networkOnly":["/blog/*","/files/**"]}"#
+    );
+}
+
+#[test]
+fn precache_manifest() {
+    use crate::{AssetRoute, Route};
+
+    assert_eq!(
+        Route::precache_manifest(),
+        PrecacheManifest {
+            precache: vec!["/hello_world".to_string()],
+            network_only_patterns: vec![
+                "/hello_world/*",
+                "/",
+                "/files/**",
+                "/docs/**",
+                "/gone/*",
+                "/search/*/*/*",
+                "/debug/routes",
+                "/*",
+                "/*/*",
+            ],
+            revision: Route::ROUTES_HASH,
+        }
+    );
+    assert_eq!(
+        AssetRoute::precache_manifest().network_only_patterns,
+        ["/feed.xml", "/avatar/*.png", "/avatar/*"]
+    );
+}
@@ -0,0 +1,256 @@
+//! Queries in route patterns: `?(name)` after the path, like `query` in `/search?(query)`.
+//!
+//! The parser splits the location at its first `?` before it splits the path into segments, so a `/` in the query
+//! is part of the query. A `#(fragment)` after the query is split off before that. The raw query after the `?`,
+//! still percent-encoded, goes to the [`FromQuery`] of the field. A location without a query, or with nothing after
+//! the `?`, parses the field from an empty string, so a type that accepts an empty query makes the query optional.
+//! Display writes the path and then `?` and the [`ToQuery`] string, and nothing after the path if the string is
+//! empty.
+//!
+//! [`String`] takes the query as it is. [`QueryParams`] decodes it into `(key, value)` pairs.
+
+#[cfg(test)]
+use super::*;
+use crate::encoding::{decode_query_component, encode_query_component};
+use std::fmt;
+
+/// A type that can be parsed from the query of a location.
+pub trait FromQuery: Sized {
+    type Err: fmt::Display;
+
+    /// Parse the raw query, without the `?`
+    fn from_query(query: &str) -> Result<Self, Self::Err>;
+}
+
+/// A type that can be written as the query of a location.
+pub trait ToQuery {
+    /// The query without the `?`, percent-encoded
+    fn to_query_string(&self) -> String;
+
+    /// If the query isn't empty. Routes leave out the `?` before an empty query.
+    fn has_query(&self) -> bool {
+        !self.to_query_string().is_empty()
+    }
+}
+
+/// Displays a query with [`ToQuery::to_query_string`].
+pub struct DisplayQuery<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ToQuery + ?Sized> fmt::Display for DisplayQuery<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.to_query_string())
+    }
+}
+
+impl FromQuery for String {
+    type Err = std::convert::Infallible;
+
+    fn from_query(query: &str) -> Result<Self, Self::Err> {
+        Ok(query.to_string())
+    }
+}
+
+impl ToQuery for String {
+    fn to_query_string(&self) -> String {
+        self.clone()
+    }
+
+    fn has_query(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+/// The decoded `key=value` pairs of a query, in the order they appear, for routes that read them by key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct QueryParams(pub Vec<(String, String)>);
+
+impl QueryParams {
+    /// The value of the first pair with this key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find_map(|(k, value)| (k == key).then_some(value.as_str()))
+    }
+}
+
+/// A key or value of a query that isn't valid percent-encoded UTF-8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidQuery(pub String);
+
+impl fmt::Display for InvalidQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not valid percent-encoded UTF-8", self.0)
+    }
+}
+
+impl FromQuery for QueryParams {
+    type Err = InvalidQuery;
+
+    fn from_query(query: &str) -> Result<Self, Self::Err> {
+        let decode =
+            |part: &str| decode_query_component(part).ok_or_else(|| InvalidQuery(part.to_string()));
+        let mut pairs = Vec::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            pairs.push((decode(key)?, decode(value)?));
+        }
+        Ok(Self(pairs))
+    }
+}
+
+impl ToQuery for QueryParams {
+    fn to_query_string(&self) -> String {
+        let pairs: Vec<_> = self
+            .0
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}={}",
+                    encode_query_component(key),
+                    encode_query_component(value)
+                )
+            })
+            .collect();
+        pairs.join("&")
+    }
+}
+
+#[test]
+fn query_params() {
+    let params = QueryParams::from_query("q=milk+and%20honey&page=2&&flag").unwrap();
+    assert_eq!(params.get("q"), Some("milk and honey"));
+    assert_eq!(params.get("page"), Some("2"));
+    assert_eq!(params.get("flag"), Some(""));
+    assert_eq!(params.get("sort"), None);
+    assert_eq!(
+        QueryParams::from_query(&params.to_query_string()),
+        Ok(params)
+    );
+    assert_eq!(QueryParams::from_query(""), Ok(QueryParams::default()));
+    assert!(!QueryParams::default().has_query());
+    assert_eq!(
+        QueryParams::from_query("q=%E0%A4"),
+        Err(InvalidQuery("%E0%A4".to_string()))
+    );
+}
+
+/// The catalog keeps its search terms and the page of an item in the query
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+pub enum CatalogRoute {
+    #[route("/" CatalogHome)]
+    CatalogHome {},
+    #[route("/catalog?(params)" Catalog)]
+    Catalog { params: QueryParams },
+    #[route("/catalog/(id)?(page)" CatalogItem)]
+    CatalogItem { id: u32, page: PageQuery },
+}
+
+/// `page=2` in the query, or the first page without a query
+#[cfg(test)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageQuery(pub u32);
+
+#[cfg(test)]
+impl FromQuery for PageQuery {
+    type Err = String;
+
+    fn from_query(query: &str) -> Result<Self, Self::Err> {
+        match query.strip_prefix("page=") {
+            Some(page) => page
+                .parse()
+                .map(Self)
+                .map_err(|_| format!("'{page}' is not a page")),
+            None if query.is_empty() => Ok(Self(1)),
+            None => Err(format!("expected 'page=', found '{query}'")),
+        }
+    }
+}
+
+#[cfg(test)]
+impl ToQuery for PageQuery {
+    fn to_query_string(&self) -> String {
+        match self.0 {
+            1 => String::new(),
+            page => format!("page={page}"),
+        }
+    }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn CatalogHome(cx: Scope) -> Element {
+    render! { "Catalog" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn Catalog(cx: Scope, params: QueryParams) -> Element {
+    render! { "Catalog {params:?}" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn CatalogItem(cx: Scope, id: u32, page: PageQuery) -> Element {
+    render! { "Item {id} page {page.0}" }
+}
+
+#[test]
+fn query_patterns() {
+    let catalog = |pairs: &[(&str, &str)]| CatalogRoute::Catalog {
+        params: QueryParams(
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        ),
+    };
+    assert_eq!(
+        CatalogRoute::from_str("/catalog?q=milk&page=2"),
+        Ok(catalog(&[("q", "milk"), ("page", "2")]))
+    );
+    assert_eq!(
+        catalog(&[("q", "milk"), ("page", "2")]).to_string(),
+        "/catalog?q=milk&page=2"
+    );
+    // No query and an empty query both parse as an empty one, which is written without the `?`
+    assert_eq!(CatalogRoute::from_str("/catalog"), Ok(catalog(&[])));
+    assert_eq!(CatalogRoute::from_str("/catalog?"), Ok(catalog(&[])));
+    assert_eq!(catalog(&[]).to_string(), "/catalog");
+    // The query is split off before the path, so its slashes aren't segments
+    assert_eq!(
+        CatalogRoute::from_str("/catalog?path=a/b/c"),
+        Ok(catalog(&[("path", "a/b/c")]))
+    );
+    assert_eq!(
+        CatalogRoute::from_str("/?q=milk"),
+        Ok(CatalogRoute::CatalogHome {})
+    );
+
+    let item = |page| CatalogRoute::CatalogItem {
+        id: 7,
+        page: PageQuery(page),
+    };
+    assert_eq!(CatalogRoute::from_str("/catalog/7?page=3"), Ok(item(3)));
+    assert_eq!(CatalogRoute::from_str("/catalog/7"), Ok(item(1)));
+    assert_eq!(item(3).to_string(), "/catalog/7?page=3");
+    assert_eq!(item(1).to_string(), "/catalog/7");
+    let err = CatalogRoute::from_str("/catalog/7?page=x").unwrap_err();
+    assert!(err
+        .attempted_routes
+        .contains(&CatalogRouteMatchError::CatalogItem {
+            error: CatalogItemParseError::pageParseError("'x' is not a page".to_string()),
+            span: 10..17,
+        }));
+
+    // The router hands the query to the route and doesn't add it again
+    use dioxus_router_core::history::MemoryHistory;
+    let history = MemoryHistory::with_initial_path("/catalog/7?page=2").unwrap();
+    let mut router = Router::<CatalogRoute>::new(history).unwrap();
+    assert_eq!(router.route, item(2));
+    router.push(item(4));
+    assert_eq!(router.current_url().as_ref(), "/catalog/7?page=4");
+}
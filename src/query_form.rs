@@ -0,0 +1,137 @@
+//! Forms that navigate to a route with a typed query, like a search form that goes to `/search?q=rust&page=1`.
+//!
+//! [`QueryForm`] is a `GET` form whose `action` is the path of its route, so without JavaScript the browser submits
+//! it like any other form and the route parses the query on the next page load. With JavaScript, a submit builds the
+//! [`FromQuery`] type of the route from the named fields of the form and pushes the route with the [`Navigator`]
+//! instead, so the form and the url can't drift apart.
+//!
+//! [`Navigator`]: crate::navigator::Navigator
+
+use crate::context::use_router;
+use crate::encoding::encode_query_component;
+use crate::navigator::use_navigator;
+use crate::query::FromQuery;
+use crate::Routable;
+use dioxus::prelude::*;
+use std::str::FromStr;
+
+/// Parse the fields of a submitted form as a query, in the order they are given. The keys and values are encoded
+/// like the browser encodes a `GET` form, so the query parses the same with and without JavaScript.
+pub fn query_from_fields<'a, Q: FromQuery>(
+    fields: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<Q, Q::Err> {
+    let pairs: Vec<_> = fields
+        .into_iter()
+        .map(|(key, value)| {
+            format!(
+                "{}={}",
+                encode_query_component(key),
+                encode_query_component(value)
+            )
+        })
+        .collect();
+    Q::from_query(&pairs.join("&"))
+}
+
+/// The path of the route `to` builds from an empty query, which a form without JavaScript submits to. `None` if the
+/// query type doesn't accept an empty query.
+pub fn form_action<R: Routable, Q: FromQuery>(to: &dyn Fn(Q) -> R) -> Option<String>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let route = to(Q::from_query("").ok()?).to_string();
+    let end = route.find(['?', '#']).unwrap_or(route.len());
+    Some(route[..end].to_string())
+}
+
+#[derive(Props)]
+pub struct QueryFormProps<'a, R: 'static, Q: FromQuery + 'static> {
+    /// Builds the route the form navigates to from the query of its fields
+    to: &'a dyn Fn(Q) -> R,
+    /// The path the form submits to without JavaScript, for query types that don't accept an empty query. Defaults
+    /// to the [`form_action`] of `to`.
+    action: Option<&'a str>,
+    /// Called with the error instead of navigating when the fields don't parse as the query
+    onerror: Option<EventHandler<'a, Q::Err>>,
+    children: Element<'a>,
+}
+
+/// A form that navigates to the route `to` builds from its fields with the nearest router with the route type `R`.
+///
+/// Only the fields with a `name` are submitted. The form event has them in a map, so they are sorted by name before
+/// they are parsed; query types that read the query by key, like [`QueryParams`](crate::query::QueryParams), don't
+/// depend on the order.
+#[allow(non_snake_case)]
+pub fn QueryForm<'a, R: Routable + 'static, Q: FromQuery + 'static>(
+    cx: Scope<'a, QueryFormProps<'a, R, Q>>,
+) -> Element<'a>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let router = use_router::<R>(cx)?.router().clone();
+    let navigator = use_navigator::<R>(cx)?;
+    let action = cx
+        .props
+        .action
+        .map(String::from)
+        .or_else(|| form_action(cx.props.to))
+        .map(|path| router.borrow().href(&path))
+        .unwrap_or_default();
+    render! {
+        form {
+            action: "{action}",
+            method: "get",
+            prevent_default: "onsubmit",
+            onsubmit: move |event: FormEvent| {
+                let mut fields: Vec<_> = event
+                    .values
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+                    .collect();
+                fields.sort();
+                match query_from_fields(fields) {
+                    Ok(query) => navigator.push((cx.props.to)(query)),
+                    Err(err) => {
+                        if let Some(onerror) = &cx.props.onerror {
+                            onerror.call(err);
+                        }
+                    }
+                }
+            },
+            &cx.props.children
+        }
+    }
+}
+
+#[test]
+fn form_queries() {
+    use crate::query::QueryParams;
+    use crate::query::{CatalogRoute, PageQuery};
+    use crate::Router;
+    use dioxus_router_core::history::MemoryHistory;
+
+    let params: QueryParams = query_from_fields([("q", "milk & honey"), ("sort", "new")]).unwrap();
+    assert_eq!(params.get("q"), Some("milk & honey"));
+    assert_eq!(params.get("sort"), Some("new"));
+
+    let page = |page| CatalogRoute::CatalogItem { id: 7, page };
+    assert_eq!(query_from_fields([("page", "3")]), Ok(PageQuery(3)));
+    assert_eq!(
+        query_from_fields::<PageQuery>([("page", "x")]),
+        Err("'x' is not a page".to_string())
+    );
+    // Without JavaScript the browser submits the fields to the path of the route
+    assert_eq!(form_action(&page).as_deref(), Some("/catalog/7"));
+    let catalog = |params| CatalogRoute::Catalog { params };
+    assert_eq!(form_action(&catalog).as_deref(), Some("/catalog"));
+
+    // The route of a submit is the route the browser would load from the same fields
+    let mut router = Router::<CatalogRoute>::new(MemoryHistory::default()).unwrap();
+    router.push(catalog(query_from_fields([("q", "milk & honey")]).unwrap()));
+    let submitted = router.current_url().to_string();
+    assert_eq!(submitted, "/catalog?q=milk%20%26%20honey");
+    assert_eq!(
+        CatalogRoute::from_str("/catalog?q=milk+%26+honey"),
+        Ok(router.route.clone())
+    );
+}
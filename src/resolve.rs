@@ -0,0 +1,97 @@
+//! Answer "what would happen if the router navigated here?" without navigating, for link previews and prefetching.
+
+use crate::{NavigationError, Routable, Router, RouterConfiguration};
+use dioxus_router_core::history::{HistoryProvider, MemoryHistory};
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// Where a navigation would end, returned by [`Router::resolve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution<R> {
+    /// The route the router would end up at
+    pub route: R,
+    /// The location the history would be at, after rewrite rules and redirects
+    pub location: Rc<str>,
+    /// The patterns of the actions that ran on the way, in order
+    pub redirects: Vec<&'static str>,
+    /// The pattern of the action that wasn't run because it isn't declared `dry_run_safe = true`. The route is
+    /// that action's route, since the resolution can't tell where the action would go.
+    pub unevaluated: Option<&'static str>,
+    /// The reason the navigation would stop, like a redirect loop
+    pub error: Option<NavigationError>,
+}
+
+impl<R: Routable> Router<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Resolve a location like a navigation to it would: apply the rewrite rules, parse it and follow the actions
+    /// that redirect. For a route, resolve `route.to_string()`.
+    ///
+    /// The navigation happens on a separate router with its own history, so the history, the current route and
+    /// the subscribers of this router are not touched. Actions can have other side effects, like logging out, so
+    /// only actions declared with `dry_run_safe = true` are run; the first other action stops the resolution and is
+    /// reported in [`Resolution::unevaluated`].
+    pub fn resolve(&self, location: &str) -> Result<Resolution<R>, R::Err> {
+        let mut history = MemoryHistory::default();
+        history.replace(location.to_string());
+        let config = RouterConfiguration {
+            parse_cache_capacity: 0,
+            strip_unknown_query: self.strip_unknown_query,
+            rewrites: self.rewrites.clone(),
+            max_redirects: self.max_redirects,
+            excluded_prefixes: self.excluded_prefixes.clone(),
+            guards: self.guards.clone(),
+            contexts: self.contexts.clone(),
+            error_route: self.error_route,
+            strict_roundtrip: self.strict_roundtrip,
+            dry_run: true,
+            // The configuration was checked when this router started
+            validate_on_start: false,
+            document_root: None,
+            #[cfg(feature = "devtools")]
+            coverage_storage: None,
+            ..Default::default()
+        };
+        let router = Self::with_configuration(history, config)?;
+        Ok(Resolution {
+            location: router.current_url(),
+            redirects: router.redirect_chain,
+            unevaluated: router.unevaluated_action,
+            error: router.navigation_error,
+            route: router.route,
+        })
+    }
+}
+
+#[test]
+fn resolve_without_navigating() {
+    use crate::rewrite::RewriteRule;
+    use crate::MovedRoute;
+
+    let config = RouterConfiguration {
+        rewrites: vec![RewriteRule::new("/legacy", "/v1").unwrap()],
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/").unwrap();
+    let router = Router::<MovedRoute>::with_configuration(history, config).unwrap();
+
+    let resolution = router.resolve("/legacy?ref=mail").unwrap();
+    assert_eq!(resolution.route, MovedRoute::Home {});
+    assert_eq!(&*resolution.location, "/?ref=mail");
+    assert_eq!(resolution.redirects, ["/v1", "/v2"]);
+    assert_eq!(resolution.unevaluated, None);
+    assert_eq!(resolution.error, None);
+
+    // Logging out isn't safe to run, so the resolution stops at the logout route
+    let resolution = router.resolve("/logout").unwrap();
+    assert_eq!(resolution.route, MovedRoute::Logout {});
+    assert!(resolution.redirects.is_empty());
+    assert_eq!(resolution.unevaluated, Some("/logout"));
+
+    assert!(router.resolve("/missing").is_err());
+    // Nothing changed on the router itself
+    assert_eq!(router.route, MovedRoute::Home {});
+    assert_eq!(&*router.current_url(), "/");
+    assert!(!router.history.can_go_back());
+}
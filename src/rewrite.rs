@@ -0,0 +1,362 @@
+use crate::pattern::{PatternSegment as RouteSegment, RoutePattern};
+use dioxus_router_core::history::HistoryProvider;
+
+/// A rule that rewrites a legacy path to its current path before the router parses it, like
+/// `/old/(id)` to `/new/(id)`.
+///
+/// Patterns use the same segment syntax as `#[route(...)]`: static segments, `(name)` for one segment and
+/// `(...name)` for a trailing catch-all that matches zero or more segments. Captured segments are copied into the
+/// target as they are, without decoding them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteRule {
+    from: Vec<PatternSegment>,
+    to: Vec<PatternSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternSegment {
+    Static(String),
+    Dynamic(String),
+    CatchAll(String),
+}
+
+/// The reason a rewrite rule was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RewriteRuleError {
+    /// The pattern does not start with `/`.
+    MissingLeadingSlash(String),
+    /// A catch-all segment is followed by more segments.
+    CatchAllNotLast(String),
+    /// The same segment name is used twice in the source pattern.
+    DuplicateSegment(String),
+    /// The target uses a segment the source pattern doesn't capture.
+    UnknownSegment(String),
+}
+
+impl std::fmt::Display for RewriteRuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingLeadingSlash(pattern) => write!(f, "'{pattern}' does not start with /"),
+            Self::CatchAllNotLast(pattern) => {
+                write!(
+                    f,
+                    "the catch-all segment in '{pattern}' must be the last segment"
+                )
+            }
+            Self::DuplicateSegment(name) => write!(f, "the segment '{name}' is captured twice"),
+            Self::UnknownSegment(name) => {
+                write!(
+                    f,
+                    "the target uses '{name}', which the source pattern doesn't capture"
+                )
+            }
+        }
+    }
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<PatternSegment>, RewriteRuleError> {
+    let rest = pattern
+        .strip_prefix('/')
+        .ok_or_else(|| RewriteRuleError::MissingLeadingSlash(pattern.to_string()))?;
+
+    let mut segments = Vec::new();
+    for segment in rest.split('/') {
+        if matches!(segments.last(), Some(PatternSegment::CatchAll(_))) {
+            return Err(RewriteRuleError::CatchAllNotLast(pattern.to_string()));
+        }
+        let parsed = if let Some(name) = segment
+            .strip_prefix("(...")
+            .and_then(|s| s.strip_suffix(')'))
+        {
+            PatternSegment::CatchAll(name.to_string())
+        } else if let Some(name) = segment.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+            PatternSegment::Dynamic(name.to_string())
+        } else {
+            PatternSegment::Static(segment.to_string())
+        };
+        segments.push(parsed);
+    }
+    Ok(segments)
+}
+
+impl RewriteRule {
+    pub fn new(from: &str, to: &str) -> Result<Self, RewriteRuleError> {
+        let from = parse_pattern(from)?;
+        let to = parse_pattern(to)?;
+
+        let mut captured = Vec::new();
+        for segment in &from {
+            if let PatternSegment::Dynamic(name) | PatternSegment::CatchAll(name) = segment {
+                if captured.contains(&name) {
+                    return Err(RewriteRuleError::DuplicateSegment(name.clone()));
+                }
+                captured.push(name);
+            }
+        }
+        for segment in &to {
+            if let PatternSegment::Dynamic(name) | PatternSegment::CatchAll(name) = segment {
+                if !captured.contains(&name) {
+                    return Err(RewriteRuleError::UnknownSegment(name.clone()));
+                }
+            }
+        }
+
+        Ok(Self { from, to })
+    }
+
+    /// Rewrite a path without its query, or return `None` if the rule doesn't match it.
+    pub fn apply(&self, path: &str) -> Option<String> {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let mut segments: Vec<&str> = path.split('/').collect();
+        // Ignore a trailing slash, like the generated parsers
+        if segments.len() > 1 && segments.last() == Some(&"") {
+            segments.pop();
+        }
+
+        let mut captured = Vec::new();
+        let mut remaining = segments.as_slice();
+        for segment in &self.from {
+            match segment {
+                PatternSegment::Static(expected) => {
+                    let (first, rest) = remaining.split_first()?;
+                    if first != expected {
+                        return None;
+                    }
+                    remaining = rest;
+                }
+                PatternSegment::Dynamic(name) => {
+                    let (first, rest) = remaining.split_first()?;
+                    if first.is_empty() {
+                        return None;
+                    }
+                    captured.push((name, first.to_string()));
+                    remaining = rest;
+                }
+                PatternSegment::CatchAll(name) => {
+                    let rest = if remaining == [""] {
+                        &[][..]
+                    } else {
+                        remaining
+                    };
+                    captured.push((name, rest.join("/")));
+                    remaining = &[];
+                }
+            }
+        }
+        if !remaining.is_empty() {
+            return None;
+        }
+
+        let mut rewritten = String::new();
+        for segment in &self.to {
+            let value = match segment {
+                PatternSegment::Static(segment) => segment.as_str(),
+                PatternSegment::Dynamic(name) | PatternSegment::CatchAll(name) => captured
+                    .iter()
+                    .find(|(captured, _)| *captured == name)
+                    .map(|(_, value)| value.as_str())?,
+            };
+            // An empty catch-all is written without a trailing slash
+            if value.is_empty() && matches!(segment, PatternSegment::CatchAll(_)) {
+                continue;
+            }
+            rewritten.push('/');
+            rewritten.push_str(value);
+        }
+        if rewritten.is_empty() {
+            rewritten.push('/');
+        }
+        Some(rewritten)
+    }
+}
+
+impl RewriteRule {
+    /// The pattern the rule rewrites to, like `/new/(id)`
+    pub fn target(&self) -> String {
+        write_pattern(&self.to)
+    }
+
+    /// If a path the rule rewrites to can match the route pattern. Captured values are only known when the rule
+    /// runs, so they can match any dynamic segment of the pattern but no static one.
+    pub fn target_may_match(&self, pattern: &RoutePattern) -> bool {
+        target_may_match(&self.to, pattern.segments())
+    }
+}
+
+impl std::fmt::Display for RewriteRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> {}",
+            write_pattern(&self.from),
+            write_pattern(&self.to)
+        )
+    }
+}
+
+fn write_pattern(segments: &[PatternSegment]) -> String {
+    let mut pattern = String::new();
+    for segment in segments {
+        match segment {
+            PatternSegment::Static(segment) => pattern.push_str(&format!("/{segment}")),
+            PatternSegment::Dynamic(name) => pattern.push_str(&format!("/({name})")),
+            PatternSegment::CatchAll(name) => pattern.push_str(&format!("/(...{name})")),
+        }
+    }
+    if pattern.is_empty() {
+        pattern.push('/');
+    }
+    pattern
+}
+
+fn target_may_match(target: &[PatternSegment], pattern: &[RouteSegment]) -> bool {
+    match (target.split_first(), pattern.split_first()) {
+        (_, Some((RouteSegment::CatchAll(_), _))) | (None, None) => true,
+        // A captured catch-all has any number of segments, so it can fill dynamic segments but not static ones
+        (Some((PatternSegment::CatchAll(_), _)), _) => pattern
+            .iter()
+            .all(|segment| !matches!(segment, RouteSegment::Static(_))),
+        (None, Some(_)) | (Some(_), None) => false,
+        (Some((first, target)), Some((expected, pattern))) => {
+            let matches = match (first, expected) {
+                (PatternSegment::Static(value), RouteSegment::Static(expected)) => {
+                    value == expected
+                }
+                (PatternSegment::Static(value), RouteSegment::Dynamic { suffix, .. }) => {
+                    value.len() > suffix.len() && value.ends_with(suffix.as_str())
+                }
+                (PatternSegment::Dynamic(_), RouteSegment::Dynamic { .. }) => true,
+                _ => false,
+            };
+            matches && target_may_match(target, pattern)
+        }
+    }
+}
+
+/// Apply the first rule that matches the current path of the history, replacing the current entry with the rewritten
+/// path and keeping the query. Returns the path the router should parse.
+///
+/// Only one rule is applied per navigation, so rules that rewrite into each other can't loop.
+pub fn rewrite_location(history: &mut dyn HistoryProvider, rules: &[RewriteRule]) -> String {
+    let path = history.current_path();
+    let Some(rewritten) = rules.iter().find_map(|rule| rule.apply(&path)) else {
+        return path;
+    };
+    let location = match history.current_query() {
+        Some(query) => format!("{rewritten}?{query}"),
+        None => rewritten.clone(),
+    };
+    history.replace(location);
+    rewritten
+}
+
+#[test]
+fn rewrite_rules() {
+    let rule = RewriteRule::new("/old/(id)", "/new/(id)").unwrap();
+    assert_eq!(rule.apply("/old/12"), Some("/new/12".to_string()));
+    assert_eq!(rule.apply("/old/12/"), Some("/new/12".to_string()));
+    assert_eq!(rule.apply("/old/"), None);
+    assert_eq!(rule.apply("/old/12/extra"), None);
+    assert_eq!(rule.apply("/new/12"), None);
+
+    let rule = RewriteRule::new("/wiki/(...page)", "/docs/(...page)").unwrap();
+    assert_eq!(
+        rule.apply("/wiki/a/b%20c"),
+        Some("/docs/a/b%20c".to_string())
+    );
+    assert_eq!(rule.apply("/wiki"), Some("/docs".to_string()));
+
+    let rule = RewriteRule::new("/(user)/profile", "/users/(user)").unwrap();
+    assert_eq!(
+        rule.apply("/ferris/profile"),
+        Some("/users/ferris".to_string())
+    );
+
+    assert_eq!(
+        RewriteRule::new("old/(id)", "/new/(id)"),
+        Err(RewriteRuleError::MissingLeadingSlash(
+            "old/(id)".to_string()
+        ))
+    );
+    assert_eq!(
+        RewriteRule::new("/old/(id)", "/new/(slug)"),
+        Err(RewriteRuleError::UnknownSegment("slug".to_string()))
+    );
+    assert_eq!(
+        RewriteRule::new("/(id)/(id)", "/(id)"),
+        Err(RewriteRuleError::DuplicateSegment("id".to_string()))
+    );
+    assert_eq!(
+        RewriteRule::new("/(...rest)/end", "/(...rest)"),
+        Err(RewriteRuleError::CatchAllNotLast(
+            "/(...rest)/end".to_string()
+        ))
+    );
+}
+
+#[test]
+fn rewrite_targets() {
+    let pattern = |pattern| RoutePattern::parse(pattern).unwrap();
+    let rule = RewriteRule::new("/old/(id)", "/new/(id)").unwrap();
+    assert_eq!(rule.to_string(), "/old/(id) -> /new/(id)");
+    assert!(rule.target_may_match(&pattern("/new/(id)")));
+    assert!(rule.target_may_match(&pattern("/(section)/(id)")));
+    assert!(rule.target_may_match(&pattern("/new/(...rest)")));
+    assert!(!rule.target_may_match(&pattern("/new/settings")));
+    assert!(!rule.target_may_match(&pattern("/new")));
+
+    let rule = RewriteRule::new("/wiki/(...page)", "/docs/(...page)").unwrap();
+    assert_eq!(rule.target(), "/docs/(...page)");
+    assert!(rule.target_may_match(&pattern("/docs")));
+    assert!(rule.target_may_match(&pattern("/docs/(section)/(page)")));
+    assert!(!rule.target_may_match(&pattern("/docs/api/(page)")));
+
+    let rule = RewriteRule::new("/feed", "/feed.xml").unwrap();
+    assert!(rule.target_may_match(&pattern("/(name).xml")));
+    assert!(!rule.target_may_match(&pattern("/(name).json")));
+}
+
+#[test]
+fn rewrite_location_applies_one_rule() {
+    use dioxus_router_core::history::MemoryHistory;
+
+    let rules = [
+        RewriteRule::new("/a/(id)", "/b/(id)").unwrap(),
+        RewriteRule::new("/b/(id)", "/a/(id)").unwrap(),
+    ];
+    let mut history = MemoryHistory::with_initial_path("/a/1?x=y").unwrap();
+    assert_eq!(rewrite_location(&mut history, &rules), "/b/1");
+    assert_eq!(history.current_path(), "/b/1");
+    assert_eq!(history.current_query().as_deref(), Some("x=y"));
+    assert!(!history.can_go_back());
+
+    let mut history = MemoryHistory::with_initial_path("/c").unwrap();
+    assert_eq!(rewrite_location(&mut history, &rules), "/c");
+}
+
+#[test]
+fn rewrite_legacy_paths() {
+    use crate::{Route, Router, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+
+    let config = || RouterConfiguration {
+        rewrites: vec![
+            RewriteRule::new("/old/(id)", "/hello_world/(id)").unwrap(),
+            RewriteRule::new("/old/(...rest)", "/unused").unwrap(),
+        ],
+        ..Default::default()
+    };
+    let history = MemoryHistory::with_initial_path("/old/1234?ref=mail").unwrap();
+    let mut router = Router::<Route>::with_configuration(history, config()).unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 1234 });
+    assert_eq!(&*router.current_url(), "/hello_world/1234?ref=mail");
+
+    router.history.push("/old/42".to_string());
+    router.sync_route().unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 42 });
+    assert_eq!(router.history.current_path(), "/hello_world/42");
+    // The legacy path was replaced, so going back skips it
+    router.go_back().unwrap();
+    assert_eq!(router.route, Route::Route3 { dynamic: 1234 });
+    assert!(!router.history.can_go_back());
+}
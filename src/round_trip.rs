@@ -0,0 +1,221 @@
+//! A check that the routes the app navigates to parse back from their own path.
+//!
+//! `strict_display` checks the segments the derive knows how to write, but a segment type with its own `Display`
+//! and `FromStr`, like a price that is written with two decimals, can lose part of its value on the way. The route
+//! still displays and parses, so the app only notices when a reload lands on a different page than the one it
+//! navigated to. In debug builds the router parses the path of every route it is asked to push or replace and
+//! compares the result with the route, field by field with `PartialEq`. It warns with the `tracing` feature when they
+//! differ, and panics instead with [`RouterConfiguration::strict_roundtrip`](crate::RouterConfiguration), which is
+//! meant for tests.
+
+#[cfg(test)]
+use super::*;
+use crate::{Routable, Router};
+use std::str::FromStr;
+
+/// How the path of a route failed to parse back into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoundTripError {
+    /// The path doesn't parse at all
+    NoMatch {
+        pattern: &'static str,
+        path: String,
+        error: String,
+    },
+    /// The path parses as another route
+    OtherRoute {
+        pattern: &'static str,
+        path: String,
+        parsed: &'static str,
+    },
+    /// The path parses as the same route, but these fields have different values
+    Fields {
+        pattern: &'static str,
+        path: String,
+        fields: Vec<&'static str>,
+    },
+}
+
+impl std::fmt::Display for RoundTripError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoMatch {
+                pattern,
+                path,
+                error,
+            } => write!(f, "The route '{pattern}' displays as '{path}', which doesn't parse: {error}"),
+            Self::OtherRoute {
+                pattern,
+                path,
+                parsed,
+            } => write!(
+                f,
+                "The route '{pattern}' displays as '{path}', which parses as the route '{parsed}'"
+            ),
+            Self::Fields {
+                pattern,
+                path,
+                fields,
+            } => write!(
+                f,
+                "The route '{pattern}' displays as '{path}', which parses back with different values of {}",
+                fields.join(", ")
+            ),
+        }
+    }
+}
+
+/// Parse the path of a route and compare the result with it. Routes that can't compare their fields, like
+/// hand-written implementations of [`Routable`], only have their pattern checked.
+pub fn check_round_trip<R: Routable>(route: &R) -> Result<(), RoundTripError>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let pattern = route.pattern();
+    let path = route.to_string();
+    let parsed = match R::from_str(&path) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            return Err(RoundTripError::NoMatch {
+                pattern,
+                path,
+                error: err.to_string(),
+            })
+        }
+    };
+    if parsed.pattern() != pattern {
+        return Err(RoundTripError::OtherRoute {
+            pattern,
+            path,
+            parsed: parsed.pattern(),
+        });
+    }
+    match route.differing_fields(&parsed) {
+        Some(fields) if !fields.is_empty() => Err(RoundTripError::Fields {
+            pattern,
+            path,
+            fields,
+        }),
+        _ => Ok(()),
+    }
+}
+
+impl<R: Routable> Router<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Check that a route the app navigates to parses back from its path, in debug builds or with
+    /// [`RouterConfiguration::strict_roundtrip`](crate::RouterConfiguration).
+    pub fn check_round_trip(&self, route: &R) {
+        if !cfg!(debug_assertions) && !self.strict_roundtrip {
+            return;
+        }
+        let Err(err) = check_round_trip(route) else {
+            return;
+        };
+        if self.strict_roundtrip {
+            panic!("{err}");
+        }
+        #[cfg(feature = "tracing")]
+        tracing::warn!(route = std::any::type_name::<R>(), "{err}");
+    }
+}
+
+#[test]
+fn round_trips() {
+    use crate::{ItemRoute, Route, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+
+    assert_eq!(check_round_trip(&Route::Route3 { dynamic: 7 }), Ok(()));
+    assert_eq!(
+        check_round_trip(&PriceRoute::Price {
+            amount: Euros(2.499)
+        }),
+        Err(RoundTripError::Fields {
+            pattern: "/prices/(amount)",
+            path: "/prices/2.50".to_string(),
+            fields: vec!["amount"],
+        })
+    );
+    assert_eq!(
+        check_round_trip(&PriceRoute::Price { amount: Euros(2.5) }),
+        Ok(())
+    );
+    assert_eq!(
+        check_round_trip(&ItemRoute::ItemBySlug {
+            slug: "12".to_string()
+        }),
+        Err(RoundTripError::OtherRoute {
+            pattern: "/items/(slug)",
+            path: "/items/12".to_string(),
+            parsed: "/items/(id)",
+        })
+    );
+
+    let router = |strict_roundtrip| {
+        let config = RouterConfiguration {
+            strict_roundtrip,
+            ..Default::default()
+        };
+        Router::<PriceRoute>::with_configuration(MemoryHistory::default(), config).unwrap()
+    };
+    // Without the flag the router only warns, and goes where it was asked to
+    let mut lenient = router(false);
+    lenient.push(PriceRoute::Price {
+        amount: Euros(2.499),
+    });
+    assert_eq!(lenient.current_url().as_ref(), "/prices/2.50");
+
+    let mut strict = router(true);
+    strict.push(PriceRoute::Price { amount: Euros(4.0) });
+    let pushed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        strict.push(PriceRoute::Price {
+            amount: Euros(2.499),
+        })
+    }));
+    assert!(pushed.is_err());
+}
+
+/// A price written with two decimals, so its path can lose part of the value
+#[cfg(test)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Euros(f64);
+
+#[cfg(test)]
+impl std::fmt::Display for Euros {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.2}", self.0)
+    }
+}
+
+#[cfg(test)]
+impl FromStr for Euros {
+    type Err = std::num::ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum PriceRoute {
+    #[route("/" PriceList)]
+    PriceList {},
+    #[route("/prices/(amount)" Price)]
+    Price { amount: Euros },
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn PriceList(cx: Scope) -> Element {
+    render! { "Prices" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn Price(cx: Scope, amount: Euros) -> Element {
+    render! { "Price {amount}" }
+}
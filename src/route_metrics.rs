@@ -0,0 +1,240 @@
+//! Route metrics reported through the [`metrics`] facade, enabled with the `metrics` feature.
+//!
+//! Matches are labeled with the [`Routable::pattern`] of the route rather than its path, so the number of label
+//! values is bounded by the number of routes.
+
+use crate::navigation::NavigationSource;
+use crate::Routable;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Every attempt to parse a path into a route, including parses answered by the cache
+pub const PARSES: &str = "router_parses_total";
+/// Parses that found no route, so the error fallback was shown instead
+pub const FALLBACKS: &str = "router_fallbacks_total";
+/// How long parsing a path took, in seconds
+pub const PARSE_DURATION: &str = "router_parse_duration_seconds";
+/// Every time a route became the current route, labeled with `pattern` and the [`NavigationSource::name`] as `source`
+pub const MATCHES: &str = "router_matches_total";
+/// Every time a deprecated route became the current route, labeled with `pattern`
+pub const DEPRECATED_MATCHES: &str = "router_deprecated_matches_total";
+/// Parses answered by a [`SharedRouteCache`](crate::cache::SharedRouteCache)
+pub const SHARED_CACHE_HITS: &str = "router_shared_cache_hits_total";
+/// Parses a [`SharedRouteCache`](crate::cache::SharedRouteCache) didn't have, so the location was parsed
+pub const SHARED_CACHE_MISSES: &str = "router_shared_cache_misses_total";
+/// Every page the legacy route table rendered, labeled with the `prefix` of the legacy table the path was under
+pub const LEGACY_HITS: &str = "router_legacy_hits_total";
+
+pub fn record_parse<R: Routable>(result: &Result<R, R::Err>, elapsed: Duration)
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    metrics::counter!(PARSES).increment(1);
+    metrics::histogram!(PARSE_DURATION).record(elapsed.as_secs_f64());
+    if result.is_err() {
+        metrics::counter!(FALLBACKS).increment(1);
+    }
+}
+
+pub fn record_match<R: Routable>(route: &R, source: &NavigationSource)
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    metrics::counter!(MATCHES, "pattern" => route.pattern(), "source" => source.name())
+        .increment(1);
+    if route.is_deprecated().is_some() {
+        metrics::counter!(DEPRECATED_MATCHES, "pattern" => route.pattern()).increment(1);
+    }
+}
+
+pub fn record_shared_cache(hit: bool) {
+    match hit {
+        true => metrics::counter!(SHARED_CACHE_HITS).increment(1),
+        false => metrics::counter!(SHARED_CACHE_MISSES).increment(1),
+    }
+}
+
+pub fn record_legacy_hit(prefix: &str) {
+    metrics::counter!(LEGACY_HITS, "prefix" => prefix.to_string()).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Route, Router, RouterProps};
+    use dioxus::prelude::*;
+    use dioxus_router_core::history::MemoryHistory;
+    use metrics::{
+        Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+        SharedString, Unit,
+    };
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    /// Counts every counter increment and histogram sample by the name and labels of the metric
+    #[derive(Default)]
+    struct StubRecorder {
+        counts: Arc<Mutex<BTreeMap<String, u64>>>,
+    }
+
+    struct StubHandle {
+        key: String,
+        counts: Arc<Mutex<BTreeMap<String, u64>>>,
+    }
+
+    impl StubHandle {
+        fn add(&self, value: u64) {
+            *self
+                .counts
+                .lock()
+                .unwrap()
+                .entry(self.key.clone())
+                .or_default() += value;
+        }
+    }
+
+    impl CounterFn for StubHandle {
+        fn increment(&self, value: u64) {
+            self.add(value)
+        }
+
+        fn absolute(&self, _value: u64) {}
+    }
+
+    impl HistogramFn for StubHandle {
+        fn record(&self, _value: f64) {
+            self.add(1)
+        }
+    }
+
+    impl StubRecorder {
+        fn handle(&self, key: &Key) -> Arc<StubHandle> {
+            let labels: Vec<_> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            let key = match labels.is_empty() {
+                true => key.name().to_string(),
+                false => format!("{}{{{}}}", key.name(), labels.join(",")),
+            };
+            Arc::new(StubHandle {
+                key,
+                counts: self.counts.clone(),
+            })
+        }
+
+        fn count(&self, key: &str) -> u64 {
+            self.counts.lock().unwrap().get(key).copied().unwrap_or(0)
+        }
+    }
+
+    impl Recorder for StubRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.handle(key))
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(self.handle(key))
+        }
+    }
+
+    #[test]
+    fn navigations_are_counted() {
+        let recorder = StubRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            let history = MemoryHistory::with_initial_path("/hello_world/1").unwrap();
+            let mut router = Router::<Route>::new(history).unwrap();
+            router.push(Route::Route3 { dynamic: 2 });
+            router.push(Route::Route2 {});
+            router.go_back().unwrap();
+            // Parsed from the cache, but still counted as a parse
+            router.go_back().unwrap();
+
+            router.history.push("/not/a/route/at/all".to_string());
+            assert!(router.sync_route().is_err());
+        });
+
+        assert_eq!(recorder.count(PARSES), 4);
+        assert_eq!(recorder.count(PARSE_DURATION), 4);
+        assert_eq!(recorder.count(FALLBACKS), 1);
+        assert_eq!(
+            recorder.count("router_matches_total{pattern=/hello_world/(dynamic),source=browser}"),
+            3
+        );
+        assert_eq!(
+            recorder
+                .count("router_matches_total{pattern=/hello_world/(dynamic),source=programmatic}"),
+            1
+        );
+        assert_eq!(
+            recorder.count("router_matches_total{pattern=/hello_world,source=programmatic}"),
+            1
+        );
+    }
+
+    #[test]
+    fn server_renders_are_counted() {
+        let recorder = StubRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            for path in ["/hello_world/1", "/hello_world/2", "/not/a/route/at/all"] {
+                let mut vdom = VirtualDom::new_with_props(
+                    Route::comp,
+                    RouterProps {
+                        current_route: path.to_string(),
+                        max_suggestions: 3,
+                        dynamic_routes: Default::default(),
+                        legacy_fallback: None,
+                    },
+                );
+                let _ = vdom.rebuild();
+            }
+        });
+
+        assert_eq!(recorder.count(PARSES), 3);
+        assert_eq!(recorder.count(FALLBACKS), 1);
+        assert_eq!(
+            recorder.count("router_matches_total{pattern=/hello_world/(dynamic),source=browser}"),
+            2
+        );
+    }
+
+    #[test]
+    fn legacy_hits_are_counted() {
+        let fallback = crate::LegacyFallback {
+            prefixes: vec!["/hello_world".to_string()],
+            matcher: crate::legacy::legacy_articles,
+        };
+        let recorder = StubRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            for path in ["/hello_world/1", "/hello_world/2", "/hello_world/3"] {
+                let mut vdom = VirtualDom::new_with_props(
+                    Route::comp,
+                    RouterProps {
+                        current_route: path.to_string(),
+                        max_suggestions: 3,
+                        dynamic_routes: Default::default(),
+                        legacy_fallback: Some(fallback.clone()),
+                    },
+                );
+                let _ = vdom.rebuild();
+            }
+        });
+
+        assert_eq!(
+            recorder.count("router_legacy_hits_total{prefix=/hello_world}"),
+            2
+        );
+        // Only the path the legacy table didn't match was parsed
+        assert_eq!(recorder.count(PARSES), 1);
+    }
+}
@@ -0,0 +1,316 @@
+//! Helpers for testing navigation end to end.
+
+use crate::context::{
+    use_query_raw, use_query_value, use_query_values, use_router_provider, use_router_store,
+    use_segment, use_shared_router_provider, RouterId, RouterStore,
+};
+use crate::navigation::NavigationSource;
+use crate::{Routable, Router};
+use dioxus::prelude::*;
+use dioxus_router_core::history::MemoryHistory;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// A virtual dom that renders the current route of a router backed by a [`MemoryHistory`].
+///
+/// Every navigation re-renders the dom before it returns, so [`TestRouter::rendered_html`] always shows the
+/// current route.
+pub struct TestRouter<R: Routable + 'static>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    vdom: VirtualDom,
+    router: Rc<RefCell<Router<R>>>,
+}
+
+struct TestRootProps<R: Routable + 'static>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    router: Rc<RefCell<Router<R>>>,
+}
+
+fn test_root<R: Routable + 'static>(cx: Scope<TestRootProps<R>>) -> Element
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    use_shared_router_provider(cx, RouterId("test"), &cx.props.router);
+    let route = cx.props.router.borrow().route.clone();
+    route.render(cx)
+}
+
+impl<R: Routable + 'static> TestRouter<R>
+where
+    <R as FromStr>::Err: std::fmt::Display + std::fmt::Debug,
+{
+    /// Mount a router at the given path. Panics if the path doesn't parse.
+    pub fn new(initial_path: &str) -> Self {
+        let history = MemoryHistory::with_initial_path(initial_path).unwrap();
+        let router = Rc::new(RefCell::new(Router::new(history).unwrap()));
+        let mut vdom = VirtualDom::new_with_props(
+            test_root::<R>,
+            TestRootProps {
+                router: router.clone(),
+            },
+        );
+        let _ = vdom.rebuild();
+        Self { vdom, router }
+    }
+
+    /// Push a route and render it.
+    pub fn navigate(&mut self, route: impl Into<R>) {
+        self.router.borrow_mut().push(route);
+        self.rerender();
+    }
+
+    /// The routes this router matched, to merge into the coverage of a whole test suite.
+    #[cfg(feature = "devtools")]
+    pub fn coverage(&self) -> crate::coverage::RouteCoverage {
+        self.router.borrow().route_coverage().clone()
+    }
+
+    /// Go back in the history and render the route there.
+    pub fn back(&mut self) {
+        self.router.borrow_mut().go_back().unwrap();
+        self.rerender();
+    }
+
+    pub fn current_route(&self) -> R {
+        self.router.borrow().route.clone()
+    }
+
+    /// If the last navigation only changed the values of the route, see [`Router::is_soft_navigation`].
+    pub fn is_soft_navigation(&self) -> bool {
+        self.router.borrow().is_soft_navigation()
+    }
+
+    pub fn rendered_html(&self) -> String {
+        dioxus_ssr::render(&self.vdom)
+    }
+
+    fn rerender(&mut self) {
+        self.vdom.mark_dirty(ScopeId(0));
+        let _ = self.vdom.render_immediate();
+    }
+}
+
+#[test]
+fn three_step_navigation() {
+    use crate::Route;
+
+    let mut router = TestRouter::<Route>::new("/");
+    assert!(router.rendered_html().contains("Route5 {}"));
+
+    router.navigate(Route::Route3 { dynamic: 1234 });
+    assert!(router.rendered_html().contains("dynamic: 1234"));
+
+    router.navigate(Route::Route4 {
+        number1: 1,
+        number2: 2,
+    });
+    assert!(router.rendered_html().contains("number2: 2"));
+
+    router.back();
+    assert_eq!(router.current_route(), Route::Route3 { dynamic: 1234 });
+    assert!(router.rendered_html().contains("dynamic: 1234"));
+}
+
+#[test]
+fn route_provides_context() {
+    use crate::Route;
+
+    let mut router = TestRouter::<Route>::new("/search/dioxus%20router/1/stars");
+    assert!(router.rendered_html().contains("2 search terms"));
+
+    // Like any context, the value is read when a component renders, so leave the page to remount it
+    router.navigate(Route::Route5 {});
+    router.navigate(Route::Search {
+        query: "rust".to_string(),
+        page: 1,
+        sort: "stars".to_string(),
+    });
+    assert!(router.rendered_html().contains("1 search terms"));
+}
+
+thread_local! {
+    static QUERY_RENDERS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+#[allow(non_snake_case)]
+fn QueryLabel(cx: Scope) -> Element {
+    QUERY_RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let query = use_segment::<crate::Route>(cx, "query").unwrap_or_default();
+    render! { "query: {query}" }
+}
+
+#[test]
+fn segment_subscriptions_skip_unrelated_navigations() {
+    use crate::Route;
+
+    fn app(cx: Scope) -> Element {
+        let router = cx.use_hook(|| {
+            let history = MemoryHistory::with_initial_path("/search/rust/1/stars").unwrap();
+            Rc::new(RefCell::new(Router::<Route>::new(history).unwrap()))
+        });
+        use_shared_router_provider(cx, RouterId("test"), router);
+        render! { QueryLabel {} }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let _ = vdom.rebuild();
+    let router = vdom
+        .base_scope()
+        .consume_context::<crate::context::RouterContext<Route>>()
+        .unwrap()
+        .router()
+        .clone();
+    let renders = || QUERY_RENDERS.with(|renders| renders.get());
+    assert_eq!(renders(), 1);
+
+    let search = |query: &str, page| Route::Search {
+        query: query.to_string(),
+        page,
+        sort: "stars".to_string(),
+    };
+    router.borrow_mut().push(search("rust", 2));
+    let _ = vdom.render_immediate();
+    assert_eq!(renders(), 1);
+
+    router.borrow_mut().push(search("dioxus", 2));
+    let _ = vdom.render_immediate();
+    assert_eq!(renders(), 2);
+    assert!(dioxus_ssr::render(&vdom).contains("query: dioxus"));
+
+    // Routes without a query segment select `None`, which is a change too
+    router.borrow_mut().push(Route::Route5 {});
+    let _ = vdom.render_immediate();
+    assert_eq!(renders(), 3);
+    assert!(dioxus_ssr::render(&vdom).contains("query: "));
+}
+
+thread_local! {
+    static DEBUG_RENDERS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+#[allow(non_snake_case)]
+fn DebugPanel(cx: Scope) -> Element {
+    DEBUG_RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let debug = use_query_value::<crate::Route, u8>(cx, "debug");
+    let tags = use_query_values::<crate::Route>(cx, "tag").join(",");
+    let raw = use_query_raw::<crate::Route>(cx).unwrap_or_default();
+    render! { "debug: {debug:?} tags: {tags} raw: {raw}" }
+}
+
+#[test]
+fn query_hooks_skip_path_changes() {
+    use crate::Route;
+
+    fn app(cx: Scope) -> Element {
+        let router = cx.use_hook(|| {
+            let history = MemoryHistory::with_initial_path("/hello?debug=1&tag=a+b&tag=c").unwrap();
+            Rc::new(RefCell::new(Router::<Route>::new(history).unwrap()))
+        });
+        use_shared_router_provider(cx, RouterId("test"), router);
+        render! { DebugPanel {} }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let _ = vdom.rebuild();
+    let router = vdom
+        .base_scope()
+        .consume_context::<crate::context::RouterContext<Route>>()
+        .unwrap()
+        .router()
+        .clone();
+    let renders = || DEBUG_RENDERS.with(|renders| renders.get());
+    assert_eq!(renders(), 1);
+    assert!(dioxus_ssr::render(&vdom)
+        .contains("debug: Some(Ok(1)) tags: a b,c raw: debug=1&tag=a+b&tag=c"));
+
+    // Only the path changes
+    router.borrow_mut().push_location(
+        Route::Route2 {},
+        "/hello_world?debug=1&tag=a+b&tag=c".to_string(),
+        NavigationSource::Programmatic,
+    );
+    let _ = vdom.render_immediate();
+    assert_eq!(renders(), 1);
+
+    router.borrow_mut().push_location(
+        Route::Route2 {},
+        "/hello_world?debug=x".to_string(),
+        NavigationSource::Programmatic,
+    );
+    let _ = vdom.render_immediate();
+    assert_eq!(renders(), 2);
+    assert!(dioxus_ssr::render(&vdom).contains("debug: Some(Err("));
+    assert!(dioxus_ssr::render(&vdom).contains("raw: debug=x"));
+
+    router.borrow_mut().push(Route::Route5 {});
+    let _ = vdom.render_immediate();
+    assert_eq!(renders(), 3);
+    assert!(dioxus_ssr::render(&vdom).contains("debug: None tags:  raw: "));
+}
+
+thread_local! {
+    static ROUTER_MOUNTED: std::cell::Cell<bool> = std::cell::Cell::new(true);
+    static FRESH_ROUTER: std::cell::Cell<bool> = std::cell::Cell::new(false);
+}
+
+#[allow(non_snake_case)]
+fn RouterHost(cx: Scope) -> Element {
+    use crate::Route;
+
+    let router = use_router_provider(cx, RouterId("app"), || {
+        let history = MemoryHistory::with_initial_path("/").unwrap();
+        let router = Router::<Route>::new(history).unwrap();
+        match FRESH_ROUTER.with(|fresh| fresh.get()) {
+            true => router.fresh(),
+            false => router,
+        }
+    });
+    let route = router.route();
+    route.render(cx)
+}
+
+#[test]
+fn remounted_router_keeps_its_state() {
+    use crate::Route;
+
+    fn app(cx: Scope) -> Element {
+        use_router_store(cx);
+        match ROUTER_MOUNTED.with(|mounted| mounted.get()) {
+            true => render! { RouterHost {} },
+            false => None,
+        }
+    }
+
+    fn remount(vdom: &mut VirtualDom) {
+        for mounted in [false, true] {
+            ROUTER_MOUNTED.with(|cell| cell.set(mounted));
+            vdom.mark_dirty(ScopeId(0));
+            let _ = vdom.render_immediate();
+        }
+    }
+
+    let mut vdom = VirtualDom::new(app);
+    let _ = vdom.rebuild();
+    let store = vdom.base_scope().consume_context::<RouterStore>().unwrap();
+    let router = || store.router::<Route>(RouterId("app")).unwrap();
+    router().borrow_mut().push(Route::Route3 { dynamic: 1 });
+    router().borrow_mut().push(Route::Route4 {
+        number1: 1,
+        number2: 2,
+    });
+
+    remount(&mut vdom);
+    assert!(dioxus_ssr::render(&vdom).contains("number2: 2"));
+    router().borrow_mut().go_back().unwrap();
+    assert_eq!(router().borrow().route, Route::Route3 { dynamic: 1 });
+
+    FRESH_ROUTER.with(|fresh| fresh.set(true));
+    remount(&mut vdom);
+    assert_eq!(router().borrow().route, Route::Route5 {});
+    assert!(dioxus_ssr::render(&vdom).contains("Route5 {}"));
+}
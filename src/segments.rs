@@ -0,0 +1,571 @@
+#[cfg(test)]
+use super::*;
+use crate::encoding::{decode_path_segment, DecodeError, EncodedSegment};
+use crate::BuildError;
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// An iterator over the segments of a path that keeps track of where each segment is in the joined path.
+///
+/// The generated parsers match on a cursor so they can report spans whether the path was split from a string or
+/// handed over as separate segments.
+#[derive(Clone)]
+pub struct SegmentCursor<I> {
+    segments: I,
+    // The start of the next segment
+    offset: usize,
+    last: Range<usize>,
+}
+
+impl<'a, I: Iterator<Item = &'a str> + Clone> SegmentCursor<I> {
+    /// Create a cursor over segments that start at `offset` in the joined path.
+    pub fn new(segments: I, offset: usize) -> Self {
+        Self {
+            segments,
+            offset,
+            last: offset..offset,
+        }
+    }
+
+    /// The span of the segment that was returned last.
+    pub fn span(&self) -> Range<usize> {
+        self.last.clone()
+    }
+
+    /// The span of every segment that has not been returned yet, including the slashes between them.
+    pub fn rest_span(&self) -> Range<usize> {
+        self.offset..self.end().max(self.offset)
+    }
+
+    /// The end of the joined path.
+    pub fn end(&self) -> usize {
+        self.segments
+            .clone()
+            .fold(self.last.end, |end, segment| end + 1 + segment.len())
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Iterator for SegmentCursor<I> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let segment = self.segments.next()?;
+        self.last = self.offset..self.offset + segment.len();
+        self.offset = self.last.end + 1;
+        Some(segment)
+    }
+}
+
+/// A dynamic segment that was missing from the end of a path and was filled with its default value by a
+/// lenient parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilledSegment {
+    /// The name of the field the segment binds to
+    pub name: &'static str,
+    /// The index of the segment in the route pattern
+    pub index: usize,
+}
+
+/// A dynamic segment that is none of the values of a `#[derive(RouteSegmentEnum)]` enum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownSegmentValue {
+    /// The decoded segment
+    pub value: String,
+    /// Every value the enum accepts
+    pub allowed: &'static [&'static str],
+}
+
+impl std::fmt::Display for UnknownSegmentValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' is not one of: {}",
+            self.value,
+            self.allowed.join(", ")
+        )
+    }
+}
+
+/// The segments a catch-all with `#[route_segment(include_query)]` parses: `?` and the raw query are appended to the
+/// last segment, or are the only segment if the path ended before the catch-all.
+pub fn with_query<'a>(segments: impl Iterator<Item = &'a str>, query: Option<&str>) -> Vec<String> {
+    let mut segments: Vec<String> = segments.map(String::from).collect();
+    if let Some(query) = query {
+        match segments.last_mut() {
+            Some(last) => {
+                last.push('?');
+                last.push_str(query);
+            }
+            None => segments.push(format!("?{query}")),
+        }
+    }
+    segments
+}
+
+/// One route pattern parsed and written by `route_segments!("/blog/(id: u64)")`, for routes that implement
+/// `Routable` by hand.
+///
+/// A hand-written router can move to the derive one route at a time: patterns written with `route_segments!`
+/// parse, percent-encode and report errors exactly like a derived route with the same fields, and a derived enum
+/// can be nested inside the hand-written one by trying its parser after the others.
+pub trait SegmentPattern {
+    /// The values of the dynamic and catch-all segments, as a tuple in the order they appear in the pattern
+    type Params;
+    type Err: std::fmt::Display;
+
+    /// The pattern without the types of its segments, like `/blog/(id)`
+    fn pattern(&self) -> &'static str;
+
+    fn parse(&self, path: &str) -> Result<Self::Params, Self::Err>;
+
+    /// Append the path with these values to `out`, like `Routable::write_path`.
+    fn write_path(&self, params: &Self::Params, out: &mut impl std::fmt::Write)
+        -> std::fmt::Result;
+
+    /// Parse the values of the segments from their names, like `from_pattern_and_params` of a derived route.
+    fn from_params(&self, params: &[(&str, &str)]) -> Result<Self::Params, BuildError>;
+}
+
+/// A type that can be parsed from a dynamic segment. Every type that implements `FromStr` can, and a type whose
+/// URL form isn't its `FromStr`, like a base64 id, implements this instead.
+pub trait FromRouteSegment: Sized {
+    type Err;
+
+    /// Parse the percent-decoded segment
+    fn from_route_segment(segment: &str) -> Result<Self, Self::Err>;
+}
+
+impl<T: std::str::FromStr> FromRouteSegment for T {
+    type Err = T::Err;
+
+    fn from_route_segment(segment: &str) -> Result<Self, Self::Err> {
+        segment.parse()
+    }
+}
+
+/// A type that can be written as a dynamic segment. Every type that implements `Display` can, and a type whose URL
+/// form isn't its `Display` implements this instead.
+pub trait ToRouteSegment {
+    /// Write the segment before it is percent-encoded
+    fn display_route_segment(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
+}
+
+impl<T: std::fmt::Display + ?Sized> ToRouteSegment for T {
+    fn display_route_segment(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt(f)
+    }
+}
+
+/// Displays a dynamic segment with [`ToRouteSegment::display_route_segment`].
+pub struct DisplayRouteSegment<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ToRouteSegment + ?Sized> std::fmt::Display for DisplayRouteSegment<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.display_route_segment(f)
+    }
+}
+
+/// Types that can be the value of a dynamic segment: every type that implements [`FromRouteSegment`] and
+/// [`ToRouteSegment`], which includes every type with `FromStr` and `Display`. Derived routes check their segment
+/// types against it, so a type that can't be a segment is reported with a hint.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be the value of a dynamic segment",
+    label = "this type needs `FromStr` and `Display`, or `FromRouteSegment` and `ToRouteSegment`",
+    note = "a newtype like `struct UserId(u64)` can get both with `#[derive(RouteSegment)]`, and a fieldless enum with `#[derive(RouteSegmentEnum)]`"
+)]
+pub trait SegmentValue: FromRouteSegment + ToRouteSegment {}
+
+impl<T: FromRouteSegment + ToRouteSegment> SegmentValue for T {}
+
+/// Types that can be parsed from the remaining segments of a route for a catch-all segment.
+///
+/// The segments are handed over one at a time so implementors can consume them without joining
+/// them into an intermediate string first.
+pub trait FromRouteSegments: Sized {
+    type Err;
+
+    fn from_route_segments<'a, I>(segments: I) -> Result<Self, Self::Err>
+    where
+        I: Iterator<Item = &'a str> + Clone;
+}
+
+/// Types that can be written back out as the segments of a catch-all segment.
+pub trait ToRouteSegments {
+    /// Write the segments separated by `/`, without a leading slash.
+    fn display_route_segments(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
+
+    /// The number of segments written by [`ToRouteSegments::display_route_segments`].
+    fn route_segment_count(&self) -> usize {
+        self.to_route_segments_string().split('/').count()
+    }
+
+    /// If [`ToRouteSegments::display_route_segments`] writes anything. Routes leave out the slash before an
+    /// empty catch-all.
+    fn has_route_segments(&self) -> bool {
+        !self.to_route_segments_string().is_empty()
+    }
+
+    /// Collect the segments written by [`ToRouteSegments::display_route_segments`] into a string.
+    fn to_route_segments_string(&self) -> String {
+        DisplayRouteSegments(self).to_string()
+    }
+}
+
+/// Displays a catch-all value with [`ToRouteSegments::display_route_segments`].
+pub struct DisplayRouteSegments<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ToRouteSegments + ?Sized> std::fmt::Display for DisplayRouteSegments<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.display_route_segments(f)
+    }
+}
+
+/// The segments are joined as they are, still percent-encoded, so a proxy can forward them. [`UrlPath`](crate::UrlPath)
+/// and `Vec<String>` decode them.
+impl FromRouteSegments for String {
+    type Err = Infallible;
+
+    fn from_route_segments<'a, I>(segments: I) -> Result<Self, Self::Err>
+    where
+        I: Iterator<Item = &'a str> + Clone,
+    {
+        let mut len = 0;
+        for (i, segment) in segments.clone().enumerate() {
+            len += segment.len() + (i > 0) as usize;
+        }
+
+        let mut joined = String::with_capacity(len);
+        for (i, segment) in segments.enumerate() {
+            if i > 0 {
+                joined.push('/');
+            }
+            joined.push_str(segment);
+        }
+        Ok(joined)
+    }
+}
+
+impl ToRouteSegments for String {
+    fn display_route_segments(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+
+    fn has_route_segments(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+/// Each segment is percent-decoded like a dynamic segment, so `a%2Fb` is one element with a slash in it, and
+/// Display encodes the elements again.
+impl FromRouteSegments for Vec<String> {
+    type Err = DecodeError;
+
+    fn from_route_segments<'a, I>(segments: I) -> Result<Self, Self::Err>
+    where
+        I: Iterator<Item = &'a str> + Clone,
+    {
+        segments
+            .map(|segment| {
+                decode_path_segment(segment)
+                    .map(Cow::into_owned)
+                    .ok_or(DecodeError::InvalidUtf8)
+            })
+            .collect()
+    }
+}
+
+impl ToRouteSegments for Vec<String> {
+    fn display_route_segments(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{}", EncodedSegment(segment))?;
+        }
+        Ok(())
+    }
+
+    fn route_segment_count(&self) -> usize {
+        self.len()
+    }
+
+    fn has_route_segments(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl FromRouteSegments for PathBuf {
+    type Err = Infallible;
+
+    fn from_route_segments<'a, I>(segments: I) -> Result<Self, Self::Err>
+    where
+        I: Iterator<Item = &'a str> + Clone,
+    {
+        Ok(segments.collect())
+    }
+}
+
+impl ToRouteSegments for PathBuf {
+    fn display_route_segments(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, component) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{}", component.to_string_lossy())?;
+        }
+        Ok(())
+    }
+
+    fn route_segment_count(&self) -> usize {
+        self.iter().count()
+    }
+
+    fn has_route_segments(&self) -> bool {
+        self.iter().next().is_some()
+    }
+}
+
+#[test]
+fn catch_all_string_has_exact_capacity() {
+    let segments = "a/bc/def".split('/');
+    let joined = String::from_route_segments(segments).unwrap();
+    assert_eq!(joined, "a/bc/def");
+    assert_eq!(joined.capacity(), joined.len());
+}
+
+/// Parse a 50-segment catch-all into a `String` by joining the segments first, like catch-alls were parsed before
+/// `FromRouteSegments`, and with `FromRouteSegments`, which copies each segment once. Run with
+/// `cargo test --release catch_all_benchmark -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn catch_all_benchmark() {
+    use std::time::Instant;
+
+    const RUNS: usize = 200_000;
+    let tail = (0..50)
+        .map(|i| format!("segment-{i}"))
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let joined = tail.split('/').collect::<Vec<_>>().join("/");
+        let _ = std::hint::black_box(joined.parse::<String>());
+    }
+    let joined = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let _ = std::hint::black_box(String::from_route_segments(tail.split('/')));
+    }
+    let segments = start.elapsed();
+
+    println!(
+        "{RUNS} catch-alls of 50 segments: {joined:?} joining them first, {segments:?} with FromRouteSegments"
+    );
+}
+
+#[test]
+fn catch_all_vec_decodes_segments() {
+    let segments = Vec::<String>::from_route_segments("a%2Fb/John%20Doe/100%".split('/')).unwrap();
+    assert_eq!(segments, ["a/b", "John Doe", "100%"]);
+    assert_eq!(
+        segments.to_route_segments_string(),
+        "a%2Fb/John%20Doe/100%25"
+    );
+    assert_eq!(segments.route_segment_count(), 3);
+    assert_eq!(
+        Vec::<String>::from_route_segments("a/%E0%A4".split('/')),
+        Err(DecodeError::InvalidUtf8)
+    );
+}
+
+/// Short links with ids written in hex, next to a counter that is a plain `u32`
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum ShortLinkRoute {
+    #[route("/s/(id)" ShortLink)]
+    ShortLink { id: HexId },
+    #[route("/n/(n)" Numbered)]
+    Numbered { n: u32 },
+}
+
+/// An id that is written in hex in URLs. It has no `FromStr` or `Display`, only the segment traits
+#[cfg(test)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct HexId(u32);
+
+#[cfg(test)]
+impl FromRouteSegment for HexId {
+    type Err = std::num::ParseIntError;
+
+    fn from_route_segment(segment: &str) -> Result<Self, Self::Err> {
+        u32::from_str_radix(segment, 16).map(Self)
+    }
+}
+
+#[cfg(test)]
+impl ToRouteSegment for HexId {
+    fn display_route_segment(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:x}", self.0)
+    }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn ShortLink(cx: Scope, id: HexId) -> Element {
+    render! { "Link {id.0}" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn Numbered(cx: Scope, n: u32) -> Element {
+    render! { "Number {n}" }
+}
+
+#[test]
+fn route_segment_traits() {
+    let link = ShortLinkRoute::ShortLink { id: HexId(255) };
+    assert_eq!(ShortLinkRoute::from_str("/s/ff"), Ok(link.clone()));
+    assert_eq!(link.to_string(), "/s/ff");
+    let err = ShortLinkRoute::from_str("/s/zz").unwrap_err();
+    assert!(err
+        .attempted_routes
+        .contains(&ShortLinkRouteMatchError::ShortLink {
+            error: ShortLinkParseError::idParseError(SegmentError::ParseError(
+                u32::from_str_radix("zz", 16).unwrap_err()
+            )),
+            span: 3..5,
+        }));
+
+    // Types with `FromStr` and `Display` go through the blanket impls
+    let numbered = ShortLinkRoute::Numbered { n: 12 };
+    assert_eq!(ShortLinkRoute::from_str("/n/12"), Ok(numbered.clone()));
+    assert_eq!(numbered.to_string(), "/n/12");
+    assert_eq!(<u32 as FromRouteSegment>::from_route_segment("12"), Ok(12));
+    assert_eq!(DisplayRouteSegment(&12u32).to_string(), "12");
+}
+
+/// How a listing is sorted, written in urls as `newest`, `most-viewed` and `a-z`
+#[cfg(test)]
+#[derive(RouteSegmentEnum, Clone, Copy, Debug, PartialEq)]
+enum SortOrder {
+    Newest,
+    MostViewed,
+    #[segment(rename = "a-z")]
+    Alphabetical,
+}
+
+/// Listings that can be sorted in different orders
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum ListingRoute {
+    #[route("/posts/(sort)" Posts)]
+    Posts { sort: SortOrder },
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn Posts(cx: Scope, sort: SortOrder) -> Element {
+    render! { "Posts sorted by {sort}" }
+}
+
+#[test]
+fn segment_enums() {
+    assert_eq!(
+        SortOrder::ALL,
+        [
+            SortOrder::Newest,
+            SortOrder::MostViewed,
+            SortOrder::Alphabetical
+        ]
+    );
+    for sort in SortOrder::ALL {
+        let route = ListingRoute::Posts { sort: *sort };
+        assert_eq!(ListingRoute::from_str(&route.to_string()), Ok(route));
+    }
+    assert_eq!(
+        ListingRoute::Posts {
+            sort: SortOrder::MostViewed
+        }
+        .to_string(),
+        "/posts/most-viewed"
+    );
+    assert_eq!(
+        ListingRoute::from_str("/posts/a-z"),
+        Ok(ListingRoute::Posts {
+            sort: SortOrder::Alphabetical
+        })
+    );
+
+    let error = ListingRoute::from_str("/posts/Newest")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("'Newest' is not one of: newest, most-viewed, a-z"),
+        "{error}"
+    );
+}
+
+/// The id of a user, which can't be confused with the other ids in a route
+#[cfg(test)]
+#[derive(RouteSegment, Clone, Copy, Debug, PartialEq)]
+struct UserId(u64);
+
+/// The order of a user's posts, which only allows the orders of other listings
+#[cfg(test)]
+#[derive(RouteSegment, Clone, Copy, Debug, PartialEq)]
+struct PostOrder(SortOrder);
+
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+#[route("/users/(user)/posts/(order)" UserPosts)]
+struct UserPostsRoute {
+    user: UserId,
+    order: PostOrder,
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn UserPosts(cx: Scope, user: UserId, order: PostOrder) -> Element {
+    render! { "Posts of {user} sorted by {order}" }
+}
+
+#[test]
+fn newtype_segments() {
+    let route = UserPostsRoute {
+        user: UserId(7),
+        order: PostOrder(SortOrder::MostViewed),
+    };
+    assert_eq!(route.to_string(), "/users/7/posts/most-viewed");
+    assert_eq!(
+        UserPostsRoute::from_str("/users/7/posts/most-viewed"),
+        Ok(route)
+    );
+
+    // The errors are the ones of the wrapped types
+    assert_eq!(
+        "x7".parse::<UserId>().unwrap_err().to_string(),
+        "invalid digit found in string"
+    );
+    let error = UserPostsRoute::from_str("/users/7/posts/oldest")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("'oldest' is not one of: newest, most-viewed, a-z"),
+        "{error}"
+    );
+    assert_eq!(
+        "oldest".parse::<PostOrder>(),
+        Err(PostOrderSegmentError(UnknownSegmentValue {
+            value: "oldest".to_string(),
+            allowed: SortOrder::SEGMENT_VALUES,
+        }))
+    );
+}
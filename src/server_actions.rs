@@ -0,0 +1,225 @@
+//! Server actions for progressive-enhancement forms: a request with another method than `GET`, like `POST /todos`,
+//! is handled by an action registered for the pattern of the route, while `GET /todos` renders the page.
+//!
+//! Actions are registered next to the routes instead of on them, so the same pattern can have a page and an action.
+//! Patterns are checked against the route table when they are registered, which is meant to happen at startup.
+
+use crate::Routable;
+use dioxus::prelude::*;
+use http::{Method, Uri};
+use std::str::FromStr;
+
+/// The reason an action could not be registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerActionError {
+    /// The route type has no route with this pattern. `valid` lists the patterns it has.
+    UnknownPattern {
+        pattern: String,
+        valid: Vec<&'static str>,
+    },
+    /// An action is already registered for this method and pattern
+    Duplicate {
+        method: Method,
+        pattern: &'static str,
+    },
+}
+
+impl std::fmt::Display for ServerActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownPattern { pattern, valid } => write!(
+                f,
+                "No route has the pattern '{pattern}'. The patterns are: {}",
+                valid.join(", ")
+            ),
+            Self::Duplicate { method, pattern } => {
+                write!(f, "{method} {pattern} already has an action")
+            }
+        }
+    }
+}
+
+struct ServerAction<B, T> {
+    method: Method,
+    pattern: &'static str,
+    /// Parse the request's uri and run the handler if it matches, or hand the body back
+    handle: Box<dyn Fn(&Uri, B) -> Result<T, B>>,
+}
+
+/// The actions of a server, whose handlers take the request body `B` and return `T`.
+pub struct ServerActions<B, T> {
+    actions: Vec<ServerAction<B, T>>,
+}
+
+/// Start registering server actions.
+pub fn server_actions<B, T>() -> ServerActions<B, T> {
+    ServerActions {
+        actions: Vec::new(),
+    }
+}
+
+impl<B: 'static, T: 'static> ServerActions<B, T> {
+    /// Handle requests with `method` to paths that parse as a route with `pattern`. The handler gets the parsed
+    /// route and the body of the request.
+    pub fn route<R: Routable + 'static>(
+        mut self,
+        method: Method,
+        pattern: &str,
+        handler: impl Fn(R, B) -> T + 'static,
+    ) -> Result<Self, ServerActionError>
+    where
+        <R as FromStr>::Err: std::fmt::Display,
+    {
+        let Some(pattern) = R::route_patterns()
+            .iter()
+            .copied()
+            .find(|known| *known == pattern)
+        else {
+            return Err(ServerActionError::UnknownPattern {
+                pattern: pattern.to_string(),
+                valid: R::route_patterns().to_vec(),
+            });
+        };
+        if self
+            .actions
+            .iter()
+            .any(|action| action.method == method && action.pattern == pattern)
+        {
+            return Err(ServerActionError::Duplicate { method, pattern });
+        }
+
+        let handle = move |uri: &Uri, body: B| {
+            // Routes that include the query parse it along with the path, like in the router
+            let parsed = match (R::parses_query(), uri.query()) {
+                (true, Some(query)) => R::from_str(&format!("{}?{query}", uri.path())),
+                _ => R::from_str(uri.path()),
+            };
+            match parsed {
+                Ok(route) if route.pattern() == pattern => Ok(handler(route, body)),
+                _ => Err(body),
+            }
+        };
+        self.actions.push(ServerAction {
+            method,
+            pattern,
+            handle: Box::new(handle),
+        });
+        Ok(self)
+    }
+
+    /// Run the action for the method and uri of a request. Returns the body if no action handles the request, so
+    /// the server can render the page instead.
+    pub fn dispatch(&self, method: &Method, uri: &Uri, mut body: B) -> Result<T, B> {
+        for action in self
+            .actions
+            .iter()
+            .filter(|action| action.method == *method)
+        {
+            body = match (action.handle)(uri, body) {
+                Ok(response) => return Ok(response),
+                Err(body) => body,
+            };
+        }
+        Err(body)
+    }
+
+    /// The `method` a form that submits to `route` should have: `post` if a `POST` action handles the route's
+    /// pattern, so the form still reaches the action without JavaScript, and `get` otherwise.
+    pub fn form_method<R: Routable>(&self, route: &R) -> &'static str
+    where
+        <R as FromStr>::Err: std::fmt::Display,
+    {
+        let pattern = route.pattern();
+        let has_post = self
+            .actions
+            .iter()
+            .any(|action| action.method == Method::POST && action.pattern == pattern);
+        match has_post {
+            true => "post",
+            false => "get",
+        }
+    }
+}
+
+#[derive(Props)]
+pub struct RouteFormProps<'a> {
+    /// The path the form submits to
+    action: String,
+    /// `get` or `post`, usually from [`ServerActions::form_method`]
+    #[props(default = "get")]
+    method: &'static str,
+    children: Element<'a>,
+}
+
+/// A form that submits to a route. With a `post` method it works without JavaScript, since the browser sends the
+/// request straight to the server action.
+#[allow(non_snake_case)]
+pub fn RouteForm<'a>(cx: Scope<'a, RouteFormProps<'a>>) -> Element<'a> {
+    render! {
+        form {
+            action: "{cx.props.action}",
+            method: cx.props.method,
+            &cx.props.children
+        }
+    }
+}
+
+#[test]
+fn server_action_routes() {
+    use crate::{AssetRoute, Route};
+
+    let actions = server_actions::<String, String>()
+        .route::<Route>(
+            Method::POST,
+            Route::pattern_of_variant("Route2").unwrap(),
+            |route, body| format!("{route}: {body}"),
+        )
+        .unwrap()
+        .route::<Route>(Method::DELETE, "/gone/(id)", |route, _| route.to_string())
+        .unwrap();
+
+    let uri: http::Uri = "/hello_world?x=1".parse().unwrap();
+    assert_eq!(
+        actions.dispatch(&Method::POST, &uri, "title=milk".to_string()),
+        Ok("/hello_world: title=milk".to_string())
+    );
+    // Other methods and patterns are left for the page
+    assert_eq!(
+        actions.dispatch(&Method::GET, &uri, "body".to_string()),
+        Err("body".to_string())
+    );
+    let uri: http::Uri = "/hello_world/1".parse().unwrap();
+    assert_eq!(
+        actions.dispatch(&Method::POST, &uri, "body".to_string()),
+        Err("body".to_string())
+    );
+    assert_eq!(actions.form_method(&Route::Route2 {}), "post");
+    assert_eq!(actions.form_method(&Route::Gone { id: 1 }), "get");
+
+    assert_eq!(Route::pattern_of_variant("Todos"), None);
+    let err = server_actions::<(), ()>()
+        .route::<AssetRoute>(Method::POST, "/todos", |_, _| ())
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "No route has the pattern '/todos'. The patterns are: /feed.xml, /avatar/(user_id).png, /avatar/(file)"
+    );
+    assert!(matches!(
+        actions.route::<Route>(Method::POST, "/hello_world", |_, _| String::new()),
+        Err(ServerActionError::Duplicate { .. })
+    ));
+
+    #[allow(non_snake_case)]
+    fn Form(cx: Scope) -> Element {
+        render! {
+            RouteForm { action: Route::Route2 {}.to_string(), method: "post", "Add" }
+        }
+    }
+    let mut vdom = VirtualDom::new(Form);
+    let _ = vdom.rebuild();
+    assert_eq!(
+        dioxus_ssr::render(&vdom),
+        "<form action=\"/hello_world\" method=\"post\">Add</form>"
+    );
+}
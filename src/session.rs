@@ -0,0 +1,148 @@
+//! Keeping the scroll positions and states of the history entries across reloads.
+//!
+//! A reload recreates the router, so the [`ScrollHistory`] it keeps for the entries it navigated through starts over,
+//! even though the browser still has the entries. With a [`RouterSession`] in
+//! [`RouterConfiguration::session`](crate::RouterConfiguration) the router stores it after every navigation and
+//! every saved scroll position, in `sessionStorage` with the `web` feature, under a key with the id of the router.
+//! When the router starts at the location it stored last, it picks the entries up again and scrolls back to where
+//! the page was. Stored data for another location or that doesn't parse is ignored.
+//!
+//! Sessions are off by default, since they write to the storage on every navigation.
+
+use crate::context::RouterId;
+use crate::fragment::ScrollHistory;
+
+/// Where a [`RouterSession`] keeps the entries, `sessionStorage` in a browser. Anything else, like a stub in tests,
+/// can stand in for it.
+pub trait SessionStorage {
+    fn load(&self, key: &str) -> Option<String>;
+    fn save(&mut self, key: &str, value: &str);
+}
+
+#[cfg(feature = "web")]
+impl SessionStorage for web_sys::Storage {
+    fn load(&self, key: &str) -> Option<String> {
+        self.get_item(key).ok().flatten()
+    }
+
+    fn save(&mut self, key: &str, value: &str) {
+        let _ = self.set_item(key, value);
+    }
+}
+
+/// The stored entries of one router.
+pub struct RouterSession {
+    key: String,
+    storage: Box<dyn SessionStorage>,
+}
+
+impl RouterSession {
+    /// Keep the entries of the router with the id `id` in `storage`
+    pub fn new(id: RouterId, storage: impl SessionStorage + 'static) -> Self {
+        Self {
+            key: format!("router-session-{}", id.0),
+            storage: Box::new(storage),
+        }
+    }
+
+    /// Keep the entries of the router with the id `id` in the `sessionStorage` of the page, or `None` if the page
+    /// has none, like in a sandboxed frame.
+    #[cfg(feature = "web")]
+    pub fn browser(id: RouterId) -> Option<Self> {
+        let storage = web_sys::window()?.session_storage().ok()??;
+        Some(Self::new(id, storage))
+    }
+
+    /// The entries stored for `location`, or `None` if the last stored location is another one or the stored
+    /// value doesn't parse.
+    pub fn restore(&self, location: &str, capacity: usize) -> Option<ScrollHistory> {
+        let stored = self.storage.load(&self.key)?;
+        let (stored_location, entries) = stored.split_once('\n')?;
+        if stored_location != location {
+            return None;
+        }
+        ScrollHistory::from_storage(entries, capacity)
+    }
+
+    /// Store the entries, with the location of the current one
+    pub fn save(&mut self, location: &str, scroll: &ScrollHistory) {
+        let value = format!("{location}\n{}", scroll.to_storage());
+        self.storage.save(&self.key, &value);
+    }
+}
+
+#[test]
+fn session_round_trip() {
+    use crate::fragment::ScrollRequest;
+    use crate::{Route, Router, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct MemoryStorage(Rc<RefCell<Option<(String, String)>>>);
+
+    impl SessionStorage for MemoryStorage {
+        fn load(&self, key: &str) -> Option<String> {
+            let stored = self.0.borrow();
+            stored
+                .as_ref()
+                .filter(|(other, _)| other == key)
+                .map(|(_, value)| value.clone())
+        }
+
+        fn save(&mut self, key: &str, value: &str) {
+            *self.0.borrow_mut() = Some((key.to_string(), value.to_string()));
+        }
+    }
+
+    let storage = MemoryStorage::default();
+    let router = |path: &str| {
+        let history = MemoryHistory::with_initial_path(path).unwrap();
+        let config = RouterConfiguration {
+            session: Some(RouterSession::new(RouterId("app"), storage.clone())),
+            ..Default::default()
+        };
+        Router::<Route>::with_configuration(history, config).unwrap()
+    };
+
+    let mut before = router("/hello_world");
+    before.save_scroll_position(120.0);
+    before.push_location_with(
+        Route::Route3 { dynamic: 1 },
+        "/hello_world/1#top".to_string(),
+        crate::NavigationSource::Programmatic,
+        Some("draft=yes&lines=1\n2".to_string()),
+        None,
+    );
+    before.save_scroll_position(40.5);
+    assert_eq!(
+        storage.0.borrow().as_ref().map(|(key, _)| key.as_str()),
+        Some("router-session-app")
+    );
+
+    // The reload starts at the same location, so the entries and their states come back
+    let mut after = router("/hello_world/1");
+    assert_eq!(after.scroll, before.scroll);
+    assert_eq!(
+        after.take_scroll_request(),
+        Some(ScrollRequest::Position(40.5))
+    );
+    assert_eq!(after.scroll.state(), Some("draft=yes&lines=1\n2"));
+    after.scroll.go_back();
+    assert_eq!(after.scroll.restore(), ScrollRequest::Position(120.0));
+
+    // Entries stored for another location, or that don't parse, are ignored
+    let fresh = ScrollHistory::new(None, 100);
+    assert_eq!(router("/").scroll, fresh);
+    for corrupt in [
+        "/\n",
+        "/\nindex=3&dropped_before=0&dropped_after=0\n",
+        "/\nindex=0&dropped_before=0&dropped_after=0\nposition=NaN",
+        "/\nindex=0&dropped_before=0\nfragment=%FF",
+        "/",
+    ] {
+        *storage.0.borrow_mut() = Some(("router-session-app".to_string(), corrupt.to_string()));
+        assert_eq!(router("/").scroll, fresh, "{corrupt:?}");
+    }
+}
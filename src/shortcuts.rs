@@ -0,0 +1,185 @@
+//! Keyboard shortcuts that navigate to routes, declared with `#[route("/inbox", shortcut = "g i")]`.
+//!
+//! A shortcut is a sequence of keys separated by spaces, compared with the `key` of keydown events. The keys of a
+//! sequence must be pressed within [`SEQUENCE_TIMEOUT`] of each other.
+
+use crate::context::use_router;
+use crate::{Routable, Router};
+use dioxus::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// How long after a key the next key of a sequence can be pressed
+pub const SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Matches key presses against the shortcuts of a route type.
+pub struct ShortcutMatcher<R> {
+    shortcuts: Vec<(Vec<String>, R)>,
+    pending: Vec<String>,
+    last_key: Option<Instant>,
+}
+
+impl<R: Routable> ShortcutMatcher<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    pub fn new() -> Self {
+        Self {
+            shortcuts: R::shortcuts()
+                .into_iter()
+                .map(|(keys, route)| (keys.split_whitespace().map(String::from).collect(), route))
+                .collect(),
+            pending: Vec::new(),
+            last_key: None,
+        }
+    }
+
+    /// Handle a key pressed at `now`. Returns the route of the shortcut the key completes.
+    pub fn key(&mut self, key: &str, now: Instant) -> Option<R> {
+        let timed_out = self
+            .last_key
+            .is_some_and(|last| now.duration_since(last) > SEQUENCE_TIMEOUT);
+        if timed_out {
+            self.pending.clear();
+        }
+        self.last_key = Some(now);
+        self.pending.push(key.to_string());
+
+        // A key that doesn't continue the sequence may still start a new one
+        if !self.continues_a_shortcut() {
+            self.pending = vec![key.to_string()];
+            if !self.continues_a_shortcut() {
+                self.pending.clear();
+                return None;
+            }
+        }
+        let (_, route) = self
+            .shortcuts
+            .iter()
+            .find(|(keys, _)| *keys == self.pending)?;
+        self.pending.clear();
+        Some(route.clone())
+    }
+
+    fn continues_a_shortcut(&self) -> bool {
+        self.shortcuts
+            .iter()
+            .any(|(keys, _)| keys.starts_with(&self.pending))
+    }
+}
+
+impl<R: Routable> Default for ShortcutMatcher<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The shortcuts of the nearest router, returned by [`use_route_shortcuts`].
+pub struct RouteShortcuts<R: Routable>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    router: Rc<RefCell<Router<R>>>,
+    matcher: RefCell<ShortcutMatcher<R>>,
+    editing: Cell<bool>,
+}
+
+impl<R: Routable> RouteShortcuts<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Handle the `key` of a keydown event and navigate if it completes a shortcut. Returns if it navigated.
+    pub fn keydown(&self, key: &str) -> bool {
+        if self.editing.get() {
+            return false;
+        }
+        let Some(route) = self.matcher.borrow_mut().key(key, Instant::now()) else {
+            return false;
+        };
+        self.router.borrow_mut().push(route);
+        true
+    }
+
+    /// Turn the shortcuts off while an editable element has focus, so typing in it doesn't navigate. Events don't
+    /// say which element they came from, so inputs report it from their `onfocusin` and `onfocusout` handlers.
+    pub fn set_editing(&self, editing: bool) {
+        self.editing.set(editing);
+    }
+}
+
+/// Navigate the nearest router with the route type `R` with the shortcuts of its routes. Pass the keydown events
+/// of a single listener on the root element to [`RouteShortcuts::keydown`].
+pub fn use_route_shortcuts<R: Routable + 'static>(cx: &ScopeState) -> Option<&RouteShortcuts<R>>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let router = use_router::<R>(cx)?.router().clone();
+    Some(cx.use_hook(|| RouteShortcuts {
+        router,
+        matcher: RefCell::new(ShortcutMatcher::new()),
+        editing: Cell::new(false),
+    }))
+}
+
+#[test]
+fn key_sequences() {
+    use crate::Route;
+
+    let mut matcher = ShortcutMatcher::<Route>::new();
+    let start = Instant::now();
+    let at = |millis| start + Duration::from_millis(millis);
+    assert_eq!(matcher.key("g", at(0)), None);
+    assert_eq!(matcher.key("w", at(500)), Some(Route::Route2 {}));
+
+    // A key that breaks a sequence can start the next one
+    assert_eq!(matcher.key("g", at(1000)), None);
+    assert_eq!(matcher.key("x", at(1100)), None);
+    assert_eq!(matcher.key("g", at(1200)), None);
+    assert_eq!(matcher.key("g", at(1300)), None);
+    assert_eq!(matcher.key("h", at(1400)), Some(Route::Route5 {}));
+
+    // The keys of a sequence must be pressed within the timeout
+    assert_eq!(matcher.key("g", at(2000)), None);
+    assert_eq!(matcher.key("h", at(3500)), None);
+}
+
+#[test]
+fn shortcuts_navigate_outside_of_inputs() {
+    use crate::context::{use_shared_router_provider, RouterId};
+    use crate::Route;
+    use dioxus_router_core::history::MemoryHistory;
+
+    let history = MemoryHistory::with_initial_path("/").unwrap();
+    let router = Rc::new(RefCell::new(Router::<Route>::new(history).unwrap()));
+
+    struct AppProps {
+        router: Rc<RefCell<Router<Route>>>,
+    }
+
+    #[allow(non_snake_case)]
+    fn App(cx: Scope<AppProps>) -> Element {
+        use_shared_router_provider(cx, RouterId("app"), &cx.props.router);
+        let shortcuts = use_route_shortcuts::<Route>(cx).unwrap();
+        shortcuts.set_editing(true);
+        assert!(!shortcuts.keydown("g"));
+        assert!(!shortcuts.keydown("w"));
+        shortcuts.set_editing(false);
+        assert!(!shortcuts.keydown("g"));
+        assert!(shortcuts.keydown("w"));
+        None
+    }
+
+    let mut vdom = VirtualDom::new_with_props(
+        App,
+        AppProps {
+            router: router.clone(),
+        },
+    );
+    let _ = vdom.rebuild();
+    assert_eq!(router.borrow().route, Route::Route2 {});
+}
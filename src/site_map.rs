@@ -0,0 +1,69 @@
+//! The routes of a routable type as data, for static site generators and navigation menus.
+//!
+//! Every derived type has a `SITE_MAP` with a [`RouteInfo`] for each variant, in the order they are declared, and a
+//! `static_routes` function that builds every variant without dynamic segments, which are the pages a static site
+//! generator can render without knowing any values. The `#[route(not_found)]` variant has no pattern of its own, so
+//! it is in neither.
+
+/// A variant of a routable type and the segments of its pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteInfo {
+    /// The name of the variant
+    pub variant: &'static str,
+    /// The pattern of the variant, like `/blog/(id)`. Localized patterns and aliases aren't listed.
+    pub pattern: &'static str,
+    /// The segments of the pattern, in the order they appear in it
+    pub segments: &'static [SegmentInfo],
+}
+
+impl RouteInfo {
+    /// If the route has no dynamic segments, so it has a single path
+    pub fn is_static(&self) -> bool {
+        self.segments
+            .iter()
+            .all(|segment| matches!(segment, SegmentInfo::Static(_)))
+    }
+}
+
+/// A segment of a [`RouteInfo`]. Dynamic segments have the name of their field and the type as it is written in the
+/// route, like `Vec<String>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentInfo {
+    /// A segment that is always this text
+    Static(&'static str),
+    /// `(name)`, including a prefix or suffix around it, which are part of the pattern
+    Dynamic(&'static str, &'static str),
+    /// `(...name)`, which takes the rest of the path
+    CatchAll(&'static str, &'static str),
+    /// `(?name)`, a segment that can be left out at the end of the path
+    Optional(&'static str, &'static str),
+    /// `;(name)`, the matrix params of the segment before it
+    Matrix(&'static str, &'static str),
+    /// `?(name)` after the path
+    Query(&'static str, &'static str),
+    /// `#(name)` at the end of the location
+    Fragment(&'static str, &'static str),
+}
+
+#[test]
+fn site_map() {
+    use crate::ArticleRoute;
+
+    assert_eq!(
+        ArticleRoute::SITE_MAP[1],
+        RouteInfo {
+            variant: "Article",
+            pattern: "/blog/(id)",
+            segments: &[
+                SegmentInfo::Static("blog"),
+                SegmentInfo::Dynamic("id", "u32")
+            ],
+        }
+    );
+    assert!(ArticleRoute::SITE_MAP[0].is_static());
+    assert!(!ArticleRoute::SITE_MAP[2].is_static());
+    assert_eq!(
+        ArticleRoute::static_routes(),
+        vec![ArticleRoute::ArticleHome {}]
+    );
+}
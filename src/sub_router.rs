@@ -0,0 +1,355 @@
+//! Paths under a prefix handed to routes the host app doesn't know the type of, like a settings app built
+//! separately and mounted at `/settings`.
+//!
+//! The guest provides a [`SubRouterHandle`], usually [`GuestRoutes`] of its route type, and the host mounts it with
+//! [`RouterConfiguration::sub_routers`](crate::RouterConfiguration). The host declares a route with a catch-all at
+//! the prefix, like `/settings/(...rest)`, so the host router keeps the only history and its current route and url
+//! cover the guest pages too. The component of that route renders [`render_sub_router`], which hands the rest of the
+//! url to the guest. Guest components navigate with the [`SubRouterNavigator`] in their context: it writes the path
+//! of a guest route under the prefix and pushes it on the host router.
+//!
+//! Guest routes cross to the host as [`AnyRoute`] and paths as strings, so the host only depends on the handle.
+
+#[cfg(test)]
+use super::*;
+use crate::context::RouterContext;
+use crate::failure::render_fallback;
+use crate::{Routable, Router};
+use dioxus::prelude::*;
+use std::any::Any;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// A route of any type, to hand to a [`SubRouterHandle`] without naming the type.
+pub trait AnyRoute: Any {
+    /// The path of the route, like its `Display`
+    fn path(&self) -> String;
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<R: Routable + 'static> AnyRoute for R
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    fn path(&self) -> String {
+        self.to_string()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// The routes of a guest, as the host sees them. Paths are the part of the url after the prefix, starting with `/`.
+pub trait SubRouterHandle {
+    /// Check that a path parses as a guest route, or why it doesn't
+    fn check(&self, path: &str) -> Result<(), String>;
+
+    /// Render the guest route of a path
+    fn render<'a>(&self, cx: &'a ScopeState, path: &str) -> Element<'a>;
+
+    /// The path of a guest route, or `None` if the route isn't one of the guest's
+    fn path_of(&self, route: &dyn AnyRoute) -> Option<String>;
+}
+
+/// The [`SubRouterHandle`] of a route type.
+pub struct GuestRoutes<G>(PhantomData<G>);
+
+impl<G: Routable + 'static> GuestRoutes<G>
+where
+    <G as FromStr>::Err: std::fmt::Display,
+{
+    pub fn handle() -> Rc<dyn SubRouterHandle> {
+        Rc::new(Self(PhantomData))
+    }
+}
+
+impl<G: Routable + 'static> SubRouterHandle for GuestRoutes<G>
+where
+    <G as FromStr>::Err: std::fmt::Display,
+{
+    fn check(&self, path: &str) -> Result<(), String> {
+        G::from_str(path).map(drop).map_err(|err| err.to_string())
+    }
+
+    fn render<'a>(&self, cx: &'a ScopeState, path: &str) -> Element<'a> {
+        match G::from_str(path) {
+            Ok(route) => route.render(cx),
+            Err(err) => render_fallback(cx, &err.to_string()),
+        }
+    }
+
+    fn path_of(&self, route: &dyn AnyRoute) -> Option<String> {
+        route.as_any().downcast_ref::<G>().map(ToString::to_string)
+    }
+}
+
+/// A guest mounted under a prefix of the host's paths.
+#[derive(Clone)]
+pub struct MountedSubRouter {
+    /// The prefix, like `/settings`. It matches whole segments, like the excluded prefixes of the router
+    pub prefix: String,
+    pub handle: Rc<dyn SubRouterHandle>,
+}
+
+impl MountedSubRouter {
+    pub fn new(prefix: &str, handle: Rc<dyn SubRouterHandle>) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            handle,
+        }
+    }
+
+    /// The path the guest sees for a url of the host, if the url is under the prefix
+    pub fn guest_path(&self, url: &str) -> Option<String> {
+        crate::link::excluded_prefix(std::slice::from_ref(&self.prefix), url)?;
+        let rest = &url[self.prefix.len()..];
+        match rest.starts_with('/') {
+            true => Some(rest.to_string()),
+            false => Some(format!("/{rest}")),
+        }
+    }
+
+    /// The location of a guest route in the host, or `None` if the route isn't one of the guest's. The root of the
+    /// guest is the prefix itself.
+    pub fn location_of(&self, route: &dyn AnyRoute) -> Option<String> {
+        let path = self.handle.path_of(route)?;
+        match path.strip_prefix('/').unwrap_or(&path) {
+            "" => Some(self.prefix.clone()),
+            rest if rest.starts_with(['?', '#']) => Some(format!("{}{rest}", self.prefix)),
+            rest => Some(format!("{}/{rest}", self.prefix)),
+        }
+    }
+}
+
+/// The reason a guest route couldn't be navigated to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuestNavigationError {
+    /// No mounted guest has routes of this type
+    NotMounted,
+    /// The location of the route doesn't parse as a route of the host, so the host has no route at the prefix
+    NotRouted { location: String, error: String },
+}
+
+impl std::fmt::Display for GuestNavigationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotMounted => write!(f, "No sub router is mounted for the route"),
+            Self::NotRouted { location, error } => write!(
+                f,
+                "The host has no route for '{location}', which a sub router is mounted at: {error}"
+            ),
+        }
+    }
+}
+
+impl<R: Routable> Router<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// The mounted guest a url of the host is under, and the path the guest sees
+    pub fn sub_router_at(&self, url: &str) -> Option<(&MountedSubRouter, String)> {
+        self.sub_routers
+            .iter()
+            .find_map(|mounted| Some((mounted, mounted.guest_path(url)?)))
+    }
+
+    /// Navigate to a route of a mounted guest, adding a new entry to the history of this router.
+    pub fn push_guest(&mut self, route: &dyn AnyRoute) -> Result<(), GuestNavigationError> {
+        let location = self
+            .sub_routers
+            .iter()
+            .find_map(|mounted| mounted.location_of(route))
+            .ok_or(GuestNavigationError::NotMounted)?;
+        let host = self
+            .parse(&location)
+            .map_err(|err| GuestNavigationError::NotRouted {
+                location: location.clone(),
+                error: err.to_string(),
+            })?;
+        let source = self.programmatic_source();
+        self.push_location(host, location, source);
+        Ok(())
+    }
+}
+
+/// How guest components navigate: the routes they push go through the host router.
+#[derive(Clone)]
+pub struct SubRouterNavigator {
+    push: Rc<dyn Fn(&dyn AnyRoute) -> Result<(), GuestNavigationError>>,
+}
+
+impl SubRouterNavigator {
+    pub fn new<R: Routable + 'static>(router: Rc<RefCell<Router<R>>>) -> Self
+    where
+        <R as FromStr>::Err: std::fmt::Display,
+    {
+        Self {
+            push: Rc::new(move |route| router.borrow_mut().push_guest(route)),
+        }
+    }
+
+    /// Navigate to a guest route
+    pub fn push(&self, route: &dyn AnyRoute) -> Result<(), GuestNavigationError> {
+        (self.push)(route)
+    }
+}
+
+/// Render the guest the current url of the nearest router with the route type `R` is under, for the component of
+/// the host route at the prefix. Guest components get a [`SubRouterNavigator`] in their context.
+pub fn render_sub_router<R: Routable + 'static>(cx: &ScopeState) -> Element
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let context = cx.consume_context::<RouterContext<R>>()?;
+    use_context_provider(cx, || SubRouterNavigator::new(context.router().clone()));
+    let router = context.router().borrow();
+    let url = router.current_url();
+    let (mounted, path) = router.sub_router_at(&url)?;
+    let handle = mounted.handle.clone();
+    drop(router);
+    match handle.check(&path) {
+        Ok(()) => handle.render(cx, &path),
+        Err(err) => render_fallback(cx, &err),
+    }
+}
+
+#[test]
+fn sub_routers() {
+    use crate::context::{use_shared_router_provider, RouterId};
+    use crate::RouterConfiguration;
+    use dioxus_router_core::history::MemoryHistory;
+
+    let mounted = MountedSubRouter::new("/settings/", GuestRoutes::<GuestRoute>::handle());
+    assert_eq!(
+        mounted.location_of(&GuestRoute::Invoice { id: 3 }),
+        Some("/settings/invoices/3".to_string())
+    );
+    assert_eq!(
+        mounted.location_of(&GuestRoute::Profile {}),
+        Some("/settings".to_string())
+    );
+    assert_eq!(mounted.location_of(&HostRoute::Home {}), None);
+    assert_eq!(mounted.guest_path("/settings"), Some("/".to_string()));
+    assert_eq!(
+        mounted.guest_path("/settings/invoices/3"),
+        Some("/invoices/3".to_string())
+    );
+    assert_eq!(mounted.guest_path("/settingsx"), None);
+    assert!(mounted.handle.check("/invoices/x").is_err());
+
+    let router = |path: &str| {
+        let history = MemoryHistory::with_initial_path(path).unwrap();
+        let config = RouterConfiguration {
+            sub_routers: vec![mounted.clone()],
+            ..Default::default()
+        };
+        Rc::new(RefCell::new(
+            Router::<HostRoute>::with_configuration(history, config).unwrap(),
+        ))
+    };
+
+    // A guest navigation is a navigation of the host, with one history
+    let host = router("/");
+    let navigator = SubRouterNavigator::new(host.clone());
+    navigator.push(&GuestRoute::Invoice { id: 3 }).unwrap();
+    assert_eq!(
+        host.borrow().route,
+        HostRoute::Settings {
+            rest: vec!["invoices".to_string(), "3".to_string()]
+        }
+    );
+    assert_eq!(host.borrow().current_url().as_ref(), "/settings/invoices/3");
+    host.borrow_mut().go_back().unwrap();
+    assert_eq!(host.borrow().route, HostRoute::Home {});
+    assert_eq!(
+        navigator.push(&HostRoute::Home {}),
+        Err(GuestNavigationError::NotMounted)
+    );
+
+    struct RootProps {
+        router: Rc<RefCell<Router<HostRoute>>>,
+    }
+
+    fn root(cx: Scope<RootProps>) -> Element {
+        use_shared_router_provider(cx, RouterId("host"), &cx.props.router);
+        let route = cx.props.router.borrow().route.clone();
+        route.render(cx)
+    }
+
+    let render = |path: &str| {
+        let mut vdom = VirtualDom::new_with_props(
+            root,
+            RootProps {
+                router: router(path),
+            },
+        );
+        let _ = vdom.rebuild();
+        dioxus_ssr::render(&vdom)
+    };
+    assert!(render("/settings/invoices/7").contains("Invoice 7"));
+    assert!(render("/settings").contains("Profile"));
+    // The host routes the prefix, but the guest has no route for the rest
+    assert!(render("/settings/nope").contains("Something went wrong"));
+
+    let config = RouterConfiguration::<crate::ItemRoute> {
+        sub_routers: vec![mounted],
+        ..Default::default()
+    };
+    assert_eq!(
+        config.validate(),
+        Err(vec![crate::validate::ConfigError::UnroutedSubRouter {
+            prefix: "/settings".to_string()
+        }])
+    );
+}
+
+/// An app that hands everything under `/settings` to the routes of a separately built guest
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum HostRoute {
+    #[route("/" HostHome)]
+    Home {},
+    #[route("/settings/(...rest)" HostSettings)]
+    Settings { rest: Vec<String> },
+}
+
+/// The routes of the guest mounted at `/settings`, which the host only knows through its handle
+#[cfg(test)]
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum GuestRoute {
+    #[route("/" GuestProfile)]
+    Profile {},
+    #[route("/invoices/(id)" GuestInvoice)]
+    Invoice { id: u32 },
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn HostHome(cx: Scope) -> Element {
+    render! { "Home" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn HostSettings(cx: Scope, rest: Vec<String>) -> Element {
+    let _ = rest;
+    render_sub_router::<HostRoute>(cx)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+fn GuestProfile(cx: Scope) -> Element {
+    render! { "Profile" }
+}
+
+#[cfg(test)]
+#[inline_props]
+#[allow(non_snake_case)]
+fn GuestInvoice(cx: Scope, id: u32) -> Element {
+    render! { "Invoice {id}" }
+}
@@ -0,0 +1,153 @@
+//! Navigate to a route together with the state of its history entry and where to scroll, as one navigation.
+//!
+//! Setting the state after navigating would let subscribers see the new route with the state of the entry before
+//! it. A [`NavigationTransaction`] collects everything first, and [`NavigationTransaction::commit`] makes one push or
+//! replace on the history and notifies the subscribers once, after the state is set. The state is kept by the
+//! router with the entry, like its scroll position, so it works the same with every history.
+
+use crate::fragment::ScrollRequest;
+use crate::{Routable, Router};
+use std::str::FromStr;
+
+/// A navigation that is only made when it is committed, returned by [`Router::transaction`]. Dropping it without
+/// committing does nothing.
+#[must_use = "a transaction does nothing until it is committed"]
+pub struct NavigationTransaction<'a, R: Routable>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    router: &'a mut Router<R>,
+    route: R,
+    replace: bool,
+    state: Option<String>,
+    scroll: Option<ScrollRequest>,
+}
+
+impl<'a, R: Routable> NavigationTransaction<'a, R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Replace the current history entry instead of pushing a new one.
+    pub fn replace(mut self) -> Self {
+        self.replace = true;
+        self
+    }
+
+    /// The state of the new entry, returned by [`Router::current_state`] until the entry is replaced.
+    pub fn state(mut self, state: impl Into<String>) -> Self {
+        self.state = Some(state.into());
+        self
+    }
+
+    /// Where to scroll after the navigation, instead of the fragment of the route or the top.
+    pub fn scroll(mut self, scroll: ScrollRequest) -> Self {
+        self.scroll = Some(scroll);
+        self
+    }
+
+    /// Make the navigation. Guards run like for [`Router::push`], and the state and scroll target go with the entry
+    /// the guards end at.
+    pub fn commit(self) {
+        let Self {
+            router,
+            route,
+            replace,
+            state,
+            scroll,
+        } = self;
+        let location = router.programmatic_location(&route);
+        let source = router.programmatic_source();
+        match replace {
+            true => router.replace_location_with(route, location, source, state, scroll),
+            false => router.push_location_with(route, location, source, state, scroll),
+        }
+    }
+}
+
+impl<R: Routable> Router<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Start a navigation to `route` that also sets the state of its history entry or where to scroll. Nothing
+    /// happens until [`NavigationTransaction::commit`].
+    pub fn transaction(&mut self, route: R) -> NavigationTransaction<'_, R> {
+        NavigationTransaction {
+            router: self,
+            route,
+            replace: false,
+            state: None,
+            scroll: None,
+        }
+    }
+
+    /// The state of the current history entry, set with [`Router::transaction`].
+    pub fn current_state(&self) -> Option<&str> {
+        self.scroll.state()
+    }
+}
+
+#[test]
+fn transactions() {
+    use crate::Route;
+    use dioxus_router_core::history::MemoryHistory;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let history = MemoryHistory::with_initial_path("/hello_world").unwrap();
+    let mut router = Router::<Route>::new(history).unwrap();
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    router.subscribers.subscribe({
+        let changes = changes.clone();
+        move |change: crate::RouteChange<Route>| {
+            changes
+                .borrow_mut()
+                .push((change.route.clone(), change.state.map(String::from)));
+        }
+    });
+
+    // Dropped transactions do nothing
+    drop(router.transaction(Route::Route5 {}).state("dropped"));
+    assert_eq!(router.route, Route::Route2 {});
+    assert!(changes.borrow().is_empty());
+
+    router
+        .transaction(Route::Route3 { dynamic: 1 })
+        .state("{\"tab\":2}")
+        .scroll(ScrollRequest::Position(300.0))
+        .commit();
+    assert_eq!(router.current_state(), Some("{\"tab\":2}"));
+    assert_eq!(
+        router.take_scroll_request(),
+        Some(ScrollRequest::Position(300.0))
+    );
+
+    router.transaction(Route::Route5 {}).replace().commit();
+    assert_eq!(router.current_state(), None);
+    assert!(!router.history.can_go_forward());
+
+    router.push(Route::Route3 { dynamic: 2 });
+    router.go_back().unwrap();
+    router
+        .transaction(Route::Route3 { dynamic: 3 })
+        .state("3")
+        .commit();
+    router.go_back().unwrap();
+    router.go_forward().unwrap();
+
+    // Every route was observed with the state of its own entry, and only once
+    assert_eq!(
+        *changes.borrow(),
+        [
+            (
+                Route::Route3 { dynamic: 1 },
+                Some("{\"tab\":2}".to_string())
+            ),
+            (Route::Route5 {}, None),
+            (Route::Route3 { dynamic: 2 }, None),
+            (Route::Route5 {}, None),
+            (Route::Route3 { dynamic: 3 }, Some("3".to_string())),
+            (Route::Route5 {}, None),
+            (Route::Route3 { dynamic: 3 }, Some("3".to_string())),
+        ]
+    );
+}
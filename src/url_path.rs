@@ -0,0 +1,188 @@
+use crate::encoding::{decode_path_segment, EncodedSegment};
+use crate::segments::{FromRouteSegments, ToRouteSegments};
+use std::path::{Path, PathBuf};
+
+/// A catch-all path that is safe to resolve against a directory.
+///
+/// Every segment is percent-decoded and checked when it is parsed, so a `UrlPath` can never contain `.` or `..`
+/// components or a segment that decodes to a path separator. Joining it onto a base directory can't leave that
+/// directory.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct UrlPath {
+    segments: Vec<String>,
+}
+
+/// Which components [`UrlPath::parse`] accepts beyond plain file names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UrlPathRules {
+    /// Skip empty components (`a//b`) instead of rejecting them. A single trailing empty component from a
+    /// trailing slash is always skipped.
+    pub allow_empty: bool,
+}
+
+/// The reason a catch-all path was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlPathError {
+    /// The segment does not decode to valid UTF-8.
+    InvalidUtf8(String),
+    /// The segment is `.` or `..`.
+    DotSegment(String),
+    /// The segment is empty.
+    EmptySegment,
+    /// The segment decodes to a path separator or a nul byte, like `a%2F..`.
+    ForbiddenCharacter(String),
+}
+
+impl std::fmt::Display for UrlPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidUtf8(segment) => write!(f, "'{segment}' is not valid UTF-8"),
+            Self::DotSegment(segment) => write!(f, "'{segment}' components are not allowed"),
+            Self::EmptySegment => write!(f, "empty components are not allowed"),
+            Self::ForbiddenCharacter(segment) => {
+                write!(f, "'{segment}' contains a path separator or nul byte")
+            }
+        }
+    }
+}
+
+impl UrlPath {
+    /// Parse the segments of a catch-all with the given rules.
+    pub fn parse<'a>(
+        segments: impl Iterator<Item = &'a str>,
+        rules: UrlPathRules,
+    ) -> Result<Self, UrlPathError> {
+        let mut segments = segments.peekable();
+        let mut decoded_segments = Vec::new();
+
+        while let Some(segment) = segments.next() {
+            if segment.is_empty() {
+                if rules.allow_empty || segments.peek().is_none() {
+                    continue;
+                }
+                return Err(UrlPathError::EmptySegment);
+            }
+
+            let decoded = decode_path_segment(segment)
+                .ok_or_else(|| UrlPathError::InvalidUtf8(segment.to_string()))?;
+            if decoded == "." || decoded == ".." {
+                return Err(UrlPathError::DotSegment(decoded.into_owned()));
+            }
+            if decoded.contains(['/', '\\', '\0']) {
+                return Err(UrlPathError::ForbiddenCharacter(segment.to_string()));
+            }
+            decoded_segments.push(decoded.into_owned());
+        }
+
+        Ok(Self {
+            segments: decoded_segments,
+        })
+    }
+
+    /// The decoded segments of the path.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().map(String::as_str)
+    }
+
+    /// Resolve the path inside a base directory.
+    ///
+    /// Segments are checked when the path is parsed, so the result is always inside `base`.
+    pub fn join(&self, base: &Path) -> PathBuf {
+        let mut path = base.to_path_buf();
+        for segment in &self.segments {
+            path.push(segment);
+        }
+        path
+    }
+}
+
+impl FromRouteSegments for UrlPath {
+    type Err = UrlPathError;
+
+    fn from_route_segments<'a, I>(segments: I) -> Result<Self, Self::Err>
+    where
+        I: Iterator<Item = &'a str> + Clone,
+    {
+        Self::parse(segments, UrlPathRules::default())
+    }
+}
+
+impl ToRouteSegments for UrlPath {
+    fn display_route_segments(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{}", EncodedSegment(segment))?;
+        }
+        Ok(())
+    }
+
+    fn route_segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    fn has_route_segments(&self) -> bool {
+        !self.segments.is_empty()
+    }
+}
+
+#[test]
+fn url_path_rejects_traversal() {
+    let parse = |path: &str| UrlPath::from_route_segments(path.split('/'));
+
+    let path = parse("guide/%E4%BD%A0%E5%A5%BD/intro.md/").unwrap();
+    assert_eq!(
+        path.iter().collect::<Vec<_>>(),
+        ["guide", "你好", "intro.md"]
+    );
+    assert_eq!(
+        path.join(Path::new("/srv/docs")),
+        Path::new("/srv/docs/guide/你好/intro.md")
+    );
+    assert_eq!(
+        path.to_route_segments_string(),
+        "guide/%E4%BD%A0%E5%A5%BD/intro.md"
+    );
+
+    assert_eq!(
+        parse("a/../b"),
+        Err(UrlPathError::DotSegment("..".to_string()))
+    );
+    assert_eq!(
+        parse("a/%2E%2E/b"),
+        Err(UrlPathError::DotSegment("..".to_string()))
+    );
+    assert_eq!(parse("./a"), Err(UrlPathError::DotSegment(".".to_string())));
+    assert_eq!(
+        parse("a%2F..%2Fb"),
+        Err(UrlPathError::ForbiddenCharacter("a%2F..%2Fb".to_string()))
+    );
+    assert_eq!(parse("a//b"), Err(UrlPathError::EmptySegment));
+
+    let lenient = UrlPathRules { allow_empty: true };
+    let path = UrlPath::parse("a//b".split('/'), lenient).unwrap();
+    assert_eq!(path.iter().collect::<Vec<_>>(), ["a", "b"]);
+}
+
+#[test]
+fn url_path_routes() {
+    use crate::{DocsParseError, Route, RouteMatchError};
+    use std::str::FromStr;
+
+    assert_eq!(
+        Route::from_str("/docs/guide/intro.md"),
+        Ok(Route::Docs {
+            path: UrlPath::from_route_segments("guide/intro.md".split('/')).unwrap()
+        })
+    );
+
+    let err = Route::from_str("/docs/../secret").unwrap_err();
+    assert!(err.attempted_routes.iter().any(|err| matches!(
+        err,
+        RouteMatchError::Docs {
+            error: DocsParseError::pathParseError(UrlPathError::DotSegment(_)),
+            ..
+        }
+    )));
+}
@@ -0,0 +1,165 @@
+//! Check a [`RouterConfiguration`] against the routes of `R` when the router starts, so a typo in a rewrite rule or
+//! an excluded prefix shows up before a user hits it.
+//!
+//! Guards, actions and the error route are functions the routes and the configuration name directly, so the
+//! compiler already checks them, and a [`BaseUrl`](crate::link::BaseUrl) only parses from an absolute url and drops
+//! the trailing slash of its path. What is left are the strings of the configuration.
+
+use crate::edit_distance::edit_distance;
+use crate::pattern::{PatternSegment, RoutePattern};
+use crate::{link, Routable, RouterConfiguration};
+use std::str::FromStr;
+
+/// A part of a [`RouterConfiguration`] that doesn't fit the routes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// No route matches the target of a rewrite rule, so every path it rewrites fails to parse. `closest` is the
+    /// pattern closest to the target, if there are any routes
+    UnmatchedRewrite {
+        rule: String,
+        closest: Option<&'static str>,
+    },
+    /// Every path of the route with this pattern is under an excluded prefix, so the router can never show it
+    ShadowedRoute {
+        prefix: String,
+        pattern: &'static str,
+    },
+    /// No route parses the prefix of a mounted sub router, so the router can never show the guest
+    UnroutedSubRouter { prefix: String },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnmatchedRewrite { rule, closest } => {
+                write!(f, "No route matches the target of the rewrite rule {rule}")?;
+                match closest {
+                    Some(closest) => write!(f, ", the closest route is {closest}"),
+                    None => Ok(()),
+                }
+            }
+            Self::ShadowedRoute { prefix, pattern } => {
+                write!(f, "The excluded prefix {prefix} covers the route {pattern}")
+            }
+            Self::UnroutedSubRouter { prefix } => {
+                write!(f, "No route parses {prefix}, where a sub router is mounted")
+            }
+        }
+    }
+}
+
+impl<R: Routable> RouterConfiguration<R>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    /// Check the configuration against the routes of `R`, returning every problem at once.
+    ///
+    /// The router does this when it starts if [`RouterConfiguration::validate_on_start`] is set, which it is in
+    /// debug builds, and panics with the errors.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let patterns: Vec<_> = R::route_patterns()
+            .iter()
+            .filter_map(|pattern| Some((*pattern, RoutePattern::parse(pattern).ok()?)))
+            .collect();
+        let mut errors = Vec::new();
+
+        for rule in &self.rewrites {
+            if !patterns
+                .iter()
+                .any(|(_, pattern)| rule.target_may_match(pattern))
+            {
+                let target = rule.target();
+                errors.push(ConfigError::UnmatchedRewrite {
+                    rule: rule.to_string(),
+                    closest: patterns
+                        .iter()
+                        .map(|(source, _)| *source)
+                        .min_by_key(|source| edit_distance(&target, source)),
+                });
+            }
+        }
+
+        for (source, pattern) in &patterns {
+            // Routes with a dynamic segment inside the prefix, like `/(page)` for `/static`, keep their other paths
+            if let Some(prefix) =
+                link::excluded_prefix(&self.excluded_prefixes, &static_prefix(pattern))
+            {
+                errors.push(ConfigError::ShadowedRoute {
+                    prefix: prefix.to_string(),
+                    pattern: source,
+                });
+            }
+        }
+
+        for mounted in &self.sub_routers {
+            if R::from_str(&mounted.prefix).is_err() {
+                errors.push(ConfigError::UnroutedSubRouter {
+                    prefix: mounted.prefix.clone(),
+                });
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+/// The static segments a pattern starts with, like `/docs/api` for `/docs/api/(page)`
+fn static_prefix(pattern: &RoutePattern) -> String {
+    let mut prefix = String::new();
+    for segment in pattern.segments() {
+        match segment {
+            PatternSegment::Static(segment) => {
+                prefix.push('/');
+                prefix.push_str(segment);
+            }
+            _ => break,
+        }
+    }
+    prefix
+}
+
+#[test]
+fn validate_configuration() {
+    use crate::rewrite::RewriteRule;
+    use crate::Route;
+
+    let config = RouterConfiguration::<Route> {
+        rewrites: vec![
+            RewriteRule::new("/old/(id)", "/hello_world/(id)").unwrap(),
+            RewriteRule::new("/old/(id)/edit", "/hello_wrld/(id)/edit").unwrap(),
+        ],
+        excluded_prefixes: vec!["/static".to_string(), "/docs".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        config.validate(),
+        Err(vec![
+            ConfigError::UnmatchedRewrite {
+                rule: "/old/(id)/edit -> /hello_wrld/(id)/edit".to_string(),
+                closest: Some("/hello_world/(dynamic)"),
+            },
+            ConfigError::ShadowedRoute {
+                prefix: "/docs".to_string(),
+                pattern: "/docs/(...path)",
+            },
+        ])
+    );
+    assert!(RouterConfiguration::<Route>::default().validate().is_ok());
+}
+
+#[test]
+#[should_panic(expected = "The excluded prefix /hello_world covers the route /hello_world")]
+fn validate_on_start() {
+    use crate::{Route, Router};
+
+    let config = RouterConfiguration {
+        excluded_prefixes: vec!["/hello_world".to_string()],
+        validate_on_start: true,
+        ..Default::default()
+    };
+    let history = dioxus_router_core::history::MemoryHistory::default();
+    let _ = Router::<Route>::with_configuration(history, config);
+}
@@ -0,0 +1,103 @@
+//! A watermark with the pattern of the rendered route, so screenshots taken while testing show which route made
+//! the page.
+//!
+//! Turned on with [`RouterConfiguration::route_watermarks`](crate::RouterConfiguration) and the `devtools` feature.
+//! The watermark is rendered after the route as a sibling with a fixed position, so the elements of the route keep
+//! their parents and `:first-child` selectors still match them. Pages rendered with
+//! [`server_render`](crate::failure::server_render) never have one.
+
+use crate::context::RouterContext;
+use crate::Routable;
+use dioxus::prelude::*;
+use std::str::FromStr;
+
+/// The class of the watermark element
+pub const WATERMARK_CLASS: &str = "router-watermark";
+
+const WATERMARK_STYLE: &str = "position: fixed; bottom: 4px; right: 4px; z-index: 2147483647; \
+    pointer-events: none; padding: 1px 4px; font: 11px monospace; color: #555; background: #fffc; opacity: 0.7;";
+
+/// The text of the watermark of a route: the route type and the pattern, and how many routers of the same type the
+/// router is nested in, if any.
+pub fn watermark_label<R: Routable>(route: &R, depth: usize) -> String
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let name = std::any::type_name::<R>()
+        .rsplit("::")
+        .next()
+        .unwrap_or_default();
+    match depth {
+        0 => format!("{name} {}", route.pattern()),
+        depth => format!("{name} {} (nested {depth})", route.pattern()),
+    }
+}
+
+/// Render a route with the watermark of the nearest router with the route type `R`, if it has watermarks turned on.
+pub fn render_route<'a, R: Routable + 'static>(cx: &'a ScopeState, route: R) -> Element<'a>
+where
+    <R as FromStr>::Err: std::fmt::Display,
+{
+    let label = cx
+        .consume_context::<RouterContext<R>>()
+        .filter(|context| context.router().borrow().route_watermarks)
+        .map(|context| watermark_label(&route, context.depth()));
+    let node = route.render(cx);
+    match label {
+        Some(label) => render! {
+            node
+            div { class: WATERMARK_CLASS, style: WATERMARK_STYLE, "{label}" }
+        },
+        None => node,
+    }
+}
+
+#[test]
+fn route_watermarks() {
+    use crate::context::{use_shared_router_provider, RouterId};
+    use crate::failure::server_render;
+    use crate::{Route, Router, RouterConfiguration};
+    use dioxus_router_core::history::MemoryHistory;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct RootProps {
+        router: Rc<RefCell<Router<Route>>>,
+    }
+
+    fn root(cx: Scope<RootProps>) -> Element {
+        use_shared_router_provider(cx, RouterId("qa"), &cx.props.router);
+        let route = cx.props.router.borrow().route.clone();
+        render_route(cx, route)
+    }
+
+    let router = |route_watermarks: bool| {
+        let history = MemoryHistory::with_initial_path("/hello_world/3").unwrap();
+        let config = RouterConfiguration {
+            route_watermarks,
+            ..Default::default()
+        };
+        Router::<Route>::with_configuration(history, config).unwrap()
+    };
+    let render = |route_watermarks: bool| {
+        let router = Rc::new(RefCell::new(router(route_watermarks)));
+        let mut vdom = VirtualDom::new_with_props(root, RootProps { router });
+        let _ = vdom.rebuild();
+        dioxus_ssr::render(&vdom)
+    };
+
+    let html = render(true);
+    assert!(
+        html.contains(&format!("class=\"{WATERMARK_CLASS}\"")),
+        "{html}"
+    );
+    assert!(html.contains("Route /hello_world/(dynamic)"), "{html}");
+    // The watermark comes after the route instead of wrapping it
+    let route = html.find("dynamic: 3").unwrap();
+    assert!(route < html.find(WATERMARK_CLASS).unwrap(), "{html}");
+
+    assert!(!render(false).contains(WATERMARK_CLASS));
+    assert!(!server_render(router(true)).html.contains(WATERMARK_CLASS));
+
+    assert_eq!(watermark_label(&Route::Route5 {}, 1), "Route / (nested 1)");
+}